@@ -1,8 +1,50 @@
+use crate::entities::cluster::Cluster;
+use crate::entities::keyspace_events::KeyspaceEvents;
+use crate::entities::log_target::LogTarget;
+use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+use crate::entities::message_queue::OverflowPolicy;
+use crate::entities::rate_limiter::RateLimitPolicy;
 use std::fs::File;
 use std::io;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::path::Path;
+use std::str::FromStr;
+
+/// Reglas de guardado por defecto, expresadas como (segundos, cambios).
+const DEFAULT_SAVE_RULES: [(u64, u64); 3] = [(3600, 1), (300, 100), (60, 10000)];
+
+/// Cantidad máxima de mensajes pendientes por defecto en la cola de cada suscriptor.
+const DEFAULT_PUBSUB_QUEUE_SIZE: usize = 10000;
+
+/// Microsegundos a partir de los cuales un comando se registra en el slowlog por defecto.
+const DEFAULT_SLOWLOG_LOG_SLOWER_THAN: i64 = 10000;
+
+/// Cantidad máxima de entradas del slowlog por defecto.
+const DEFAULT_SLOWLOG_MAX_LEN: usize = 128;
+
+/// Tamaño máximo por defecto de cada valor guardado, en bytes: 512 MB, como los strings de Redis.
+const DEFAULT_MAX_VALUE_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Cantidad máxima de archivos anidados mediante `include`, para evitar inclusiones cíclicas.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Directivas del archivo de configuración que se aplican una vez leídos todos los archivos.
+#[derive(Debug, Default)]
+struct FileDirectives {
+    save_rules: Option<Vec<SaveRule>>,
+    cluster_enabled: bool,
+    cluster_nodes: Vec<String>,
+}
+
+/// Struct usado para representar una regla `save <segundos> <cambios>`: la base de datos se
+/// persiste si pasaron al menos `seconds` segundos desde el último guardado y se realizaron al
+/// menos `changes` modificaciones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SaveRule {
+    pub seconds: u64,
+    pub changes: u64,
+}
 
 /// Struct usado para representar la configuración posible de nuestra base de datos Redis.
 #[derive(Debug)]
@@ -12,6 +54,10 @@ pub struct Config {
     verbose: u8,
     /// port: Indica el puerto en el cual el servidor estará escuchando peticiones.
     port: u16,
+    /// httpport: Indica el puerto en el cual el servidor atiende las peticiones REST.
+    httpport: u16,
+    /// httpenabled: Indica si el servidor atiende peticiones REST.
+    httpenabled: bool,
     /// timeout: un valor entero indicando cu ́antos segundos esperar a que un cliente envíe un
     /// comando antes de cerrar la conexión. Si el valor es 0 se deshabilita el timeout.
     timeout: u64,
@@ -20,10 +66,62 @@ pub struct Config {
     dbfilename: String,
     /// logfile: un string indicando el nombre del archivo en el cual se grabara el log
     logfile: String,
+    /// logtarget: indica si los logs se graban en `logfile` o se envían al syslog local.
+    logtarget: LogTarget,
     /// loglevel: indica el nivel de log a implementar en el server [error:1, info:2, debug:3]
     loglevel: u8,
     /// configfile: guarda en la configuración la ruta del archivo de configuración usado.
     configfile: String,
+    /// requirepass: contraseña que deben enviar los clientes mediante AUTH antes de ejecutar
+    /// cualquier otro comando. Si es vacía no se requiere autenticación.
+    requirepass: String,
+    /// notify_keyspace_events: eventos sobre las claves que se publican por Pub/Sub. Por defecto
+    /// no se publica ningún evento.
+    notify_keyspace_events: KeyspaceEvents,
+    /// save_rules: reglas que indican cuándo persistir la base de datos. Si no hay ninguna, la
+    /// base de datos no se persiste automáticamente.
+    save_rules: Vec<SaveRule>,
+    /// maxmemory: límite de memoria en bytes para los datos. Si es 0 no hay límite.
+    maxmemory: u64,
+    /// maxmemory_policy: qué hacer cuando la memoria usada supera `maxmemory`: responder un error
+    /// o descartar las claves accedidas con menor frecuencia.
+    maxmemory_policy: MaxmemoryPolicy,
+    /// pubsub_queue_size: cantidad máxima de mensajes publicados pendientes de enviar a cada
+    /// suscriptor. Si es 0 no hay límite.
+    pubsub_queue_size: usize,
+    /// pubsub_overflow_policy: qué hacer cuando un suscriptor no lee sus mensajes a tiempo y su
+    /// cola está llena: descartar los mensajes más antiguos o desconectarlo.
+    pubsub_overflow_policy: OverflowPolicy,
+    /// slowlog_log_slower_than: microsegundos a partir de los cuales un comando se registra en el
+    /// slowlog. Con 0 se registran todos los comandos y con un valor negativo, ninguno.
+    slowlog_log_slower_than: i64,
+    /// slowlog_max_len: cantidad máxima de entradas del slowlog. Al superarla se descartan las
+    /// más antiguas.
+    slowlog_max_len: usize,
+    /// max_key_length: largo máximo en bytes de las claves que escriben los comandos. Si es 0 no
+    /// hay límite.
+    max_key_length: u64,
+    /// max_value_size: tamaño máximo en bytes de cada valor o elemento que guardan los comandos.
+    /// Si es 0 no hay límite.
+    max_value_size: u64,
+    /// max_collection_elements: cantidad máxima de elementos de cada lista, set o sorted set. Si
+    /// es 0 no hay límite.
+    max_collection_elements: usize,
+    /// client_max_commands_per_sec: cantidad máxima de comandos por segundo que ejecuta cada
+    /// cliente conectado. Si es 0 no hay límite.
+    client_max_commands_per_sec: u64,
+    /// client_rate_limit_policy: qué hacer con los comandos de un cliente que supera
+    /// `client_max_commands_per_sec`: demorarlos o rechazarlos.
+    client_rate_limit_policy: RateLimitPolicy,
+    /// track_key_access: indica si se registra el último acceso y la frecuencia de acceso de cada
+    /// clave, que informan OBJECT FREQ y DEBUG OBJECT. Deshabilitarlo agiliza las lecturas.
+    track_key_access: bool,
+    /// cluster: reparto de los hash slots entre los nodos del cluster, si el servidor se ejecuta
+    /// en modo cluster (`cluster-enabled yes`).
+    cluster: Option<Cluster>,
+    /// warnings: advertencias generadas al leer el archivo de configuración, como directivas
+    /// desconocidas o valores inválidos.
+    warnings: Vec<String>,
 }
 
 #[allow(dead_code)]
@@ -33,23 +131,93 @@ impl Config {
         Config {
             verbose: 0,
             port: 8080,
+            httpport: 7878,
+            httpenabled: true,
             timeout: 0,
             dbfilename: "dump.rdb".to_string(),
             logfile: "log.log".to_string(),
+            logtarget: LogTarget::File,
             loglevel: 3,
             configfile: "file.conf".to_string(),
+            requirepass: "".to_string(),
+            notify_keyspace_events: KeyspaceEvents::default(),
+            save_rules: DEFAULT_SAVE_RULES
+                .iter()
+                .map(|(seconds, changes)| SaveRule {
+                    seconds: *seconds,
+                    changes: *changes,
+                })
+                .collect(),
+            maxmemory: 0,
+            maxmemory_policy: MaxmemoryPolicy::NoEviction,
+            pubsub_queue_size: DEFAULT_PUBSUB_QUEUE_SIZE,
+            pubsub_overflow_policy: OverflowPolicy::Disconnect,
+            slowlog_log_slower_than: DEFAULT_SLOWLOG_LOG_SLOWER_THAN,
+            slowlog_max_len: DEFAULT_SLOWLOG_MAX_LEN,
+            max_key_length: 0,
+            max_value_size: DEFAULT_MAX_VALUE_SIZE,
+            max_collection_elements: 0,
+            client_max_commands_per_sec: 0,
+            client_rate_limit_policy: RateLimitPolicy::Reject,
+            track_key_access: true,
+            cluster: None,
+            warnings: vec![],
+        }
+    }
+
+    /// Este metodo permite generar una configuración por código, partiendo de los valores por
+    /// defecto: `Config::builder().port(6380).dbfilename("x.rdb").build()`.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder {
+            config: Config::new(),
+            save_rules: None,
+            errors: vec![],
         }
     }
 
     /// Este metodo permite generar una configuración con valores definidos en un archivo de conf.
+    /// Las directivas desconocidas y los valores inválidos se ignoran, dejando una advertencia que
+    /// puede obtenerse con `take_warnings`.
     pub fn new_from_file(path: String) -> Result<Config, io::Error> {
         let mut config = Config::new();
         config.set_configfile(path.clone());
-        let path = Path::new(&path);
+        let mut directives = FileDirectives::default();
+        config.load_file(Path::new(&path), &mut directives, 0)?;
+
+        // Como en Redis, las reglas `save` del archivo reemplazan a las reglas por defecto.
+        if let Some(save_rules) = directives.save_rules {
+            config.save_rules = save_rules;
+        }
+        // El cluster se arma al final, ya que el puerto puede configurarse después de los nodos.
+        if directives.cluster_enabled {
+            config.cluster = Some(
+                Cluster::new(&directives.cluster_nodes, config.port)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+            );
+        }
+
+        Ok(config)
+    }
+
+    /// Lee las directivas de un archivo de configuración. Los archivos indicados con
+    /// `include <archivo>` se leen en el lugar de la directiva; si su ruta es relativa, se
+    /// resuelve desde el directorio del archivo que lo incluye.
+    fn load_file(
+        &mut self,
+        path: &Path,
+        directives: &mut FileDirectives,
+        depth: usize,
+    ) -> Result<(), io::Error> {
+        if depth > MAX_INCLUDE_DEPTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Too many nested includes in {}", path.display()),
+            ));
+        }
         let file = File::open(path)?;
         let content = BufReader::new(&file);
 
-        for line in content.lines() {
+        for (number, line) in content.lines().enumerate() {
             // Remuevo espacios al principio y al final de la línea.
             let line = line?;
             let line = line.trim();
@@ -67,19 +235,93 @@ impl Config {
             let parameters = Config::clean_and_parse_lines(tokens);
             let param = parameters[0].clone();
 
-            // Seteo los valores de la configuración∫
-            match name.to_lowercase().as_str() {
-                "verbose" => config.set_verbose(param),
-                "port" => config.set_port(param),
-                "timeout" => config.set_timeout(param),
-                "dbfilename" => config.set_dbfilename(param),
-                "logfile" => config.set_logfile(param),
-                "loglevel" => config.set_loglevel(param),
-                _ => (),
+            // Seteo los valores de la configuración
+            let result = match name.to_lowercase().as_str() {
+                "include" => {
+                    let included = Path::new(&param);
+                    let included = match path.parent() {
+                        Some(dir) if included.is_relative() => dir.join(included),
+                        _ => included.to_path_buf(),
+                    };
+                    self.load_file(&included, directives, depth + 1)?;
+                    Ok(())
+                }
+                "verbose" => {
+                    self.set_verbose(param);
+                    Ok(())
+                }
+                "port" => {
+                    self.set_port(param);
+                    Ok(())
+                }
+                "httpport" => self.set_httpport(param),
+                "httpenabled" => self.set_httpenabled(param),
+                "timeout" => self.set_timeout(param),
+                "dbfilename" => {
+                    self.set_dbfilename(param);
+                    Ok(())
+                }
+                "logfile" => {
+                    self.set_logfile(param);
+                    Ok(())
+                }
+                "loglevel" => self.set_loglevel(param),
+                "logtarget" => self.set_logtarget(param),
+                "maxmemory" => self.set_maxmemory(param),
+                "maxmemory-policy" => self.set_maxmemory_policy(param),
+                "pubsub-queue-size" => self.set_pubsub_queue_size(param),
+                "pubsub-overflow-policy" => self.set_pubsub_overflow_policy(param),
+                "slowlog-log-slower-than" => self.set_slowlog_log_slower_than(param),
+                "slowlog-max-len" => self.set_slowlog_max_len(param),
+                "max-key-length" => self.set_max_key_length(param),
+                "max-value-size" => self.set_max_value_size(param),
+                "max-collection-elements" => self.set_max_collection_elements(param),
+                "client-max-commands-per-sec" => self.set_client_max_commands_per_sec(param),
+                "client-rate-limit-policy" => self.set_client_rate_limit_policy(param),
+                "track-key-access" => self.set_track_key_access(param),
+                "requirepass" => {
+                    self.set_requirepass(param);
+                    Ok(())
+                }
+                "notify-keyspace-events" => self.set_notify_keyspace_events(param),
+                "save" => {
+                    directives
+                        .save_rules
+                        .get_or_insert_with(Vec::new)
+                        .append(&mut parse_save_rules(&param));
+                    Ok(())
+                }
+                "cluster-enabled" => {
+                    directives.cluster_enabled = param.eq_ignore_ascii_case("yes");
+                    Ok(())
+                }
+                "cluster-nodes" => {
+                    directives.cluster_nodes.extend(
+                        parameters
+                            .iter()
+                            .flat_map(|param| param.split_whitespace())
+                            .map(|addr| addr.to_string()),
+                    );
+                    Ok(())
+                }
+                _ => Err(format!("Unknown directive '{}'", name)),
+            };
+
+            if let Err(e) = result {
+                self.warnings.push(format!(
+                    "{} at line {} of {}",
+                    e,
+                    number + 1,
+                    path.display()
+                ));
             }
         }
+        Ok(())
+    }
 
-        Ok(config)
+    /// Devuelve las advertencias generadas al leer el archivo de configuración, vaciándolas.
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
     }
 
     fn clean_and_parse_lines(tokens: &[&str]) -> Vec<String> {
@@ -115,11 +357,31 @@ impl Config {
         }
     }
 
-    fn set_timeout(&mut self, timeout: String) {
-        let val = timeout.parse::<u64>();
-        if let Ok(value) = val {
-            self.timeout = value
-        }
+    /// Setea el puerto de las peticiones REST. Devuelve error si no es un puerto válido.
+    fn set_httpport(&mut self, port: String) -> Result<(), String> {
+        self.httpport = port
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid argument '{}' for 'httpport'", port))?;
+        Ok(())
+    }
+
+    /// Habilita (`yes`) o deshabilita (`no`) la atención de peticiones REST.
+    fn set_httpenabled(&mut self, enabled: String) -> Result<(), String> {
+        self.httpenabled = match enabled.to_lowercase().as_str() {
+            "yes" => true,
+            "no" => false,
+            _ => return Err(format!("Invalid argument '{}' for 'httpenabled'", enabled)),
+        };
+        Ok(())
+    }
+
+    /// Setea el timeout de los clientes, en segundos. Devuelve error si no es un entero no
+    /// negativo, sin modificar la configuración.
+    pub fn set_timeout(&mut self, timeout: String) -> Result<(), String> {
+        self.timeout = timeout
+            .parse::<u64>()
+            .map_err(|_| invalid_argument(&timeout, "timeout"))?;
+        Ok(())
     }
 
     pub fn set_dbfilename(&mut self, dbfilename: String) {
@@ -130,22 +392,158 @@ impl Config {
         self.logfile = logfile;
     }
 
+    pub fn set_logtarget(&mut self, logtarget: String) -> Result<(), String> {
+        self.logtarget = logtarget
+            .parse()
+            .map_err(|_| invalid_argument(&logtarget, "logtarget"))?;
+        Ok(())
+    }
+
     fn set_configfile(&mut self, configfile: String) {
         self.configfile = configfile;
     }
 
-    fn set_loglevel(&mut self, loglevel: String) {
-        match loglevel.to_lowercase().as_str() {
-            "error" => self.loglevel = 1,
-            "info" => self.loglevel = 2,
-            _ => self.loglevel = 3,
+    /// Setea el nivel de log (error, info o debug). Devuelve error si el nivel no existe, sin
+    /// modificar la configuración.
+    pub fn set_loglevel(&mut self, loglevel: String) -> Result<(), String> {
+        self.loglevel = match loglevel.to_lowercase().as_str() {
+            "error" => 1,
+            "info" => 2,
+            "debug" => 3,
+            _ => return Err(invalid_argument(&loglevel, "loglevel")),
+        };
+        Ok(())
+    }
+
+    /// Setea el límite de memoria, en bytes o con las unidades k, kb, m, mb, g y gb. Devuelve
+    /// error si el valor no es válido, sin modificar la configuración.
+    pub fn set_maxmemory(&mut self, maxmemory: String) -> Result<(), String> {
+        self.maxmemory =
+            parse_memory(&maxmemory).ok_or_else(|| invalid_argument(&maxmemory, "maxmemory"))?;
+        Ok(())
+    }
+
+    pub fn set_maxmemory_policy(&mut self, policy: String) -> Result<(), String> {
+        self.maxmemory_policy = policy
+            .parse()
+            .map_err(|_| invalid_argument(&policy, "maxmemory-policy"))?;
+        Ok(())
+    }
+
+    /// Reemplaza las reglas de guardado por los pares `<segundos> <cambios>` indicados. Un valor
+    /// vacío deshabilita el guardado automático. Devuelve error si algún par no es válido, sin
+    /// modificar la configuración.
+    pub fn set_pubsub_queue_size(&mut self, size: String) -> Result<(), String> {
+        self.pubsub_queue_size = size
+            .parse()
+            .map_err(|_| invalid_argument(&size, "pubsub-queue-size"))?;
+        Ok(())
+    }
+
+    pub fn set_pubsub_overflow_policy(&mut self, policy: String) -> Result<(), String> {
+        self.pubsub_overflow_policy = policy
+            .parse()
+            .map_err(|_| invalid_argument(&policy, "pubsub-overflow-policy"))?;
+        Ok(())
+    }
+
+    pub fn set_slowlog_log_slower_than(&mut self, micros: String) -> Result<(), String> {
+        self.slowlog_log_slower_than = micros
+            .parse()
+            .map_err(|_| invalid_argument(&micros, "slowlog-log-slower-than"))?;
+        Ok(())
+    }
+
+    pub fn set_slowlog_max_len(&mut self, len: String) -> Result<(), String> {
+        self.slowlog_max_len = len
+            .parse()
+            .map_err(|_| invalid_argument(&len, "slowlog-max-len"))?;
+        Ok(())
+    }
+
+    /// Setea el largo máximo de las claves, en bytes o con las mismas unidades que `maxmemory`.
+    pub fn set_max_key_length(&mut self, length: String) -> Result<(), String> {
+        self.max_key_length =
+            parse_memory(&length).ok_or_else(|| invalid_argument(&length, "max-key-length"))?;
+        Ok(())
+    }
+
+    /// Setea el tamaño máximo de los valores, en bytes o con las mismas unidades que `maxmemory`.
+    pub fn set_max_value_size(&mut self, size: String) -> Result<(), String> {
+        self.max_value_size =
+            parse_memory(&size).ok_or_else(|| invalid_argument(&size, "max-value-size"))?;
+        Ok(())
+    }
+
+    pub fn set_max_collection_elements(&mut self, elements: String) -> Result<(), String> {
+        self.max_collection_elements = elements
+            .parse()
+            .map_err(|_| invalid_argument(&elements, "max-collection-elements"))?;
+        Ok(())
+    }
+
+    pub fn set_client_max_commands_per_sec(&mut self, rate: String) -> Result<(), String> {
+        self.client_max_commands_per_sec = rate
+            .parse()
+            .map_err(|_| invalid_argument(&rate, "client-max-commands-per-sec"))?;
+        Ok(())
+    }
+
+    pub fn set_client_rate_limit_policy(&mut self, policy: String) -> Result<(), String> {
+        self.client_rate_limit_policy = policy
+            .parse()
+            .map_err(|_| invalid_argument(&policy, "client-rate-limit-policy"))?;
+        Ok(())
+    }
+
+    pub fn set_track_key_access(&mut self, enabled: String) -> Result<(), String> {
+        self.track_key_access = match enabled.to_lowercase().as_str() {
+            "yes" => true,
+            "no" => false,
+            _ => return Err(invalid_argument(&enabled, "track-key-access")),
+        };
+        Ok(())
+    }
+
+    pub fn set_save(&mut self, save: String) -> Result<(), String> {
+        let values: Vec<&str> = save.split_whitespace().collect();
+        if !values.len().is_multiple_of(2) {
+            return Err(invalid_argument(&save, "save"));
+        }
+        let mut save_rules = vec![];
+        for pair in values.chunks(2) {
+            match (pair[0].parse(), pair[1].parse()) {
+                (Ok(seconds), Ok(changes)) => save_rules.push(SaveRule { seconds, changes }),
+                _ => return Err(invalid_argument(&save, "save")),
+            }
         }
+        self.save_rules = save_rules;
+        Ok(())
+    }
+
+    pub fn set_requirepass(&mut self, requirepass: String) {
+        self.requirepass = requirepass;
+    }
+
+    /// Setea los eventos sobre las claves que se publican, con el formato de flags de Redis.
+    /// Devuelve error si algún flag no es válido, sin modificar la configuración.
+    pub fn set_notify_keyspace_events(&mut self, flags: String) -> Result<(), String> {
+        self.notify_keyspace_events = KeyspaceEvents::from_str(flags.trim())?;
+        Ok(())
     }
 
     pub fn get_port(&self) -> String {
         self.port.to_string()
     }
 
+    pub fn get_httpport(&self) -> String {
+        self.httpport.to_string()
+    }
+
+    pub fn is_httpenabled(&self) -> bool {
+        self.httpenabled
+    }
+
     pub fn get_verbose(&self) -> String {
         self.verbose.to_string()
     }
@@ -162,6 +560,10 @@ impl Config {
         self.logfile.to_string()
     }
 
+    pub fn get_logtarget(&self) -> LogTarget {
+        self.logtarget
+    }
+
     pub fn get_configfile(&self) -> String {
         self.configfile.to_string()
     }
@@ -169,6 +571,374 @@ impl Config {
     pub fn get_loglevel(&self) -> u8 {
         self.loglevel
     }
+
+    pub fn get_maxmemory(&self) -> u64 {
+        self.maxmemory
+    }
+
+    pub fn get_maxmemory_policy(&self) -> MaxmemoryPolicy {
+        self.maxmemory_policy
+    }
+
+    pub fn get_pubsub_queue_size(&self) -> usize {
+        self.pubsub_queue_size
+    }
+
+    pub fn get_pubsub_overflow_policy(&self) -> OverflowPolicy {
+        self.pubsub_overflow_policy
+    }
+
+    pub fn get_slowlog_log_slower_than(&self) -> i64 {
+        self.slowlog_log_slower_than
+    }
+
+    pub fn get_slowlog_max_len(&self) -> usize {
+        self.slowlog_max_len
+    }
+
+    pub fn get_max_key_length(&self) -> u64 {
+        self.max_key_length
+    }
+
+    pub fn get_max_value_size(&self) -> u64 {
+        self.max_value_size
+    }
+
+    pub fn get_max_collection_elements(&self) -> usize {
+        self.max_collection_elements
+    }
+
+    pub fn get_client_max_commands_per_sec(&self) -> u64 {
+        self.client_max_commands_per_sec
+    }
+
+    pub fn get_client_rate_limit_policy(&self) -> RateLimitPolicy {
+        self.client_rate_limit_policy
+    }
+
+    pub fn is_track_key_access(&self) -> bool {
+        self.track_key_access
+    }
+
+    pub fn get_requirepass(&self) -> String {
+        self.requirepass.to_string()
+    }
+
+    pub fn get_notify_keyspace_events(&self) -> KeyspaceEvents {
+        self.notify_keyspace_events.clone()
+    }
+
+    pub fn set_save_rules(&mut self, save_rules: Vec<SaveRule>) {
+        self.save_rules = save_rules;
+    }
+
+    pub fn get_save_rules(&self) -> Vec<SaveRule> {
+        self.save_rules.clone()
+    }
+
+    pub fn set_cluster(&mut self, cluster: Option<Cluster>) {
+        self.cluster = cluster;
+    }
+
+    pub fn get_cluster(&self) -> Option<Cluster> {
+        self.cluster.clone()
+    }
+
+    /// Devuelve los pares (nombre, valor) de los parámetros de configuración, con los nombres y el
+    /// formato de los valores que usa CONFIG GET.
+    pub fn parameters(&self) -> Vec<(String, String)> {
+        let loglevel = match self.loglevel {
+            1 => "error",
+            2 => "info",
+            _ => "debug",
+        };
+        let save = self
+            .save_rules
+            .iter()
+            .map(|rule| format!("{} {}", rule.seconds, rule.changes))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let cluster_enabled = if self.cluster.is_some() { "yes" } else { "no" };
+
+        vec![
+            ("dbfilename", self.get_dbfilename()),
+            ("logfile", self.get_logfile()),
+            ("logtarget", self.logtarget.to_string()),
+            ("port", self.get_port()),
+            ("httpport", self.get_httpport()),
+            (
+                "httpenabled",
+                if self.httpenabled { "yes" } else { "no" }.to_string(),
+            ),
+            ("verbose", self.get_verbose()),
+            ("timeout", self.timeout.to_string()),
+            ("loglevel", loglevel.to_string()),
+            ("requirepass", self.get_requirepass()),
+            (
+                "notify-keyspace-events",
+                self.notify_keyspace_events.to_string(),
+            ),
+            ("save", save),
+            ("maxmemory", self.maxmemory.to_string()),
+            ("maxmemory-policy", self.maxmemory_policy.to_string()),
+            ("pubsub-queue-size", self.pubsub_queue_size.to_string()),
+            (
+                "pubsub-overflow-policy",
+                self.pubsub_overflow_policy.to_string(),
+            ),
+            (
+                "slowlog-log-slower-than",
+                self.slowlog_log_slower_than.to_string(),
+            ),
+            ("slowlog-max-len", self.slowlog_max_len.to_string()),
+            ("max-key-length", self.max_key_length.to_string()),
+            ("max-value-size", self.max_value_size.to_string()),
+            (
+                "max-collection-elements",
+                self.max_collection_elements.to_string(),
+            ),
+            (
+                "client-max-commands-per-sec",
+                self.client_max_commands_per_sec.to_string(),
+            ),
+            (
+                "client-rate-limit-policy",
+                self.client_rate_limit_policy.to_string(),
+            ),
+            (
+                "track-key-access",
+                if self.track_key_access { "yes" } else { "no" }.to_string(),
+            ),
+            ("cluster-enabled", cluster_enabled.to_string()),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    }
+}
+
+/// Parsea los pares `<segundos> <cambios>` de una línea `save`. Los pares inválidos se ignoran,
+/// por lo que `save ""` no agrega ninguna regla y deshabilita el guardado automático.
+fn parse_save_rules(param: &str) -> Vec<SaveRule> {
+    let values: Vec<&str> = param.split_whitespace().collect();
+    values
+        .chunks(2)
+        .filter_map(|pair| match pair {
+            [seconds, changes] => Some(SaveRule {
+                seconds: seconds.parse().ok()?,
+                changes: changes.parse().ok()?,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Struct usado para construir una configuración por código, sin escribir un archivo de
+/// configuración. Se obtiene con `Config::builder()`.
+#[derive(Debug)]
+pub struct ConfigBuilder {
+    config: Config,
+    /// Reglas de guardado indicadas; si no se indica ninguna se usan las reglas por defecto.
+    save_rules: Option<Vec<SaveRule>>,
+    /// Errores de los valores inválidos, que se informan al construir la configuración.
+    errors: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl ConfigBuilder {
+    pub fn verbose(mut self, verbose: u8) -> Self {
+        self.config.verbose = verbose;
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.config.port = port;
+        self
+    }
+
+    pub fn httpport(mut self, port: u16) -> Self {
+        self.config.httpport = port;
+        self
+    }
+
+    pub fn httpenabled(mut self, enabled: bool) -> Self {
+        self.config.httpenabled = enabled;
+        self
+    }
+
+    /// Timeout de los clientes, en segundos. Si es 0 se deshabilita.
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    pub fn dbfilename(mut self, dbfilename: &str) -> Self {
+        self.config.dbfilename = dbfilename.to_string();
+        self
+    }
+
+    pub fn logfile(mut self, logfile: &str) -> Self {
+        self.config.logfile = logfile.to_string();
+        self
+    }
+
+    /// Destino de los logs: el archivo `logfile` o el syslog local.
+    pub fn logtarget(mut self, logtarget: LogTarget) -> Self {
+        self.config.logtarget = logtarget;
+        self
+    }
+
+    /// Nivel de log: error, info o debug.
+    pub fn loglevel(mut self, loglevel: &str) -> Self {
+        if let Err(e) = self.config.set_loglevel(loglevel.to_string()) {
+            self.errors.push(e);
+        }
+        self
+    }
+
+    pub fn requirepass(mut self, requirepass: &str) -> Self {
+        self.config.requirepass = requirepass.to_string();
+        self
+    }
+
+    /// Eventos sobre las claves a publicar, con los mismos flags que `notify-keyspace-events`.
+    pub fn notify_keyspace_events(mut self, flags: &str) -> Self {
+        if let Err(e) = self.config.set_notify_keyspace_events(flags.to_string()) {
+            self.errors.push(e);
+        }
+        self
+    }
+
+    /// Límite de memoria, en bytes. Si es 0 no hay límite.
+    pub fn maxmemory(mut self, maxmemory: u64) -> Self {
+        self.config.maxmemory = maxmemory;
+        self
+    }
+
+    /// Qué hacer cuando la memoria usada supera el límite de memoria.
+    pub fn maxmemory_policy(mut self, policy: MaxmemoryPolicy) -> Self {
+        self.config.maxmemory_policy = policy;
+        self
+    }
+
+    /// Cantidad máxima de mensajes pendientes por suscriptor. Si es 0 no hay límite.
+    pub fn pubsub_queue_size(mut self, size: usize) -> Self {
+        self.config.pubsub_queue_size = size;
+        self
+    }
+
+    /// Qué hacer con los suscriptores cuya cola de mensajes está llena.
+    pub fn pubsub_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.config.pubsub_overflow_policy = policy;
+        self
+    }
+
+    /// Microsegundos a partir de los cuales un comando se registra en el slowlog. Con un valor
+    /// negativo no se registra ninguno.
+    pub fn slowlog_log_slower_than(mut self, micros: i64) -> Self {
+        self.config.slowlog_log_slower_than = micros;
+        self
+    }
+
+    /// Cantidad máxima de entradas del slowlog.
+    pub fn slowlog_max_len(mut self, len: usize) -> Self {
+        self.config.slowlog_max_len = len;
+        self
+    }
+
+    /// Largo máximo de las claves, en bytes. Si es 0 no hay límite.
+    pub fn max_key_length(mut self, length: u64) -> Self {
+        self.config.max_key_length = length;
+        self
+    }
+
+    /// Tamaño máximo de cada valor, en bytes. Si es 0 no hay límite.
+    pub fn max_value_size(mut self, size: u64) -> Self {
+        self.config.max_value_size = size;
+        self
+    }
+
+    /// Cantidad máxima de elementos de cada colección. Si es 0 no hay límite.
+    pub fn max_collection_elements(mut self, elements: usize) -> Self {
+        self.config.max_collection_elements = elements;
+        self
+    }
+
+    /// Cantidad máxima de comandos por segundo de cada cliente. Si es 0 no hay límite.
+    pub fn client_max_commands_per_sec(mut self, rate: u64) -> Self {
+        self.config.client_max_commands_per_sec = rate;
+        self
+    }
+
+    /// Qué hacer con los comandos de los clientes que superan su límite de comandos por segundo.
+    pub fn client_rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.config.client_rate_limit_policy = policy;
+        self
+    }
+
+    /// Indica si se registran los accesos a las claves.
+    pub fn track_key_access(mut self, enabled: bool) -> Self {
+        self.config.track_key_access = enabled;
+        self
+    }
+
+    /// Agrega una regla de guardado. Como en el archivo de configuración, las reglas indicadas
+    /// reemplazan a las reglas por defecto.
+    pub fn save(mut self, seconds: u64, changes: u64) -> Self {
+        self.save_rules
+            .get_or_insert_with(Vec::new)
+            .push(SaveRule { seconds, changes });
+        self
+    }
+
+    /// Deshabilita el guardado automático de la base de datos.
+    pub fn no_save(mut self) -> Self {
+        self.save_rules = Some(vec![]);
+        self
+    }
+
+    /// Construye la configuración. Devuelve el error del primer valor inválido indicado.
+    pub fn build(mut self) -> Result<Config, String> {
+        if let Some(error) = self.errors.into_iter().next() {
+            return Err(error);
+        }
+        if let Some(save_rules) = self.save_rules {
+            self.config.save_rules = save_rules;
+        }
+        Ok(self.config)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
+/// Parsea una cantidad de memoria con las unidades de Redis: k y m y g multiplican por potencias
+/// de 1000, mientras que kb, mb y gb multiplican por potencias de 1024.
+fn parse_memory(value: &str) -> Option<u64> {
+    let value = value.to_lowercase();
+    let digits = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier: u64 = match &value[digits.len()..] {
+        "" | "b" => 1,
+        "k" => 1000,
+        "kb" => 1024,
+        "m" => 1000 * 1000,
+        "mb" => 1024 * 1024,
+        "g" => 1000 * 1000 * 1000,
+        "gb" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Mensaje de error de CONFIG SET para un valor inválido del parámetro.
+fn invalid_argument(value: &str, parameter: &str) -> String {
+    format!(
+        "ERR Invalid argument '{}' for CONFIG SET '{}'",
+        value, parameter
+    )
 }
 
 fn is_invalid_line(line: &str) -> bool {
@@ -177,8 +947,15 @@ fn is_invalid_line(line: &str) -> bool {
 
 #[allow(unused_imports)]
 mod test {
-    use crate::config::server_config::{is_invalid_line, Config};
+    use crate::config::server_config::{
+        is_invalid_line, parse_memory, parse_save_rules, Config, SaveRule,
+    };
     use crate::entities::log_level::LogLevel;
+    use crate::entities::log_target::LogTarget;
+    use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+    use crate::entities::message_queue::OverflowPolicy;
+    use crate::entities::rate_limiter::RateLimitPolicy;
+    use std::fs;
     use std::iter::FromIterator;
 
     #[test]
@@ -190,6 +967,8 @@ mod test {
         assert_eq!("dump.rdb".to_string(), config.get_dbfilename());
         assert_eq!("log.log".to_string(), config.get_logfile());
         assert_eq!(3, config.loglevel);
+        assert_eq!("".to_string(), config.get_requirepass());
+        assert_eq!("", config.get_notify_keyspace_events().to_string());
     }
 
     #[test]
@@ -213,4 +992,216 @@ mod test {
         let line: &str = "esta línea es valida";
         assert!(!is_invalid_line(line))
     }
+
+    #[test]
+    fn set_notify_keyspace_events() {
+        let mut config = Config::new();
+
+        assert!(config.set_notify_keyspace_events("Elg".to_string()).is_ok());
+        assert_eq!("glE", config.get_notify_keyspace_events().to_string());
+        assert!(config.set_notify_keyspace_events("Ew".to_string()).is_err());
+        assert_eq!("glE", config.get_notify_keyspace_events().to_string());
+    }
+
+    #[test]
+    fn parse_save_rules_pairs() {
+        assert_eq!(
+            vec![
+                SaveRule {
+                    seconds: 900,
+                    changes: 1
+                },
+                SaveRule {
+                    seconds: 300,
+                    changes: 10
+                },
+            ],
+            parse_save_rules("900 1 300 10")
+        );
+        assert!(parse_save_rules("\"\"").is_empty());
+        assert!(parse_save_rules("900").is_empty());
+        assert_eq!(3, Config::new().get_save_rules().len());
+    }
+
+    #[test]
+    fn parse_memory_units() {
+        assert_eq!(Some(100), parse_memory("100"));
+        assert_eq!(Some(2000), parse_memory("2k"));
+        assert_eq!(Some(2048), parse_memory("2KB"));
+        assert_eq!(Some(1024 * 1024 * 1024), parse_memory("1gb"));
+        assert_eq!(None, parse_memory("1tb"));
+        assert_eq!(None, parse_memory("mb"));
+    }
+
+    #[test]
+    fn set_runtime_parameters_validates_values() {
+        let mut config = Config::new();
+
+        assert!(config.set_timeout("30".to_string()).is_ok());
+        assert_eq!(30, config.get_timeout());
+        assert!(config.set_timeout("-1".to_string()).is_err());
+        assert_eq!(30, config.get_timeout());
+
+        assert!(config.set_loglevel("ERROR".to_string()).is_ok());
+        assert_eq!(1, config.get_loglevel());
+        assert!(config.set_loglevel("verbose".to_string()).is_err());
+        assert_eq!(1, config.get_loglevel());
+
+        assert!(config.set_maxmemory("10mb".to_string()).is_ok());
+        assert_eq!(10 * 1024 * 1024, config.get_maxmemory());
+
+        assert!(config.set_save("900 1 60".to_string()).is_err());
+        assert_eq!(3, config.get_save_rules().len());
+        assert!(config.set_save("900 1".to_string()).is_ok());
+        assert_eq!(1, config.get_save_rules().len());
+        assert!(config.set_save("".to_string()).is_ok());
+        assert!(config.get_save_rules().is_empty());
+
+        assert!(config.set_pubsub_queue_size("100".to_string()).is_ok());
+        assert_eq!(100, config.get_pubsub_queue_size());
+        assert!(config.set_pubsub_queue_size("-1".to_string()).is_err());
+        assert!(config.set_slowlog_log_slower_than("-1".to_string()).is_ok());
+        assert_eq!(-1, config.get_slowlog_log_slower_than());
+        assert!(config.set_slowlog_max_len("-1".to_string()).is_err());
+        assert!(config.set_max_key_length("1kb".to_string()).is_ok());
+        assert_eq!(1024, config.get_max_key_length());
+        assert!(config.set_max_value_size("big".to_string()).is_err());
+        assert_eq!(512 * 1024 * 1024, config.get_max_value_size());
+        assert!(config
+            .set_max_collection_elements("-1".to_string())
+            .is_err());
+        assert!(config
+            .set_client_max_commands_per_sec("100".to_string())
+            .is_ok());
+        assert_eq!(100, config.get_client_max_commands_per_sec());
+        assert!(config
+            .set_client_rate_limit_policy("delay".to_string())
+            .is_ok());
+        assert_eq!(
+            RateLimitPolicy::Delay,
+            config.get_client_rate_limit_policy()
+        );
+        assert!(config
+            .set_client_rate_limit_policy("drop".to_string())
+            .is_err());
+        assert!(config.set_track_key_access("no".to_string()).is_ok());
+        assert!(!config.is_track_key_access());
+        assert!(config.set_track_key_access("off".to_string()).is_err());
+        assert!(config
+            .set_pubsub_overflow_policy("drop-oldest".to_string())
+            .is_ok());
+        assert_eq!(
+            OverflowPolicy::DropOldest,
+            config.get_pubsub_overflow_policy()
+        );
+        assert!(config
+            .set_pubsub_overflow_policy("block".to_string())
+            .is_err());
+        assert!(config
+            .set_maxmemory_policy("allkeys-lfu".to_string())
+            .is_ok());
+        assert_eq!(MaxmemoryPolicy::AllkeysLfu, config.get_maxmemory_policy());
+        assert!(config
+            .set_maxmemory_policy("allkeys-lru".to_string())
+            .is_err());
+
+        assert!(config.set_logtarget("syslog".to_string()).is_ok());
+        assert_eq!(LogTarget::Syslog, config.get_logtarget());
+        assert!(config.set_logtarget("stdout".to_string()).is_err());
+    }
+
+    #[test]
+    fn new_from_file_with_comments_unknown_directives_and_include() {
+        let dir = std::env::temp_dir().join(format!("config_include_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("redis.conf"),
+            "# Archivo exportado de Redis\n\nport 6390\n   # comentario indentado\n\
+             daemonize no\ninclude extra.conf\nloglevel notice\ntimeout 10\n",
+        )
+        .unwrap();
+        fs::write(dir.join("extra.conf"), "timeout 5\nsave 60 100\n").unwrap();
+
+        let mut config =
+            Config::new_from_file(dir.join("redis.conf").to_str().unwrap().to_string()).unwrap();
+
+        assert_eq!("6390", config.get_port());
+        assert_eq!(10, config.get_timeout());
+        assert_eq!(3, config.get_loglevel());
+        assert_eq!(
+            vec![SaveRule {
+                seconds: 60,
+                changes: 100
+            }],
+            config.get_save_rules()
+        );
+        let warnings = config.take_warnings();
+        assert_eq!(2, warnings.len());
+        assert!(warnings[0].starts_with("Unknown directive 'daemonize' at line 5 of"));
+        assert!(warnings[1].contains("'notice'"));
+        assert!(config.take_warnings().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn new_from_file_with_http_settings() {
+        let dir = std::env::temp_dir().join(format!("config_http_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("redis.conf"),
+            "httpport 9090\nhttpenabled no\nhttpenabled maybe\n",
+        )
+        .unwrap();
+
+        let mut config =
+            Config::new_from_file(dir.join("redis.conf").to_str().unwrap().to_string()).unwrap();
+
+        assert_eq!("9090", config.get_httpport());
+        assert!(!config.is_httpenabled());
+        assert_eq!(1, config.take_warnings().len());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn builder_sets_values() {
+        let config = Config::builder()
+            .port(6380)
+            .dbfilename("x.rdb")
+            .loglevel("info")
+            .timeout(30)
+            .save(60, 100)
+            .build()
+            .unwrap();
+
+        assert_eq!("6380", config.get_port());
+        assert_eq!("x.rdb", config.get_dbfilename());
+        assert_eq!(2, config.get_loglevel());
+        assert_eq!(30, config.get_timeout());
+        assert_eq!(
+            vec![SaveRule {
+                seconds: 60,
+                changes: 100
+            }],
+            config.get_save_rules()
+        );
+        assert_eq!("log.log", config.get_logfile());
+
+        let config = Config::builder().no_save().build().unwrap();
+        assert!(config.get_save_rules().is_empty());
+        assert!(Config::builder().loglevel("loud").build().is_err());
+    }
+
+    #[test]
+    fn new_from_file_with_missing_include_fails() {
+        let dir = std::env::temp_dir().join(format!("config_missing_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("redis.conf"), "include missing.conf\n").unwrap();
+
+        let config = Config::new_from_file(dir.join("redis.conf").to_str().unwrap().to_string());
+        assert!(config.is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
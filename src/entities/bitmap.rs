@@ -0,0 +1,195 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// BitUnit: Enum usado para indicar si los índices de un rango se expresan en bytes o en bits.
+pub enum BitUnit {
+    Byte,
+    Bit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// BitRange: Struct usado para representar el rango recibido por BITCOUNT. Ambos extremos son
+/// inclusivos y admiten valores negativos, contados desde el final.
+pub struct BitRange {
+    pub start: i64,
+    pub end: i64,
+    pub unit: BitUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// BitOperation: Enum usado para representar las operaciones permitidas por BITOP.
+pub enum BitOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+/// Devuelve el bit en la posición indicada, siendo 0 el bit más significativo del primer byte.
+/// Los bits fuera del string valen 0.
+pub fn get_bit(bytes: &[u8], offset: usize) -> bool {
+    match bytes.get(offset / 8) {
+        Some(byte) => byte >> (7 - offset % 8) & 1 == 1,
+        None => false,
+    }
+}
+
+/// Setea el bit en la posición indicada, agrandando el string con ceros si es necesario.
+/// Devuelve el valor previo del bit.
+pub fn set_bit(bytes: &mut Vec<u8>, offset: usize, value: bool) -> bool {
+    let index = offset / 8;
+    if bytes.len() <= index {
+        bytes.resize(index + 1, 0);
+    }
+
+    let previous = get_bit(bytes, offset);
+    let mask = 1 << (7 - offset % 8);
+    if value {
+        bytes[index] |= mask;
+    } else {
+        bytes[index] &= !mask;
+    }
+    previous
+}
+
+/// Cuenta la cantidad de bits en 1, dentro del rango indicado o en todo el string.
+pub fn count(bytes: &[u8], range: Option<BitRange>) -> usize {
+    match range {
+        None => count_ones(bytes),
+        Some(range) => match bit_range(bytes, range.start, Some(range.end), range.unit) {
+            Some((start, end)) => (start..=end).filter(|&i| get_bit(bytes, i)).count(),
+            None => 0,
+        },
+    }
+}
+
+/// Devuelve la posición del primer bit con el valor indicado dentro del rango, o -1 si no existe.
+///
+/// Si se busca un 0 y no se indicó el final del rango, se considera que el string continúa con
+/// ceros, devolviendo la primera posición posterior al string.
+pub fn position(bytes: &[u8], bit: bool, start: i64, end: Option<i64>, unit: BitUnit) -> i64 {
+    if bytes.is_empty() {
+        return if bit { -1 } else { 0 };
+    }
+
+    let found = bit_range(bytes, start, end, unit)
+        .and_then(|(start, end)| (start..=end).find(|&i| get_bit(bytes, i) == bit));
+
+    match found {
+        Some(position) => position as i64,
+        None if !bit && end.is_none() => (bytes.len() * 8) as i64,
+        None => -1,
+    }
+}
+
+/// Aplica la operación indicada byte a byte sobre los strings. Los strings más cortos se
+/// completan con ceros.
+pub fn operate(operation: BitOperation, sources: &[Vec<u8>]) -> Vec<u8> {
+    let len = sources.iter().map(|source| source.len()).max().unwrap_or(0);
+
+    (0..len)
+        .map(|i| {
+            let mut bytes = sources.iter().map(|source| *source.get(i).unwrap_or(&0));
+            let first = bytes.next().unwrap_or(0);
+            match operation {
+                BitOperation::And => bytes.fold(first, |acc, byte| acc & byte),
+                BitOperation::Or => bytes.fold(first, |acc, byte| acc | byte),
+                BitOperation::Xor => bytes.fold(first, |acc, byte| acc ^ byte),
+                BitOperation::Not => !first,
+            }
+        })
+        .collect()
+}
+
+/// Cuenta la cantidad de bits en 1 de los bytes.
+fn count_ones(bytes: &[u8]) -> usize {
+    bytes.iter().map(|byte| byte.count_ones() as usize).sum()
+}
+
+/// Normaliza un rango (inclusivo y con índices negativos) expresado en la unidad indicada, y lo
+/// devuelve como un rango de posiciones de bits. Devuelve None si el rango es vacío.
+fn bit_range(bytes: &[u8], start: i64, end: Option<i64>, unit: BitUnit) -> Option<(usize, usize)> {
+    let len = match unit {
+        BitUnit::Byte => bytes.len() as i64,
+        BitUnit::Bit => (bytes.len() * 8) as i64,
+    };
+    let end = end.unwrap_or(len - 1);
+
+    let start = if start < 0 { start + len } else { start }.max(0);
+    let end = if end < 0 { end + len } else { end }.min(len - 1);
+    if start > end || end < 0 {
+        return None;
+    }
+
+    match unit {
+        BitUnit::Byte => Some((start as usize * 8, end as usize * 8 + 7)),
+        BitUnit::Bit => Some((start as usize, end as usize)),
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::bitmap::{
+        count, get_bit, operate, position, set_bit, BitOperation, BitRange, BitUnit,
+    };
+
+    #[test]
+    fn test_set_bit_grows_and_returns_previous_value() {
+        let mut bytes = vec![];
+
+        assert!(!set_bit(&mut bytes, 7, true));
+        assert_eq!(vec![1], bytes);
+        assert!(set_bit(&mut bytes, 7, false));
+        assert_eq!(vec![0], bytes);
+
+        set_bit(&mut bytes, 17, true);
+        assert_eq!(vec![0, 0, 0b0100_0000], bytes);
+        assert!(get_bit(&bytes, 17));
+        assert!(!get_bit(&bytes, 100));
+    }
+
+    #[test]
+    fn test_count_with_byte_and_bit_ranges() {
+        let bytes = b"foobar".to_vec();
+
+        assert_eq!(26, count(&bytes, None));
+        let range = BitRange {
+            start: 1,
+            end: 1,
+            unit: BitUnit::Byte,
+        };
+        assert_eq!(6, count(&bytes, Some(range)));
+        let range = BitRange {
+            start: 5,
+            end: 30,
+            unit: BitUnit::Bit,
+        };
+        assert_eq!(17, count(&bytes, Some(range)));
+        let range = BitRange {
+            start: -2,
+            end: -1,
+            unit: BitUnit::Byte,
+        };
+        assert_eq!(7, count(&bytes, Some(range)));
+    }
+
+    #[test]
+    fn test_position_of_set_and_clear_bits() {
+        let bytes = vec![0xff, 0xf0, 0x00];
+
+        assert_eq!(12, position(&bytes, false, 0, None, BitUnit::Byte));
+        assert_eq!(-1, position(&bytes, true, 2, None, BitUnit::Byte));
+        assert_eq!(8, position(&bytes, true, 1, None, BitUnit::Byte));
+        assert_eq!(8, position(&[0xff], false, 0, None, BitUnit::Byte));
+        assert_eq!(-1, position(&[0xff], false, 0, Some(-1), BitUnit::Byte));
+        assert_eq!(-1, position(&[], true, 0, None, BitUnit::Byte));
+    }
+
+    #[test]
+    fn test_operate_pads_shorter_sources() {
+        let sources = vec![vec![0b1100, 0xff], vec![0b1010]];
+
+        assert_eq!(vec![0b1000, 0], operate(BitOperation::And, &sources));
+        assert_eq!(vec![0b1110, 0xff], operate(BitOperation::Or, &sources));
+        assert_eq!(vec![0b0110, 0xff], operate(BitOperation::Xor, &sources));
+        assert_eq!(vec![0xff], operate(BitOperation::Not, &[vec![0]]));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::entities::redis_element::RedisElement;
+use crate::entities::sorted_set::ScoreSide;
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::Sender;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// ListSide: Enum usado para indicar de qué extremo de la lista debe obtenerse un elemento.
+pub enum ListSide {
+    /// Inicio de la lista (BLPOP).
+    Left,
+    /// Final de la lista (BRPOP).
+    Right,
+}
+
+impl ListSide {
+    /// Devuelve el nombre del evento de keyspace que produce quitar un elemento de este extremo.
+    pub fn pop_event(self) -> &'static str {
+        match self {
+            ListSide::Left => "lpop",
+            ListSide::Right => "rpop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// PopSide: Enum usado para indicar qué tipo de dato espera un cliente bloqueado, y de qué extremo
+/// debe obtenerse el elemento.
+pub enum PopSide {
+    /// Lista (BLPOP / BRPOP).
+    List(ListSide),
+    /// Sorted set (BZPOPMIN / BZPOPMAX).
+    SortedSet(ScoreSide),
+}
+
+#[derive(Debug)]
+/// Waiter: Representa a un cliente bloqueado esperando que alguna de sus listas o sorted sets
+/// tenga elementos.
+pub struct Waiter {
+    /// Canal por el cual se le envía la respuesta al cliente.
+    pub sender: Sender<RedisElement>,
+    /// Claves sobre las cuales el cliente está esperando.
+    pub keys: Vec<String>,
+    /// Tipo de dato y extremo del cual se debe obtener el elemento.
+    pub side: PopSide,
+    /// Momento en el cual vence la espera. None si el cliente espera indefinidamente.
+    pub deadline: Option<SystemTime>,
+}
+
+#[derive(Debug, Default)]
+/// BlockedClients: Registro de los clientes bloqueados por BLPOP/BRPOP y BZPOPMIN/BZPOPMAX,
+/// indexado por el nombre de la clave sobre la cual esperan.
+///
+/// Los clientes de una misma clave se atienden en orden de llegada.
+pub struct BlockedClients {
+    /// Identificador a asignar al próximo cliente registrado.
+    next_id: u64,
+    /// Mapa {id, Waiter} con los clientes bloqueados.
+    waiters: HashMap<u64, Waiter>,
+    /// Mapa {lista, ids de los clientes esperando por ella}.
+    by_key: HashMap<String, VecDeque<u64>>,
+}
+
+impl BlockedClients {
+    /// Constructor de un nuevo registro vacío.
+    pub fn new() -> Self {
+        BlockedClients {
+            next_id: 0,
+            waiters: HashMap::new(),
+            by_key: HashMap::new(),
+        }
+    }
+
+    /// Registra un cliente bloqueado sobre cada una de sus listas.
+    pub fn register(&mut self, waiter: Waiter) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        for key in &waiter.keys {
+            self.by_key
+                .entry(key.to_string())
+                .or_default()
+                .push_back(id);
+        }
+        self.waiters.insert(id, waiter);
+    }
+
+    /// Quita del registro y devuelve al cliente que hace más tiempo espera por la clave indicada,
+    /// entre los que cumplen `accepts` (ej: los que esperan una lista). El resto de los clientes
+    /// siguen esperando.
+    ///
+    /// El cliente deja de esperar también sobre el resto de sus claves.
+    pub fn pop_waiter<F: Fn(&Waiter) -> bool>(&mut self, key: &str, accepts: F) -> Option<Waiter> {
+        let ids = self.by_key.get_mut(key)?;
+        let waiters = &mut self.waiters;
+        ids.retain(|id| waiters.contains_key(id));
+
+        let waiter = ids
+            .iter()
+            .position(|id| accepts(&waiters[id]))
+            .and_then(|position| ids.remove(position))
+            .and_then(|id| waiters.remove(&id));
+        if ids.is_empty() {
+            self.by_key.remove(key);
+        }
+
+        waiter
+    }
+
+    /// Quita del registro y devuelve a los clientes cuya espera venció.
+    pub fn expire(&mut self, now: SystemTime) -> Vec<Waiter> {
+        let expired: Vec<u64> = self
+            .waiters
+            .iter()
+            .filter(|(_, waiter)| waiter.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(id, _)| *id)
+            .collect();
+
+        if expired.is_empty() {
+            return vec![];
+        }
+
+        let expired: Vec<Waiter> = expired
+            .iter()
+            .filter_map(|id| self.waiters.remove(id))
+            .collect();
+
+        let waiters = &self.waiters;
+        for ids in self.by_key.values_mut() {
+            ids.retain(|id| waiters.contains_key(id));
+        }
+        self.by_key.retain(|_, ids| !ids.is_empty());
+
+        expired
+    }
+
+    /// Indica si no hay clientes bloqueados.
+    pub fn is_empty(&self) -> bool {
+        self.waiters.is_empty()
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::blocked_clients::{BlockedClients, ListSide, PopSide, Waiter};
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::sorted_set::ScoreSide;
+    use std::sync::mpsc;
+    use std::sync::mpsc::{Receiver, Sender};
+    use std::time::{Duration, SystemTime};
+
+    #[allow(dead_code)]
+    fn waiter(keys: Vec<&str>, deadline: Option<SystemTime>) -> Waiter {
+        let (sender, _): (Sender<RedisElement>, Receiver<RedisElement>) = mpsc::channel();
+        Waiter {
+            sender,
+            keys: keys.into_iter().map(|key| key.to_string()).collect(),
+            side: PopSide::List(ListSide::Left),
+            deadline,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn any(_: &Waiter) -> bool {
+        true
+    }
+
+    #[test]
+    fn test_pop_waiter_in_arrival_order() {
+        let mut blocked = BlockedClients::new();
+        blocked.register(waiter(vec!["a"], None));
+        blocked.register(waiter(vec!["b", "a"], None));
+
+        let first = blocked.pop_waiter("a", any).unwrap();
+        assert_eq!(first.keys, vec!["a".to_string()]);
+
+        let second = blocked.pop_waiter("a", any).unwrap();
+        assert_eq!(second.keys, vec!["b".to_string(), "a".to_string()]);
+
+        assert!(blocked.pop_waiter("b", any).is_none());
+        assert!(blocked.is_empty());
+    }
+
+    #[test]
+    fn test_expire_removes_only_expired_waiters() {
+        let mut blocked = BlockedClients::new();
+        let now = SystemTime::now();
+        blocked.register(waiter(vec!["a"], Some(now - Duration::from_secs(1))));
+        blocked.register(waiter(vec!["a"], None));
+
+        let expired = blocked.expire(now);
+        assert_eq!(expired.len(), 1);
+
+        assert!(blocked.pop_waiter("a", any).unwrap().deadline.is_none());
+        assert!(blocked.is_empty());
+    }
+
+    #[test]
+    fn test_pop_waiter_skips_waiters_not_accepted() {
+        let mut blocked = BlockedClients::new();
+        blocked.register(waiter(vec!["a"], None));
+        let mut zset_waiter = waiter(vec!["a"], None);
+        zset_waiter.side = PopSide::SortedSet(ScoreSide::Min);
+        blocked.register(zset_waiter);
+
+        let is_sorted_set = |waiter: &Waiter| matches!(waiter.side, PopSide::SortedSet(_));
+        let served = blocked.pop_waiter("a", is_sorted_set).unwrap();
+        assert_eq!(PopSide::SortedSet(ScoreSide::Min), served.side);
+        assert!(blocked.pop_waiter("a", is_sorted_set).is_none());
+
+        let served = blocked.pop_waiter("a", any).unwrap();
+        assert_eq!(PopSide::List(ListSide::Left), served.side);
+        assert!(blocked.is_empty());
+    }
+}
@@ -0,0 +1,31 @@
+#[derive(Debug)]
+/// ClientParam: Enum usado para representar los subcomandos permitidos para el Command::Client.
+pub enum ClientParam {
+    /// Representa el subcomando Id, que devuelve el id del cliente.
+    Id,
+    /// Representa el subcomando List, que describe a todos los clientes conectados.
+    List,
+    /// Representa el subcomando Info, que describe al cliente que envía el comando.
+    Info,
+    /// Representa el subcomando SetName, que asigna un nombre al cliente.
+    SetName(String),
+    /// Representa el subcomando GetName, que devuelve el nombre del cliente.
+    GetName,
+    /// Representa el subcomando Kill con la forma `CLIENT KILL ip:port`, que cierra la conexión
+    /// del cliente conectado desde esa dirección.
+    KillAddr(String),
+    /// Representa el subcomando Kill con filtros, que cierra las conexiones de todos los clientes
+    /// que cumplan con ellos.
+    Kill(KillFilter),
+}
+
+#[derive(Debug, Default, PartialEq)]
+/// KillFilter: Filtros del subcomando `CLIENT KILL <filtro> <valor> ...`.
+pub struct KillFilter {
+    /// Id del cliente a cerrar (`ID`).
+    pub id: Option<u64>,
+    /// Dirección del cliente a cerrar (`ADDR`).
+    pub addr: Option<String>,
+    /// Indica si se omite al cliente que envía el comando (`SKIPME`). Por defecto es `true`.
+    pub skipme: bool,
+}
@@ -0,0 +1,77 @@
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Clock: Fuente de la hora actual usada para calcular las expiraciones de las claves. Permite
+/// reemplazar la hora del sistema por una controlada, para avanzar el tiempo sin esperar.
+pub trait Clock: Debug + Send + Sync {
+    /// Devuelve la hora actual.
+    fn now(&self) -> SystemTime;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// SystemClock: Reloj que devuelve la hora del sistema.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[derive(Debug)]
+/// MockClock: Reloj cuya hora sólo cambia al avanzarlo o fijarla explícitamente.
+pub struct MockClock {
+    now: Mutex<SystemTime>,
+}
+
+impl MockClock {
+    /// Crea un reloj detenido en `now`.
+    pub fn new(now: SystemTime) -> Self {
+        MockClock {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Adelanta el reloj en `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+
+    /// Fija la hora del reloj.
+    pub fn set(&self, now: SystemTime) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::clock::{Clock, MockClock, SystemClock};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_mock_clock_only_moves_when_advanced() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let clock = MockClock::new(start);
+        assert_eq!(start, clock.now());
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(start + Duration::from_secs(5), clock.now());
+
+        clock.set(start);
+        assert_eq!(start, clock.now());
+    }
+
+    #[test]
+    fn test_system_clock_returns_the_system_time() {
+        let before = SystemTime::now();
+        let now = SystemClock.now();
+        assert!(before <= now && now <= SystemTime::now());
+    }
+}
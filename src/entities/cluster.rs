@@ -0,0 +1,312 @@
+use crate::entities::redis_element::RedisElement;
+
+/// Cantidad de hash slots en los que se reparten las claves del cluster.
+pub const CLUSTER_SLOTS: u16 = 16384;
+
+/// Polinomio del CRC16 XMODEM, el que usa Redis para calcular el slot de las claves.
+const POLY: u16 = 0x1021;
+
+#[derive(Debug, Clone, PartialEq)]
+/// ClusterNode: Nodo del cluster y el rango de slots que tiene asignado.
+pub struct ClusterNode {
+    /// Host del nodo.
+    pub host: String,
+    /// Puerto en el que el nodo atiende a los clientes.
+    pub port: u16,
+    /// Primer slot asignado al nodo.
+    pub first_slot: u16,
+    /// Último slot asignado al nodo, inclusive.
+    pub last_slot: u16,
+}
+
+impl ClusterNode {
+    /// Devuelve la dirección `host:port` del nodo.
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Cluster: Reparto de los hash slots entre los nodos configurados. Cada nodo atiende solamente las
+/// claves de sus slots y redirige a los clientes con `MOVED` para el resto.
+pub struct Cluster {
+    nodes: Vec<ClusterNode>,
+    /// Posición de este servidor dentro de `nodes`.
+    myself: usize,
+}
+
+impl Cluster {
+    /// Crea el cluster a partir de las direcciones `host:port` de los nodos, repartiendo los slots
+    /// en rangos consecutivos de igual tamaño según el orden de las direcciones. Este servidor es el
+    /// nodo que escucha en `port`; si no hay direcciones, es el único nodo del cluster.
+    ///
+    /// Retorna error si alguna dirección es inválida o si ninguna corresponde a este servidor.
+    pub fn new(addrs: &[String], port: u16) -> Result<Cluster, String> {
+        let addrs = if addrs.is_empty() {
+            vec![format!("127.0.0.1:{}", port)]
+        } else {
+            addrs.to_vec()
+        };
+
+        let count = addrs.len();
+        if count > CLUSTER_SLOTS as usize {
+            return Err("Too many cluster nodes".to_string());
+        }
+        let mut nodes = vec![];
+        for (i, addr) in addrs.iter().enumerate() {
+            let (host, node_port) = addr
+                .rsplit_once(':')
+                .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+                .filter(|(host, _)| !host.is_empty())
+                .ok_or_else(|| format!("Invalid cluster node address '{}'", addr))?;
+            nodes.push(ClusterNode {
+                host: host.to_string(),
+                port: node_port,
+                first_slot: (i * CLUSTER_SLOTS as usize / count) as u16,
+                last_slot: ((i + 1) * CLUSTER_SLOTS as usize / count - 1) as u16,
+            });
+        }
+
+        let myself = nodes
+            .iter()
+            .position(|node| node.port == port)
+            .ok_or_else(|| format!("No cluster node listens on port {}", port))?;
+        Ok(Cluster { nodes, myself })
+    }
+
+    /// Devuelve el nodo al que está asignado el slot.
+    pub fn node_for_slot(&self, slot: u16) -> &ClusterNode {
+        self.nodes
+            .iter()
+            .find(|node| node.last_slot >= slot)
+            .unwrap_or(&self.nodes[self.myself])
+    }
+
+    /// Verifica que el comando pueda ejecutarse en este nodo.
+    ///
+    /// Retorna el error `CROSSSLOT` si las claves del comando pertenecen a distintos slots, o
+    /// `MOVED slot host:port` si pertenecen a un slot asignado a otro nodo.
+    pub fn check_command(&self, params: &[String]) -> Result<(), String> {
+        let mut slots = command_keys(params).into_iter().map(key_slot);
+        let slot = match slots.next() {
+            Some(slot) => slot,
+            None => return Ok(()),
+        };
+        if slots.any(|other| other != slot) {
+            return Err("CROSSSLOT Keys in request don't hash to the same slot".to_string());
+        }
+
+        let node = self.node_for_slot(slot);
+        if *node == self.nodes[self.myself] {
+            Ok(())
+        } else {
+            Err(format!("MOVED {} {}", slot, node.addr()))
+        }
+    }
+
+    /// Describe los rangos de slots de cada nodo, con el formato de CLUSTER SLOTS.
+    pub fn slots(&self) -> RedisElement {
+        RedisElement::Array(
+            self.nodes
+                .iter()
+                .map(|node| {
+                    RedisElement::Array(vec![
+                        RedisElement::Integer(node.first_slot as i64),
+                        RedisElement::Integer(node.last_slot as i64),
+                        RedisElement::Array(vec![
+                            RedisElement::String(node.host.clone()),
+                            RedisElement::Integer(node.port as i64),
+                        ]),
+                    ])
+                })
+                .collect(),
+        )
+    }
+
+    /// Describe el estado del cluster, con el formato de CLUSTER INFO.
+    pub fn info(&self) -> String {
+        [
+            "cluster_enabled:1".to_string(),
+            "cluster_state:ok".to_string(),
+            format!("cluster_slots_assigned:{}", CLUSTER_SLOTS),
+            format!("cluster_slots_ok:{}", CLUSTER_SLOTS),
+            "cluster_slots_pfail:0".to_string(),
+            "cluster_slots_fail:0".to_string(),
+            format!("cluster_known_nodes:{}", self.nodes.len()),
+            format!("cluster_size:{}", self.nodes.len()),
+            "cluster_current_epoch:0".to_string(),
+            "cluster_my_epoch:0".to_string(),
+        ]
+        .join("\r\n")
+            + "\r\n"
+    }
+}
+
+/// Calcula el CRC16 XMODEM de los bytes.
+pub fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |crc, byte| {
+        let mut crc = crc ^ ((*byte as u16) << 8);
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ POLY
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// Devuelve el hash slot de la clave. Si la clave contiene un hash tag `{...}` no vacío, solo se
+/// usa el contenido del tag, lo que permite forzar a varias claves a compartir slot.
+pub fn key_slot(key: &str) -> u16 {
+    let bytes = key.as_bytes();
+    let hashed = match bytes.iter().position(|b| *b == b'{') {
+        Some(open) => match bytes[open + 1..].iter().position(|b| *b == b'}') {
+            Some(len) if len > 0 => &bytes[open + 1..open + 1 + len],
+            _ => bytes,
+        },
+        None => bytes,
+    };
+    crc16(hashed) % CLUSTER_SLOTS
+}
+
+/// Devuelve las claves a las que accede el comando, recibido como la lista de sus parámetros con
+/// el nombre del comando primero.
+pub fn command_keys(params: &[String]) -> Vec<&str> {
+    let name = match params.first() {
+        Some(name) => name.to_lowercase(),
+        None => return vec![],
+    };
+    let args: Vec<&str> = params[1..].iter().map(|arg| arg.as_str()).collect();
+
+    match name.as_str() {
+        "ping" | "echo" | "info" | "config" | "dbsize" | "flushdb" | "flushall" | "randomkey"
+        | "monitor" | "auth" | "client" | "store" | "load" | "keys" | "command" | "cluster"
+        | "pubsub" | "publish" | "subscribe" | "unsubscribe" | "connectedclients" | "addclient"
+        | "removeclient" => {
+            vec![]
+        }
+        "del" | "unlink" | "exists" | "touch" | "mget" | "sinter" | "sunion" | "sdiff" => args,
+        "mset" | "msetnx" => args.into_iter().step_by(2).collect(),
+        "blpop" | "brpop" | "bzpopmin" | "bzpopmax" => {
+            args[..args.len().saturating_sub(1)].to_vec()
+        }
+        "bitop" => args.into_iter().skip(2).collect(),
+        "copy" | "rename" | "renamenx" => args.into_iter().take(2).collect(),
+        "object" | "memory" => args.into_iter().skip(1).take(1).collect(),
+        "sort" => {
+            let store = args
+                .iter()
+                .position(|arg| arg.eq_ignore_ascii_case("store"))
+                .and_then(|pos| args.get(pos + 1));
+            args.iter().take(1).chain(store).copied().collect()
+        }
+        "sintercard" => {
+            let numkeys = args
+                .first()
+                .and_then(|numkeys| numkeys.parse::<usize>().ok())
+                .unwrap_or(0);
+            args.into_iter().skip(1).take(numkeys).collect()
+        }
+        "zunionstore" | "zinterstore" => {
+            let numkeys = args
+                .get(1)
+                .and_then(|numkeys| numkeys.parse::<usize>().ok())
+                .unwrap_or(0);
+            args.iter()
+                .take(1)
+                .chain(args.iter().skip(2).take(numkeys))
+                .copied()
+                .collect()
+        }
+        "xread" | "xreadgroup" => {
+            match args
+                .iter()
+                .position(|arg| arg.eq_ignore_ascii_case("streams"))
+            {
+                Some(pos) => {
+                    let streams = &args[pos + 1..];
+                    streams[..streams.len() / 2].to_vec()
+                }
+                None => vec![],
+            }
+        }
+        _ => args.into_iter().take(1).collect(),
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::cluster::{command_keys, crc16, key_slot, Cluster, CLUSTER_SLOTS};
+
+    #[allow(dead_code)]
+    fn params(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_key_slot_matches_redis() {
+        assert_eq!(0x31c3, crc16(b"123456789"));
+        assert_eq!(12182, key_slot("foo"));
+        assert_eq!(11058, key_slot("somekey"));
+        assert_eq!(key_slot("user1000"), key_slot("{user1000}.following"));
+        assert_eq!(key_slot("{}.a"), crc16(b"{}.a") % CLUSTER_SLOTS);
+    }
+
+    #[test]
+    fn test_slots_are_split_between_nodes() {
+        let cluster = Cluster::new(&params(&["127.0.0.1:7000", "127.0.0.1:7001"]), 7000).unwrap();
+
+        assert_eq!(8191, cluster.nodes[0].last_slot);
+        assert_eq!(8192, cluster.nodes[1].first_slot);
+        assert_eq!(CLUSTER_SLOTS - 1, cluster.nodes[1].last_slot);
+        assert!(Cluster::new(&params(&["127.0.0.1:7001"]), 7000).is_err());
+        assert!(Cluster::new(&params(&["7000"]), 7000).is_err());
+    }
+
+    #[test]
+    fn test_check_command_redirects_to_owner() {
+        let cluster = Cluster::new(&params(&["127.0.0.1:7000", "127.0.0.1:7001"]), 7000).unwrap();
+
+        assert_eq!(
+            Err("MOVED 12182 127.0.0.1:7001".to_string()),
+            cluster.check_command(&params(&["get", "foo"]))
+        );
+        assert!(cluster.check_command(&params(&["get", "bar"])).is_ok());
+        assert!(cluster.check_command(&params(&["ping"])).is_ok());
+        assert_eq!(
+            Err("CROSSSLOT Keys in request don't hash to the same slot".to_string()),
+            cluster.check_command(&params(&["mget", "foo", "bar"]))
+        );
+    }
+
+    #[test]
+    fn test_command_keys() {
+        let mset = params(&["MSET", "a", "1", "b", "2"]);
+        let xread = params(&["xread", "count", "1", "STREAMS", "s1", "s2", "0", "0"]);
+        let blpop = params(&["blpop", "l1", "l2", "0"]);
+
+        assert_eq!(vec!["a", "b"], command_keys(&mset));
+        assert_eq!(vec!["s1", "s2"], command_keys(&xread));
+        assert_eq!(vec!["l1", "l2"], command_keys(&blpop));
+        assert_eq!(
+            vec!["list", "dest"],
+            command_keys(&params(&["sort", "list", "desc", "STORE", "dest"]))
+        );
+        assert_eq!(
+            vec!["dest", "z1", "z2"],
+            command_keys(&params(&[
+                "zunionstore",
+                "dest",
+                "2",
+                "z1",
+                "z2",
+                "WEIGHTS",
+                "1",
+                "2"
+            ]))
+        );
+        assert!(command_keys(&params(&["dbsize"])).is_empty());
+    }
+}
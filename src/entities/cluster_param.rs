@@ -0,0 +1,10 @@
+#[derive(Debug)]
+/// ClusterParam: Enum usado para representar los subcomandos permitidos para el Command::Cluster.
+pub enum ClusterParam {
+    /// Representa el subcomando KeySlot, que devuelve el hash slot de la clave.
+    KeySlot(String),
+    /// Representa el subcomando Slots, que describe los rangos de slots asignados a cada nodo.
+    Slots,
+    /// Representa el subcomando Info, que describe el estado del cluster.
+    Info,
+}
@@ -1,25 +1,91 @@
+use crate::entities::bitmap::{BitOperation, BitRange, BitUnit};
+use crate::entities::client_param::ClientParam;
+use crate::entities::cluster_param::ClusterParam;
+use crate::entities::command_param::CommandParam;
+use crate::entities::consumer_group_param::{PendingRange, XgroupParam};
+use crate::entities::debug_param::DebugParam;
+use crate::entities::expire_condition::ExpireCondition;
+use crate::entities::geo::{GeoAddOptions, GeoSearchOptions, GeoUnit};
 use crate::entities::info_param::InfoParam;
+use crate::entities::memory_param::MemoryParam;
+use crate::entities::object_param::ObjectParam;
 use crate::entities::pubsub_param::PubSubParam;
+use crate::entities::script_param::ScriptParam;
+use crate::entities::set_options::SetOptions;
+use crate::entities::slowlog_param::SlowlogParam;
+use crate::entities::sort_options::SortOptions;
+use crate::entities::sorted_set::{Aggregate, ScoreBound};
+use crate::entities::stream::StreamId;
 use std::collections::HashSet;
 use std::time::{Duration, SystemTime};
 
+/// Datos que un comando escribe en la base de datos, usados para controlar los límites de tamaño
+/// configurados antes de ejecutarlo.
+#[derive(Debug, Default, PartialEq)]
+pub struct WrittenData<'a> {
+    /// Claves que el comando crea o modifica.
+    pub keys: Vec<&'a str>,
+    /// Valores y elementos que el comando guarda.
+    pub values: Vec<&'a str>,
+    /// Clave del string al que el comando agrega bytes al final, y la cantidad de bytes agregados.
+    pub appended: Option<(&'a str, usize)>,
+    /// Clave de la colección a la que el comando agrega elementos, y los elementos agregados. Los
+    /// miembros de los sets y sorted sets no se repiten.
+    pub added: Option<(&'a str, Vec<&'a str>)>,
+}
+
 #[derive(Debug)]
-#[allow(dead_code)]
+#[allow(dead_code, clippy::enum_variant_names)]
 /// Enum usado para representar los comandos que puede ejecutar nuestra base de datos redis.
 pub enum Command {
     // Server
-    Ping,
-    Flushdb,
+    Ping {
+        message: Option<String>,
+    },
+    Echo {
+        message: String,
+    },
+    Flushdb {
+        asynchronous: bool,
+    },
+    Flushall {
+        asynchronous: bool,
+    },
     Dbsize,
-    Monitor,
+    Monitor {
+        client_id: u64,
+    },
+    Quit,
     Info {
         param: InfoParam,
     },
-    ConfigGet,
+    ConfigGet {
+        patterns: Vec<String>,
+    },
+    ConfigResetstat,
     ConfigSet {
         parameter: String,
         value: String,
     },
+    Auth {
+        password: String,
+    },
+    Client {
+        param: ClientParam,
+        client_id: u64,
+    },
+    Cluster {
+        param: ClusterParam,
+    },
+    Memory {
+        param: MemoryParam,
+    },
+    Slowlog {
+        param: SlowlogParam,
+    },
+    Debug {
+        param: DebugParam,
+    },
 
     // System
     Store {
@@ -29,7 +95,9 @@ pub enum Command {
         path: String,
     },
     AddClient,
-    RemoveClient,
+    RemoveClient {
+        client_id: u64,
+    },
 
     // Strings
     Get {
@@ -38,14 +106,23 @@ pub enum Command {
     Set {
         key: String,
         value: String,
+        options: SetOptions,
+    },
+    Setnx {
+        key: String,
+        value: String,
     },
     Incrby {
         key: String,
-        increment: u32,
+        increment: i64,
     },
     Decrby {
         key: String,
-        decrement: u32,
+        decrement: i64,
+    },
+    Incrbyfloat {
+        key: String,
+        increment: f64,
     },
     Getdel {
         key: String,
@@ -64,10 +141,40 @@ pub enum Command {
     Mset {
         key_values: Vec<(String, String)>,
     },
+    Msetnx {
+        key_values: Vec<(String, String)>,
+    },
     Strlen {
         key: String,
     },
 
+    // Bitmaps
+    Setbit {
+        key: String,
+        offset: usize,
+        value: bool,
+    },
+    Getbit {
+        key: String,
+        offset: usize,
+    },
+    Bitcount {
+        key: String,
+        range: Option<BitRange>,
+    },
+    Bitpos {
+        key: String,
+        bit: bool,
+        start: i64,
+        end: Option<i64>,
+        unit: BitUnit,
+    },
+    Bitop {
+        operation: BitOperation,
+        destkey: String,
+        keys: Vec<String>,
+    },
+
     // Keys
     Copy {
         key_origin: String,
@@ -76,36 +183,78 @@ pub enum Command {
     Del {
         keys: Vec<String>,
     },
+    Dump {
+        key: String,
+    },
     Exists {
         keys: Vec<String>,
     },
     Expire {
         key: String,
         ttl: Duration,
+        conditions: Vec<ExpireCondition>,
     },
     Expireat {
         key: String,
         ttl: SystemTime,
+        conditions: Vec<ExpireCondition>,
     },
     Keys {
         pattern: String,
     },
+    Pexpire {
+        key: String,
+        ttl: Duration,
+        conditions: Vec<ExpireCondition>,
+    },
+    Pexpireat {
+        key: String,
+        ttl: SystemTime,
+        conditions: Vec<ExpireCondition>,
+    },
     Persist {
         key: String,
     },
+    Randomkey,
     Rename {
         key_origin: String,
         key_destination: String,
     },
+    Renamenx {
+        key_origin: String,
+        key_destination: String,
+    },
+    Restore {
+        key: String,
+        ttl: Option<SystemTime>,
+        payload: String,
+        replace: bool,
+    },
+    Object {
+        param: ObjectParam,
+    },
     Sort {
         key: String,
+        options: SortOptions,
     },
     Touch {
         keys: Vec<String>,
     },
+    Unlink {
+        keys: Vec<String>,
+    },
     Ttl {
         key: String,
     },
+    Pttl {
+        key: String,
+    },
+    Expiretime {
+        key: String,
+    },
+    Pexpiretime {
+        key: String,
+    },
     Type {
         key: String,
     },
@@ -128,7 +277,7 @@ pub enum Command {
     },
     Lpop {
         key: String,
-        count: usize,
+        count: Option<usize>,
     },
     Lrange {
         key: String,
@@ -147,7 +296,7 @@ pub enum Command {
     },
     Rpop {
         key: String,
-        count: usize,
+        count: Option<usize>,
     },
     Rpush {
         key: String,
@@ -157,6 +306,14 @@ pub enum Command {
         key: String,
         value: Vec<String>,
     },
+    Blpop {
+        keys: Vec<String>,
+        timeout: Option<Duration>,
+    },
+    Brpop {
+        keys: Vec<String>,
+        timeout: Option<Duration>,
+    },
 
     // Sets
     Sadd {
@@ -173,10 +330,184 @@ pub enum Command {
     Smembers {
         key: String,
     },
+    Smismember {
+        key: String,
+        members: Vec<String>,
+    },
+    Sinter {
+        keys: Vec<String>,
+    },
+    Sintercard {
+        keys: Vec<String>,
+        limit: usize,
+    },
+    Sunion {
+        keys: Vec<String>,
+    },
+    Sdiff {
+        keys: Vec<String>,
+    },
+    Spop {
+        key: String,
+        count: Option<usize>,
+    },
+    Srandmember {
+        key: String,
+        count: Option<i64>,
+    },
     Srem {
         key: String,
         values: HashSet<String>,
     },
+    Sscan {
+        key: String,
+        cursor: u64,
+        pattern: Option<String>,
+        count: usize,
+    },
+
+    // Sorted Sets
+    Bzpopmax {
+        keys: Vec<String>,
+        timeout: Option<Duration>,
+    },
+    Bzpopmin {
+        keys: Vec<String>,
+        timeout: Option<Duration>,
+    },
+    Zadd {
+        key: String,
+        members: Vec<(f64, String)>,
+    },
+    Zcard {
+        key: String,
+    },
+    Zincrby {
+        key: String,
+        increment: f64,
+        member: String,
+    },
+    Zinterstore {
+        destination: String,
+        keys: Vec<String>,
+        weights: Vec<f64>,
+        aggregate: Aggregate,
+    },
+    Zpopmax {
+        key: String,
+        count: Option<usize>,
+    },
+    Zpopmin {
+        key: String,
+        count: Option<usize>,
+    },
+    Zrange {
+        key: String,
+        start: i64,
+        stop: i64,
+        with_scores: bool,
+    },
+    Zrangebyscore {
+        key: String,
+        min: ScoreBound,
+        max: ScoreBound,
+        with_scores: bool,
+    },
+    Zrank {
+        key: String,
+        member: String,
+    },
+    Zrem {
+        key: String,
+        members: Vec<String>,
+    },
+    Zscore {
+        key: String,
+        member: String,
+    },
+    Zunionstore {
+        destination: String,
+        keys: Vec<String>,
+        weights: Vec<f64>,
+        aggregate: Aggregate,
+    },
+
+    // Geo
+    Geoadd {
+        key: String,
+        options: GeoAddOptions,
+        members: Vec<(f64, f64, String)>,
+    },
+    Geodist {
+        key: String,
+        member1: String,
+        member2: String,
+        unit: GeoUnit,
+    },
+    Geopos {
+        key: String,
+        members: Vec<String>,
+    },
+    Geosearch {
+        key: String,
+        options: GeoSearchOptions,
+    },
+
+    // Streams
+    Xadd {
+        key: String,
+        id: Option<StreamId>,
+        max_len: Option<usize>,
+        fields: Vec<(String, String)>,
+    },
+    Xlen {
+        key: String,
+    },
+    Xrange {
+        key: String,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+    },
+    Xrevrange {
+        key: String,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+    },
+    Xread {
+        count: Option<usize>,
+        streams: Vec<(String, Option<StreamId>)>,
+    },
+    Xgroup {
+        key: String,
+        param: XgroupParam,
+    },
+    Xreadgroup {
+        group: String,
+        consumer: String,
+        count: Option<usize>,
+        no_ack: bool,
+        streams: Vec<(String, Option<StreamId>)>,
+    },
+    Xack {
+        key: String,
+        group: String,
+        ids: Vec<StreamId>,
+    },
+    Xpending {
+        key: String,
+        group: String,
+        range: Option<PendingRange>,
+    },
+    Xclaim {
+        key: String,
+        group: String,
+        consumer: String,
+        min_idle: Duration,
+        ids: Vec<StreamId>,
+        just_id: bool,
+    },
 
     // pubsub
     Pubsub {
@@ -184,7 +515,7 @@ pub enum Command {
     },
     Subscribe {
         channels: Vec<String>,
-        client_id: String,
+        client_id: u64,
     },
     Publish {
         channel: String,
@@ -192,22 +523,49 @@ pub enum Command {
     },
     Unsubscribe {
         channels: Vec<String>,
-        client_id: String,
+        client_id: u64,
+    },
+    Command {
+        param: CommandParam,
+    },
+
+    // Scripting
+    Eval {
+        script: String,
+        keys: Vec<String>,
+        args: Vec<String>,
+    },
+    Evalsha {
+        sha1: String,
+        keys: Vec<String>,
+        args: Vec<String>,
+    },
+    Script {
+        param: ScriptParam,
     },
-    Command,
 }
 
 impl Command {
     pub fn as_str(&self) -> &'static str {
         match *self {
             // Server
-            Command::Ping => "ping",
-            Command::Flushdb => "flushdb",
+            Command::Ping { .. } => "ping",
+            Command::Echo { .. } => "echo",
+            Command::Flushdb { .. } => "flushdb",
+            Command::Flushall { .. } => "flushall",
             Command::Dbsize => "dbsize",
-            Command::Monitor => "monitor",
+            Command::Monitor { .. } => "monitor",
+            Command::Quit => "quit",
             Command::Info { .. } => "info",
-            Command::ConfigGet => "config get",
+            Command::ConfigGet { .. } => "config get",
+            Command::Client { .. } => "client",
+            Command::Cluster { .. } => "cluster",
+            Command::Memory { .. } => "memory",
+            Command::Slowlog { .. } => "slowlog",
+            Command::Debug { .. } => "debug",
             Command::ConfigSet { .. } => "config set",
+            Command::ConfigResetstat => "config resetstat",
+            Command::Auth { .. } => "auth",
 
             // Strings
             Command::Append { .. } => "append",
@@ -216,23 +574,44 @@ impl Command {
             Command::Getdel { .. } => "getdel",
             Command::Getset { .. } => "getset",
             Command::Incrby { .. } => "incrby",
+            Command::Incrbyfloat { .. } => "incrbyfloat",
             Command::Mget { .. } => "mget",
             Command::Mset { .. } => "mset",
+            Command::Msetnx { .. } => "msetnx",
             Command::Set { .. } => "set",
+            Command::Setnx { .. } => "setnx",
             Command::Strlen { .. } => "strlen",
 
+            // Bitmaps
+            Command::Setbit { .. } => "setbit",
+            Command::Getbit { .. } => "getbit",
+            Command::Bitcount { .. } => "bitcount",
+            Command::Bitpos { .. } => "bitpos",
+            Command::Bitop { .. } => "bitop",
+
             // Keys
             Command::Copy { .. } => "copy",
             Command::Del { .. } => "del",
+            Command::Dump { .. } => "dump",
             Command::Exists { .. } => "exists",
             Command::Expire { .. } => "expire",
             Command::Expireat { .. } => "expireat",
             Command::Persist { .. } => "persist",
+            Command::Pexpire { .. } => "pexpire",
+            Command::Pexpireat { .. } => "pexpireat",
+            Command::Pttl { .. } => "pttl",
+            Command::Expiretime { .. } => "expiretime",
+            Command::Pexpiretime { .. } => "pexpiretime",
+            Command::Randomkey => "randomkey",
             Command::Rename { .. } => "rename",
+            Command::Renamenx { .. } => "renamenx",
+            Command::Restore { .. } => "restore",
             Command::Keys { .. } => "keys",
+            Command::Object { .. } => "object",
             Command::Touch { .. } => "touch",
             Command::Ttl { .. } => "ttl",
             Command::Type { .. } => "type",
+            Command::Unlink { .. } => "unlink",
 
             // Lists
             Command::Lindex { .. } => "lindex",
@@ -246,20 +625,165 @@ impl Command {
             Command::Rpop { .. } => "rpop",
             Command::Rpush { .. } => "rpush",
             Command::Rpushx { .. } => "rpushx",
+            Command::Blpop { .. } => "blpop",
+            Command::Brpop { .. } => "brpop",
 
             // Sets
             Command::Sadd { .. } => "sadd",
             Command::Scard { .. } => "scard",
             Command::Sismember { .. } => "sismember",
             Command::Smembers { .. } => "smember",
+            Command::Smismember { .. } => "smismember",
+            Command::Sinter { .. } => "sinter",
+            Command::Sintercard { .. } => "sintercard",
+            Command::Sunion { .. } => "sunion",
+            Command::Sdiff { .. } => "sdiff",
+            Command::Spop { .. } => "spop",
+            Command::Srandmember { .. } => "srandmember",
             Command::Srem { .. } => "srem",
+            Command::Sscan { .. } => "sscan",
+
+            // Sorted Sets
+            Command::Bzpopmax { .. } => "bzpopmax",
+            Command::Bzpopmin { .. } => "bzpopmin",
+            Command::Zadd { .. } => "zadd",
+            Command::Zcard { .. } => "zcard",
+            Command::Zincrby { .. } => "zincrby",
+            Command::Zinterstore { .. } => "zinterstore",
+            Command::Zpopmax { .. } => "zpopmax",
+            Command::Zpopmin { .. } => "zpopmin",
+            Command::Zrange { .. } => "zrange",
+            Command::Zrangebyscore { .. } => "zrangebyscore",
+            Command::Zrank { .. } => "zrank",
+            Command::Zrem { .. } => "zrem",
+            Command::Zscore { .. } => "zscore",
+            Command::Zunionstore { .. } => "zunionstore",
+
+            // Geo
+            Command::Geoadd { .. } => "geoadd",
+            Command::Geodist { .. } => "geodist",
+            Command::Geopos { .. } => "geopos",
+            Command::Geosearch { .. } => "geosearch",
+
+            // Streams
+            Command::Xadd { .. } => "xadd",
+            Command::Xlen { .. } => "xlen",
+            Command::Xrange { .. } => "xrange",
+            Command::Xrevrange { .. } => "xrevrange",
+            Command::Xread { .. } => "xread",
+            Command::Xgroup { .. } => "xgroup",
+            Command::Xreadgroup { .. } => "xreadgroup",
+            Command::Xack { .. } => "xack",
+            Command::Xpending { .. } => "xpending",
+            Command::Xclaim { .. } => "xclaim",
 
             // Pubsub
             Command::Pubsub { .. } => "pubsub",
             Command::Subscribe { .. } => "subscribe",
             Command::Publish { .. } => "publish",
             Command::Unsubscribe { .. } => "unsubscribe",
+            Command::Command { .. } => "command",
+
+            // Scripting
+            Command::Eval { .. } => "eval",
+            Command::Evalsha { .. } => "evalsha",
+            Command::Script { .. } => "script",
             _ => "",
         }
     }
+
+    /// Devuelve las claves y los valores que el comando escribe en la base de datos. Los comandos
+    /// que sólo leen no escriben ningún dato.
+    pub fn written_data(&self) -> WrittenData<'_> {
+        let mut data = WrittenData::default();
+        match self {
+            Command::Set { key, value, .. }
+            | Command::Setnx { key, value }
+            | Command::Getset { key, value } => {
+                data.keys.push(key);
+                data.values.push(value);
+            }
+            Command::Mset { key_values } | Command::Msetnx { key_values } => {
+                for (key, value) in key_values {
+                    data.keys.push(key);
+                    data.values.push(value);
+                }
+            }
+            Command::Append { key, value } => {
+                data.keys.push(key);
+                data.appended = Some((key, value.len()));
+            }
+            Command::Incrby { key, .. }
+            | Command::Decrby { key, .. }
+            | Command::Incrbyfloat { key, .. }
+            | Command::Setbit { key, .. } => data.keys.push(key),
+            Command::Bitop { destkey, .. } => data.keys.push(destkey),
+            Command::Copy {
+                key_destination, ..
+            }
+            | Command::Rename {
+                key_destination, ..
+            }
+            | Command::Renamenx {
+                key_destination, ..
+            } => data.keys.push(key_destination),
+            Command::Restore { key, payload, .. } => {
+                data.keys.push(key);
+                data.values.push(payload);
+            }
+            Command::Lpush { key, value }
+            | Command::Lpushx { key, value }
+            | Command::Rpush { key, value }
+            | Command::Rpushx { key, value } => {
+                data.keys.push(key);
+                data.values.extend(value.iter().map(String::as_str));
+                data.added = Some((key, value.iter().map(String::as_str).collect()));
+            }
+            Command::Lset { key, element, .. } => {
+                data.keys.push(key);
+                data.values.push(element);
+            }
+            Command::Sadd { key, values } => {
+                data.keys.push(key);
+                data.values.extend(values.iter().map(String::as_str));
+                data.added = Some((key, values.iter().map(String::as_str).collect()));
+            }
+            Command::Zadd { key, members } => {
+                data.keys.push(key);
+                data.values
+                    .extend(members.iter().map(|(_, member)| member.as_str()));
+                let unique: HashSet<&str> = members.iter().map(|(_, m)| m.as_str()).collect();
+                data.added = Some((key, unique.into_iter().collect()));
+            }
+            Command::Zincrby { key, member, .. } => {
+                data.keys.push(key);
+                data.values.push(member);
+                data.added = Some((key, vec![member.as_str()]));
+            }
+            Command::Geoadd { key, members, .. } => {
+                data.keys.push(key);
+                data.values
+                    .extend(members.iter().map(|(_, _, member)| member.as_str()));
+                let unique: HashSet<&str> = members.iter().map(|(_, _, m)| m.as_str()).collect();
+                data.added = Some((key, unique.into_iter().collect()));
+            }
+            Command::Xadd { key, fields, .. } => {
+                data.keys.push(key);
+                for (field, value) in fields {
+                    data.values.push(field);
+                    data.values.push(value);
+                }
+            }
+            Command::Zunionstore { destination, .. } | Command::Zinterstore { destination, .. } => {
+                data.keys.push(destination)
+            }
+            Command::Sort { options, .. } => {
+                if let Some(store) = &options.store {
+                    data.keys.push(store);
+                }
+            }
+            _ => {}
+        }
+        data
+    }
 }
@@ -0,0 +1,14 @@
+#[derive(Debug)]
+/// CommandParam: Enum usado para representar los subcomandos permitidos para el Command::Command.
+pub enum CommandParam {
+    /// Representa el comando sin subcomando, que describe todos los comandos del servidor.
+    All,
+    /// Representa el subcomando Count, que devuelve la cantidad de comandos del servidor.
+    Count,
+    /// Representa el subcomando Info, que describe los comandos indicados, o todos si no se indica
+    /// ninguno.
+    Info(Vec<String>),
+    /// Representa el subcomando Docs, que devuelve la documentación de los comandos indicados, o
+    /// de todos si no se indica ninguno.
+    Docs(Vec<String>),
+}
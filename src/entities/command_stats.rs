@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// CommandStat: Estadísticas de ejecución de un tipo de comando.
+pub struct CommandStat {
+    /// Cantidad de veces que se ejecutó el comando.
+    pub calls: u64,
+    /// Microsegundos totales que demoraron las ejecuciones.
+    pub usec: u64,
+    /// Microsegundos que demoró la ejecución más lenta.
+    pub max_usec: u64,
+}
+
+#[derive(Debug, Default)]
+/// CommandStats: Estadísticas de ejecución de cada tipo de comando, que se muestran en la sección
+/// Commandstats de INFO.
+pub struct CommandStats {
+    stats: HashMap<&'static str, CommandStat>,
+}
+
+impl CommandStats {
+    /// Crea las estadísticas vacías.
+    pub fn new() -> Self {
+        CommandStats::default()
+    }
+
+    /// Registra una ejecución del comando `name` que demoró `elapsed`.
+    pub fn record(&mut self, name: &'static str, elapsed: Duration) {
+        let usec = elapsed.as_micros() as u64;
+        let stat = self.stats.entry(name).or_default();
+        stat.calls += 1;
+        stat.usec += usec;
+        stat.max_usec = stat.max_usec.max(usec);
+    }
+
+    /// Descarta todas las estadísticas registradas.
+    pub fn reset(&mut self) {
+        self.stats.clear();
+    }
+
+    /// Describe las estadísticas con el formato de la sección Commandstats de INFO, ordenadas por
+    /// el nombre del comando. Los subcomandos se muestran como `comando|subcomando`.
+    pub fn info(&self) -> String {
+        let mut names: Vec<&&str> = self.stats.keys().collect();
+        names.sort();
+
+        let mut lines = vec!["# Commandstats".to_string()];
+        for name in names {
+            let stat = &self.stats[*name];
+            lines.push(format!(
+                "cmdstat_{}:calls={},usec={},usec_per_call={:.2},max_usec={}",
+                name.replace(' ', "|"),
+                stat.calls,
+                stat.usec,
+                stat.usec as f64 / stat.calls as f64,
+                stat.max_usec
+            ));
+        }
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::command_stats::{CommandStat, CommandStats};
+    use std::time::Duration;
+
+    #[test]
+    fn test_record_and_reset() {
+        let mut stats = CommandStats::new();
+        stats.record("get", Duration::from_micros(10));
+        stats.record("get", Duration::from_micros(5));
+
+        assert_eq!(
+            Some(&CommandStat {
+                calls: 2,
+                usec: 15,
+                max_usec: 10
+            }),
+            stats.stats.get("get")
+        );
+
+        stats.reset();
+        assert!(stats.stats.is_empty());
+    }
+
+    #[test]
+    fn test_info_format() {
+        let mut stats = CommandStats::new();
+        stats.record("set", Duration::from_micros(4));
+        stats.record("config set", Duration::from_micros(3));
+
+        assert_eq!(
+            "# Commandstats\r\n\
+             cmdstat_config|set:calls=1,usec=3,usec_per_call=3.00,max_usec=3\r\n\
+             cmdstat_set:calls=1,usec=4,usec_per_call=4.00,max_usec=4\r\n",
+            stats.info()
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use crate::entities::redis_element::RedisElement;
+
+#[derive(Debug, PartialEq)]
+/// CommandSpec: Descripción estática de un comando, con el formato que usa Redis en COMMAND.
+pub struct CommandSpec {
+    /// Nombre del comando, en minúsculas.
+    pub name: &'static str,
+    /// Cantidad de argumentos, incluyendo el nombre del comando. Si es negativa, indica la
+    /// cantidad mínima de argumentos.
+    pub arity: i64,
+    /// Flags del comando (write, readonly, fast, ...).
+    pub flags: &'static [&'static str],
+    /// Posición del primer argumento que es una clave, o 0 si el comando no recibe claves.
+    pub first_key: i64,
+    /// Posición del último argumento que es una clave. Si es negativa, se cuenta desde el final.
+    pub last_key: i64,
+    /// Distancia entre dos claves consecutivas.
+    pub step: i64,
+    /// Grupo al que pertenece el comando en la documentación de Redis.
+    pub group: &'static str,
+}
+
+const WRITE: &[&str] = &["write"];
+const WRITE_FAST: &[&str] = &["write", "fast"];
+const WRITE_DENYOOM: &[&str] = &["write", "denyoom"];
+const WRITE_DENYOOM_FAST: &[&str] = &["write", "denyoom", "fast"];
+const READONLY: &[&str] = &["readonly"];
+const READONLY_FAST: &[&str] = &["readonly", "fast"];
+const BLOCKING: &[&str] = &["write", "noscript", "blocking"];
+const ADMIN: &[&str] = &["admin", "noscript", "loading", "stale"];
+const LOADING_STALE: &[&str] = &["loading", "stale"];
+const PUBSUB: &[&str] = &["pubsub", "noscript", "loading", "stale"];
+
+/// Crea la descripción de un comando. `keys` son la posición de la primera clave, la de la última
+/// y la distancia entre claves.
+const fn spec(
+    name: &'static str,
+    arity: i64,
+    flags: &'static [&'static str],
+    keys: (i64, i64, i64),
+    group: &'static str,
+) -> CommandSpec {
+    CommandSpec {
+        name,
+        arity,
+        flags,
+        first_key: keys.0,
+        last_key: keys.1,
+        step: keys.2,
+        group,
+    }
+}
+
+/// Tabla de los comandos que soporta el servidor.
+pub const COMMAND_TABLE: &[CommandSpec] = &[
+    // Server
+    spec("ping", -1, &["fast"], (0, 0, 0), "connection"),
+    spec("echo", 2, &["fast"], (0, 0, 0), "connection"),
+    spec("flushdb", -1, WRITE, (0, 0, 0), "server"),
+    spec("flushall", -1, WRITE, (0, 0, 0), "server"),
+    spec("dbsize", 1, READONLY_FAST, (0, 0, 0), "server"),
+    spec("monitor", 1, ADMIN, (0, 0, 0), "server"),
+    spec("quit", -1, &["fast"], (0, 0, 0), "connection"),
+    spec("info", -1, LOADING_STALE, (0, 0, 0), "server"),
+    spec("store", -2, ADMIN, (0, 0, 0), "server"),
+    spec("load", -2, ADMIN, (0, 0, 0), "server"),
+    spec("config", -2, ADMIN, (0, 0, 0), "server"),
+    spec(
+        "auth",
+        -2,
+        &["noscript", "loading", "stale", "fast", "no_auth"],
+        (0, 0, 0),
+        "connection",
+    ),
+    spec(
+        "client",
+        -2,
+        &["noscript", "loading", "stale"],
+        (0, 0, 0),
+        "connection",
+    ),
+    spec("cluster", -2, &[], (0, 0, 0), "cluster"),
+    spec("memory", -2, READONLY, (0, 0, 0), "server"),
+    spec(
+        "slowlog",
+        -2,
+        &["admin", "random", "loading", "stale"],
+        (0, 0, 0),
+        "server",
+    ),
+    spec("command", -1, LOADING_STALE, (0, 0, 0), "server"),
+    spec("debug", -2, ADMIN, (0, 0, 0), "server"),
+    // Strings
+    spec("get", 2, READONLY_FAST, (1, 1, 1), "string"),
+    spec("getset", 3, WRITE_DENYOOM_FAST, (1, 1, 1), "string"),
+    spec("set", -3, WRITE_DENYOOM, (1, 1, 1), "string"),
+    spec("setnx", 3, WRITE_DENYOOM_FAST, (1, 1, 1), "string"),
+    spec("setex", 4, WRITE_DENYOOM, (1, 1, 1), "string"),
+    spec("psetex", 4, WRITE_DENYOOM, (1, 1, 1), "string"),
+    spec("incrby", 3, WRITE_DENYOOM_FAST, (1, 1, 1), "string"),
+    spec("decrby", 3, WRITE_DENYOOM_FAST, (1, 1, 1), "string"),
+    spec("incrbyfloat", 3, WRITE_DENYOOM_FAST, (1, 1, 1), "string"),
+    spec("getdel", 2, WRITE_FAST, (1, 1, 1), "string"),
+    spec("append", 3, WRITE_DENYOOM_FAST, (1, 1, 1), "string"),
+    spec("mget", -2, READONLY_FAST, (1, -1, 1), "string"),
+    spec("mset", -3, WRITE_DENYOOM, (1, -1, 2), "string"),
+    spec("msetnx", -3, WRITE_DENYOOM, (1, -1, 2), "string"),
+    spec("strlen", 2, READONLY_FAST, (1, 1, 1), "string"),
+    // Bitmaps
+    spec("setbit", 4, WRITE_DENYOOM, (1, 1, 1), "bitmap"),
+    spec("getbit", 3, READONLY_FAST, (1, 1, 1), "bitmap"),
+    spec("bitcount", -2, READONLY, (1, 1, 1), "bitmap"),
+    spec("bitpos", -3, READONLY, (1, 1, 1), "bitmap"),
+    spec("bitop", -4, WRITE_DENYOOM, (2, -1, 1), "bitmap"),
+    // Keys
+    spec("copy", -3, WRITE_DENYOOM, (1, 2, 1), "generic"),
+    spec("del", -2, WRITE, (1, -1, 1), "generic"),
+    spec("dump", 2, READONLY, (1, 1, 1), "generic"),
+    spec("exists", -2, READONLY_FAST, (1, -1, 1), "generic"),
+    spec("expire", -3, WRITE_FAST, (1, 1, 1), "generic"),
+    spec("expireat", -3, WRITE_FAST, (1, 1, 1), "generic"),
+    spec("persist", 2, WRITE_FAST, (1, 1, 1), "generic"),
+    spec("randomkey", 1, READONLY, (0, 0, 0), "generic"),
+    spec("rename", 3, WRITE, (1, 2, 1), "generic"),
+    spec("renamenx", 3, WRITE_FAST, (1, 2, 1), "generic"),
+    spec("restore", -4, WRITE_DENYOOM, (1, 1, 1), "generic"),
+    spec(
+        "sort",
+        -2,
+        &["write", "denyoom", "movablekeys"],
+        (1, 1, 1),
+        "generic",
+    ),
+    spec("object", -2, READONLY, (2, 2, 1), "generic"),
+    spec("touch", -2, READONLY_FAST, (1, -1, 1), "generic"),
+    spec("ttl", 2, READONLY_FAST, (1, 1, 1), "generic"),
+    spec("pexpire", -3, WRITE_FAST, (1, 1, 1), "generic"),
+    spec("pexpireat", -3, WRITE_FAST, (1, 1, 1), "generic"),
+    spec("pttl", 2, READONLY_FAST, (1, 1, 1), "generic"),
+    spec("expiretime", 2, READONLY_FAST, (1, 1, 1), "generic"),
+    spec("pexpiretime", 2, READONLY_FAST, (1, 1, 1), "generic"),
+    spec("type", 2, READONLY_FAST, (1, 1, 1), "generic"),
+    spec("unlink", -2, WRITE_FAST, (1, -1, 1), "generic"),
+    spec("keys", 2, READONLY, (0, 0, 0), "generic"),
+    // Lists
+    spec("lindex", 3, READONLY, (1, 1, 1), "list"),
+    spec("llen", 2, READONLY_FAST, (1, 1, 1), "list"),
+    spec("lpop", -2, WRITE_FAST, (1, 1, 1), "list"),
+    spec("lpush", -3, WRITE_DENYOOM_FAST, (1, 1, 1), "list"),
+    spec("lpushx", -3, WRITE_DENYOOM_FAST, (1, 1, 1), "list"),
+    spec("lrange", 4, READONLY, (1, 1, 1), "list"),
+    spec("lrem", 4, WRITE, (1, 1, 1), "list"),
+    spec("lset", 4, WRITE_DENYOOM, (1, 1, 1), "list"),
+    spec("rpop", -2, WRITE_FAST, (1, 1, 1), "list"),
+    spec("rpush", -3, WRITE_DENYOOM_FAST, (1, 1, 1), "list"),
+    spec("rpushx", -3, WRITE_DENYOOM_FAST, (1, 1, 1), "list"),
+    spec("blpop", -3, BLOCKING, (1, -2, 1), "list"),
+    spec("brpop", -3, BLOCKING, (1, -2, 1), "list"),
+    // Sets
+    spec("sadd", -3, WRITE_DENYOOM_FAST, (1, 1, 1), "set"),
+    spec("scard", 2, READONLY_FAST, (1, 1, 1), "set"),
+    spec("sismember", 3, READONLY_FAST, (1, 1, 1), "set"),
+    spec("smembers", 2, READONLY, (1, 1, 1), "set"),
+    spec("smismember", -3, READONLY_FAST, (1, 1, 1), "set"),
+    spec("sinter", -2, READONLY, (1, -1, 1), "set"),
+    spec(
+        "sintercard",
+        -3,
+        &["readonly", "movablekeys"],
+        (0, 0, 0),
+        "set",
+    ),
+    spec("sunion", -2, READONLY, (1, -1, 1), "set"),
+    spec("sdiff", -2, READONLY, (1, -1, 1), "set"),
+    spec("spop", -2, WRITE_FAST, (1, 1, 1), "set"),
+    spec("srandmember", -2, READONLY, (1, 1, 1), "set"),
+    spec("srem", -3, WRITE_FAST, (1, 1, 1), "set"),
+    spec("sscan", -3, READONLY, (1, 1, 1), "set"),
+    // Sorted Sets
+    spec("bzpopmax", -3, BLOCKING, (1, -2, 1), "sorted-set"),
+    spec("bzpopmin", -3, BLOCKING, (1, -2, 1), "sorted-set"),
+    spec("zadd", -4, WRITE_DENYOOM_FAST, (1, 1, 1), "sorted-set"),
+    spec("zcard", 2, READONLY_FAST, (1, 1, 1), "sorted-set"),
+    spec("zincrby", 4, WRITE_DENYOOM_FAST, (1, 1, 1), "sorted-set"),
+    spec(
+        "zinterstore",
+        -4,
+        &["write", "denyoom", "movablekeys"],
+        (1, 1, 1),
+        "sorted-set",
+    ),
+    spec("zpopmax", -2, WRITE_FAST, (1, 1, 1), "sorted-set"),
+    spec("zpopmin", -2, WRITE_FAST, (1, 1, 1), "sorted-set"),
+    spec("zrange", -4, READONLY, (1, 1, 1), "sorted-set"),
+    spec("zrangebyscore", -4, READONLY, (1, 1, 1), "sorted-set"),
+    spec("zrank", 3, READONLY_FAST, (1, 1, 1), "sorted-set"),
+    spec("zrem", -3, WRITE_FAST, (1, 1, 1), "sorted-set"),
+    spec("zscore", 3, READONLY_FAST, (1, 1, 1), "sorted-set"),
+    spec(
+        "zunionstore",
+        -4,
+        &["write", "denyoom", "movablekeys"],
+        (1, 1, 1),
+        "sorted-set",
+    ),
+    // Geo
+    spec("geoadd", -5, WRITE_DENYOOM, (1, 1, 1), "geo"),
+    spec("geodist", -4, READONLY, (1, 1, 1), "geo"),
+    spec("geopos", -2, READONLY, (1, 1, 1), "geo"),
+    spec("geosearch", -6, READONLY, (1, 1, 1), "geo"),
+    // Streams
+    spec("xadd", -5, WRITE_DENYOOM_FAST, (1, 1, 1), "stream"),
+    spec("xlen", 2, READONLY_FAST, (1, 1, 1), "stream"),
+    spec("xrange", -4, READONLY, (1, 1, 1), "stream"),
+    spec("xrevrange", -4, READONLY, (1, 1, 1), "stream"),
+    spec(
+        "xread",
+        -4,
+        &["readonly", "blocking", "movablekeys"],
+        (0, 0, 0),
+        "stream",
+    ),
+    spec("xgroup", -2, WRITE, (2, 2, 1), "stream"),
+    spec(
+        "xreadgroup",
+        -7,
+        &["write", "blocking", "movablekeys"],
+        (0, 0, 0),
+        "stream",
+    ),
+    spec("xack", -4, WRITE_FAST, (1, 1, 1), "stream"),
+    spec("xpending", -3, READONLY, (1, 1, 1), "stream"),
+    spec("xclaim", -6, WRITE_FAST, (1, 1, 1), "stream"),
+    // PubSub
+    spec("pubsub", -2, PUBSUB, (0, 0, 0), "pubsub"),
+    spec("subscribe", -2, PUBSUB, (0, 0, 0), "pubsub"),
+    spec(
+        "publish",
+        3,
+        &["pubsub", "loading", "stale", "fast"],
+        (0, 0, 0),
+        "pubsub",
+    ),
+    spec("unsubscribe", -1, PUBSUB, (0, 0, 0), "pubsub"),
+    // Scripting
+    spec(
+        "eval",
+        -3,
+        &["noscript", "movablekeys"],
+        (0, 0, 0),
+        "scripting",
+    ),
+    spec(
+        "evalsha",
+        -3,
+        &["noscript", "movablekeys"],
+        (0, 0, 0),
+        "scripting",
+    ),
+    spec("script", -2, &["noscript"], (0, 0, 0), "scripting"),
+];
+
+impl CommandSpec {
+    /// Devuelve si el comando acepta `argc` argumentos, contando el nombre del comando.
+    pub fn accepts(&self, argc: usize) -> bool {
+        if self.arity >= 0 {
+            argc as i64 == self.arity
+        } else {
+            argc as i64 >= -self.arity
+        }
+    }
+
+    /// Describe el comando con el formato de COMMAND INFO: nombre, aridad, flags, primera clave,
+    /// última clave y distancia entre claves.
+    pub fn info(&self) -> RedisElement {
+        RedisElement::Array(vec![
+            RedisElement::String(self.name.to_string()),
+            RedisElement::Integer(self.arity),
+            RedisElement::Array(
+                self.flags
+                    .iter()
+                    .map(|flag| RedisElement::SimpleString(flag.to_string()))
+                    .collect(),
+            ),
+            RedisElement::Integer(self.first_key),
+            RedisElement::Integer(self.last_key),
+            RedisElement::Integer(self.step),
+        ])
+    }
+
+    /// Describe la documentación del comando con el formato de COMMAND DOCS.
+    pub fn docs(&self) -> RedisElement {
+        RedisElement::Array(vec![
+            RedisElement::String(self.name.to_string()),
+            RedisElement::Array(vec![
+                RedisElement::String("group".to_string()),
+                RedisElement::String(self.group.to_string()),
+                RedisElement::String("arity".to_string()),
+                RedisElement::Integer(self.arity),
+            ]),
+        ])
+    }
+}
+
+/// Busca la descripción de un comando por su nombre, sin distinguir mayúsculas de minúsculas.
+pub fn lookup(name: &str) -> Option<&'static CommandSpec> {
+    COMMAND_TABLE
+        .iter()
+        .find(|spec| spec.name.eq_ignore_ascii_case(name))
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::command_table::{lookup, COMMAND_TABLE};
+    use crate::entities::redis_element::RedisElement;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_lookup_and_arity() {
+        let get = lookup("GET").unwrap();
+        assert!(get.accepts(2));
+        assert!(!get.accepts(3));
+
+        let mset = lookup("mset").unwrap();
+        assert!(!mset.accepts(2));
+        assert!(mset.accepts(5));
+        assert!(lookup("nonexistent").is_none());
+    }
+
+    #[test]
+    fn test_command_names_are_unique() {
+        let names: HashSet<&str> = COMMAND_TABLE.iter().map(|spec| spec.name).collect();
+        assert_eq!(COMMAND_TABLE.len(), names.len());
+    }
+
+    #[test]
+    fn test_info_format() {
+        assert_eq!(
+            RedisElement::Array(vec![
+                RedisElement::String("mset".to_string()),
+                RedisElement::Integer(-3),
+                RedisElement::Array(vec![
+                    RedisElement::SimpleString("write".to_string()),
+                    RedisElement::SimpleString("denyoom".to_string()),
+                ]),
+                RedisElement::Integer(1),
+                RedisElement::Integer(-1),
+                RedisElement::Integer(2),
+            ]),
+            lookup("mset").unwrap().info()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::net::{Shutdown, TcpStream};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug)]
+/// ClientInfo: Representa a un cliente conectado al servidor.
+pub struct ClientInfo {
+    /// Identificador único del cliente.
+    pub id: u64,
+    /// Dirección (ip:puerto) desde la cual se conectó el cliente.
+    pub addr: String,
+    /// Nombre asignado por el cliente mediante CLIENT SETNAME.
+    pub name: String,
+    /// Momento en el cual se conectó el cliente.
+    pub connected_at: SystemTime,
+    /// Momento en el cual el cliente envió su último comando.
+    pub last_interaction: SystemTime,
+    /// Nombre del último comando enviado por el cliente.
+    pub last_command: String,
+    /// Copia del socket del cliente, usada para cerrar la conexión mediante CLIENT KILL.
+    connection: Option<TcpStream>,
+}
+
+impl ClientInfo {
+    /// Devuelve la descripción del cliente con el formato de CLIENT LIST, indicando la cantidad de
+    /// canales y patrones a los que está subscripto.
+    pub fn describe(&self, sub: usize, psub: usize) -> String {
+        let now = SystemTime::now();
+        format!(
+            "id={} addr={} name={} age={} idle={} db=0 sub={} psub={} cmd={}",
+            self.id,
+            self.addr,
+            self.name,
+            elapsed_secs(self.connected_at, now),
+            elapsed_secs(self.last_interaction, now),
+            sub,
+            psub,
+            self.last_command
+        )
+    }
+}
+
+/// Devuelve los segundos transcurridos desde `since` hasta `now`.
+fn elapsed_secs(since: SystemTime, now: SystemTime) -> u64 {
+    now.duration_since(since)
+        .unwrap_or_else(|_| Duration::from_secs(0))
+        .as_secs()
+}
+
+#[derive(Debug, Default)]
+/// ConnectedClients: Registro de los clientes conectados al servidor, indexado por su id.
+///
+/// Los clientes se registran al ser aceptada su conexión y se quitan al desconectarse.
+pub struct ConnectedClients {
+    /// Identificador a asignar al próximo cliente registrado.
+    next_id: u64,
+    /// Mapa {id, ClientInfo} con los clientes conectados, ordenados por id.
+    clients: BTreeMap<u64, ClientInfo>,
+}
+
+impl ConnectedClients {
+    /// Constructor de un nuevo registro vacío.
+    pub fn new() -> Self {
+        ConnectedClients {
+            next_id: 1,
+            clients: BTreeMap::new(),
+        }
+    }
+
+    /// Registra un nuevo cliente conectado desde `addr` y devuelve el id asignado.
+    ///
+    /// Si se indica el socket del cliente, la conexión puede cerrarse con `kill`.
+    pub fn register(&mut self, addr: String, connection: Option<TcpStream>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let now = SystemTime::now();
+        self.clients.insert(
+            id,
+            ClientInfo {
+                id,
+                addr,
+                name: String::new(),
+                connected_at: now,
+                last_interaction: now,
+                last_command: "NULL".to_string(),
+                connection,
+            },
+        );
+        id
+    }
+
+    /// Quita al cliente del registro.
+    pub fn unregister(&mut self, id: u64) {
+        self.clients.remove(&id);
+    }
+
+    /// Registra que el cliente envió el comando indicado.
+    pub fn touch(&mut self, id: u64, command: &str) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.last_interaction = SystemTime::now();
+            client.last_command = command.to_string();
+        }
+    }
+
+    /// Cierra la conexión del cliente y lo quita del registro. Devuelve `false` si el cliente no
+    /// estaba conectado.
+    pub fn kill(&mut self, id: u64) -> bool {
+        match self.clients.remove(&id) {
+            Some(client) => {
+                if let Some(connection) = client.connection {
+                    let _ = connection.shutdown(Shutdown::Both);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Asigna un nombre al cliente.
+    pub fn set_name(&mut self, id: u64, name: String) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.name = name;
+        }
+    }
+
+    /// Devuelve el cliente con el id indicado, si está conectado.
+    pub fn get(&self, id: u64) -> Option<&ClientInfo> {
+        self.clients.get(&id)
+    }
+
+    /// Devuelve un iterador sobre los clientes conectados, ordenados por id.
+    pub fn iter(&self) -> impl Iterator<Item = &ClientInfo> {
+        self.clients.values()
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::connected_clients::ConnectedClients;
+    use std::io::Read;
+    use std::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn test_register_assigns_increasing_ids() {
+        let mut clients = ConnectedClients::new();
+
+        let first = clients.register("127.0.0.1:5000".to_string(), None);
+        let second = clients.register("127.0.0.1:5001".to_string(), None);
+
+        assert!(first < second);
+        assert_eq!("127.0.0.1:5001", clients.get(second).unwrap().addr);
+        assert_eq!(
+            vec![first, second],
+            clients.iter().map(|client| client.id).collect::<Vec<u64>>()
+        );
+    }
+
+    #[test]
+    fn test_unregister_removes_client() {
+        let mut clients = ConnectedClients::new();
+
+        let id = clients.register("127.0.0.1:5000".to_string(), None);
+        clients.unregister(id);
+
+        assert!(clients.get(id).is_none());
+        assert_eq!(0, clients.iter().count());
+    }
+
+    #[test]
+    fn test_describe_client() {
+        let mut clients = ConnectedClients::new();
+
+        let id = clients.register("127.0.0.1:5000".to_string(), None);
+        clients.touch(id, "get");
+        clients.set_name(id, "worker".to_string());
+
+        assert_eq!(
+            format!(
+                "id={} addr=127.0.0.1:5000 name=worker age=0 idle=0 db=0 sub=2 psub=1 cmd=get",
+                id
+            ),
+            clients.get(id).unwrap().describe(2, 1)
+        );
+    }
+
+    #[test]
+    fn test_kill_closes_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server_side, addr) = listener.accept().unwrap();
+        let mut clients = ConnectedClients::new();
+
+        let id = clients.register(addr.to_string(), Some(server_side));
+
+        assert!(clients.kill(id));
+        assert!(!clients.kill(id));
+        assert!(clients.get(id).is_none());
+        let mut buffer = [0; 1];
+        assert_eq!(0, client.read(&mut buffer).unwrap());
+    }
+}
@@ -0,0 +1,39 @@
+use crate::entities::stream::StreamId;
+use std::time::Duration;
+
+#[derive(Debug)]
+/// XgroupParam: Enum usado para representar los subcomandos permitidos para el Command::Xgroup.
+///
+/// Los IDs en None representan a `$`, es decir, a la última entrada del stream.
+pub enum XgroupParam {
+    /// Crea un grupo, opcionalmente creando el stream si no existe (MKSTREAM).
+    Create {
+        group: String,
+        id: Option<StreamId>,
+        mkstream: bool,
+    },
+    /// Elimina un grupo.
+    Destroy { group: String },
+    /// Crea un consumidor en el grupo.
+    CreateConsumer { group: String, consumer: String },
+    /// Elimina un consumidor del grupo junto con sus entradas pendientes.
+    DelConsumer { group: String, consumer: String },
+    /// Modifica el ID de la última entrada entregada por el grupo.
+    SetId { group: String, id: Option<StreamId> },
+}
+
+#[derive(Debug, PartialEq)]
+/// PendingRange: Struct usado para representar la forma extendida del Command::Xpending, que
+/// detalla las entradas pendientes dentro de un rango de IDs.
+pub struct PendingRange {
+    /// Tiempo mínimo de inactividad de las entradas (IDLE).
+    pub min_idle: Option<Duration>,
+    /// Límite inferior del rango, inclusivo.
+    pub start: StreamId,
+    /// Límite superior del rango, inclusivo.
+    pub end: StreamId,
+    /// Cantidad máxima de entradas a devolver.
+    pub count: usize,
+    /// Consumidor al cual deben pertenecer las entradas.
+    pub consumer: Option<String>,
+}
@@ -0,0 +1,48 @@
+/// Polinomio de Jones reflejado, el que usa Redis para el checksum de los archivos RDB.
+const POLY: u64 = 0x95ac_9329_ac4b_c9b5;
+
+/// Tabla con el CRC de cada valor posible de un byte, calculada al compilar.
+const TABLE: [u64; 256] = table();
+
+const fn table() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Calcula el CRC64 de los bytes, partiendo del CRC `crc` de los bytes anteriores (0 si no hay).
+pub fn crc64(crc: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(crc, |crc, byte| {
+        TABLE[((crc ^ *byte as u64) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::crc64::crc64;
+
+    #[test]
+    fn test_crc64_matches_redis() {
+        assert_eq!(0xe9c6_d914_c4b8_d9ca, crc64(0, b"123456789"));
+        assert_eq!(0, crc64(0, b""));
+    }
+
+    #[test]
+    fn test_crc64_can_be_computed_in_parts() {
+        assert_eq!(crc64(0, b"123456789"), crc64(crc64(0, b"1234"), b"56789"));
+    }
+}
@@ -0,0 +1,13 @@
+use std::time::Duration;
+
+#[derive(Debug)]
+/// DebugParam: Enum usado para representar los subcomandos permitidos para el Command::Debug.
+pub enum DebugParam {
+    /// Representa el subcomando Sleep, que bloquea el hilo de la base de datos durante la duración.
+    Sleep { duration: Duration },
+    /// Representa el subcomando SetActiveExpire, que habilita o deshabilita el ciclo de expiración
+    /// activa de claves.
+    SetActiveExpire { enabled: bool },
+    /// Representa el subcomando Object, que muestra los metadatos internos de la clave.
+    Object { key: String },
+}
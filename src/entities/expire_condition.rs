@@ -0,0 +1,32 @@
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// ExpireCondition: Enum usado para representar las condiciones bajo las cuales los comandos
+/// EXPIRE, EXPIREAT, PEXPIRE y PEXPIREAT modifican la expiración de la clave.
+pub enum ExpireCondition {
+    /// Se modifica la expiración solo si la clave no tiene una (NX).
+    NoExpiry,
+    /// Se modifica la expiración solo si la clave ya tiene una (XX).
+    HasExpiry,
+    /// Se modifica la expiración solo si la nueva es posterior a la actual (GT). Las claves
+    /// persistentes se consideran con una expiración infinita.
+    Greater,
+    /// Se modifica la expiración solo si la nueva es anterior a la actual (LT). Las claves
+    /// persistentes se consideran con una expiración infinita.
+    Less,
+}
+
+impl ExpireCondition {
+    /// Devuelve si se cumple la condición para reemplazar la expiración `current` (None si la
+    /// clave es persistente) por `new`.
+    pub fn holds(&self, current: Option<SystemTime>, new: SystemTime) -> bool {
+        match (self, current) {
+            (ExpireCondition::NoExpiry, current) => current.is_none(),
+            (ExpireCondition::HasExpiry, current) => current.is_some(),
+            (ExpireCondition::Greater, Some(current)) => new > current,
+            (ExpireCondition::Greater, None) => false,
+            (ExpireCondition::Less, Some(current)) => new < current,
+            (ExpireCondition::Less, None) => true,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::entities::set_options::SetCondition;
+
+/// Radio de la Tierra en metros, el mismo que utiliza Redis.
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+/// Cantidad de bits usados para codificar cada coordenada en el geohash.
+const GEO_STEP: u32 = 26;
+const LONGITUDE_MIN: f64 = -180.0;
+const LONGITUDE_MAX: f64 = 180.0;
+/// Límites de latitud de la proyección EPSG:900913, los mismos que utiliza Redis.
+const LATITUDE_MIN: f64 = -85.05112878;
+const LATITUDE_MAX: f64 = 85.05112878;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// GeoUnit: Enum usado para representar las unidades de distancia permitidas por los comandos geo.
+pub enum GeoUnit {
+    Meters,
+    Kilometers,
+    Feet,
+    Miles,
+}
+
+impl GeoUnit {
+    /// Devuelve la cantidad de metros que representa una unidad.
+    pub fn to_meters(self) -> f64 {
+        match self {
+            GeoUnit::Meters => 1.0,
+            GeoUnit::Kilometers => 1000.0,
+            GeoUnit::Feet => 0.3048,
+            GeoUnit::Miles => 1609.34,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// GeoOrigin: Enum usado para representar el centro de una búsqueda de GEOSEARCH.
+pub enum GeoOrigin {
+    /// Posición de un miembro del sorted set (FROMMEMBER).
+    Member(String),
+    /// Longitud y latitud indicadas (FROMLONLAT).
+    LonLat(f64, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// GeoShape: Enum usado para representar el área de una búsqueda de GEOSEARCH. Las medidas se
+/// expresan en metros.
+pub enum GeoShape {
+    /// Círculo con el radio indicado (BYRADIUS).
+    Radius(f64),
+    /// Rectángulo con el ancho y alto indicados (BYBOX).
+    Box(f64, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// GeoAddOptions: Struct usado para representar las opciones permitidas para el Command::Geoadd.
+pub struct GeoAddOptions {
+    /// Condición bajo la cual se agrega o actualiza cada miembro (NX, XX).
+    pub condition: SetCondition,
+    /// Indica si se deben contar los miembros modificados además de los agregados (CH).
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// GeoSearchOptions: Struct usado para representar la búsqueda realizada por el
+/// Command::Geosearch.
+pub struct GeoSearchOptions {
+    /// Centro de la búsqueda.
+    pub origin: GeoOrigin,
+    /// Área de la búsqueda.
+    pub shape: GeoShape,
+    /// Unidad en la cual se devuelven las distancias.
+    pub unit: GeoUnit,
+    /// Orden de los resultados según la distancia: Some(true) ascendente, Some(false) descendente.
+    pub ascending: Option<bool>,
+    /// Cantidad máxima de resultados.
+    pub count: Option<usize>,
+    /// Indica si se devuelven los primeros resultados encontrados, sin buscar los más cercanos.
+    pub any: bool,
+    /// Indica si se devuelven las coordenadas de cada resultado.
+    pub with_coord: bool,
+    /// Indica si se devuelve la distancia al centro de cada resultado.
+    pub with_dist: bool,
+    /// Indica si se devuelve el geohash de cada resultado.
+    pub with_hash: bool,
+}
+
+/// Valida que la longitud y latitud se encuentren dentro de los límites admitidos.
+pub fn validate(longitude: f64, latitude: f64) -> Result<(), String> {
+    if !(LONGITUDE_MIN..=LONGITUDE_MAX).contains(&longitude)
+        || !(LATITUDE_MIN..=LATITUDE_MAX).contains(&latitude)
+    {
+        return Err(format!(
+            "ERR invalid longitude,latitude pair {:.6},{:.6}",
+            longitude, latitude
+        ));
+    }
+    Ok(())
+}
+
+/// Codifica una posición como un geohash de 52 bits, que se almacena como score del sorted set.
+///
+/// Los bits de la latitud ocupan las posiciones pares y los de la longitud las impares.
+pub fn encode(longitude: f64, latitude: f64) -> u64 {
+    let scale = (1u64 << GEO_STEP) as f64;
+    let latitude = ((latitude - LATITUDE_MIN) / (LATITUDE_MAX - LATITUDE_MIN) * scale) as u64;
+    let longitude = ((longitude - LONGITUDE_MIN) / (LONGITUDE_MAX - LONGITUDE_MIN) * scale) as u64;
+
+    (0..GEO_STEP).fold(0, |hash, bit| {
+        hash | ((latitude >> bit) & 1) << (2 * bit) | ((longitude >> bit) & 1) << (2 * bit + 1)
+    })
+}
+
+/// Decodifica un geohash, devolviendo la longitud y latitud del centro del área que representa.
+pub fn decode(hash: u64) -> (f64, f64) {
+    let (latitude, longitude) = (0..GEO_STEP).fold((0u64, 0u64), |(latitude, longitude), bit| {
+        (
+            latitude | ((hash >> (2 * bit)) & 1) << bit,
+            longitude | ((hash >> (2 * bit + 1)) & 1) << bit,
+        )
+    });
+
+    let scale = (1u64 << GEO_STEP) as f64;
+    let center = |cell: u64, min: f64, max: f64| {
+        let from = min + (cell as f64 / scale) * (max - min);
+        let to = min + ((cell + 1) as f64 / scale) * (max - min);
+        ((from + to) / 2.0).clamp(min, max)
+    };
+
+    (
+        center(longitude, LONGITUDE_MIN, LONGITUDE_MAX),
+        center(latitude, LATITUDE_MIN, LATITUDE_MAX),
+    )
+}
+
+/// Calcula la distancia en metros entre dos posiciones con la fórmula de haversine.
+pub fn distance(longitude1: f64, latitude1: f64, longitude2: f64, latitude2: f64) -> f64 {
+    let v = ((longitude2.to_radians() - longitude1.to_radians()) / 2.0).sin();
+    if v == 0.0 {
+        return latitude_distance(latitude1, latitude2);
+    }
+    let u = ((latitude2.to_radians() - latitude1.to_radians()) / 2.0).sin();
+    let a = u * u + latitude1.to_radians().cos() * latitude2.to_radians().cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+/// Indica si la posición se encuentra dentro del área indicada alrededor del centro, devolviendo
+/// su distancia al centro en metros.
+pub fn within(shape: GeoShape, center: (f64, f64), position: (f64, f64)) -> Option<f64> {
+    let (center_longitude, center_latitude) = center;
+    let (longitude, latitude) = position;
+
+    match shape {
+        GeoShape::Radius(radius) => {
+            let distance = distance(center_longitude, center_latitude, longitude, latitude);
+            Some(distance).filter(|distance| *distance <= radius)
+        }
+        GeoShape::Box(width, height) => {
+            if latitude_distance(latitude, center_latitude) > height / 2.0 {
+                return None;
+            }
+            if distance(longitude, latitude, center_longitude, latitude) > width / 2.0 {
+                return None;
+            }
+            Some(distance(
+                center_longitude,
+                center_latitude,
+                longitude,
+                latitude,
+            ))
+        }
+    }
+}
+
+/// Calcula la distancia en metros entre dos latitudes.
+fn latitude_distance(latitude1: f64, latitude2: f64) -> f64 {
+    EARTH_RADIUS_IN_METERS * (latitude2.to_radians() - latitude1.to_radians()).abs()
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::geo::{decode, distance, encode, validate, within, GeoShape};
+
+    #[test]
+    fn test_encode_and_decode_round_trip() {
+        let hash = encode(13.361389, 38.115556);
+
+        assert_eq!(3479099956230698, hash);
+        let (longitude, latitude) = decode(hash);
+        assert!((longitude - 13.361389).abs() < 0.00001);
+        assert!((latitude - 38.115556).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_distance_between_palermo_and_catania() {
+        let distance = distance(13.361389, 38.115556, 15.087269, 37.502669);
+
+        assert!((distance - 166274.1516).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_within_radius_and_box() {
+        let center = (15.0, 37.0);
+        let catania = (15.087269, 37.502669);
+
+        assert!(within(GeoShape::Radius(60000.0), center, catania).is_some());
+        assert!(within(GeoShape::Radius(50000.0), center, catania).is_none());
+        assert!(within(GeoShape::Box(40000.0, 120000.0), center, catania).is_some());
+        assert!(within(GeoShape::Box(40000.0, 100000.0), center, catania).is_none());
+    }
+
+    #[test]
+    fn test_validate_coordinates() {
+        assert!(validate(180.0, 85.0).is_ok());
+        assert_eq!(
+            Err("ERR invalid longitude,latitude pair 181.000000,0.000000".to_string()),
+            validate(181.0, 0.0)
+        );
+        assert!(validate(0.0, 86.0).is_err());
+    }
+}
@@ -13,4 +13,12 @@ pub enum InfoParam {
     ConfigFile,
     /// Utilizado para el parametro ConnectedClients del Comando Info
     ConnectedClients,
+    /// Utilizado para la sección Replication del Comando Info
+    Replication,
+    /// Utilizado para la sección Commandstats del Comando Info
+    Commandstats,
+    /// Utilizado para la sección Stats del Comando Info
+    Stats,
+    /// Utilizado para la sección Memory del Comando Info
+    Memory,
 }
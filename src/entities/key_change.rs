@@ -0,0 +1,14 @@
+use crate::entities::redis_element::RedisElement;
+
+#[derive(Debug, Clone, PartialEq)]
+/// KeyChange: Modificación de una clave, informada a los observadores registrados con
+/// `Redis::watch_changes`.
+pub struct KeyChange {
+    /// Clave modificada.
+    pub key: String,
+    /// Evento que produjo la modificación, con los mismos nombres que las notificaciones de
+    /// keyspace (ej: `set`, `lpush`, `del`, `expired`), o `flushdb` si se vació la base de datos.
+    pub event: String,
+    /// Valor de la clave luego de la modificación, o None si la clave ya no existe.
+    pub value: Option<RedisElement>,
+}
@@ -0,0 +1,486 @@
+use crate::entities::clock::{Clock, SystemClock};
+use crate::entities::expire_condition::ExpireCondition;
+use crate::entities::redis_element::RedisElement;
+use crate::entities::ttl_hash_map::TtlHashMap;
+use crate::service::random::random_index;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, SystemTime};
+
+/// Cantidad de shards en los que se divide el espacio de claves.
+pub const SHARDS: usize = 16;
+
+/// Mapa de cada shard: las claves con sus valores, expiraciones y accesos.
+pub type Shard = TtlHashMap<String, RedisElement>;
+
+#[derive(Debug)]
+/// Keyspace: Espacio de claves de la base de datos, dividido en shards según el hash de cada clave.
+/// Cada shard tiene su propio lock, de modo que los hilos de los clientes pueden leer y escribir
+/// claves de distintos shards en paralelo con una referencia compartida (`read` y `write`).
+///
+/// Con una referencia mutable no se toman los locks, y los métodos se comportan como los de un
+/// único `TtlHashMap` con todas las claves.
+pub struct Keyspace {
+    shards: Vec<RwLock<Shard>>,
+}
+
+impl Keyspace {
+    #[allow(dead_code)]
+    /// Crea un espacio de claves vacío.
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Crea un espacio de claves vacío que toma la hora actual de `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Keyspace {
+            shards: (0..SHARDS)
+                .map(|_| RwLock::new(TtlHashMap::with_clock(Arc::clone(&clock))))
+                .collect(),
+        }
+    }
+
+    /// Crea un espacio de claves con las claves del mapa, repartidas en los shards junto con sus
+    /// expiraciones y accesos. El registro de accesos se toma del mapa.
+    pub fn from_map(mut map: Shard) -> Self {
+        let mut keyspace = Self::with_clock(map.clock());
+        keyspace.set_access_tracking(map.tracks_access());
+        let keys: Vec<String> = map.keys().cloned().collect();
+        for key in keys {
+            let shard = keyspace.shard_mut(&key);
+            map.move_to(&key, shard, key.clone());
+        }
+        keyspace
+    }
+
+    /// Devuelve la posición del shard que contiene la clave.
+    fn shard_index(key: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % SHARDS as u64) as usize
+    }
+
+    /// Devuelve el shard que contiene la clave, sin tomar su lock.
+    pub fn shard_mut(&mut self, key: &str) -> &mut Shard {
+        self.shards[Self::shard_index(key)].get_mut().unwrap()
+    }
+
+    /// Toma en modo lectura el shard que contiene la clave.
+    pub fn read(&self, key: &str) -> RwLockReadGuard<'_, Shard> {
+        self.shards[Self::shard_index(key)].read().unwrap()
+    }
+
+    /// Toma en modo escritura el shard que contiene la clave.
+    pub fn write(&self, key: &str) -> RwLockWriteGuard<'_, Shard> {
+        self.shards[Self::shard_index(key)].write().unwrap()
+    }
+
+    /// Devuelve todos los shards, sin tomar sus locks.
+    fn shards_mut(&mut self) -> impl Iterator<Item = &mut Shard> {
+        self.shards.iter_mut().map(|shard| shard.get_mut().unwrap())
+    }
+
+    /// Reemplaza el reloj con el que se calculan las expiraciones y los accesos.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        for shard in self.shards_mut() {
+            shard.set_clock(Arc::clone(&clock));
+        }
+    }
+
+    /// Habilita o deshabilita el registro de los accesos a las claves en todos los shards.
+    pub fn set_access_tracking(&mut self, enabled: bool) {
+        for shard in self.shards_mut() {
+            shard.set_access_tracking(enabled);
+        }
+    }
+
+    /// Indica si se registran los accesos a las claves.
+    pub fn tracks_access(&self) -> bool {
+        self.shards[0].read().unwrap().tracks_access()
+    }
+
+    /// Devuelve la cantidad de claves guardadas, sin chequear que no hayan expirado.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().len())
+            .sum()
+    }
+
+    /// Guarda un par clave-valor.
+    pub fn insert(&mut self, key: String, value: RedisElement) {
+        self.shard_mut(&key).insert(key, value)
+    }
+
+    /// Devuelve el valor correspondiente a la clave. Si expiró, la borra y devuelve None.
+    pub fn get(&mut self, key: &String) -> Option<&RedisElement> {
+        self.shard_mut(key).get(key)
+    }
+
+    /// Devuelve el valor correspondiente a la clave sin registrar un acceso. Si expiró, la borra y
+    /// devuelve None.
+    pub fn peek(&mut self, key: &String) -> Option<&RedisElement> {
+        self.shard_mut(key).peek(key)
+    }
+
+    /// Devuelve una referencia mutable del valor correspondiente a la clave. Si expiró, la borra y
+    /// devuelve None.
+    pub fn get_mut(&mut self, key: &String) -> Option<&mut RedisElement> {
+        self.shard_mut(key).get_mut(key)
+    }
+
+    /// Devuelve si una clave existe o no, chequeando que no haya expirado. Si expiró, la borra.
+    pub fn contains_key(&mut self, key: &String) -> bool {
+        self.shard_mut(key).contains_key(key)
+    }
+
+    /// Elimina el par clave-valor y devuelve el valor, o None si no existía la clave.
+    pub fn remove(&mut self, key: &String) -> Option<RedisElement> {
+        self.shard_mut(key).remove(key)
+    }
+
+    /// Elimina el par clave-valor descartando el valor. Devuelve false si no existía la clave.
+    pub fn delete(&mut self, key: &String) -> bool {
+        self.shard_mut(key).delete(key)
+    }
+
+    /// Mueve el valor de la clave `from` a la clave `to`, junto con su expiración, su último acceso
+    /// y su frecuencia de acceso, aunque pertenezcan a distintos shards. Si `to` existía, su valor
+    /// se descarta. Devuelve false si no existe la clave `from` o expiró.
+    pub fn rename(&mut self, from: &String, to: String) -> bool {
+        let (from_index, to_index) = (Self::shard_index(from), Self::shard_index(&to));
+        if from_index == to_index {
+            return self.shard_mut(from).rename(from, to);
+        }
+        let (low, high) = self.shards.split_at_mut(from_index.max(to_index));
+        let (first, second) = (
+            low[from_index.min(to_index)].get_mut().unwrap(),
+            high[0].get_mut().unwrap(),
+        );
+        if from_index < to_index {
+            first.move_to(from, second, to)
+        } else {
+            second.move_to(from, first, to)
+        }
+    }
+
+    /// Actualiza el último acceso a la clave y registra el acceso en su frecuencia. Devuelve el
+    /// tiempo transcurrido desde el anterior acceso, o None si no existe la clave o expiró.
+    pub fn update_last_access(&mut self, key: &String) -> Option<Duration> {
+        self.shard_mut(key).update_last_access(key)
+    }
+
+    /// Devuelve el tiempo transcurrido desde el último acceso a la clave, sin registrar un acceso.
+    pub fn idle_time(&self, key: &String) -> Option<Duration> {
+        self.read(key).idle_time(key)
+    }
+
+    /// Devuelve la frecuencia de acceso a la clave sin registrar un nuevo acceso. Devuelve None si
+    /// no existe la clave o expiró.
+    pub fn get_frequency(&mut self, key: &String) -> Option<u8> {
+        self.shard_mut(key).get_frequency(key)
+    }
+
+    /// Setea una expiración para la clave en un cierto SystemTime, como
+    /// `TtlHashMap::set_ttl_absolute`.
+    pub fn set_ttl_absolute(&mut self, key: String, ttl: SystemTime) -> Option<SystemTime> {
+        self.shard_mut(&key).set_ttl_absolute(key, ttl)
+    }
+
+    /// Setea una expiración para la clave si se cumplen todas las condiciones, como
+    /// `TtlHashMap::set_ttl_if`.
+    pub fn set_ttl_if(
+        &mut self,
+        key: String,
+        ttl: SystemTime,
+        conditions: &[ExpireCondition],
+    ) -> bool {
+        self.shard_mut(&key).set_ttl_if(key, ttl, conditions)
+    }
+
+    /// Elimina la expiración de la clave y devuelve la que tenía.
+    pub fn delete_ttl(&mut self, key: &String) -> Option<SystemTime> {
+        self.shard_mut(key).delete_ttl(key)
+    }
+
+    /// Obtiene el tiempo restante de expiración de una clave, como `TtlHashMap::get_ttl`.
+    pub fn get_ttl(&mut self, key: &String) -> Option<Duration> {
+        self.shard_mut(key).get_ttl(key)
+    }
+
+    /// Devuelve el momento en el que expira la clave, o Some(None) si la clave es persistente.
+    /// Devuelve None si no existe la clave o expiró.
+    pub fn get_expiration(&mut self, key: &String) -> Option<Option<SystemTime>> {
+        self.shard_mut(key).get_expiration(key)
+    }
+
+    /// Devuelve las claves eliminadas por haber expirado en cualquier shard desde la última
+    /// llamada, incluidas las eliminadas desde los hilos de los clientes.
+    pub fn take_expired_keys(&mut self) -> Vec<String> {
+        self.shards_mut()
+            .flat_map(|shard| shard.take_expired_keys())
+            .collect()
+    }
+
+    /// Indica si hay alguna clave con expiración.
+    pub fn has_volatile_keys(&self) -> bool {
+        self.shards
+            .iter()
+            .any(|shard| shard.read().unwrap().has_volatile_keys())
+    }
+
+    /// Revisa hasta `samples` claves con expiración de un shard elegido al azar entre los que
+    /// tienen alguna, y elimina las que expiraron. Devuelve la cantidad de claves eliminadas.
+    pub fn expire_sample(&mut self, samples: usize) -> usize {
+        match self.random_shard(|shard| shard.volatile_len()) {
+            Some(shard) => shard.expire_sample(samples),
+            None => 0,
+        }
+    }
+
+    /// Elimina las claves que expiraron, registrándolas entre las claves expiradas, y devuelve las
+    /// claves restantes.
+    pub fn keys_purged(&mut self) -> impl Iterator<Item = &String> {
+        self.shards_mut().flat_map(|shard| shard.keys_purged())
+    }
+
+    /// Recorre los pares clave-valor que no expiraron, sin modificar los shards ni registrar
+    /// accesos.
+    pub fn for_each_valid(&self, mut f: impl FnMut(&String, &RedisElement)) {
+        for shard in &self.shards {
+            for (key, value) in shard.read().unwrap().iter_valid() {
+                f(key, value);
+            }
+        }
+    }
+
+    /// Devuelve la memoria que ocupan las claves con sus valores, sumando la cuenta que lleva cada
+    /// shard.
+    pub fn used_memory(&mut self) -> usize {
+        self.shards_mut().map(|shard| shard.used_memory()).sum()
+    }
+
+    /// Devuelve una clave al azar que no haya expirado, o None si no hay ninguna. Cada shard se
+    /// elige con una probabilidad proporcional a su cantidad de claves.
+    pub fn random_key(&mut self) -> Option<String> {
+        loop {
+            // Si el shard elegido no devuelve ninguna clave es porque borró todas sus claves
+            // expiradas, por lo que la siguiente elección es sobre menos claves.
+            if let Some(key) = self.random_shard(|shard| shard.len())?.random_key() {
+                return Some(key);
+            }
+        }
+    }
+
+    /// Elige la clave a descartar con las políticas LFU de `maxmemory-policy`, como
+    /// `TtlHashMap::lfu_candidate`: entre hasta `samples` claves elegidas al azar de todos los
+    /// shards, devuelve la accedida con menor frecuencia. Las muestras se reparten eligiendo cada
+    /// una un shard con una probabilidad proporcional a su cantidad de claves candidatas; si no
+    /// hay más de `samples` candidatas, se consideran todas.
+    pub fn lfu_candidate(&mut self, samples: usize, volatile_only: bool) -> Option<String> {
+        let candidates = |shard: &Shard| {
+            if volatile_only {
+                shard.volatile_len()
+            } else {
+                shard.len()
+            }
+        };
+        loop {
+            let weights: Vec<usize> = self.shards_mut().map(|shard| candidates(shard)).collect();
+            let total: usize = weights.iter().sum();
+            if total == 0 {
+                return None;
+            }
+            let shard_samples = if total <= samples {
+                weights
+            } else {
+                let mut shard_samples = vec![0; SHARDS];
+                for _ in 0..samples {
+                    shard_samples[Self::weighted_index(&weights, total)] += 1;
+                }
+                shard_samples
+            };
+
+            // Si todas las claves de la muestra expiraron, los shards las borran y se vuelve a
+            // intentar con menos candidatas.
+            let candidate = self
+                .shards_mut()
+                .zip(shard_samples)
+                .filter(|(_, samples)| *samples > 0)
+                .filter_map(|(shard, samples)| {
+                    let key = shard.lfu_candidate(samples, volatile_only)?;
+                    Some((shard.get_frequency(&key).unwrap_or(0), key))
+                })
+                .min_by_key(|(frequency, _)| *frequency);
+            if let Some((_, key)) = candidate {
+                return Some(key);
+            }
+        }
+    }
+
+    /// Elige un shard al azar con una probabilidad proporcional a su peso, sin recorrer sus claves.
+    /// Devuelve None si todos los shards pesan 0.
+    fn random_shard(&mut self, weight: impl Fn(&Shard) -> usize) -> Option<&mut Shard> {
+        let weights: Vec<usize> = self.shards_mut().map(|shard| weight(shard)).collect();
+        let total = weights.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let index = Self::weighted_index(&weights, total);
+        self.shards[index].get_mut().ok()
+    }
+
+    /// Elige una posición al azar con una probabilidad proporcional a su peso. `total` es la suma
+    /// de los pesos, y debe ser mayor a 0.
+    fn weighted_index(weights: &[usize], total: usize) -> usize {
+        let mut index = random_index(total);
+        weights
+            .iter()
+            .position(|weight| {
+                if index < *weight {
+                    return true;
+                }
+                index -= weight;
+                false
+            })
+            .unwrap_or(0)
+    }
+
+    /// Devuelve una copia de los shards, que puede serializarse en otro hilo sin bloquear el
+    /// acceso a la base de datos. Los valores se comparten como en `TtlHashMap::snapshot`.
+    pub fn snapshot(&self) -> Self {
+        Keyspace {
+            shards: self
+                .shards
+                .iter()
+                .map(|shard| RwLock::new(shard.read().unwrap().snapshot()))
+                .collect(),
+        }
+    }
+
+    /// Devuelve las claves de todos los shards serializadas con el formato RDB de Redis, como una
+    /// única base de datos.
+    pub fn serialize(&self) -> Vec<u8> {
+        let shards: Vec<RwLockReadGuard<'_, Shard>> = self
+            .shards
+            .iter()
+            .map(|shard| shard.read().unwrap())
+            .collect();
+        let shards: Vec<&Shard> = shards.iter().map(|shard| &**shard).collect();
+        TtlHashMap::serialize_all(&shards)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::keyspace::{Keyspace, SHARDS};
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::ttl_hash_map::TtlHashMap;
+    use std::collections::HashSet;
+    use std::time::{Duration, SystemTime};
+
+    #[allow(dead_code)]
+    fn value(value: &str) -> RedisElement {
+        RedisElement::String(value.to_string())
+    }
+
+    #[test]
+    fn test_keys_are_spread_across_shards() {
+        let mut keyspace = Keyspace::new();
+        for i in 0..1000 {
+            keyspace.insert(format!("key:{}", i), value("v"));
+        }
+
+        assert_eq!(1000, keyspace.len());
+        assert!(keyspace
+            .shards
+            .iter()
+            .all(|shard| shard.read().unwrap().len() > 0));
+        assert_eq!(1000, keyspace.keys_purged().count());
+    }
+
+    #[test]
+    fn test_write_guard_is_seen_by_the_keyspace() {
+        let mut keyspace = Keyspace::new();
+        keyspace
+            .write("key")
+            .insert("key".to_string(), value("shared"));
+
+        assert_eq!(Some(&value("shared")), keyspace.get(&"key".to_string()));
+        assert!(keyspace
+            .read("key")
+            .get_shared(&"key".to_string())
+            .is_some());
+    }
+
+    #[test]
+    fn test_rename_across_shards_keeps_ttl() {
+        let mut keyspace = Keyspace::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        keyspace.insert("from".to_string(), value("v"));
+        keyspace.set_ttl_absolute("from".to_string(), ttl);
+        let to = (0..)
+            .map(|i| format!("to:{}", i))
+            .find(|to| Keyspace::shard_index(to) != Keyspace::shard_index("from"))
+            .unwrap();
+
+        assert!(keyspace.rename(&"from".to_string(), to.clone()));
+
+        assert!(!keyspace.contains_key(&"from".to_string()));
+        assert_eq!(Some(Some(ttl)), keyspace.get_expiration(&to));
+        assert!(!keyspace.rename(&"from".to_string(), to));
+    }
+
+    #[test]
+    fn test_random_key_and_eviction_sample_every_shard() {
+        let mut keyspace = Keyspace::new();
+        for i in 0..SHARDS * 8 {
+            keyspace.insert(format!("key:{}", i), value("v"));
+        }
+
+        let shards: HashSet<usize> = (0..1000)
+            .map(|_| Keyspace::shard_index(&keyspace.random_key().unwrap()))
+            .collect();
+        assert_eq!(SHARDS, shards.len());
+        assert!(keyspace.lfu_candidate(5, false).is_some());
+        assert!(keyspace.lfu_candidate(5, true).is_none());
+    }
+
+    #[test]
+    fn test_expired_keys_are_collected_from_every_shard() {
+        let mut keyspace = Keyspace::new();
+        for i in 0..100 {
+            let key = format!("key:{}", i);
+            keyspace.insert(key.clone(), value("v"));
+            keyspace.set_ttl_absolute(key, SystemTime::UNIX_EPOCH);
+        }
+
+        while keyspace.has_volatile_keys() {
+            keyspace.expire_sample(20);
+        }
+
+        assert_eq!(0, keyspace.len());
+        assert_eq!(0, keyspace.used_memory());
+        assert_eq!(100, keyspace.take_expired_keys().len());
+        assert!(keyspace.random_key().is_none());
+    }
+
+    #[test]
+    fn test_from_map_and_serialize_round_trip() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        for i in 0..100 {
+            map.insert(format!("key:{}", i), value("v"));
+        }
+        map.set_ttl_absolute("key:7".to_string(), ttl);
+
+        let keyspace = Keyspace::from_map(map);
+        assert_eq!(100, keyspace.len());
+        let bytes = keyspace.snapshot().serialize();
+
+        let mut map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
+        assert_eq!(100, map.len());
+        assert!(map.get_expiration(&"key:7".to_string()).unwrap().is_some());
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Clases de eventos incluidas por el flag `A`, en el orden en que se muestran.
+const ALL_CLASSES: &str = "g$lshzxet";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// EventClass: Enum usado para representar la clase de un evento de keyspace, que determina qué
+/// flag de `notify-keyspace-events` debe estar habilitado para que se publique.
+pub enum EventClass {
+    /// Comandos genéricos como DEL, EXPIRE o RENAME (flag `g`).
+    Generic,
+    /// Comandos de strings (flag `$`).
+    String,
+    /// Comandos de listas (flag `l`).
+    List,
+    /// Comandos de sets (flag `s`).
+    Set,
+    /// Comandos de sorted sets (flag `z`).
+    SortedSet,
+    /// Claves eliminadas por haber expirado (flag `x`).
+    Expired,
+    /// Claves descartadas por superar `maxmemory` (flag `e`).
+    Evicted,
+    /// Comandos de streams (flag `t`).
+    Stream,
+}
+
+impl EventClass {
+    /// Devuelve el flag de `notify-keyspace-events` que habilita la clase.
+    fn flag(self) -> char {
+        match self {
+            EventClass::Generic => 'g',
+            EventClass::String => '$',
+            EventClass::List => 'l',
+            EventClass::Set => 's',
+            EventClass::SortedSet => 'z',
+            EventClass::Expired => 'x',
+            EventClass::Evicted => 'e',
+            EventClass::Stream => 't',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// KeyspaceEvents: Struct usado para representar el parámetro de configuración
+/// `notify-keyspace-events`, que indica qué eventos sobre las claves se publican por Pub/Sub.
+///
+/// Por defecto no se publica ningún evento.
+pub struct KeyspaceEvents {
+    /// Indica si se publica en el canal `__keyspace@0__:<clave>` con el evento como mensaje (K).
+    keyspace: bool,
+    /// Indica si se publica en el canal `__keyevent@0__:<evento>` con la clave como mensaje (E).
+    keyevent: bool,
+    /// Flags de las clases de eventos habilitadas.
+    classes: String,
+}
+
+impl KeyspaceEvents {
+    /// Indica si no se publica ningún evento.
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty() || !(self.keyspace || self.keyevent)
+    }
+
+    /// Devuelve los pares (canal, mensaje) a publicar para el evento sobre la clave indicada.
+    ///
+    /// Si la clase del evento no está habilitada, o no se indicó ni `K` ni `E`, no se publica nada.
+    pub fn messages(&self, class: EventClass, event: &str, key: &str) -> Vec<(String, String)> {
+        let mut messages = Vec::new();
+        if !self.classes.contains(class.flag()) {
+            return messages;
+        }
+        if self.keyspace {
+            messages.push(("__keyspace@0__:".to_string() + key, event.to_string()));
+        }
+        if self.keyevent {
+            messages.push(("__keyevent@0__:".to_string() + event, key.to_string()));
+        }
+        messages
+    }
+}
+
+impl FromStr for KeyspaceEvents {
+    type Err = String;
+
+    /// Parsea los flags con el mismo formato que Redis, por ejemplo `KEA` o `Ex`.
+    fn from_str(flags: &str) -> Result<Self, Self::Err> {
+        let mut events = KeyspaceEvents::default();
+        for flag in flags.chars() {
+            match flag {
+                'K' => events.keyspace = true,
+                'E' => events.keyevent = true,
+                'A' => events.classes.push_str(ALL_CLASSES),
+                _ if ALL_CLASSES.contains(flag) => events.classes.push(flag),
+                _ => {
+                    return Err(format!(
+                        "ERR Invalid argument '{}' for CONFIG SET 'notify-keyspace-events'",
+                        flags
+                    ))
+                }
+            }
+        }
+        // Se normalizan las clases para que se muestren en el mismo orden que las incluye `A`.
+        events.classes = ALL_CLASSES
+            .chars()
+            .filter(|class| events.classes.contains(*class))
+            .collect();
+        Ok(events)
+    }
+}
+
+impl fmt::Display for KeyspaceEvents {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.classes == ALL_CLASSES {
+            write!(fmt, "A")?;
+        } else {
+            write!(fmt, "{}", self.classes)?;
+        }
+        if self.keyspace {
+            write!(fmt, "K")?;
+        }
+        if self.keyevent {
+            write!(fmt, "E")?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::keyspace_events::{EventClass, KeyspaceEvents};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_default_publishes_nothing() {
+        let events = KeyspaceEvents::default();
+
+        assert!(events
+            .messages(EventClass::Generic, "del", "key")
+            .is_empty());
+        assert!(events.is_empty());
+        assert_eq!("", events.to_string());
+    }
+
+    #[test]
+    fn test_all_classes_on_both_channels() {
+        let events = KeyspaceEvents::from_str("KEA").unwrap();
+
+        assert_eq!(
+            vec![
+                ("__keyspace@0__:key".to_string(), "lpush".to_string()),
+                ("__keyevent@0__:lpush".to_string(), "key".to_string()),
+            ],
+            events.messages(EventClass::List, "lpush", "key")
+        );
+        assert_eq!("AKE", events.to_string());
+    }
+
+    #[test]
+    fn test_only_enabled_classes_are_published() {
+        let events = KeyspaceEvents::from_str("E$g").unwrap();
+
+        assert_eq!(
+            vec![("__keyevent@0__:set".to_string(), "key".to_string())],
+            events.messages(EventClass::String, "set", "key")
+        );
+        assert!(events.messages(EventClass::List, "lpush", "key").is_empty());
+        assert_eq!("g$E", events.to_string());
+    }
+
+    #[test]
+    fn test_classes_without_channel_publish_nothing() {
+        let events = KeyspaceEvents::from_str("A").unwrap();
+
+        assert!(events.messages(EventClass::Set, "sadd", "key").is_empty());
+        assert!(events.is_empty());
+        assert!(!KeyspaceEvents::from_str("K$").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_flag() {
+        assert_eq!(
+            Err("ERR Invalid argument 'KEy' for CONFIG SET 'notify-keyspace-events'".to_string()),
+            KeyspaceEvents::from_str("KEy")
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::service::random::random_u64;
+use std::time::{Duration, SystemTime};
+
+/// Valor inicial del contador de las claves nuevas, para que no sean las primeras en descartarse.
+const LFU_INIT_VAL: u8 = 5;
+/// Factor logarítmico: cuanto mayor es, más accesos se necesitan para incrementar el contador.
+const LFU_LOG_FACTOR: f64 = 10.0;
+/// Tiempo sin accesos tras el cual el contador se decrementa en uno.
+const LFU_DECAY_TIME: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// LfuCounter: Contador de la frecuencia de acceso a una clave, con el mismo algoritmo que Redis.
+/// El contador crece de forma logarítmica con los accesos y decrece con el paso del tiempo, por lo
+/// que un byte alcanza para distinguir las claves más accedidas.
+pub struct LfuCounter {
+    counter: u8,
+    /// Momento del último acceso, a partir del cual se calcula el decremento.
+    last_access: SystemTime,
+}
+
+impl LfuCounter {
+    /// Crea el contador de una clave nueva.
+    pub fn new() -> Self {
+        LfuCounter {
+            counter: LFU_INIT_VAL,
+            last_access: SystemTime::now(),
+        }
+    }
+
+    /// Devuelve el valor del contador, descontando los períodos transcurridos sin accesos.
+    pub fn value(&self) -> u8 {
+        let elapsed = self.last_access.elapsed().unwrap_or_default();
+        let periods = elapsed.as_secs() / LFU_DECAY_TIME.as_secs();
+        self.counter
+            .saturating_sub(periods.min(u8::MAX as u64) as u8)
+    }
+
+    /// Registra un acceso a la clave. El contador se incrementa con probabilidad
+    /// `1 / ((contador - LFU_INIT_VAL) * LFU_LOG_FACTOR + 1)`, hasta un máximo de 255.
+    pub fn access(&mut self) {
+        let counter = self.value();
+        let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+        let probability = 1.0 / (base * LFU_LOG_FACTOR + 1.0);
+        let random = random_u64() as f64 / u64::MAX as f64;
+
+        self.counter = if counter < u8::MAX && random < probability {
+            counter + 1
+        } else {
+            counter
+        };
+        self.last_access = SystemTime::now();
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::lfu::{LfuCounter, LFU_DECAY_TIME, LFU_INIT_VAL};
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_counter_grows_logarithmically() {
+        let mut counter = LfuCounter::new();
+        assert_eq!(LFU_INIT_VAL, counter.value());
+
+        counter.access();
+        assert_eq!(LFU_INIT_VAL + 1, counter.value());
+
+        for _ in 0..1000 {
+            counter.access();
+        }
+        assert!(counter.value() > LFU_INIT_VAL + 1);
+        assert!(counter.value() < 30);
+    }
+
+    #[test]
+    fn test_counter_decays_over_time() {
+        let counter = LfuCounter {
+            counter: 10,
+            last_access: SystemTime::now() - LFU_DECAY_TIME * 3,
+        };
+        assert_eq!(7, counter.value());
+
+        let counter = LfuCounter {
+            counter: 10,
+            last_access: SystemTime::now() - LFU_DECAY_TIME * 100,
+        };
+        assert_eq!(0, counter.value());
+    }
+}
@@ -0,0 +1,322 @@
+/// Fin de un listpack o de un ziplist.
+const END: u8 = 0xff;
+/// Tamaño del encabezado de un listpack: total de bytes (u32) y cantidad de elementos (u16).
+const LISTPACK_HEADER_SIZE: usize = 6;
+/// Tamaño del encabezado de un ziplist: total de bytes (u32), offset del último elemento (u32) y
+/// cantidad de elementos (u16).
+const ZIPLIST_HEADER_SIZE: usize = 10;
+
+/// Codifica los elementos como un listpack, el formato compacto que usa Redis para guardar los
+/// nodos de los streams y las colecciones chicas.
+///
+/// Los elementos que representan un entero se guardan como entero, como lo hace Redis.
+pub fn encode(elements: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = vec![0; LISTPACK_HEADER_SIZE];
+    for element in elements {
+        let entry = encode_entry(element);
+        bytes.extend_from_slice(&entry);
+        encode_backlen(&mut bytes, entry.len());
+    }
+    bytes.push(END);
+
+    let total = bytes.len() as u32;
+    bytes[0..4].copy_from_slice(&total.to_le_bytes());
+    let count = elements.len().min(u16::MAX as usize) as u16;
+    bytes[4..6].copy_from_slice(&count.to_le_bytes());
+    bytes
+}
+
+/// Devuelve el entero representado por el elemento, si su forma canónica coincide con el texto.
+fn as_integer(element: &[u8]) -> Option<i64> {
+    let text = std::str::from_utf8(element).ok()?;
+    let value = text.parse::<i64>().ok()?;
+    if value.to_string() == text {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Codifica un elemento del listpack, sin su largo hacia atrás.
+fn encode_entry(element: &[u8]) -> Vec<u8> {
+    if let Some(value) = as_integer(element) {
+        return if (0..128).contains(&value) {
+            // 0xxxxxxx: entero sin signo de 7 bits
+            vec![value as u8]
+        } else if (-4096..4096).contains(&value) {
+            // 110xxxxx yyyyyyyy: entero de 13 bits
+            let value = (value as u16) & 0x1fff;
+            vec![0xc0 | (value >> 8) as u8, value as u8]
+        } else if (i16::MIN as i64..=i16::MAX as i64).contains(&value) {
+            [&[0xf1][..], &(value as i16).to_le_bytes()].concat()
+        } else if (-(1 << 23)..1 << 23).contains(&value) {
+            [&[0xf2][..], &(value as i32).to_le_bytes()[..3]].concat()
+        } else if (i32::MIN as i64..=i32::MAX as i64).contains(&value) {
+            [&[0xf3][..], &(value as i32).to_le_bytes()].concat()
+        } else {
+            [&[0xf4][..], &value.to_le_bytes()].concat()
+        };
+    }
+
+    let len = element.len();
+    let mut entry = if len < 1 << 6 {
+        // 10xxxxxx: string de hasta 63 bytes
+        vec![0x80 | len as u8]
+    } else if len < 1 << 12 {
+        // 1110xxxx yyyyyyyy: string de hasta 4095 bytes
+        vec![0xe0 | (len >> 8) as u8, len as u8]
+    } else {
+        [&[0xf0][..], &(len as u32).to_le_bytes()].concat()
+    };
+    entry.extend_from_slice(element);
+    entry
+}
+
+/// Agrega el largo del elemento anterior, que permite recorrer el listpack hacia atrás.
+fn encode_backlen(bytes: &mut Vec<u8>, len: usize) {
+    let size = backlen_size(len);
+    for i in (0..size).rev() {
+        let byte = ((len >> (7 * i)) & 0x7f) as u8;
+        // Todos los bytes salvo el primero indican que el largo continúa.
+        bytes.push(if i == size - 1 { byte } else { byte | 0x80 });
+    }
+}
+
+/// Cantidad de bytes que ocupa el largo hacia atrás de un elemento de `len` bytes.
+fn backlen_size(len: usize) -> usize {
+    match len {
+        0..=127 => 1,
+        128..=16382 => 2,
+        16383..=2097150 => 3,
+        2097151..=268435454 => 4,
+        _ => 5,
+    }
+}
+
+/// Lector secuencial de los bytes de un listpack, ziplist o intset.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.bytes.len() - self.pos {
+            return Err("Unexpected end of encoded collection".to_string());
+        }
+        let bytes = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Lee un entero con signo de `len` bytes en little endian.
+    fn int_le(&mut self, len: usize) -> Result<i64, String> {
+        let bytes = self.take(len)?;
+        let mut buffer = [0; 8];
+        buffer[..len].copy_from_slice(bytes);
+        // Se extiende el signo desplazando el valor a la parte alta del entero.
+        let shift = 64 - 8 * len as u32;
+        Ok(i64::from_le_bytes(buffer).wrapping_shl(shift) >> shift)
+    }
+
+    fn u32_le(&mut self) -> Result<u32, String> {
+        Ok(self.int_le(4)? as u32)
+    }
+}
+
+/// Decodifica un listpack, devolviendo sus elementos. Los enteros se devuelven como texto.
+pub fn decode(bytes: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let mut cursor = Cursor { bytes, pos: 0 };
+    let total = cursor.u32_le()? as usize;
+    if total != bytes.len() {
+        return Err("Invalid listpack size".to_string());
+    }
+    cursor.take(2)?;
+
+    let mut elements = vec![];
+    loop {
+        let first = cursor.byte()?;
+        if first == END {
+            break;
+        }
+        let start = cursor.pos - 1;
+        let element = match first {
+            0x00..=0x7f => first.to_string().into_bytes(),
+            0x80..=0xbf => cursor.take((first & 0x3f) as usize)?.to_vec(),
+            0xc0..=0xdf => {
+                let value = (((first & 0x1f) as i64) << 8) | cursor.byte()? as i64;
+                // Entero de 13 bits en complemento a dos.
+                let value = if value >= 1 << 12 {
+                    value - (1 << 13)
+                } else {
+                    value
+                };
+                value.to_string().into_bytes()
+            }
+            0xe0..=0xef => {
+                let len = (((first & 0x0f) as usize) << 8) | cursor.byte()? as usize;
+                cursor.take(len)?.to_vec()
+            }
+            0xf0 => {
+                let len = cursor.u32_le()? as usize;
+                cursor.take(len)?.to_vec()
+            }
+            0xf1 => cursor.int_le(2)?.to_string().into_bytes(),
+            0xf2 => cursor.int_le(3)?.to_string().into_bytes(),
+            0xf3 => cursor.int_le(4)?.to_string().into_bytes(),
+            0xf4 => cursor.int_le(8)?.to_string().into_bytes(),
+            _ => return Err("Invalid listpack entry encoding".to_string()),
+        };
+        cursor.take(backlen_size(cursor.pos - start))?;
+        elements.push(element);
+    }
+    Ok(elements)
+}
+
+/// Decodifica un ziplist, el formato compacto que usaban las versiones de Redis anteriores a la 7.
+pub fn decode_ziplist(bytes: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let mut cursor = Cursor { bytes, pos: 0 };
+    let total = cursor.u32_le()? as usize;
+    if total != bytes.len() {
+        return Err("Invalid ziplist size".to_string());
+    }
+    cursor.take(ZIPLIST_HEADER_SIZE - 4)?;
+
+    let mut elements = vec![];
+    loop {
+        // Largo del elemento anterior: un byte, o 0xfe seguido de 4 bytes.
+        match cursor.byte()? {
+            END => break,
+            0xfe => {
+                cursor.take(4)?;
+            }
+            _ => (),
+        }
+
+        let encoding = cursor.byte()?;
+        let element = match encoding >> 6 {
+            0b00 => cursor.take((encoding & 0x3f) as usize)?.to_vec(),
+            0b01 => {
+                let len = (((encoding & 0x3f) as usize) << 8) | cursor.byte()? as usize;
+                cursor.take(len)?.to_vec()
+            }
+            0b10 => {
+                let mut buffer = [0; 4];
+                buffer.copy_from_slice(cursor.take(4)?);
+                cursor.take(u32::from_be_bytes(buffer) as usize)?.to_vec()
+            }
+            _ => {
+                let value = match encoding {
+                    0xc0 => cursor.int_le(2)?,
+                    0xd0 => cursor.int_le(4)?,
+                    0xe0 => cursor.int_le(8)?,
+                    0xf0 => cursor.int_le(3)?,
+                    0xfe => cursor.int_le(1)?,
+                    // 1111xxxx: entero inmediato entre 0 y 12.
+                    0xf1..=0xfd => (encoding & 0x0f) as i64 - 1,
+                    _ => return Err("Invalid ziplist entry encoding".to_string()),
+                };
+                value.to_string().into_bytes()
+            }
+        };
+        elements.push(element);
+    }
+    Ok(elements)
+}
+
+/// Decodifica un intset, el formato que usa Redis para los sets chicos formados solo por enteros.
+pub fn decode_intset(bytes: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let mut cursor = Cursor { bytes, pos: 0 };
+    let encoding = cursor.u32_le()? as usize;
+    if ![2, 4, 8].contains(&encoding) {
+        return Err("Invalid intset encoding".to_string());
+    }
+    let len = cursor.u32_le()? as usize;
+    if len * encoding != bytes.len() - cursor.pos {
+        return Err("Invalid intset size".to_string());
+    }
+
+    (0..len)
+        .map(|_| Ok(cursor.int_le(encoding)?.to_string().into_bytes()))
+        .collect()
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::listpack::{decode, decode_intset, decode_ziplist, encode};
+
+    #[allow(dead_code)]
+    fn elements(values: &[&str]) -> Vec<Vec<u8>> {
+        values
+            .iter()
+            .map(|value| value.as_bytes().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_decode_listpack() {
+        let long = "x".repeat(5000);
+        let values = elements(&[
+            "0",
+            "127",
+            "128",
+            "-1",
+            "-4096",
+            "4096",
+            "-32768",
+            "8388607",
+            "-2147483648",
+            "9223372036854775807",
+            "007",
+            "",
+            "field",
+            &"y".repeat(200),
+            &long,
+        ]);
+
+        let bytes = encode(&values);
+
+        assert_eq!(
+            bytes.len() as u32,
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        );
+        assert_eq!(values, decode(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_encode_listpack_matches_redis() {
+        // Listpack generado por Redis para los elementos "a" y 1.
+        let bytes = [0x0c, 0, 0, 0, 2, 0, 0x81, b'a', 2, 1, 1, 0xff];
+
+        assert_eq!(bytes.to_vec(), encode(&elements(&["a", "1"])));
+    }
+
+    #[test]
+    fn test_decode_ziplist() {
+        let bytes = [
+            24, 0, 0, 0, 19, 0, 0, 0, 4, 0, 0, 0x02, b'h', b'i', 4, 0xf2, 2, 0xfe, 0xf6, 3, 0xc0,
+            0xd2, 0x04, 0xff,
+        ];
+
+        assert_eq!(
+            elements(&["hi", "1", "-10", "1234"]),
+            decode_ziplist(&bytes).unwrap()
+        );
+        assert!(decode_ziplist(&bytes[..20]).is_err());
+    }
+
+    #[test]
+    fn test_decode_intset() {
+        let bytes = [2, 0, 0, 0, 3, 0, 0, 0, 0xff, 0xff, 1, 0, 0x39, 0x30];
+
+        assert_eq!(
+            elements(&["-1", "1", "12345"]),
+            decode_intset(&bytes).unwrap()
+        );
+        assert!(decode_intset(&bytes[..12]).is_err());
+    }
+}
@@ -1,6 +1,10 @@
 use crate::entities::log_level::LogLevel;
 use crate::service::timestamp_to_string::timestamp_to_string;
-use std::time::SystemTime;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Facility de syslog con la que se envían los logs (`local0`, la que usa Redis por defecto).
+const SYSLOG_FACILITY: u8 = 16;
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -11,6 +15,10 @@ pub struct Log {
     col: u32,
     file: String,
     msg: String,
+    /// Id y dirección del cliente que originó el log, si corresponde a un comando de un cliente.
+    client: Option<(u64, String)>,
+    /// Tiempo que demoró la ejecución del comando que originó el log.
+    elapsed: Option<Duration>,
 }
 
 impl Log {
@@ -22,7 +30,33 @@ impl Log {
             col,
             file,
             msg,
+            client: None,
+            elapsed: None,
+        }
+    }
+
+    /// Indica el cliente que originó el log.
+    pub fn with_client(mut self, id: u64, addr: String) -> Log {
+        self.client = Some((id, addr));
+        self
+    }
+
+    /// Indica el tiempo que demoró la ejecución del comando que originó el log.
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Log {
+        self.elapsed = Some(elapsed);
+        self
+    }
+
+    /// Devuelve el mensaje del log seguido del cliente y del tiempo de ejecución, si se indicaron.
+    fn message(&self) -> String {
+        let mut message = self.msg.clone();
+        if let Some((id, addr)) = &self.client {
+            message.push_str(&format!(" - client id={} addr={}", id, addr));
         }
+        if let Some(elapsed) = self.elapsed {
+            message.push_str(&format!(" - {}us", elapsed.as_micros()));
+        }
+        message
     }
 
     /// Retorna el Nivel de loggeo del Log
@@ -33,30 +67,48 @@ impl Log {
             LogLevel::Debug => 1,
         }
     }
+
+    /// Representa el log con el formato de syslog (RFC 3164): la prioridad, que combina la
+    /// facility y la severidad del log, el identificador y el pid del proceso, y el mensaje. La
+    /// fecha la agrega el daemon de syslog al recibirlo.
+    pub fn to_syslog(&self, ident: &str, pid: u32) -> String {
+        let severity = match self.level {
+            LogLevel::Error => 3,
+            LogLevel::Info => 6,
+            LogLevel::Debug => 7,
+        };
+        format!(
+            "<{}>{}[{}]: {}:{}:{} - {}",
+            SYSLOG_FACILITY * 8 + severity,
+            ident,
+            pid,
+            self.file,
+            self.line,
+            self.col,
+            self.message()
+        )
+    }
 }
 
-impl ToString for Log {
+impl fmt::Display for Log {
     /// Trait: impl usado para poder transformar un Log a tipo String.
-    fn to_string(&self) -> String {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let level = match self.level {
             LogLevel::Debug => "[DEBUG]",
             LogLevel::Info => "[INFO] ",
             LogLevel::Error => "[ERROR]",
         };
 
-        level.to_owned()
-            + " - "
-            + &timestamp_to_string(SystemTime::now())
-            + " UTC"
-            + " - "
-            + &self.file
-            + " - "
-            + &self.line.to_string()
-            + ":"
-            + &self.col.to_string()
-            + " - "
-            + &self.msg
-            + "\n"
+        writeln!(
+            fmt,
+            "{} - {} UTC - {} - {}:{} - {}",
+            level,
+            timestamp_to_string(SystemTime::now()),
+            self.file,
+            self.line,
+            self.col,
+            self.message()
+        )
     }
 }
 
@@ -64,6 +116,7 @@ impl ToString for Log {
 mod test {
     use crate::entities::log::Log;
     use crate::entities::log_level::LogLevel;
+    use std::time::Duration;
 
     #[test]
     fn test_log_to_string() {
@@ -77,6 +130,50 @@ mod test {
         assert_ne!("".to_string(), log.to_string());
     }
 
+    #[test]
+    fn test_log_includes_client_and_elapsed_time() {
+        let log = Log::new(
+            LogLevel::Debug,
+            10,
+            5,
+            "test".to_string(),
+            "Command GET executed".to_string(),
+        )
+        .with_client(7, "127.0.0.1:5000".to_string())
+        .with_elapsed(Duration::from_micros(42));
+
+        assert!(log.to_string().ends_with(
+            " - test - 10:5 - Command GET executed - client id=7 addr=127.0.0.1:5000 - 42us\n"
+        ));
+        assert_eq!(
+            "<135>redis[1]: test:10:5 - Command GET executed - client id=7 addr=127.0.0.1:5000 - 42us",
+            log.to_syslog("redis", 1)
+        );
+    }
+
+    #[test]
+    fn test_log_to_syslog_uses_level_as_severity() {
+        let log = Log::new(
+            LogLevel::Debug,
+            10,
+            5,
+            "test".to_string(),
+            "mensaje".to_string(),
+        );
+        assert_eq!(
+            "<135>redis[42]: test:10:5 - mensaje",
+            log.to_syslog("redis", 42)
+        );
+        let log = Log::new(
+            LogLevel::Info,
+            10,
+            5,
+            "test".to_string(),
+            "mensaje".to_string(),
+        );
+        assert!(log.to_syslog("redis", 42).starts_with("<134>"));
+    }
+
     #[allow(dead_code)]
     fn test_get_level() {
         let log = Log::new(
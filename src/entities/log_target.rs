@@ -0,0 +1,46 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// LogTarget: Enum usado para representar el parámetro de configuración `logtarget`, que indica
+/// a dónde se envían los logs del servidor.
+pub enum LogTarget {
+    /// Los logs se agregan al archivo indicado en `logfile`.
+    File,
+    /// Los logs se envían al daemon de syslog local, sin escribir ningún archivo.
+    Syslog,
+}
+
+impl FromStr for LogTarget {
+    type Err = String;
+
+    fn from_str(target: &str) -> Result<Self, Self::Err> {
+        match target.to_lowercase().as_str() {
+            "file" => Ok(LogTarget::File),
+            "syslog" => Ok(LogTarget::Syslog),
+            _ => Err(format!("Invalid log target '{}'", target)),
+        }
+    }
+}
+
+impl fmt::Display for LogTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogTarget::File => write!(f, "file"),
+            LogTarget::Syslog => write!(f, "syslog"),
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::log_target::LogTarget;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_and_display_log_target() {
+        assert_eq!(LogTarget::Syslog, LogTarget::from_str("SYSLOG").unwrap());
+        assert_eq!("file", LogTarget::from_str("file").unwrap().to_string());
+        assert!(LogTarget::from_str("stdout").is_err());
+    }
+}
@@ -0,0 +1,185 @@
+/// Cantidad máxima de bytes de una secuencia de literales.
+const MAX_LITERALS: usize = 32;
+/// Distancia máxima hacia atrás de una referencia.
+const MAX_DISTANCE: usize = 1 << 13;
+/// Longitud máxima de una referencia.
+const MAX_REFERENCE_LEN: usize = 264;
+/// Cantidad de bits del hash usado para buscar coincidencias.
+const HASH_LOG: u32 = 14;
+
+/// Comprime los bytes con LZF, el algoritmo que usa Redis para comprimir los strings de los
+/// archivos RDB.
+///
+/// Las coincidencias de al menos 3 bytes con los bytes anteriores se reemplazan por una referencia
+/// hacia atrás, y el resto se guarda como secuencias de literales.
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut output: Vec<u8> = Vec::new();
+    let mut table: Vec<Option<usize>> = vec![None; 1 << HASH_LOG];
+    let mut literals_start = 0;
+    let mut pos = 0;
+
+    while pos + 2 < input.len() {
+        let hash = hash(&input[pos..pos + 3]);
+        let candidate = table[hash];
+        table[hash] = Some(pos);
+
+        match candidate {
+            Some(start)
+                if pos - start <= MAX_DISTANCE
+                    && input[start..start + 3] == input[pos..pos + 3] =>
+            {
+                let max_len = MAX_REFERENCE_LEN.min(input.len() - pos);
+                let mut len = 3;
+                while len < max_len && input[start + len] == input[pos + len] {
+                    len += 1;
+                }
+
+                push_literals(&mut output, &input[literals_start..pos]);
+                push_reference(&mut output, len, pos - start - 1);
+                pos += len;
+                literals_start = pos;
+            }
+            _ => pos += 1,
+        }
+    }
+
+    push_literals(&mut output, &input[literals_start..]);
+    output
+}
+
+/// Devuelve el hash de los 3 bytes indicados.
+fn hash(bytes: &[u8]) -> usize {
+    let value = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32;
+    (value.wrapping_mul(2_654_435_761) >> (32 - HASH_LOG)) as usize
+}
+
+/// Agrega los bytes como secuencias de hasta 32 literales, precedidas por su longitud menos 1.
+fn push_literals(output: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_LITERALS) {
+        output.push((chunk.len() - 1) as u8);
+        output.extend_from_slice(chunk);
+    }
+}
+
+/// Agrega una referencia de `len` bytes a `offset + 1` bytes hacia atrás.
+fn push_reference(output: &mut Vec<u8>, len: usize, offset: usize) {
+    let len = len - 2;
+    if len < 7 {
+        output.push(((len << 5) | (offset >> 8)) as u8);
+    } else {
+        output.push(((7 << 5) | (offset >> 8)) as u8);
+        output.push((len - 7) as u8);
+    }
+    output.push(offset as u8);
+}
+
+/// Descomprime un bloque comprimido con LZF, el algoritmo que usa Redis para comprimir los strings
+/// de los archivos RDB.
+///
+/// # Arguments
+///
+/// * `input` - Bytes comprimidos.
+/// * `expected_len` - Longitud de los datos descomprimidos, que se guarda junto al bloque.
+///
+/// Retorna error si el bloque está mal formado o no tiene la longitud indicada.
+pub fn decompress(input: &[u8], expected_len: usize) -> Result<Vec<u8>, String> {
+    let mut output: Vec<u8> = Vec::new();
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let ctrl = input[pos] as usize;
+        pos += 1;
+
+        if ctrl < 32 {
+            // Secuencia de ctrl + 1 bytes literales.
+            let end = pos + ctrl + 1;
+            if end > input.len() {
+                return Err("LZF literal run out of bounds".to_string());
+            }
+            if output.len() + ctrl + 1 > expected_len {
+                return Err("LZF decompressed length mismatch".to_string());
+            }
+            output.extend_from_slice(&input[pos..end]);
+            pos = end;
+        } else {
+            // Referencia a bytes ya descomprimidos: longitud y distancia hacia atrás.
+            let mut len = ctrl >> 5;
+            if len == 7 {
+                len += *input.get(pos).ok_or("LZF back reference out of bounds")? as usize;
+                pos += 1;
+            }
+            let low = *input.get(pos).ok_or("LZF back reference out of bounds")? as usize;
+            pos += 1;
+
+            let distance = ((ctrl & 0x1f) << 8) + low + 1;
+            if distance > output.len() {
+                return Err("LZF back reference out of bounds".to_string());
+            }
+            if output.len() + len + 2 > expected_len {
+                return Err("LZF decompressed length mismatch".to_string());
+            }
+            let start = output.len() - distance;
+            // Las referencias pueden solaparse con los bytes que se están copiando.
+            for i in 0..len + 2 {
+                output.push(output[start + i]);
+            }
+        }
+    }
+
+    if output.len() != expected_len {
+        return Err("LZF decompressed length mismatch".to_string());
+    }
+    Ok(output)
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::lzf::{compress, decompress};
+
+    #[test]
+    fn test_decompress_literals() {
+        let input = [2, b'a', b'b', b'c'];
+
+        assert_eq!(b"abc".to_vec(), decompress(&input, 3).unwrap());
+    }
+
+    #[test]
+    fn test_decompress_back_reference() {
+        // "ab" literal, luego una referencia de 6 bytes a distancia 2: "abababab".
+        let input = [1, b'a', b'b', 4 << 5, 1];
+
+        assert_eq!(b"abababab".to_vec(), decompress(&input, 8).unwrap());
+    }
+
+    #[test]
+    fn test_decompress_invalid_input() {
+        assert!(decompress(&[5, b'a'], 6).is_err());
+        assert!(decompress(&[1 << 5, 9], 3).is_err());
+        assert!(decompress(&[0, b'a'], 2).is_err());
+    }
+
+    #[test]
+    fn test_compress_repetitive_input() {
+        let input = "abcabcabc-".repeat(100).into_bytes();
+
+        let compressed = compress(&input);
+
+        assert!(compressed.len() < input.len() / 10);
+        assert_eq!(input, decompress(&compressed, input.len()).unwrap());
+    }
+
+    #[test]
+    fn test_compress_round_trip() {
+        let mut seed: u32 = 7;
+        let random: Vec<u8> = (0..5000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8 % 4
+            })
+            .collect();
+
+        for input in [vec![], b"ab".to_vec(), random] {
+            assert_eq!(input, decompress(&compress(&input), input.len()).unwrap());
+        }
+    }
+}
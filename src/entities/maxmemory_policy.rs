@@ -0,0 +1,37 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// MaxmemoryPolicy: Qué hacer cuando la memoria usada por los datos supera `maxmemory` y se
+/// ejecuta un comando que puede agregar datos.
+pub enum MaxmemoryPolicy {
+    /// No se descarta ninguna clave: el comando se responde con un error.
+    NoEviction,
+    /// Se descartan las claves accedidas con menor frecuencia, entre todas las claves.
+    AllkeysLfu,
+    /// Se descartan las claves accedidas con menor frecuencia, entre las que tienen expiración.
+    VolatileLfu,
+}
+
+impl FromStr for MaxmemoryPolicy {
+    type Err = String;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.to_lowercase().as_str() {
+            "noeviction" => Ok(MaxmemoryPolicy::NoEviction),
+            "allkeys-lfu" => Ok(MaxmemoryPolicy::AllkeysLfu),
+            "volatile-lfu" => Ok(MaxmemoryPolicy::VolatileLfu),
+            _ => Err(format!("Invalid maxmemory policy '{}'", policy)),
+        }
+    }
+}
+
+impl fmt::Display for MaxmemoryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaxmemoryPolicy::NoEviction => write!(f, "noeviction"),
+            MaxmemoryPolicy::AllkeysLfu => write!(f, "allkeys-lfu"),
+            MaxmemoryPolicy::VolatileLfu => write!(f, "volatile-lfu"),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use crate::entities::lfu::LfuCounter;
+use crate::entities::redis_element::RedisElement;
+use crate::entities::stream::{PendingEntry, StreamFields, StreamId};
+use std::mem::size_of;
+use std::time::SystemTime;
+
+/// Cantidad de elementos que se muestrean por defecto en las colecciones.
+pub const DEFAULT_SAMPLES: usize = 5;
+
+/// Bytes de control que usa un `HashMap` por cada entrada.
+const HASH_ENTRY_OVERHEAD: usize = 1;
+
+/// Largo máximo de un string codificado como `embstr` en Redis.
+const EMBSTR_MAX_LEN: usize = 44;
+
+/// Cantidad máxima de elementos de una colección codificada como `listpack` en Redis.
+const LISTPACK_MAX_ENTRIES: usize = 128;
+
+/// Largo máximo de cada elemento de una colección codificada como `listpack` en Redis.
+const LISTPACK_MAX_VALUE: usize = 64;
+
+/// Cantidad máxima de elementos de un set codificado como `intset` en Redis.
+const INTSET_MAX_ENTRIES: usize = 512;
+
+/// Estima los bytes que ocupa en memoria la clave junto con su valor: el texto de la clave, las
+/// entradas en los mapas del `TtlHashMap` y el contenido del valor.
+///
+/// En las colecciones se calcula el tamaño promedio de los primeros `samples` elementos y se lo
+/// multiplica por la cantidad de elementos, como hace Redis. Con `samples` igual a 0 se recorren
+/// todos los elementos.
+pub fn memory_usage(key: &str, value: &RedisElement, samples: usize) -> usize {
+    // La clave se guarda en el mapa de valores, en el de últimos accesos y en el de frecuencias.
+    let key_size = 3 * (size_of::<String>() + key.len() + HASH_ENTRY_OVERHEAD);
+    let entry_size = size_of::<RedisElement>() + size_of::<SystemTime>() + size_of::<LfuCounter>();
+    key_size + entry_size + value_size(value, samples)
+}
+
+/// Valores cuyo tamaño en memoria puede estimarse, para que el `TtlHashMap` lleve la cuenta de
+/// la memoria que usan sus claves sin recorrerlas.
+pub trait MemoryUsage {
+    /// Estima los bytes que ocupa el valor junto con la clave en la que está guardado.
+    fn memory_usage(&self, key: &str) -> usize;
+}
+
+impl MemoryUsage for RedisElement {
+    fn memory_usage(&self, key: &str) -> usize {
+        memory_usage(key, self, DEFAULT_SAMPLES)
+    }
+}
+
+/// Devuelve la codificación que usaría Redis para guardar el valor, según su tipo, su tamaño y
+/// los valores por defecto de la configuración de Redis.
+pub fn encoding(value: &RedisElement) -> &'static str {
+    match value {
+        RedisElement::String(string) | RedisElement::SimpleString(string) => {
+            if string.len() <= EMBSTR_MAX_LEN {
+                "embstr"
+            } else {
+                "raw"
+            }
+        }
+        // Como en Redis, los strings modificados por los comandos de bitmaps no se comparten.
+        RedisElement::Bytes(_) => "raw",
+        RedisElement::List(list) => {
+            if fits_listpack(list.len(), list.iter()) {
+                "listpack"
+            } else {
+                "quicklist"
+            }
+        }
+        RedisElement::Set(set) => {
+            if set.len() <= INTSET_MAX_ENTRIES
+                && set.iter().all(|member| member.parse::<i64>().is_ok())
+            {
+                "intset"
+            } else if fits_listpack(set.len(), set.iter()) {
+                "listpack"
+            } else {
+                "hashtable"
+            }
+        }
+        RedisElement::SortedSet(sorted_set) => {
+            if fits_listpack(
+                sorted_set.len(),
+                sorted_set.iter().map(|(member, _)| member),
+            ) {
+                "listpack"
+            } else {
+                "skiplist"
+            }
+        }
+        RedisElement::Stream(_) => "stream",
+        RedisElement::Integer(_) => "int",
+        RedisElement::Array(_) | RedisElement::Nil => "raw",
+    }
+}
+
+/// Indica si una colección de `len` elementos entra en un `listpack`.
+fn fits_listpack<'a, I: Iterator<Item = &'a String>>(len: usize, mut elements: I) -> bool {
+    len <= LISTPACK_MAX_ENTRIES && elements.all(|element| element.len() <= LISTPACK_MAX_VALUE)
+}
+
+/// Expresa una cantidad de bytes con la unidad más grande que corresponda, como Redis en
+/// `used_memory_human`: `512B`, `1.50K`, `2.00M`.
+pub fn human_bytes(bytes: u64) -> String {
+    let units = ["K", "M", "G", "T"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2}{}", value, units[unit])
+}
+
+/// Estima los bytes del contenido del valor, sin contar el propio `RedisElement`.
+fn value_size(value: &RedisElement, samples: usize) -> usize {
+    match value {
+        RedisElement::String(string) | RedisElement::SimpleString(string) => string.capacity(),
+        RedisElement::Bytes(bytes) => bytes.capacity(),
+        RedisElement::List(list) => sampled(
+            list.iter().map(|element| string_size(element)),
+            list.len(),
+            samples,
+        ),
+        RedisElement::Set(set) => sampled(
+            set.iter()
+                .map(|element| string_size(element) + HASH_ENTRY_OVERHEAD),
+            set.len(),
+            samples,
+        ),
+        // Cada miembro se guarda en el mapa de scores y en el índice ordenado, junto a su score.
+        RedisElement::SortedSet(sorted_set) => sampled(
+            sorted_set.iter().map(|(member, _)| {
+                2 * (string_size(member) + size_of::<f64>()) + HASH_ENTRY_OVERHEAD
+            }),
+            sorted_set.len(),
+            samples,
+        ),
+        RedisElement::Stream(stream) => {
+            let entries = sampled(
+                stream.iter().map(|(_, fields)| {
+                    size_of::<StreamId>()
+                        + size_of::<StreamFields>()
+                        + fields
+                            .iter()
+                            .map(|(field, value)| string_size(field) + string_size(value))
+                            .sum::<usize>()
+                }),
+                stream.len(),
+                samples,
+            );
+            let groups: usize = stream
+                .groups()
+                .map(|(name, group)| {
+                    string_size(name)
+                        + group
+                            .consumers
+                            .keys()
+                            .map(|consumer| string_size(consumer) + size_of::<SystemTime>())
+                            .sum::<usize>()
+                        + group.pending.len() * (size_of::<StreamId>() + size_of::<PendingEntry>())
+                })
+                .sum();
+            entries + groups
+        }
+        RedisElement::Array(array) => array
+            .iter()
+            .map(|element| size_of::<RedisElement>() + value_size(element, samples))
+            .sum(),
+        RedisElement::Nil | RedisElement::Integer(_) => 0,
+    }
+}
+
+/// Bytes que ocupa un `String` guardado dentro de una colección.
+fn string_size(string: &str) -> usize {
+    size_of::<String>() + string.len()
+}
+
+/// Extrapola el tamaño total de una colección de `len` elementos a partir del tamaño de los
+/// primeros `samples` elementos (todos si `samples` es 0).
+fn sampled<I: Iterator<Item = usize>>(sizes: I, len: usize, samples: usize) -> usize {
+    let count = if samples == 0 { len } else { samples.min(len) };
+    if count == 0 {
+        return 0;
+    }
+    sizes.take(count).sum::<usize>() * len / count
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::memory::{encoding, human_bytes, memory_usage, DEFAULT_SAMPLES};
+    use crate::entities::redis_element::RedisElement;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_memory_usage_grows_with_value() {
+        let short = RedisElement::String("a".to_string());
+        let long = RedisElement::String("a".repeat(1000));
+
+        let short_usage = memory_usage("key", &short, DEFAULT_SAMPLES);
+        let long_usage = memory_usage("key", &long, DEFAULT_SAMPLES);
+
+        assert_eq!(999, long_usage - short_usage);
+        assert!(memory_usage("longer key", &short, DEFAULT_SAMPLES) > short_usage);
+    }
+
+    #[test]
+    fn test_memory_usage_samples_collections() {
+        let mut list = vec!["x".repeat(100); 5];
+        list.extend(vec!["y".to_string(); 5]);
+        let list = RedisElement::List(list);
+
+        // Con los 5 primeros elementos se estima que todos son grandes.
+        let sampled = memory_usage("list", &list, 5);
+        let exact = memory_usage("list", &list, 0);
+        assert_eq!(5 * 99, sampled - exact);
+
+        let set: HashSet<String> = (0..10).map(|i| i.to_string()).collect();
+        let set = RedisElement::Set(set);
+        assert_eq!(memory_usage("set", &set, 0), memory_usage("set", &set, 100));
+    }
+
+    #[test]
+    fn test_encoding_depends_on_type_and_size() {
+        assert_eq!("int", encoding(&RedisElement::Integer(-12)));
+        assert_eq!(
+            "embstr",
+            encoding(&RedisElement::String("value".to_string()))
+        );
+        assert_eq!("raw", encoding(&RedisElement::String("a".repeat(45))));
+
+        assert_eq!(
+            "listpack",
+            encoding(&RedisElement::List(vec!["a".to_string()]))
+        );
+        assert_eq!(
+            "quicklist",
+            encoding(&RedisElement::List(vec!["a".repeat(65)]))
+        );
+
+        let integers: HashSet<String> = (0..10).map(|i| i.to_string()).collect();
+        assert_eq!("intset", encoding(&RedisElement::Set(integers)));
+        let members: HashSet<String> = (0..200).map(|i| format!("m{}", i)).collect();
+        assert_eq!("hashtable", encoding(&RedisElement::Set(members)));
+    }
+
+    #[test]
+    fn test_human_bytes() {
+        assert_eq!("512B", human_bytes(512));
+        assert_eq!("1.50K", human_bytes(1536));
+        assert_eq!("2.00M", human_bytes(2 * 1024 * 1024));
+    }
+}
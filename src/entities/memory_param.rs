@@ -0,0 +1,7 @@
+#[derive(Debug)]
+/// MemoryParam: Enum usado para representar los subcomandos permitidos para el Command::Memory.
+pub enum MemoryParam {
+    /// Representa el subcomando Usage, que estima los bytes que ocupa la clave. `samples` indica
+    /// cuántos elementos de las colecciones se muestrean (0 para recorrerlos todos).
+    Usage { key: String, samples: usize },
+}
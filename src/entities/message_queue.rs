@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::mpsc::{RecvError, SendError, TryRecvError, TrySendError};
+use std::sync::{Arc, Condvar, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// OverflowPolicy: Qué hacer cuando la cola de mensajes de un suscriptor está llena porque el
+/// cliente no los lee a tiempo.
+pub enum OverflowPolicy {
+    /// Se descarta el mensaje más antiguo de la cola para hacer lugar al nuevo.
+    DropOldest,
+    /// Se desconecta al cliente.
+    Disconnect,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.to_lowercase().as_str() {
+            "drop-oldest" => Ok(OverflowPolicy::DropOldest),
+            "disconnect" => Ok(OverflowPolicy::Disconnect),
+            _ => Err(format!("Invalid overflow policy '{}'", policy)),
+        }
+    }
+}
+
+impl fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OverflowPolicy::DropOldest => write!(f, "drop-oldest"),
+            OverflowPolicy::Disconnect => write!(f, "disconnect"),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Estado compartido entre los extremos de la cola.
+struct State<T> {
+    messages: VecDeque<T>,
+    /// Cantidad de `MessageSender` vivos. Sin ninguno, el receptor deja de esperar mensajes.
+    senders: usize,
+    /// Indica si el `MessageReceiver` sigue vivo. Sin él, los envíos fallan.
+    receiver: bool,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    available: Condvar,
+    /// Cantidad máxima de mensajes pendientes. Si es 0 no hay límite.
+    capacity: usize,
+}
+
+#[derive(Debug)]
+/// MessageSender: Extremo de envío de una cola de mensajes con capacidad limitada. Como el
+/// `Sender` de `mpsc`, puede clonarse para enviar desde varios lugares a un mismo receptor.
+pub struct MessageSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+#[derive(Debug)]
+/// MessageReceiver: Extremo de recepción de una cola de mensajes, con la misma interfaz que el
+/// `Receiver` de `mpsc`.
+pub struct MessageReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Crea una cola de mensajes que admite hasta `capacity` mensajes pendientes. Si `capacity` es 0
+/// no hay límite.
+pub fn message_queue<T>(capacity: usize) -> (MessageSender<T>, MessageReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            messages: VecDeque::new(),
+            senders: 1,
+            receiver: true,
+        }),
+        available: Condvar::new(),
+        capacity,
+    });
+    (
+        MessageSender {
+            shared: Arc::clone(&shared),
+        },
+        MessageReceiver { shared },
+    )
+}
+
+impl<T> MessageSender<T> {
+    /// Encola el mensaje sin importar la capacidad de la cola. Falla si ya no existe el receptor.
+    pub fn send(&self, message: T) -> Result<(), SendError<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.receiver {
+            return Err(SendError(message));
+        }
+        state.messages.push_back(message);
+        self.shared.available.notify_one();
+        Ok(())
+    }
+
+    /// Encola el mensaje si hay lugar en la cola.
+    pub fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.receiver {
+            return Err(TrySendError::Disconnected(message));
+        }
+        if self.shared.capacity > 0 && state.messages.len() >= self.shared.capacity {
+            return Err(TrySendError::Full(message));
+        }
+        state.messages.push_back(message);
+        self.shared.available.notify_one();
+        Ok(())
+    }
+
+    /// Encola el mensaje, descartando el más antiguo si la cola está llena. Devuelve si se
+    /// descartó un mensaje.
+    pub fn send_dropping_oldest(&self, message: T) -> Result<bool, SendError<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.receiver {
+            return Err(SendError(message));
+        }
+        let full = self.shared.capacity > 0 && state.messages.len() >= self.shared.capacity;
+        if full {
+            state.messages.pop_front();
+        }
+        state.messages.push_back(message);
+        self.shared.available.notify_one();
+        Ok(full)
+    }
+}
+
+impl<T> Clone for MessageSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().unwrap().senders += 1;
+        MessageSender {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for MessageSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.shared.available.notify_all();
+        }
+    }
+}
+
+impl<T> MessageReceiver<T> {
+    /// Espera el próximo mensaje. Falla si la cola está vacía y ya no quedan emisores.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            if let Some(message) = state.messages.pop_front() {
+                return Ok(message);
+            }
+            if state.senders == 0 {
+                return Err(RecvError);
+            }
+            state = self.shared.available.wait(state).unwrap();
+        }
+    }
+
+    /// Devuelve el próximo mensaje sin esperar.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.messages.pop_front() {
+            Some(message) => Ok(message),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Itera los mensajes pendientes sin esperar a que lleguen nuevos.
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(move || self.try_recv().ok())
+    }
+}
+
+impl<T> Drop for MessageReceiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.receiver = false;
+        state.messages.clear();
+    }
+}
+
+impl<T> IntoIterator for MessageReceiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Itera los mensajes esperando a que lleguen, hasta que no queden emisores.
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { receiver: self }
+    }
+}
+
+/// Iterador que espera los mensajes de un `MessageReceiver`.
+pub struct IntoIter<T> {
+    receiver: MessageReceiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::message_queue::{message_queue, OverflowPolicy};
+    use std::str::FromStr;
+    use std::sync::mpsc::TrySendError;
+    use std::thread;
+
+    #[test]
+    fn try_send_fails_when_full() {
+        let (sender, receiver) = message_queue(2);
+        assert!(sender.try_send(1).is_ok());
+        assert!(sender.try_send(2).is_ok());
+        assert_eq!(Err(TrySendError::Full(3)), sender.try_send(3));
+
+        assert_eq!(Ok(1), receiver.recv());
+        assert!(sender.try_send(3).is_ok());
+        assert_eq!(vec![2, 3], receiver.try_iter().collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn send_dropping_oldest_keeps_the_newest_messages() {
+        let (sender, receiver) = message_queue(2);
+        assert_eq!(Ok(false), sender.send_dropping_oldest(1));
+        assert_eq!(Ok(false), sender.send_dropping_oldest(2));
+        assert_eq!(Ok(true), sender.send_dropping_oldest(3));
+        // `send` no respeta la capacidad.
+        assert!(sender.send(4).is_ok());
+
+        assert_eq!(vec![2, 3, 4], receiver.try_iter().collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn receiver_ends_when_every_sender_is_dropped() {
+        let (sender, receiver) = message_queue(0);
+        let clone = sender.clone();
+        drop(sender);
+        let handle = thread::spawn(move || receiver.into_iter().collect::<Vec<i32>>());
+        clone.send(1).unwrap();
+        drop(clone);
+
+        assert_eq!(vec![1], handle.join().unwrap());
+    }
+
+    #[test]
+    fn send_fails_without_receiver() {
+        let (sender, receiver) = message_queue(0);
+        drop(receiver);
+
+        assert!(sender.send(1).is_err());
+        assert_eq!(Err(TrySendError::Disconnected(1)), sender.try_send(1));
+    }
+
+    #[test]
+    fn parse_overflow_policy() {
+        let policy = OverflowPolicy::from_str("DROP-OLDEST").unwrap();
+        assert_eq!(OverflowPolicy::DropOldest, policy);
+        assert_eq!("disconnect", OverflowPolicy::Disconnect.to_string());
+        assert!(OverflowPolicy::from_str("block").is_err());
+    }
+}
@@ -1,8 +1,47 @@
+pub mod bitmap;
+pub mod blocked_clients;
+pub mod client_param;
+pub mod clock;
+pub mod cluster;
+pub mod cluster_param;
 pub mod command;
+pub mod command_param;
+pub mod command_stats;
+pub mod command_table;
+pub mod connected_clients;
+pub mod consumer_group_param;
+pub mod crc64;
+pub mod debug_param;
+pub mod expire_condition;
+pub mod geo;
 pub mod info_param;
+pub mod key_change;
+pub mod keyspace;
+pub mod keyspace_events;
+pub mod lfu;
+pub mod listpack;
 pub mod log;
 pub mod log_level;
+pub mod log_target;
+pub mod lzf;
+pub mod maxmemory_policy;
+pub mod memory;
+pub mod memory_param;
+pub mod message_queue;
+pub mod object_param;
 pub mod pubsub_param;
+pub mod rate_limiter;
+pub mod rdb;
 pub mod redis_element;
 pub mod response;
+pub mod script_param;
+pub mod server_stats;
+pub mod set_options;
+pub mod sha1;
+pub mod shared_commands;
+pub mod slowlog;
+pub mod slowlog_param;
+pub mod sort_options;
+pub mod sorted_set;
+pub mod stream;
 pub mod ttl_hash_map;
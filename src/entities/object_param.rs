@@ -0,0 +1,8 @@
+#[derive(Debug)]
+/// ObjectParam: Enum usado para representar los subcomandos permitidos para el Command::Object.
+pub enum ObjectParam {
+    /// Representa el subcomando Freq, que devuelve la frecuencia de acceso a la clave.
+    Freq(String),
+    /// Representa el subcomando Encoding, que devuelve la codificación interna del valor.
+    Encoding(String),
+}
@@ -9,4 +9,6 @@ pub enum PubSubParam {
     Numsub,
     /// Representa el Parametro Numsub de PubSub con canales específicos.
     NumsubWithChannels(Vec<String>),
+    /// Representa el Parametro Numpat de PubSub.
+    Numpat,
 }
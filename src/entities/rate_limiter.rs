@@ -0,0 +1,130 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// RateLimitPolicy: Qué hacer con los comandos de un cliente que supera la cantidad máxima de
+/// comandos por segundo.
+pub enum RateLimitPolicy {
+    /// Se espera hasta que el cliente pueda volver a ejecutar un comando.
+    Delay,
+    /// Se responde un error sin ejecutar el comando.
+    Reject,
+}
+
+impl FromStr for RateLimitPolicy {
+    type Err = String;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.to_lowercase().as_str() {
+            "delay" => Ok(RateLimitPolicy::Delay),
+            "reject" => Ok(RateLimitPolicy::Reject),
+            _ => Err(format!("Invalid rate limit policy '{}'", policy)),
+        }
+    }
+}
+
+impl fmt::Display for RateLimitPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RateLimitPolicy::Delay => write!(f, "delay"),
+            RateLimitPolicy::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// RateLimiter: Token bucket que limita la cantidad de comandos por segundo de un cliente. El
+/// bucket se llena a razón de `rate` tokens por segundo hasta tener `rate` tokens, por lo que el
+/// cliente puede enviar ráfagas de hasta un segundo de comandos.
+pub struct RateLimiter {
+    /// Tokens disponibles. Cada comando consume uno.
+    tokens: f64,
+    /// Momento en que se actualizaron los tokens por última vez.
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Crea un limitador con el bucket lleno. Como la tasa se conoce recién al consumir, los tokens
+    /// se limitan a la capacidad del bucket en cada llamada a `acquire`.
+    pub fn new(now: Instant) -> Self {
+        RateLimiter {
+            tokens: f64::INFINITY,
+            last_refill: now,
+        }
+    }
+
+    /// Intenta consumir un token con una tasa de `rate` comandos por segundo, que se recibe en
+    /// cada llamada porque puede modificarse con CONFIG SET. Devuelve None si el comando puede
+    /// ejecutarse, o el tiempo que falta para que haya un token disponible. Con `rate` 0 no hay
+    /// límite.
+    pub fn acquire(&mut self, rate: u64, now: Instant) -> Option<Duration> {
+        if rate == 0 {
+            return None;
+        }
+        let rate = rate as f64;
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens.min(rate) + elapsed * rate).min(rate);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::rate_limiter::{RateLimitPolicy, RateLimiter};
+    use std::str::FromStr;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_acquire_allows_a_burst_of_rate_commands() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter::new(now);
+
+        for _ in 0..10 {
+            assert_eq!(None, limiter.acquire(10, now));
+        }
+        assert_eq!(Some(Duration::from_millis(100)), limiter.acquire(10, now));
+    }
+
+    #[test]
+    fn test_acquire_refills_tokens_over_time() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter::new(now);
+        for _ in 0..2 {
+            assert_eq!(None, limiter.acquire(2, now));
+        }
+        let wait = limiter.acquire(2, now).unwrap();
+        assert_eq!(Duration::from_millis(500), wait);
+
+        assert_eq!(None, limiter.acquire(2, now + wait));
+        assert!(limiter.acquire(2, now + wait).is_some());
+    }
+
+    #[test]
+    fn test_acquire_without_rate_has_no_limit() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter::new(now);
+        for _ in 0..1000 {
+            assert_eq!(None, limiter.acquire(0, now));
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_policy_from_str() {
+        assert_eq!(
+            Ok(RateLimitPolicy::Delay),
+            RateLimitPolicy::from_str("DELAY")
+        );
+        assert_eq!("reject", RateLimitPolicy::Reject.to_string());
+        assert!(RateLimitPolicy::from_str("drop").is_err());
+    }
+}
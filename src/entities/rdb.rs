@@ -0,0 +1,939 @@
+use crate::entities::crc64::crc64;
+use crate::entities::listpack;
+use crate::entities::lzf;
+use crate::entities::redis_element::RedisElement;
+use crate::entities::sorted_set::SortedSet;
+use crate::entities::stream::{ConsumerGroup, PendingEntry, Stream, StreamFields, StreamId};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::str::from_utf8;
+use std::time::{Duration, SystemTime};
+
+/// Versión del formato RDB que se escribe. Es la versión de Redis 5 y 6, que las versiones
+/// posteriores de Redis también pueden cargar.
+pub const RDB_VERSION: u32 = 9;
+/// Máxima versión del formato RDB que se puede cargar.
+pub const MAX_RDB_VERSION: u32 = 12;
+
+pub const OP_SLOT_INFO: u8 = 0xf4;
+pub const OP_FUNCTION2: u8 = 0xf5;
+pub const OP_FUNCTION: u8 = 0xf6;
+pub const OP_MODULE_AUX: u8 = 0xf7;
+pub const OP_IDLE: u8 = 0xf8;
+pub const OP_FREQ: u8 = 0xf9;
+pub const OP_AUX: u8 = 0xfa;
+pub const OP_RESIZEDB: u8 = 0xfb;
+pub const OP_EXPIRETIME_MS: u8 = 0xfc;
+pub const OP_EXPIRETIME: u8 = 0xfd;
+pub const OP_SELECTDB: u8 = 0xfe;
+pub const OP_EOF: u8 = 0xff;
+
+pub const TYPE_STRING: u8 = 0;
+pub const TYPE_LIST: u8 = 1;
+pub const TYPE_SET: u8 = 2;
+pub const TYPE_ZSET: u8 = 3;
+pub const TYPE_HASH: u8 = 4;
+pub const TYPE_ZSET_2: u8 = 5;
+pub const TYPE_MODULE: u8 = 6;
+pub const TYPE_MODULE_2: u8 = 7;
+pub const TYPE_HASH_ZIPMAP: u8 = 9;
+pub const TYPE_LIST_ZIPLIST: u8 = 10;
+pub const TYPE_SET_INTSET: u8 = 11;
+pub const TYPE_ZSET_ZIPLIST: u8 = 12;
+pub const TYPE_HASH_ZIPLIST: u8 = 13;
+pub const TYPE_LIST_QUICKLIST: u8 = 14;
+pub const TYPE_STREAM_LISTPACKS: u8 = 15;
+pub const TYPE_HASH_LISTPACK: u8 = 16;
+pub const TYPE_ZSET_LISTPACK: u8 = 17;
+pub const TYPE_LIST_QUICKLIST_2: u8 = 18;
+pub const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+pub const TYPE_SET_LISTPACK: u8 = 20;
+pub const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+/// Encodings especiales de los strings, indicados con los bits `11` en el primer byte del largo.
+const ENC_INT8: u8 = 0;
+const ENC_INT16: u8 = 1;
+const ENC_INT32: u8 = 2;
+const ENC_LZF: u8 = 3;
+
+/// Largo a partir del cual se intenta comprimir un string, el mismo que usa Redis.
+const COMPRESSION_THRESHOLD: usize = 20;
+
+/// Error devuelto por RESTORE cuando la versión o el checksum del valor serializado no son válidos.
+pub const BAD_PAYLOAD_MSG: &str = "ERR DUMP payload version or checksum are wrong";
+/// Error devuelto por RESTORE cuando el valor serializado está mal formado.
+pub const BAD_FORMAT_MSG: &str = "ERR Bad data format";
+
+/// Tipos de nodo de las listas de tipo QUICKLIST_2.
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+const QUICKLIST_NODE_PACKED: u64 = 2;
+
+/// Flags de las entradas de los nodos de un stream.
+const STREAM_ITEM_FLAG_DELETED: i64 = 1;
+const STREAM_ITEM_FLAG_SAMEFIELDS: i64 = 2;
+
+/// Agrega el largo codificado según el formato RDB.
+pub fn write_length(bytes: &mut Vec<u8>, length: u64) {
+    if length < 1 << 6 {
+        // 00 + largo en 6 bits
+        bytes.push(length as u8);
+    } else if length < 1 << 14 {
+        // 01 + largo en 14 bits
+        bytes.push(0x40 | (length >> 8) as u8);
+        bytes.push(length as u8);
+    } else if length <= u32::MAX as u64 {
+        // 1000 0000 + largo en 32 bits
+        bytes.push(0x80);
+        bytes.extend_from_slice(&(length as u32).to_be_bytes());
+    } else {
+        // 1000 0001 + largo en 64 bits
+        bytes.push(0x81);
+        bytes.extend_from_slice(&length.to_be_bytes());
+    }
+}
+
+/// Agrega el string precedido de su largo. Los strings de más de 20 bytes se comprimen con LZF,
+/// siempre que se ahorren al menos 4 bytes.
+pub fn write_string(bytes: &mut Vec<u8>, string: &[u8]) {
+    if string.len() > COMPRESSION_THRESHOLD {
+        let compressed = lzf::compress(string);
+        if compressed.len() + 4 <= string.len() {
+            bytes.push(0xc0 | ENC_LZF);
+            write_length(bytes, compressed.len() as u64);
+            write_length(bytes, string.len() as u64);
+            bytes.extend_from_slice(&compressed);
+            return;
+        }
+    }
+    write_length(bytes, string.len() as u64);
+    bytes.extend_from_slice(string);
+}
+
+/// Agrega un string con codificación `int`. Como Redis, si el entero entra en 32 bits se guarda
+/// como un entero de 1, 2 o 4 bytes, y si no como texto.
+pub fn write_integer(bytes: &mut Vec<u8>, integer: i64) {
+    if let Ok(integer) = i8::try_from(integer) {
+        bytes.push(0xc0 | ENC_INT8);
+        bytes.extend_from_slice(&integer.to_le_bytes());
+    } else if let Ok(integer) = i16::try_from(integer) {
+        bytes.push(0xc0 | ENC_INT16);
+        bytes.extend_from_slice(&integer.to_le_bytes());
+    } else if let Ok(integer) = i32::try_from(integer) {
+        bytes.push(0xc0 | ENC_INT32);
+        bytes.extend_from_slice(&integer.to_le_bytes());
+    } else {
+        write_string(bytes, integer.to_string().as_bytes());
+    }
+}
+
+/// Agrega un double en binario (little endian), como lo usan los sorted sets de tipo ZSET_2.
+pub fn write_binary_double(bytes: &mut Vec<u8>, value: f64) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Agrega un instante expresado en milisegundos (little endian).
+pub fn write_millis(bytes: &mut Vec<u8>, millis: u64) {
+    bytes.extend_from_slice(&millis.to_le_bytes());
+}
+
+/// Agrega un instante expresado en milisegundos desde UNIX_EPOCH (little endian).
+pub fn write_time(bytes: &mut Vec<u8>, time: SystemTime) {
+    let millis = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_else(|_| Duration::from_secs(0))
+        .as_millis();
+    write_millis(bytes, millis as u64);
+}
+
+/// Agrega el encabezado del archivo: la palabra REDIS seguida de la versión en 4 dígitos.
+pub fn write_header(bytes: &mut Vec<u8>) {
+    bytes.extend_from_slice(format!("REDIS{:04}", RDB_VERSION).as_bytes());
+}
+
+/// Agrega un campo auxiliar, con información sobre el archivo que no forma parte de la base.
+pub fn write_aux(bytes: &mut Vec<u8>, key: &str, value: &str) {
+    bytes.push(OP_AUX);
+    write_string(bytes, key.as_bytes());
+    write_string(bytes, value.as_bytes());
+}
+
+/// Valores que pueden guardarse en un archivo RDB. Cada tipo de dato indica con qué tipo RDB se
+/// guarda y cómo se codifica, por lo que `TtlHashMap` puede persistir cualquier valor que lo
+/// implemente.
+pub trait Serializable: Sized {
+    /// Devuelve el tipo RDB con el que se guarda el valor, o None si el valor no puede guardarse.
+    fn rdb_type(&self) -> Option<u8>;
+
+    /// Agrega el valor codificado según su tipo RDB.
+    fn write_rdb(&self, bytes: &mut Vec<u8>);
+
+    /// Lee un valor codificado con el tipo RDB indicado.
+    fn read_rdb(reader: &mut RdbReader, value_type: u8) -> Result<Self, String>;
+}
+
+impl Serializable for RedisElement {
+    fn rdb_type(&self) -> Option<u8> {
+        value_type(self)
+    }
+
+    fn write_rdb(&self, bytes: &mut Vec<u8>) {
+        write_value(bytes, self)
+    }
+
+    fn read_rdb(reader: &mut RdbReader, value_type: u8) -> Result<Self, String> {
+        reader.read_value(value_type)
+    }
+}
+
+/// Devuelve el tipo con el que se guarda el valor, o None si el valor no puede guardarse.
+pub fn value_type(value: &RedisElement) -> Option<u8> {
+    match value {
+        RedisElement::String(_) | RedisElement::Integer(_) | RedisElement::Bytes(_) => {
+            Some(TYPE_STRING)
+        }
+        RedisElement::List(_) => Some(TYPE_LIST),
+        RedisElement::Set(_) => Some(TYPE_SET),
+        RedisElement::SortedSet(_) => Some(TYPE_ZSET_2),
+        RedisElement::Stream(_) => Some(TYPE_STREAM_LISTPACKS),
+        _ => None,
+    }
+}
+
+/// Agrega el valor codificado según su tipo, indicado por `value_type`.
+pub fn write_value(bytes: &mut Vec<u8>, value: &RedisElement) {
+    match value {
+        RedisElement::String(string) => write_string(bytes, string.as_bytes()),
+        RedisElement::Bytes(value) => write_string(bytes, value),
+        RedisElement::Integer(integer) => write_integer(bytes, *integer),
+        RedisElement::List(list) => {
+            write_length(bytes, list.len() as u64);
+            for element in list {
+                write_string(bytes, element.as_bytes());
+            }
+        }
+        RedisElement::Set(set) => {
+            write_length(bytes, set.len() as u64);
+            for element in set {
+                write_string(bytes, element.as_bytes());
+            }
+        }
+        RedisElement::SortedSet(sorted_set) => {
+            write_length(bytes, sorted_set.len() as u64);
+            for (member, score) in sorted_set.iter() {
+                write_string(bytes, member.as_bytes());
+                write_binary_double(bytes, score);
+            }
+        }
+        RedisElement::Stream(stream) => write_stream(bytes, stream),
+        _ => (),
+    }
+}
+
+/// Devuelve el valor serializado como lo hace el comando DUMP de Redis: el tipo y el valor
+/// codificados como en el archivo RDB, seguidos de la versión del formato (2 bytes) y del CRC64 de
+/// todo lo anterior (8 bytes).
+///
+/// Como los comandos sólo transportan texto UTF-8, los bytes se devuelven en hexadecimal. Devuelve
+/// None si el valor no puede guardarse.
+pub fn dump_value(value: &RedisElement) -> Option<String> {
+    let mut bytes = vec![value_type(value)?];
+    write_value(&mut bytes, value);
+    bytes.extend_from_slice(&(RDB_VERSION as u16).to_le_bytes());
+    let checksum = crc64(0, &bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    Some(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Devuelve el valor serializado por `dump_value`, verificando su versión y su checksum.
+pub fn restore_value(payload: &str) -> Result<RedisElement, String> {
+    let bytes = hex_decode(payload).ok_or_else(|| BAD_PAYLOAD_MSG.to_string())?;
+    if bytes.len() < 10 {
+        return Err(BAD_PAYLOAD_MSG.to_string());
+    }
+    let (body, footer) = bytes.split_at(bytes.len() - 10);
+    let version = u16::from_le_bytes([footer[0], footer[1]]) as u32;
+    let mut checksum = [0; 8];
+    checksum.copy_from_slice(&footer[2..]);
+    if version > MAX_RDB_VERSION
+        || crc64(0, &bytes[..bytes.len() - 8]) != u64::from_le_bytes(checksum)
+    {
+        return Err(BAD_PAYLOAD_MSG.to_string());
+    }
+
+    let mut reader = RdbReader::new(body);
+    let value = reader
+        .read_u8()
+        .and_then(|value_type| reader.read_value(value_type))
+        .map_err(|_| BAD_FORMAT_MSG.to_string())?;
+    if reader.pos != body.len() {
+        return Err(BAD_FORMAT_MSG.to_string());
+    }
+    Ok(value)
+}
+
+/// Convierte un texto en hexadecimal a bytes. Devuelve None si el texto no es hexadecimal.
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Devuelve el ID de un stream con el formato binario de 16 bytes (big endian) que usa Redis.
+fn raw_stream_id(id: &StreamId) -> Vec<u8> {
+    [id.ms.to_be_bytes(), id.seq.to_be_bytes()].concat()
+}
+
+/// Agrega un stream con el formato STREAM_LISTPACKS. Todas las entradas se guardan en un único
+/// nodo, cuyos campos maestros son los de la primera entrada.
+fn write_stream(bytes: &mut Vec<u8>, stream: &Stream) {
+    let entries: Vec<(&StreamId, &StreamFields)> = stream.iter().collect();
+    match entries.first() {
+        Some((master_id, _)) => {
+            write_length(bytes, 1);
+            write_string(bytes, &raw_stream_id(master_id));
+            write_string(bytes, &listpack::encode(&stream_node(&entries)));
+        }
+        None => write_length(bytes, 0),
+    }
+
+    write_length(bytes, stream.len() as u64);
+    write_length(bytes, stream.last_id().ms);
+    write_length(bytes, stream.last_id().seq);
+
+    let groups: Vec<(&String, &ConsumerGroup)> = stream.groups().collect();
+    write_length(bytes, groups.len() as u64);
+    for (name, group) in groups {
+        write_string(bytes, name.as_bytes());
+        write_length(bytes, group.last_delivered.ms);
+        write_length(bytes, group.last_delivered.seq);
+
+        write_length(bytes, group.pending.len() as u64);
+        for (id, entry) in group.pending.iter() {
+            bytes.extend_from_slice(&raw_stream_id(id));
+            write_time(bytes, entry.delivery_time);
+            write_length(bytes, entry.delivery_count);
+        }
+
+        // Las entradas pendientes pueden pertenecer a consumidores que no figuran en el grupo.
+        let mut consumers = group.consumers.clone();
+        for entry in group.pending.values() {
+            consumers
+                .entry(entry.consumer.clone())
+                .or_insert(SystemTime::UNIX_EPOCH);
+        }
+        write_length(bytes, consumers.len() as u64);
+        for (consumer, seen_time) in consumers {
+            write_string(bytes, consumer.as_bytes());
+            write_time(bytes, seen_time);
+            let pending: Vec<&StreamId> = group
+                .pending
+                .iter()
+                .filter(|(_, entry)| entry.consumer == consumer)
+                .map(|(id, _)| id)
+                .collect();
+            write_length(bytes, pending.len() as u64);
+            for id in pending {
+                bytes.extend_from_slice(&raw_stream_id(id));
+            }
+        }
+    }
+}
+
+/// Devuelve los elementos del listpack de un nodo de stream con las entradas indicadas.
+///
+/// El nodo comienza con la entrada maestra (cantidad de entradas, cantidad de entradas borradas y
+/// campos maestros). Cada entrada guarda la diferencia de su ID con el del nodo, y omite los
+/// nombres de los campos si coinciden con los maestros.
+fn stream_node(entries: &[(&StreamId, &StreamFields)]) -> Vec<Vec<u8>> {
+    let (master_id, master_fields) = entries[0];
+    let mut node: Vec<String> = vec![entries.len().to_string(), "0".to_string()];
+    node.push(master_fields.len().to_string());
+    node.extend(master_fields.iter().map(|(field, _)| field.clone()));
+    node.push("0".to_string());
+
+    for (id, fields) in entries {
+        let same_fields = fields.len() == master_fields.len()
+            && fields
+                .iter()
+                .zip(master_fields.iter())
+                .all(|((field, _), (master, _))| field == master);
+        let flags = if same_fields {
+            STREAM_ITEM_FLAG_SAMEFIELDS
+        } else {
+            0
+        };
+        node.push(flags.to_string());
+        node.push((id.ms.wrapping_sub(master_id.ms) as i64).to_string());
+        node.push((id.seq.wrapping_sub(master_id.seq) as i64).to_string());
+        if same_fields {
+            node.extend(fields.iter().map(|(_, value)| value.clone()));
+            node.push((fields.len() + 3).to_string());
+        } else {
+            node.push(fields.len().to_string());
+            for (field, value) in fields.iter() {
+                node.push(field.clone());
+                node.push(value.clone());
+            }
+            node.push((2 * fields.len() + 4).to_string());
+        }
+    }
+    node.into_iter().map(String::into_bytes).collect()
+}
+
+/// Lee los elementos de un nodo de stream y agrega sus entradas al stream. Devuelve None si el
+/// nodo está mal formado.
+fn read_stream_node(stream: &mut Stream, master_id: StreamId, node: &[Vec<u8>]) -> Option<()> {
+    let mut node = node.iter().map(|element| from_utf8(element).ok());
+
+    let count = node.next()??.parse::<u64>().ok()?;
+    let deleted = node.next()??.parse::<u64>().ok()?;
+    let master_fields_len = node.next()??.parse::<usize>().ok()?;
+    let mut master_fields = vec![];
+    for _ in 0..master_fields_len {
+        master_fields.push(node.next()??.to_string());
+    }
+    if node.next()?? != "0" {
+        return None;
+    }
+
+    for _ in 0..count + deleted {
+        let flags = node.next()??.parse::<i64>().ok()?;
+        let ms_diff = node.next()??.parse::<i64>().ok()?;
+        let seq_diff = node.next()??.parse::<i64>().ok()?;
+        let id = StreamId::new(
+            master_id.ms.wrapping_add(ms_diff as u64),
+            master_id.seq.wrapping_add(seq_diff as u64),
+        );
+
+        let mut fields = vec![];
+        if flags & STREAM_ITEM_FLAG_SAMEFIELDS != 0 {
+            for field in master_fields.iter() {
+                fields.push((field.clone(), node.next()??.to_string()));
+            }
+        } else {
+            let fields_len = node.next()??.parse::<usize>().ok()?;
+            for _ in 0..fields_len {
+                fields.push((node.next()??.to_string(), node.next()??.to_string()));
+            }
+        }
+        // Cantidad de elementos de la entrada, usada para recorrer el nodo hacia atrás.
+        node.next()??;
+
+        if flags & STREAM_ITEM_FLAG_DELETED == 0 {
+            stream.add(id, fields).ok()?;
+        }
+    }
+    Some(())
+}
+
+/// Lector de un archivo RDB. Recorre los bytes llevando la posición actual, que se informa en los
+/// mensajes de error para ubicar la corrupción.
+pub struct RdbReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> RdbReader<'a> {
+    /// Crea un lector posicionado al inicio de los bytes indicados.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        RdbReader { bytes, pos: 0 }
+    }
+
+    /// Devuelve el mensaje de error indicando la posición actual.
+    pub fn error(&self, msg: &str) -> String {
+        format!("{} at offset {}", msg, self.pos)
+    }
+
+    /// Lee los siguientes `len` bytes.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.bytes.len() - self.pos {
+            return Err(self.error("Unexpected end of file"));
+        }
+        let bytes = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u32_le(&mut self) -> Result<u32, String> {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(buffer))
+    }
+
+    pub fn read_u64_le(&mut self) -> Result<u64, String> {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_le_bytes(buffer))
+    }
+
+    pub fn read_u64_be(&mut self) -> Result<u64, String> {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_be_bytes(buffer))
+    }
+
+    /// Lee un largo codificado. Si el largo indica un encoding especial de string, se retorna
+    /// `(true, encoding)`.
+    fn read_length_or_encoding(&mut self) -> Result<(bool, u64), String> {
+        let first = self.read_u8()?;
+        match first >> 6 {
+            0b00 => Ok((false, (first & 0x3f) as u64)),
+            0b01 => Ok((
+                false,
+                (((first & 0x3f) as u64) << 8) | self.read_u8()? as u64,
+            )),
+            0b11 => Ok((true, (first & 0x3f) as u64)),
+            _ => match first {
+                0x80 => {
+                    let mut buffer = [0; 4];
+                    buffer.copy_from_slice(self.read_bytes(4)?);
+                    Ok((false, u32::from_be_bytes(buffer) as u64))
+                }
+                0x81 => Ok((false, self.read_u64_be()?)),
+                _ => Err(self.error("Invalid length encoding")),
+            },
+        }
+    }
+
+    /// Lee un largo codificado.
+    pub fn read_length(&mut self) -> Result<u64, String> {
+        match self.read_length_or_encoding()? {
+            (false, length) => Ok(length),
+            (true, _) => Err(self.error("Unexpected string encoding")),
+        }
+    }
+
+    /// Lee un largo codificado que indica una cantidad de bytes o de elementos que siguen en el
+    /// archivo. Como cada elemento ocupa al menos un byte, un largo mayor que los bytes restantes
+    /// solo puede deberse a un archivo corrupto.
+    pub fn read_len(&mut self) -> Result<usize, String> {
+        let length = self.read_length()?;
+        if length > (self.bytes.len() - self.pos) as u64 {
+            return Err(self.error("Invalid length"));
+        }
+        Ok(length as usize)
+    }
+
+    /// Lee un string, que puede estar guardado como texto, como entero o comprimido con LZF.
+    pub fn read_string(&mut self) -> Result<Vec<u8>, String> {
+        let (encoded, length) = self.read_length_or_encoding()?;
+        if !encoded {
+            if length > (self.bytes.len() - self.pos) as u64 {
+                return Err(self.error("Unexpected end of file"));
+            }
+            return Ok(self.read_bytes(length as usize)?.to_vec());
+        }
+
+        match length as u8 {
+            ENC_INT8 => Ok((self.read_u8()? as i8).to_string().into_bytes()),
+            ENC_INT16 => {
+                let mut buffer = [0; 2];
+                buffer.copy_from_slice(self.read_bytes(2)?);
+                Ok(i16::from_le_bytes(buffer).to_string().into_bytes())
+            }
+            ENC_INT32 => Ok((self.read_u32_le()? as i32).to_string().into_bytes()),
+            ENC_LZF => {
+                let compressed_len = self.read_len()?;
+                let len = self.read_length()? as usize;
+                let compressed = self.read_bytes(compressed_len)?;
+                lzf::decompress(compressed, len).map_err(|msg| self.error(&msg))
+            }
+            _ => Err(self.error("Invalid string encoding")),
+        }
+    }
+
+    /// Lee un string que debe ser texto UTF-8, ya que los valores de la base de datos son `String`.
+    pub fn read_utf8_string(&mut self) -> Result<String, String> {
+        let bytes = self.read_string()?;
+        match from_utf8(&bytes) {
+            Ok(string) => Ok(string.to_string()),
+            Err(_) => Err(self.error("Value is not valid UTF-8")),
+        }
+    }
+
+    /// Lee un double guardado como texto con un byte de largo, como lo usan los sorted sets de tipo
+    /// ZSET.
+    pub fn read_string_double(&mut self) -> Result<f64, String> {
+        match self.read_u8()? {
+            253 => Ok(f64::NAN),
+            254 => Ok(f64::INFINITY),
+            255 => Ok(f64::NEG_INFINITY),
+            len => {
+                let bytes = self.read_bytes(len as usize)?;
+                from_utf8(bytes)
+                    .ok()
+                    .and_then(|score| score.parse::<f64>().ok())
+                    .ok_or_else(|| self.error("Invalid double value"))
+            }
+        }
+    }
+
+    /// Lee un double en binario (little endian).
+    pub fn read_binary_double(&mut self) -> Result<f64, String> {
+        Ok(f64::from_bits(self.read_u64_le()?))
+    }
+
+    /// Lee un instante expresado en milisegundos desde UNIX_EPOCH (little endian).
+    pub fn read_time(&mut self) -> Result<SystemTime, String> {
+        Ok(SystemTime::UNIX_EPOCH + Duration::from_millis(self.read_u64_le()?))
+    }
+
+    /// Lee el checksum que sigue al fin del archivo y verifica que corresponda a los bytes leídos.
+    /// Un checksum en 0 indica que el archivo se generó sin checksum.
+    pub fn verify_checksum(&mut self) -> Result<(), String> {
+        let expected = crc64(0, &self.bytes[..self.pos]);
+        let checksum = self.read_u64_le()?;
+        if checksum != 0 && checksum != expected {
+            self.pos -= 8;
+            return Err(self.error(&format!(
+                "Wrong RDB checksum expected: ({:016x}) got: ({:016x})",
+                expected, checksum
+            )));
+        }
+        Ok(())
+    }
+
+    /// Lee el encabezado del archivo y devuelve la versión del formato.
+    pub fn read_header(&mut self) -> Result<u32, String> {
+        if self.bytes.len() < 9 || &self.bytes[..5] != b"REDIS" {
+            return Err(self.error("Wrong signature trying to load DB from file"));
+        }
+        let version = from_utf8(&self.bytes[5..9])
+            .ok()
+            .and_then(|version| version.parse::<u32>().ok())
+            .ok_or_else(|| self.error("Wrong signature trying to load DB from file"))?;
+        if !(1..=MAX_RDB_VERSION).contains(&version) {
+            return Err(self.error(&format!("Can't handle RDB format version {}", version)));
+        }
+        self.pos = 9;
+        Ok(version)
+    }
+
+    /// Lee un valor del tipo indicado.
+    pub fn read_value(&mut self, value_type: u8) -> Result<RedisElement, String> {
+        match value_type {
+            TYPE_STRING => Ok(RedisElement::from_bytes(self.read_string()?)),
+            TYPE_LIST => Ok(RedisElement::List(self.read_strings()?)),
+            TYPE_SET => Ok(RedisElement::Set(
+                self.read_strings()?.into_iter().collect(),
+            )),
+            TYPE_ZSET | TYPE_ZSET_2 => {
+                let len = self.read_len()?;
+                let mut sorted_set = SortedSet::new();
+                for _ in 0..len {
+                    let member = self.read_utf8_string()?;
+                    let score = if value_type == TYPE_ZSET {
+                        self.read_string_double()?
+                    } else {
+                        self.read_binary_double()?
+                    };
+                    sorted_set.insert(member, score);
+                }
+                Ok(RedisElement::SortedSet(sorted_set))
+            }
+            TYPE_LIST_ZIPLIST => {
+                let elements = listpack::decode_ziplist(&self.read_string()?);
+                Ok(RedisElement::List(self.decoded_strings(elements)?))
+            }
+            TYPE_SET_INTSET | TYPE_SET_LISTPACK => {
+                let blob = self.read_string()?;
+                let elements = if value_type == TYPE_SET_INTSET {
+                    listpack::decode_intset(&blob)
+                } else {
+                    listpack::decode(&blob)
+                };
+                let set = self.decoded_strings(elements)?.into_iter().collect();
+                Ok(RedisElement::Set(set))
+            }
+            TYPE_ZSET_ZIPLIST | TYPE_ZSET_LISTPACK => {
+                let blob = self.read_string()?;
+                let elements = if value_type == TYPE_ZSET_ZIPLIST {
+                    listpack::decode_ziplist(&blob)
+                } else {
+                    listpack::decode(&blob)
+                };
+                let elements = self.decoded_strings(elements)?;
+                let mut sorted_set = SortedSet::new();
+                for pair in elements.chunks(2) {
+                    match pair {
+                        [member, score] => match score.parse::<f64>() {
+                            Ok(score) => sorted_set.insert(member.clone(), score),
+                            Err(_) => return Err(self.error("Invalid double value")),
+                        },
+                        _ => return Err(self.error("Invalid sorted set encoding")),
+                    };
+                }
+                Ok(RedisElement::SortedSet(sorted_set))
+            }
+            TYPE_LIST_QUICKLIST | TYPE_LIST_QUICKLIST_2 => {
+                let nodes = self.read_len()?;
+                let mut list = vec![];
+                for _ in 0..nodes {
+                    let container = if value_type == TYPE_LIST_QUICKLIST_2 {
+                        self.read_length()?
+                    } else {
+                        QUICKLIST_NODE_PACKED
+                    };
+                    let blob = self.read_string()?;
+                    let elements = match container {
+                        QUICKLIST_NODE_PLAIN => Ok(vec![blob]),
+                        QUICKLIST_NODE_PACKED if value_type == TYPE_LIST_QUICKLIST => {
+                            listpack::decode_ziplist(&blob)
+                        }
+                        QUICKLIST_NODE_PACKED => listpack::decode(&blob),
+                        _ => return Err(self.error("Invalid quicklist node container")),
+                    };
+                    list.append(&mut self.decoded_strings(elements)?);
+                }
+                Ok(RedisElement::List(list))
+            }
+            TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+                Ok(RedisElement::Stream(self.read_stream(value_type)?))
+            }
+            TYPE_HASH | TYPE_HASH_ZIPMAP | TYPE_HASH_ZIPLIST | TYPE_HASH_LISTPACK => {
+                Err(self.error("Hash values are not supported"))
+            }
+            TYPE_MODULE | TYPE_MODULE_2 => Err(self.error("Module values are not supported")),
+            _ => Err(self.error(&format!("Unknown value type {}", value_type))),
+        }
+    }
+
+    /// Lee una cantidad de strings seguida de los strings.
+    fn read_strings(&mut self) -> Result<Vec<String>, String> {
+        let len = self.read_len()?;
+        (0..len).map(|_| self.read_utf8_string()).collect()
+    }
+
+    /// Convierte los elementos de una colección codificada a texto, indicando la posición del
+    /// error si la colección está mal formada.
+    fn decoded_strings(
+        &self,
+        elements: Result<Vec<Vec<u8>>, String>,
+    ) -> Result<Vec<String>, String> {
+        elements
+            .map_err(|msg| self.error(&msg))?
+            .into_iter()
+            .map(|element| {
+                String::from_utf8(element).map_err(|_| self.error("Value is not valid UTF-8"))
+            })
+            .collect()
+    }
+
+    /// Lee un ID de stream guardado con el formato binario de 16 bytes.
+    fn read_raw_stream_id(&mut self) -> Result<StreamId, String> {
+        let ms = self.read_u64_be()?;
+        let seq = self.read_u64_be()?;
+        Ok(StreamId::new(ms, seq))
+    }
+
+    /// Lee un ID de stream guardado como dos largos codificados.
+    fn read_stream_id(&mut self) -> Result<StreamId, String> {
+        let ms = self.read_length()?;
+        let seq = self.read_length()?;
+        Ok(StreamId::new(ms, seq))
+    }
+
+    /// Lee un stream con alguno de los formatos STREAM_LISTPACKS. Los formatos más nuevos agregan
+    /// información que este servidor no utiliza, por lo que se descarta.
+    fn read_stream(&mut self, value_type: u8) -> Result<Stream, String> {
+        let mut stream = Stream::new();
+        let nodes = self.read_len()?;
+        for _ in 0..nodes {
+            let key = self.read_string()?;
+            if key.len() != 16 {
+                return Err(self.error("Invalid stream node key"));
+            }
+            let master_id = RdbReader::new(&key).read_raw_stream_id()?;
+            let node = listpack::decode(&self.read_string()?).map_err(|msg| self.error(&msg))?;
+            read_stream_node(&mut stream, master_id, &node)
+                .ok_or_else(|| self.error("Invalid stream node"))?;
+        }
+
+        self.read_length()?;
+        let last_id = self.read_stream_id()?;
+        if value_type != TYPE_STREAM_LISTPACKS {
+            // Primer ID, máximo ID borrado y cantidad de entradas agregadas.
+            self.read_stream_id()?;
+            self.read_stream_id()?;
+            self.read_length()?;
+        }
+        stream.set_last_id(last_id);
+
+        let groups = self.read_len()?;
+        for _ in 0..groups {
+            let name = self.read_utf8_string()?;
+            let mut group = ConsumerGroup::new(self.read_stream_id()?);
+            if value_type != TYPE_STREAM_LISTPACKS {
+                // Cantidad de entradas leídas por el grupo.
+                self.read_length()?;
+            }
+
+            let mut pending = BTreeMap::new();
+            let pending_len = self.read_len()?;
+            for _ in 0..pending_len {
+                let id = self.read_raw_stream_id()?;
+                let delivery_time = self.read_time()?;
+                let delivery_count = self.read_length()?;
+                pending.insert(id, (delivery_time, delivery_count));
+            }
+
+            let consumers = self.read_len()?;
+            for _ in 0..consumers {
+                let consumer = self.read_utf8_string()?;
+                let seen_time = self.read_time()?;
+                if value_type == TYPE_STREAM_LISTPACKS_3 {
+                    // Momento de la última lectura del consumidor.
+                    self.read_time()?;
+                }
+                let consumer_pending = self.read_len()?;
+                for _ in 0..consumer_pending {
+                    let id = self.read_raw_stream_id()?;
+                    let (delivery_time, delivery_count) = pending
+                        .remove(&id)
+                        .ok_or_else(|| self.error("Consumer pending entry not found in group"))?;
+                    let entry = PendingEntry {
+                        consumer: consumer.clone(),
+                        delivery_time,
+                        delivery_count,
+                    };
+                    group.pending.insert(id, entry);
+                }
+                group.consumers.insert(consumer, seen_time);
+            }
+            if !pending.is_empty() {
+                return Err(self.error("Group pending entry without consumer"));
+            }
+            stream.insert_group(name, group);
+        }
+        Ok(stream)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::rdb::{
+        value_type, write_integer, write_length, write_string, write_value, RdbReader,
+        TYPE_STREAM_LISTPACKS, TYPE_STRING,
+    };
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::stream::{Stream, StreamId};
+
+    #[test]
+    fn test_length_encode_decode() {
+        for length in [0, 63, 64, 16383, 16384, u32::MAX as u64, u64::MAX] {
+            let mut bytes = vec![];
+            write_length(&mut bytes, length);
+
+            let mut reader = RdbReader::new(&bytes);
+            assert_eq!(length, reader.read_length().unwrap());
+            assert!(reader.read_u8().is_err());
+        }
+    }
+
+    #[test]
+    fn test_read_integer_encoded_strings() {
+        let bytes = [0xc0, 0xfe, 0xc1, 0x39, 0x30, 0xc2, 0x00, 0x00, 0x00, 0x80];
+        let mut reader = RdbReader::new(&bytes);
+
+        assert_eq!("-2", reader.read_utf8_string().unwrap());
+        assert_eq!("12345", reader.read_utf8_string().unwrap());
+        assert_eq!("-2147483648", reader.read_utf8_string().unwrap());
+    }
+
+    #[test]
+    fn test_integers_round_trip_with_int_encoding() {
+        let mut bytes = vec![];
+        for integer in [-2, 12345, i32::MIN as i64, i64::MAX].iter() {
+            write_integer(&mut bytes, *integer);
+        }
+        assert_eq!([0xc0, 0xfe, 0xc1, 0x39, 0x30, 0xc2], bytes[..6]);
+
+        let mut reader = RdbReader::new(&bytes);
+        for integer in [-2, 12345, i32::MIN as i64, i64::MAX].iter() {
+            assert_eq!(
+                RedisElement::Integer(*integer),
+                reader.read_value(TYPE_STRING).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_binary_strings_round_trip() {
+        let value = RedisElement::Bytes(vec![0x80, 0xff, b'a']);
+        let mut bytes = vec![];
+        write_value(&mut bytes, &value);
+
+        assert_eq!(Some(TYPE_STRING), value_type(&value));
+        assert_eq!(
+            value,
+            RdbReader::new(&bytes).read_value(TYPE_STRING).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_lzf_string() {
+        let bytes = [0xc3, 5, 8, 1, b'a', b'b', 4 << 5, 1];
+        let mut reader = RdbReader::new(&bytes);
+
+        assert_eq!("abababab", reader.read_utf8_string().unwrap());
+    }
+
+    #[test]
+    fn test_truncated_string_reports_offset() {
+        let mut bytes = vec![0xfa];
+        write_string(&mut bytes, b"value");
+        bytes.truncate(4);
+        let mut reader = RdbReader::new(&bytes);
+        reader.read_u8().unwrap();
+
+        assert_eq!(
+            "Unexpected end of file at offset 2",
+            reader.read_string().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_stream_with_different_fields_round_trip() {
+        let mut stream = Stream::new();
+        let fields = vec![("a".to_string(), "1".to_string())];
+        stream.add(StreamId::new(10, 5), fields.clone()).unwrap();
+        stream
+            .add(
+                StreamId::new(12, 0),
+                vec![("b".to_string(), "-7".to_string())],
+            )
+            .unwrap();
+        stream.add(StreamId::new(12, 1), fields).unwrap();
+        let value = RedisElement::Stream(stream);
+
+        let mut bytes = vec![];
+        write_value(&mut bytes, &value);
+        let mut reader = RdbReader::new(&bytes);
+
+        assert_eq!(Some(TYPE_STREAM_LISTPACKS), value_type(&value));
+        assert_eq!(value, reader.read_value(TYPE_STREAM_LISTPACKS).unwrap());
+    }
+
+    #[test]
+    fn test_long_strings_are_compressed() {
+        let value = "value-".repeat(50);
+        let mut bytes = vec![];
+        write_string(&mut bytes, value.as_bytes());
+
+        assert_eq!(0xc3, bytes[0]);
+        assert!(bytes.len() < value.len() / 4);
+        assert_eq!(value, RdbReader::new(&bytes).read_utf8_string().unwrap());
+
+        let mut bytes = vec![];
+        write_string(&mut bytes, b"a short value");
+        assert_eq!(13, bytes[0]);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::entities::sorted_set::SortedSet;
+use crate::entities::stream::Stream;
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::fmt;
 
@@ -8,20 +11,100 @@ use std::fmt;
 pub enum RedisElement {
     /// Representa los tipos de dato String de Redis
     String(String),
+    /// Representa los strings de Redis guardados como bytes, que pueden no ser texto UTF-8
+    /// válido (ej: los bitmaps que modifican SETBIT y BITOP)
+    Bytes(Vec<u8>),
     /// Representa los tipos de dato String especiales de Redis
     SimpleString(String),
+    /// Representa las respuestas de tipo entero (ej: la cantidad de claves eliminadas por DEL), y
+    /// los strings guardados con la codificación `int` (ver `RedisElement::from_string`)
+    Integer(i64),
     /// Representa los tipos de dato Set de Redis
     Set(HashSet<String>),
     /// Representa los tipos de dato List de Redis
     List(Vec<String>),
+    /// Representa los tipos de dato Sorted Set de Redis
+    SortedSet(SortedSet),
+    /// Representa los tipos de dato Stream de Redis
+    Stream(Stream),
+    /// Representa respuestas compuestas por otros elementos (ej: la respuesta de SSCAN)
+    Array(Vec<RedisElement>),
     /// Representa los tipos de dato Nil de Redis
     Nil,
 }
 
+impl RedisElement {
+    /// Crea el valor de tipo string a guardar en la base de datos. Como la codificación `int` de
+    /// Redis, los strings que representan un entero de 64 bits (sin ceros a la izquierda ni signo
+    /// `+`) se guardan como `Integer`, evitando parsearlos en cada INCRBY.
+    pub fn from_string(value: String) -> Self {
+        match value.parse::<i64>() {
+            Ok(integer) if value.len() <= 20 && integer.to_string() == value => {
+                RedisElement::Integer(integer)
+            }
+            _ => RedisElement::String(value),
+        }
+    }
+
+    /// Devuelve el texto de un valor de tipo string guardado en la base de datos, tenga o no la
+    /// codificación `int`. Devuelve None si el valor no es un string.
+    ///
+    /// Los bytes que no forman texto UTF-8 válido se reemplazan por `U+FFFD`; para obtener el
+    /// valor exacto debe usarse `as_bytes`.
+    pub fn as_string(&self) -> Option<Cow<'_, str>> {
+        match self {
+            RedisElement::String(string) => Some(Cow::Borrowed(string)),
+            RedisElement::Integer(integer) => Some(Cow::Owned(integer.to_string())),
+            RedisElement::Bytes(bytes) => Some(String::from_utf8_lossy(bytes)),
+            _ => None,
+        }
+    }
+
+    /// Devuelve los bytes de un valor de tipo string guardado en la base de datos, sea texto,
+    /// bytes o tenga la codificación `int`. Devuelve None si el valor no es un string.
+    pub fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        match self {
+            RedisElement::String(string) => Some(Cow::Borrowed(string.as_bytes())),
+            RedisElement::Integer(integer) => Some(Cow::Owned(integer.to_string().into_bytes())),
+            RedisElement::Bytes(bytes) => Some(Cow::Borrowed(bytes)),
+            _ => None,
+        }
+    }
+
+    /// Devuelve un valor de tipo string guardado en la base de datos como respuesta de un comando
+    /// (ej: GET): con la codificación `int` se responde su texto, y los `Bytes` se responden como
+    /// texto sólo si son UTF-8 válido. Devuelve None si el valor no es un string.
+    pub fn string_reply(&self) -> Option<RedisElement> {
+        match self {
+            RedisElement::String(string) => Some(RedisElement::String(string.clone())),
+            RedisElement::Integer(integer) => Some(RedisElement::String(integer.to_string())),
+            RedisElement::Bytes(bytes) => Some(match String::from_utf8(bytes.clone()) {
+                Ok(string) => RedisElement::String(string),
+                Err(e) => RedisElement::Bytes(e.into_bytes()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Crea el valor de tipo string correspondiente a los bytes indicados: como `from_string` si
+    /// son texto UTF-8 válido, o `Bytes` si no lo son.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(string) => RedisElement::from_string(string),
+            Err(e) => RedisElement::Bytes(e.into_bytes()),
+        }
+    }
+}
+
 impl fmt::Display for RedisElement {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             RedisElement::String(s) => write!(fmt, "{}", s.replace(" - ", "-"))?,
+            RedisElement::Bytes(bytes) => write!(
+                fmt,
+                "{}",
+                String::from_utf8_lossy(bytes).replace(" - ", "-")
+            )?,
             RedisElement::Set(set) => {
                 write!(fmt, "{{")?;
 
@@ -46,10 +129,47 @@ impl fmt::Display for RedisElement {
                     write!(fmt, "]")?;
                 }
             }
+            RedisElement::SortedSet(sorted_set) => {
+                write!(fmt, "{{")?;
+
+                let mut sorted_set = sorted_set.iter();
+                if let Some((member, score)) = sorted_set.next() {
+                    write!(fmt, "{}:{}", member.replace(" - ", "-"), score)?;
+                }
+                for (member, score) in sorted_set {
+                    write!(fmt, " - {}:{}", member.replace(" - ", "-"), score)?;
+                }
+                write!(fmt, "}}")?;
+            }
+            RedisElement::Stream(stream) => {
+                write!(fmt, "{{")?;
+
+                let mut entries = stream.iter();
+                if let Some((id, fields)) = entries.next() {
+                    write!(fmt, "{}:{}", id, fields.len())?;
+                }
+                for (id, fields) in entries {
+                    write!(fmt, " - {}:{}", id, fields.len())?;
+                }
+                write!(fmt, "}}")?;
+            }
+            RedisElement::Array(array) => {
+                write!(fmt, "[")?;
+
+                let mut array = array.iter();
+                if let Some(element) = array.next() {
+                    write!(fmt, "{}", element)?;
+                }
+                for element in array {
+                    write!(fmt, " - {}", element)?;
+                }
+                write!(fmt, "]")?;
+            }
             RedisElement::Nil => {
                 write!(fmt, "(nil)")?;
             }
             RedisElement::SimpleString(s) => write!(fmt, "{}", s.replace(" - ", "-"))?,
+            RedisElement::Integer(integer) => write!(fmt, "{}", integer)?,
         }
         Ok(())
     }
@@ -1,3 +1,4 @@
+use crate::entities::message_queue::MessageReceiver;
 use crate::entities::redis_element::RedisElement;
 use std::sync::mpsc::Receiver;
 
@@ -8,7 +9,10 @@ pub enum Response {
     /// Respuesta de una linea
     Normal(RedisElement),
     /// Respuesta como flujo de datos
-    Stream(Receiver<RedisElement>),
+    Stream(MessageReceiver<RedisElement>),
+    /// Respuesta diferida de una linea, que se recibe una vez que el comando deja de estar
+    /// bloqueado (ej: BLPOP)
+    Blocked(Receiver<RedisElement>),
     /// Error de comando
     Error(String),
 }
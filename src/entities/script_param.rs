@@ -0,0 +1,10 @@
+#[derive(Debug)]
+/// ScriptParam: Enum usado para representar los subcomandos permitidos para el Command::Script.
+pub enum ScriptParam {
+    /// Representa el subcomando Load, que guarda el script en el cache sin ejecutarlo.
+    Load { script: String },
+    /// Representa el subcomando Exists, que indica si los scripts están en el cache.
+    Exists { sha1s: Vec<String> },
+    /// Representa el subcomando Flush, que vacía el cache de scripts.
+    Flush,
+}
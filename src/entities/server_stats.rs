@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Cantidad de muestras que se promedian para calcular las métricas instantáneas.
+const STATS_METRIC_SAMPLES: usize = 16;
+/// Tiempo mínimo entre dos muestras de las métricas instantáneas.
+const STATS_SAMPLE_PERIOD: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Default)]
+/// InstantaneousMetric: Tasa por segundo de un contador, promediada entre sus últimas muestras.
+struct InstantaneousMetric {
+    /// Valor del contador al tomar la última muestra.
+    last_value: u64,
+    /// Últimas tasas por segundo calculadas.
+    samples: VecDeque<f64>,
+}
+
+impl InstantaneousMetric {
+    /// Registra la tasa por segundo con la que creció el contador hasta `value` en `elapsed`.
+    fn sample(&mut self, value: u64, elapsed: Duration) {
+        let rate = value.saturating_sub(self.last_value) as f64 / elapsed.as_secs_f64();
+        self.samples.push_back(rate);
+        if self.samples.len() > STATS_METRIC_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.last_value = value;
+    }
+
+    /// Devuelve el promedio de las muestras, o 0 si todavía no hay ninguna.
+    fn value(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f64>() / self.samples.len() as f64
+    }
+}
+
+#[derive(Debug)]
+/// ServerStats: Estadísticas de los comandos procesados y del tráfico de red del servidor, que se
+/// muestran en la sección Stats de INFO.
+pub struct ServerStats {
+    /// Cantidad de comandos procesados.
+    commands_processed: u64,
+    /// Bytes recibidos de los clientes.
+    net_input_bytes: u64,
+    /// Bytes enviados a los clientes.
+    net_output_bytes: u64,
+    /// Momento en que se tomó la última muestra.
+    last_sample: Instant,
+    ops: InstantaneousMetric,
+    input: InstantaneousMetric,
+    output: InstantaneousMetric,
+}
+
+impl ServerStats {
+    /// Crea las estadísticas vacías.
+    pub fn new() -> Self {
+        ServerStats {
+            commands_processed: 0,
+            net_input_bytes: 0,
+            net_output_bytes: 0,
+            last_sample: Instant::now(),
+            ops: InstantaneousMetric::default(),
+            input: InstantaneousMetric::default(),
+            output: InstantaneousMetric::default(),
+        }
+    }
+
+    /// Registra un comando procesado.
+    pub fn add_command(&mut self) {
+        self.commands_processed += 1;
+    }
+
+    /// Registra bytes recibidos de un cliente.
+    pub fn add_input(&mut self, bytes: usize) {
+        self.net_input_bytes += bytes as u64;
+    }
+
+    /// Registra bytes enviados a un cliente.
+    pub fn add_output(&mut self, bytes: usize) {
+        self.net_output_bytes += bytes as u64;
+    }
+
+    /// Toma una muestra de las métricas instantáneas, si pasó el tiempo mínimo desde la anterior.
+    pub fn sample(&mut self) {
+        self.sample_at(Instant::now());
+    }
+
+    fn sample_at(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_sample);
+        if elapsed < STATS_SAMPLE_PERIOD {
+            return;
+        }
+        self.ops.sample(self.commands_processed, elapsed);
+        self.input.sample(self.net_input_bytes, elapsed);
+        self.output.sample(self.net_output_bytes, elapsed);
+        self.last_sample = now;
+    }
+
+    /// Descarta las estadísticas registradas.
+    pub fn reset(&mut self) {
+        *self = ServerStats::new();
+    }
+
+    /// Describe las estadísticas con el formato de la sección Stats de INFO.
+    pub fn info(&self) -> Vec<String> {
+        vec![
+            format!("total_commands_processed:{}", self.commands_processed),
+            format!("instantaneous_ops_per_sec:{}", self.ops.value().round()),
+            format!("total_net_input_bytes:{}", self.net_input_bytes),
+            format!("total_net_output_bytes:{}", self.net_output_bytes),
+            format!(
+                "instantaneous_input_kbps:{:.2}",
+                self.input.value() / 1024.0
+            ),
+            format!(
+                "instantaneous_output_kbps:{:.2}",
+                self.output.value() / 1024.0
+            ),
+        ]
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::server_stats::ServerStats;
+    use std::time::Duration;
+
+    #[test]
+    fn test_instantaneous_metrics() {
+        let mut stats = ServerStats::new();
+        let start = stats.last_sample;
+        for _ in 0..50 {
+            stats.add_command();
+        }
+        stats.add_input(2048);
+        stats.add_output(1024);
+
+        // Las muestras tomadas antes del período mínimo se descartan.
+        stats.sample_at(start + Duration::from_millis(10));
+        assert_eq!(0.0, stats.ops.value());
+
+        stats.sample_at(start + Duration::from_millis(500));
+        assert_eq!(100.0, stats.ops.value());
+        assert_eq!(4096.0, stats.input.value());
+
+        stats.sample_at(start + Duration::from_millis(1000));
+        assert_eq!(50.0, stats.ops.value());
+
+        let info = stats.info();
+        assert!(info.contains(&"total_commands_processed:50".to_string()));
+        assert!(info.contains(&"instantaneous_ops_per_sec:50".to_string()));
+        assert!(info.contains(&"total_net_input_bytes:2048".to_string()));
+        assert!(info.contains(&"instantaneous_output_kbps:1.00".to_string()));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut stats = ServerStats::new();
+        stats.add_command();
+        stats.add_output(10);
+
+        stats.reset();
+        assert_eq!(0, stats.commands_processed);
+        assert_eq!(0, stats.net_output_bytes);
+    }
+}
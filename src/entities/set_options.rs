@@ -0,0 +1,40 @@
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// SetCondition: Enum usado para representar la condición bajo la cual el Command::Set escribe
+/// el valor.
+pub enum SetCondition {
+    /// Se escribe siempre el valor.
+    #[default]
+    Always,
+    /// Se escribe el valor solo si la clave no existe (NX).
+    IfNotExists,
+    /// Se escribe el valor solo si la clave ya existe (XX).
+    IfExists,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// SetExpiration: Enum usado para representar qué sucede con la expiración de la clave al
+/// ejecutar el Command::Set.
+pub enum SetExpiration {
+    /// Se descarta la expiración previa de la clave, que pasa a ser persistente.
+    #[default]
+    Discard,
+    /// La clave expira luego de la duración indicada (EX, PX, SETEX, PSETEX).
+    Relative(Duration),
+    /// La clave expira en el momento indicado (EXAT, PXAT).
+    Absolute(SystemTime),
+    /// Se conserva la expiración previa de la clave (KEEPTTL).
+    Keep,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// SetOptions: Struct usado para representar las opciones permitidas para el Command::Set.
+pub struct SetOptions {
+    /// Condición bajo la cual se escribe el valor.
+    pub condition: SetCondition,
+    /// Expiración a aplicar sobre la clave.
+    pub expiration: SetExpiration,
+    /// Indica si se retorna el valor previo de la clave en lugar de OK (GET).
+    pub get: bool,
+}
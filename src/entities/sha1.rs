@@ -0,0 +1,103 @@
+/// Valores iniciales del estado de SHA1.
+const INITIAL_STATE: [u32; 5] = [
+    0x6745_2301,
+    0xefcd_ab89,
+    0x98ba_dcfe,
+    0x1032_5476,
+    0xc3d2_e1f0,
+];
+
+/// Calcula el SHA1 de los bytes y lo devuelve en hexadecimal, en minúsculas, como lo usa Redis
+/// para identificar a los scripts.
+pub fn sha1_hex(bytes: &[u8]) -> String {
+    sha1(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Calcula el SHA1 de los bytes.
+fn sha1(bytes: &[u8]) -> [u8; 20] {
+    // El mensaje se completa con un bit en 1, ceros y su largo en bits, hasta ser múltiplo de 64.
+    let mut message = bytes.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((bytes.len() as u64).wrapping_mul(8)).to_be_bytes());
+
+    let mut state = INITIAL_STATE;
+    for block in message.chunks(64) {
+        process_block(&mut state, block);
+    }
+
+    let mut digest = [0; 20];
+    for (i, word) in state.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// Procesa un bloque de 64 bytes, actualizando el estado.
+fn process_block(state: &mut [u32; 5], block: &[u8]) {
+    let mut words = [0u32; 80];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        words[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for i in 16..80 {
+        words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, word) in words.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+            20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+            _ => (b ^ c ^ d, 0xca62_c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (value, new) in state.iter_mut().zip([a, b, c, d, e]) {
+        *value = value.wrapping_add(new);
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::sha1::sha1_hex;
+
+    #[test]
+    fn test_sha1_known_digests() {
+        assert_eq!("da39a3ee5e6b4b0d3255bfef95601890afd80709", sha1_hex(b""));
+        assert_eq!("a9993e364706816aba3e25717850c26c9cd0d89d", sha1_hex(b"abc"));
+        // SCRIPT LOAD "return 1" en Redis.
+        assert_eq!(
+            "e0e1f9fabfc9d4800c877a703b823ac0578ff8db",
+            sha1_hex(b"return 1")
+        );
+    }
+
+    #[test]
+    fn test_sha1_of_several_blocks() {
+        assert_eq!(
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+            sha1_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")
+        );
+        assert_eq!(
+            "34aa973cd4c4daa4f61eeb2bdbad27316534016f",
+            sha1_hex(&[b'a'; 1_000_000])
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+/// SharedCommand: Comando de una única clave atendido desde el hilo de un cliente, cuyos efectos
+/// sobre las estadísticas, los accesos a las claves y las reglas de guardado se aplican luego en el
+/// hilo de la DB.
+pub struct SharedCommand {
+    /// Nombre del comando, para la sección Commandstats de INFO.
+    pub command: &'static str,
+    /// Id del cliente que envió el comando, para el slowlog.
+    pub client_id: Option<u64>,
+    /// Argumentos recibidos, para el slowlog.
+    pub args: Vec<String>,
+    /// Tiempo que demoró la ejecución del comando.
+    pub elapsed: Duration,
+    /// Clave buscada y si existía, para registrar el acceso y el acierto o fallo en INFO stats.
+    pub lookup: Option<(String, bool)>,
+    /// Indica si el comando modificó la clave, para contarlo en las reglas de guardado.
+    pub modified: bool,
+}
+
+#[derive(Debug, Default)]
+/// SharedCommands: Comandos atendidos fuera del hilo de la DB que todavía no fueron registrados.
+pub struct SharedCommands {
+    commands: Mutex<Vec<SharedCommand>>,
+}
+
+impl SharedCommands {
+    /// Crea el registro vacío.
+    pub fn new() -> Self {
+        SharedCommands::default()
+    }
+
+    /// Agrega un comando. Sólo requiere una referencia compartida, ya que se usa desde los hilos
+    /// de los clientes.
+    pub fn push(&self, command: SharedCommand) {
+        self.commands.lock().unwrap().push(command);
+    }
+
+    /// Devuelve los comandos registrados desde la última llamada.
+    pub fn take(&self) -> Vec<SharedCommand> {
+        std::mem::take(&mut *self.commands.lock().unwrap())
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::shared_commands::{SharedCommand, SharedCommands};
+    use std::time::Duration;
+
+    #[test]
+    fn take_returns_commands_in_order_and_empties() {
+        let commands = SharedCommands::new();
+        commands.push(SharedCommand {
+            command: "get",
+            client_id: Some(1),
+            args: vec!["GET".to_string(), "key".to_string()],
+            elapsed: Duration::from_micros(3),
+            lookup: Some(("key".to_string(), true)),
+            modified: false,
+        });
+        commands.push(SharedCommand {
+            command: "set",
+            client_id: None,
+            args: vec![],
+            elapsed: Duration::from_micros(1),
+            lookup: None,
+            modified: true,
+        });
+
+        let taken = commands.take();
+        assert_eq!(2, taken.len());
+        assert_eq!("get", taken[0].command);
+        assert_eq!("set", taken[1].command);
+        assert!(taken[1].modified);
+        assert!(commands.take().is_empty());
+    }
+}
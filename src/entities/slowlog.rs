@@ -0,0 +1,204 @@
+use crate::entities::redis_element::RedisElement;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cantidad de entradas que devuelve SLOWLOG GET si no se indica otra.
+pub const DEFAULT_SLOWLOG_COUNT: usize = 10;
+/// Cantidad máxima de argumentos que se guardan de cada comando, como en Redis.
+const MAX_ARGS: usize = 32;
+/// Cantidad máxima de bytes que se guardan de cada argumento, como en Redis.
+const MAX_ARG_LEN: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+/// SlowlogEntry: Comando que demoró más que `slowlog-log-slower-than`.
+pub struct SlowlogEntry {
+    /// Identificador único de la entrada, creciente.
+    pub id: u64,
+    /// Momento en el que se ejecutó el comando, en segundos desde el epoch.
+    pub timestamp: u64,
+    /// Tiempo que demoró la ejecución.
+    pub duration: Duration,
+    /// Comando y argumentos, recortados como en Redis.
+    pub args: Vec<String>,
+    /// Dirección del cliente que envió el comando.
+    pub addr: String,
+    /// Nombre asignado por el cliente mediante CLIENT SETNAME.
+    pub name: String,
+}
+
+impl SlowlogEntry {
+    /// Devuelve la entrada con el formato de SLOWLOG GET.
+    fn to_redis_element(&self) -> RedisElement {
+        RedisElement::Array(vec![
+            RedisElement::Integer(self.id as i64),
+            RedisElement::Integer(self.timestamp as i64),
+            RedisElement::Integer(self.duration.as_micros() as i64),
+            RedisElement::List(self.args.clone()),
+            RedisElement::String(self.addr.clone()),
+            RedisElement::String(self.name.clone()),
+        ])
+    }
+}
+
+#[derive(Debug, Default)]
+/// Slowlog: Registro de los últimos comandos lentos, que se consulta con SLOWLOG GET.
+pub struct Slowlog {
+    entries: VecDeque<SlowlogEntry>,
+    next_id: u64,
+}
+
+impl Slowlog {
+    /// Crea el registro vacío.
+    pub fn new() -> Self {
+        Slowlog::default()
+    }
+
+    /// Registra un comando lento, descartando las entradas más antiguas para no superar
+    /// `max_len`.
+    pub fn push(
+        &mut self,
+        args: &[String],
+        duration: Duration,
+        addr: String,
+        name: String,
+        max_len: usize,
+    ) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.entries.push_front(SlowlogEntry {
+            id: self.next_id,
+            timestamp,
+            duration,
+            args: trim_args(args),
+            addr,
+            name,
+        });
+        self.next_id += 1;
+        self.entries.truncate(max_len);
+    }
+
+    /// Devuelve las `count` entradas más recientes, de la más nueva a la más antigua.
+    pub fn get(&self, count: usize) -> Vec<RedisElement> {
+        self.entries
+            .iter()
+            .take(count)
+            .map(SlowlogEntry::to_redis_element)
+            .collect()
+    }
+
+    /// Cantidad de entradas registradas.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Descarta todas las entradas registradas.
+    pub fn reset(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Recorta los argumentos como Redis: se guardan hasta `MAX_ARGS` argumentos, indicando en el
+/// último cuántos se omitieron, y hasta `MAX_ARG_LEN` bytes de cada uno.
+fn trim_args(args: &[String]) -> Vec<String> {
+    let mut trimmed: Vec<String> = args
+        .iter()
+        .take(if args.len() > MAX_ARGS {
+            MAX_ARGS - 1
+        } else {
+            MAX_ARGS
+        })
+        .map(|arg| trim_arg(arg))
+        .collect();
+    if args.len() > MAX_ARGS {
+        trimmed.push(format!(
+            "... ({} more arguments)",
+            args.len() - MAX_ARGS + 1
+        ));
+    }
+    trimmed
+}
+
+/// Recorta un argumento a `MAX_ARG_LEN` bytes, sin partir ningún caracter.
+fn trim_arg(arg: &str) -> String {
+    if arg.len() <= MAX_ARG_LEN {
+        return arg.to_string();
+    }
+    let mut end = MAX_ARG_LEN;
+    while !arg.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} more bytes)", &arg[..end], arg.len() - end)
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::slowlog::Slowlog;
+    use std::time::Duration;
+
+    #[allow(dead_code)]
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn get_returns_newest_entries_first() {
+        let mut slowlog = Slowlog::new();
+        for key in ["a", "b", "c"] {
+            slowlog.push(
+                &args(&["get", key]),
+                Duration::from_micros(20),
+                "127.0.0.1:5000".to_string(),
+                "".to_string(),
+                2,
+            );
+        }
+        assert_eq!(2, slowlog.len());
+
+        let entries = slowlog.get(10);
+        match &entries[0] {
+            RedisElement::Array(fields) => {
+                assert_eq!(RedisElement::Integer(2), fields[0]);
+                assert_eq!(RedisElement::Integer(20), fields[2]);
+                assert_eq!(RedisElement::List(args(&["get", "c"])), fields[3]);
+                assert_eq!(
+                    RedisElement::String("127.0.0.1:5000".to_string()),
+                    fields[4]
+                );
+            }
+            _ => panic!("La entrada no es un array"),
+        }
+        assert_eq!(1, slowlog.get(1).len());
+
+        slowlog.reset();
+        assert_eq!(0, slowlog.len());
+    }
+
+    #[test]
+    fn long_commands_are_trimmed() {
+        let mut slowlog = Slowlog::new();
+        let mut command = vec!["x".repeat(130)];
+        command.extend((0..40).map(|i| i.to_string()));
+        slowlog.push(
+            &command,
+            Duration::from_micros(1),
+            "".to_string(),
+            "".to_string(),
+            10,
+        );
+
+        match &slowlog.get(1)[0] {
+            RedisElement::Array(fields) => match &fields[3] {
+                RedisElement::List(stored) => {
+                    assert_eq!(32, stored.len());
+                    assert_eq!(format!("{}... (2 more bytes)", "x".repeat(128)), stored[0]);
+                    assert_eq!("... (10 more arguments)", stored[31]);
+                }
+                _ => panic!("Los argumentos no son una lista"),
+            },
+            _ => panic!("La entrada no es un array"),
+        }
+    }
+}
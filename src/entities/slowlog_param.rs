@@ -0,0 +1,10 @@
+#[derive(Debug)]
+/// SlowlogParam: Enum usado para representar los subcomandos permitidos para el Command::Slowlog.
+pub enum SlowlogParam {
+    /// Representa el subcomando Get, que devuelve las `count` entradas más recientes.
+    Get { count: usize },
+    /// Representa el subcomando Len, que devuelve la cantidad de entradas registradas.
+    Len,
+    /// Representa el subcomando Reset, que descarta todas las entradas.
+    Reset,
+}
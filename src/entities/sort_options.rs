@@ -0,0 +1,19 @@
+#[derive(Debug, Clone, Default, PartialEq)]
+/// SortOptions: Struct usado para representar las opciones permitidas para el Command::Sort.
+pub struct SortOptions {
+    /// Patrón de las claves externas por las que se ordena (BY). El primer `*` se reemplaza por
+    /// cada elemento; si el patrón no tiene `*`, los elementos no se ordenan.
+    pub by: Option<String>,
+    /// Cantidad de elementos a saltear y cantidad máxima a devolver (LIMIT offset count). Un
+    /// count negativo devuelve todos los elementos restantes.
+    pub limit: Option<(i64, i64)>,
+    /// Patrones de las claves externas a devolver en lugar de los elementos (GET). El patrón `#`
+    /// devuelve el propio elemento.
+    pub get: Vec<String>,
+    /// Se ordena de mayor a menor (DESC).
+    pub descending: bool,
+    /// Se ordena lexicográficamente en lugar de numéricamente (ALPHA).
+    pub alpha: bool,
+    /// Clave en la que se guarda el resultado como lista (STORE).
+    pub store: Option<String>,
+}
@@ -0,0 +1,446 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::iter::FromIterator;
+
+#[derive(Debug, Clone, Copy)]
+/// Score: Wrapper sobre `f64` que le da un orden total, para poder usarlo como clave del índice
+/// ordenado del `SortedSet`.
+struct Score(f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// ScoreBound: Enum usado para representar los límites de un rango de scores, como los recibidos
+/// por ZRANGEBYSCORE.
+pub enum ScoreBound {
+    /// El límite incluye al valor indicado (ej: `1.5`).
+    Inclusive(f64),
+    /// El límite excluye al valor indicado (ej: `(1.5`).
+    Exclusive(f64),
+}
+
+impl ScoreBound {
+    /// Indica si el score se encuentra por encima del límite, tomándolo como límite inferior.
+    fn above(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(min) => score >= *min,
+            ScoreBound::Exclusive(min) => score > *min,
+        }
+    }
+
+    /// Indica si el score se encuentra por debajo del límite, tomándolo como límite superior.
+    fn below(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(max) => score <= *max,
+            ScoreBound::Exclusive(max) => score < *max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// ScoreSide: Enum usado para indicar de qué extremo del sorted set debe quitarse un miembro.
+pub enum ScoreSide {
+    /// Miembros con menor score (ZPOPMIN).
+    Min,
+    /// Miembros con mayor score (ZPOPMAX).
+    Max,
+}
+
+impl ScoreSide {
+    /// Devuelve el nombre del evento de keyspace que produce quitar un miembro de este extremo.
+    pub fn pop_event(self) -> &'static str {
+        match self {
+            ScoreSide::Min => "zpopmin",
+            ScoreSide::Max => "zpopmax",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// Aggregate: Enum usado para indicar cómo se combinan los scores de un mismo miembro en
+/// ZUNIONSTORE y ZINTERSTORE (AGGREGATE SUM|MIN|MAX).
+pub enum Aggregate {
+    /// Se suman los scores.
+    #[default]
+    Sum,
+    /// Se toma el menor score.
+    Min,
+    /// Se toma el mayor score.
+    Max,
+}
+
+impl Aggregate {
+    /// Combina dos scores. Como en Redis, una suma indefinida (`inf` + `-inf`) da 0.
+    fn combine(self, a: f64, b: f64) -> f64 {
+        match self {
+            Aggregate::Sum => zero_if_nan(a + b),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+/// Devuelve 0 si el score no es un número, como Redis al operar con scores infinitos.
+fn zero_if_nan(score: f64) -> f64 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// SortedSet: Estructura usada para representar el tipo de dato Sorted Set de Redis.
+///
+/// Cada miembro tiene asociado un score, y los miembros se recuperan ordenados por score (y
+/// lexicográficamente en caso de empate).
+pub struct SortedSet {
+    /// Mapa {miembro, score} para acceder al score de un miembro en O(1).
+    scores: HashMap<String, f64>,
+    /// Índice ordenado por (score, miembro).
+    ordered: BTreeSet<(Score, String)>,
+}
+
+impl SortedSet {
+    /// Constructor de un nuevo SortedSet vacío.
+    pub fn new() -> Self {
+        SortedSet {
+            scores: HashMap::new(),
+            ordered: BTreeSet::new(),
+        }
+    }
+
+    /// Agrega un miembro con su score. Si el miembro ya existía, actualiza su score.
+    ///
+    /// Devuelve el score previo del miembro, o None si el miembro es nuevo.
+    pub fn insert(&mut self, member: String, score: f64) -> Option<f64> {
+        let previous = self.scores.insert(member.clone(), score);
+        if let Some(previous) = previous {
+            self.ordered.remove(&(Score(previous), member.clone()));
+        }
+        self.ordered.insert((Score(score), member));
+        previous
+    }
+
+    /// Elimina un miembro. Devuelve true si el miembro existía.
+    pub fn remove(&mut self, member: &str) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => {
+                self.ordered.remove(&(Score(score), member.to_string()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Suma `increment` al score del miembro, agregándolo con score 0 si no pertenecía al set.
+    ///
+    /// Devuelve el nuevo score, o None sin modificar el set si el resultado no es un número (ej:
+    /// al sumar `-inf` a un score `+inf`).
+    pub fn increment(&mut self, member: String, increment: f64) -> Option<f64> {
+        let score = self.score(&member).unwrap_or(0.0) + increment;
+        if score.is_nan() {
+            return None;
+        }
+        self.insert(member, score);
+        Some(score)
+    }
+
+    /// Quita hasta `count` miembros del extremo indicado y los devuelve junto con sus scores, en el
+    /// orden en el cual se quitaron.
+    pub fn pop(&mut self, side: ScoreSide, count: usize) -> Vec<(String, f64)> {
+        let mut popped = Vec::new();
+        while popped.len() < count {
+            let entry = match side {
+                ScoreSide::Min => self.ordered.pop_first(),
+                ScoreSide::Max => self.ordered.pop_last(),
+            };
+            match entry {
+                Some((score, member)) => {
+                    self.scores.remove(&member);
+                    popped.push((member, score.0));
+                }
+                None => break,
+            }
+        }
+        popped
+    }
+
+    /// Devuelve la posición del miembro en el set ordenado de menor a mayor score, empezando en 0,
+    /// o None si no pertenece al set.
+    pub fn rank(&self, member: &str) -> Option<usize> {
+        let score = self.score(member)?;
+        Some(
+            self.ordered
+                .range(..(Score(score), member.to_string()))
+                .count(),
+        )
+    }
+
+    /// Devuelve el score de un miembro, o None si no pertenece al set.
+    pub fn score(&self, member: &str) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Devuelve la cantidad de miembros del set.
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Indica si el set no tiene miembros.
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Devuelve todos los pares (miembro, score) ordenados por score.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, f64)> {
+        self.ordered.iter().map(|(score, member)| (member, score.0))
+    }
+
+    /// Devuelve los pares (miembro, score) entre las posiciones `start` y `stop`, ambas inclusive.
+    ///
+    /// Los índices negativos se cuentan desde el final: -1 es el último elemento.
+    pub fn range(&self, start: i64, stop: i64) -> Vec<(String, f64)> {
+        let len = self.len() as i64;
+        let start = if start < 0 { start + len } else { start }.max(0);
+        let stop = if stop < 0 { stop + len } else { stop }.min(len - 1);
+
+        if start > stop {
+            return vec![];
+        }
+
+        self.iter()
+            .skip(start as usize)
+            .take((stop - start + 1) as usize)
+            .map(|(member, score)| (member.to_string(), score))
+            .collect()
+    }
+
+    /// Devuelve la unión de los sets, multiplicando los scores de cada uno por su peso y
+    /// combinando los scores de los miembros repetidos según `aggregate`.
+    pub fn union(sets: &[(SortedSet, f64)], aggregate: Aggregate) -> SortedSet {
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for (set, weight) in sets {
+            for (member, score) in set.iter() {
+                let score = zero_if_nan(score * weight);
+                let combined = match scores.get(member) {
+                    Some(previous) => aggregate.combine(*previous, score),
+                    None => score,
+                };
+                scores.insert(member.clone(), combined);
+            }
+        }
+        scores
+            .iter()
+            .map(|(member, score)| (member.as_str(), *score))
+            .collect()
+    }
+
+    /// Devuelve la intersección de los sets, multiplicando los scores de cada uno por su peso y
+    /// combinando los scores de cada miembro según `aggregate`. Sin sets, el resultado es vacío.
+    pub fn intersection(sets: &[(SortedSet, f64)], aggregate: Aggregate) -> SortedSet {
+        let (first, rest) = match sets.split_first() {
+            Some(split) => split,
+            None => return SortedSet::new(),
+        };
+        let (first, weight) = first;
+        first
+            .iter()
+            .filter_map(|(member, score)| {
+                let mut combined = zero_if_nan(score * weight);
+                for (set, weight) in rest {
+                    let score = zero_if_nan(set.score(member)? * weight);
+                    combined = aggregate.combine(combined, score);
+                }
+                Some((member.as_str(), combined))
+            })
+            .collect()
+    }
+
+    /// Devuelve los pares (miembro, score) cuyo score se encuentra entre `min` y `max`.
+    pub fn range_by_score(&self, min: ScoreBound, max: ScoreBound) -> Vec<(String, f64)> {
+        self.iter()
+            .skip_while(|(_, score)| !min.above(*score))
+            .take_while(|(_, score)| max.below(*score))
+            .map(|(member, score)| (member.to_string(), score))
+            .collect()
+    }
+}
+
+impl PartialEq for SortedSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.scores == other.scores
+    }
+}
+
+impl<'a> FromIterator<(&'a str, f64)> for SortedSet {
+    fn from_iter<I: IntoIterator<Item = (&'a str, f64)>>(iter: I) -> Self {
+        let mut set = SortedSet::new();
+        for (member, score) in iter {
+            set.insert(member.to_string(), score);
+        }
+        set
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::sorted_set::{Aggregate, ScoreBound, ScoreSide, SortedSet};
+
+    #[test]
+    fn test_insert_returns_previous_score() {
+        let mut set = SortedSet::new();
+
+        assert_eq!(set.insert("a".to_string(), 1.0), None);
+        assert_eq!(set.insert("a".to_string(), 2.0), Some(1.0));
+        assert_eq!(set.score("a"), Some(2.0));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_iter_is_ordered_by_score_then_member() {
+        let set: SortedSet = vec![("c", 1.0), ("b", 2.0), ("a", 1.0)]
+            .into_iter()
+            .collect();
+
+        let members: Vec<&String> = set.iter().map(|(member, _)| member).collect();
+        assert_eq!(members, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_remove_member() {
+        let mut set: SortedSet = vec![("a", 1.0), ("b", 2.0)].into_iter().collect();
+
+        assert!(set.remove("a"));
+        assert!(!set.remove("a"));
+        assert_eq!(set.range(0, -1), vec![("b".to_string(), 2.0)]);
+    }
+
+    #[test]
+    fn test_range_with_negative_indexes() {
+        let set: SortedSet = vec![("a", 1.0), ("b", 2.0), ("c", 3.0)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            set.range(-2, -1),
+            vec![("b".to_string(), 2.0), ("c".to_string(), 3.0)]
+        );
+        assert_eq!(set.range(5, 10), vec![]);
+        assert_eq!(set.range(0, 100).len(), 3);
+    }
+
+    #[test]
+    fn test_range_by_score_with_exclusive_bounds() {
+        let set: SortedSet = vec![("a", 1.0), ("b", 2.0), ("c", 3.0)]
+            .into_iter()
+            .collect();
+
+        let range = set.range_by_score(ScoreBound::Exclusive(1.0), ScoreBound::Inclusive(3.0));
+        assert_eq!(range, vec![("b".to_string(), 2.0), ("c".to_string(), 3.0)]);
+
+        let range = set.range_by_score(
+            ScoreBound::Inclusive(f64::NEG_INFINITY),
+            ScoreBound::Exclusive(2.0),
+        );
+        assert_eq!(range, vec![("a".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn test_increment_adds_missing_members_and_rejects_nan() {
+        let mut set: SortedSet = vec![("a", f64::INFINITY)].into_iter().collect();
+
+        assert_eq!(set.increment("b".to_string(), 2.5), Some(2.5));
+        assert_eq!(set.increment("b".to_string(), -1.0), Some(1.5));
+        assert_eq!(set.increment("a".to_string(), f64::NEG_INFINITY), None);
+        assert_eq!(set.score("a"), Some(f64::INFINITY));
+        assert_eq!(set.range(0, -1)[0], ("b".to_string(), 1.5));
+    }
+
+    #[test]
+    fn test_rank_follows_score_order() {
+        let set: SortedSet = vec![("c", 1.0), ("b", 2.0), ("a", 1.0)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(set.rank("a"), Some(0));
+        assert_eq!(set.rank("c"), Some(1));
+        assert_eq!(set.rank("b"), Some(2));
+        assert_eq!(set.rank("d"), None);
+    }
+
+    #[test]
+    fn test_pop_from_both_sides() {
+        let mut set: SortedSet = vec![("a", 1.0), ("b", 2.0), ("c", 3.0)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(set.pop(ScoreSide::Max, 1), vec![("c".to_string(), 3.0)]);
+        assert_eq!(
+            set.pop(ScoreSide::Min, 5),
+            vec![("a".to_string(), 1.0), ("b".to_string(), 2.0)]
+        );
+        assert!(set.is_empty());
+        assert_eq!(set.score("a"), None);
+    }
+
+    #[test]
+    fn test_union_with_weights_and_aggregate() {
+        let a: SortedSet = vec![("x", 1.0), ("y", 2.0)].into_iter().collect();
+        let b: SortedSet = vec![("y", 3.0), ("z", 4.0)].into_iter().collect();
+        let sets = vec![(a, 2.0), (b, 1.0)];
+
+        let sum = SortedSet::union(&sets, Aggregate::Sum);
+        assert_eq!(
+            sum.range(0, -1),
+            vec![
+                ("x".to_string(), 2.0),
+                ("z".to_string(), 4.0),
+                ("y".to_string(), 7.0)
+            ]
+        );
+        assert_eq!(
+            SortedSet::union(&sets, Aggregate::Min).score("y"),
+            Some(3.0)
+        );
+        assert_eq!(
+            SortedSet::union(&sets, Aggregate::Max).score("y"),
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    fn test_intersection_keeps_common_members() {
+        let a: SortedSet = vec![("x", 1.0), ("y", f64::INFINITY)].into_iter().collect();
+        let b: SortedSet = vec![("y", f64::NEG_INFINITY), ("z", 4.0)]
+            .into_iter()
+            .collect();
+        let sets = vec![(a, 1.0), (b, 1.0)];
+
+        let sum = SortedSet::intersection(&sets, Aggregate::Sum);
+        assert_eq!(sum.range(0, -1), vec![("y".to_string(), 0.0)]);
+        assert_eq!(
+            SortedSet::intersection(&sets, Aggregate::Max).score("y"),
+            Some(f64::INFINITY)
+        );
+        assert!(SortedSet::intersection(&[], Aggregate::Sum).is_empty());
+    }
+}
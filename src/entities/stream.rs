@@ -0,0 +1,578 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+const INVALID_ID_MSG: &str = "ERR Invalid stream ID specified as stream command argument";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// StreamId: Identificador de una entrada de un stream, compuesto por un timestamp en milisegundos
+/// y un número de secuencia (`<ms>-<seq>`).
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl StreamId {
+    /// Mínimo ID posible (`0-0`).
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    /// Máximo ID posible.
+    pub const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    /// Constructor de un nuevo StreamId.
+    pub fn new(ms: u64, seq: u64) -> Self {
+        StreamId { ms, seq }
+    }
+
+    /// Devuelve el ID inmediatamente posterior, o None si es el máximo posible.
+    pub fn next(&self) -> Option<StreamId> {
+        match self.seq.checked_add(1) {
+            Some(seq) => Some(StreamId::new(self.ms, seq)),
+            None => Some(StreamId::new(self.ms.checked_add(1)?, 0)),
+        }
+    }
+
+    /// Devuelve el ID inmediatamente anterior, o None si es el mínimo posible.
+    pub fn previous(&self) -> Option<StreamId> {
+        match self.seq.checked_sub(1) {
+            Some(seq) => Some(StreamId::new(self.ms, seq)),
+            None => Some(StreamId::new(self.ms.checked_sub(1)?, u64::MAX)),
+        }
+    }
+
+    /// Parsea un ID que puede omitir el número de secuencia (ej: `1526919030474`), en cuyo caso
+    /// se utiliza la secuencia indicada.
+    pub fn parse_with_default_seq(id: &str, default_seq: u64) -> Result<StreamId, String> {
+        match id.split_once('-') {
+            Some(_) => id.parse(),
+            None => match id.parse::<u64>() {
+                Ok(ms) => Ok(StreamId::new(ms, default_seq)),
+                Err(_) => Err(INVALID_ID_MSG.to_string()),
+            },
+        }
+    }
+}
+
+impl FromStr for StreamId {
+    type Err = String;
+
+    /// Parsea un ID completo con el formato `<ms>-<seq>`.
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        let (ms, seq) = id
+            .split_once('-')
+            .ok_or_else(|| INVALID_ID_MSG.to_string())?;
+        match (ms.parse::<u64>(), seq.parse::<u64>()) {
+            (Ok(ms), Ok(seq)) => Ok(StreamId::new(ms, seq)),
+            _ => Err(INVALID_ID_MSG.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}-{}", self.ms, self.seq)
+    }
+}
+
+/// StreamFields: Pares (campo, valor) de una entrada de un stream.
+pub type StreamFields = Vec<(String, String)>;
+
+/// StreamEntry: Entrada de un stream, formada por su ID y sus campos.
+pub type StreamEntry = (StreamId, StreamFields);
+
+#[derive(Debug, Clone, PartialEq)]
+/// PendingEntry: Entrada entregada a un consumidor de un grupo que todavía no fue confirmada con
+/// XACK.
+pub struct PendingEntry {
+    /// Consumidor al cual se entregó la entrada por última vez.
+    pub consumer: String,
+    /// Momento de la última entrega.
+    pub delivery_time: SystemTime,
+    /// Cantidad de veces que se entregó la entrada.
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// ConsumerGroup: Grupo de consumidores de un stream. Cada entrada nueva se entrega a un único
+/// consumidor del grupo, y queda pendiente hasta que se confirma.
+pub struct ConsumerGroup {
+    /// ID de la última entrada entregada a algún consumidor del grupo.
+    pub last_delivered: StreamId,
+    /// Mapa {consumidor, momento de su última actividad}.
+    pub consumers: BTreeMap<String, SystemTime>,
+    /// Lista de entradas pendientes (PEL), indexada por ID.
+    pub pending: BTreeMap<StreamId, PendingEntry>,
+}
+
+impl ConsumerGroup {
+    /// Constructor de un nuevo grupo que entrega las entradas posteriores a `last_delivered`.
+    pub fn new(last_delivered: StreamId) -> Self {
+        ConsumerGroup {
+            last_delivered,
+            consumers: BTreeMap::new(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Agrega un consumidor al grupo. Devuelve false si ya existía.
+    pub fn create_consumer(&mut self, consumer: &str, now: SystemTime) -> bool {
+        if self.consumers.contains_key(consumer) {
+            return false;
+        }
+        self.consumers.insert(consumer.to_string(), now);
+        true
+    }
+
+    /// Elimina un consumidor del grupo junto con sus entradas pendientes. Devuelve la cantidad de
+    /// entradas pendientes que tenía, o None si el consumidor no existía.
+    pub fn delete_consumer(&mut self, consumer: &str) -> Option<usize> {
+        self.consumers.remove(consumer)?;
+
+        let before = self.pending.len();
+        self.pending.retain(|_, entry| entry.consumer != consumer);
+        Some(before - self.pending.len())
+    }
+
+    /// Confirma las entradas indicadas, quitándolas de la lista de pendientes. Devuelve la
+    /// cantidad de entradas que estaban pendientes.
+    pub fn ack(&mut self, ids: &[StreamId]) -> usize {
+        ids.iter()
+            .filter(|id| self.pending.remove(id).is_some())
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Stream: Estructura usada para representar el tipo de dato Stream de Redis.
+///
+/// Las entradas se recuperan ordenadas por ID, y cada nueva entrada debe tener un ID mayor al de
+/// la última entrada agregada (aunque esta haya sido eliminada).
+pub struct Stream {
+    /// Entradas del stream, indexadas por ID.
+    entries: BTreeMap<StreamId, StreamFields>,
+    /// ID de la última entrada agregada.
+    last_id: StreamId,
+    /// Grupos de consumidores, indexados por nombre.
+    groups: BTreeMap<String, ConsumerGroup>,
+}
+
+impl Stream {
+    /// Constructor de un nuevo Stream vacío.
+    pub fn new() -> Self {
+        Stream {
+            entries: BTreeMap::new(),
+            last_id: StreamId::MIN,
+            groups: BTreeMap::new(),
+        }
+    }
+
+    /// Genera el ID para una nueva entrada a partir del timestamp actual en milisegundos.
+    ///
+    /// Si el reloj es anterior a la última entrada, se reutiliza su timestamp incrementando la
+    /// secuencia. Devuelve None si no existen IDs mayores al último.
+    pub fn next_id(&self, now_ms: u64) -> Option<StreamId> {
+        if now_ms > self.last_id.ms {
+            Some(StreamId::new(now_ms, 0))
+        } else {
+            self.last_id.next()
+        }
+    }
+
+    /// Agrega una entrada con el ID indicado, que debe ser mayor al de la última entrada.
+    pub fn add(&mut self, id: StreamId, fields: StreamFields) -> Result<(), String> {
+        if id == StreamId::MIN {
+            return Err("ERR The ID specified in XADD must be greater than 0-0".to_string());
+        }
+        if id <= self.last_id {
+            return Err(
+                "ERR The ID specified in XADD is equal or smaller than the target stream top item"
+                    .to_string(),
+            );
+        }
+
+        self.entries.insert(id, fields);
+        self.last_id = id;
+        Ok(())
+    }
+
+    /// Elimina las entradas más antiguas hasta que el stream tenga como máximo `max_len`
+    /// entradas. Devuelve la cantidad de entradas eliminadas.
+    pub fn trim(&mut self, max_len: usize) -> usize {
+        let mut removed = 0;
+        while self.entries.len() > max_len {
+            let oldest = *self.entries.keys().next().unwrap();
+            self.entries.remove(&oldest);
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Devuelve la cantidad de entradas del stream.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[allow(dead_code)]
+    /// Indica si el stream no tiene entradas.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Devuelve el ID de la última entrada agregada.
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    /// Devuelve las entradas cuyo ID se encuentra entre `start` y `end`, ambos inclusive, en orden
+    /// ascendente y limitadas a `count` entradas.
+    pub fn range(&self, start: StreamId, end: StreamId, count: Option<usize>) -> Vec<StreamEntry> {
+        if start > end {
+            return vec![];
+        }
+
+        self.entries
+            .range(start..=end)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| (*id, fields.clone()))
+            .collect()
+    }
+
+    /// Devuelve las entradas cuyo ID se encuentra entre `start` y `end`, ambos inclusive, en orden
+    /// descendente y limitadas a `count` entradas.
+    pub fn rev_range(
+        &self,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+    ) -> Vec<StreamEntry> {
+        if start > end {
+            return vec![];
+        }
+
+        self.entries
+            .range(start..=end)
+            .rev()
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| (*id, fields.clone()))
+            .collect()
+    }
+
+    /// Devuelve todas las entradas en orden ascendente.
+    pub fn iter(&self) -> impl Iterator<Item = (&StreamId, &StreamFields)> {
+        self.entries.iter()
+    }
+
+    /// Setea el ID de la última entrada agregada. Se usa al cargar el stream desde disco, ya que
+    /// puede ser mayor al de la última entrada existente.
+    pub fn set_last_id(&mut self, id: StreamId) {
+        self.last_id = self.last_id.max(id);
+    }
+
+    /// Crea un grupo de consumidores que entrega las entradas posteriores al ID indicado, o a la
+    /// última entrada del stream si no se indica.
+    pub fn create_group(
+        &mut self,
+        name: &str,
+        last_delivered: Option<StreamId>,
+    ) -> Result<(), String> {
+        if self.groups.contains_key(name) {
+            return Err("BUSYGROUP Consumer Group name already exists".to_string());
+        }
+
+        let last_delivered = last_delivered.unwrap_or(self.last_id);
+        self.groups
+            .insert(name.to_string(), ConsumerGroup::new(last_delivered));
+        Ok(())
+    }
+
+    /// Agrega un grupo de consumidores ya construido. Se usa al cargar el stream desde disco.
+    pub fn insert_group(&mut self, name: String, group: ConsumerGroup) {
+        self.groups.insert(name, group);
+    }
+
+    /// Elimina un grupo de consumidores. Devuelve true si el grupo existía.
+    pub fn destroy_group(&mut self, name: &str) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    /// Devuelve el grupo de consumidores con el nombre indicado.
+    pub fn group(&self, name: &str) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    /// Devuelve el grupo de consumidores con el nombre indicado, permitiendo modificarlo.
+    pub fn group_mut(&mut self, name: &str) -> Option<&mut ConsumerGroup> {
+        self.groups.get_mut(name)
+    }
+
+    /// Devuelve todos los grupos de consumidores ordenados por nombre.
+    pub fn groups(&self) -> impl Iterator<Item = (&String, &ConsumerGroup)> {
+        self.groups.iter()
+    }
+
+    /// Lee entradas del stream como el consumidor indicado del grupo. Devuelve None si el grupo no
+    /// existe.
+    ///
+    /// Si no se indica un ID, se entregan las entradas que el grupo aún no entregó, y se agregan a
+    /// la lista de pendientes del consumidor (salvo con `no_ack`). Si se indica un ID, se
+    /// devuelven las entradas pendientes del consumidor posteriores a él; las entradas que ya no
+    /// existen en el stream se devuelven sin campos.
+    pub fn read_group(
+        &mut self,
+        group: &str,
+        consumer: &str,
+        id: Option<StreamId>,
+        count: Option<usize>,
+        no_ack: bool,
+        now: SystemTime,
+    ) -> Option<Vec<(StreamId, Option<StreamFields>)>> {
+        let group = self.groups.get_mut(group)?;
+        group.consumers.insert(consumer.to_string(), now);
+        let count = count.unwrap_or(usize::MAX);
+
+        let entries = match id {
+            None => {
+                let start = match group.last_delivered.next() {
+                    Some(start) => start,
+                    None => return Some(vec![]),
+                };
+                let entries: Vec<StreamEntry> = self
+                    .entries
+                    .range(start..)
+                    .take(count)
+                    .map(|(id, fields)| (*id, fields.clone()))
+                    .collect();
+
+                if let Some((last, _)) = entries.last() {
+                    group.last_delivered = *last;
+                }
+                if !no_ack {
+                    for (id, _) in entries.iter() {
+                        let entry = PendingEntry {
+                            consumer: consumer.to_string(),
+                            delivery_time: now,
+                            delivery_count: 1,
+                        };
+                        group.pending.insert(*id, entry);
+                    }
+                }
+                entries
+                    .into_iter()
+                    .map(|(id, fields)| (id, Some(fields)))
+                    .collect()
+            }
+            Some(id) => match id.next() {
+                Some(start) => {
+                    let stream_entries = &self.entries;
+                    group
+                        .pending
+                        .range(start..)
+                        .filter(|(_, entry)| entry.consumer == consumer)
+                        .take(count)
+                        .map(|(id, _)| (*id, stream_entries.get(id).cloned()))
+                        .collect()
+                }
+                None => vec![],
+            },
+        };
+        Some(entries)
+    }
+
+    /// Transfiere al consumidor indicado las entradas pendientes del grupo que llevan inactivas al
+    /// menos `min_idle`. Devuelve None si el grupo no existe.
+    ///
+    /// Las entradas que ya no existen en el stream se quitan de la lista de pendientes. Salvo con
+    /// `just_id`, se incrementa la cantidad de entregas de cada entrada transferida.
+    pub fn claim(
+        &mut self,
+        group: &str,
+        consumer: &str,
+        min_idle: Duration,
+        ids: &[StreamId],
+        just_id: bool,
+        now: SystemTime,
+    ) -> Option<Vec<StreamEntry>> {
+        let group = self.groups.get_mut(group)?;
+        group.consumers.insert(consumer.to_string(), now);
+
+        let mut claimed = vec![];
+        for id in ids {
+            let entry = match group.pending.get_mut(id) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let idle = now.duration_since(entry.delivery_time).unwrap_or_default();
+            if idle < min_idle {
+                continue;
+            }
+
+            match self.entries.get(id) {
+                Some(fields) => {
+                    entry.consumer = consumer.to_string();
+                    entry.delivery_time = now;
+                    if !just_id {
+                        entry.delivery_count += 1;
+                    }
+                    claimed.push((*id, fields.clone()));
+                }
+                None => {
+                    group.pending.remove(id);
+                }
+            }
+        }
+        Some(claimed)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::stream::{Stream, StreamId};
+    use std::time::{Duration, SystemTime};
+
+    #[allow(dead_code)]
+    fn fields(field: &str, value: &str) -> Vec<(String, String)> {
+        vec![(field.to_string(), value.to_string())]
+    }
+
+    #[test]
+    fn test_parse_stream_ids() {
+        assert_eq!(Ok(StreamId::new(5, 1)), "5-1".parse::<StreamId>());
+        assert!("5".parse::<StreamId>().is_err());
+        assert!("a-1".parse::<StreamId>().is_err());
+        assert_eq!(
+            Ok(StreamId::new(5, u64::MAX)),
+            StreamId::parse_with_default_seq("5", u64::MAX)
+        );
+        assert_eq!("5-1", StreamId::new(5, 1).to_string());
+    }
+
+    #[test]
+    fn test_next_id_uses_sequence_when_clock_goes_back() {
+        let mut stream = Stream::new();
+        assert_eq!(Some(StreamId::new(10, 0)), stream.next_id(10));
+
+        stream.add(StreamId::new(10, 0), fields("a", "1")).unwrap();
+        assert_eq!(Some(StreamId::new(10, 1)), stream.next_id(10));
+        assert_eq!(Some(StreamId::new(10, 1)), stream.next_id(5));
+        assert_eq!(Some(StreamId::new(11, 0)), stream.next_id(11));
+    }
+
+    #[test]
+    fn test_add_rejects_smaller_ids() {
+        let mut stream = Stream::new();
+
+        assert!(stream.add(StreamId::MIN, fields("a", "1")).is_err());
+        assert!(stream.add(StreamId::new(2, 0), fields("a", "1")).is_ok());
+        assert!(stream.add(StreamId::new(2, 0), fields("a", "1")).is_err());
+        assert!(stream.add(StreamId::new(1, 5), fields("a", "1")).is_err());
+        assert_eq!(1, stream.len());
+    }
+
+    #[test]
+    fn test_range_trim_and_last_id() {
+        let mut stream = Stream::new();
+        for ms in 1..=4 {
+            stream.add(StreamId::new(ms, 0), fields("a", "1")).unwrap();
+        }
+
+        let ids: Vec<StreamId> = stream
+            .range(StreamId::new(2, 0), StreamId::MAX, Some(2))
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(vec![StreamId::new(2, 0), StreamId::new(3, 0)], ids);
+
+        let ids: Vec<StreamId> = stream
+            .rev_range(StreamId::MIN, StreamId::new(3, 0), None)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(
+            vec![
+                StreamId::new(3, 0),
+                StreamId::new(2, 0),
+                StreamId::new(1, 0)
+            ],
+            ids
+        );
+
+        assert_eq!(2, stream.trim(2));
+        assert_eq!(2, stream.len());
+        assert_eq!(StreamId::new(4, 0), stream.last_id());
+    }
+
+    #[test]
+    fn test_read_group_delivers_new_entries_once() {
+        let mut stream = Stream::new();
+        let now = SystemTime::now();
+        stream.add(StreamId::new(1, 0), fields("a", "1")).unwrap();
+        stream.create_group("group", Some(StreamId::MIN)).unwrap();
+        stream.add(StreamId::new(2, 0), fields("b", "2")).unwrap();
+
+        let entries = stream
+            .read_group("group", "alice", None, Some(1), false, now)
+            .unwrap();
+        assert_eq!(vec![(StreamId::new(1, 0), Some(fields("a", "1")))], entries);
+
+        let entries = stream
+            .read_group("group", "bob", None, None, false, now)
+            .unwrap();
+        assert_eq!(vec![(StreamId::new(2, 0), Some(fields("b", "2")))], entries);
+
+        let entries = stream
+            .read_group("group", "alice", Some(StreamId::MIN), None, false, now)
+            .unwrap();
+        assert_eq!(vec![(StreamId::new(1, 0), Some(fields("a", "1")))], entries);
+
+        let group = stream.group_mut("group").unwrap();
+        assert_eq!(1, group.ack(&[StreamId::new(1, 0), StreamId::new(9, 0)]));
+        assert_eq!(Some(1), group.delete_consumer("bob"));
+        assert!(group.pending.is_empty());
+
+        assert!(stream
+            .read_group("missing", "alice", None, None, false, now)
+            .is_none());
+        assert!(stream.create_group("group", None).is_err());
+    }
+
+    #[test]
+    fn test_claim_only_idle_entries() {
+        let mut stream = Stream::new();
+        let now = SystemTime::now();
+        let before = now - Duration::from_secs(10);
+        stream.add(StreamId::new(1, 0), fields("a", "1")).unwrap();
+        stream.create_group("group", Some(StreamId::MIN)).unwrap();
+        stream.read_group("group", "alice", None, None, false, before);
+
+        let claimed = stream
+            .claim(
+                "group",
+                "bob",
+                Duration::from_secs(20),
+                &[StreamId::new(1, 0)],
+                false,
+                now,
+            )
+            .unwrap();
+        assert!(claimed.is_empty());
+
+        let claimed = stream
+            .claim(
+                "group",
+                "bob",
+                Duration::from_secs(5),
+                &[StreamId::new(1, 0)],
+                false,
+                now,
+            )
+            .unwrap();
+        assert_eq!(vec![(StreamId::new(1, 0), fields("a", "1"))], claimed);
+
+        let entry = &stream.group("group").unwrap().pending[&StreamId::new(1, 0)];
+        assert_eq!("bob", entry.consumer);
+        assert_eq!(2, entry.delivery_count);
+    }
+}
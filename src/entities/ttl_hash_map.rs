@@ -1,75 +1,247 @@
-use crate::entities::redis_element::RedisElement;
-use std::collections::hash_map::Keys;
+use crate::entities::clock::{Clock, SystemClock};
+use crate::entities::crc64::crc64;
+use crate::entities::expire_condition::ExpireCondition;
+use crate::entities::lfu::LfuCounter;
+use crate::entities::memory::MemoryUsage;
+use crate::entities::rdb::{self, RdbReader, Serializable};
+use crate::service::random::random_index;
 use std::collections::HashMap;
 use std::hash::Hash;
-use std::str::from_utf8;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use std::vec::Drain;
+
+#[derive(Debug, Clone, Copy)]
+/// Último acceso a una clave y frecuencia con la que se accede a ella.
+struct Access {
+    last: SystemTime,
+    frequency: LfuCounter,
+}
+
+impl Access {
+    /// Crea el registro de accesos de una clave accedida en `now`.
+    fn new(now: SystemTime) -> Self {
+        Access {
+            last: now,
+            frequency: LfuCounter::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Entrada del mapa: el valor de una clave junto con su expiración y sus accesos, de modo que
+/// insertar o borrar una clave modifica todo a la vez.
+struct Entry<V> {
+    /// Valor de la clave. Se comparte con las copias tomadas con `snapshot`, y se copia recién al
+    /// modificarlo mientras alguna copia lo siga usando.
+    value: Arc<V>,
+    /// Momento en el que expira la clave, o None si es persistente.
+    expires_at: Option<SystemTime>,
+    /// Accesos a la clave, o None si no se registran.
+    access: Option<Access>,
+    /// Posición de la clave en el vector de claves del mapa.
+    index: usize,
+    /// Posición de la clave en el vector de claves con expiración, o None si es persistente.
+    volatile_index: Option<usize>,
+    /// Memoria que ocupa la clave junto con su valor, según la última estimación.
+    size: usize,
+    /// Indica si el valor pudo modificarse desde que se estimó su tamaño.
+    resized: bool,
+}
+
+impl<V> Entry<V> {
+    fn expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|ttl| ttl <= now)
+    }
+}
 
 #[derive(Debug)]
-/// Estructura para almacenar los pares clave-valor con su expiración, si corresponde. Además maneja los últimos accesos a cada clave.
+/// Estructura para almacenar los pares clave-valor con su expiración, si corresponde. Además maneja los últimos accesos a cada clave y la frecuencia con la que se accede a ellas.
 pub struct TtlHashMap<K: Eq + Hash, V> {
-    store: HashMap<K, V>,
-    ttls: HashMap<K, SystemTime>,
-    last_access: HashMap<K, SystemTime>,
+    store: HashMap<K, Entry<V>>,
+    /// Claves del mapa, para poder elegir claves al azar en tiempo constante. Cada entrada guarda
+    /// su posición en el vector, de modo que al borrarla se la reemplaza por la última clave.
+    keys: Vec<K>,
+    /// Claves con expiración, como el diccionario `expires` de Redis, para revisar sólo esas
+    /// claves al eliminar las que expiraron. Cada entrada con expiración guarda su posición.
+    volatile: Vec<K>,
+    /// Indica si se registran los accesos a las claves. Está deshabilitado por defecto, ya que
+    /// agrega trabajo a cada lectura.
+    track_access: bool,
+    /// Claves eliminadas por haber expirado que todavía no fueron informadas.
+    expired_keys: Vec<K>,
+    /// Memoria que ocupan las claves con sus valores, según la última estimación de cada una.
+    used_memory: usize,
+    /// Claves cuyos valores pudieron modificarse desde que se estimó su tamaño, que se vuelve a
+    /// estimar recién al consultar la memoria usada.
+    resized: Vec<K>,
+    /// Reloj con el que se calculan las expiraciones y los accesos.
+    clock: Arc<dyn Clock>,
 }
 
-impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
+impl<K: Clone + Eq + Hash + AsRef<str>, V: Clone + MemoryUsage> TtlHashMap<K, V> {
+    #[allow(dead_code)]
     /// Constructor de un nuevo TtlHashMap
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Constructor de un nuevo TtlHashMap que toma la hora actual de `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         TtlHashMap {
             store: HashMap::new(),
-            ttls: HashMap::new(),
-            last_access: HashMap::new(),
+            keys: Vec::new(),
+            volatile: Vec::new(),
+            track_access: false,
+            expired_keys: Vec::new(),
+            used_memory: 0,
+            resized: Vec::new(),
+            clock,
         }
     }
 
-    fn expired(&self, key: &K) -> bool {
-        match self.ttls.get(key) {
-            Some(ttl) => ttl.elapsed().is_ok(),
-            None => false,
+    /// Reemplaza el reloj con el que se calculan las expiraciones y los accesos.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Devuelve el reloj con el que se calculan las expiraciones y los accesos.
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    /// Habilita o deshabilita el registro de los accesos a las claves, usado por OBJECT FREQ y
+    /// DEBUG OBJECT. Al deshabilitarlo se descartan los accesos registrados, y al habilitarlo las
+    /// claves existentes se consideran accedidas en ese momento.
+    pub fn set_access_tracking(&mut self, enabled: bool) {
+        if enabled != self.track_access {
+            let now = self.clock.now();
+            for entry in self.store.values_mut() {
+                entry.access = if enabled {
+                    Some(Access::new(now))
+                } else {
+                    None
+                };
+            }
         }
+        self.track_access = enabled;
     }
 
-    /// Actualiza el último acceso a la clave.
-    /// Devuelve el tiempo transcurrido desde el anterior acceso, o None si no existe la clave o expiró.
-    pub fn update_last_access(&mut self, key: &K) -> Option<Duration> {
-        if !self.contains_key(key) {
+    /// Indica si se registran los accesos a las claves.
+    pub fn tracks_access(&self) -> bool {
+        self.track_access
+    }
+
+    fn expired(&self, key: &K) -> bool {
+        let now = self.clock.now();
+        self.store.get(key).is_some_and(|entry| entry.expired(now))
+    }
+
+    /// Devuelve la entrada de la clave si existe y no expiró. Si expiró, la borra.
+    fn valid_entry(&mut self, key: &K) -> Option<&mut Entry<V>> {
+        if self.expired(key) {
+            self.remove_expired(key);
             return None;
         }
+        self.store.get_mut(key)
+    }
 
-        self.last_access
-            .insert(key.clone(), SystemTime::now())
-            .map(|value| value.elapsed().unwrap_or_else(|_| Duration::from_secs(0)))
+    /// Registra un acceso a la entrada, si se registran los accesos. Devuelve el tiempo
+    /// transcurrido desde el acceso anterior, o 0 si no se registran.
+    fn touch(entry: &mut Entry<V>, now: SystemTime) -> Duration {
+        match entry.access.as_mut() {
+            Some(access) => {
+                access.frequency.access();
+                let previous = std::mem::replace(&mut access.last, now);
+                now.duration_since(previous)
+                    .unwrap_or_else(|_| Duration::from_secs(0))
+            }
+            None => Duration::from_secs(0),
+        }
+    }
+
+    /// Reemplaza la expiración de la clave, que debe existir, y devuelve la anterior.
+    fn replace_expiration(&mut self, key: &K, ttl: Option<SystemTime>) -> Option<SystemTime> {
+        let entry = self.store.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.expires_at, ttl);
+        match (entry.volatile_index, ttl.is_some()) {
+            (None, true) => self.index_volatile(key),
+            (Some(index), false) => {
+                entry.volatile_index = None;
+                self.unindex_volatile(index);
+            }
+            _ => (),
+        }
+        previous
+    }
+
+    /// Agrega la clave, que debe existir, al vector de claves con expiración.
+    fn index_volatile(&mut self, key: &K) {
+        if let Some(entry) = self.store.get_mut(key) {
+            entry.volatile_index = Some(self.volatile.len());
+            self.volatile.push(key.clone());
+        }
+    }
+
+    /// Quita la clave en la posición indicada del vector de claves con expiración, reemplazándola
+    /// por la última.
+    fn unindex_volatile(&mut self, index: usize) {
+        self.volatile.swap_remove(index);
+        if let Some(moved) = self.volatile.get(index) {
+            if let Some(moved) = self.store.get_mut(moved) {
+                moved.volatile_index = Some(index);
+            }
+        }
+    }
+
+    /// Actualiza el último acceso a la clave y registra el acceso en su frecuencia.
+    /// Devuelve el tiempo transcurrido desde el anterior acceso, o None si no existe la clave o expiró.
+    /// Si no se registran los accesos, devuelve 0 para las claves existentes.
+    pub fn update_last_access(&mut self, key: &K) -> Option<Duration> {
+        let now = self.clock.now();
+        self.valid_entry(key).map(|entry| Self::touch(entry, now))
     }
 
     /// Setea una expiración para la clave a partir de una Duration.
-    /// Devuelve None si no existe la clave, y SystemTime::UNIX_EPOCH si era persistente. Sino, devuelve el valor previo de ttl.
+    /// Devuelve None si no existe la clave o la expiración desborda, y SystemTime::UNIX_EPOCH si era persistente. Sino, devuelve el valor previo de ttl.
     pub fn set_ttl_relative(&mut self, key: K, duration: Duration) -> Option<SystemTime> {
-        if !self.contains_key(&key) {
-            return None;
+        let ttl = self.clock.now().checked_add(duration)?;
+        self.set_ttl_absolute(key, ttl)
+    }
+
+    /// Setea una expiración para la clave en un cierto SystemTime, solamente si se cumplen todas
+    /// las condiciones indicadas comparando la nueva expiración con la actual.
+    /// Devuelve true si se modificó la expiración, y false si no existe la clave o no se cumplió
+    /// alguna condición.
+    pub fn set_ttl_if(&mut self, key: K, ttl: SystemTime, conditions: &[ExpireCondition]) -> bool {
+        let current = match self.valid_entry(&key) {
+            Some(entry) => entry.expires_at,
+            None => return false,
+        };
+        if !conditions
+            .iter()
+            .all(|condition| condition.holds(current, ttl))
+        {
+            return false;
         }
-        let ttl = SystemTime::now() + duration;
-        Some(self.ttls.insert(key, ttl).unwrap_or(SystemTime::UNIX_EPOCH))
+        self.replace_expiration(&key, Some(ttl));
+        true
     }
 
     /// Setea una expiración para la clave en un cierto SystemTime.
     /// Devuelve None si no existe la clave, y SystemTime::UNIX_EPOCH si era persistente. Sino, devuelve el valor previo de ttl.
     pub fn set_ttl_absolute(&mut self, key: K, ttl: SystemTime) -> Option<SystemTime> {
-        if !self.contains_key(&key) {
-            return None;
-        }
-        Some(self.ttls.insert(key, ttl).unwrap_or(SystemTime::UNIX_EPOCH))
+        self.valid_entry(&key)?;
+        Some(
+            self.replace_expiration(&key, Some(ttl))
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        )
     }
 
     /// Elimina la expiración de la clave.
     /// Devuelve el ttl que tenía. Si no tenía una expiración, devuelve None.
     pub fn delete_ttl(&mut self, key: &K) -> Option<SystemTime> {
-        if self.expired(key) {
-            self.remove(key);
-            return None;
-        }
-        self.ttls.remove(key)
+        self.valid_entry(key)?;
+        self.replace_expiration(key, None)
     }
 
     /// Obtiene el tiempo restante de expiración de una clave como Duration.
@@ -78,13 +250,13 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
         if !self.contains_key(key) {
             return None;
         }
-        let ttl = match self.ttls.get(key) {
-            Some(value) => value
-                .duration_since(SystemTime::now())
-                .unwrap_or_else(|_| Duration::from_secs(0)),
-            None => Duration::from_secs(0),
-        };
-        Some(ttl)
+        self.get_ttl_shared(key).flatten()
+    }
+
+    /// Devuelve el momento en el que expira la clave, o Some(None) si la clave es persistente.
+    /// Devuelve None si no existe la clave o expiró.
+    pub fn get_expiration(&mut self, key: &K) -> Option<Option<SystemTime>> {
+        self.valid_entry(key).map(|entry| entry.expires_at)
     }
 
     /// Devuelve la cantidad de claves guardadas, sin chequear que no hayan expirado.
@@ -94,262 +266,556 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
 
     /// Guarda un par clave-valor.
     pub fn insert(&mut self, key: K, value: V) {
-        self.remove(&key);
-        self.last_access.insert(key.clone(), SystemTime::now());
-        self.store.insert(key, value);
+        if self.expired(&key) {
+            self.remove_expired(&key);
+        }
+        self.delete(&key);
+        let access = if self.track_access {
+            Some(Access::new(self.clock.now()))
+        } else {
+            None
+        };
+        self.insert_entry(
+            key,
+            Entry {
+                value: Arc::new(value),
+                expires_at: None,
+                access,
+                index: 0,
+                volatile_index: None,
+                size: 0,
+                resized: false,
+            },
+        );
+    }
+
+    /// Agrega la entrada de una clave que no existe, registrándola en el vector de claves y, si
+    /// tiene expiración, en el de claves con expiración.
+    fn insert_entry(&mut self, key: K, mut entry: Entry<V>) {
+        let volatile = entry.expires_at.is_some();
+        entry.index = self.keys.len();
+        entry.volatile_index = None;
+        entry.size = entry.value.memory_usage(key.as_ref());
+        entry.resized = false;
+        self.used_memory += entry.size;
+        self.keys.push(key.clone());
+        self.store.insert(key.clone(), entry);
+        if volatile {
+            self.index_volatile(&key);
+        }
     }
 
     /// Devuelve si una clave existe o no, chequeando que no haya expirado. Si expiró, la borra.
     pub fn contains_key(&mut self, key: &K) -> bool {
-        match self.store.get(key) {
-            Some(_value) => {
-                if self.expired(key) {
-                    self.remove(key);
-                    return false;
-                }
-                true
-            }
-            None => false,
-        }
+        self.valid_entry(key).is_some()
     }
 
     /// Elimina el par clave-valor.
-    /// Devuelve el valor, y si no existía la clave, devuelve None.
+    /// Devuelve el valor, y si no existía la clave, devuelve None. Si el valor es compartido con un
+    /// snapshot, se devuelve una copia; si no se necesita el valor, usar `delete`.
     pub fn remove(&mut self, key: &K) -> Option<V> {
-        self.ttls.remove(key);
-        self.last_access.remove(key);
-        self.store.remove(key)
+        let entry = self.remove_entry(key)?;
+        Some(Arc::try_unwrap(entry.value).unwrap_or_else(|value| (*value).clone()))
+    }
+
+    /// Elimina el par clave-valor descartando el valor, sin copiarlo aunque sea compartido con un
+    /// snapshot. Devuelve false si no existía la clave.
+    pub fn delete(&mut self, key: &K) -> bool {
+        self.remove_entry(key).is_some()
+    }
+
+    /// Quita la entrada de la clave, actualizando el vector de claves y el de claves con
+    /// expiración.
+    fn remove_entry(&mut self, key: &K) -> Option<Entry<V>> {
+        let entry = self.store.remove(key)?;
+        self.used_memory -= entry.size;
+        if let Some(index) = entry.volatile_index {
+            self.unindex_volatile(index);
+        }
+        self.keys.swap_remove(entry.index);
+        if let Some(moved) = self.keys.get(entry.index) {
+            if let Some(moved) = self.store.get_mut(moved) {
+                moved.index = entry.index;
+            }
+        }
+        Some(entry)
+    }
+
+    /// Mueve el valor de la clave `from` a la clave `to`, junto con su expiración, su último acceso
+    /// y su frecuencia de acceso. Si `to` existía, su valor se descarta.
+    /// Devuelve false si no existe la clave `from` o expiró.
+    pub fn rename(&mut self, from: &K, to: K) -> bool {
+        if !self.contains_key(from) {
+            return false;
+        }
+        if *from == to {
+            return true;
+        }
+
+        self.delete(&to);
+        if let Some(entry) = self.remove_entry(from) {
+            self.insert_entry(to, entry);
+        }
+        true
+    }
+
+    /// Mueve el valor de la clave `from` a la clave `to` de otro mapa, junto con su expiración, su
+    /// último acceso y su frecuencia de acceso. Si `to` existía en el otro mapa, su valor se
+    /// descarta. Devuelve false si no existe la clave `from` o expiró.
+    pub fn move_to(&mut self, from: &K, other: &mut Self, to: K) -> bool {
+        if !self.contains_key(from) {
+            return false;
+        }
+        if other.expired(&to) {
+            other.remove_expired(&to);
+        }
+        other.delete(&to);
+        if let Some(entry) = self.remove_entry(from) {
+            other.insert_entry(to, entry);
+        }
+        true
+    }
+
+    /// Elimina una clave que expiró, registrándola entre las claves expiradas. Devuelve false si
+    /// la clave ya no existía.
+    fn remove_expired(&mut self, key: &K) -> bool {
+        let removed = self.delete(key);
+        if removed {
+            self.expired_keys.push(key.clone());
+        }
+        removed
+    }
+
+    /// Indica si hay alguna clave con expiración.
+    pub fn has_volatile_keys(&self) -> bool {
+        !self.volatile.is_empty()
+    }
+
+    /// Devuelve la cantidad de claves con expiración, sin chequear que no hayan expirado.
+    pub fn volatile_len(&self) -> usize {
+        self.volatile.len()
+    }
+
+    /// Revisa hasta `samples` claves con expiración elegidas al azar, y elimina las que expiraron.
+    /// Devuelve la cantidad de claves eliminadas.
+    pub fn expire_sample(&mut self, samples: usize) -> usize {
+        let now = self.clock.now();
+        let expired: Vec<K> = Self::sample(&self.volatile, samples)
+            .filter(|key| self.store[*key].expired(now))
+            .cloned()
+            .collect();
+        expired
+            .iter()
+            .filter(|key| self.remove_expired(key))
+            .count()
+    }
+
+    /// Elimina todas las claves que expiraron. Devuelve la cantidad de claves eliminadas.
+    pub fn remove_all_expired(&mut self) -> usize {
+        let now = self.clock.now();
+        let expired: Vec<K> = self
+            .volatile
+            .iter()
+            .filter(|key| self.store[*key].expired(now))
+            .cloned()
+            .collect();
+        expired
+            .iter()
+            .filter(|key| self.remove_expired(key))
+            .count()
+    }
+
+    /// Devuelve el tiempo transcurrido desde el último acceso a la clave, sin registrar un acceso.
+    /// Devuelve None si la clave nunca fue accedida.
+    pub fn idle_time(&self, key: &K) -> Option<Duration> {
+        let access = self.store.get(key)?.access?;
+        Some(
+            self.clock
+                .now()
+                .duration_since(access.last)
+                .unwrap_or_else(|_| Duration::from_secs(0)),
+        )
+    }
+
+    /// Devuelve las claves eliminadas por haber expirado desde la última llamada.
+    pub fn take_expired_keys(&mut self) -> Vec<K> {
+        std::mem::take(&mut self.expired_keys)
     }
 
     /// Devuelve el valor correspondiente a la clave. Si expiró, la borra y devuelve None.
     pub fn get(&mut self, key: &K) -> Option<&V> {
-        if self.expired(key) {
-            self.remove(key);
-            return None;
+        let now = self.clock.now();
+        let entry = self.valid_entry(key)?;
+        Self::touch(entry, now);
+        Some(&*entry.value)
+    }
+
+    /// Devuelve el valor correspondiente a la clave sin registrar un acceso. Si expiró, la borra y
+    /// devuelve None.
+    pub fn peek(&mut self, key: &K) -> Option<&V> {
+        self.valid_entry(key).map(|entry| &*entry.value)
+    }
+
+    /// Devuelve el valor correspondiente a la clave sin modificar el mapa, por lo que puede usarse
+    /// con una referencia compartida. No registra un acceso.
+    /// Devuelve Some(None) si no existe la clave, y None si expiró pero todavía no fue borrada.
+    pub fn get_shared(&self, key: &K) -> Option<Option<&V>> {
+        match self.store.get(key) {
+            Some(entry) if entry.expired(self.clock.now()) => None,
+            entry => Some(entry.map(|entry| &*entry.value)),
         }
-        self.update_last_access(key);
-        self.store.get(key)
+    }
+
+    /// Obtiene el tiempo restante de expiración de una clave sin modificar el mapa, con el mismo
+    /// formato que `get_ttl`. Devuelve None si la clave expiró pero todavía no fue borrada.
+    pub fn get_ttl_shared(&self, key: &K) -> Option<Option<Duration>> {
+        let now = self.clock.now();
+        let entry = match self.store.get(key) {
+            Some(entry) if entry.expired(now) => return None,
+            Some(entry) => entry,
+            None => return Some(None),
+        };
+        Some(Some(match entry.expires_at {
+            Some(value) => value
+                .duration_since(now)
+                .unwrap_or_else(|_| Duration::from_secs(0)),
+            None => Duration::from_secs(0),
+        }))
     }
 
     /// Devuelve una referencia mutable del valor correspondiente a la clave. Si expiró, la borra y devuelve None.
     pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        if self.expired(key) {
-            self.remove(key);
-            return None;
+        let now = self.clock.now();
+        self.valid_entry(key)?;
+        let entry = self.store.get_mut(key)?;
+        if !entry.resized {
+            entry.resized = true;
+            self.resized.push(key.clone());
         }
-        self.update_last_access(key);
-        self.store.get_mut(key)
+        Self::touch(entry, now);
+        Some(Arc::make_mut(&mut entry.value))
     }
 
-    /// Devuelve todas las claves, sin chequear que no hayan expirado.
-    pub fn keys(&self) -> Keys<K, V> {
-        self.store.keys()
+    /// Devuelve la memoria que ocupan las claves con sus valores, sin recorrerlas: sólo se vuelve
+    /// a estimar el tamaño de los valores que pudieron modificarse desde la última consulta.
+    pub fn used_memory(&mut self) -> usize {
+        for key in std::mem::take(&mut self.resized) {
+            if let Some(entry) = self.store.get_mut(&key) {
+                if entry.resized {
+                    let size = entry.value.memory_usage(key.as_ref());
+                    self.used_memory = self.used_memory - entry.size + size;
+                    entry.size = size;
+                    entry.resized = false;
+                }
+            }
+        }
+        self.used_memory
     }
 
-    fn set_size(&mut self, store_size: usize, ttl_size: usize) {
-        self.store.reserve(store_size);
-        self.ttls.reserve(ttl_size);
+    /// Devuelve la frecuencia de acceso a la clave, como el contador logarítmico de Redis, sin
+    /// registrar un nuevo acceso. Devuelve None si no existe la clave o expiró.
+    pub fn get_frequency(&mut self, key: &K) -> Option<u8> {
+        let entry = self.valid_entry(key)?;
+        Some(entry.access.map_or(0, |access| access.frequency.value()))
     }
-}
-
-const OP_EOF: u8 = 0xff;
-const OP_EXPIRETIME: u8 = 0xfd;
-const OP_RESIZEDB: u8 = 0xfb;
-const WRONG_ELEMENT_TYPE: u8 = 3;
 
-impl TtlHashMap<String, RedisElement> {
-    /// Devuelve un vector de bytes con el TtlHashMap serializado según el estandar de REDIS.
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut s: Vec<u8> = vec![OP_RESIZEDB];
-        s.append(&mut TtlHashMap::length_encode(self.store.len()));
-        s.append(&mut TtlHashMap::length_encode(self.ttls.len()));
-
-        for (key, value) in self.store.iter() {
-            if let Some(ttl) = self.ttls.get(key) {
-                let secs = ttl
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_else(|_| Duration::from_secs(0))
-                    .as_secs();
-                s.push(OP_EXPIRETIME);
-                s.append(&mut (secs as u32).to_be_bytes().to_vec());
-            }
-            let value_type = TtlHashMap::value_type_encode(value);
-            if value_type != WRONG_ELEMENT_TYPE {
-                s.push(value_type);
-                s.append(&mut TtlHashMap::string_encode(key.to_string()));
-                s.append(&mut TtlHashMap::value_encode(value.clone()));
+    /// Devuelve una clave al azar que no haya expirado, o None si no hay ninguna. Las claves
+    /// expiradas que se encuentren en el camino se borran.
+    pub fn random_key(&mut self) -> Option<K> {
+        loop {
+            let key = Self::sample(&self.keys, 1).next()?.clone();
+            if !self.expired(&key) {
+                return Some(key);
             }
+            self.remove_expired(&key);
         }
-        s.push(OP_EOF);
-        s
     }
 
-    // Deserializa un vector de bytes para devolver un TtlHashMap cargado con todos los RedisElements.
-    pub fn deserialize(mut s: Vec<u8>) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
-        let mut s = s.drain(..);
-
-        match s.next().unwrap_or(0) {
-            OP_RESIZEDB => {
-                map.set_size(
-                    TtlHashMap::length_decode(&mut s).ok_or("Corrupt store size")? as usize,
-                    TtlHashMap::length_decode(&mut s).ok_or("Corrupt ttl size")? as usize,
-                );
-                map.load(&mut s)?;
-                Ok(map)
+    /// Elige hasta `samples` claves al azar de `keys`: si no hay más de `samples` claves se
+    /// devuelven todas, y si no se devuelven `samples` claves elegidas en tiempo constante, que
+    /// pueden repetirse.
+    fn sample(keys: &[K], samples: usize) -> impl Iterator<Item = &K> {
+        let random = keys.len() > samples;
+        (0..samples.min(keys.len())).map(move |index| {
+            if random {
+                &keys[random_index(keys.len())]
+            } else {
+                &keys[index]
             }
-            OP_EOF => Ok(map),
-            _ => Err("Found unknown OP code.".into()),
-        }
+        })
     }
 
-    fn load(&mut self, s: &mut Drain<'_, u8>) -> Result<(), Box<dyn std::error::Error>> {
-        while let Some(op_code) = s.next() {
-            match op_code {
-                OP_EXPIRETIME => {
-                    let secs = TtlHashMap::read_int(s).ok_or("Corrupt expiry time")?;
-                    let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64);
+    /// Elige la clave a descartar con las políticas LFU de `maxmemory-policy`: entre hasta
+    /// `samples` claves elegidas al azar, devuelve la accedida con menor frecuencia. Con
+    /// `volatile_only` sólo se consideran las claves con expiración. Si no se registran los
+    /// accesos, todas las claves tienen la misma frecuencia. Las claves expiradas que se
+    /// encuentren en la muestra se borran. Devuelve None si no hay ninguna clave candidata.
+    pub fn lfu_candidate(&mut self, samples: usize, volatile_only: bool) -> Option<K> {
+        loop {
+            let now = self.clock.now();
+            let candidates = if volatile_only {
+                &self.volatile
+            } else {
+                &self.keys
+            };
+            let (expired, valid): (Vec<&K>, Vec<&K>) =
+                Self::sample(candidates, samples).partition(|key| self.store[*key].expired(now));
+            let candidate = valid.into_iter().min_by_key(|key| {
+                self.store[*key]
+                    .access
+                    .map_or(0, |access| access.frequency.value())
+            });
+            if let Some(candidate) = candidate {
+                return Some(candidate.clone());
+            }
 
-                    if SystemTime::now().duration_since(ttl).is_err() {
-                        let value_type = s.next().ok_or("Corrupt value type")?;
-                        let key = TtlHashMap::string_decode(s).ok_or("Corrupt key")?;
-                        let value =
-                            TtlHashMap::value_decode(s, value_type).ok_or("Corrupt value")?;
-                        self.insert(key.clone(), value);
-                        self.set_ttl_absolute(key, ttl);
-                    }
-                }
-                OP_EOF => (),
-                _ => {
-                    let value_type = op_code;
-                    let key = TtlHashMap::string_decode(s).ok_or("Corrupt key")?;
-                    let value = TtlHashMap::value_decode(s, value_type).ok_or("Corrupt value")?;
-                    self.insert(key, value);
-                }
+            // Si toda la muestra expiró, se borra y se vuelve a intentar.
+            let expired: Vec<K> = expired.into_iter().cloned().collect();
+            if expired.is_empty() {
+                return None;
+            }
+            for key in &expired {
+                self.remove_expired(key);
             }
         }
-        Ok(())
     }
 
-    fn bytes_as_u32_be(bytes: &[u8]) -> u32 {
-        ((bytes[0] as u32) << 24)
-            | ((bytes[1] as u32) << 16)
-            | ((bytes[2] as u32) << 8)
-            | (bytes[3] as u32)
+    /// Devuelve todas las claves, sin chequear que no hayan expirado.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.store.keys()
     }
 
-    fn read_int(s: &mut Drain<'_, u8>) -> Option<u32> {
-        Some(TtlHashMap::bytes_as_u32_be(&[
-            s.next()?,
-            s.next()?,
-            s.next()?,
-            s.next()?,
-        ]))
+    /// Devuelve los pares clave-valor que no expiraron, sin modificar el mapa, por lo que puede
+    /// usarse con una referencia compartida. No registra accesos.
+    pub fn iter_valid(&self) -> impl Iterator<Item = (&K, &V)> {
+        let now = self.clock.now();
+        self.store
+            .iter()
+            .filter(move |(_, entry)| !entry.expired(now))
+            .map(|(key, entry)| (key, &*entry.value))
     }
 
-    fn string_decode(s: &mut Drain<'_, u8>) -> Option<String> {
-        let mut bytes: Vec<u8> = Vec::new();
-        let len = TtlHashMap::length_decode(s)?;
-        for _ in 0..len {
-            bytes.push(s.next()?);
-        }
-        Some(from_utf8(&bytes).ok()?.to_string())
+    #[allow(dead_code)]
+    /// Devuelve las claves que no expiraron, sin modificar el mapa.
+    pub fn keys_valid(&self) -> impl Iterator<Item = &K> {
+        self.iter_valid().map(|(key, _)| key)
     }
 
-    pub fn string_encode(string: String) -> Vec<u8> {
-        let mut bytes: Vec<u8> = vec![];
-        bytes.append(&mut TtlHashMap::length_encode(string.len()));
-        bytes.append(&mut string.as_bytes().to_vec());
-        bytes
+    /// Elimina las claves que expiraron, registrándolas entre las claves expiradas, y devuelve las
+    /// claves restantes.
+    pub fn keys_purged(&mut self) -> impl Iterator<Item = &K> {
+        self.remove_all_expired();
+        self.store.keys()
     }
 
-    pub fn list_encode(list: Vec<String>) -> Vec<u8> {
-        let mut bytes = TtlHashMap::length_encode(list.len());
-        for value in list {
-            bytes.append(&mut TtlHashMap::string_encode(value));
+    /// Devuelve una copia de las claves, sus valores y sus ttls, que puede serializarse en otro
+    /// hilo sin bloquear el acceso a la base de datos. Los valores no se copian sino que se
+    /// comparten con el mapa original, y cada uno se copia recién cuando se modifica en el
+    /// original mientras la copia siga existiendo, por lo que tomarla no depende del tamaño de los
+    /// valores.
+    pub fn snapshot(&self) -> Self {
+        let store = self
+            .store
+            .iter()
+            .map(|(key, entry)| {
+                let entry = Entry {
+                    value: Arc::clone(&entry.value),
+                    expires_at: entry.expires_at,
+                    access: None,
+                    index: entry.index,
+                    volatile_index: entry.volatile_index,
+                    size: entry.size,
+                    resized: entry.resized,
+                };
+                (key.clone(), entry)
+            })
+            .collect();
+        TtlHashMap {
+            store,
+            keys: self.keys.clone(),
+            volatile: self.volatile.clone(),
+            track_access: false,
+            expired_keys: Vec::new(),
+            used_memory: self.used_memory,
+            resized: self.resized.clone(),
+            clock: self.clock(),
         }
-        bytes
     }
 
-    fn list_decode(s: &mut Drain<'_, u8>) -> Option<Vec<String>> {
-        let len = TtlHashMap::length_decode(s)?;
-        let mut vec: Vec<String> = vec![];
-        for _ in 0..len {
-            vec.push(TtlHashMap::string_decode(s)?);
-        }
-        Some(vec)
+    fn set_size(&mut self, size: usize) {
+        self.store.reserve(size);
+        self.keys.reserve(size);
     }
+}
 
-    pub fn length_encode(length: usize) -> Vec<u8> {
-        if length < 64 {
-            // 00 + length in 6 bits
-            vec![length as u8]
-        } else if length < 16384 {
-            // 01 + length in 14 bits
-            vec![0x40 | (length >> 8) as u8, length as u8]
-        } else {
-            // 1000 0000 + length in 32 bits
-            vec![
-                0x80,
-                (length >> 24) as u8,
-                (length >> 16) as u8,
-                (length >> 8) as u8,
-                length as u8,
-            ]
-        }
+impl<V: Clone + Serializable + MemoryUsage> TtlHashMap<String, V> {
+    /// Devuelve un vector de bytes con el TtlHashMap serializado con el formato RDB de Redis, que
+    /// puede cargarse en un servidor de Redis. Las claves expiradas no se persisten, y sólo se
+    /// escriben los ttls de las claves persistidas.
+    #[allow(dead_code)]
+    pub fn serialize(&self) -> Vec<u8> {
+        Self::serialize_all(&[self])
     }
 
-    fn length_decode(s: &mut Drain<'_, u8>) -> Option<u32> {
-        let first_byte = s.next().unwrap_or(5);
-        match first_byte >> 6 {
-            0b00 => Some(first_byte as u32),
-            0b01 => Some(TtlHashMap::bytes_as_u32_be(&[
-                0,
-                0,
-                first_byte & 0b00111111,
-                s.next()?,
-            ])),
-            0b10 => Some(TtlHashMap::read_int(s)?),
-            _ => None,
+    /// Serializa las claves de todos los mapas indicados como una única base de datos, con el
+    /// mismo formato que `serialize`. Los mapas no deben compartir claves. La hora actual se toma
+    /// del reloj del primero.
+    pub fn serialize_all(maps: &[&Self]) -> Vec<u8> {
+        let mut s: Vec<u8> = vec![];
+        rdb::write_header(&mut s);
+        rdb::write_aux(&mut s, "redis-bits", "64");
+        let now = maps
+            .first()
+            .map_or_else(SystemTime::now, |map| map.clock.now());
+        let ctime = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0))
+            .as_secs();
+        rdb::write_aux(&mut s, "ctime", &ctime.to_string());
+
+        s.push(rdb::OP_SELECTDB);
+        rdb::write_length(&mut s, 0);
+
+        // El ttl de cada clave se lee una única vez, para que la cantidad de claves con expiración
+        // coincida con las escritas aunque alguna expire durante la serialización.
+        let entries: Vec<(&String, &V, u8, Option<SystemTime>)> = maps
+            .iter()
+            .flat_map(|map| map.store.iter())
+            .filter(|(_, entry)| !entry.expired(now))
+            .filter_map(|(key, entry)| {
+                let value = &*entry.value;
+                Some((key, value, value.rdb_type()?, entry.expires_at))
+            })
+            .collect();
+        let expires = entries
+            .iter()
+            .filter(|(_, _, _, ttl)| ttl.is_some())
+            .count();
+        s.push(rdb::OP_RESIZEDB);
+        rdb::write_length(&mut s, entries.len() as u64);
+        rdb::write_length(&mut s, expires as u64);
+
+        for (key, value, value_type, ttl) in entries {
+            if let Some(ttl) = ttl {
+                s.push(rdb::OP_EXPIRETIME_MS);
+                rdb::write_time(&mut s, ttl);
+            }
+            s.push(value_type);
+            rdb::write_string(&mut s, key.as_bytes());
+            value.write_rdb(&mut s);
         }
-    }
 
-    pub fn value_encode(value: RedisElement) -> Vec<u8> {
-        match value {
-            RedisElement::String(string) => TtlHashMap::string_encode(string),
-            RedisElement::List(list) => TtlHashMap::list_encode(list),
-            RedisElement::Set(set) => TtlHashMap::list_encode(set.into_iter().collect()),
-            _ => vec![],
-        }
+        s.push(rdb::OP_EOF);
+        let checksum = crc64(0, &s);
+        s.extend_from_slice(&checksum.to_le_bytes());
+        s
     }
 
-    fn value_decode(s: &mut Drain<'_, u8>, value_type: u8) -> Option<RedisElement> {
-        match value_type {
-            0 => Some(RedisElement::String(TtlHashMap::string_decode(s)?)),
-            1 => Some(RedisElement::List(TtlHashMap::list_decode(s)?)),
-            2 => Some(RedisElement::Set(
-                TtlHashMap::list_decode(s)?.into_iter().collect(),
-            )),
-            _ => None,
-        }
+    /// Deserializa un archivo con el formato RDB de Redis para devolver un TtlHashMap cargado con
+    /// todos los valores de la base 0. Las claves expiradas se descartan.
+    ///
+    /// Retorna error si el archivo está truncado o si su checksum no corresponde a su contenido,
+    /// sin cargar ninguna clave.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::deserialize_with_clock(bytes, Arc::new(SystemClock))
     }
 
-    pub fn value_type_encode(value: &RedisElement) -> u8 {
-        match value {
-            RedisElement::String(_) => 0,
-            RedisElement::List(_) => 1,
-            RedisElement::Set(_) => 2,
-            _ => WRONG_ELEMENT_TYPE,
+    /// Igual que `deserialize`, pero el TtlHashMap devuelto toma la hora actual de `clock`, que
+    /// también se usa para descartar las claves expiradas.
+    pub fn deserialize_with_clock(
+        bytes: &[u8],
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut map: TtlHashMap<String, V> = TtlHashMap::with_clock(clock);
+        let mut reader = RdbReader::new(bytes);
+        let version = reader.read_header()?;
+        let mut db = 0;
+        let mut ttl: Option<SystemTime> = None;
+
+        loop {
+            match reader.read_u8()? {
+                rdb::OP_EOF => {
+                    if version >= 5 {
+                        reader.verify_checksum()?;
+                    }
+                    return Ok(map);
+                }
+                rdb::OP_AUX => {
+                    reader.read_string()?;
+                    reader.read_string()?;
+                }
+                rdb::OP_SELECTDB => db = reader.read_length()?,
+                rdb::OP_RESIZEDB => {
+                    let store_size = reader.read_len()?;
+                    reader.read_len()?;
+                    if db == 0 {
+                        map.set_size(store_size);
+                    }
+                }
+                rdb::OP_EXPIRETIME_MS => ttl = Some(reader.read_time()?),
+                rdb::OP_EXPIRETIME => {
+                    let secs = reader.read_u32_le()?;
+                    ttl = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64));
+                }
+                rdb::OP_IDLE => {
+                    reader.read_length()?;
+                }
+                rdb::OP_FREQ => {
+                    reader.read_u8()?;
+                }
+                rdb::OP_SLOT_INFO => {
+                    for _ in 0..3 {
+                        reader.read_length()?;
+                    }
+                }
+                rdb::OP_MODULE_AUX | rdb::OP_FUNCTION | rdb::OP_FUNCTION2 => {
+                    return Err(reader
+                        .error("Modules and functions are not supported")
+                        .into());
+                }
+                value_type => {
+                    let key = reader.read_utf8_string()?;
+                    let value = V::read_rdb(&mut reader, value_type)?;
+                    let ttl = ttl.take();
+                    let expired = ttl.is_some_and(|ttl| ttl <= map.clock.now());
+                    if db == 0 && !expired {
+                        map.insert(key.clone(), value);
+                        if let Some(ttl) = ttl {
+                            map.set_ttl_absolute(key, ttl);
+                        }
+                    }
+                }
+            }
         }
     }
 }
 
 #[allow(unused_imports)]
 mod test {
-    use crate::entities::ttl_hash_map::RedisElement;
+    use crate::entities::clock::MockClock;
+    use crate::entities::crc64::crc64;
+    use crate::entities::expire_condition::ExpireCondition;
+    use crate::entities::memory::{memory_usage, MemoryUsage, DEFAULT_SAMPLES};
+    use crate::entities::rdb;
+    use crate::entities::rdb::{RdbReader, Serializable};
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::stream::{Stream, StreamId};
     use crate::entities::ttl_hash_map::TtlHashMap;
+    use std::sync::Arc;
     use std::time::{Duration, SystemTime};
 
+    impl MemoryUsage for u8 {
+        fn memory_usage(&self, key: &str) -> usize {
+            key.len() + 1
+        }
+    }
+
+    impl MemoryUsage for String {
+        fn memory_usage(&self, key: &str) -> usize {
+            key.len() + self.len()
+        }
+    }
+
     #[test]
     fn test_get_key() {
         let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
@@ -372,16 +838,20 @@ mod test {
         assert_eq!(map.get(&key).unwrap(), &2);
     }
 
-    #[ignore]
     #[test]
     fn test_ttl_relative_deletes_key() {
-        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::with_clock(clock.clone());
         let key = "key".to_string();
 
         map.insert(key.clone(), 1);
         map.set_ttl_relative(key.clone(), Duration::from_secs(1));
 
-        std::thread::sleep(Duration::from_secs(1));
+        clock.advance(Duration::from_millis(999));
+        assert!(map.get(&key).is_some());
+        assert_eq!(Some(Duration::from_millis(1)), map.get_ttl(&key));
+
+        clock.advance(Duration::from_millis(1));
 
         assert!(map.get(&key).is_none());
     }
@@ -408,6 +878,21 @@ mod test {
         assert!(!map.contains_key(&key));
     }
 
+    #[test]
+    fn test_take_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        map.insert("expired".to_string(), 1);
+        map.insert("removed".to_string(), 2);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::now());
+
+        map.remove(&"removed".to_string());
+        assert!(map.get(&"expired".to_string()).is_none());
+        assert!(map.get(&"expired".to_string()).is_none());
+
+        assert_eq!(vec!["expired".to_string()], map.take_expired_keys());
+        assert!(map.take_expired_keys().is_empty());
+    }
+
     #[test]
     fn test_delete_ttl_on_expired_key() {
         let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
@@ -444,118 +929,457 @@ mod test {
         assert!(map.get(&key).is_some());
     }
 
-    #[ignore]
     #[test]
     fn test_new_key_last_access() {
-        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::with_clock(clock.clone());
+        map.set_access_tracking(true);
         let key = "key".to_string();
 
         map.insert(key.clone(), 1);
-        std::thread::sleep(Duration::from_secs(1));
+        clock.advance(Duration::from_secs(1));
 
         assert_eq!(map.update_last_access(&key).unwrap().as_secs(), 1);
     }
 
-    #[ignore]
     #[test]
     fn test_get_changes_last_access() {
-        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::with_clock(clock.clone());
+        map.set_access_tracking(true);
         let key = "key".to_string();
 
         map.insert(key.clone(), 1);
-        std::thread::sleep(Duration::from_secs(1));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(Some(Duration::from_secs(1)), map.idle_time(&key));
         map.get(&key);
 
         assert_eq!(map.update_last_access(&key).unwrap().as_secs(), 0);
     }
 
     #[test]
-    fn test_remove_key_and_add_again() {
+    fn test_access_tracking_is_opt_in() {
         let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
         let key = "key".to_string();
+        map.insert(key.clone(), 1);
+        map.get(&key);
 
-        assert!(map.remove(&key).is_none());
+        assert!(!map.tracks_access());
+        assert!(map.store.values().all(|entry| entry.access.is_none()));
+        assert_eq!(None, map.idle_time(&key));
+        assert_eq!(Some(Duration::from_secs(0)), map.update_last_access(&key));
+
+        map.set_access_tracking(true);
+        assert_eq!(Some(5), map.get_frequency(&key));
+        assert!(map.idle_time(&key).is_some());
+
+        map.set_access_tracking(false);
+        assert!(map.store.values().all(|entry| entry.access.is_none()));
+    }
+
+    #[test]
+    fn test_get_increments_frequency() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        map.set_access_tracking(true);
+        let key = "key".to_string();
+
+        map.insert(key.clone(), 1);
+        assert_eq!(Some(5), map.get_frequency(&key));
+
+        map.get(&key);
+        assert_eq!(Some(6), map.get_frequency(&key));
+        map.peek(&key);
+        assert_eq!(Some(6), map.get_frequency(&key));
+        assert_eq!(None, map.get_frequency(&"other".to_string()));
     }
 
     #[test]
-    fn test_length_encode_decode() {
-        let number: u32 = 15;
-        let mut encoded: Vec<u8> = TtlHashMap::length_encode(number as usize);
-        let decoded: u32 = TtlHashMap::length_decode(&mut encoded.drain(..)).unwrap();
-        assert_eq!(number, decoded);
+    fn test_lfu_candidate_is_the_least_frequently_accessed_key() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        map.set_access_tracking(true);
+        assert_eq!(None, map.lfu_candidate(5, false));
+
+        map.insert("rare".to_string(), 1);
+        map.insert("frequent".to_string(), 2);
+        map.get(&"frequent".to_string());
+        assert_eq!(Some("rare".to_string()), map.lfu_candidate(5, false));
+
+        // Con `volatile_only` sólo se eligen claves con expiración.
+        assert_eq!(None, map.lfu_candidate(5, true));
+        map.set_ttl_relative("frequent".to_string(), Duration::from_secs(60));
+        assert_eq!(Some("frequent".to_string()), map.lfu_candidate(5, true));
     }
 
     #[test]
-    fn test_value_encode_decode() {
-        let value = RedisElement::String("value".to_string());
-        let mut encoded = TtlHashMap::value_encode(value.clone());
-        let decoded = TtlHashMap::value_decode(
-            &mut encoded.drain(..),
-            TtlHashMap::value_type_encode(&value),
+    fn test_lfu_candidate_skips_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        for i in 0..100 {
+            map.insert(i.to_string(), 1);
+            map.set_ttl_absolute(i.to_string(), SystemTime::UNIX_EPOCH);
+        }
+        assert_eq!(None, map.lfu_candidate(5, true));
+        assert_eq!(0, map.len());
+
+        for i in 0..100 {
+            map.insert(i.to_string(), 1);
+            map.set_ttl_absolute(i.to_string(), SystemTime::UNIX_EPOCH);
+        }
+        map.insert("key".to_string(), 1);
+        assert_eq!(Some("key".to_string()), map.lfu_candidate(5, false));
+    }
+
+    #[test]
+    fn test_random_key_skips_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        assert_eq!(None, map.random_key());
+
+        map.insert("expired".to_string(), 1);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::UNIX_EPOCH);
+        assert_eq!(None, map.random_key());
+        assert_eq!(vec!["expired".to_string()], map.take_expired_keys());
+
+        map.insert("key".to_string(), 1);
+        assert_eq!(Some("key".to_string()), map.random_key());
+    }
+
+    #[test]
+    fn test_random_key_after_removals_and_renames() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        for i in 0..10 {
+            map.insert(i.to_string(), i);
+        }
+        map.delete(&"0".to_string());
+        map.remove(&"5".to_string());
+        map.rename(&"9".to_string(), "renamed".to_string());
+        map.insert("3".to_string(), 30);
+
+        // Cada clave del vector apunta a su propia posición.
+        assert_eq!(map.store.len(), map.keys.len());
+        for (index, key) in map.keys.iter().enumerate() {
+            assert_eq!(index, map.store[key].index);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..1000 {
+            let key = map.random_key().unwrap();
+            assert!(map.contains_key(&key));
+            seen.insert(key);
+        }
+        assert_eq!(8, seen.len());
+    }
+
+    #[test]
+    fn test_expire_sample_removes_only_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        assert_eq!(0, map.expire_sample(20));
+
+        map.insert("expired".to_string(), 1);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::UNIX_EPOCH);
+        map.insert("volatile".to_string(), 1);
+        map.set_ttl_relative("volatile".to_string(), Duration::from_secs(100));
+        map.insert("persistent".to_string(), 1);
+
+        assert_eq!(1, map.expire_sample(20));
+        assert_eq!(vec!["expired".to_string()], map.take_expired_keys());
+        assert_eq!(0, map.expire_sample(20));
+        assert!(map.contains_key(&"volatile".to_string()));
+        assert!(map.contains_key(&"persistent".to_string()));
+
+        for i in 0..30 {
+            map.insert(i.to_string(), 1);
+            map.set_ttl_absolute(i.to_string(), SystemTime::UNIX_EPOCH);
+        }
+        assert_eq!(30, map.remove_all_expired());
+        assert_eq!(2, map.len());
+    }
+
+    #[test]
+    fn test_expire_sample_only_samples_volatile_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        for i in 0..1000 {
+            map.insert(i.to_string(), 1);
+        }
+        assert!(!map.has_volatile_keys());
+
+        map.set_ttl_absolute("500".to_string(), SystemTime::UNIX_EPOCH);
+        assert!(map.has_volatile_keys());
+        assert_eq!(1, map.expire_sample(20));
+        assert_eq!(vec!["500".to_string()], map.take_expired_keys());
+        assert!(!map.has_volatile_keys());
+        assert_eq!(999, map.len());
+    }
+
+    #[test]
+    fn test_used_memory_follows_every_change() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        let exact = |map: &TtlHashMap<String, RedisElement>| -> usize {
+            map.iter_valid()
+                .map(|(key, value)| memory_usage(key, value, DEFAULT_SAMPLES))
+                .sum()
+        };
+        assert_eq!(0, map.used_memory());
+
+        map.insert("string".to_string(), RedisElement::String("a".repeat(100)));
+        map.insert(
+            "list".to_string(),
+            RedisElement::List(vec!["a".to_string()]),
         );
-        assert_eq!(value, decoded.unwrap());
+        assert_eq!(exact(&map), map.used_memory());
+
+        if let Some(RedisElement::List(list)) = map.get_mut(&"list".to_string()) {
+            list.extend(vec!["b".repeat(1000); 10]);
+        }
+        assert_eq!(exact(&map), map.used_memory());
+
+        map.rename(&"list".to_string(), "a much longer key".to_string());
+        map.insert("string".to_string(), RedisElement::String("a".to_string()));
+        assert_eq!(exact(&map), map.used_memory());
+
+        map.insert("expired".to_string(), RedisElement::String("a".to_string()));
+        map.set_ttl_absolute("expired".to_string(), SystemTime::UNIX_EPOCH);
+        map.remove_all_expired();
+        map.delete(&"string".to_string());
+        assert_eq!(exact(&map), map.used_memory());
+
+        map.delete(&"a much longer key".to_string());
+        assert_eq!(0, map.used_memory());
+    }
+
+    #[test]
+    fn test_set_ttl_if_conditions() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let key = "key".to_string();
+        let soon = SystemTime::now() + Duration::from_secs(60);
+        let later = soon + Duration::from_secs(60);
+        map.insert(key.clone(), 1);
+
+        assert!(!map.set_ttl_if(key.clone(), soon, &[ExpireCondition::HasExpiry]));
+        assert!(!map.set_ttl_if(key.clone(), soon, &[ExpireCondition::Greater]));
+        assert!(map.set_ttl_if(key.clone(), later, &[ExpireCondition::NoExpiry]));
+        assert!(!map.set_ttl_if(key.clone(), soon, &[ExpireCondition::NoExpiry]));
+        assert!(!map.set_ttl_if(key.clone(), soon, &[ExpireCondition::Greater]));
+        assert!(map.set_ttl_if(
+            key.clone(),
+            soon,
+            &[ExpireCondition::HasExpiry, ExpireCondition::Less]
+        ));
+        assert_eq!(Some(Some(soon)), map.get_expiration(&key));
+        assert!(!map.set_ttl_if("other".to_string(), soon, &[]));
+    }
+
+    #[test]
+    fn test_shared_reads_do_not_remove_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let key = "key".to_string();
+        map.insert(key.clone(), 1);
+
+        assert_eq!(Some(Some(&1)), map.get_shared(&key));
+        assert_eq!(Some(Some(Duration::from_secs(0))), map.get_ttl_shared(&key));
+        assert_eq!(Some(None), map.get_shared(&"other".to_string()));
+        assert_eq!(Some(None), map.get_ttl_shared(&"other".to_string()));
+
+        map.set_ttl_absolute(key.clone(), SystemTime::UNIX_EPOCH);
+        assert_eq!(None, map.get_shared(&key));
+        assert_eq!(None, map.get_ttl_shared(&key));
+        assert_eq!(1, map.len());
+        assert!(map.take_expired_keys().is_empty());
+    }
+
+    #[test]
+    fn test_valid_iterators_skip_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        map.insert("valid".to_string(), 1);
+        map.insert("expired".to_string(), 2);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::UNIX_EPOCH);
+
+        let entries: Vec<(&String, &u8)> = map.iter_valid().collect();
+        assert_eq!(vec![(&"valid".to_string(), &1)], entries);
+        let keys: Vec<&String> = map.keys_valid().collect();
+        assert_eq!(vec![&"valid".to_string()], keys);
+        assert_eq!(2, map.len());
+
+        let keys: Vec<&String> = map.keys_purged().collect();
+        assert_eq!(vec![&"valid".to_string()], keys);
+        assert_eq!(1, map.len());
+        assert_eq!(vec!["expired".to_string()], map.take_expired_keys());
+    }
+
+    #[test]
+    fn test_get_expiration() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        map.insert("key".to_string(), 1);
+
+        assert_eq!(Some(None), map.get_expiration(&"key".to_string()));
+        map.set_ttl_absolute("key".to_string(), ttl);
+        assert_eq!(Some(Some(ttl)), map.get_expiration(&"key".to_string()));
+        assert_eq!(None, map.get_expiration(&"other".to_string()));
+    }
+
+    #[test]
+    fn test_volatile_count_follows_expirations() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        for key in ["a", "b", "c"] {
+            map.insert(key.to_string(), 1);
+            map.set_ttl_absolute(key.to_string(), ttl);
+        }
+        map.set_ttl_absolute("a".to_string(), ttl);
+        assert_eq!(3, map.volatile.len());
+
+        map.delete_ttl(&"a".to_string());
+        map.remove(&"b".to_string());
+        assert_eq!(1, map.volatile.len());
+        map.rename(&"c".to_string(), "a".to_string());
+        assert_eq!(1, map.volatile.len());
+        map.insert("a".to_string(), 2);
+        assert_eq!(0, map.volatile.len());
+        assert_eq!(0, map.expire_sample(20));
+    }
+
+    #[test]
+    fn test_rename_keeps_ttl() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        map.insert("from".to_string(), 1);
+        map.insert("to".to_string(), 2);
+        map.set_ttl_absolute("from".to_string(), ttl);
+
+        assert!(map.rename(&"from".to_string(), "to".to_string()));
+
+        assert_eq!(None, map.get(&"from".to_string()));
+        assert_eq!(Some(&1), map.get(&"to".to_string()));
+        assert_eq!(Some(Some(ttl)), map.get_expiration(&"to".to_string()));
+        assert!(!map.rename(&"from".to_string(), "to".to_string()));
+    }
+
+    #[test]
+    fn test_move_to_keeps_ttl_and_memory() {
+        let mut from: TtlHashMap<String, u8> = TtlHashMap::new();
+        let mut to: TtlHashMap<String, u8> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        from.insert("from".to_string(), 1);
+        from.set_ttl_absolute("from".to_string(), ttl);
+        to.insert("to".to_string(), 2);
+
+        assert!(from.move_to(&"from".to_string(), &mut to, "to".to_string()));
+
+        assert_eq!(0, from.len());
+        assert_eq!(0, from.used_memory());
+        assert!(!from.has_volatile_keys());
+        assert_eq!(Some(&1), to.get(&"to".to_string()));
+        assert_eq!(Some(Some(ttl)), to.get_expiration(&"to".to_string()));
+        assert_eq!(1, to.volatile_len());
+        assert_eq!(3, to.used_memory());
+        assert!(!from.move_to(&"from".to_string(), &mut to, "to".to_string()));
+    }
+
+    #[test]
+    fn test_remove_key_and_add_again() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let key = "key".to_string();
+
+        assert!(map.remove(&key).is_none());
     }
 
     #[test]
     fn test_serialize() {
         let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
         let key = "key".to_string();
-        let value = RedisElement::String("value".to_string());
-        map.insert(key.clone(), value.clone());
-        map.set_ttl_relative(key.clone(), Duration::from_secs(2));
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_millis(4_102_444_800_123);
+        map.insert(key.clone(), RedisElement::String("value".to_string()));
+        map.set_ttl_absolute(key, ttl);
         let bytes = map.serialize();
 
-        let op_resizedb = 0xfb;
-        let mut store_len = TtlHashMap::length_encode(1);
-        let mut ttl_len = TtlHashMap::length_encode(1);
-        let op_expiretime = 0xfd;
-        let secs = (SystemTime::now() + Duration::from_secs(2))
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let mut ttl = (secs as u32).to_be_bytes().to_vec();
-        let byte_value_type = TtlHashMap::value_type_encode(&RedisElement::String("".to_string()));
-        let mut key_encoded = TtlHashMap::string_encode(key);
-        let mut value_encoded = TtlHashMap::string_encode("value".to_string());
-        let op_eof = 0xff;
-
-        let mut vec = vec![op_resizedb];
-        vec.append(&mut store_len);
-        vec.append(&mut ttl_len);
-        vec.push(op_expiretime);
-        vec.append(&mut ttl);
-        vec.push(byte_value_type);
-        vec.append(&mut key_encoded);
-        vec.append(&mut value_encoded);
-        vec.push(op_eof);
-        assert_eq!(bytes, vec);
+        assert_eq!(b"REDIS0009", &bytes[..9]);
+        let mut expected = vec![rdb::OP_SELECTDB, 0, rdb::OP_RESIZEDB, 1, 1];
+        expected.push(rdb::OP_EXPIRETIME_MS);
+        expected.extend_from_slice(&4_102_444_800_123u64.to_le_bytes());
+        expected.push(rdb::TYPE_STRING);
+        rdb::write_string(&mut expected, b"key");
+        rdb::write_string(&mut expected, b"value");
+        expected.push(rdb::OP_EOF);
+        assert!(bytes[..bytes.len() - 8].ends_with(&expected));
+        assert_eq!(
+            crc64(0, &bytes[..bytes.len() - 8]).to_le_bytes(),
+            bytes[bytes.len() - 8..]
+        );
     }
 
     #[test]
     fn test_deserialize() {
-        let op_resizedb = 0xfb;
-        let mut store_len = TtlHashMap::length_encode(1);
-        let mut ttl_len = TtlHashMap::length_encode(0);
-        let byte_value_type = TtlHashMap::value_type_encode(&RedisElement::String("".to_string()));
-        let key = "key".to_string();
-        let mut key_encoded = TtlHashMap::string_encode(key.clone());
-        let mut value_encoded = TtlHashMap::string_encode("value".to_string());
-        let op_eof = 0xff;
-
-        let mut bytes = vec![op_resizedb];
-        bytes.append(&mut store_len);
-        bytes.append(&mut ttl_len);
-        bytes.push(byte_value_type);
-        bytes.append(&mut key_encoded);
-        bytes.append(&mut value_encoded);
-        bytes.push(op_eof);
+        // Archivo con el formato que genera Redis 6 luego de `SET key value`, `RPUSH list a 1` y `SADD set 1 2`.
+        let mut bytes = b"REDIS0009".to_vec();
+        rdb::write_aux(&mut bytes, "redis-ver", "6.0.16");
+        bytes.extend_from_slice(&[rdb::OP_SELECTDB, 0, rdb::OP_RESIZEDB, 3, 0]);
+        bytes.push(rdb::TYPE_STRING);
+        rdb::write_string(&mut bytes, b"key");
+        rdb::write_string(&mut bytes, b"value");
+        bytes.push(rdb::TYPE_LIST_QUICKLIST);
+        rdb::write_string(&mut bytes, b"list");
+        bytes.push(1);
+        rdb::write_string(
+            &mut bytes,
+            &[
+                0x10, 0, 0, 0, 0x0d, 0, 0, 0, 2, 0, 0, 0x01, b'a', 3, 0xf2, 0xff,
+            ],
+        );
+        bytes.push(rdb::TYPE_SET_INTSET);
+        rdb::write_string(&mut bytes, b"set");
+        rdb::write_string(&mut bytes, &[2, 0, 0, 0, 2, 0, 0, 0, 1, 0, 2, 0]);
+        bytes.push(rdb::OP_EOF);
+        bytes.extend_from_slice(&[0; 8]);
 
-        let mut map = TtlHashMap::deserialize(bytes).unwrap();
+        let mut map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
 
         assert_eq!(
-            *map.get(&key).unwrap(),
+            *map.get(&"key".to_string()).unwrap(),
             RedisElement::String("value".to_string())
         );
+        assert_eq!(
+            *map.get(&"list".to_string()).unwrap(),
+            RedisElement::List(vec!["a".to_string(), "1".to_string()])
+        );
+        assert_eq!(
+            *map.get(&"set".to_string()).unwrap(),
+            RedisElement::Set(vec!["1".to_string(), "2".to_string()].into_iter().collect())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_skips_expired_keys_and_other_databases() {
+        let mut bytes = b"REDIS0009".to_vec();
+        bytes.extend_from_slice(&[rdb::OP_SELECTDB, 0, rdb::OP_EXPIRETIME_MS]);
+        bytes.extend_from_slice(&1000u64.to_le_bytes());
+        bytes.push(rdb::TYPE_STRING);
+        rdb::write_string(&mut bytes, b"expired");
+        rdb::write_string(&mut bytes, b"value");
+        bytes.extend_from_slice(&[rdb::OP_SELECTDB, 1, rdb::TYPE_STRING]);
+        rdb::write_string(&mut bytes, b"other");
+        rdb::write_string(&mut bytes, b"value");
+        bytes.push(rdb::OP_EOF);
+        bytes.extend_from_slice(&[0; 8]);
+
+        let map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
+
+        assert_eq!(0, map.len());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_unsupported_files() {
+        assert!(TtlHashMap::<String, RedisElement>::deserialize(b"REDIS0001\xff").is_ok());
+        assert!(TtlHashMap::<String, RedisElement>::deserialize(b"REDIS0013\xff").is_err());
+        assert!(TtlHashMap::<String, RedisElement>::deserialize(b"RDB").is_err());
+
+        let mut bytes = b"REDIS0009".to_vec();
+        bytes.push(rdb::TYPE_HASH);
+        rdb::write_string(&mut bytes, b"hash");
+        assert_eq!(
+            "Hash values are not supported at offset 15",
+            TtlHashMap::<String, RedisElement>::deserialize(&bytes)
+                .unwrap_err()
+                .to_string()
+        );
     }
 
     #[test]
@@ -566,7 +1390,7 @@ mod test {
         map.insert(key.clone(), value.clone());
 
         let bytes = map.serialize();
-        let mut new_map = TtlHashMap::deserialize(bytes).unwrap();
+        let mut new_map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
 
         assert_eq!(*new_map.get(&key).unwrap(), value);
     }
@@ -579,7 +1403,48 @@ mod test {
         map.insert(key.clone(), value.clone());
 
         let bytes = map.serialize();
-        let mut new_map = TtlHashMap::deserialize(bytes).unwrap();
+        let mut new_map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
+
+        assert_eq!(*new_map.get(&key).unwrap(), value);
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_key_value_sorted_set() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        let key = "key".to_string();
+        let value = RedisElement::SortedSet(
+            vec![("a", 1.5), ("b", -2.0), ("c", f64::INFINITY)]
+                .into_iter()
+                .collect(),
+        );
+        map.insert(key.clone(), value.clone());
+
+        let bytes = map.serialize();
+        let mut new_map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
+
+        assert_eq!(*new_map.get(&key).unwrap(), value);
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_key_value_stream() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        let key = "key".to_string();
+        let mut stream = Stream::new();
+        let fields = vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ];
+        stream.add(StreamId::new(1, 0), fields.clone()).unwrap();
+        stream.add(StreamId::new(5, 2), fields).unwrap();
+        stream.trim(1);
+        stream.create_group("group", Some(StreamId::MIN)).unwrap();
+        let delivery_time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_600_000_000_123);
+        stream.read_group("group", "consumer", None, None, false, delivery_time);
+        let value = RedisElement::Stream(stream);
+        map.insert(key.clone(), value.clone());
+
+        let bytes = map.serialize();
+        let mut new_map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
 
         assert_eq!(*new_map.get(&key).unwrap(), value);
     }
@@ -595,11 +1460,211 @@ mod test {
 
         let bytes = map.serialize();
 
-        let mut new_map = TtlHashMap::deserialize(bytes).unwrap();
+        let mut new_map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
         assert_eq!(new_map.get(&key).unwrap().to_string(), "value");
         assert_eq!(
             new_map.get_ttl(&key).unwrap().as_secs(),
             ttl.duration_since(SystemTime::now()).unwrap().as_secs()
         );
     }
+
+    #[test]
+    fn test_serialize_all_writes_a_single_database() {
+        let mut first: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        let mut second: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        first.insert("a".to_string(), RedisElement::String("1".to_string()));
+        second.insert("b".to_string(), RedisElement::String("2".to_string()));
+        second.set_ttl_absolute("b".to_string(), ttl);
+
+        let bytes = TtlHashMap::serialize_all(&[&first, &second]);
+
+        let mut map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
+        assert_eq!(2, map.len());
+        assert_eq!("1", map.get(&"a".to_string()).unwrap().to_string());
+        assert_eq!("2", map.get(&"b".to_string()).unwrap().to_string());
+        assert!(map.get_expiration(&"b".to_string()).unwrap().is_some());
+    }
+
+    /// Tipo de dato de prueba que se persiste como un string RDB con su valor en decimal.
+    #[allow(dead_code)]
+    #[derive(Debug, Clone, PartialEq)]
+    struct Counter(u64);
+
+    impl MemoryUsage for Counter {
+        fn memory_usage(&self, key: &str) -> usize {
+            key.len() + 8
+        }
+    }
+
+    impl Serializable for Counter {
+        fn rdb_type(&self) -> Option<u8> {
+            Some(rdb::TYPE_STRING)
+        }
+
+        fn write_rdb(&self, bytes: &mut Vec<u8>) {
+            rdb::write_string(bytes, self.0.to_string().as_bytes());
+        }
+
+        fn read_rdb(reader: &mut RdbReader, value_type: u8) -> Result<Self, String> {
+            match reader.read_value(value_type)? {
+                RedisElement::Integer(value) => Ok(Counter(value as u64)),
+                _ => Err("Counter must be an integer".to_string()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_other_value_types() {
+        let mut map: TtlHashMap<String, Counter> = TtlHashMap::new();
+        map.insert("visits".to_string(), Counter(42));
+
+        let bytes = map.serialize();
+        let mut new_map = TtlHashMap::<String, Counter>::deserialize(&bytes).unwrap();
+        assert_eq!(Some(&Counter(42)), new_map.get(&"visits".to_string()));
+
+        // El archivo es un RDB válido, que también puede cargarse con RedisElements.
+        let mut map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
+        assert_eq!(
+            Some(&RedisElement::Integer(42)),
+            map.get(&"visits".to_string())
+        );
+    }
+
+    #[test]
+    fn test_serialize_skips_expired_keys() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(60);
+        map.insert("valid".to_string(), RedisElement::String("1".to_string()));
+        map.insert(
+            "volatile".to_string(),
+            RedisElement::String("2".to_string()),
+        );
+        map.set_ttl_absolute("volatile".to_string(), ttl);
+        map.insert("expired".to_string(), RedisElement::String("3".to_string()));
+        map.set_ttl_absolute("expired".to_string(), SystemTime::UNIX_EPOCH);
+
+        let bytes = map.serialize();
+        let resizedb = bytes
+            .windows(3)
+            .position(|window| window[0] == rdb::OP_RESIZEDB)
+            .unwrap();
+        assert_eq!(&[rdb::OP_RESIZEDB, 2, 1], &bytes[resizedb..resizedb + 3]);
+
+        let mut new_map = TtlHashMap::<String, RedisElement>::deserialize(&bytes).unwrap();
+        assert_eq!(2, new_map.len());
+        let mut keys: Vec<&String> = new_map.keys().collect();
+        keys.sort();
+        assert_eq!(vec!["valid", "volatile"], keys);
+        assert!(new_map.get(&"expired".to_string()).is_none());
+        assert_eq!(1, new_map.volatile.len());
+    }
+
+    #[test]
+    fn test_snapshot_is_independent_of_the_original() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        map.insert("key".to_string(), RedisElement::String("value".to_string()));
+
+        let snapshot = map.snapshot();
+        map.insert("key".to_string(), RedisElement::String("other".to_string()));
+
+        assert_eq!(map.serialize().len(), snapshot.serialize().len());
+        assert_ne!(map.serialize(), snapshot.serialize());
+    }
+
+    #[test]
+    fn test_remove_all_expired_follows_the_clock() {
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::with_clock(clock.clone());
+        for (key, secs) in [("short", 10), ("long", 20)].iter() {
+            map.insert(key.to_string(), 1);
+            map.set_ttl_relative(key.to_string(), Duration::from_secs(*secs));
+        }
+        map.insert("persistent".to_string(), 1);
+
+        assert_eq!(0, map.remove_all_expired());
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(1, map.remove_all_expired());
+        assert_eq!(vec!["short".to_string()], map.take_expired_keys());
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(1, map.remove_all_expired());
+        assert_eq!(1, map.len());
+    }
+
+    #[test]
+    fn test_deserialize_with_clock_discards_keys_expired_for_the_clock() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        map.insert("key".to_string(), RedisElement::String("value".to_string()));
+        map.set_ttl_relative("key".to_string(), Duration::from_secs(60));
+        let bytes = map.serialize();
+
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        clock.advance(Duration::from_secs(60));
+        let map = TtlHashMap::<String, RedisElement>::deserialize_with_clock(&bytes, clock);
+        assert_eq!(0, map.unwrap().len());
+    }
+
+    #[test]
+    fn test_snapshot_shares_values_until_they_are_modified() {
+        let mut map: TtlHashMap<String, String> = TtlHashMap::new();
+        map.insert("key".to_string(), "value".to_string());
+
+        let mut snapshot = map.snapshot();
+        assert!(Arc::ptr_eq(
+            &map.store["key"].value,
+            &snapshot.store["key"].value
+        ));
+
+        map.get_mut(&"key".to_string()).unwrap().push('!');
+        assert_eq!(Some(&"value!".to_string()), map.get(&"key".to_string()));
+        assert_eq!(
+            Some(&"value".to_string()),
+            snapshot.peek(&"key".to_string())
+        );
+        assert_eq!(Some("value!".to_string()), map.remove(&"key".to_string()));
+    }
+
+    #[test]
+    fn test_reading_or_deleting_a_shared_value_does_not_copy_it() {
+        let mut map: TtlHashMap<String, String> = TtlHashMap::new();
+        map.insert("a".to_string(), "value".to_string());
+        map.insert("b".to_string(), "value".to_string());
+        let snapshot = map.snapshot();
+
+        assert_eq!(Some(&"value".to_string()), map.get(&"a".to_string()));
+        assert!(Arc::ptr_eq(
+            &map.store["a"].value,
+            &snapshot.store["a"].value
+        ));
+
+        map.insert("a".to_string(), "other".to_string());
+        assert!(map.delete(&"b".to_string()));
+        assert!(!map.delete(&"b".to_string()));
+        assert_eq!(1, Arc::strong_count(&snapshot.store["a"].value));
+        assert_eq!(1, Arc::strong_count(&snapshot.store["b"].value));
+    }
+
+    #[test]
+    fn test_deserialize_rejects_corrupted_or_truncated_files() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        map.insert("key".to_string(), RedisElement::String("value".to_string()));
+        let bytes = map.serialize();
+
+        let mut corrupted = bytes.clone();
+        let value_offset = corrupted.len() - 12;
+        corrupted[value_offset] = b'x';
+        assert!(TtlHashMap::<String, RedisElement>::deserialize(&corrupted)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Wrong RDB checksum"));
+        assert!(
+            TtlHashMap::<String, RedisElement>::deserialize(&bytes[..bytes.len() - 4]).is_err()
+        );
+
+        // Los archivos generados sin checksum tienen el checksum en 0.
+        let mut without_checksum = bytes[..bytes.len() - 8].to_vec();
+        without_checksum.extend_from_slice(&[0; 8]);
+        assert!(TtlHashMap::<String, RedisElement>::deserialize(&without_checksum).is_ok());
+    }
 }
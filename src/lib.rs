@@ -1,28 +1,86 @@
-use crate::config::server_config::Config;
+use crate::service::benchmark;
+use crate::service::benchmark::BenchmarkOptions;
+use crate::service::rdb_check;
 use crate::service::server::Server;
+use std::fs;
 
 mod config;
 mod entities;
 mod protocol;
 mod service;
 
-pub fn run_redis(argv: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
-    let config: Config;
+// API para embeber la base de datos en otros programas, sin levantar el servidor.
+pub use crate::config::server_config::{Config, ConfigBuilder};
+pub use crate::entities::clock::{Clock, MockClock, SystemClock};
+pub use crate::entities::command::Command;
+pub use crate::entities::key_change::KeyChange;
+pub use crate::entities::log_target::LogTarget;
+pub use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+pub use crate::entities::message_queue::OverflowPolicy;
+pub use crate::entities::redis_element::RedisElement;
+pub use crate::entities::response::Response;
+pub use crate::service::redis::Redis;
+pub use crate::service::redis_handle::RedisHandle;
 
-    match argv.len() {
+pub fn run_redis(argv: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config: Config = match argv.len() {
         // no arguments passed
-        0 => config = Config::new(),
+        0 => Config::new(),
         // one argument passed
-        1 => {
-            config = Config::new_from_file(argv[1].to_string())?;
-        }
+        1 => Config::new_from_file(argv[0].to_string())?,
         _ => {
             println!("Incorrect params, Try passing one or two arguments!");
             return Err("Incorrect params".into());
         }
+    };
+    for warning in config.take_warnings() {
+        println!("[warning] {}", warning);
     }
 
     let server = Server::new(config)?;
     server.serve()?;
     Ok(())
 }
+
+/// Valida el archivo RDB indicado, imprimiendo las estadísticas de sus claves o la posición de la
+/// corrupción encontrada.
+pub fn run_rdb_check(argv: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    if argv.len() != 1 {
+        println!("Usage: rdb_check <dump.rdb>");
+        return Err("Incorrect params".into());
+    }
+
+    println!("[info] Checking RDB file {}", argv[0]);
+    let bytes = fs::read(&argv[0])?;
+    match rdb_check::check(&bytes) {
+        Ok(report) => {
+            print!("{}", report);
+            println!("RDB looks OK!");
+            Ok(())
+        }
+        Err(e) => {
+            println!("--- RDB ERROR DETECTED ---");
+            println!("[error] {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+/// Ejecuta una prueba de carga contra un servidor en ejecución, al estilo de redis-benchmark,
+/// imprimiendo para cada prueba el throughput y los percentiles de latencia.
+pub fn run_benchmark(argv: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let options = match BenchmarkOptions::parse(&argv) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            println!("{}", benchmark::USAGE);
+            return Err(e.into());
+        }
+    };
+
+    for test in options.tests.iter() {
+        let report = benchmark::run_test(&options, test)?;
+        println!("{}", report);
+    }
+    Ok(())
+}
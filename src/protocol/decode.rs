@@ -2,10 +2,22 @@ use crate::protocol::type_data::TypeData;
 
 /// Longitud del `\r\n`.
 const CRLF: usize = 2;
+/// Mensaje de error usado cuando todavía no se recibieron todos los bytes del comando.
+pub const INCOMPLETE_MSG: &str = "Error comando incompleto";
+/// Largo máximo de un bulk string, el mismo que utiliza Redis por defecto (512MB).
+const MAX_BULK_LEN: usize = 512 * 1024 * 1024;
+/// Cantidad máxima de elementos de un array, la misma que utiliza Redis.
+const MAX_ARRAY_LEN: usize = 1024 * 1024;
+/// Largo máximo de una línea (un comando inline o el encabezado de un tipo de dato).
+const MAX_LINE_LEN: usize = 64 * 1024;
+/// Profundidad máxima de arrays anidados.
+const MAX_NESTING: usize = 32;
 
 ///Decodifica el comando recibido desde redis-cli.
 ///
-/// En caso de que el comando esté incompleto, devuelve un error de tipo `String`.
+/// En caso de que el comando esté incompleto, devuelve el error `INCOMPLETE_MSG`, de modo que se
+/// pueda volver a intentar al recibir más bytes. Ante cualquier otro error el comando está mal
+/// formado, y se devuelve un error de protocolo para informar al cliente.
 ///
 /// De otro modo, retorna un `TypeData` que representa a los bytes decodificados y un `usize`, que indica la posición del último byte que se decodificó.
 /// # Arguments
@@ -13,79 +25,312 @@ const CRLF: usize = 2;
 /// * `bytes` - Comando representado en bytes
 /// * `start` - Posición desde donde se debe comenzar a decodificar los bytes
 pub fn decode(bytes: &[u8], start: usize) -> std::result::Result<(TypeData, usize), String> {
+    decode_nested(bytes, start, 0)
+}
+
+/// Estado del tipo de dato que comienza en una posición del buffer, según `check_frame`.
+enum Frame<T = usize> {
+    /// El tipo de dato está completo y termina en la posición indicada.
+    Complete(T),
+    /// Faltan recibir al menos la cantidad de bytes indicada.
+    Incomplete(usize),
+    /// El tipo de dato está mal formado.
+    Invalid,
+}
+
+/// Calcula, sin decodificar ni reservar memoria, la cantidad mínima de bytes que todavía faltan
+/// recibir para que el tipo de dato que comienza en `start` esté completo.
+///
+/// Retorna 0 si el tipo de dato ya está completo, o si está mal formado, en cuyo caso `decode`
+/// informa el error. Permite esperar a tener un comando completo antes de decodificarlo, en vez
+/// de volver a decodificar todo el buffer cada vez que llegan bytes nuevos.
+/// # Arguments
+///
+/// * `bytes` - Comando representado en bytes
+/// * `start` - Posición desde donde comienza el tipo de dato
+pub fn bytes_needed(bytes: &[u8], start: usize) -> usize {
+    match check_frame(bytes, start, 0) {
+        Frame::Incomplete(needed) => needed,
+        Frame::Complete(_) | Frame::Invalid => 0,
+    }
+}
+
+/// Verifica si el tipo de dato que comienza en `start`, dentro de `depth` arrays, está completo.
+/// Aplica los mismos límites que `decode_nested`.
+fn check_frame(bytes: &[u8], start: usize, depth: usize) -> Frame {
     if !size_ok(bytes, start) {
-        return Err("Error comando incompleto".to_string());
+        return Frame::Incomplete(1);
+    }
+    match bytes[start] {
+        b'+' | b'-' | b':' => match line_end(bytes, start + 1) {
+            Frame::Complete((_, end)) => Frame::Complete(end),
+            Frame::Incomplete(needed) => Frame::Incomplete(needed),
+            Frame::Invalid => Frame::Invalid,
+        },
+        b'$' => match check_length(bytes, start + 1, MAX_BULK_LEN) {
+            Frame::Complete((Some(length), end)) if bytes.len() < end + length + CRLF => {
+                Frame::Incomplete(end + length + CRLF - bytes.len())
+            }
+            Frame::Complete((Some(length), end)) => Frame::Complete(end + length + CRLF),
+            Frame::Complete((None, end)) => Frame::Complete(end),
+            Frame::Incomplete(needed) => Frame::Incomplete(needed),
+            Frame::Invalid => Frame::Invalid,
+        },
+        b'*' if depth == MAX_NESTING => Frame::Invalid,
+        b'*' => match check_length(bytes, start + 1, MAX_ARRAY_LEN) {
+            Frame::Complete((Some(length), mut end)) => {
+                for _ in 0..length {
+                    match check_frame(bytes, end, depth + 1) {
+                        Frame::Complete(element_end) => end = element_end,
+                        other => return other,
+                    }
+                }
+                Frame::Complete(end)
+            }
+            Frame::Complete((None, end)) => Frame::Complete(end),
+            Frame::Incomplete(needed) => Frame::Incomplete(needed),
+            Frame::Invalid => Frame::Invalid,
+        },
+        _ => match bytes[start..].iter().position(|byte| *byte == b'\n') {
+            Some(position) if position <= MAX_LINE_LEN => Frame::Complete(start + position + 1),
+            None if bytes.len() - start <= MAX_LINE_LEN => Frame::Incomplete(1),
+            _ => Frame::Invalid,
+        },
+    }
+}
+
+/// Busca el `\r\n` que termina la línea que comienza en `pos`. Si está completa, retorna la
+/// posición del `\r\n` y la posición siguiente a él.
+fn line_end(bytes: &[u8], pos: usize) -> Frame<(usize, usize)> {
+    match bytes[pos..]
+        .windows(CRLF)
+        .position(|window| window == b"\r\n")
+    {
+        Some(index) if index <= MAX_LINE_LEN => Frame::Complete((pos + index, pos + index + CRLF)),
+        None if bytes.len() - pos <= MAX_LINE_LEN => Frame::Incomplete(1),
+        _ => Frame::Invalid,
+    }
+}
+
+/// Verifica el largo declarado de un bulk string o de un array, como `parse_length`. Si la línea
+/// está completa, retorna el largo (`None` si es -1) y la posición siguiente al `\r\n`.
+fn check_length(bytes: &[u8], pos: usize, max: usize) -> Frame<(Option<usize>, usize)> {
+    let (line_end, end) = match line_end(bytes, pos) {
+        Frame::Complete(line) => line,
+        Frame::Incomplete(needed) => return Frame::Incomplete(needed),
+        Frame::Invalid => return Frame::Invalid,
+    };
+    let length = match std::str::from_utf8(&bytes[pos..line_end]) {
+        Ok(length) => length,
+        Err(_) => return Frame::Invalid,
+    };
+    if length == "-1" {
+        return Frame::Complete((None, end));
+    }
+    match length.parse::<usize>() {
+        Ok(length) if length <= max => Frame::Complete((Some(length), end)),
+        _ => Frame::Invalid,
+    }
+}
+
+/// Decodifica un tipo de dato que se encuentra dentro de `depth` arrays.
+fn decode_nested(
+    bytes: &[u8],
+    start: usize,
+    depth: usize,
+) -> std::result::Result<(TypeData, usize), String> {
+    if !size_ok(bytes, start) {
+        return Err(INCOMPLETE_MSG.to_string());
     }
     match bytes[start] as char {
         '+' => {
-            if let Ok((string, final_index)) = parse(bytes, start + 1) {
-                Ok((TypeData::String(string), final_index))
-            } else {
-                Err("Error parseando el comando enviado".to_string())
-            }
+            let (string, final_index) = parse(bytes, start + 1)?;
+            Ok((TypeData::String(string), final_index))
         }
         '-' => {
-            if let Ok((error, final_index)) = parse(bytes, start + 1) {
-                return Ok((TypeData::Error(error), final_index));
-            }
-            Err("Error parseando el comando enviado".to_string())
+            let (error, final_index) = parse(bytes, start + 1)?;
+            Ok((TypeData::Error(error), final_index))
         }
         ':' => {
-            if let Ok((integer, final_index)) = parse(bytes, start + 1) {
-                return Ok((
-                    TypeData::Integer(integer.parse::<i64>().unwrap()),
-                    final_index,
-                ));
+            let (integer, final_index) = parse(bytes, start + 1)?;
+            match integer.parse::<i64>() {
+                Ok(integer) => Ok((TypeData::Integer(integer), final_index)),
+                Err(_) => Err("ERR Protocol error: invalid integer".to_string()),
             }
-            Err("Error parseando el comando enviado".to_string())
         }
 
         '$' => {
-            if let Ok((bulk_len, final_index)) = parse(bytes, start + 1) {
-                let length = bulk_len.parse::<usize>().unwrap();
-                if !size_ok(bytes, final_index) {
-                    return Err("Error parseando el comando enviado".to_string());
-                }
-                let bulk =
-                    String::from_utf8(bytes[final_index..length + final_index].to_vec()).unwrap();
-                return Ok((TypeData::BulkString(bulk), length + final_index + CRLF));
+            let (length, final_index) = parse_length(bytes, start + 1, MAX_BULK_LEN, "bulk")?;
+            let length = match length {
+                Some(length) => length,
+                None => return Ok((TypeData::Nil, final_index)),
+            };
+            // El contenido puede incluir `\r\n`, por lo que se espera a tener todos sus bytes.
+            if !size_ok(bytes, length + final_index + CRLF - 1) {
+                return Err(INCOMPLETE_MSG.to_string());
+            }
+            if &bytes[length + final_index..length + final_index + CRLF] != b"\r\n" {
+                return Err("ERR Protocol error: invalid bulk length".to_string());
+            }
+            match String::from_utf8(bytes[final_index..length + final_index].to_vec()) {
+                Ok(bulk) => Ok((TypeData::BulkString(bulk), length + final_index + CRLF)),
+                Err(_) => Err("ERR Protocol error: invalid UTF-8 string".to_string()),
             }
-            Err("Error parseando el comando enviado".to_string())
         }
         '*' => {
-            if let Ok((array_len, mut final_index)) = parse(bytes, start + 1) {
-                let length = array_len.parse::<usize>().unwrap();
-                let mut array: Vec<TypeData> = Vec::new();
+            if depth == MAX_NESTING {
+                return Err("ERR Protocol error: too many nested arrays".to_string());
+            }
+            let (length, mut final_index) =
+                parse_length(bytes, start + 1, MAX_ARRAY_LEN, "multibulk")?;
+            let length = match length {
+                Some(length) => length,
+                None => return Ok((TypeData::Nil, final_index)),
+            };
+            let mut array: Vec<TypeData> = Vec::new();
 
-                for _ in 0..length {
-                    match decode(bytes, final_index) {
-                        Ok((element, final_pos)) => {
-                            array.push(element);
-                            final_index = final_pos;
-                        }
-                        Err(e) => return Err(e),
+            for _ in 0..length {
+                let (element, final_pos) = decode_nested(bytes, final_index, depth + 1)?;
+                array.push(element);
+                final_index = final_pos;
+            }
+            Ok((TypeData::Array(array), final_index))
+        }
+
+        _ => decode_inline(bytes, start),
+    }
+}
+
+/// Parsea el largo declarado de un bulk string o de un array. Retorna `None` si el largo es -1,
+/// que representa el valor nulo.
+///
+/// Devuelve un error de protocolo si el largo no es un número, o si es mayor a `max`.
+fn parse_length(
+    bytes: &[u8],
+    pos: usize,
+    max: usize,
+    kind: &str,
+) -> std::result::Result<(Option<usize>, usize), String> {
+    let (length, final_index) = parse(bytes, pos)?;
+    if length == "-1" {
+        return Ok((None, final_index));
+    }
+    match length.parse::<usize>() {
+        Ok(length) if length <= max => Ok((Some(length), final_index)),
+        _ => Err(format!("ERR Protocol error: invalid {} length", kind)),
+    }
+}
+
+/// Decodifica un comando enviado con el protocolo inline (por ejemplo `SET foo bar\r\n`, escrito
+/// desde telnet), que termina en `\n` o `\r\n`.
+///
+/// Los argumentos se separan por espacios y pueden encerrarse entre comillas dobles, que admiten
+/// secuencias de escape como `\n` o `\x41`, o entre comillas simples, que solo admiten `\'`.
+///
+/// Retorna un `TypeData::Array` con un `TypeData::BulkString` por argumento. Si las comillas no
+/// están balanceadas, o la línea supera el largo máximo, retorna un error de protocolo.
+fn decode_inline(bytes: &[u8], start: usize) -> std::result::Result<(TypeData, usize), String> {
+    let end = match bytes[start..].iter().position(|byte| *byte == b'\n') {
+        Some(position) if position <= MAX_LINE_LEN => start + position,
+        None if bytes.len() - start <= MAX_LINE_LEN => return Err(INCOMPLETE_MSG.to_string()),
+        _ => return Err("ERR Protocol error: too big inline request".to_string()),
+    };
+    let line = match String::from_utf8(bytes[start..end].to_vec()) {
+        Ok(line) => line,
+        Err(_) => return Err("ERR Protocol error: invalid UTF-8 string".to_string()),
+    };
+
+    match split_args(line.strip_suffix('\r').unwrap_or(&line)) {
+        Some(args) => Ok((
+            TypeData::Array(args.into_iter().map(TypeData::BulkString).collect()),
+            end + 1,
+        )),
+        None => Err("ERR Protocol error: unbalanced quotes in request".to_string()),
+    }
+}
+
+/// Separa una línea inline en sus argumentos, con el mismo criterio que Redis.
+///
+/// Retorna `None` si hay comillas sin cerrar, o si una comilla de cierre no está seguida de un
+/// espacio o del final de la línea.
+fn split_args(line: &str) -> Option<Vec<String>> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut args = Vec::new();
+    let mut i = 0;
+
+    loop {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if i == chars.len() {
+            return Some(args);
+        }
+
+        let mut arg = String::new();
+        let quote = match chars[i] {
+            '"' | '\'' => {
+                i += 1;
+                Some(chars[i - 1])
+            }
+            _ => None,
+        };
+
+        loop {
+            match (quote, chars.get(i)) {
+                (Some(_), None) => return None,
+                (None, None) => break,
+                (None, Some(c)) if c.is_whitespace() => break,
+                (Some(q), Some(c)) if *c == q => {
+                    i += 1;
+                    // La comilla de cierre debe estar seguida de un espacio o del final.
+                    if chars.get(i).is_some_and(|c| !c.is_whitespace()) {
+                        return None;
                     }
+                    break;
+                }
+                (Some('"'), Some('\\')) if i + 1 < chars.len() => {
+                    let (c, len) = unescape(&chars[i + 1..]);
+                    arg.push(c);
+                    i += 1 + len;
+                }
+                (Some('\''), Some('\\')) if chars.get(i + 1) == Some(&'\'') => {
+                    arg.push('\'');
+                    i += 2;
+                }
+                (_, Some(c)) => {
+                    arg.push(*c);
+                    i += 1;
                 }
-                return Ok((TypeData::Array(array), final_index));
             }
-            Err("Error parseando el comando enviado".to_string())
         }
+        args.push(arg);
+    }
+}
 
-        _ => {
-            let vector = Vec::from(bytes);
-            if let Ok(string) = String::from_utf8(vector) {
-                let length = string.len();
-                let bulk = String::from_utf8(bytes[0..length - CRLF].to_vec()).unwrap();
-                return Ok((TypeData::BulkString(bulk), length));
-            }
-            Err("Error parseando el comando enviado".to_string())
+/// Interpreta la secuencia de escape que sigue a una `\\` dentro de comillas dobles. Retorna el
+/// caracter resultante y la cantidad de caracteres consumidos.
+fn unescape(chars: &[char]) -> (char, usize) {
+    if chars[0] == 'x' && chars.len() >= 3 {
+        let hex: String = chars[1..3].iter().collect();
+        if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+            return (byte as char, 3);
         }
     }
+    let c = match chars[0] {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'b' => '\u{8}',
+        'a' => '\u{7}',
+        c => c,
+    };
+    (c, 1)
 }
 
 ///Parsea los bytes desde la posición indicada hasta encontrar el primer `/r/n`.
 ///
-/// En caso de que el comando (representado en bytes) esté mal formado, devuelve un error de tipo `String`.
+/// Si todavía no se recibió el `\r\n`, devuelve el error `INCOMPLETE_MSG`. Si los bytes no son
+/// UTF-8 válido, o la línea supera el largo máximo, devuelve un error de protocolo.
 ///
 /// De otro modo, retorna un `String` que representa a los bytes decodificados y un `usize`, que indica la posición del último byte que se decodificó.
 /// # Arguments
@@ -93,20 +338,23 @@ pub fn decode(bytes: &[u8], start: usize) -> std::result::Result<(TypeData, usiz
 /// * `bytes` - Comando representado en bytes
 /// * `pos` - Posición desde donde se debe comenzar a decodificar los bytes
 pub fn parse(bytes: &[u8], pos: usize) -> std::result::Result<(String, usize), String> {
-    let vector = Vec::from(&bytes[pos..]);
-    if let Ok(string) = String::from_utf8(vector) {
-        if let Some(index) = string.find("\r\n") {
-            if let Ok(string) = String::from_utf8((bytes[pos..index + pos]).to_vec()) {
-                return Ok((string, index + pos + CRLF));
-            }
-        }
+    let index = match bytes[pos..]
+        .windows(CRLF)
+        .position(|window| window == b"\r\n")
+    {
+        Some(index) if index <= MAX_LINE_LEN => index,
+        None if bytes.len() - pos <= MAX_LINE_LEN => return Err(INCOMPLETE_MSG.to_string()),
+        _ => return Err("ERR Protocol error: too big line".to_string()),
+    };
+    match String::from_utf8((bytes[pos..index + pos]).to_vec()) {
+        Ok(string) => Ok((string, index + pos + CRLF)),
+        Err(_) => Err("ERR Protocol error: invalid UTF-8 string".to_string()),
     }
-    Err("Error parseando el comando recibido".to_string())
 }
 
 /// Pregunta si la longitud de los bytes a codificar está bien.
 ///
-/// En caso de que `pos` sea mayor o igual a la longitud de `bytes` (convertidos en `String`), devuelve `false`.
+/// En caso de que `pos` sea mayor o igual a la longitud de `bytes`, devuelve `false`.
 ///
 /// De otro modo, retorna `true`.
 /// # Arguments
@@ -114,18 +362,12 @@ pub fn parse(bytes: &[u8], pos: usize) -> std::result::Result<(String, usize), S
 /// * `bytes` - Comando representado en bytes
 /// * `pos` - Posición desde donde se debe comenzar a decodificar los bytes
 pub fn size_ok(bytes: &[u8], pos: usize) -> bool {
-    let vector = Vec::from(bytes);
-    if let Ok(string) = String::from_utf8(vector) {
-        if string.len() <= pos {
-            return false;
-        }
-    }
-    true
+    pos < bytes.len()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::protocol::decode::decode;
+    use crate::protocol::decode::{bytes_needed, decode, INCOMPLETE_MSG};
     use crate::protocol::type_data::TypeData;
 
     #[test]
@@ -197,4 +439,125 @@ mod test {
             TypeData::Array(vector)
         )
     }
+
+    #[test]
+    fn test_decode_inline_command() {
+        let bytes = "SET foo bar\r\n";
+        let array = vec![
+            TypeData::BulkString("SET".to_string()),
+            TypeData::BulkString("foo".to_string()),
+            TypeData::BulkString("bar".to_string()),
+        ];
+        assert_eq!(
+            decode(bytes.as_bytes(), 0).ok().unwrap(),
+            (TypeData::Array(array), bytes.len())
+        )
+    }
+
+    #[test]
+    fn test_decode_inline_command_without_carriage_return() {
+        let bytes = "  ping   \n";
+        assert_eq!(
+            decode(bytes.as_bytes(), 0).ok().unwrap().0,
+            TypeData::Array(vec![TypeData::BulkString("ping".to_string())])
+        )
+    }
+
+    #[test]
+    fn test_decode_inline_quoted_arguments() {
+        let bytes = "set \"hello world\" 'it\\'s' \"a\\x41\\n\" \"\"\r\n";
+        let array = vec![
+            TypeData::BulkString("set".to_string()),
+            TypeData::BulkString("hello world".to_string()),
+            TypeData::BulkString("it's".to_string()),
+            TypeData::BulkString("aA\n".to_string()),
+            TypeData::BulkString("".to_string()),
+        ];
+        assert_eq!(
+            decode(bytes.as_bytes(), 0).ok().unwrap().0,
+            TypeData::Array(array)
+        )
+    }
+
+    #[test]
+    fn test_decode_inline_unbalanced_quotes() {
+        let error = Err("ERR Protocol error: unbalanced quotes in request".to_string());
+        assert_eq!(decode("set \"foo bar\r\n".as_bytes(), 0), error);
+        assert_eq!(decode("set \"foo\"bar\r\n".as_bytes(), 0), error)
+    }
+
+    #[test]
+    fn test_decode_inline_incomplete_line() {
+        assert!(decode("set foo".as_bytes(), 0).is_err())
+    }
+
+    #[test]
+    fn test_decode_incomplete_frames() {
+        let incomplete = Err(INCOMPLETE_MSG.to_string());
+        assert_eq!(decode("*2\r\n$3\r\nget\r\n".as_bytes(), 0), incomplete);
+        assert_eq!(decode("$10\r\nhello\r\n".as_bytes(), 0), incomplete);
+        assert_eq!(decode("+OK".as_bytes(), 0), incomplete);
+    }
+
+    #[test]
+    fn test_bytes_needed() {
+        assert_eq!(0, bytes_needed(b"$6\r\nfoobar\r\n", 0));
+        assert_eq!(4, bytes_needed(b"$6\r\nfoob", 0));
+        assert_eq!(1, bytes_needed(b"*2\r\n$3\r\nget\r", 0));
+        assert_eq!(3, bytes_needed(b"*2\r\n$3\r\nget\r\n$1\r\n", 0));
+        assert_eq!(0, bytes_needed(b"*2\r\n$3\r\nget\r\n$1\r\nk\r\n", 0));
+        assert_eq!(1, bytes_needed(b"get ke", 0));
+        assert_eq!(0, bytes_needed(b"get key\r\n", 0));
+        // Los comandos mal formados se consideran completos, para que `decode` informe el error.
+        assert_eq!(0, bytes_needed(b"*2\r\n$x\r\n", 0));
+        assert_eq!(0, bytes_needed(b"$536870913\r\n", 0));
+    }
+
+    #[test]
+    fn test_decode_null_bulk_string_and_array() {
+        assert_eq!(decode("$-1\r\n".as_bytes(), 0), Ok((TypeData::Nil, 5)));
+        assert_eq!(decode("*-1\r\n".as_bytes(), 0), Ok((TypeData::Nil, 5)));
+    }
+
+    #[test]
+    fn test_decode_invalid_lengths() {
+        let bulk = Err("ERR Protocol error: invalid bulk length".to_string());
+        assert_eq!(decode("$abc\r\nfoo\r\n".as_bytes(), 0), bulk);
+        assert_eq!(decode("$-2\r\n".as_bytes(), 0), bulk);
+        assert_eq!(decode("$999999999999\r\n".as_bytes(), 0), bulk);
+        assert_eq!(decode("$2\r\nfoo\r\n".as_bytes(), 0), bulk);
+        assert_eq!(
+            decode("*9999999999\r\n".as_bytes(), 0),
+            Err("ERR Protocol error: invalid multibulk length".to_string())
+        );
+        assert_eq!(
+            decode(":12a\r\n".as_bytes(), 0),
+            Err("ERR Protocol error: invalid integer".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        let error = Err("ERR Protocol error: invalid UTF-8 string".to_string());
+        assert_eq!(decode(b"$2\r\n\xff\xfe\r\n", 0), error);
+        assert_eq!(decode(b"get \xff\r\n", 0), error);
+    }
+
+    #[test]
+    fn test_decode_too_many_nested_arrays() {
+        let bytes = "*1\r\n".repeat(40);
+        assert_eq!(
+            decode(bytes.as_bytes(), 0),
+            Err("ERR Protocol error: too many nested arrays".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_too_big_inline_request() {
+        let bytes = "a".repeat(70 * 1024);
+        assert_eq!(
+            decode(bytes.as_bytes(), 0),
+            Err("ERR Protocol error: too big inline request".to_string())
+        );
+    }
 }
@@ -1,4 +1,6 @@
 use crate::protocol::type_data::TypeData;
+use std::io;
+use std::io::Write;
 
 /// Codifica la respuesta del comando para enviárselo a redis-cli, utilizando el protocolo RESP.
 ///
@@ -8,72 +10,50 @@ use crate::protocol::type_data::TypeData;
 ///
 /// * `data` - Respuesta, representada como `TypeData`, a codificar.
 pub fn encode(data: TypeData) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    // Escribir en un Vec<u8> no puede fallar.
+    let _ = encode_to(&data, &mut bytes);
+    bytes
+}
+
+/// Codifica la respuesta del comando con el protocolo RESP, escribiéndola a medida que se genera
+/// en lugar de armar un único byte slice con toda la respuesta.
+///
+/// # Arguments
+///
+/// * `data` - Respuesta, representada como `TypeData`, a codificar.
+/// * `writer` - Destino en el cual se escribe la respuesta codificada.
+pub fn encode_to<W: Write>(data: &TypeData, writer: &mut W) -> io::Result<()> {
     match data {
-        TypeData::String(string) => {
-            let bytes = [
-                "+".to_string().as_bytes(),
-                string.as_bytes(),
-                "\r\n".as_bytes(),
-            ]
-            .concat();
-            bytes
-        }
-        TypeData::Error(error) => {
-            let bytes = [
-                "-".to_string().as_bytes(),
-                error.as_bytes(),
-                "\r\n".as_bytes(),
-            ]
-            .concat();
-            bytes
-        }
-        TypeData::Integer(int) => {
-            let bytes = [
-                ":".to_string().as_bytes(),
-                int.to_string().as_bytes(),
-                "\r\n".as_bytes(),
-            ]
-            .concat();
-            bytes
-        }
-        TypeData::BulkString(bulk) => {
-            let bytes = [
-                "$".to_string().as_bytes(),
-                bulk.len().to_string().as_bytes(),
-                "\r\n".as_bytes(),
-                bulk.as_bytes(),
-                "\r\n".as_bytes(),
-            ]
-            .concat();
-            bytes
+        TypeData::String(string) => write!(writer, "+{}\r\n", string),
+        TypeData::Error(error) => write!(writer, "-{}\r\n", error),
+        TypeData::Integer(int) => write!(writer, ":{}\r\n", int),
+        TypeData::BulkString(bulk) => write!(writer, "${}\r\n{}\r\n", bulk.len(), bulk),
+        TypeData::BulkBytes(bulk) => {
+            write!(writer, "${}\r\n", bulk.len())?;
+            writer.write_all(bulk)?;
+            writer.write_all(b"\r\n")
         }
         TypeData::Array(array) => {
-            let mut bytes = [
-                "*".to_string().as_bytes(),
-                array.len().to_string().as_bytes(),
-                "\r\n".as_bytes(),
-            ]
-            .concat();
+            encode_array_header(array.len(), writer)?;
             for element in array {
-                let result = encode(element.clone());
-                bytes = [bytes, result].concat();
+                encode_to(element, writer)?;
             }
-            bytes
-        }
-        TypeData::Nil => {
-            let bytes = [
-                "$".to_string().as_bytes(),
-                "-1".to_string().as_bytes(),
-                "\r\n".as_bytes(),
-            ]
-            .concat();
-            bytes
+            Ok(())
         }
+        TypeData::Nil => writer.write_all(b"$-1\r\n"),
     }
 }
+
+/// Escribe el encabezado RESP de un array con la cantidad de elementos indicada. Los elementos
+/// deben escribirse a continuación.
+pub fn encode_array_header<W: Write>(len: usize, writer: &mut W) -> io::Result<()> {
+    write!(writer, "*{}\r\n", len)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::protocol::encode::encode;
+    use crate::protocol::encode::{encode, encode_to};
     use crate::protocol::type_data::TypeData;
 
     #[test]
@@ -109,4 +89,17 @@ mod test {
         ];
         assert_eq!(encode(TypeData::Array(array)), bytes)
     }
+
+    #[test]
+    fn test_encode_to_nested_array() {
+        let bytes = "*2\r\n*1\r\n:1\r\n$-1\r\n".as_bytes();
+        let array = TypeData::Array(vec![
+            TypeData::Array(vec![TypeData::Integer(1)]),
+            TypeData::Nil,
+        ]);
+        let mut written = Vec::new();
+        encode_to(&array, &mut written).unwrap();
+        assert_eq!(written, bytes);
+        assert_eq!(encode(array), bytes)
+    }
 }
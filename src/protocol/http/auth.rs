@@ -0,0 +1,96 @@
+/// Headers de la respuesta a un request sin credenciales válidas. `WWW-Authenticate` hace que el
+/// browser le pida la contraseña al usuario.
+pub const UNAUTHORIZED_HEADERS: &str = "HTTP/1.1 401 Unauthorized\r\n\
+                                        WWW-Authenticate: Basic realm=\"redis\"\r\n\
+                                        Content-Type: application/json\r\n";
+
+/// Indica si el header `Authorization` de un request REST contiene la contraseña configurada en
+/// `requirepass`. Se acepta con el esquema `Basic` (con cualquier usuario, ya que el servidor
+/// sólo tiene el usuario por defecto) o como un token con el esquema `Bearer`.
+///
+/// Si no hay contraseña configurada, todos los requests están autorizados.
+///
+/// # Arguments
+///
+/// * `authorization` - Valor del header `Authorization`, si el request lo incluye.
+/// * `requirepass` - Contraseña configurada en el servidor.
+pub fn is_authorized(authorization: Option<&str>, requirepass: &str) -> bool {
+    if requirepass.is_empty() {
+        return true;
+    }
+    let (scheme, credentials) = match authorization.and_then(|value| value.trim().split_once(' ')) {
+        Some(header) => header,
+        None => return false,
+    };
+    let credentials = credentials.trim();
+    if scheme.eq_ignore_ascii_case("basic") {
+        decode_base64(credentials)
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .and_then(|decoded| {
+                decoded
+                    .split_once(':')
+                    .map(|(_, password)| password == requirepass)
+            })
+            .unwrap_or(false)
+    } else if scheme.eq_ignore_ascii_case("bearer") {
+        credentials == requirepass
+    } else {
+        false
+    }
+}
+
+/// Decodifica un texto en base64 estándar, con o sin padding. Retorna `None` si no es válido.
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in encoded.trim_end_matches('=').bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::protocol::http::auth::{decode_base64, is_authorized};
+
+    #[test]
+    fn decode_base64_with_and_without_padding() {
+        assert_eq!(
+            Some(b"user:secret".to_vec()),
+            decode_base64("dXNlcjpzZWNyZXQ=")
+        );
+        assert_eq!(Some(b":ab".to_vec()), decode_base64("OmFi"));
+        assert_eq!(Some(b"a".to_vec()), decode_base64("YQ"));
+        assert_eq!(None, decode_base64("no válido"));
+    }
+
+    #[test]
+    fn authorized_with_basic_or_bearer_credentials() {
+        // "default:secret"
+        let basic = Some("Basic ZGVmYXVsdDpzZWNyZXQ=");
+        assert!(is_authorized(basic, "secret"));
+        assert!(!is_authorized(basic, "other"));
+        assert!(is_authorized(Some("Bearer secret"), "secret"));
+        assert!(!is_authorized(Some("Bearer wrong"), "secret"));
+        assert!(!is_authorized(None, "secret"));
+    }
+
+    #[test]
+    fn everything_is_authorized_without_requirepass() {
+        assert!(is_authorized(None, ""));
+    }
+}
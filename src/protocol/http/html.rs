@@ -6,6 +6,8 @@ static START_LINES: &str = "<!--start-lines-->";
 static INDEX_FILE: &str = "index.html";
 /// Representa el nombre de nuestro archivo HTML en caso de error 404.
 static ERROR_FILE: &str = "404.html";
+/// Representa el nombre del archivo HTML del dashboard de administración.
+static DASHBOARD_FILE: &str = "dashboard.html";
 
 /// Estructura que representa el código HTML de nuestra página web.
 pub struct Html {
@@ -89,6 +91,12 @@ impl Html {
         std::fs::read(url)
     }
 
+    /// Devuelve el código HTML del dashboard de administración, que se actualiza ejecutando
+    /// comandos con respuestas en JSON.
+    pub fn get_dashboard() -> io::Result<String> {
+        std::fs::read_to_string(DASHBOARD_FILE)
+    }
+
     /// Devuelve el código HTML en caso de error 404.
     pub fn get_404() -> io::Result<String> {
         std::fs::read_to_string(ERROR_FILE)
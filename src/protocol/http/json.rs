@@ -0,0 +1,144 @@
+use crate::entities::redis_element::RedisElement;
+
+/// Codifica el resultado de un comando como un objeto JSON, para los clientes HTTP que envían
+/// `Accept: application/json`.
+///
+/// Retorna `{"ok": true, "result": ...}` si el comando se ejecutó correctamente y
+/// `{"ok": false, "error": "..."}` en caso contrario.
+///
+/// # Arguments
+///
+/// * `result` - Respuesta del comando o mensaje de error.
+pub fn encode_result(result: Result<RedisElement, String>) -> String {
+    match result {
+        Ok(element) => format!("{{\"ok\": true, \"result\": {}}}", encode_element(element)),
+        Err(msg) => format!("{{\"ok\": false, \"error\": {}}}", encode_string(&msg)),
+    }
+}
+
+/// Codifica un `RedisElement` como un valor JSON: los strings como strings, los enteros como
+/// números, las colecciones como arrays y `Nil` como `null`.
+///
+/// # Arguments
+///
+/// * `redis_element` - Elemento a codificar.
+fn encode_element(redis_element: RedisElement) -> String {
+    match redis_element {
+        RedisElement::String(string) | RedisElement::SimpleString(string) => encode_string(&string),
+        RedisElement::Bytes(bytes) => encode_string(&String::from_utf8_lossy(&bytes)),
+        RedisElement::Integer(integer) => integer.to_string(),
+        RedisElement::List(list) => encode_array(list.iter().map(|element| encode_string(element))),
+        RedisElement::Set(set) => {
+            let mut members: Vec<String> = set.into_iter().collect();
+            members.sort();
+            encode_array(members.iter().map(|member| encode_string(member)))
+        }
+        RedisElement::SortedSet(sorted_set) => {
+            encode_array(sorted_set.iter().map(|(member, score)| {
+                format!(
+                    "{{\"member\": {}, \"score\": {}}}",
+                    encode_string(member),
+                    encode_score(score)
+                )
+            }))
+        }
+        RedisElement::Stream(stream) => encode_array(stream.iter().map(|(id, fields)| {
+            let fields = fields
+                .iter()
+                .map(|(field, value)| format!("{}: {}", encode_string(field), encode_string(value)))
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!(
+                "{{\"id\": {}, \"fields\": {{{}}}}}",
+                encode_string(&id.to_string()),
+                fields
+            )
+        })),
+        RedisElement::Array(array) => encode_array(array.into_iter().map(encode_element)),
+        RedisElement::Nil => "null".to_string(),
+    }
+}
+
+/// Une los valores ya codificados en un array JSON.
+fn encode_array(values: impl Iterator<Item = String>) -> String {
+    format!("[{}]", values.collect::<Vec<String>>().join(", "))
+}
+
+/// Codifica un score de un sorted set. JSON no admite infinitos, por lo que `inf` y `-inf` se
+/// envían como strings.
+fn encode_score(score: f64) -> String {
+    if score.is_finite() {
+        score.to_string()
+    } else if score > 0.0 {
+        encode_string("inf")
+    } else {
+        encode_string("-inf")
+    }
+}
+
+/// Codifica un string JSON, escapando las comillas, las barras y los caracteres de control.
+fn encode_string(string: &str) -> String {
+    let mut encoded = String::with_capacity(string.len() + 2);
+    encoded.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            '\t' => encoded.push_str("\\t"),
+            c if (c as u32) < 0x20 => encoded.push_str(&format!("\\u{:04x}", c as u32)),
+            c => encoded.push(c),
+        }
+    }
+    encoded.push('"');
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::protocol::http::json::encode_result;
+    use std::collections::HashSet;
+
+    #[test]
+    fn encode_scalar_results() {
+        assert_eq!(
+            "{\"ok\": true, \"result\": \"say \\\"hi\\\"\\n\"}",
+            encode_result(Ok(RedisElement::String("say \"hi\"\n".to_string())))
+        );
+        assert_eq!(
+            "{\"ok\": true, \"result\": 3}",
+            encode_result(Ok(RedisElement::Integer(3)))
+        );
+        assert_eq!(
+            "{\"ok\": true, \"result\": null}",
+            encode_result(Ok(RedisElement::Nil))
+        );
+    }
+
+    #[test]
+    fn encode_collection_results() {
+        let set: HashSet<String> = ["b", "a"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            "{\"ok\": true, \"result\": [\"a\", \"b\"]}",
+            encode_result(Ok(RedisElement::Set(set)))
+        );
+        let array = RedisElement::Array(vec![
+            RedisElement::String("0".to_string()),
+            RedisElement::List(vec![]),
+        ]);
+        assert_eq!(
+            "{\"ok\": true, \"result\": [\"0\", []]}",
+            encode_result(Ok(array))
+        );
+    }
+
+    #[test]
+    fn encode_error_result() {
+        assert_eq!(
+            "{\"ok\": false, \"error\": \"ERR wrong\"}",
+            encode_result(Err("ERR wrong".to_string()))
+        );
+    }
+}
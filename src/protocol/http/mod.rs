@@ -1,3 +1,6 @@
+pub mod auth;
 pub mod html;
+pub mod json;
 pub mod parse_request;
 pub mod parse_response;
+pub mod sse;
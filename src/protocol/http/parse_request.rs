@@ -1,7 +1,13 @@
 use std::collections::HashMap;
 
+/// Ruta del recurso REST que representa a las claves de la base de datos.
+const KEYS_RESOURCE: &str = "/keys";
+/// Ruta desde la que se reciben los mensajes publicados en un canal.
+const SUBSCRIBE_RESOURCE: &str = "/subscribe";
+
 /// Representa el request que envía el browser, utilizando el protocolo HTTP.
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct Request {
     /// Representa los métodos que utiliza el protocolo HTTP que indica la acción a realizar,
     /// como por ejemplo: GET, POST, etc.
@@ -30,12 +36,25 @@ enum RequestParseState {
 pub enum HttpMethod {
     /// Representa el método GET.
     Get(String),
-    /// Representa el método POST.
-    Post(Vec<String>),
-    /// Representa otros métodos HTTP, como: DELETE, PUT, etc.
+    /// Representa el método POST, con el comando a ejecutar y el formato de la respuesta.
+    Post(Vec<String>, ResponseFormat),
+    /// Representa el método PUT, con la URL y el body del request.
+    Put(String, String),
+    /// Representa el método DELETE, con la URL del request.
+    Delete(String),
+    /// Representa otros métodos HTTP, como: HEAD, PATCH, etc.
     Other(),
 }
 
+/// Formato en el que se devuelve el resultado de un comando, según el header `Accept`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseFormat {
+    /// La página web con el comando y su resultado, para los browsers.
+    Html,
+    /// Un objeto JSON con el resultado, para los clientes que envían `Accept: application/json`.
+    Json,
+}
+
 /// Parsea un request HTTP, diferencia segun el metodo HTTP recibido y guarda la informacion
 /// necesaria para procesar.
 ///
@@ -48,25 +67,190 @@ pub fn parse_command_rest(data: &[u8]) -> HttpMethod {
     let request = parse_request(data);
     match request.method.as_str() {
         "POST" => {
-            let body = request.body;
-            return if let Some(index_command) = body.find("command") {
-                let command_len = 7;
-                let equal = 1;
-                let slice = &body[index_command + command_len + equal..];
-                let command: Vec<String> = slice.split('+').map(String::from).collect();
-                HttpMethod::Post(command)
-            } else {
-                HttpMethod::Post(vec![])
-            };
+            // El formulario se envía como `application/x-www-form-urlencoded`: los argumentos del
+            // comando se separan con `+` y los caracteres especiales se codifican como `%XX`.
+            let command = request
+                .body
+                .split('&')
+                .find_map(|field| field.strip_prefix("command="))
+                .map(|value| {
+                    value
+                        .split('+')
+                        .filter(|arg| !arg.is_empty())
+                        .map(decode_form_value)
+                        .collect()
+                })
+                .unwrap_or_default();
+            HttpMethod::Post(command, response_format(&request))
         }
         "GET" => {
             let url = request.url;
             HttpMethod::Get(url)
         }
+        "PUT" => HttpMethod::Put(request.url, request.body),
+        "DELETE" => HttpMethod::Delete(request.url),
         _ => HttpMethod::Other(),
     }
 }
 
+/// Traduce los requests sobre el recurso `/keys` al comando que corresponde ejecutar:
+///
+/// * `GET /keys?pattern=...` - KEYS, con el patrón `*` si no se indica ninguno.
+/// * `GET /keys/{key}` - GET.
+/// * `PUT /keys/{key}` - SET, usando el body como valor.
+/// * `DELETE /keys/{key}` - DEL.
+///
+/// Retorna `None` si el request no corresponde a ninguna de estas rutas.
+///
+/// # Arguments
+///
+/// * `method` - Request HTTP parseado.
+pub fn key_command(method: &HttpMethod) -> Option<Vec<String>> {
+    match method {
+        HttpMethod::Get(url) => {
+            let (path, query) = url.split_once('?').unwrap_or((url, ""));
+            if path == KEYS_RESOURCE {
+                let pattern = query
+                    .split('&')
+                    .find_map(|param| param.strip_prefix("pattern="))
+                    .map(|pattern| decode_form_value(&pattern.replace('+', " ")))
+                    .unwrap_or_else(|| "*".to_string());
+                Some(vec!["keys".to_string(), pattern])
+            } else {
+                Some(vec!["get".to_string(), resource_key(url)?])
+            }
+        }
+        HttpMethod::Put(url, value) => {
+            Some(vec!["set".to_string(), resource_key(url)?, value.clone()])
+        }
+        HttpMethod::Delete(url) => Some(vec!["del".to_string(), resource_key(url)?]),
+        _ => None,
+    }
+}
+
+/// Obtiene el canal de un request `GET /subscribe/{channel}`, que abre un stream de
+/// Server-Sent Events con los mensajes publicados en el canal.
+///
+/// Retorna `None` si el request no corresponde a esta ruta.
+///
+/// # Arguments
+///
+/// * `method` - Request HTTP parseado.
+pub fn subscribe_channel(method: &HttpMethod) -> Option<String> {
+    match method {
+        HttpMethod::Get(url) => {
+            let path = url.split('?').next().unwrap_or_default();
+            path.strip_prefix(SUBSCRIBE_RESOURCE)?
+                .strip_prefix('/')
+                .filter(|channel| !channel.is_empty())
+                .map(decode_form_value)
+        }
+        _ => None,
+    }
+}
+
+/// Obtiene la clave de una URL de la forma `/keys/{key}`, ignorando la query.
+fn resource_key(url: &str) -> Option<String> {
+    let path = url.split('?').next().unwrap_or_default();
+    path.strip_prefix(KEYS_RESOURCE)?
+        .strip_prefix('/')
+        .filter(|key| !key.is_empty())
+        .map(decode_form_value)
+}
+
+/// Calcula el largo en bytes del primer request HTTP recibido: sus headers y tantos bytes de body
+/// como indica el header `Content-Length`. Como el browser puede enviar el body por separado, y
+/// en una conexión keep-alive el siguiente request a continuación, el request está completo
+/// cuando se recibieron al menos esos bytes.
+///
+/// Retorna `None` si todavía no se recibieron todos los headers.
+///
+/// # Arguments
+///
+/// * `data` - Bytes recibidos hasta el momento desde el browser.
+pub fn request_len(data: &[u8]) -> Option<usize> {
+    let end = data.windows(4).position(|window| window == b"\r\n\r\n")? + 4;
+    let content_length = String::from_utf8_lossy(&data[..end])
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    Some(end + content_length)
+}
+
+/// Indica si la conexión puede reutilizarse para otro request luego de responder. En HTTP/1.1
+/// se mantiene abierta salvo que el cliente envíe `Connection: close`, y en HTTP/1.0 sólo si
+/// envía `Connection: keep-alive`.
+///
+/// # Arguments
+///
+/// * `data` - Bytes del request HTTP.
+pub fn keep_alive(data: &[u8]) -> bool {
+    let request = parse_request(data);
+    let connection = request
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("connection"))
+        .map(|(_, value)| value.to_lowercase());
+    match connection.as_deref() {
+        Some("close") => false,
+        Some("keep-alive") => true,
+        _ => request.http_version != "HTTP/1.0",
+    }
+}
+
+/// Devuelve el valor del header indicado, sin distinguir mayúsculas de minúsculas en su nombre.
+///
+/// # Arguments
+///
+/// * `data` - Bytes recibidos desde el browser que representan el request HTTP.
+/// * `name` - Nombre del header.
+pub fn header(data: &[u8], name: &str) -> Option<String> {
+    parse_request(data)
+        .headers
+        .into_iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+}
+
+/// Elige el formato de la respuesta a partir del header `Accept` del request.
+fn response_format(request: &Request) -> ResponseFormat {
+    let accepts_json = request.headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("accept") && value.contains("application/json")
+    });
+    if accepts_json {
+        ResponseFormat::Json
+    } else {
+        ResponseFormat::Html
+    }
+}
+
+/// Decodifica un valor de un formulario, reemplazando cada `%XX` por el byte que representa.
+/// Las secuencias inválidas se mantienen tal cual.
+fn decode_form_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
 /// Parsea un request HTTP, convirtiendolo en un objeto `Request`.
 ///
 /// Retorna un `Request` que representa el request HTTP, el cual contiene sus partes diferenciadas.
@@ -80,7 +264,7 @@ fn parse_request(data: &[u8]) -> Request {
     let mut url = 0;
     let mut http_version = 0;
     let mut header = 0;
-    let mut body = 0;
+    let mut body = None;
     let mut headers_key: Vec<usize> = vec![];
     let mut headers_value: Vec<usize> = vec![];
     for (i, current) in data.iter().enumerate() {
@@ -112,15 +296,15 @@ fn parse_request(data: &[u8]) -> Request {
                         state = RequestParseState::Body;
                     }
                 } else if current == &b'\r' {
-                    if String::from_utf8(data[header + 3..header + 4].to_vec()).unwrap() == "\r" {
-                        state = RequestParseState::Headers { is_end: true };
-                    } else {
+                    // El valor del último header también termina en el `\r\n\r\n` final.
+                    if headers_key.len() > headers_value.len() {
                         headers_value.push(header);
-                        header = 0;
                     }
-                } else if current == &b':'
-                    && String::from_utf8(data[i + 1..i + 2].to_vec()).unwrap() == " "
-                {
+                    header = 0;
+                    if data.get(i + 2) == Some(&b'\r') {
+                        state = RequestParseState::Headers { is_end: true };
+                    }
+                } else if current == &b':' && data.get(i + 1) == Some(&b' ') {
                     headers_key.push(header);
                     header = 0;
                 } else {
@@ -128,7 +312,7 @@ fn parse_request(data: &[u8]) -> Request {
                 }
             }
             RequestParseState::Body => {
-                body = i;
+                body = Some(i);
                 break;
             }
         }
@@ -148,7 +332,10 @@ fn parse_request(data: &[u8]) -> Request {
         headers.insert(key_slice, value_slice);
     }
 
-    let body_slice = convert_to_string(&data[body + 2..]).unwrap();
+    let body_slice = match body {
+        Some(body) => convert_to_string(&data[body + 2..]).unwrap(),
+        None => String::new(),
+    };
 
     Request {
         method: method_slice,
@@ -173,3 +360,133 @@ fn convert_to_string(data: &[u8]) -> Result<String, String> {
     }
     Err("Error intentando parsear el request".to_string())
 }
+
+#[cfg(test)]
+mod test {
+    use crate::protocol::http::parse_request::{
+        decode_form_value, header, keep_alive, key_command, parse_command_rest, request_len,
+        subscribe_channel, HttpMethod, ResponseFormat,
+    };
+
+    #[test]
+    fn post_command_is_decoded_from_the_form() {
+        let request = b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 41\r\n\r\n\
+                        command=set+key+hello%20world%21&submit=1";
+        match parse_command_rest(request) {
+            HttpMethod::Post(command, format) => {
+                assert_eq!(vec!["set", "key", "hello world!"], command);
+                assert_eq!(ResponseFormat::Html, format);
+            }
+            _ => panic!("Se esperaba un POST"),
+        }
+    }
+
+    #[test]
+    fn post_responds_json_when_accepted() {
+        let request = b"POST / HTTP/1.1\r\naccept: application/json\r\nContent-Length: 12\r\n\r\n\
+                        command=ping";
+        match parse_command_rest(request) {
+            HttpMethod::Post(command, format) => {
+                assert_eq!(vec!["ping"], command);
+                assert_eq!(ResponseFormat::Json, format);
+            }
+            _ => panic!("Se esperaba un POST"),
+        }
+    }
+
+    #[test]
+    fn decode_form_value_keeps_invalid_sequences() {
+        assert_eq!("a+b%", decode_form_value("a%2Bb%"));
+        assert_eq!("100%zz", decode_form_value("100%zz"));
+        assert_eq!("ñ", decode_form_value("%C3%B1"));
+    }
+
+    #[test]
+    fn request_len_includes_the_body() {
+        let headers = b"POST / HTTP/1.1\r\nContent-Length: 12\r\n\r\n";
+        assert_eq!(None, request_len(b"POST / HTTP/1.1\r\nContent-Len"));
+        assert_eq!(Some(headers.len() + 12), request_len(headers));
+
+        // En una conexión keep-alive, el request siguiente no forma parte del anterior.
+        let pipelined = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\nGET /keys HTTP/1.1\r\n\r\n";
+        assert_eq!(Some(35), request_len(pipelined));
+    }
+
+    #[test]
+    fn keep_alive_depends_on_the_version_and_connection_header() {
+        assert!(keep_alive(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n"));
+        assert!(!keep_alive(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n"));
+        assert!(!keep_alive(b"GET / HTTP/1.0\r\nHost: localhost\r\n\r\n"));
+        assert!(keep_alive(
+            b"GET / HTTP/1.0\r\nConnection: Keep-Alive\r\n\r\n"
+        ));
+    }
+
+    #[test]
+    fn key_resource_routes_map_to_commands() {
+        let get = parse_command_rest(b"GET /keys/user%3A1 HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            Some(vec!["get".to_string(), "user:1".to_string()]),
+            key_command(&get)
+        );
+
+        let put =
+            parse_command_rest(b"PUT /keys/name HTTP/1.1\r\nContent-Length: 9\r\n\r\nJohn+Doe!");
+        let expected = vec![
+            "set".to_string(),
+            "name".to_string(),
+            "John+Doe!".to_string(),
+        ];
+        assert_eq!(Some(expected), key_command(&put));
+
+        let delete = parse_command_rest(b"DELETE /keys/name HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            Some(vec!["del".to_string(), "name".to_string()]),
+            key_command(&delete)
+        );
+    }
+
+    #[test]
+    fn keys_route_uses_the_pattern_query() {
+        let keys = parse_command_rest(b"GET /keys?pattern=user%3A* HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            Some(vec!["keys".to_string(), "user:*".to_string()]),
+            key_command(&keys)
+        );
+
+        let all = parse_command_rest(b"GET /keys HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            Some(vec!["keys".to_string(), "*".to_string()]),
+            key_command(&all)
+        );
+
+        let index = parse_command_rest(b"GET / HTTP/1.1\r\n\r\n");
+        assert_eq!(None, key_command(&index));
+        let empty_key = parse_command_rest(b"DELETE /keys/ HTTP/1.1\r\n\r\n");
+        assert_eq!(None, key_command(&empty_key));
+    }
+
+    #[test]
+    fn subscribe_route_gets_the_channel() {
+        let subscribe = parse_command_rest(b"GET /subscribe/news%3Asports HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            Some("news:sports".to_string()),
+            subscribe_channel(&subscribe)
+        );
+
+        let keys = parse_command_rest(b"GET /keys/news HTTP/1.1\r\n\r\n");
+        assert_eq!(None, subscribe_channel(&keys));
+        let empty = parse_command_rest(b"GET /subscribe/ HTTP/1.1\r\n\r\n");
+        assert_eq!(None, subscribe_channel(&empty));
+    }
+
+    #[test]
+    fn header_ignores_the_name_case() {
+        let request = b"GET / HTTP/1.1\r\nauthorization: Bearer secret\r\n\r\n";
+        assert_eq!(
+            Some("Bearer secret".to_string()),
+            header(request, "Authorization")
+        );
+        assert_eq!(None, header(request, "Accept"));
+    }
+}
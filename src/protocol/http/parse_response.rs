@@ -22,60 +22,78 @@ const EMPTY_LIST_SET: &str = "(empty list or set)";
 /// * `redis_element` - Respuesta del comando
 pub fn parse_response_rest(redis_element: RedisElement) -> String {
     match redis_element {
-        RedisElement::String(string) => {
-            if let Ok(number) = convert_to_number(string.clone()) {
-                [INTEGER.to_string(), number.to_string()].concat()
-            } else {
-                [STRING.to_string(), string, STRING.to_string()].concat()
-            }
-        }
+        RedisElement::String(string) => [STRING.to_string(), string, STRING.to_string()].concat(),
+        RedisElement::Bytes(bytes) => [
+            STRING.to_string(),
+            String::from_utf8_lossy(&bytes).into_owned(),
+            STRING.to_string(),
+        ]
+        .concat(),
+        RedisElement::Integer(integer) => [INTEGER.to_string(), integer.to_string()].concat(),
         RedisElement::List(list) => parse_list_and_set(list),
         RedisElement::Set(set) => parse_list_and_set(Vec::from_iter(set)),
+        RedisElement::SortedSet(sorted_set) => parse_list_and_set(
+            sorted_set
+                .iter()
+                .map(|(member, _)| member.to_string())
+                .collect(),
+        ),
+        RedisElement::Stream(stream) => {
+            parse_list_and_set(stream.iter().map(|(id, _)| id.to_string()).collect())
+        }
+        RedisElement::Array(array) => parse_array(array),
         RedisElement::Nil => NIL.to_string(),
         RedisElement::SimpleString(string) => string,
     }
 }
 
-/// Intenta convertir un `String` en un número `i64`.
-///
-/// En caso de que no se pueda convertir, devuelve un error representado como `String`.
+/// Parsea un `Vec<String>` al formato correspondiente para mostrar en el html.
 ///
-/// De otro modo, retorna el número representado como `i64`.
+/// Retorna un `String` con el formato correspondiente.
 ///
 /// # Arguments
 ///
-/// * `string` - String a convertir.
-fn convert_to_number(string: String) -> Result<i64, String> {
-    let number = string.parse::<i64>();
-    match number {
-        Ok(number) => Ok(number),
-        Err(_) => Err("Error intentando convertir en número".to_string()),
+/// * `vector` - Vector a parsear.
+fn parse_list_and_set(vector: Vec<String>) -> String {
+    let mut string = "".to_string();
+    if vector.is_empty() {
+        return EMPTY_LIST_SET.to_string();
+    }
+    for (count, element) in (1..).zip(vector) {
+        string = [
+            string,
+            count.to_string(),
+            START_LIST.to_string(),
+            element,
+            END_LIST.to_string(),
+        ]
+        .concat();
     }
+    string
 }
 
-/// Parsea un `Vec<String>` al formato correspondiente para mostrar en el html.
+/// Parsea un `Vec<RedisElement>` al formato correspondiente para mostrar en el html, parseando
+/// cada elemento según su tipo.
 ///
 /// Retorna un `String` con el formato correspondiente.
 ///
 /// # Arguments
 ///
 /// * `vector` - Vector a parsear.
-fn parse_list_and_set(vector: Vec<String>) -> String {
-    let mut count = 1;
-    let mut string = "".to_string();
+fn parse_array(vector: Vec<RedisElement>) -> String {
     if vector.is_empty() {
         return EMPTY_LIST_SET.to_string();
     }
-    for element in vector {
+    let mut string = "".to_string();
+    for (count, element) in (1..).zip(vector) {
         string = [
             string,
             count.to_string(),
-            START_LIST.to_string(),
-            element,
-            END_LIST.to_string(),
+            ") ".to_string(),
+            parse_response_rest(element),
+            " <br>".to_string(),
         ]
         .concat();
-        count += 1;
     }
     string
 }
@@ -0,0 +1,76 @@
+use crate::entities::redis_element::RedisElement;
+
+/// Headers de la respuesta que abre un stream de Server-Sent Events.
+pub const SSE_HEADERS: &str = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                               Cache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+
+/// Convierte un mensaje de pub/sub en un evento de Server-Sent Events.
+///
+/// La confirmación de la suscripción se envía como un evento `subscribe` con el nombre del canal
+/// y cada mensaje publicado como un evento `message` con su contenido.
+///
+/// Retorna `None` si el elemento no es un mensaje de pub/sub.
+///
+/// # Arguments
+///
+/// * `message` - Mensaje recibido por el suscriptor.
+pub fn encode_event(message: RedisElement) -> Option<String> {
+    match message {
+        RedisElement::List(parts) => match parts.as_slice() {
+            [kind, _, payload] if kind == "message" => Some(frame("message", payload)),
+            _ => None,
+        },
+        RedisElement::Array(parts) => match parts.as_slice() {
+            [RedisElement::String(kind), RedisElement::String(channel), _]
+                if kind == "subscribe" =>
+            {
+                Some(frame("subscribe", channel))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Arma un evento. Cada línea de los datos se envía en su propio campo `data`, ya que el
+/// protocolo usa los saltos de línea como separadores.
+fn frame(event: &str, data: &str) -> String {
+    let data: String = data
+        .split('\n')
+        .map(|line| format!("data: {}\n", line.trim_end_matches('\r')))
+        .collect();
+    format!("event: {}\n{}\n", event, data)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::protocol::http::sse::encode_event;
+
+    #[test]
+    fn encode_published_message() {
+        let message = RedisElement::List(vec![
+            "message".to_string(),
+            "news".to_string(),
+            "first\nsecond".to_string(),
+        ]);
+        assert_eq!(
+            Some("event: message\ndata: first\ndata: second\n\n".to_string()),
+            encode_event(message)
+        );
+    }
+
+    #[test]
+    fn encode_subscription_confirmation() {
+        let confirmation = RedisElement::Array(vec![
+            RedisElement::String("subscribe".to_string()),
+            RedisElement::String("news".to_string()),
+            RedisElement::Integer(1),
+        ]);
+        assert_eq!(
+            Some("event: subscribe\ndata: news\n\n".to_string()),
+            encode_event(confirmation)
+        );
+        assert_eq!(None, encode_event(RedisElement::Nil));
+    }
+}
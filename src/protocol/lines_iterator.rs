@@ -1,42 +1,194 @@
-use crate::protocol::decode::decode;
+use crate::protocol::decode::{bytes_needed, decode, INCOMPLETE_MSG};
 use crate::protocol::type_data::TypeData;
 use std::io::{BufRead, BufReader};
 use std::net::TcpStream;
 
 /// Iterador de líneas, enviadas por Redis, que el servidor lee.
-pub struct LinesIterator<'a> {
-    input: &'a mut BufReader<TcpStream>,
+///
+/// Acumula los bytes recibidos entre lecturas, de modo que solo devuelve comandos completos aunque
+/// lleguen fragmentados en varios paquetes, o varios comandos lleguen en un mismo paquete.
+pub struct LinesIterator<'a, R: BufRead = BufReader<TcpStream>> {
+    input: &'a mut R,
+    /// Bytes recibidos que todavía no forman un comando completo.
+    buffer: Vec<u8>,
+    /// Largo que debe alcanzar el buffer para que el comando incompleto que contiene pueda estar
+    /// completo. Evita volver a revisar el buffer en cada lectura mientras llega, por ejemplo, un
+    /// bulk string grande.
+    needed: usize,
+    /// Comando ya decodificado del buffer que todavía no fue devuelto.
+    pending: Option<TypeData>,
 }
 
-impl<'a> LinesIterator<'a> {
+impl<'a, R: BufRead> LinesIterator<'a, R> {
     /// Crea un iterador nuevo.
     ///
     /// # Arguments
     ///
     /// * `input` - `BufReader<TcpStream>`.
-    pub fn new(input: &'a mut BufReader<TcpStream>) -> Self {
-        let input = input;
-        Self { input }
+    pub fn new(input: &'a mut R) -> Self {
+        Self {
+            input,
+            buffer: Vec::new(),
+            needed: 0,
+            pending: None,
+        }
+    }
+
+    /// Indica si ya se recibió otro comando completo, que puede procesarse sin esperar a leer de
+    /// la entrada. Permite procesar todos los comandos enviados en pipeline antes de responder.
+    pub fn has_pending_command(&mut self) -> bool {
+        if self.pending.is_none() {
+            self.pending = self.decode_buffer();
+        }
+        self.pending.is_some()
+    }
+
+    /// Intenta decodificar un comando del buffer, quitando sus bytes.
+    ///
+    /// Si el comando está mal formado, se descarta el buffer y se retorna
+    /// `Some(TypeData::Error(msg))`. Si está incompleto, se retorna `None`. Las líneas vacías
+    /// enviadas con el protocolo inline se ignoran.
+    ///
+    /// Solo se decodifica una vez que el comando está completo, de modo que recibir un comando en
+    /// muchas lecturas no lo decodifica (ni reserva memoria para sus argumentos) en cada una.
+    fn decode_buffer(&mut self) -> Option<TypeData> {
+        while !self.buffer.is_empty() && self.buffer.len() >= self.needed {
+            let missing = bytes_needed(&self.buffer, 0);
+            if missing > 0 {
+                self.needed = self.buffer.len() + missing;
+                return None;
+            }
+            self.needed = 0;
+            match decode(&self.buffer, 0) {
+                Ok((data, length)) => {
+                    self.buffer.drain(..length);
+                    if data != TypeData::Array(vec![]) {
+                        return Some(data);
+                    }
+                }
+                Err(msg) if msg != INCOMPLETE_MSG => {
+                    self.buffer.clear();
+                    return Some(TypeData::Error(msg));
+                }
+                Err(_) => return None,
+            }
+        }
+        None
+    }
+
+    /// Lee los bytes disponibles en la entrada y los agrega al buffer. Devuelve `None` si la
+    /// conexión se cerró.
+    fn read_more(&mut self) -> Option<()> {
+        let available = self.input.fill_buf().ok()?;
+        if available.is_empty() {
+            return None;
+        }
+        let length = available.len();
+        self.buffer.extend_from_slice(available);
+        self.input.consume(length);
+        Some(())
     }
 }
 
-impl Iterator for LinesIterator<'_> {
+impl<R: BufRead> Iterator for LinesIterator<'_, R> {
     type Item = TypeData;
 
-    /// Función que implementa el `next`, para pedir una nueva línea.
+    /// Función que implementa el `next`, para pedir un nuevo comando.
+    ///
+    /// Se intenta decodificar el buffer. En el caso de que el comando esté incompleto, se leen más
+    /// bytes y se vuelve a intentar.
     ///
-    /// Mientras se lee una linea, se intenta decodificar.
-    /// En el caso de que no se pueda, se pide la siguiente línea.
+    /// Si se logró decodificar correctamente, se retorna el comando decodificado como
+    /// `Some(command)`. Si el comando está mal formado, se retorna `Some(TypeData::Error(msg))`.
     ///
-    /// Si se logró decodificar correctamente, se retorna la línea decodificada como `Some(line)`.
+    /// Si la conexión se cerró, se retorna `None`.
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        let mut buf = String::new();
-        while self.input.read_line(&mut buf).ok()? != 0 {
-            if let Ok(result) = decode(buf.as_bytes(), 0) {
-                let (data, _) = result;
+        loop {
+            if let Some(data) = self.pending.take().or_else(|| self.decode_buffer()) {
                 return Some(data);
             }
+            self.read_more()?;
         }
-        Some(TypeData::Error("Se ha producido un error".to_string()))
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::protocol::lines_iterator::LinesIterator;
+    use crate::protocol::type_data::TypeData;
+    use std::io::BufReader;
+
+    #[allow(dead_code)]
+    fn command(args: Vec<&str>) -> TypeData {
+        TypeData::Array(
+            args.into_iter()
+                .map(|arg| TypeData::BulkString(arg.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_command_fragmented_in_small_reads() {
+        let bytes = "*3\r\n$3\r\nset\r\n$3\r\nkey\r\n$12\r\nhello\r\nworld\r\n".as_bytes();
+        let mut input = BufReader::with_capacity(3, bytes);
+        let mut lines = LinesIterator::new(&mut input);
+
+        assert_eq!(
+            Some(command(vec!["set", "key", "hello\r\nworld"])),
+            lines.next()
+        );
+        assert_eq!(None, lines.next());
+    }
+
+    #[test]
+    fn test_pipelined_commands() {
+        let bytes = "*1\r\n$4\r\nping\r\n\r\nget key\r\n*2\r\n$3\r\nget\r\n$1\r\nk\r\n".as_bytes();
+        let mut input = BufReader::new(bytes);
+        let lines = LinesIterator::new(&mut input);
+
+        assert_eq!(
+            vec![
+                command(vec!["ping"]),
+                command(vec!["get", "key"]),
+                command(vec!["get", "k"]),
+            ],
+            lines.collect::<Vec<TypeData>>()
+        );
+    }
+
+    #[test]
+    fn test_incomplete_command_at_end_of_input() {
+        let bytes = "*2\r\n$3\r\nget\r\n".as_bytes();
+        let mut input = BufReader::new(bytes);
+        let mut lines = LinesIterator::new(&mut input);
+
+        assert_eq!(None, lines.next());
+    }
+
+    #[test]
+    fn test_bulk_string_waits_for_all_its_bytes() {
+        let bytes = "*2\r\n$3\r\nget\r\n$10\r\n0123456789\r\n".as_bytes();
+        let mut input = BufReader::with_capacity(20, bytes);
+        let mut lines = LinesIterator::new(&mut input);
+
+        lines.read_more();
+        assert!(!lines.has_pending_command());
+        assert_eq!(30, lines.needed);
+        assert_eq!(Some(command(vec!["get", "0123456789"])), lines.next());
+        assert_eq!(0, lines.needed);
+    }
+
+    #[test]
+    fn test_has_pending_command() {
+        let bytes = "ping\r\nget key\r\n*1\r\n$4\r\nping".as_bytes();
+        let mut input = BufReader::new(bytes);
+        let mut lines = LinesIterator::new(&mut input);
+
+        assert!(!lines.has_pending_command());
+        assert_eq!(Some(command(vec!["ping"])), lines.next());
+        assert!(lines.has_pending_command());
+        assert!(lines.has_pending_command());
+        assert_eq!(Some(command(vec!["get", "key"])), lines.next());
+        assert!(!lines.has_pending_command());
     }
 }
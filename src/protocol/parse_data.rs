@@ -1,6 +1,8 @@
 use crate::entities::redis_element::RedisElement;
-use crate::protocol::encode::encode;
+use crate::protocol::encode::{encode, encode_array_header, encode_to};
 use crate::protocol::type_data::TypeData;
+use std::io;
+use std::io::Write;
 use std::iter::FromIterator;
 
 /// Parsea el comando recibido (`TypeData`) a un `Vec<String>`.
@@ -16,13 +18,47 @@ pub fn parse_command(data: TypeData) -> Vec<String> {
     empty_vector
 }
 
-/// Parsea la respuesta de un comando, en caso de éxito, a bytes (`Vec<u8>`).
+/// Escribe la respuesta de un comando, en caso de éxito, codificada con el protocolo RESP.
+///
+/// Las listas y sets se codifican de a un elemento, por lo que las respuestas con muchos elementos
+/// se escriben a medida que se generan (en bloques, si `writer` es un `BufWriter`) sin armar antes
+/// toda la respuesta en memoria.
 ///
 /// # Arguments
 ///
 /// * `redis_element` - Respuesta de un comando, representado como `RedisElement`.
-pub fn parse_response_ok(redis_element: RedisElement) -> Vec<u8> {
-    encode(parse_response(redis_element))
+/// * `writer` - Destino en el cual se escribe la respuesta.
+pub fn write_response_ok<W: Write>(redis_element: RedisElement, writer: &mut W) -> io::Result<()> {
+    match redis_element {
+        RedisElement::List(list) => write_list_and_set(list.len(), list.into_iter(), writer),
+        RedisElement::Set(set) => write_list_and_set(set.len(), set.into_iter(), writer),
+        RedisElement::SortedSet(sorted_set) => write_list_and_set(
+            sorted_set.len(),
+            sorted_set.iter().map(|(member, _)| member.to_string()),
+            writer,
+        ),
+        RedisElement::Array(array) => {
+            encode_array_header(array.len(), writer)?;
+            for element in array {
+                write_response_ok(element, writer)?;
+            }
+            Ok(())
+        }
+        redis_element => encode_to(&parse_response(redis_element), writer),
+    }
+}
+
+/// Escribe los elementos de una lista o set como un array RESP, codificando de a un elemento.
+fn write_list_and_set<W: Write>(
+    len: usize,
+    elements: impl Iterator<Item = String>,
+    writer: &mut W,
+) -> io::Result<()> {
+    encode_array_header(len, writer)?;
+    for element in elements {
+        encode_to(&parse_response(RedisElement::String(element)), writer)?;
+    }
+    Ok(())
 }
 
 /// Parsea la respuesta de un comando, en caso de error, a bytes (`Vec<u8>`).
@@ -41,15 +77,23 @@ pub fn parse_response_error(error: String) -> Vec<u8> {
 /// * `redis_element` - Redis element.
 fn parse_response(redis_element: RedisElement) -> TypeData {
     match redis_element {
-        RedisElement::String(string) => {
-            let number = string.parse::<i64>();
-            match number {
-                Ok(number) => TypeData::Integer(number),
-                Err(_) => TypeData::BulkString(string),
-            }
-        }
+        RedisElement::String(string) => TypeData::BulkString(string),
+        RedisElement::Bytes(bytes) => TypeData::BulkBytes(bytes),
+        RedisElement::Integer(integer) => TypeData::Integer(integer),
         RedisElement::List(list) => parse_list_and_set(list),
         RedisElement::Set(set) => parse_list_and_set(Vec::from_iter(set)),
+        RedisElement::SortedSet(sorted_set) => parse_list_and_set(
+            sorted_set
+                .iter()
+                .map(|(member, _)| member.to_string())
+                .collect(),
+        ),
+        RedisElement::Stream(stream) => {
+            parse_list_and_set(stream.iter().map(|(id, _)| id.to_string()).collect())
+        }
+        RedisElement::Array(array) => {
+            TypeData::Array(array.into_iter().map(parse_response).collect())
+        }
         RedisElement::Nil => TypeData::Nil,
         RedisElement::SimpleString(string) => TypeData::String(string),
     }
@@ -106,3 +150,47 @@ fn parse_type_data(type_data: TypeData) -> Result<String, String> {
         _ => Err("Error tipo de dato".to_string()),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::protocol::parse_data::write_response_ok;
+
+    fn written(redis_element: RedisElement) -> String {
+        let mut bytes = Vec::new();
+        write_response_ok(redis_element, &mut bytes).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_numeric_strings_are_encoded_as_bulk_strings() {
+        assert_eq!(
+            "$3\r\n123\r\n",
+            written(RedisElement::String("123".to_string()))
+        );
+        assert_eq!(
+            "*2\r\n$1\r\n1\r\n$2\r\n-2\r\n",
+            written(RedisElement::List(vec!["1".to_string(), "-2".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_integers_and_nil_are_encoded_with_their_own_types() {
+        assert_eq!(":-2\r\n", written(RedisElement::Integer(-2)));
+        assert_eq!("$-1\r\n", written(RedisElement::Nil));
+        assert_eq!(
+            "*2\r\n$7\r\nchannel\r\n:0\r\n",
+            written(RedisElement::Array(vec![
+                RedisElement::String("channel".to_string()),
+                RedisElement::Integer(0),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_bytes_are_written_without_conversion() {
+        let mut bytes = Vec::new();
+        write_response_ok(RedisElement::Bytes(vec![0x80, b'a']), &mut bytes).unwrap();
+        assert_eq!(b"$2\r\n\x80a\r\n".to_vec(), bytes);
+    }
+}
@@ -9,6 +9,8 @@ pub enum TypeData {
     Integer(i64),
     /// Representa Bulk Strings (aquellos que tiene como primer byte `$`)
     BulkString(String),
+    /// Representa Bulk Strings cuyo contenido puede no ser texto UTF-8 válido
+    BulkBytes(Vec<u8>),
     /// Representa las matrices (aquellas que tiene como primer byte `*`)
     Array(Vec<TypeData>),
     /// Representa el nulo (`*-1\r\n`)
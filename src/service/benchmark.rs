@@ -0,0 +1,326 @@
+use crate::protocol::encode::encode;
+use crate::protocol::lines_iterator::LinesIterator;
+use crate::protocol::type_data::TypeData;
+use crate::service::random::random_index;
+use std::fmt;
+use std::io;
+use std::io::{BufReader, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Pruebas que se ejecutan si no se indica la opción `-t`.
+const DEFAULT_TESTS: &str = "ping,set,get,incr,lpush,rpush,lpop,rpop,sadd";
+
+/// Percentiles de latencia informados en el reporte de cada prueba.
+const PERCENTILES: [f64; 4] = [50.0, 95.0, 99.0, 100.0];
+
+/// Mensaje de uso del binario `benchmark`.
+pub const USAGE: &str = "Usage: benchmark [-h <host>] [-p <port>] [-c <clients>] \
+[-n <requests>] [-d <size>] [-r <keyspacelen>] [-t <tests>]";
+
+#[derive(Debug, Clone, PartialEq)]
+/// BenchmarkOptions: Opciones de la prueba de carga, con los mismos nombres que redis-benchmark.
+pub struct BenchmarkOptions {
+    /// Host del servidor (`-h`).
+    pub host: String,
+    /// Puerto del servidor (`-p`).
+    pub port: u16,
+    /// Cantidad de conexiones concurrentes (`-c`).
+    pub clients: usize,
+    /// Cantidad total de requests de cada prueba (`-n`).
+    pub requests: usize,
+    /// Tamaño en bytes de los valores enviados por SET, LPUSH, RPUSH y SADD (`-d`).
+    pub data_size: usize,
+    /// Si se indica (`-r`), las claves se eligen al azar entre esa cantidad de claves distintas.
+    pub keyspace: Option<usize>,
+    /// Pruebas a ejecutar, en orden (`-t`, separadas por coma).
+    pub tests: Vec<String>,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        BenchmarkOptions {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            clients: 50,
+            requests: 100000,
+            data_size: 3,
+            keyspace: None,
+            tests: DEFAULT_TESTS
+                .split(',')
+                .map(|test| test.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl BenchmarkOptions {
+    /// Parsea las opciones recibidas por línea de comandos. Las opciones no indicadas toman su
+    /// valor por defecto.
+    pub fn parse(argv: &[String]) -> Result<Self, String> {
+        let mut options = BenchmarkOptions::default();
+        let mut args = argv.iter();
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for option {}", flag))?;
+            match flag.as_str() {
+                "-h" => options.host = value.to_string(),
+                "-p" => options.port = parse_number(flag, value)?,
+                "-c" => options.clients = parse_number(flag, value)?,
+                "-n" => options.requests = parse_number(flag, value)?,
+                "-d" => options.data_size = parse_number(flag, value)?,
+                "-r" => options.keyspace = Some(parse_number(flag, value)?),
+                "-t" => {
+                    options.tests = value
+                        .split(',')
+                        .map(|test| test.trim().to_lowercase())
+                        .filter(|test| !test.is_empty())
+                        .collect()
+                }
+                _ => return Err(format!("Unknown option {}", flag)),
+            }
+        }
+
+        if options.clients == 0 || options.requests == 0 || options.keyspace == Some(0) {
+            return Err("Clients, requests and keyspace length must be positive".to_string());
+        }
+        if let Some(test) = options
+            .tests
+            .iter()
+            .find(|test| bench_command(test, "key", "x").is_none())
+        {
+            return Err(format!("Unknown test {}", test));
+        }
+        Ok(options)
+    }
+
+    /// Devuelve la clave a usar en un request: una clave fija, o una al azar si se indicó `-r`.
+    fn key(&self, prefix: &str) -> String {
+        match self.keyspace {
+            Some(keyspace) => format!("{}:{:012}", prefix, random_index(keyspace)),
+            None => format!("{}:__rand_int__", prefix),
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for option {}", value, flag))
+}
+
+/// Arma el comando que envía la prueba indicada, o None si la prueba no existe.
+fn bench_command(test: &str, key: &str, value: &str) -> Option<Vec<String>> {
+    let command = match test {
+        "ping" => vec!["PING"],
+        "set" => vec!["SET", key, value],
+        "get" => vec!["GET", key],
+        "incr" => vec!["INCR", key],
+        "lpush" => vec!["LPUSH", key, value],
+        "rpush" => vec!["RPUSH", key, value],
+        "lpop" => vec!["LPOP", key],
+        "rpop" => vec!["RPOP", key],
+        "sadd" => vec!["SADD", key, value],
+        "spop" => vec!["SPOP", key],
+        _ => return None,
+    };
+    Some(command.into_iter().map(|arg| arg.to_string()).collect())
+}
+
+/// Prefijo de las claves de cada prueba, de modo que las pruebas de un mismo tipo de dato
+/// compartan las claves (por ejemplo, LPOP extrae los elementos agregados por LPUSH).
+fn key_prefix(test: &str) -> &'static str {
+    match test {
+        "incr" => "counter",
+        "lpush" | "rpush" | "lpop" | "rpop" => "mylist",
+        "sadd" | "spop" => "myset",
+        _ => "key",
+    }
+}
+
+#[derive(Debug)]
+/// BenchmarkReport: Resultado de una prueba de carga.
+pub struct BenchmarkReport {
+    /// Nombre de la prueba.
+    pub test: String,
+    /// Cantidad de requests respondidos.
+    pub requests: usize,
+    /// Cantidad de conexiones concurrentes.
+    pub clients: usize,
+    /// Tamaño en bytes de los valores enviados.
+    pub data_size: usize,
+    /// Tiempo total de la prueba.
+    pub elapsed: Duration,
+    /// Latencia de cada request, ordenadas de menor a mayor.
+    pub latencies: Vec<Duration>,
+}
+
+impl BenchmarkReport {
+    /// Requests respondidos por segundo.
+    pub fn throughput(&self) -> f64 {
+        self.requests as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Latencia por debajo de la cual se respondió el porcentaje indicado de los requests.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::from_secs(0);
+        }
+        let rank = (percentile / 100.0 * self.latencies.len() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.latencies.len()) - 1]
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(fmt, "====== {} ======", self.test.to_uppercase())?;
+        writeln!(
+            fmt,
+            "  {} requests completed in {:.2} seconds",
+            self.requests,
+            self.elapsed.as_secs_f64()
+        )?;
+        writeln!(fmt, "  {} parallel clients", self.clients)?;
+        writeln!(fmt, "  {} bytes payload", self.data_size)?;
+        writeln!(fmt)?;
+        writeln!(fmt, "Latency percentiles (msec):")?;
+        for percentile in PERCENTILES.iter() {
+            writeln!(
+                fmt,
+                "  {:>6.2}% <= {:.3}",
+                percentile,
+                self.percentile(*percentile).as_secs_f64() * 1000.0
+            )?;
+        }
+        writeln!(fmt)?;
+        writeln!(fmt, "  {:.2} requests per second", self.throughput())
+    }
+}
+
+/// Ejecuta una prueba: abre `clients` conexiones que envían en total `requests` comandos,
+/// esperando cada respuesta antes de enviar el siguiente, y mide la latencia de cada uno.
+pub fn run_test(options: &BenchmarkOptions, test: &str) -> io::Result<BenchmarkReport> {
+    let value = "x".repeat(options.data_size);
+    let sent = Arc::new(AtomicUsize::new(0));
+    let start = Instant::now();
+
+    let mut handlers = vec![];
+    for _ in 0..options.clients {
+        let stream = TcpStream::connect((options.host.as_str(), options.port))?;
+        let options = options.clone();
+        let test = test.to_string();
+        let value = value.clone();
+        let sent = Arc::clone(&sent);
+        handlers.push(thread::spawn(move || -> io::Result<Vec<Duration>> {
+            let mut writer = stream.try_clone()?;
+            let mut reader = BufReader::new(stream);
+            let mut replies = LinesIterator::new(&mut reader);
+            let mut latencies = vec![];
+
+            while sent.fetch_add(1, Ordering::Relaxed) < options.requests {
+                let key = options.key(key_prefix(&test));
+                let command = bench_command(&test, &key, &value).unwrap_or_default();
+                let request =
+                    TypeData::Array(command.into_iter().map(TypeData::BulkString).collect());
+
+                let sent_at = Instant::now();
+                writer.write_all(&encode(request))?;
+                if replies.next().is_none() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "Connection closed by the server",
+                    ));
+                }
+                latencies.push(sent_at.elapsed());
+            }
+            Ok(latencies)
+        }));
+    }
+
+    let mut latencies = vec![];
+    for handler in handlers {
+        let client_latencies = handler
+            .join()
+            .map_err(|_| io::Error::other("Benchmark client panicked"))??;
+        latencies.extend(client_latencies);
+    }
+    latencies.sort();
+
+    Ok(BenchmarkReport {
+        test: test.to_string(),
+        requests: latencies.len(),
+        clients: options.clients,
+        data_size: options.data_size,
+        elapsed: start.elapsed(),
+        latencies,
+    })
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::service::benchmark::{bench_command, BenchmarkOptions, BenchmarkReport};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_options() {
+        let argv: Vec<String> = vec![
+            "-p", "6380", "-c", "4", "-n", "10", "-t", "SET, get", "-r", "100",
+        ]
+        .into_iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let options = BenchmarkOptions::parse(&argv).unwrap();
+
+        assert_eq!("127.0.0.1", options.host);
+        assert_eq!(6380, options.port);
+        assert_eq!(4, options.clients);
+        assert_eq!(10, options.requests);
+        assert_eq!(Some(100), options.keyspace);
+        assert_eq!(vec!["set".to_string(), "get".to_string()], options.tests);
+
+        for argv in [
+            vec!["-c"],
+            vec!["-c", "0"],
+            vec!["-x", "1"],
+            vec!["-t", "flushall"],
+        ] {
+            let argv: Vec<String> = argv.into_iter().map(|arg| arg.to_string()).collect();
+            assert!(BenchmarkOptions::parse(&argv).is_err());
+        }
+    }
+
+    #[test]
+    fn test_bench_command() {
+        assert_eq!(
+            Some(vec![
+                "SET".to_string(),
+                "key".to_string(),
+                "xxx".to_string()
+            ]),
+            bench_command("set", "key", "xxx")
+        );
+        assert_eq!(None, bench_command("flushall", "key", "xxx"));
+    }
+
+    #[test]
+    fn test_report_percentiles_and_throughput() {
+        let report = BenchmarkReport {
+            test: "get".to_string(),
+            requests: 4,
+            clients: 1,
+            data_size: 3,
+            elapsed: Duration::from_secs(2),
+            latencies: (1..=4).map(Duration::from_millis).collect(),
+        };
+
+        assert_eq!(Duration::from_millis(2), report.percentile(50.0));
+        assert_eq!(Duration::from_millis(4), report.percentile(99.0));
+        assert_eq!(Duration::from_millis(4), report.percentile(100.0));
+        assert!((report.throughput() - 2.0).abs() < f64::EPSILON);
+        assert!(report.to_string().contains("2.00 requests per second"));
+    }
+}
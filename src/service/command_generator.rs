@@ -1,6 +1,26 @@
+use crate::entities::bitmap::{BitOperation, BitRange, BitUnit};
+use crate::entities::client_param::{ClientParam, KillFilter};
+use crate::entities::cluster_param::ClusterParam;
 use crate::entities::command::Command;
+use crate::entities::command_param::CommandParam;
+use crate::entities::command_table;
+use crate::entities::consumer_group_param::{PendingRange, XgroupParam};
+use crate::entities::debug_param::DebugParam;
+use crate::entities::expire_condition::ExpireCondition;
+use crate::entities::geo;
+use crate::entities::geo::{GeoAddOptions, GeoOrigin, GeoSearchOptions, GeoShape, GeoUnit};
 use crate::entities::info_param::InfoParam;
+use crate::entities::memory::DEFAULT_SAMPLES;
+use crate::entities::memory_param::MemoryParam;
+use crate::entities::object_param::ObjectParam;
 use crate::entities::pubsub_param::PubSubParam;
+use crate::entities::script_param::ScriptParam;
+use crate::entities::set_options::{SetCondition, SetExpiration, SetOptions};
+use crate::entities::slowlog::DEFAULT_SLOWLOG_COUNT;
+use crate::entities::slowlog_param::SlowlogParam;
+use crate::entities::sort_options::SortOptions;
+use crate::entities::sorted_set::{Aggregate, ScoreBound};
+use crate::entities::stream::StreamId;
 use core::time::Duration;
 use std::collections::HashSet;
 use std::iter::FromIterator;
@@ -8,49 +28,90 @@ use std::time::SystemTime;
 
 #[allow(dead_code)]
 /// Generador de comandos validos a partir de listado de strings provenientes del Cliente.
-pub fn generate(params: Vec<String>, client_id: String) -> Result<Command, String> {
+pub fn generate(params: Vec<String>, client_id: u64) -> Result<Command, String> {
     if params.is_empty() {
         return Err("Params can't be empty".to_string());
     }
 
-    let command = params.first().unwrap();
+    let command = params.first().unwrap().to_lowercase();
+    if let Some(spec) = command_table::lookup(&command) {
+        if !spec.accepts(params.len()) {
+            return Err(format!(
+                "ERR wrong number of arguments for '{}' command",
+                command
+            ));
+        }
+    }
+
     let params = Vec::from(params.get(1..).unwrap());
-    match command.to_lowercase().as_str() {
+    match command.as_str() {
         // Server
         "ping" => generate_ping(params),
+        "echo" => generate_echo(params),
         "flushdb" => generate_flushdb(params),
+        "flushall" => generate_flushall(params),
         "dbsize" => generate_dbsize(params),
-        "monitor" => generate_monitor(params),
+        "monitor" => generate_monitor(params, client_id),
+        "quit" => Ok(Command::Quit),
         "info" => generate_info(params),
 
         "store" => generate_store(params),
         "load" => generate_load(params),
         "config" => generate_config(params),
+        "auth" => generate_auth(params),
+        "client" => generate_client(params, client_id),
+        "cluster" => generate_cluster(params),
+        "memory" => generate_memory(params),
+        "slowlog" => generate_slowlog(params),
+        "debug" => generate_debug(params),
 
         // Strings
         "get" => generate_get(params),
         "getset" => generate_getset(params),
         "set" => generate_set(params),
+        "setnx" => generate_setnx(params),
+        "setex" => generate_setex(params, "setex", Duration::from_secs),
+        "psetex" => generate_setex(params, "psetex", Duration::from_millis),
         "incrby" => generate_incrby(params),
         "decrby" => generate_decrby(params),
+        "incrbyfloat" => generate_incrbyfloat(params),
         "getdel" => generate_getdel(params),
         "append" => generate_append(params),
         "mget" => generate_mget(params),
         "mset" => generate_mset(params),
+        "msetnx" => generate_msetnx(params),
         "strlen" => generate_strlen(params),
 
+        // Bitmaps
+        "setbit" => generate_setbit(params),
+        "getbit" => generate_getbit(params),
+        "bitcount" => generate_bitcount(params),
+        "bitpos" => generate_bitpos(params),
+        "bitop" => generate_bitop(params),
+
         // Keys
         "copy" => generate_copy(params),
         "del" => generate_del(params),
+        "dump" => generate_dump(params),
         "exists" => generate_exists(params),
         "expire" => generate_expire(params),
         "expireat" => generate_expireat(params),
         "persist" => generate_persist(params),
+        "randomkey" => generate_randomkey(params),
         "rename" => generate_rename(params),
+        "renamenx" => generate_renamenx(params),
+        "restore" => generate_restore(params),
         "sort" => generate_sort(params),
+        "object" => generate_object(params),
         "touch" => generate_touch(params),
         "ttl" => generate_ttl(params),
+        "pexpire" => generate_pexpire(params),
+        "pexpireat" => generate_pexpireat(params),
+        "pttl" => generate_pttl(params),
+        "expiretime" => generate_expiretime(params),
+        "pexpiretime" => generate_pexpiretime(params),
         "type" => generate_type(params),
+        "unlink" => generate_unlink(params),
 
         // Lists
         "lindex" => generate_lindex(params),
@@ -64,21 +125,70 @@ pub fn generate(params: Vec<String>, client_id: String) -> Result<Command, Strin
         "rpop" => generate_rpop(params),
         "rpush" => generate_rpush(params),
         "rpushx" => generate_rpushx(params),
+        "blpop" => generate_blpop(params),
+        "brpop" => generate_brpop(params),
 
         //Sets
         "sadd" => generate_sadd(params),
         "scard" => generate_scard(params),
         "sismember" => generate_sismember(params),
         "smembers" => generate_smembers(params),
+        "smismember" => generate_smismember(params),
+        "sinter" => generate_sinter(params),
+        "sintercard" => generate_sintercard(params),
+        "sunion" => generate_sunion(params),
+        "sdiff" => generate_sdiff(params),
+        "spop" => generate_spop(params),
+        "srandmember" => generate_srandmember(params),
         "srem" => generate_srem(params),
+        "sscan" => generate_sscan(params),
         "keys" => generate_keys(params),
 
+        //Sorted Sets
+        "bzpopmax" => generate_bzpop(params, "bzpopmax"),
+        "bzpopmin" => generate_bzpop(params, "bzpopmin"),
+        "zadd" => generate_zadd(params),
+        "zcard" => generate_zcard(params),
+        "zincrby" => generate_zincrby(params),
+        "zinterstore" => generate_zstore(params, "zinterstore"),
+        "zpopmax" => generate_zpop(params, "zpopmax"),
+        "zpopmin" => generate_zpop(params, "zpopmin"),
+        "zrange" => generate_zrange(params),
+        "zrangebyscore" => generate_zrangebyscore(params),
+        "zrank" => generate_zrank(params),
+        "zrem" => generate_zrem(params),
+        "zscore" => generate_zscore(params),
+        "zunionstore" => generate_zstore(params, "zunionstore"),
+
+        //Geo
+        "geoadd" => generate_geoadd(params),
+        "geodist" => generate_geodist(params),
+        "geopos" => generate_geopos(params),
+        "geosearch" => generate_geosearch(params),
+
+        //Streams
+        "xadd" => generate_xadd(params),
+        "xlen" => generate_xlen(params),
+        "xrange" => generate_xrange(params, false),
+        "xrevrange" => generate_xrange(params, true),
+        "xread" => generate_xread(params),
+        "xgroup" => generate_xgroup(params),
+        "xreadgroup" => generate_xreadgroup(params),
+        "xack" => generate_xack(params),
+        "xpending" => generate_xpending(params),
+        "xclaim" => generate_xclaim(params),
+
         //PubSub
         "pubsub" => generate_pubsub(params),
         "subscribe" => generate_subscribe(params, client_id),
         "publish" => generate_publish(params),
         "unsubscribe" => Ok(generate_unsubscribe(params, client_id)),
-        "command" => Ok(Command::Command),
+        "command" => generate_command(params),
+
+        //Scripting
+        "eval" => generate_eval(params, false),
+        "evalsha" => generate_eval(params, true),
+        "script" => generate_script(params),
 
         _ => Err("Command not valid".to_string()),
     }
@@ -90,16 +200,29 @@ fn generate_ping(params: Vec<String>) -> Result<Command, String> {
         return Err("ERR wrong number of arguments for 'ping' command".to_string());
     }
 
-    Ok(Command::Ping)
+    Ok(Command::Ping {
+        message: params.first().cloned(),
+    })
+}
+
+/// Generador de comando Command::Echo.
+fn generate_echo(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'echo' command".to_string());
+    }
+
+    Ok(Command::Echo {
+        message: params[0].clone(),
+    })
 }
 
 /// Generador de comando Command::Monitor.
-fn generate_monitor(params: Vec<String>) -> Result<Command, String> {
+fn generate_monitor(params: Vec<String>, client_id: u64) -> Result<Command, String> {
     if params.len() > 1 {
         return Err("ERR wrong number of arguments for 'monitor' command".to_string());
     }
 
-    Ok(Command::Monitor)
+    Ok(Command::Monitor { client_id })
 }
 
 /// Generador de comando Command::Info
@@ -127,6 +250,18 @@ fn generate_info(params: Vec<String>) -> Result<Command, String> {
         "connectedclients" => Ok(Command::Info {
             param: InfoParam::ConnectedClients,
         }),
+        "replication" => Ok(Command::Info {
+            param: InfoParam::Replication,
+        }),
+        "commandstats" => Ok(Command::Info {
+            param: InfoParam::Commandstats,
+        }),
+        "stats" => Ok(Command::Info {
+            param: InfoParam::Stats,
+        }),
+        "memory" => Ok(Command::Info {
+            param: InfoParam::Memory,
+        }),
         _ => Err("ERR wrong command param".to_string()),
     }
 }
@@ -146,19 +281,382 @@ fn generate_config(params: Vec<String>) -> Result<Command, String> {
             let value = params[2].clone();
             Ok(Command::ConfigSet { parameter, value })
         }
-        "get" => Ok(Command::ConfigGet),
+        "get" => {
+            if params.len() < 2 {
+                return Err("ERR wrong number of arguments for 'config get' command".to_string());
+            }
+            Ok(Command::ConfigGet {
+                patterns: params[1..].to_vec(),
+            })
+        }
+        "resetstat" if params.len() == 1 => Ok(Command::ConfigResetstat),
         _ => Err("ERR wrong arguments for 'config' command".to_string()),
     }
 }
 
+/// Generador de comando Command::Auth
+///
+/// Acepta la forma `AUTH password` y la forma `AUTH username password`, en cuyo caso el usuario
+/// se ignora ya que el servidor solo cuenta con el usuario por defecto.
+fn generate_auth(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() || params.len() > 2 {
+        return Err("ERR wrong number of arguments for 'auth' command".to_string());
+    }
+
+    let password = params.last().unwrap().to_string();
+    Ok(Command::Auth { password })
+}
+
+/// Generador de comando Command::Client
+fn generate_client(params: Vec<String>, client_id: u64) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'client' command".to_string());
+    }
+
+    let param = match (params[0].to_lowercase().as_str(), params.len()) {
+        ("id", 1) => ClientParam::Id,
+        ("list", 1) => ClientParam::List,
+        ("info", 1) => ClientParam::Info,
+        ("setname", 2) => ClientParam::SetName(params[1].clone()),
+        ("getname", 1) => ClientParam::GetName,
+        ("kill", 2) => ClientParam::KillAddr(params[1].clone()),
+        ("kill", len) if len > 2 => ClientParam::Kill(generate_kill_filter(&params[1..])?),
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Client { param, client_id })
+}
+
+/// Generador de comando Command::Cluster
+fn generate_cluster(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'cluster' command".to_string());
+    }
+
+    let param = match (params[0].to_lowercase().as_str(), params.len()) {
+        ("keyslot", 2) => ClusterParam::KeySlot(params[1].clone()),
+        ("slots", 1) => ClusterParam::Slots,
+        ("info", 1) => ClusterParam::Info,
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Cluster { param })
+}
+
+/// Generador de comando Command::Command
+fn generate_command(params: Vec<String>) -> Result<Command, String> {
+    let subcommand = match params.first() {
+        Some(subcommand) => subcommand.to_lowercase(),
+        None => {
+            return Ok(Command::Command {
+                param: CommandParam::All,
+            })
+        }
+    };
+
+    let param = match (subcommand.as_str(), params.len()) {
+        ("count", 1) => CommandParam::Count,
+        ("info", _) => CommandParam::Info(params[1..].to_vec()),
+        ("docs", _) => CommandParam::Docs(params[1..].to_vec()),
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Command { param })
+}
+
+/// Generador de comando Command::Memory
+fn generate_memory(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'memory' command".to_string());
+    }
+
+    let param = match (params[0].to_lowercase().as_str(), params.len()) {
+        ("usage", 2) => MemoryParam::Usage {
+            key: params[1].clone(),
+            samples: DEFAULT_SAMPLES,
+        },
+        ("usage", 4) if params[2].eq_ignore_ascii_case("samples") => {
+            let samples = parse_integer(&params[3])?;
+            if samples < 0 {
+                return Err("ERR syntax error".to_string());
+            }
+            MemoryParam::Usage {
+                key: params[1].clone(),
+                samples: samples as usize,
+            }
+        }
+        ("usage", len) if len > 2 => return Err("ERR syntax error".to_string()),
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Memory { param })
+}
+
+/// Generador de comando Command::Slowlog
+fn generate_slowlog(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'slowlog' command".to_string());
+    }
+
+    let param = match (params[0].to_lowercase().as_str(), params.len()) {
+        ("get", 1) => SlowlogParam::Get {
+            count: DEFAULT_SLOWLOG_COUNT,
+        },
+        // Como en Redis, un count negativo devuelve todas las entradas.
+        ("get", 2) => match parse_integer(&params[1])? {
+            count if count < 0 => SlowlogParam::Get { count: usize::MAX },
+            count => SlowlogParam::Get {
+                count: count as usize,
+            },
+        },
+        ("len", 1) => SlowlogParam::Len,
+        ("reset", 1) => SlowlogParam::Reset,
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Slowlog { param })
+}
+
+/// Generador de comando Command::Debug
+fn generate_debug(params: Vec<String>) -> Result<Command, String> {
+    let param = match (params[0].to_lowercase().as_str(), params.len()) {
+        ("sleep", 2) => match params[1].parse::<f64>() {
+            Ok(seconds) if seconds.is_finite() => DebugParam::Sleep {
+                duration: Duration::from_secs_f64(seconds.clamp(0.0, u32::MAX as f64)),
+            },
+            _ => return Err("ERR value is not a valid float".to_string()),
+        },
+        ("set-active-expire", 2) => DebugParam::SetActiveExpire {
+            enabled: parse_integer(&params[1])? != 0,
+        },
+        ("object", 2) => DebugParam::Object {
+            key: params[1].clone(),
+        },
+        // JMAP vuelca el heap de la JVM en Redis on Flash, que no tiene equivalente en este servidor.
+        ("jmap", _) => return Err("ERR DEBUG JMAP is not supported by this server".to_string()),
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Debug { param })
+}
+
+/// Parsea los pares `<filtro> <valor>` de CLIENT KILL.
+fn generate_kill_filter(params: &[String]) -> Result<KillFilter, String> {
+    if !params.len().is_multiple_of(2) {
+        return Err("ERR syntax error".to_string());
+    }
+
+    let mut filter = KillFilter {
+        skipme: true,
+        ..KillFilter::default()
+    };
+    for pair in params.chunks(2) {
+        let value = pair[1].clone();
+        match pair[0].to_lowercase().as_str() {
+            "id" => match value.parse::<u64>() {
+                Ok(id) if id > 0 => filter.id = Some(id),
+                _ => return Err("ERR client-id should be greater than 0".to_string()),
+            },
+            "addr" => filter.addr = Some(value),
+            "skipme" => match value.to_lowercase().as_str() {
+                "yes" => filter.skipme = true,
+                "no" => filter.skipme = false,
+                _ => return Err("ERR syntax error".to_string()),
+            },
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+    Ok(filter)
+}
+
 /// Generador de comando Command::Flushdb
 fn generate_flushdb(params: Vec<String>) -> Result<Command, String> {
     if params.len() > 1 {
         return Err("ERR wrong number of arguments for 'flushdb' command".to_string());
     }
 
-    Ok(Command::Flushdb)
+    let asynchronous = parse_flush_mode(&params)?;
+    Ok(Command::Flushdb { asynchronous })
+}
+
+/// Generador de comando Command::Flushall
+fn generate_flushall(params: Vec<String>) -> Result<Command, String> {
+    if params.len() > 1 {
+        return Err("ERR wrong number of arguments for 'flushall' command".to_string());
+    }
+
+    let asynchronous = parse_flush_mode(&params)?;
+    Ok(Command::Flushall { asynchronous })
+}
+
+/// Parsea la opción `ASYNC`/`SYNC` de FLUSHDB y FLUSHALL. Devuelve si el borrado es asincrónico.
+fn parse_flush_mode(params: &[String]) -> Result<bool, String> {
+    match params.first().map(|mode| mode.to_lowercase()) {
+        None => Ok(false),
+        Some(mode) if mode == "sync" => Ok(false),
+        Some(mode) if mode == "async" => Ok(true),
+        Some(_) => Err("ERR syntax error".to_string()),
+    }
+}
+
+/// Generador de comando Command::Setbit
+fn generate_setbit(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'setbit' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let offset = parse_bit_offset(&params[1])?;
+    let value = match params[2].as_str() {
+        "0" => false,
+        "1" => true,
+        _ => return Err("ERR bit is not an integer or out of range".to_string()),
+    };
+
+    Ok(Command::Setbit { key, offset, value })
+}
+
+/// Generador de comando Command::Getbit
+fn generate_getbit(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'getbit' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let offset = parse_bit_offset(&params[1])?;
+    Ok(Command::Getbit { key, offset })
+}
+
+/// Generador de comando Command::Bitcount
+///
+/// La forma del comando es `BITCOUNT key [start end [BYTE | BIT]]`.
+fn generate_bitcount(params: Vec<String>) -> Result<Command, String> {
+    let range = match params.len() {
+        1 => None,
+        3 | 4 => Some(BitRange {
+            start: parse_integer(&params[1])?,
+            end: parse_integer(&params[2])?,
+            unit: parse_bit_unit(params.get(3))?,
+        }),
+        0 => return Err("ERR wrong number of arguments for 'bitcount' command".to_string()),
+        _ => return Err("ERR syntax error".to_string()),
+    };
+
+    let key = params[0].clone();
+    Ok(Command::Bitcount { key, range })
+}
+
+/// Generador de comando Command::Bitpos
+///
+/// La forma del comando es `BITPOS key bit [start [end [BYTE | BIT]]]`.
+fn generate_bitpos(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 || params.len() > 5 {
+        return Err("ERR wrong number of arguments for 'bitpos' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let bit = match params[1].as_str() {
+        "0" => false,
+        "1" => true,
+        _ => return Err("ERR The bit argument must be 1 or 0.".to_string()),
+    };
+    let start = match params.get(2) {
+        Some(start) => parse_integer(start)?,
+        None => 0,
+    };
+    let end = match params.get(3) {
+        Some(end) => Some(parse_integer(end)?),
+        None => None,
+    };
+    let unit = parse_bit_unit(params.get(4))?;
+
+    Ok(Command::Bitpos {
+        key,
+        bit,
+        start,
+        end,
+        unit,
+    })
+}
+
+/// Generador de comando Command::Bitop
+///
+/// La forma del comando es `BITOP AND | OR | XOR | NOT destkey key [key ...]`.
+fn generate_bitop(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err("ERR wrong number of arguments for 'bitop' command".to_string());
+    }
+
+    let operation = match params[0].to_lowercase().as_str() {
+        "and" => BitOperation::And,
+        "or" => BitOperation::Or,
+        "xor" => BitOperation::Xor,
+        "not" => BitOperation::Not,
+        _ => return Err("ERR syntax error".to_string()),
+    };
+    let destkey = params[1].clone();
+    let keys = Vec::from(&params[2..]);
+
+    if operation == BitOperation::Not && keys.len() != 1 {
+        return Err("ERR BITOP NOT must be called with a single source key.".to_string());
+    }
+
+    Ok(Command::Bitop {
+        operation,
+        destkey,
+        keys,
+    })
+}
+
+/// Parsea el offset de un bit, que debe ser un entero entre 0 y 2^32 - 1.
+fn parse_bit_offset(offset: &str) -> Result<usize, String> {
+    match offset.parse::<u32>() {
+        Ok(offset) => Ok(offset as usize),
+        Err(_) => Err("ERR bit offset is not an integer or out of range".to_string()),
+    }
+}
+
+/// Parsea la unidad de un rango de bitmap. Si no se indica, el rango se expresa en bytes.
+fn parse_bit_unit(unit: Option<&String>) -> Result<BitUnit, String> {
+    match unit.map(|unit| unit.to_lowercase()) {
+        None => Ok(BitUnit::Byte),
+        Some(unit) if unit == "byte" => Ok(BitUnit::Byte),
+        Some(unit) if unit == "bit" => Ok(BitUnit::Bit),
+        Some(_) => Err("ERR syntax error".to_string()),
+    }
+}
+
+/// Parsea un entero con signo.
+fn parse_integer(value: &str) -> Result<i64, String> {
+    value
+        .parse::<i64>()
+        .map_err(|_| "ERR value is not an integer or out of range".to_string())
 }
+
 /// Generador de comando Command::Copy
 fn generate_copy(params: Vec<String>) -> Result<Command, String> {
     if params.len() != 2 {
@@ -173,6 +671,56 @@ fn generate_copy(params: Vec<String>) -> Result<Command, String> {
     })
 }
 
+/// Generador de comando Command::Dump
+fn generate_dump(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'dump' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Dump { key })
+}
+
+/// Generador de comando Command::Restore
+///
+/// El ttl se indica en milisegundos (0 si la clave no expira), relativo al momento actual o, con
+/// la opción ABSTTL, como timestamp absoluto.
+fn generate_restore(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err("ERR wrong number of arguments for 'restore' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let millis = parse_integer(&params[1])?;
+    if millis < 0 {
+        return Err("ERR Invalid TTL value, must be >= 0".to_string());
+    }
+    let payload = params[2].clone();
+
+    let mut replace = false;
+    let mut absttl = false;
+    for option in params[3..].iter() {
+        match option.to_lowercase().as_str() {
+            "replace" => replace = true,
+            "absttl" => absttl = true,
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+
+    let millis = Duration::from_millis(millis as u64);
+    let ttl = match (millis.is_zero(), absttl) {
+        (true, _) => None,
+        (false, true) => Some(SystemTime::UNIX_EPOCH + millis),
+        (false, false) => Some(SystemTime::now() + millis),
+    };
+    Ok(Command::Restore {
+        key,
+        ttl,
+        payload,
+        replace,
+    })
+}
+
 /// Generador de comando Command::Get
 fn generate_get(params: Vec<String>) -> Result<Command, String> {
     if params.len() != 1 {
@@ -195,14 +743,112 @@ fn generate_getset(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Set
+///
+/// La forma del comando es
+/// `SET key value [NX | XX] [EX seconds | PX milliseconds | EXAT timestamp | PXAT timestamp | KEEPTTL]`.
 fn generate_set(params: Vec<String>) -> Result<Command, String> {
-    if params.len() != 2 {
+    if params.len() < 2 {
         return Err("ERR syntax error".to_string());
     }
 
     let key = params[0].clone();
     let value = params[1].clone();
-    Ok(Command::Set { key, value })
+    let mut options = SetOptions::default();
+
+    let mut params = params[2..].iter();
+    while let Some(option) = params.next() {
+        let option = option.to_lowercase();
+        match (option.as_str(), options.condition, options.expiration) {
+            ("nx", SetCondition::Always, _) => options.condition = SetCondition::IfNotExists,
+            ("xx", SetCondition::Always, _) => options.condition = SetCondition::IfExists,
+            ("keepttl", _, SetExpiration::Discard) => options.expiration = SetExpiration::Keep,
+            ("get", _, _) if !options.get => options.get = true,
+            ("ex", _, SetExpiration::Discard)
+            | ("px", _, SetExpiration::Discard)
+            | ("exat", _, SetExpiration::Discard)
+            | ("pxat", _, SetExpiration::Discard) => {
+                let time = match params.next() {
+                    Some(time) => parse_expire_time(time, "set")?,
+                    None => return Err("ERR syntax error".to_string()),
+                };
+                let time = match option.as_str() {
+                    "ex" | "exat" => check_expire_duration(Duration::from_secs(time), "set")?,
+                    _ => check_expire_duration(Duration::from_millis(time), "set")?,
+                };
+                options.expiration = match option.as_str() {
+                    "ex" | "px" => SetExpiration::Relative(time),
+                    _ => SetExpiration::Absolute(SystemTime::UNIX_EPOCH + time),
+                };
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+
+    Ok(Command::Set {
+        key,
+        value,
+        options,
+    })
+}
+
+/// Generador de comando Command::Set a partir de SETEX (`to_duration` en segundos) o PSETEX
+/// (`to_duration` en milisegundos).
+fn generate_setex(
+    params: Vec<String>,
+    name: &str,
+    to_duration: fn(u64) -> Duration,
+) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
+    }
+
+    let key = params[0].clone();
+    let time = parse_expire_time(&params[1], name)?;
+    let time = check_expire_duration(to_duration(time), name)?;
+    let value = params[2].clone();
+    let options = SetOptions {
+        condition: SetCondition::Always,
+        expiration: SetExpiration::Relative(time),
+        get: false,
+    };
+
+    Ok(Command::Set {
+        key,
+        value,
+        options,
+    })
+}
+
+/// Parsea un tiempo de expiración, que debe ser un entero positivo.
+fn parse_expire_time(time: &str, name: &str) -> Result<u64, String> {
+    match time.parse::<i64>() {
+        Ok(time) if time > 0 => Ok(time as u64),
+        Ok(_) => Err(format!("ERR invalid expire time in '{}' command", name)),
+        Err(_) => Err("ERR value is not an integer or out of range".to_string()),
+    }
+}
+
+/// Verifica que un tiempo de expiración no supere el máximo que admite Redis (`i64::MAX`
+/// milisegundos), para que el momento de expiración pueda calcularse sin desbordar.
+fn check_expire_duration(time: Duration, name: &str) -> Result<Duration, String> {
+    if time.as_millis() > i64::MAX as u128 {
+        return Err(format!("ERR invalid expire time in '{}' command", name));
+    }
+    Ok(time)
+}
+
+/// Generador de comando Command::Setnx
+fn generate_setnx(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'setnx' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let value = params[1].clone();
+    Ok(Command::Setnx { key, value })
 }
 
 /// Generador de comando Command::Incrby
@@ -212,7 +858,7 @@ fn generate_incrby(params: Vec<String>) -> Result<Command, String> {
     }
 
     let key = params[0].clone();
-    let increment: Result<u32, _> = params[1].to_string().parse();
+    let increment: Result<i64, _> = params[1].to_string().parse();
 
     if increment.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -229,7 +875,7 @@ fn generate_decrby(params: Vec<String>) -> Result<Command, String> {
     }
 
     let key = params[0].clone();
-    let decrement: Result<u32, _> = params[1].to_string().parse();
+    let decrement: Result<i64, _> = params[1].to_string().parse();
 
     if decrement.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -239,6 +885,21 @@ fn generate_decrby(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Decrby { key, decrement })
 }
 
+/// Generador de comando Command::Incrbyfloat
+fn generate_incrbyfloat(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'incrbyfloat' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let increment = match params[1].parse::<f64>() {
+        Ok(increment) if increment.is_finite() => increment,
+        _ => return Err("ERR value is not a valid float".to_string()),
+    };
+
+    Ok(Command::Incrbyfloat { key, increment })
+}
+
 /// Generador de comando Command::GetDel
 fn generate_getdel(params: Vec<String>) -> Result<Command, String> {
     if params.len() != 1 {
@@ -258,6 +919,100 @@ fn generate_del(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Del { keys: params })
 }
 
+/// Generador de comando Command::Pexpire
+fn generate_pexpire(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'pexpire' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let millis = params[1]
+        .parse::<u64>()
+        .map_err(|_| "ERR value is not an integer or out of range".to_string())?;
+
+    let ttl = check_expire_duration(Duration::from_millis(millis), "pexpire")?;
+    let conditions = parse_expire_conditions(&params[2..])?;
+    Ok(Command::Pexpire {
+        key,
+        ttl,
+        conditions,
+    })
+}
+
+/// Generador de comando Command::Pexpireat
+fn generate_pexpireat(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'pexpireat' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let millis = params[1]
+        .parse::<u64>()
+        .map_err(|_| "ERR value is not an integer or out of range".to_string())?;
+
+    let ttl =
+        SystemTime::UNIX_EPOCH + check_expire_duration(Duration::from_millis(millis), "pexpireat")?;
+    let conditions = parse_expire_conditions(&params[2..])?;
+    Ok(Command::Pexpireat {
+        key,
+        ttl,
+        conditions,
+    })
+}
+
+/// Generador de comando Command::Pttl
+fn generate_pttl(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'pttl' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Pttl { key })
+}
+
+/// Generador de comando Command::Expiretime
+fn generate_expiretime(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'expiretime' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Expiretime { key })
+}
+
+/// Generador de comando Command::Pexpiretime
+fn generate_pexpiretime(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'pexpiretime' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Pexpiretime { key })
+}
+
+/// Generador de comando Command::Renamenx
+fn generate_renamenx(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'renamenx' command".to_string());
+    }
+
+    let key_origin = params[0].clone();
+    let key_destination = params[1].clone();
+    Ok(Command::Renamenx {
+        key_origin,
+        key_destination,
+    })
+}
+
+/// Generador de comando Command::Unlink
+fn generate_unlink(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'unlink' command".to_string());
+    }
+
+    Ok(Command::Unlink { keys: params })
+}
+
 /// Generador de comando Command::Append
 fn generate_append(params: Vec<String>) -> Result<Command, String> {
     if params.len() != 2 {
@@ -280,25 +1035,56 @@ fn generate_exists(params: Vec<String>) -> Result<Command, String> {
 
 /// Generador de comando Command::Expire
 fn generate_expire(params: Vec<String>) -> Result<Command, String> {
-    if params.len() != 2 {
+    if params.len() < 2 {
         return Err("ERR wrong number of arguments for 'expire' command".to_string());
     }
 
     let key = params[0].clone();
-    let seconds: Result<u32, _> = params[1].to_string().parse();
+    let seconds = parse_integer(&params[1])?;
 
-    if seconds.is_err() {
-        return Err("ERR value is not an integer or out of range".to_string());
-    }
+    // Un tiempo negativo o nulo expira la clave en el momento, por lo que se elimina.
+    let ttl = check_expire_duration(Duration::from_secs(seconds.max(0) as u64), "expire")?;
+    let conditions = parse_expire_conditions(&params[2..])?;
+
+    Ok(Command::Expire {
+        key,
+        ttl,
+        conditions,
+    })
+}
 
-    let ttl = Duration::from_secs(seconds.unwrap().into());
+/// Parsea las opciones NX, XX, GT y LT de EXPIRE, EXPIREAT, PEXPIRE y PEXPIREAT.
+fn parse_expire_conditions(params: &[String]) -> Result<Vec<ExpireCondition>, String> {
+    let mut conditions = vec![];
+    for param in params {
+        let condition = match param.to_lowercase().as_str() {
+            "nx" => ExpireCondition::NoExpiry,
+            "xx" => ExpireCondition::HasExpiry,
+            "gt" => ExpireCondition::Greater,
+            "lt" => ExpireCondition::Less,
+            _ => return Err(format!("ERR Unsupported option {}", param)),
+        };
+        if !conditions.contains(&condition) {
+            conditions.push(condition);
+        }
+    }
 
-    Ok(Command::Expire { key, ttl })
+    let nx = conditions.contains(&ExpireCondition::NoExpiry);
+    if nx && conditions.len() > 1 {
+        return Err(
+            "ERR NX and XX, GT or LT options at the same time are not compatible".to_string(),
+        );
+    }
+    if conditions.contains(&ExpireCondition::Greater) && conditions.contains(&ExpireCondition::Less)
+    {
+        return Err("ERR GT and LT options at the same time are not compatible".to_string());
+    }
+    Ok(conditions)
 }
 
 /// Generador de comando Command::ExpireAt
 fn generate_expireat(params: Vec<String>) -> Result<Command, String> {
-    if params.len() != 2 {
+    if params.len() < 2 {
         return Err("ERR wrong number of arguments for 'expireat' command".to_string());
     }
 
@@ -310,8 +1096,13 @@ fn generate_expireat(params: Vec<String>) -> Result<Command, String> {
     }
 
     let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds.unwrap().into());
+    let conditions = parse_expire_conditions(&params[2..])?;
 
-    Ok(Command::Expireat { key, ttl })
+    Ok(Command::Expireat {
+        key,
+        ttl,
+        conditions,
+    })
 }
 
 /// Generador de comando Command::Persist
@@ -324,6 +1115,15 @@ fn generate_persist(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Persist { key })
 }
 
+/// Generador de comando Command::Randomkey
+fn generate_randomkey(params: Vec<String>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'randomkey' command".to_string());
+    }
+
+    Ok(Command::Randomkey)
+}
+
 /// Generador de comando Command::Rename
 fn generate_rename(params: Vec<String>) -> Result<Command, String> {
     if params.len() != 2 {
@@ -339,16 +1139,63 @@ fn generate_rename(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Sort
+///
+/// La forma del comando es
+/// `SORT key [BY pattern] [LIMIT offset count] [GET pattern ...] [ASC | DESC] [ALPHA] [STORE destination]`.
 fn generate_sort(params: Vec<String>) -> Result<Command, String> {
-    if params.len() != 1 {
+    if params.is_empty() {
         return Err("ERR wrong number of arguments for 'sort' command".to_string());
     }
 
     let key = params[0].clone();
-    Ok(Command::Sort { key })
-}
-
-/// Generador de comando Command::Touch
+    let mut options = SortOptions::default();
+
+    let mut params = params[1..].iter();
+    while let Some(option) = params.next() {
+        match option.to_lowercase().as_str() {
+            "asc" => options.descending = false,
+            "desc" => options.descending = true,
+            "alpha" => options.alpha = true,
+            "by" => options.by = Some(next_sort_param(&mut params)?.clone()),
+            "get" => options.get.push(next_sort_param(&mut params)?.clone()),
+            "store" => options.store = Some(next_sort_param(&mut params)?.clone()),
+            "limit" => {
+                let offset = parse_integer(next_sort_param(&mut params)?)?;
+                let count = parse_integer(next_sort_param(&mut params)?)?;
+                options.limit = Some((offset, count));
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+
+    Ok(Command::Sort { key, options })
+}
+
+/// Devuelve el argumento de una opción de SORT, o un error de sintaxis si falta.
+fn next_sort_param<'a>(params: &mut std::slice::Iter<'a, String>) -> Result<&'a String, String> {
+    params.next().ok_or_else(|| "ERR syntax error".to_string())
+}
+
+/// Generador de comando Command::Object
+fn generate_object(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'object' command".to_string());
+    }
+
+    let param = match (params[0].to_lowercase().as_str(), params.len()) {
+        ("freq", 2) => ObjectParam::Freq(params[1].clone()),
+        ("encoding", 2) => ObjectParam::Encoding(params[1].clone()),
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Object { param })
+}
+
+/// Generador de comando Command::Touch
 fn generate_touch(params: Vec<String>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'touch' command".to_string());
@@ -388,7 +1235,7 @@ fn generate_mget(params: Vec<String>) -> Result<Command, String> {
 
 /// Generador de comando Command::Mset
 fn generate_mset(params: Vec<String>) -> Result<Command, String> {
-    if params.is_empty() || params.len() % 2 != 0 {
+    if params.is_empty() || !params.len().is_multiple_of(2) {
         return Err("ERR wrong number of arguments for 'mset' command".to_string());
     }
 
@@ -400,6 +1247,19 @@ fn generate_mset(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Mset { key_values })
 }
 
+/// Generador de comando Command::Msetnx
+fn generate_msetnx(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() || !params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'msetnx' command".to_string());
+    }
+
+    let key_values = params
+        .chunks(2)
+        .map(|pair| (pair[0].to_string(), pair[1].to_string()))
+        .collect();
+    Ok(Command::Msetnx { key_values })
+}
+
 /// Generador de comando Command::Strlen
 fn generate_strlen(params: Vec<String>) -> Result<Command, String> {
     if params.len() != 1 {
@@ -452,21 +1312,23 @@ fn generate_lpop(params: Vec<String>) -> Result<Command, String> {
         return Err("ERR wrong number of arguments for 'lpop' command".to_string());
     }
 
-    let mut count: usize = 0;
-    if params.len() == 2 {
-        let parse_count: Result<usize, _> = params[1].to_string().parse();
-
-        if parse_count.is_err() {
-            return Err("ERR value is not an integer or out of range".to_string());
-        }
-
-        count = parse_count.unwrap();
-    }
+    let count = match params.get(1) {
+        Some(count) => Some(parse_pop_count(count)?),
+        None => None,
+    };
 
     let key = params[0].to_string();
     Ok(Command::Lpop { key, count })
 }
 
+/// Parsea la cantidad de elementos a quitar de LPOP / RPOP, que no puede ser negativa.
+fn parse_pop_count(count: &str) -> Result<usize, String> {
+    match parse_integer(count)? {
+        count if count < 0 => Err("ERR value is out of range, must be positive".to_string()),
+        count => Ok(count as usize),
+    }
+}
+
 /// Generador de comando Command::Lrange
 fn generate_lrange(params: Vec<String>) -> Result<Command, String> {
     if params.len() != 3 {
@@ -543,16 +1405,10 @@ fn generate_rpop(params: Vec<String>) -> Result<Command, String> {
         return Err("ERR wrong number of arguments for 'rpop' command".to_string());
     }
 
-    let mut count: usize = 0;
-    if params.len() == 2 {
-        let parse_count: Result<usize, _> = params[1].to_string().parse();
-
-        if parse_count.is_err() {
-            return Err("ERR value is not an integer or out of range".to_string());
-        }
-
-        count = parse_count.unwrap();
-    }
+    let count = match params.get(1) {
+        Some(count) => Some(parse_pop_count(count)?),
+        None => None,
+    };
 
     let key = params[0].to_string();
     Ok(Command::Rpop { key, count })
@@ -606,6 +1462,48 @@ fn generate_rpushx(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Rpushx { key, value: values })
 }
 
+/// Generador de comando Command::Blpop
+fn generate_blpop(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'blpop' command".to_string());
+    }
+
+    let (keys, timeout) = parse_blocking_params(params)?;
+    Ok(Command::Blpop { keys, timeout })
+}
+
+/// Generador de comando Command::Brpop
+fn generate_brpop(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'brpop' command".to_string());
+    }
+
+    let (keys, timeout) = parse_blocking_params(params)?;
+    Ok(Command::Brpop { keys, timeout })
+}
+
+/// Separa los parámetros de un comando bloqueante (`key [key ...] timeout`) en las claves y el
+/// timeout en segundos. Un timeout de 0 indica que se espera indefinidamente.
+fn parse_blocking_params(
+    mut params: Vec<String>,
+) -> Result<(Vec<String>, Option<Duration>), String> {
+    let timeout = params.pop().unwrap();
+    let timeout = match timeout.parse::<f64>() {
+        Ok(timeout) if timeout.is_finite() => timeout,
+        _ => return Err("ERR timeout is not a float or out of range".to_string()),
+    };
+    if timeout < 0.0 {
+        return Err("ERR timeout is negative".to_string());
+    }
+
+    let timeout = if timeout == 0.0 {
+        None
+    } else {
+        Some(Duration::from_secs_f64(timeout))
+    };
+    Ok((params, timeout))
+}
+
 /// Generador de comando Command::Sadd
 fn generate_sadd(params: Vec<String>) -> Result<Command, String> {
     if params.len() <= 1 {
@@ -662,272 +1560,2548 @@ fn generate_smembers(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Smembers { key })
 }
 
-/// Generador de comando Command::Keys
-fn generate_keys(params: Vec<String>) -> Result<Command, String> {
+/// Generador de comando Command::Sinter
+fn generate_sinter(params: Vec<String>) -> Result<Command, String> {
     if params.is_empty() {
-        return Err("ERR wrong number of arguments for 'keys' command".to_string());
+        return Err("ERR wrong number of arguments for 'sinter' command".to_string());
     }
-    let pattern = params[0].clone();
-    Ok(Command::Keys { pattern })
+
+    Ok(Command::Sinter { keys: params })
 }
 
-/// Generador de comando Command::Store
-fn generate_store(params: Vec<String>) -> Result<Command, String> {
-    if params.is_empty() {
-        return Err("ERR wrong number of arguments for 'store' command".to_string());
+/// Generador de comando Command::Sintercard
+///
+/// La forma del comando es `SINTERCARD numkeys key [key ...] [LIMIT limit]`. Un LIMIT de 0
+/// indica que no hay límite.
+fn generate_sintercard(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'sintercard' command".to_string());
     }
 
-    let path = params[0].clone();
-    Ok(Command::Store { path })
+    let numkeys = params[0]
+        .parse::<usize>()
+        .map_err(|_| "ERR numkeys should be greater than 0".to_string())?;
+    if numkeys == 0 {
+        return Err("ERR numkeys should be greater than 0".to_string());
+    }
+    if numkeys > params.len() - 1 {
+        return Err("ERR Number of keys can't be greater than number of args".to_string());
+    }
+
+    let keys = Vec::from(&params[1..=numkeys]);
+    let limit = match &params[numkeys + 1..] {
+        [] => 0,
+        [option, limit] if option.to_lowercase() == "limit" => limit
+            .parse::<usize>()
+            .map_err(|_| "ERR LIMIT can't be negative".to_string())?,
+        _ => return Err("ERR syntax error".to_string()),
+    };
+
+    Ok(Command::Sintercard { keys, limit })
 }
 
-/// Generador de comando Command::Load
-fn generate_load(params: Vec<String>) -> Result<Command, String> {
+/// Generador de comando Command::Sunion
+fn generate_sunion(params: Vec<String>) -> Result<Command, String> {
     if params.is_empty() {
-        return Err("ERR wrong number of arguments for 'load' command".to_string());
+        return Err("ERR wrong number of arguments for 'sunion' command".to_string());
     }
 
-    let path = params[0].clone();
-    Ok(Command::Load { path })
+    Ok(Command::Sunion { keys: params })
 }
 
-/// Generador de comando Command::Pubsub
-fn generate_pubsub(params: Vec<String>) -> Result<Command, String> {
+/// Generador de comando Command::Sdiff
+fn generate_sdiff(params: Vec<String>) -> Result<Command, String> {
     if params.is_empty() {
-        return Err("ERR wrong number of arguments for 'pubsub' command".to_string());
+        return Err("ERR wrong number of arguments for 'sdiff' command".to_string());
     }
 
-    match params[0].clone().to_lowercase().as_str() {
-        "channels" => match params.len() {
-            1 => Ok(Command::Pubsub {
-                param: PubSubParam::Channels,
-            }),
-            2 => Ok(Command::Pubsub {
-                param: PubSubParam::ChannelsWithChannel(params[1].clone()),
-            }),
-            _ => Err(
-                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
-                    + params[0].as_str(),
-            ),
-        },
-        "numsub" => match params.len() {
-            1 => Ok(Command::Pubsub {
-                param: PubSubParam::Numsub,
-            }),
-            _ => Ok(Command::Pubsub {
-                param: PubSubParam::NumsubWithChannels(Vec::from(params.get(1..).unwrap())),
-            }),
-        },
-        _ => Err(
-            "ERR Unknown subcommand or wrong number of arguments for ".to_string()
-                + params[0].as_str(),
-        ),
-    }
+    Ok(Command::Sdiff { keys: params })
 }
 
-/// Generador de comando Command::Subscribe
-fn generate_subscribe(params: Vec<String>, client_id: String) -> Result<Command, String> {
-    if params.is_empty() {
-        return Err("ERR wrong number of arguments for 'subscribe' command".to_string());
+/// Generador de comando Command::Smismember
+fn generate_smismember(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'smismember' command".to_string());
     }
 
-    Ok(Command::Subscribe {
-        channels: params,
-        client_id,
-    })
+    let key = params[0].clone();
+    let members = Vec::from(&params[1..]);
+    Ok(Command::Smismember { key, members })
 }
 
-/// Generador de comando Command::Publish
-fn generate_publish(params: Vec<String>) -> Result<Command, String> {
-    if params.len() != 2 {
-        return Err("ERR wrong number of arguments for 'publish' command".to_string());
+/// Generador de comando Command::Spop
+fn generate_spop(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() || params.len() > 2 {
+        return Err("ERR wrong number of arguments for 'spop' command".to_string());
     }
-    let channel = params[0].clone();
-    let message = params[1].clone();
-    Ok(Command::Publish { channel, message })
+
+    let key = params[0].clone();
+    let count = match params.get(1) {
+        Some(count) => Some(
+            count
+                .parse::<usize>()
+                .map_err(|_| "ERR value is out of range, must be positive".to_string())?,
+        ),
+        None => None,
+    };
+
+    Ok(Command::Spop { key, count })
 }
 
-/// Generador de comando Command::Unsubscribe
-fn generate_unsubscribe(params: Vec<String>, client_id: String) -> Command {
-    Command::Unsubscribe {
-        channels: params,
-        client_id,
+/// Generador de comando Command::Srandmember
+fn generate_srandmember(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() || params.len() > 2 {
+        return Err("ERR wrong number of arguments for 'srandmember' command".to_string());
     }
+
+    let key = params[0].clone();
+    let count = match params.get(1) {
+        Some(count) => Some(
+            count
+                .parse::<i64>()
+                .map_err(|_| "ERR value is not an integer or out of range".to_string())?,
+        ),
+        None => None,
+    };
+
+    Ok(Command::Srandmember { key, count })
 }
 
-#[allow(unused_imports)]
-mod test {
-    use crate::entities::command::Command;
-    use crate::service::command_generator::generate;
-    use core::time::Duration;
-    use std::collections::HashSet;
-    use std::time::SystemTime;
+/// Generador de comando Command::Sscan
+///
+/// La forma del comando es `SSCAN key cursor [MATCH pattern] [COUNT count]`. Si no se indica
+/// COUNT, se recorren 10 miembros por llamada.
+fn generate_sscan(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 || !params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'sscan' command".to_string());
+    }
 
-    #[test]
-    fn generate_command_with_params_empty_err() {
-        let params = vec![];
-        let result = generate(params, "client-test".to_string());
+    let key = params[0].clone();
+    let cursor = params[1]
+        .parse::<u64>()
+        .map_err(|_| "ERR invalid cursor".to_string())?;
+    let mut pattern = None;
+    let mut count = 10;
+
+    for option in params[2..].chunks(2) {
+        match option[0].to_lowercase().as_str() {
+            "match" => pattern = Some(option[1].clone()),
+            "count" => {
+                count = option[1]
+                    .parse::<usize>()
+                    .map_err(|_| "ERR value is not an integer or out of range".to_string())?;
+                if count == 0 {
+                    return Err("ERR syntax error".to_string());
+                }
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
 
-        assert!(result.is_err())
+    Ok(Command::Sscan {
+        key,
+        cursor,
+        pattern,
+        count,
+    })
+}
+
+/// Generador de comando Command::Zadd
+fn generate_zadd(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 3 || params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'zadd' command".to_string());
     }
 
-    #[test]
-    fn generate_command_with_command_invalid_err() {
-        let params = vec!["metodo".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let key = params[0].clone();
+    let mut members: Vec<(f64, String)> = Vec::new();
+    for pair in params[1..].chunks(2) {
+        let score = parse_score(&pair[0])?;
+        members.push((score, pair[1].clone()));
+    }
 
-        assert!(result.is_err())
+    Ok(Command::Zadd { key, members })
+}
+
+/// Generador de comando Command::Zcard
+fn generate_zcard(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'zcard' command".to_string());
     }
 
-    #[test]
-    fn generate_command_with_command_ping() {
-        let params = vec!["ping".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let key = params[0].clone();
+    Ok(Command::Zcard { key })
+}
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Ping => true,
-            _ => false,
-        });
+/// Generador de comando Command::Zincrby
+fn generate_zincrby(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'zincrby' command".to_string());
     }
 
-    #[test]
-    fn generate_command_with_command_monitor() {
-        let params = vec!["monitor".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let key = params[0].clone();
+    let increment = parse_score(&params[1])?;
+    let member = params[2].clone();
+    Ok(Command::Zincrby {
+        key,
+        increment,
+        member,
+    })
+}
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Monitor => true,
-            _ => false,
-        });
+/// Generador de los comandos Command::Zunionstore y Command::Zinterstore
+///
+/// La forma del comando es `destination numkeys key [key ...] [WEIGHTS weight [weight ...]]
+/// [AGGREGATE SUM|MIN|MAX]`. Sin WEIGHTS, el peso de cada clave es 1.
+fn generate_zstore(params: Vec<String>, name: &str) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
+    }
+
+    let destination = params[0].clone();
+    let numkeys = parse_integer(&params[1])?;
+    if numkeys <= 0 {
+        return Err(format!(
+            "ERR at least 1 input key is needed for '{}' command",
+            name
+        ));
+    }
+    let numkeys = numkeys as usize;
+    if numkeys > params.len() - 2 {
+        return Err("ERR syntax error".to_string());
+    }
+    let keys = Vec::from(&params[2..2 + numkeys]);
+
+    let mut weights = vec![1.0; numkeys];
+    let mut aggregate = Aggregate::Sum;
+    let mut options = params[2 + numkeys..].iter();
+    while let Some(option) = options.next() {
+        match option.to_lowercase().as_str() {
+            "weights" => {
+                for weight in weights.iter_mut() {
+                    let value = options.next().ok_or("ERR syntax error")?;
+                    *weight = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|weight| !weight.is_nan())
+                        .ok_or("ERR weight value is not a float")?;
+                }
+            }
+            "aggregate" => {
+                let value = options.next().ok_or("ERR syntax error")?;
+                aggregate = match value.to_lowercase().as_str() {
+                    "sum" => Aggregate::Sum,
+                    "min" => Aggregate::Min,
+                    "max" => Aggregate::Max,
+                    _ => return Err("ERR syntax error".to_string()),
+                };
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
     }
 
-    #[test]
-    fn generate_command_with_command_flushdb() {
-        let params = vec!["flushdb".to_string()];
-        let result = generate(params, "client-test".to_string());
+    match name {
+        "zinterstore" => Ok(Command::Zinterstore {
+            destination,
+            keys,
+            weights,
+            aggregate,
+        }),
+        _ => Ok(Command::Zunionstore {
+            destination,
+            keys,
+            weights,
+            aggregate,
+        }),
+    }
+}
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Flushdb => true,
-            _ => false,
-        });
+/// Generador de los comandos Command::Bzpopmin y Command::Bzpopmax
+fn generate_bzpop(params: Vec<String>, name: &str) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
     }
 
-    #[test]
-    fn generate_command_copy_without_params_err() {
-        let params = vec!["copy".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let (keys, timeout) = parse_blocking_params(params)?;
+    match name {
+        "bzpopmax" => Ok(Command::Bzpopmax { keys, timeout }),
+        _ => Ok(Command::Bzpopmin { keys, timeout }),
+    }
+}
 
-        assert!(result.is_err())
+/// Generador de los comandos Command::Zpopmin y Command::Zpopmax
+fn generate_zpop(params: Vec<String>, name: &str) -> Result<Command, String> {
+    if params.is_empty() || params.len() > 2 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
     }
 
-    #[test]
-    fn generate_command_copy_with_one_param_err() {
-        let params = vec!["copy".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let count = match params.get(1) {
+        Some(count) => Some(parse_pop_count(count)?),
+        None => None,
+    };
 
-        assert!(result.is_err())
+    let key = params[0].clone();
+    match name {
+        "zpopmax" => Ok(Command::Zpopmax { key, count }),
+        _ => Ok(Command::Zpopmin { key, count }),
     }
+}
 
-    #[test]
-    fn generate_command_copy_ok() {
-        let params = vec!["copy".to_string(), "key".to_string(), "key1".to_string()];
-        let result = generate(params, "client-test".to_string());
+/// Generador de comando Command::Zrange
+fn generate_zrange(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 3 && params.len() != 4 {
+        return Err("ERR wrong number of arguments for 'zrange' command".to_string());
+    }
 
-        let _key = "key".to_string();
-        let _key2 = "key1".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Copy {
-                key_origin: _key,
-                key_destination: _key2,
-            } => true,
-            _ => false,
-        });
+    let start: Result<i64, _> = params[1].parse();
+    let stop: Result<i64, _> = params[2].parse();
+    if start.is_err() || stop.is_err() {
+        return Err("ERR value is not an integer or out of range".to_string());
     }
 
-    #[test]
-    fn generate_command_get_without_param_err() {
-        let params = vec!["get".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let key = params[0].clone();
+    Ok(Command::Zrange {
+        key,
+        start: start.unwrap(),
+        stop: stop.unwrap(),
+        with_scores: parse_withscores(params.get(3))?,
+    })
+}
 
-        assert!(result.is_err())
+/// Generador de comando Command::Zrangebyscore
+fn generate_zrangebyscore(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 3 && params.len() != 4 {
+        return Err("ERR wrong number of arguments for 'zrangebyscore' command".to_string());
     }
 
-    #[test]
-    fn generate_command_get_ok() {
-        let params = vec!["get".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let key = params[0].clone();
+    let min = parse_score_bound(&params[1])?;
+    let max = parse_score_bound(&params[2])?;
+    Ok(Command::Zrangebyscore {
+        key,
+        min,
+        max,
+        with_scores: parse_withscores(params.get(3))?,
+    })
+}
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Get { key: _key } => true,
-            _ => false,
-        });
+/// Parsea el parámetro opcional WITHSCORES de los comandos de rango de sorted sets.
+fn parse_withscores(param: Option<&String>) -> Result<bool, String> {
+    match param {
+        Some(param) if param.to_lowercase() == "withscores" => Ok(true),
+        Some(_) => Err("ERR syntax error".to_string()),
+        None => Ok(false),
     }
+}
 
-    #[test]
-    fn generate_command_getset_without_param_err() {
-        let params = vec!["getset".to_string()];
-        let result = generate(params, "client-test".to_string());
+/// Generador de comando Command::Zrank
+fn generate_zrank(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'zrank' command".to_string());
+    }
 
-        assert!(result.is_err())
+    let key = params[0].clone();
+    let member = params[1].clone();
+    Ok(Command::Zrank { key, member })
+}
+
+/// Generador de comando Command::Zrem
+fn generate_zrem(params: Vec<String>) -> Result<Command, String> {
+    if params.len() <= 1 {
+        return Err("ERR wrong number of arguments for 'zrem' command".to_string());
     }
 
-    #[test]
-    fn generate_command_getset_with_one_param_err() {
-        let params = vec!["getset".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    let key = params[0].clone();
+    let members = Vec::from(params.get(1..).unwrap());
+    Ok(Command::Zrem { key, members })
+}
 
-        assert!(result.is_err())
+/// Generador de comando Command::Zscore
+fn generate_zscore(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'zscore' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let member = params[1].clone();
+    Ok(Command::Zscore { key, member })
+}
+
+/// Parsea el score de un miembro de un sorted set. No se aceptan valores NaN.
+fn parse_score(score: &str) -> Result<f64, String> {
+    match score.parse::<f64>() {
+        Ok(score) if !score.is_nan() => Ok(score),
+        _ => Err("ERR value is not a valid float".to_string()),
+    }
+}
+
+/// Parsea un límite de rango de scores. Si comienza con `(` el límite es exclusivo.
+fn parse_score_bound(bound: &str) -> Result<ScoreBound, String> {
+    let parsed = match bound.strip_prefix('(') {
+        Some(value) => value.parse::<f64>().map(ScoreBound::Exclusive),
+        None => bound.parse::<f64>().map(ScoreBound::Inclusive),
+    };
+
+    match parsed {
+        Ok(ScoreBound::Inclusive(value)) | Ok(ScoreBound::Exclusive(value)) if value.is_nan() => {
+            Err("ERR min or max is not a float".to_string())
+        }
+        Ok(bound) => Ok(bound),
+        Err(_) => Err("ERR min or max is not a float".to_string()),
+    }
+}
+
+/// Generador de comando Command::Geoadd
+///
+/// La forma del comando es `GEOADD key [NX | XX] [CH] longitude latitude member [...]`.
+fn generate_geoadd(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 4 {
+        return Err("ERR wrong number of arguments for 'geoadd' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let mut options = GeoAddOptions {
+        condition: SetCondition::Always,
+        changed: false,
+    };
+    let mut nx = false;
+    let mut xx = false;
+    let mut index = 1;
+    while let Some(option) = params.get(index) {
+        match option.to_lowercase().as_str() {
+            "nx" => nx = true,
+            "xx" => xx = true,
+            "ch" => options.changed = true,
+            _ => break,
+        }
+        index += 1;
+    }
+    if nx && xx {
+        return Err("ERR XX and NX options at the same time are not compatible".to_string());
+    }
+    if nx {
+        options.condition = SetCondition::IfNotExists;
+    } else if xx {
+        options.condition = SetCondition::IfExists;
+    }
+
+    let values = &params[index..];
+    if values.is_empty() || !values.len().is_multiple_of(3) {
+        return Err("ERR syntax error".to_string());
+    }
+
+    let mut members = vec![];
+    for value in values.chunks(3) {
+        let longitude = parse_coordinate(&value[0])?;
+        let latitude = parse_coordinate(&value[1])?;
+        geo::validate(longitude, latitude)?;
+        members.push((longitude, latitude, value[2].clone()));
+    }
+
+    Ok(Command::Geoadd {
+        key,
+        options,
+        members,
+    })
+}
+
+/// Generador de comando Command::Geodist
+///
+/// La forma del comando es `GEODIST key member1 member2 [M | KM | FT | MI]`.
+fn generate_geodist(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 3 && params.len() != 4 {
+        return Err("ERR wrong number of arguments for 'geodist' command".to_string());
+    }
+
+    let unit = match params.get(3) {
+        Some(unit) => parse_geo_unit(unit)?,
+        None => GeoUnit::Meters,
+    };
+
+    Ok(Command::Geodist {
+        key: params[0].clone(),
+        member1: params[1].clone(),
+        member2: params[2].clone(),
+        unit,
+    })
+}
+
+/// Generador de comando Command::Geopos
+fn generate_geopos(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'geopos' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let members = Vec::from(&params[1..]);
+    Ok(Command::Geopos { key, members })
+}
+
+/// Generador de comando Command::Geosearch
+///
+/// La forma del comando es `GEOSEARCH key <FROMMEMBER member | FROMLONLAT longitude latitude>
+/// <BYRADIUS radius unit | BYBOX width height unit> [ASC | DESC] [COUNT count [ANY]]
+/// [WITHCOORD] [WITHDIST] [WITHHASH]`.
+fn generate_geosearch(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 5 {
+        return Err("ERR wrong number of arguments for 'geosearch' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let mut origin = None;
+    let mut shape = None;
+    let mut unit = GeoUnit::Meters;
+    let mut ascending = None;
+    let mut count = None;
+    let mut any = false;
+    let mut with_coord = false;
+    let mut with_dist = false;
+    let mut with_hash = false;
+
+    let mut index = 1;
+    while let Some(option) = params.get(index) {
+        let argument = |offset: usize| {
+            params
+                .get(index + offset)
+                .ok_or_else(|| "ERR syntax error".to_string())
+        };
+        match option.to_lowercase().as_str() {
+            "frommember" if origin.is_none() => {
+                origin = Some(GeoOrigin::Member(argument(1)?.clone()));
+                index += 2;
+            }
+            "fromlonlat" if origin.is_none() => {
+                let longitude = parse_coordinate(argument(1)?)?;
+                let latitude = parse_coordinate(argument(2)?)?;
+                geo::validate(longitude, latitude)?;
+                origin = Some(GeoOrigin::LonLat(longitude, latitude));
+                index += 3;
+            }
+            "frommember" | "fromlonlat" => {
+                return Err(
+                    "ERR exactly one of FROMMEMBER or FROMLONLAT can be specified for GEOSEARCH"
+                        .to_string(),
+                )
+            }
+            "byradius" if shape.is_none() => {
+                let radius = parse_geo_distance(argument(1)?)?;
+                unit = parse_geo_unit(argument(2)?)?;
+                shape = Some(GeoShape::Radius(radius * unit.to_meters()));
+                index += 3;
+            }
+            "bybox" if shape.is_none() => {
+                let width = parse_geo_distance(argument(1)?)?;
+                let height = parse_geo_distance(argument(2)?)?;
+                unit = parse_geo_unit(argument(3)?)?;
+                shape = Some(GeoShape::Box(
+                    width * unit.to_meters(),
+                    height * unit.to_meters(),
+                ));
+                index += 4;
+            }
+            "byradius" | "bybox" => {
+                return Err(
+                    "ERR exactly one of BYRADIUS and BYBOX can be specified for GEOSEARCH"
+                        .to_string(),
+                )
+            }
+            "asc" => {
+                ascending = Some(true);
+                index += 1;
+            }
+            "desc" => {
+                ascending = Some(false);
+                index += 1;
+            }
+            "count" => {
+                let value = parse_integer(argument(1)?)?;
+                if value <= 0 {
+                    return Err("ERR COUNT must be > 0".to_string());
+                }
+                count = Some(value as usize);
+                index += 2;
+                if params
+                    .get(index)
+                    .is_some_and(|option| option.to_lowercase() == "any")
+                {
+                    any = true;
+                    index += 1;
+                }
+            }
+            "withcoord" => {
+                with_coord = true;
+                index += 1;
+            }
+            "withdist" => {
+                with_dist = true;
+                index += 1;
+            }
+            "withhash" => {
+                with_hash = true;
+                index += 1;
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+
+    let origin = origin.ok_or_else(|| {
+        "ERR exactly one of FROMMEMBER or FROMLONLAT can be specified for GEOSEARCH".to_string()
+    })?;
+    let shape = shape.ok_or_else(|| {
+        "ERR exactly one of BYRADIUS and BYBOX can be specified for GEOSEARCH".to_string()
+    })?;
+    // Sin ANY, COUNT debe devolver los resultados más cercanos, por lo que se ordenan.
+    if count.is_some() && !any && ascending.is_none() {
+        ascending = Some(true);
+    }
+
+    Ok(Command::Geosearch {
+        key,
+        options: GeoSearchOptions {
+            origin,
+            shape,
+            unit,
+            ascending,
+            count,
+            any,
+            with_coord,
+            with_dist,
+            with_hash,
+        },
+    })
+}
+
+/// Parsea una longitud o latitud.
+fn parse_coordinate(coordinate: &str) -> Result<f64, String> {
+    match coordinate.parse::<f64>() {
+        Ok(coordinate) if coordinate.is_finite() => Ok(coordinate),
+        _ => Err("ERR value is not a valid float".to_string()),
+    }
+}
+
+/// Parsea un radio o una medida de un área de búsqueda, que no puede ser negativa.
+fn parse_geo_distance(distance: &str) -> Result<f64, String> {
+    match distance.parse::<f64>() {
+        Ok(distance) if distance < 0.0 => Err("ERR radius cannot be negative".to_string()),
+        Ok(distance) if distance.is_finite() => Ok(distance),
+        _ => Err("ERR need numeric radius".to_string()),
+    }
+}
+
+/// Parsea una unidad de distancia.
+fn parse_geo_unit(unit: &str) -> Result<GeoUnit, String> {
+    match unit.to_lowercase().as_str() {
+        "m" => Ok(GeoUnit::Meters),
+        "km" => Ok(GeoUnit::Kilometers),
+        "ft" => Ok(GeoUnit::Feet),
+        "mi" => Ok(GeoUnit::Miles),
+        _ => Err("ERR unsupported unit provided. please use M, KM, FT, MI".to_string()),
+    }
+}
+
+/// Generador de comando Command::Xadd
+///
+/// La forma del comando es `XADD key [MAXLEN [= | ~] threshold] <* | id> field value [field value ...]`.
+/// El recorte con `~` se realiza de forma exacta.
+fn generate_xadd(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 4 {
+        return Err("ERR wrong number of arguments for 'xadd' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let mut index = 1;
+    let mut max_len = None;
+    if params[index].to_lowercase() == "maxlen" {
+        index += 1;
+        if params
+            .get(index)
+            .is_some_and(|param| param == "=" || param == "~")
+        {
+            index += 1;
+        }
+        let threshold = params
+            .get(index)
+            .ok_or_else(|| "ERR syntax error".to_string())?;
+        max_len = Some(
+            threshold
+                .parse::<usize>()
+                .map_err(|_| "ERR The MAXLEN argument must be >= 0.".to_string())?,
+        );
+        index += 1;
+    }
+
+    let id = match params.get(index).map(|id| id.as_str()) {
+        Some("*") => None,
+        Some(id) => Some(StreamId::parse_with_default_seq(id, 0)?),
+        None => return Err("ERR syntax error".to_string()),
+    };
+    let pairs = &params[index + 1..];
+    if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'xadd' command".to_string());
+    }
+    let fields = pairs
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect();
+
+    Ok(Command::Xadd {
+        key,
+        id,
+        max_len,
+        fields,
+    })
+}
+
+/// Generador de comando Command::Xlen
+fn generate_xlen(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'xlen' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Xlen { key })
+}
+
+/// Generador de comando Command::Xrange y Command::Xrevrange
+///
+/// La forma del comando es `XRANGE key start end [COUNT count]`. En XREVRANGE los límites se
+/// reciben en orden inverso (`end start`).
+fn generate_xrange(params: Vec<String>, reverse: bool) -> Result<Command, String> {
+    let name = if reverse { "xrevrange" } else { "xrange" };
+    if params.len() != 3 && params.len() != 5 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
+    }
+
+    let key = params[0].clone();
+    let (start, end) = if reverse {
+        (&params[2], &params[1])
+    } else {
+        (&params[1], &params[2])
+    };
+    let start = parse_stream_bound(start, true)?;
+    let end = parse_stream_bound(end, false)?;
+    let count = match params.get(3) {
+        Some(option) if option.to_lowercase() == "count" => Some(parse_count(&params[4])?),
+        Some(_) => return Err("ERR syntax error".to_string()),
+        None => None,
+    };
+
+    if reverse {
+        Ok(Command::Xrevrange {
+            key,
+            start,
+            end,
+            count,
+        })
+    } else {
+        Ok(Command::Xrange {
+            key,
+            start,
+            end,
+            count,
+        })
+    }
+}
+
+/// Generador de comando Command::Xread
+///
+/// La forma del comando es `XREAD [COUNT count] STREAMS key [key ...] id [id ...]`. El ID `$`
+/// indica que solo interesan las entradas posteriores a la última del stream.
+fn generate_xread(params: Vec<String>) -> Result<Command, String> {
+    let mut count = None;
+    let mut index = 0;
+    loop {
+        match params.get(index).map(|param| param.to_lowercase()) {
+            Some(option) if option == "count" => {
+                let value = params
+                    .get(index + 1)
+                    .ok_or_else(|| "ERR syntax error".to_string())?;
+                // COUNT 0 equivale a no limitar la cantidad de entradas.
+                count = Some(parse_count(value)?).filter(|count| *count > 0);
+                index += 2;
+            }
+            Some(option) if option == "streams" => break,
+            Some(_) => return Err("ERR syntax error".to_string()),
+            None => return Err("ERR wrong number of arguments for 'xread' command".to_string()),
+        }
+    }
+
+    let streams = parse_stream_keys_and_ids(&params[index + 1..], "xread", "$")?;
+    Ok(Command::Xread { count, streams })
+}
+
+/// Generador de comando Command::Xgroup
+///
+/// La forma del comando es `XGROUP CREATE key group <id | $> [MKSTREAM]`,
+/// `XGROUP DESTROY key group`, `XGROUP CREATECONSUMER key group consumer`,
+/// `XGROUP DELCONSUMER key group consumer` o `XGROUP SETID key group <id | $>`.
+fn generate_xgroup(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'xgroup' command".to_string());
+    }
+
+    let subcommand = params[0].to_lowercase();
+    let param = match (subcommand.as_str(), params.len()) {
+        ("create", 4) | ("create", 5) => {
+            let mkstream = match params.get(4) {
+                Some(option) if option.to_lowercase() == "mkstream" => true,
+                Some(_) => return Err("ERR syntax error".to_string()),
+                None => false,
+            };
+            XgroupParam::Create {
+                group: params[2].clone(),
+                id: parse_group_id(&params[3])?,
+                mkstream,
+            }
+        }
+        ("destroy", 3) => XgroupParam::Destroy {
+            group: params[2].clone(),
+        },
+        ("createconsumer", 4) => XgroupParam::CreateConsumer {
+            group: params[2].clone(),
+            consumer: params[3].clone(),
+        },
+        ("delconsumer", 4) => XgroupParam::DelConsumer {
+            group: params[2].clone(),
+            consumer: params[3].clone(),
+        },
+        ("setid", 4) => XgroupParam::SetId {
+            group: params[2].clone(),
+            id: parse_group_id(&params[3])?,
+        },
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+
+    let key = params[1].clone();
+    Ok(Command::Xgroup { key, param })
+}
+
+/// Generador de comando Command::Xreadgroup
+///
+/// La forma del comando es
+/// `XREADGROUP GROUP group consumer [COUNT count] [NOACK] STREAMS key [key ...] id [id ...]`. El
+/// ID `>` indica que se quieren recibir las entradas que el grupo aún no entregó.
+fn generate_xreadgroup(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 3 || params[0].to_lowercase() != "group" {
+        return Err("ERR wrong number of arguments for 'xreadgroup' command".to_string());
+    }
+
+    let group = params[1].clone();
+    let consumer = params[2].clone();
+    let mut count = None;
+    let mut no_ack = false;
+    let mut index = 3;
+    loop {
+        match params.get(index).map(|param| param.to_lowercase()) {
+            Some(option) if option == "count" => {
+                let value = params
+                    .get(index + 1)
+                    .ok_or_else(|| "ERR syntax error".to_string())?;
+                count = Some(parse_count(value)?).filter(|count| *count > 0);
+                index += 2;
+            }
+            Some(option) if option == "noack" => {
+                no_ack = true;
+                index += 1;
+            }
+            Some(option) if option == "streams" => break,
+            Some(_) => return Err("ERR syntax error".to_string()),
+            None => {
+                return Err("ERR wrong number of arguments for 'xreadgroup' command".to_string())
+            }
+        }
+    }
+
+    let streams = parse_stream_keys_and_ids(&params[index + 1..], "xreadgroup", ">")?;
+    Ok(Command::Xreadgroup {
+        group,
+        consumer,
+        count,
+        no_ack,
+        streams,
+    })
+}
+
+/// Generador de comando Command::Xack
+///
+/// La forma del comando es `XACK key group id [id ...]`.
+fn generate_xack(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err("ERR wrong number of arguments for 'xack' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let group = params[1].clone();
+    let mut ids = vec![];
+    for id in params[2..].iter() {
+        ids.push(StreamId::parse_with_default_seq(id, 0)?);
+    }
+
+    Ok(Command::Xack { key, group, ids })
+}
+
+/// Generador de comando Command::Xpending
+///
+/// La forma del comando es `XPENDING key group [[IDLE min-idle-time] start end count [consumer]]`.
+fn generate_xpending(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'xpending' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let group = params[1].clone();
+    if params.len() == 2 {
+        return Ok(Command::Xpending {
+            key,
+            group,
+            range: None,
+        });
+    }
+
+    let mut index = 2;
+    let mut min_idle = None;
+    if params[index].to_lowercase() == "idle" {
+        let value = params
+            .get(index + 1)
+            .ok_or_else(|| "ERR syntax error".to_string())?;
+        min_idle = Some(Duration::from_millis(parse_count(value)? as u64));
+        index += 2;
+    }
+
+    let range = &params[index..];
+    if range.len() != 3 && range.len() != 4 {
+        return Err("ERR syntax error".to_string());
+    }
+
+    Ok(Command::Xpending {
+        key,
+        group,
+        range: Some(PendingRange {
+            min_idle,
+            start: parse_stream_bound(&range[0], true)?,
+            end: parse_stream_bound(&range[1], false)?,
+            count: parse_count(&range[2])?,
+            consumer: range.get(3).cloned(),
+        }),
+    })
+}
+
+/// Generador de comando Command::Xclaim
+///
+/// La forma del comando es `XCLAIM key group consumer min-idle-time id [id ...] [JUSTID]`.
+fn generate_xclaim(params: Vec<String>) -> Result<Command, String> {
+    if params.len() < 5 {
+        return Err("ERR wrong number of arguments for 'xclaim' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let group = params[1].clone();
+    let consumer = params[2].clone();
+    let min_idle = params[3]
+        .parse::<u64>()
+        .map(Duration::from_millis)
+        .map_err(|_| "ERR Invalid min-idle-time argument for XCLAIM".to_string())?;
+
+    let mut ids = vec![];
+    let mut just_id = false;
+    for param in params[4..].iter() {
+        if param.to_lowercase() == "justid" {
+            just_id = true;
+        } else if just_id {
+            return Err("ERR syntax error".to_string());
+        } else {
+            ids.push(StreamId::parse_with_default_seq(param, 0)?);
+        }
+    }
+
+    Ok(Command::Xclaim {
+        key,
+        group,
+        consumer,
+        min_idle,
+        ids,
+        just_id,
+    })
+}
+
+/// Parsea la lista `key [key ...] id [id ...]` recibida luego de STREAMS. El ID especial indicado
+/// (`$` o `>`) se representa como None.
+fn parse_stream_keys_and_ids(
+    keys_and_ids: &[String],
+    name: &str,
+    special_id: &str,
+) -> Result<Vec<(String, Option<StreamId>)>, String> {
+    if keys_and_ids.is_empty() || !keys_and_ids.len().is_multiple_of(2) {
+        return Err(format!(
+            "ERR Unbalanced '{}' list of streams: for each stream key an ID or '{}' must be specified.",
+            name, special_id
+        ));
+    }
+
+    let (keys, ids) = keys_and_ids.split_at(keys_and_ids.len() / 2);
+    let mut streams = vec![];
+    for (key, id) in keys.iter().zip(ids.iter()) {
+        let id = if id == special_id {
+            None
+        } else {
+            Some(StreamId::parse_with_default_seq(id, 0)?)
+        };
+        streams.push((key.clone(), id));
+    }
+    Ok(streams)
+}
+
+/// Parsea el ID recibido por XGROUP CREATE y XGROUP SETID, donde `$` representa a la última
+/// entrada del stream.
+fn parse_group_id(id: &str) -> Result<Option<StreamId>, String> {
+    match id {
+        "$" => Ok(None),
+        id => Ok(Some(StreamId::parse_with_default_seq(id, 0)?)),
+    }
+}
+
+/// Parsea uno de los límites de un rango de IDs de stream, devolviendo el límite inclusivo
+/// equivalente.
+///
+/// Se admiten `-` y `+` como mínimo y máximo ID posible, IDs sin número de secuencia y el prefijo
+/// `(` para indicar un límite exclusivo.
+fn parse_stream_bound(bound: &str, is_start: bool) -> Result<StreamId, String> {
+    let default_seq = if is_start { 0 } else { u64::MAX };
+    match bound {
+        "-" => Ok(StreamId::MIN),
+        "+" => Ok(StreamId::MAX),
+        _ => match bound.strip_prefix('(') {
+            Some(id) => {
+                let id = StreamId::parse_with_default_seq(id, default_seq)?;
+                let bound = if is_start { id.next() } else { id.previous() };
+                bound.ok_or_else(|| {
+                    let side = if is_start { "start" } else { "end" };
+                    format!("ERR invalid {} ID for the interval", side)
+                })
+            }
+            None => StreamId::parse_with_default_seq(bound, default_seq),
+        },
+    }
+}
+
+/// Parsea el valor de la opción COUNT.
+fn parse_count(count: &str) -> Result<usize, String> {
+    count
+        .parse::<usize>()
+        .map_err(|_| "ERR value is not an integer or out of range".to_string())
+}
+
+/// Generador de comando Command::Keys
+fn generate_keys(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'keys' command".to_string());
+    }
+    let pattern = params[0].clone();
+    Ok(Command::Keys { pattern })
+}
+
+/// Generador de comando Command::Store
+fn generate_store(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'store' command".to_string());
+    }
+
+    let path = params[0].clone();
+    Ok(Command::Store { path })
+}
+
+/// Generador de comando Command::Load
+fn generate_load(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'load' command".to_string());
+    }
+
+    let path = params[0].clone();
+    Ok(Command::Load { path })
+}
+
+/// Generador de comando Command::Pubsub
+fn generate_pubsub(params: Vec<String>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'pubsub' command".to_string());
+    }
+
+    match params[0].clone().to_lowercase().as_str() {
+        "channels" => match params.len() {
+            1 => Ok(Command::Pubsub {
+                param: PubSubParam::Channels,
+            }),
+            2 => Ok(Command::Pubsub {
+                param: PubSubParam::ChannelsWithChannel(params[1].clone()),
+            }),
+            _ => Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            ),
+        },
+        "numsub" => match params.len() {
+            1 => Ok(Command::Pubsub {
+                param: PubSubParam::Numsub,
+            }),
+            _ => Ok(Command::Pubsub {
+                param: PubSubParam::NumsubWithChannels(Vec::from(params.get(1..).unwrap())),
+            }),
+        },
+        "numpat" => match params.len() {
+            1 => Ok(Command::Pubsub {
+                param: PubSubParam::Numpat,
+            }),
+            _ => Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            ),
+        },
+        _ => Err(
+            "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                + params[0].as_str(),
+        ),
+    }
+}
+
+/// Generador de comando Command::Subscribe
+fn generate_subscribe(params: Vec<String>, client_id: u64) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'subscribe' command".to_string());
+    }
+
+    Ok(Command::Subscribe {
+        channels: params,
+        client_id,
+    })
+}
+
+/// Generador de comando Command::Publish
+fn generate_publish(params: Vec<String>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'publish' command".to_string());
+    }
+    let channel = params[0].clone();
+    let message = params[1].clone();
+    Ok(Command::Publish { channel, message })
+}
+
+/// Generador de comando Command::Unsubscribe
+fn generate_unsubscribe(params: Vec<String>, client_id: u64) -> Command {
+    Command::Unsubscribe {
+        channels: params,
+        client_id,
+    }
+}
+
+/// Generador de comando Command::Eval y Command::Evalsha. El primer parámetro es el script o su
+/// SHA1, seguido de la cantidad de claves, las claves y el resto de los argumentos.
+fn generate_eval(params: Vec<String>, sha: bool) -> Result<Command, String> {
+    let numkeys = parse_integer(&params[1])?;
+    if numkeys < 0 {
+        return Err("ERR Number of keys can't be negative".to_string());
+    }
+    if numkeys as usize > params.len() - 2 {
+        return Err("ERR Number of keys can't be greater than number of args".to_string());
+    }
+
+    let mut params = params.into_iter();
+    let script = params.next().unwrap_or_default();
+    let mut args: Vec<String> = params.skip(1).collect();
+    let keys: Vec<String> = args.drain(..numkeys as usize).collect();
+    if sha {
+        Ok(Command::Evalsha {
+            sha1: script.to_lowercase(),
+            keys,
+            args,
+        })
+    } else {
+        Ok(Command::Eval { script, keys, args })
+    }
+}
+
+/// Generador de comando Command::Script
+fn generate_script(params: Vec<String>) -> Result<Command, String> {
+    let param = match (params[0].to_lowercase().as_str(), params.len()) {
+        ("load", 2) => ScriptParam::Load {
+            script: params[1].clone(),
+        },
+        ("exists", len) if len > 1 => ScriptParam::Exists {
+            sha1s: params[1..].iter().map(|sha1| sha1.to_lowercase()).collect(),
+        },
+        ("flush", 1) => ScriptParam::Flush,
+        ("flush", 2) if matches!(params[1].to_lowercase().as_str(), "async" | "sync") => {
+            ScriptParam::Flush
+        }
+        _ => {
+            return Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + params[0].as_str(),
+            )
+        }
+    };
+    Ok(Command::Script { param })
+}
+
+#[allow(unused_imports, clippy::match_like_matches_macro, clippy::useless_vec)]
+mod test {
+    use crate::entities::bitmap::{BitOperation, BitRange, BitUnit};
+    use crate::entities::client_param::{ClientParam, KillFilter};
+    use crate::entities::cluster_param::ClusterParam;
+    use crate::entities::command::Command;
+    use crate::entities::command_param::CommandParam;
+    use crate::entities::command_table::COMMAND_TABLE;
+    use crate::entities::consumer_group_param::{PendingRange, XgroupParam};
+    use crate::entities::debug_param::DebugParam;
+    use crate::entities::expire_condition::ExpireCondition;
+    use crate::entities::geo::{GeoOrigin, GeoShape, GeoUnit};
+    use crate::entities::info_param::InfoParam;
+    use crate::entities::memory_param::MemoryParam;
+    use crate::entities::object_param::ObjectParam;
+    use crate::entities::pubsub_param::PubSubParam;
+    use crate::entities::script_param::ScriptParam;
+    use crate::entities::set_options::{SetCondition, SetExpiration};
+    use crate::entities::slowlog_param::SlowlogParam;
+    use crate::entities::sort_options::SortOptions;
+    use crate::entities::sorted_set::{Aggregate, ScoreBound};
+    use crate::entities::stream::StreamId;
+    use crate::service::command_generator::generate;
+    use core::time::Duration;
+    use std::collections::HashSet;
+    use std::time::SystemTime;
+
+    #[test]
+    fn generate_command_with_params_empty_err() {
+        let params = vec![];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_with_command_invalid_err() {
+        let params = vec!["metodo".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_with_command_ping() {
+        let params = vec!["ping".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Ping { .. } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_with_command_monitor() {
+        let params = vec!["monitor".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Monitor { client_id: 1 } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_with_command_quit() {
+        let params = vec!["QUIT".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(result.unwrap(), Command::Quit));
+    }
+
+    #[test]
+    fn generate_command_with_command_flushdb() {
+        let params = vec!["flushdb".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Flushdb {
+                asynchronous: false,
+            } => true,
+            _ => false,
+        });
+
+        let params = vec!["flushdb".to_string(), "other".to_string()];
+        let result = generate(params, 1);
+
+        assert_eq!("ERR syntax error", result.unwrap_err());
+    }
+
+    #[test]
+    fn generate_command_flushall_async() {
+        let params = vec!["flushall".to_string(), "ASYNC".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Flushall { asynchronous: true }
+        ));
+    }
+
+    #[test]
+    fn generate_command_copy_without_params_err() {
+        let params = vec!["copy".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_copy_with_one_param_err() {
+        let params = vec!["copy".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_restore_ok() {
+        let params = vec!["restore", "key", "1000", "0a0b", "REPLACE", "absttl"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Restore {
+                ttl: Some(ttl),
+                replace: true,
+                ..
+            } if ttl == SystemTime::UNIX_EPOCH + Duration::from_secs(1)
+        ));
+    }
+
+    #[test]
+    fn generate_command_restore_invalid_ttl() {
+        let params = vec!["restore", "key", "-1", "0a0b"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let result = generate(params, 1);
+
+        assert_eq!("ERR Invalid TTL value, must be >= 0", result.unwrap_err());
+    }
+
+    #[test]
+    fn generate_command_copy_ok() {
+        let params = vec!["copy".to_string(), "key".to_string(), "key1".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _key2 = "key1".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Copy {
+                key_origin: _key,
+                key_destination: _key2,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_get_without_param_err() {
+        let params = vec!["get".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_get_ok() {
+        let params = vec!["get".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Get { key: _key } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_getset_without_param_err() {
+        let params = vec!["getset".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_getset_with_one_param_err() {
+        let params = vec!["getset".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_getset_ok() {
+        let params = vec!["getset".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _value = "value".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Getset {
+                key: _key,
+                value: _value,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_set_without_param_err() {
+        let params = vec!["set".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_set_with_one_param_err() {
+        let params = vec!["set".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_set_ok() {
+        let params = vec!["set".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _value = "value".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Set {
+                key: _key,
+                value: _value,
+                options: _,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_del_without_param_err() {
+        let params = vec!["del".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_del_ok() {
+        let params = vec!["del".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _keys = vec!["key".to_string()];
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Del { keys: _keys } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_unlink_ok() {
+        let params = vec!["unlink".to_string(), "key1".to_string(), "key2".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(result.unwrap(), Command::Unlink { keys } if keys.len() == 2));
+
+        let params = vec!["unlink".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_mget_without_param_err() {
+        let params = vec!["mget".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_mget_ok() {
+        let params = vec!["mget".to_string(), "key1".to_string(), "key2".to_string()];
+        let result = generate(params, 1);
+
+        let _keys = vec!["key1".to_string(), "key2".to_string()];
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Mget { keys: _keys } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_mset_without_param_err() {
+        let params = vec!["mset".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_mset_with_missing_value_err() {
+        let params = vec![
+            "mset".to_string(),
+            "key1".to_string(),
+            "value1".to_string(),
+            "key2".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_mset_ok() {
+        let params = vec![
+            "mset".to_string(),
+            "key1".to_string(),
+            "value1".to_string(),
+            "key2".to_string(),
+            "value2".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        let _pairs = vec![
+            ("key1".to_string(), "value1".to_string()),
+            ("key2".to_string(), "value2".to_string()),
+        ];
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Mset { key_values: _pairs } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_strlen_without_param_err() {
+        let params = vec!["strlen".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_strlen_ok() {
+        let params = vec!["strlen".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Strlen { key: _key } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_exists_without_param_err() {
+        let params = vec!["exists".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_exists_ok() {
+        let params = vec!["exists".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _keys = ["key".to_string()];
+        assert!(result.is_ok());
+
+        assert!(matches!(result.unwrap(), Command::Exists { keys: _keys }));
+
+        let params = vec!["exists".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(match result.unwrap() {
+            Command::Ping { .. } => false,
+            _ => true,
+        });
+    }
+
+    #[test]
+    fn generate_command_randomkey() {
+        let params = vec!["randomkey".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(result.unwrap(), Command::Randomkey));
+
+        let params = vec!["randomkey".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_renamenx_ok() {
+        let params = vec!["renamenx".to_string(), "a".to_string(), "b".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Renamenx { key_origin, key_destination } if key_origin == "a" && key_destination == "b"
+        ));
+    }
+
+    #[test]
+    fn generate_command_rename_without_param_err() {
+        let params = vec!["rename".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_rename_ok() {
+        let params = vec!["rename".to_string(), "key1".to_string(), "key2".to_string()];
+        let result = generate(params, 1);
+
+        let _key_origin = "key1".to_string();
+        let _key_destination = "key2".to_string();
+
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Rename {
+                key_origin: _key_origin,
+                key_destination: _key_destination,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_expire_without_param_err() {
+        let params = vec!["expire".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_expire_with_fractional_time_err() {
+        let params = vec!["expire".to_string(), "key".to_string(), "10.5".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_expire_ok() {
+        let params = vec!["expire".to_string(), "key".to_string(), "1".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _ttl = Duration::from_secs(1);
+
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Expire {
+                key: _key,
+                ttl: _ttl,
+                ..
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_expire_negative_time_ok() {
+        let params = vec!["expire".to_string(), "key".to_string(), "-10".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Expire { ttl, .. } if ttl == Duration::from_secs(0)
+        ));
+    }
+
+    #[test]
+    fn generate_command_expire_overflow_err() {
+        let params = vec![
+            "expire".to_string(),
+            "key".to_string(),
+            "9223372036854775807".to_string(),
+        ];
+        let result = generate(params, 1);
+        assert_eq!(
+            "ERR invalid expire time in 'expire' command",
+            result.err().unwrap()
+        );
+
+        let params = vec![
+            "pexpireat".to_string(),
+            "key".to_string(),
+            "18446744073709551615".to_string(),
+        ];
+        let result = generate(params, 1);
+        assert_eq!(
+            "ERR invalid expire time in 'pexpireat' command",
+            result.err().unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_command_expire_with_conditions() {
+        let params = vec![
+            "expire".to_string(),
+            "key".to_string(),
+            "10".to_string(),
+            "XX".to_string(),
+            "lt".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Expire { conditions, .. }
+                if conditions == vec![ExpireCondition::HasExpiry, ExpireCondition::Less]
+        ));
+
+        let params = vec![
+            "expire".to_string(),
+            "key".to_string(),
+            "10".to_string(),
+            "nx".to_string(),
+            "gt".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert_eq!(
+            "ERR NX and XX, GT or LT options at the same time are not compatible",
+            result.unwrap_err()
+        );
+
+        let params = vec![
+            "pexpire".to_string(),
+            "key".to_string(),
+            "10".to_string(),
+            "other".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert_eq!("ERR Unsupported option other", result.unwrap_err());
+    }
+
+    #[test]
+    fn generate_command_expireat_without_param_err() {
+        let params = vec!["expireat".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_expireat_with_fractional_time_err() {
+        let params = vec![
+            "expireat".to_string(),
+            "key".to_string(),
+            "10.5".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_expireat_ok() {
+        let params = vec!["expireat".to_string(), "key".to_string(), "1".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Expireat {
+                key: _key,
+                ttl: _ttl,
+                ..
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_pexpire_ok() {
+        let params = vec!["pexpire".to_string(), "key".to_string(), "1500".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Pexpire { key, ttl, .. } if key == "key" && ttl == Duration::from_millis(1500)
+        ));
+
+        let params = vec!["pexpire".to_string(), "key".to_string(), "1.5".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_pexpireat_ok() {
+        let params = vec![
+            "pexpireat".to_string(),
+            "key".to_string(),
+            "1500".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Pexpireat { ttl, .. } if ttl == SystemTime::UNIX_EPOCH + Duration::from_millis(1500)
+        ));
+    }
+
+    #[test]
+    fn generate_command_pttl_ok() {
+        let params = vec!["pttl".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(result.unwrap(), Command::Pttl { key } if key == "key"));
+    }
+
+    #[test]
+    fn generate_command_expiretime_ok() {
+        let params = vec!["expiretime".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(result.unwrap(), Command::Expiretime { key } if key == "key"));
+
+        let params = vec!["pexpiretime".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_persist_without_param_err() {
+        let params = vec!["persist".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_persist_ok() {
+        let params = vec!["persist".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Persist { key: _key } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_sort_without_param_err() {
+        let params = vec!["sort".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_sort_ok() {
+        let params = vec!["sort".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Sort { key: _key, .. } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_sort_with_options_ok() {
+        let params = vec![
+            "sort", "key", "BY", "weight_*", "LIMIT", "1", "2", "get", "#", "GET", "obj_*", "DESC",
+            "alpha", "store", "dest",
+        ]
+        .iter()
+        .map(|param| param.to_string())
+        .collect();
+        let result = generate(params, 1);
+
+        let expected = SortOptions {
+            by: Some("weight_*".to_string()),
+            limit: Some((1, 2)),
+            get: vec!["#".to_string(), "obj_*".to_string()],
+            descending: true,
+            alpha: true,
+            store: Some("dest".to_string()),
+        };
+        assert!(matches!(
+            result.unwrap(),
+            Command::Sort { key, options } if key == "key" && options == expected
+        ));
+    }
+
+    #[test]
+    fn generate_command_sort_with_invalid_options_err() {
+        let params = vec!["sort".to_string(), "key".to_string(), "by".to_string()];
+        assert_eq!("ERR syntax error", generate(params, 1).err().unwrap());
+
+        let params = vec!["sort".to_string(), "key".to_string(), "random".to_string()];
+        assert_eq!("ERR syntax error", generate(params, 1).err().unwrap());
+
+        let params = vec![
+            "sort".to_string(),
+            "key".to_string(),
+            "limit".to_string(),
+            "a".to_string(),
+            "1".to_string(),
+        ];
+        assert_eq!(
+            "ERR value is not an integer or out of range",
+            generate(params, 1).err().unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_command_object_freq_ok() {
+        let params = vec!["object".to_string(), "FREQ".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Object {
+                param: ObjectParam::Freq(key)
+            } if key == "key"
+        ));
+
+        let params = vec!["object".to_string(), "freq".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "object".to_string(),
+            "encoding".to_string(),
+            "key".to_string(),
+        ];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Object {
+                param: ObjectParam::Encoding(key)
+            } if key == "key"
+        ));
+    }
+
+    #[test]
+    fn generate_command_touch_without_param_err() {
+        let params = vec!["touch".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_touch_ok() {
+        let params = vec!["touch".to_string(), "key1".to_string(), "key2".to_string()];
+        let result = generate(params, 1);
+
+        let _keys = vec!["key1".to_string(), "key2".to_string()];
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Touch { keys: _keys } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_ttl_without_param_err() {
+        let params = vec!["ttl".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_ttl_ok() {
+        let params = vec!["ttl".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Ttl { key: _key } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_type_without_param_err() {
+        let params = vec!["type".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_type_ok() {
+        let params = vec!["type".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Type { key: _key } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_incrby_without_param_err() {
+        let params = vec!["incrby".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["incrby".to_string(), "key".to_string(), "hola".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_incrby_ok() {
+        let params = vec!["incrby".to_string(), "key1".to_string(), "1".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key1".to_string();
+
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Incrby {
+                key: _key,
+                increment: 1,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_decrby_without_param_err() {
+        let params = vec!["decrby".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["decrby".to_string(), "key".to_string(), "hola".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_decrby_ok() {
+        let params = vec!["decrby".to_string(), "key1".to_string(), "1".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key1".to_string();
+
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Decrby {
+                key: _key,
+                decrement: 1,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_getdel_without_param_err() {
+        let params = vec!["getdel".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_getdel_ok() {
+        let params = vec!["getdel".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(matches!(result.unwrap(), Command::Getdel { key: _key }));
+
+        let params = vec!["getdel".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(match result.unwrap() {
+            Command::Ping { .. } => false,
+            _ => true,
+        });
+    }
+
+    #[test]
+    fn generate_command_append_without_param_err() {
+        let params = vec!["append".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_append_ok() {
+        let params = vec!["append".to_string(), "key".to_string(), "Value".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _value = "Value".to_string();
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Append {
+                key: _key,
+                value: _value,
+            }
+        ));
+
+        let params = vec!["append".to_string(), "key".to_string(), "Value".to_string()];
+        let result = generate(params, 1);
+
+        assert!(match result.unwrap() {
+            Command::Ping { .. } => false,
+            _ => true,
+        });
+    }
+
+    #[test]
+    fn generate_command_with_command_dbsize() {
+        let params = vec!["dbsize".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Dbsize => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_lindex_incorrect_params_err() {
+        let params = vec!["lindex".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["lindex".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["lindex".to_string(), "key".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "lindex".to_string(),
+            "key".to_string(),
+            "1".to_string(),
+            "value".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_lindex_ok() {
+        let params = vec!["lindex".to_string(), "key".to_string(), "1".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _index = 1;
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Lindex {
+                key: _key,
+                index: _index,
+            } => true,
+            _ => false,
+        });
+
+        let params = vec!["lindex".to_string(), "key".to_string(), "-1".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _index = -1;
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Lindex {
+                key: _key,
+                index: _index,
+            } => true,
+            _ => false,
+        });
     }
 
     #[test]
-    fn generate_command_getset_ok() {
-        let params = vec!["getset".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_llen_without_param_err() {
+        let params = vec!["llen".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_llen_ok() {
+        let params = vec!["llen".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
         let _key = "key".to_string();
-        let _value = "value".to_string();
         assert!(result.is_ok());
         assert!(match result.unwrap() {
-            Command::Getset {
+            Command::Llen { key: _key } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_lpop_without_param_err() {
+        let params = vec!["lpop".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_lpop_without_param_count_not_u32_err() {
+        let params = vec!["lpop".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_lpop_ok() {
+        let params = vec!["lpop".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Lpop {
+                key: _key,
+                count: None,
+            } => true,
+            _ => false,
+        });
+
+        let params = vec!["lpop".to_string(), "key".to_string(), "3".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Lpop {
+                key: _key,
+                count: Some(3),
+            } => true,
+            _ => false,
+        });
+
+        let params = vec!["lpop".to_string(), "key".to_string(), "-1".to_string()];
+        assert_eq!(
+            "ERR value is out of range, must be positive",
+            generate(params, 1).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn generate_command_lrange_bad_params_err() {
+        let params = vec!["lrange".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "lrange".to_string(),
+            "key".to_string(),
+            "a".to_string(),
+            "1".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "lrange".to_string(),
+            "key".to_string(),
+            "1".to_string(),
+            "a".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "lrange".to_string(),
+            "key".to_string(),
+            "1".to_string(),
+            "2".to_string(),
+            "3".to_string(),
+        ];
+        let result = generate(params, 1);
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_lrange_ok() {
+        let params = vec![
+            "lrange".to_string(),
+            "key".to_string(),
+            "0".to_string(),
+            "-1".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Lrange {
+                key: _key,
+                begin: 0,
+                end: -1,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_lrem_err() {
+        let params = vec![
+            "lrem".to_string(),
+            "key".to_string(),
+            "-1".to_string(),
+            "element".to_string(),
+            "element".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["lrem".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "lrem".to_string(),
+            "key".to_string(),
+            "a".to_string(),
+            "element".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_lrem_ok() {
+        let params = vec![
+            "lrem".to_string(),
+            "key".to_string(),
+            "0".to_string(),
+            "element".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _element = "element".to_string();
+
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Lrem {
+                key: _key,
+                count: 0,
+                element: _element,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_lset_err() {
+        let params = vec![
+            "lset".to_string(),
+            "key".to_string(),
+            "-1".to_string(),
+            "element".to_string(),
+            "element".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["lset".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "lset".to_string(),
+            "key".to_string(),
+            "a".to_string(),
+            "element".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_command_lset_ok() {
+        let params = vec![
+            "lset".to_string(),
+            "key".to_string(),
+            "1".to_string(),
+            "Hola".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        let _index = "1".to_string();
+        let _element = "Hola".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Lset {
+                key: _key,
+                index: _index,
+                element: _element,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_rpop_without_param_err() {
+        let params = vec!["rpop".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_rpop_without_param_count_not_u32_err() {
+        let params = vec!["rpop".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_rpop_ok() {
+        let params = vec!["rpop".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Rpop {
+                key: _key,
+                count: None,
+            } => true,
+            _ => false,
+        });
+
+        let params = vec!["rpop".to_string(), "key".to_string(), "3".to_string()];
+        let result = generate(params, 1);
+
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Rpop {
                 key: _key,
-                value: _value,
+                count: Some(3),
             } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_set_without_param_err() {
-        let params = vec!["set".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_lpush_incorrect_params_err() {
+        let params = vec!["lpush".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
-    }
+        assert!(result.is_err());
 
-    #[test]
-    fn generate_command_set_with_one_param_err() {
-        let params = vec!["set".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["lpush".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_set_ok() {
-        let params = vec!["set".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_lpush_ok() {
+        let params = vec!["lpush".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
 
         let _key = "key".to_string();
-        let _value = "value".to_string();
+        let _value = vec!["value".to_string()];
         assert!(result.is_ok());
         assert!(match result.unwrap() {
-            Command::Set {
+            Command::Lpush {
                 key: _key,
                 value: _value,
             } => true,
@@ -936,1159 +4110,1655 @@ mod test {
     }
 
     #[test]
-    fn generate_command_del_without_param_err() {
-        let params = vec!["del".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_lpushx_incorrect_params_err() {
+        let params = vec!["lpushx".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["lpushx".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_del_ok() {
-        let params = vec!["del".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_rpush_incorrect_params_err() {
+        let params = vec!["rpush".to_string()];
+        let result = generate(params, 1);
 
-        let _keys = vec!["key".to_string()];
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Del { keys: _keys } => true,
-            _ => false,
-        });
-    }
+        assert!(result.is_err());
 
-    #[test]
-    fn generate_command_mget_without_param_err() {
-        let params = vec!["mget".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["rpush".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_mget_ok() {
-        let params = vec!["mget".to_string(), "key1".to_string(), "key2".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_rpush_ok() {
+        let params = vec!["rpush".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
 
-        let _keys = vec!["key1".to_string(), "key2".to_string()];
+        let _key = "key".to_string();
+        let _value = vec!["value".to_string()];
         assert!(result.is_ok());
         assert!(match result.unwrap() {
-            Command::Mget { keys: _keys } => true,
+            Command::Rpush {
+                key: _key,
+                value: _value,
+            } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_mset_without_param_err() {
-        let params = vec!["mset".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_rpushx_incorrect_params_err() {
+        let params = vec!["rpushx".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["rpushx".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_mset_with_missing_value_err() {
-        let params = vec![
-            "mset".to_string(),
-            "key1".to_string(),
-            "value1".to_string(),
-            "key2".to_string(),
-        ];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sadd_incorrect_params_err() {
+        let params = vec!["sadd".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
+
+        let params = vec!["sadd".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_mset_ok() {
-        let params = vec![
-            "mset".to_string(),
-            "key1".to_string(),
-            "value1".to_string(),
-            "key2".to_string(),
-            "value2".to_string(),
-        ];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_lpushx_ok() {
+        let params = vec!["lpushx".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
 
-        let _pairs = vec![
-            ("key1".to_string(), "value1".to_string()),
-            ("key2".to_string(), "value2".to_string()),
-        ];
+        let _key = "key".to_string();
+        let _value = vec!["value".to_string()];
         assert!(result.is_ok());
         assert!(match result.unwrap() {
-            Command::Mset { key_values: _pairs } => true,
+            Command::Lpushx {
+                key: _key,
+                value: _value,
+            } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_strlen_without_param_err() {
-        let params = vec!["strlen".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        assert!(result.is_err())
-    }
-
-    #[test]
-    fn generate_command_strlen_ok() {
-        let params = vec!["strlen".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sadd_ok() {
+        let params = vec!["sadd".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
 
         let _key = "key".to_string();
+        let mut _values = HashSet::new();
+        _values.insert("value1".to_string());
+        _values.insert("value2".to_string());
         assert!(result.is_ok());
         assert!(match result.unwrap() {
-            Command::Strlen { key: _key } => true,
+            Command::Sadd {
+                key: _key,
+                values: _values,
+            } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_exists_without_param_err() {
-        let params = vec!["exists".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_scard_without_param_err() {
+        let params = vec!["scard".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_exists_ok() {
-        let params = vec!["exists".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_scard_ok() {
+        let params = vec!["scard".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
-        let _keys = vec!["key".to_string()];
+        let _key = "key".to_string();
         assert!(result.is_ok());
-
         assert!(match result.unwrap() {
-            Command::Exists { keys: _keys } => true,
+            Command::Scard { key: _key } => true,
             _ => false,
         });
-
-        let params = vec!["exists".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        assert!(match result.unwrap() {
-            Command::Ping => false,
-            _ => true,
-        });
     }
 
     #[test]
-    fn generate_command_rename_without_param_err() {
-        let params = vec!["rename".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sismember_without_param_err() {
+        let params = vec!["sismember".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_rename_ok() {
-        let params = vec!["rename".to_string(), "key1".to_string(), "key2".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sismember_ok() {
+        let params = vec![
+            "sismember".to_string(),
+            "key".to_string(),
+            "value".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let _key_origin = "key1".to_string();
-        let _key_destination = "key2".to_string();
+        let _key = "key".to_string();
+        let _value = "value".to_string();
 
         assert!(result.is_ok());
-
         assert!(match result.unwrap() {
-            Command::Rename {
-                key_origin: _key_origin,
-                key_destination: _key_destination,
+            Command::Sismember {
+                key: _key,
+                value: _value,
             } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_expire_without_param_err() {
-        let params = vec!["expire".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_srem_incorrect_params_err() {
+        let params = vec!["srem".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err());
-    }
 
-    #[test]
-    fn generate_command_expire_with_fractional_time_err() {
-        let params = vec!["expire".to_string(), "key".to_string(), "10.5".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["srem".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_expire_ok() {
-        let params = vec!["expire".to_string(), "key".to_string(), "1".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_srem_ok() {
+        let params = vec!["srem".to_string(), "key".to_string(), "value".to_string()];
+        let result = generate(params, 1);
 
         let _key = "key".to_string();
-        let _ttl = Duration::from_secs(1);
-
+        let mut _values = HashSet::new();
+        _values.insert("value1".to_string());
+        _values.insert("value2".to_string());
         assert!(result.is_ok());
-
         assert!(match result.unwrap() {
-            Command::Expire {
+            Command::Srem {
                 key: _key,
-                ttl: _ttl,
+                values: _values,
             } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_expireat_without_param_err() {
-        let params = vec!["expireat".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_smembers_without_param_err() {
+        let params = vec!["smembers".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_expireat_with_fractional_time_err() {
-        let params = vec![
-            "expireat".to_string(),
-            "key".to_string(),
-            "10.5".to_string(),
-        ];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_smembers_ok() {
+        let params = vec!["smembers".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        let _key = "key".to_string();
+        assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Smembers { key: _key } => true,
+            _ => false,
+        });
     }
 
     #[test]
-    fn generate_command_expireat_ok() {
-        let params = vec!["expireat".to_string(), "key".to_string(), "1".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        let _ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+    fn generate_command_keys_ok() {
+        let params = vec!["keys".to_string(), "/*".to_string()];
+        let result = generate(params, 1);
 
+        let _pattern = "/*".to_string();
         assert!(result.is_ok());
-
         assert!(match result.unwrap() {
-            Command::Expireat {
-                key: _key,
-                ttl: _ttl,
-            } => true,
+            Command::Keys { pattern: _pattern } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_persist_without_param_err() {
-        let params = vec!["persist".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_store_without_param_err() {
+        let params = vec!["store".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_persist_ok() {
-        let params = vec!["persist".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_store_ok() {
+        let params = vec!["store".to_string(), "/store.file".to_string()];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
+        let _path = "/store.file".to_string();
         assert!(result.is_ok());
+        assert!(match result.unwrap() {
+            Command::Store { path: _path } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_config_set_without_param_err() {
+        let params = vec!["config".to_string(), "set".to_string()];
+        let result = generate(params, 1);
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_config_set_ok() {
+        let params = vec![
+            "config".to_string(),
+            "set".to_string(),
+            "verbose".to_string(),
+            "1".to_string(),
+        ];
+        let result = generate(params, 1);
 
+        assert!(result.is_ok());
+        let _parameter = "verbose".to_string();
+        let _value = "1".to_string();
         assert!(match result.unwrap() {
-            Command::Persist { key: _key } => true,
+            Command::ConfigSet {
+                parameter: _parameter,
+                value: _value,
+            } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_sort_without_param_err() {
-        let params = vec!["sort".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_config_get_ok() {
+        let params = vec!["config".to_string(), "get".to_string(), "max*".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert!(matches!(
+            result.unwrap(),
+            Command::ConfigGet { patterns } if patterns == vec!["max*".to_string()]
+        ));
     }
 
     #[test]
-    fn generate_command_sort_ok() {
-        let params = vec!["sort".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        assert!(result.is_ok());
+    fn generate_command_config_get_without_pattern_err() {
+        let params = vec!["config".to_string(), "get".to_string()];
+        let result = generate(params, 1);
 
-        assert!(match result.unwrap() {
-            Command::Sort { key: _key } => true,
-            _ => false,
-        });
+        assert_eq!(
+            "ERR wrong number of arguments for 'config get' command",
+            result.unwrap_err()
+        );
     }
 
     #[test]
-    fn generate_command_touch_without_param_err() {
-        let params = vec!["touch".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_load_without_param_err() {
+        let params = vec!["load".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_touch_ok() {
-        let params = vec!["touch".to_string(), "key1".to_string(), "key2".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_load_ok() {
+        let params = vec!["load".to_string(), "/store.file".to_string()];
+        let result = generate(params, 1);
 
-        let _keys = vec!["key1".to_string(), "key2".to_string()];
+        let _path = "/store.file".to_string();
         assert!(result.is_ok());
-
         assert!(match result.unwrap() {
-            Command::Touch { keys: _keys } => true,
+            Command::Load { path: _path } => true,
             _ => false,
         });
     }
 
     #[test]
-    fn generate_command_ttl_without_param_err() {
-        let params = vec!["ttl".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_zadd_with_missing_member_err() {
+        let params = vec![
+            "zadd".to_string(),
+            "key".to_string(),
+            "1".to_string(),
+            "a".to_string(),
+            "2".to_string(),
+        ];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_ttl_ok() {
-        let params = vec!["ttl".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_zadd_with_invalid_score_err() {
+        let params = vec![
+            "zadd".to_string(),
+            "key".to_string(),
+            "nan".to_string(),
+            "a".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
+        assert!(result.is_err())
+    }
 
-        assert!(match result.unwrap() {
-            Command::Ttl { key: _key } => true,
-            _ => false,
-        });
+    #[test]
+    fn generate_command_zadd_ok() {
+        let params = vec![
+            "zadd".to_string(),
+            "key".to_string(),
+            "1.5".to_string(),
+            "a".to_string(),
+            "-inf".to_string(),
+            "b".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zadd { key, members }
+                if key == "key"
+                    && members == vec![(1.5, "a".to_string()), (f64::NEG_INFINITY, "b".to_string())]
+        ));
     }
 
     #[test]
-    fn generate_command_type_without_param_err() {
-        let params = vec!["type".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_zrange_with_invalid_index_err() {
+        let params = vec![
+            "zrange".to_string(),
+            "key".to_string(),
+            "a".to_string(),
+            "1".to_string(),
+        ];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_type_ok() {
-        let params = vec!["type".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
+    fn generate_command_zrangebyscore_ok() {
+        let params = vec![
+            "zrangebyscore".to_string(),
+            "key".to_string(),
+            "(1".to_string(),
+            "+inf".to_string(),
+        ];
+        let result = generate(params, 1);
 
         assert!(result.is_ok());
-
-        assert!(match result.unwrap() {
-            Command::Type { key: _key } => true,
-            _ => false,
-        });
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zrangebyscore { min, max, .. }
+                if min == ScoreBound::Exclusive(1.0) && max == ScoreBound::Inclusive(f64::INFINITY)
+        ));
     }
 
     #[test]
-    fn generate_command_incrby_without_param_err() {
-        let params = vec!["incrby".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        assert!(result.is_err());
+    fn generate_command_zrange_withscores_ok() {
+        let params = vec![
+            "zrange".to_string(),
+            "key".to_string(),
+            "0".to_string(),
+            "-1".to_string(),
+            "WithScores".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let params = vec!["incrby".to_string(), "key".to_string(), "hola".to_string()];
-        let result = generate(params, "client-test".to_string());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zrange { with_scores, .. } if with_scores
+        ));
 
-        assert!(result.is_err())
+        let params = vec![
+            "zrangebyscore".to_string(),
+            "key".to_string(),
+            "0".to_string(),
+            "1".to_string(),
+            "limit".to_string(),
+        ];
+        assert_eq!("ERR syntax error", generate(params, 1).unwrap_err());
     }
 
     #[test]
-    fn generate_command_incrby_ok() {
-        let params = vec!["incrby".to_string(), "key1".to_string(), "1".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key1".to_string();
-
-        assert!(result.is_ok());
-
-        assert!(match result.unwrap() {
-            Command::Incrby {
-                key: _key,
-                increment: 1,
-            } => true,
-            _ => false,
-        });
+    fn generate_command_zunionstore_with_options() {
+        let params: Vec<String> = vec![
+            "zunionstore",
+            "dest",
+            "2",
+            "a",
+            "b",
+            "WEIGHTS",
+            "2",
+            "-inf",
+            "aggregate",
+            "MAX",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zunionstore { destination, keys, weights, aggregate }
+                if destination == "dest"
+                    && keys == vec!["a".to_string(), "b".to_string()]
+                    && weights == vec![2.0, f64::NEG_INFINITY]
+                    && aggregate == Aggregate::Max
+        ));
     }
 
     #[test]
-    fn generate_command_decrby_without_param_err() {
-        let params = vec!["decrby".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        assert!(result.is_err());
+    fn generate_command_zinterstore_errors() {
+        let zinterstore = |args: &[&str]| {
+            let mut params = vec!["zinterstore".to_string()];
+            params.extend(args.iter().map(|arg| arg.to_string()));
+            generate(params, 1)
+        };
+
+        assert!(matches!(
+            zinterstore(&["dest", "1", "a"]).unwrap(),
+            Command::Zinterstore { weights, aggregate, .. }
+                if weights == vec![1.0] && aggregate == Aggregate::Sum
+        ));
+        assert_eq!(
+            "ERR at least 1 input key is needed for 'zinterstore' command",
+            zinterstore(&["dest", "0", "a"]).unwrap_err()
+        );
+        assert_eq!(
+            "ERR syntax error",
+            zinterstore(&["dest", "3", "a", "b"]).unwrap_err()
+        );
+        assert_eq!(
+            "ERR syntax error",
+            zinterstore(&["dest", "2", "a", "b", "weights", "1"]).unwrap_err()
+        );
+        assert_eq!(
+            "ERR weight value is not a float",
+            zinterstore(&["dest", "1", "a", "weights", "x"]).unwrap_err()
+        );
+        assert_eq!(
+            "ERR syntax error",
+            zinterstore(&["dest", "1", "a", "aggregate", "avg"]).unwrap_err()
+        );
+    }
 
-        let params = vec!["decrby".to_string(), "key".to_string(), "hola".to_string()];
-        let result = generate(params, "client-test".to_string());
+    #[test]
+    fn generate_command_zincrby_ok() {
+        let params = vec![
+            "zincrby".to_string(),
+            "key".to_string(),
+            "-2.5".to_string(),
+            "a".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zincrby { key, increment, member }
+                if key == "key" && increment == -2.5 && member == "a"
+        ));
     }
 
     #[test]
-    fn generate_command_decrby_ok() {
-        let params = vec!["decrby".to_string(), "key1".to_string(), "1".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_zpop_count() {
+        let params = vec!["zpopmax".to_string(), "key".to_string(), "2".to_string()];
+        let result = generate(params, 1);
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zpopmax { count: Some(2), .. }
+        ));
 
-        let _key = "key1".to_string();
+        let params = vec!["zpopmin".to_string(), "key".to_string()];
+        let result = generate(params, 1);
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zpopmin { count: None, .. }
+        ));
 
-        assert!(result.is_ok());
+        let params = vec!["zpopmin".to_string(), "key".to_string(), "-1".to_string()];
+        assert!(generate(params, 1).is_err());
+    }
 
-        assert!(match result.unwrap() {
-            Command::Decrby {
-                key: _key,
-                decrement: 1,
-            } => true,
-            _ => false,
-        });
+    #[test]
+    fn generate_command_bzpop_ok() {
+        let params = vec![
+            "bzpopmin".to_string(),
+            "zset1".to_string(),
+            "zset2".to_string(),
+            "0.5".to_string(),
+        ];
+        let result = generate(params, 1);
+        assert!(matches!(
+            result.unwrap(),
+            Command::Bzpopmin { keys, timeout: Some(timeout) }
+                if keys.len() == 2 && timeout == Duration::from_millis(500)
+        ));
+
+        let params = vec!["bzpopmax".to_string(), "zset".to_string(), "0".to_string()];
+        let result = generate(params, 1);
+        assert!(matches!(
+            result.unwrap(),
+            Command::Bzpopmax { timeout: None, .. }
+        ));
+
+        let params = vec!["bzpopmax".to_string(), "zset".to_string()];
+        assert_eq!(
+            "ERR wrong number of arguments for 'bzpopmax' command",
+            generate(params, 1).unwrap_err()
+        );
     }
 
     #[test]
-    fn generate_command_getdel_without_param_err() {
-        let params = vec!["getdel".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_zrangebyscore_with_invalid_bound_err() {
+        let params = vec![
+            "zrangebyscore".to_string(),
+            "key".to_string(),
+            "(a".to_string(),
+            "1".to_string(),
+        ];
+        let result = generate(params, 1);
 
         assert!(result.is_err())
     }
 
     #[test]
-    fn generate_command_getdel_ok() {
-        let params = vec!["getdel".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_auth_ok() {
+        let params = vec!["auth".to_string(), "secret".to_string()];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Getdel { key: _key } => true,
-            _ => false,
-        });
+        assert!(matches!(
+            result.unwrap(),
+            Command::Auth { password } if password == "secret"
+        ));
+    }
 
-        let params = vec!["getdel".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    #[test]
+    fn generate_command_auth_with_username_ok() {
+        let params = vec![
+            "auth".to_string(),
+            "default".to_string(),
+            "secret".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(match result.unwrap() {
-            Command::Ping => false,
-            _ => true,
-        });
+        assert!(matches!(
+            result.unwrap(),
+            Command::Auth { password } if password == "secret"
+        ));
     }
 
     #[test]
-    fn generate_command_append_without_param_err() {
-        let params = vec!["append".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_auth_without_password_err() {
+        let params = vec!["auth".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert!(result.is_err());
     }
 
     #[test]
-    fn generate_command_append_ok() {
-        let params = vec!["append".to_string(), "key".to_string(), "Value".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sscan_with_options_ok() {
+        let params = vec![
+            "sscan".to_string(),
+            "key".to_string(),
+            "0".to_string(),
+            "MATCH".to_string(),
+            "a*".to_string(),
+            "COUNT".to_string(),
+            "5".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        let _value = "Value".to_string();
+        assert!(matches!(
+            result.unwrap(),
+            Command::Sscan { cursor: 0, pattern: Some(pattern), count: 5, .. } if pattern == "a*"
+        ));
+    }
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Append {
-                key: _key,
-                value: _value,
-            } => true,
-            _ => false,
-        });
+    #[test]
+    fn generate_command_sscan_invalid_cursor_err() {
+        let params = vec!["sscan".to_string(), "key".to_string(), "abc".to_string()];
+        let result = generate(params, 1);
 
-        let params = vec!["append".to_string(), "key".to_string(), "Value".to_string()];
-        let result = generate(params, "client-test".to_string());
+        assert_eq!("ERR invalid cursor", result.err().unwrap());
+    }
 
-        assert!(match result.unwrap() {
-            Command::Ping => false,
-            _ => true,
-        });
+    #[test]
+    fn generate_command_sscan_unknown_option_err() {
+        let params = vec![
+            "sscan".to_string(),
+            "key".to_string(),
+            "0".to_string(),
+            "LIMIT".to_string(),
+            "5".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(result.is_err());
     }
 
     #[test]
-    fn generate_command_with_command_dbsize() {
-        let params = vec!["dbsize".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_blpop_ok() {
+        let params = vec![
+            "blpop".to_string(),
+            "list1".to_string(),
+            "list2".to_string(),
+            "1.5".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Dbsize => true,
-            _ => false,
-        });
+        assert!(matches!(
+            result.unwrap(),
+            Command::Blpop { keys, timeout: Some(timeout) }
+                if keys.len() == 2 && timeout == Duration::from_millis(1500)
+        ));
     }
 
     #[test]
-    fn generate_command_lindex_incorrect_params_err() {
-        let params = vec!["lindex".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_brpop_zero_timeout_blocks_forever() {
+        let params = vec!["brpop".to_string(), "list".to_string(), "0".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Brpop { timeout: None, .. }
+        ));
+    }
 
-        let params = vec!["lindex".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    #[test]
+    fn generate_command_blpop_negative_timeout_err() {
+        let params = vec!["blpop".to_string(), "list".to_string(), "-1".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert_eq!("ERR timeout is negative", result.err().unwrap());
+    }
 
-        let params = vec!["lindex".to_string(), "key".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    #[test]
+    fn generate_command_spop_negative_count_err() {
+        let params = vec!["spop".to_string(), "key".to_string(), "-1".to_string()];
+        let result = generate(params, 1);
 
         assert!(result.is_err());
+    }
 
+    #[test]
+    fn generate_command_srandmember_negative_count_ok() {
         let params = vec![
-            "lindex".to_string(),
+            "srandmember".to_string(),
             "key".to_string(),
-            "1".to_string(),
-            "value".to_string(),
+            "-2".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Srandmember {
+                count: Some(-2),
+                ..
+            }
+        ));
     }
 
     #[test]
-    fn generate_command_lindex_ok() {
-        let params = vec!["lindex".to_string(), "key".to_string(), "1".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        let _index = 1;
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lindex {
-                key: _key,
-                index: _index,
-            } => true,
-            _ => false,
-        });
-
-        let params = vec!["lindex".to_string(), "key".to_string(), "-1".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sinter_without_keys_err() {
+        let params = vec!["sinter".to_string()];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        let _index = -1;
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lindex {
-                key: _key,
-                index: _index,
-            } => true,
-            _ => false,
-        });
+        assert!(result.is_err());
     }
 
     #[test]
-    fn generate_command_llen_without_param_err() {
-        let params = vec!["llen".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sdiff_ok() {
+        let params = vec!["sdiff".to_string(), "key1".to_string(), "key2".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert!(matches!(result.unwrap(), Command::Sdiff { keys } if keys.len() == 2));
     }
 
     #[test]
-    fn generate_command_llen_ok() {
-        let params = vec!["llen".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Llen { key: _key } => true,
-            _ => false,
-        });
+    fn generate_command_sintercard_with_limit_ok() {
+        let params = vec![
+            "sintercard".to_string(),
+            "2".to_string(),
+            "key1".to_string(),
+            "key2".to_string(),
+            "LIMIT".to_string(),
+            "5".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Sintercard { keys, limit: 5 } if keys.len() == 2
+        ));
     }
 
     #[test]
-    fn generate_command_lpop_without_param_err() {
-        let params = vec!["lpop".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_sintercard_numkeys_greater_than_args_err() {
+        let params = vec![
+            "sintercard".to_string(),
+            "3".to_string(),
+            "key1".to_string(),
+            "key2".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert_eq!(
+            "ERR Number of keys can't be greater than number of args",
+            result.err().unwrap()
+        );
     }
 
     #[test]
-    fn generate_command_lpop_without_param_count_not_u32_err() {
-        let params = vec!["lpop".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_smismember_without_members_err() {
+        let params = vec!["smismember".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert!(result.is_err());
     }
 
     #[test]
-    fn generate_command_lpop_ok() {
-        let params = vec!["lpop".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lpop {
-                key: _key,
-                count: 0,
-            } => true,
-            _ => false,
-        });
-
-        let params = vec!["lpop".to_string(), "key".to_string(), "3".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_set_nx_ok() {
+        let params = vec![
+            "set".to_string(),
+            "key".to_string(),
+            "value".to_string(),
+            "NX".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lpop {
-                key: _key,
-                count: 3,
-            } => true,
-            _ => false,
-        });
+        assert!(matches!(
+            result.unwrap(),
+            Command::Set { options, .. } if options.condition == SetCondition::IfNotExists
+        ));
     }
 
     #[test]
-    fn generate_command_lrange_bad_params_err() {
-        let params = vec!["lrange".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_set_get_ok() {
+        let params = vec![
+            "set".to_string(),
+            "key".to_string(),
+            "value".to_string(),
+            "GET".to_string(),
+            "NX".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Set { options, .. } if options.get && options.condition == SetCondition::IfNotExists
+        ));
+    }
 
+    #[test]
+    fn generate_command_set_nx_and_xx_err() {
         let params = vec![
-            "lrange".to_string(),
+            "set".to_string(),
             "key".to_string(),
-            "a".to_string(),
-            "1".to_string(),
+            "value".to_string(),
+            "NX".to_string(),
+            "XX".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert_eq!("ERR syntax error", result.err().unwrap());
+    }
 
+    #[test]
+    fn generate_command_msetnx_odd_params_err() {
         let params = vec![
-            "lrange".to_string(),
-            "key".to_string(),
-            "1".to_string(),
-            "a".to_string(),
+            "msetnx".to_string(),
+            "key1".to_string(),
+            "value1".to_string(),
+            "key2".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
         assert!(result.is_err());
+    }
 
+    #[test]
+    fn generate_command_set_px_ok() {
         let params = vec![
-            "lrange".to_string(),
+            "set".to_string(),
             "key".to_string(),
-            "1".to_string(),
-            "2".to_string(),
-            "3".to_string(),
+            "value".to_string(),
+            "PX".to_string(),
+            "1500".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
-        assert!(result.is_err())
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Set { options, .. }
+                if options.expiration == SetExpiration::Relative(Duration::from_millis(1500))
+        ));
     }
 
     #[test]
-    fn generate_command_lrange_ok() {
+    fn generate_command_set_ex_and_keepttl_err() {
         let params = vec![
-            "lrange".to_string(),
+            "set".to_string(),
             "key".to_string(),
-            "0".to_string(),
-            "-1".to_string(),
+            "value".to_string(),
+            "EX".to_string(),
+            "10".to_string(),
+            "KEEPTTL".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lrange {
-                key: _key,
-                begin: 0,
-                end: -1,
-            } => true,
-            _ => false,
-        });
+        assert_eq!("ERR syntax error", result.err().unwrap());
     }
 
     #[test]
-    fn generate_command_lrem_err() {
+    fn generate_command_set_ex_overflow_err() {
         let params = vec![
-            "lrem".to_string(),
+            "set".to_string(),
             "key".to_string(),
-            "-1".to_string(),
-            "element".to_string(),
-            "element".to_string(),
+            "value".to_string(),
+            "EX".to_string(),
+            "9223372036854775807".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
-
-        assert!(result.is_err());
-
-        let params = vec!["lrem".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
+        assert_eq!(
+            "ERR invalid expire time in 'set' command",
+            result.err().unwrap()
+        );
 
-        assert!(result.is_err());
+        let params = vec![
+            "setex".to_string(),
+            "key".to_string(),
+            "9223372036854775807".to_string(),
+            "value".to_string(),
+        ];
+        let result = generate(params, 1);
+        assert_eq!(
+            "ERR invalid expire time in 'setex' command",
+            result.err().unwrap()
+        );
+    }
 
+    #[test]
+    fn generate_command_setex_ok() {
         let params = vec![
-            "lrem".to_string(),
+            "setex".to_string(),
             "key".to_string(),
-            "a".to_string(),
-            "element".to_string(),
+            "10".to_string(),
+            "value".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Set { value, options, .. }
+                if value == "value"
+                    && options.expiration == SetExpiration::Relative(Duration::from_secs(10))
+        ));
     }
 
     #[test]
-    fn generate_command_lrem_ok() {
+    fn generate_command_psetex_invalid_expire_time_err() {
         let params = vec![
-            "lrem".to_string(),
+            "psetex".to_string(),
             "key".to_string(),
             "0".to_string(),
-            "element".to_string(),
+            "value".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        let _element = "element".to_string();
+        let result = generate(params, 1);
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lrem {
-                key: _key,
-                count: 0,
-                element: _element,
-            } => true,
-            _ => false,
-        });
+        assert_eq!(
+            "ERR invalid expire time in 'psetex' command",
+            result.err().unwrap()
+        );
     }
 
     #[test]
-    fn generate_command_lset_err() {
+    fn generate_command_incrbyfloat_invalid_increment_err() {
         let params = vec![
-            "lset".to_string(),
+            "incrbyfloat".to_string(),
             "key".to_string(),
-            "-1".to_string(),
-            "element".to_string(),
-            "element".to_string(),
+            "abc".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert_eq!("ERR value is not a valid float", result.err().unwrap());
+    }
 
-        let params = vec!["lset".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    #[test]
+    fn generate_command_incrby_negative_increment_ok() {
+        let params = vec!["incrby".to_string(), "key".to_string(), "-10".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Incrby { increment: -10, .. }
+        ));
+    }
 
+    #[test]
+    fn generate_command_setbit_with_invalid_value_err() {
         let params = vec![
-            "lset".to_string(),
+            "setbit".to_string(),
             "key".to_string(),
-            "a".to_string(),
-            "element".to_string(),
+            "7".to_string(),
+            "2".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert_eq!(
+            "ERR bit is not an integer or out of range",
+            result.unwrap_err()
+        );
     }
 
     #[test]
-    fn generate_command_lset_ok() {
+    fn generate_command_bitcount_with_bit_range() {
         let params = vec![
-            "lset".to_string(),
+            "bitcount".to_string(),
             "key".to_string(),
-            "1".to_string(),
-            "Hola".to_string(),
+            "5".to_string(),
+            "-1".to_string(),
+            "bit".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        let _index = "1".to_string();
-        let _element = "Hola".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lset {
-                key: _key,
-                index: _index,
-                element: _element,
-            } => true,
-            _ => false,
-        });
+        let expected = BitRange {
+            start: 5,
+            end: -1,
+            unit: BitUnit::Bit,
+        };
+        assert!(matches!(
+            result.unwrap(),
+            Command::Bitcount { range: Some(range), .. } if range == expected
+        ));
     }
 
     #[test]
-    fn generate_command_rpop_without_param_err() {
-        let params = vec!["rpop".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_bitop_not_with_many_keys_err() {
+        let params = vec![
+            "bitop".to_string(),
+            "not".to_string(),
+            "dest".to_string(),
+            "a".to_string(),
+            "b".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert!(result.is_err());
+
+        let params = vec![
+            "bitop".to_string(),
+            "and".to_string(),
+            "dest".to_string(),
+            "a".to_string(),
+            "b".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Bitop {
+                operation: BitOperation::And,
+                ..
+            }
+        ));
     }
 
     #[test]
-    fn generate_command_rpop_without_param_count_not_u32_err() {
-        let params = vec!["rpop".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        assert!(result.is_err())
+    fn generate_command_xadd_with_maxlen_and_auto_id() {
+        let params = vec![
+            "xadd".to_string(),
+            "stream".to_string(),
+            "MAXLEN".to_string(),
+            "~".to_string(),
+            "10".to_string(),
+            "*".to_string(),
+            "field".to_string(),
+            "value".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Xadd {
+                id: None,
+                max_len: Some(10),
+                ..
+            }
+        ));
     }
 
     #[test]
-    fn generate_command_rpop_ok() {
-        let params = vec!["rpop".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_xadd_with_odd_fields_err() {
+        let params = vec![
+            "xadd".to_string(),
+            "stream".to_string(),
+            "1-1".to_string(),
+            "field".to_string(),
+            "value".to_string(),
+            "other".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Rpop {
-                key: _key,
-                count: 0,
-            } => true,
-            _ => false,
-        });
+        assert!(result.is_err());
+    }
 
-        let params = vec!["rpop".to_string(), "key".to_string(), "3".to_string()];
-        let result = generate(params, "client-test".to_string());
+    #[test]
+    fn generate_command_xrevrange_with_exclusive_bounds() {
+        let params = vec![
+            "xrevrange".to_string(),
+            "stream".to_string(),
+            "(5-0".to_string(),
+            "(2-3".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Rpop {
-                key: _key,
-                count: 3,
-            } => true,
-            _ => false,
-        });
+        let start = StreamId::new(2, 4);
+        let end = StreamId::new(4, u64::MAX);
+        assert!(matches!(
+            result.unwrap(),
+            Command::Xrevrange { start: s, end: e, count: None, .. } if s == start && e == end
+        ));
     }
 
     #[test]
-    fn generate_command_lpush_incorrect_params_err() {
-        let params = vec!["lpush".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_xread_with_unbalanced_streams_err() {
+        let params = vec![
+            "xread".to_string(),
+            "streams".to_string(),
+            "a".to_string(),
+            "b".to_string(),
+            "0".to_string(),
+        ];
+        let result = generate(params, 1);
 
         assert!(result.is_err());
 
-        let params = vec!["lpush".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec![
+            "xread".to_string(),
+            "count".to_string(),
+            "2".to_string(),
+            "streams".to_string(),
+            "a".to_string(),
+            "$".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        assert!(matches!(
+            result.unwrap(),
+            Command::Xread { count: Some(2), streams } if streams == vec![("a".to_string(), None)]
+        ));
     }
 
     #[test]
-    fn generate_command_lpush_ok() {
-        let params = vec!["lpush".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        let _value = vec!["value".to_string()];
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lpush {
-                key: _key,
-                value: _value,
-            } => true,
-            _ => false,
-        });
+    fn generate_command_xgroup_create_with_mkstream() {
+        let params = vec![
+            "xgroup".to_string(),
+            "create".to_string(),
+            "stream".to_string(),
+            "group".to_string(),
+            "$".to_string(),
+            "MKSTREAM".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Xgroup {
+                param: XgroupParam::Create {
+                    id: None,
+                    mkstream: true,
+                    ..
+                },
+                ..
+            }
+        ));
     }
 
     #[test]
-    fn generate_command_lpushx_incorrect_params_err() {
-        let params = vec!["lpushx".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_xreadgroup_with_options() {
+        let params = vec![
+            "xreadgroup".to_string(),
+            "GROUP".to_string(),
+            "group".to_string(),
+            "alice".to_string(),
+            "COUNT".to_string(),
+            "5".to_string(),
+            "NOACK".to_string(),
+            "STREAMS".to_string(),
+            "stream".to_string(),
+            ">".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Xreadgroup {
+                count: Some(5),
+                no_ack: true,
+                streams,
+                ..
+            } if streams == vec![("stream".to_string(), None)]
+        ));
+    }
 
-        let params = vec!["lpushx".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    #[test]
+    fn generate_command_xpending_extended_form() {
+        let params = vec![
+            "xpending".to_string(),
+            "stream".to_string(),
+            "group".to_string(),
+            "IDLE".to_string(),
+            "1000".to_string(),
+            "-".to_string(),
+            "+".to_string(),
+            "10".to_string(),
+            "alice".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
+        let expected = PendingRange {
+            min_idle: Some(Duration::from_secs(1)),
+            start: StreamId::MIN,
+            end: StreamId::MAX,
+            count: 10,
+            consumer: Some("alice".to_string()),
+        };
+        assert!(matches!(
+            result.unwrap(),
+            Command::Xpending { range: Some(range), .. } if range == expected
+        ));
     }
 
     #[test]
-    fn generate_command_rpush_incorrect_params_err() {
-        let params = vec!["rpush".to_string()];
-        let result = generate(params, "client-test".to_string());
-
+    fn generate_command_geoadd_with_invalid_coordinates_err() {
+        let params = vec![
+            "geoadd".to_string(),
+            "key".to_string(),
+            "NX".to_string(),
+            "XX".to_string(),
+            "13.36".to_string(),
+            "38.11".to_string(),
+            "Palermo".to_string(),
+        ];
+        let result = generate(params, 1);
         assert!(result.is_err());
 
-        let params = vec!["rpush".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec![
+            "geoadd".to_string(),
+            "key".to_string(),
+            "200".to_string(),
+            "38.11".to_string(),
+            "Palermo".to_string(),
+        ];
+        let result = generate(params, 1);
+        assert_eq!(
+            "ERR invalid longitude,latitude pair 200.000000,38.110000",
+            result.unwrap_err()
+        );
+    }
 
-        assert!(result.is_err())
+    #[test]
+    fn generate_command_geosearch_with_count_sorts_ascending() {
+        let params = vec![
+            "geosearch".to_string(),
+            "key".to_string(),
+            "FROMMEMBER".to_string(),
+            "Palermo".to_string(),
+            "BYBOX".to_string(),
+            "2".to_string(),
+            "1".to_string(),
+            "km".to_string(),
+            "COUNT".to_string(),
+            "3".to_string(),
+            "WITHCOORD".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        match result.unwrap() {
+            Command::Geosearch { options, .. } => {
+                assert_eq!(GeoOrigin::Member("Palermo".to_string()), options.origin);
+                assert_eq!(GeoShape::Box(2000.0, 1000.0), options.shape);
+                assert_eq!(GeoUnit::Kilometers, options.unit);
+                assert_eq!(Some(true), options.ascending);
+                assert_eq!(Some(3), options.count);
+                assert!(options.with_coord);
+            }
+            _ => panic!("expected GEOSEARCH"),
+        }
     }
 
     #[test]
-    fn generate_command_rpush_ok() {
-        let params = vec!["rpush".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_geosearch_without_shape_err() {
+        let params = vec![
+            "geosearch".to_string(),
+            "key".to_string(),
+            "FROMLONLAT".to_string(),
+            "15".to_string(),
+            "37".to_string(),
+            "ASC".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        let _value = vec!["value".to_string()];
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Rpush {
-                key: _key,
-                value: _value,
-            } => true,
-            _ => false,
-        });
+        assert_eq!(
+            "ERR exactly one of BYRADIUS and BYBOX can be specified for GEOSEARCH",
+            result.unwrap_err()
+        );
     }
 
     #[test]
-    fn generate_command_rpushx_incorrect_params_err() {
-        let params = vec!["rpushx".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_pubsub_numpat() {
+        let params = vec!["pubsub".to_string(), "NUMPAT".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Pubsub {
+                param: PubSubParam::Numpat
+            }
+        ));
 
-        assert!(result.is_err());
+        let params = vec![
+            "pubsub".to_string(),
+            "numpat".to_string(),
+            "extra".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        let params = vec!["rpushx".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        assert!(result.is_err());
+    }
 
-        assert!(result.is_err())
+    #[test]
+    fn generate_command_config_resetstat_and_info_commandstats() {
+        let params = vec!["config".to_string(), "resetstat".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::ConfigResetstat
+        ));
+
+        let params = vec!["info".to_string(), "commandstats".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Info {
+                param: InfoParam::Commandstats
+            }
+        ));
     }
 
     #[test]
-    fn generate_command_sadd_incorrect_params_err() {
-        let params = vec!["sadd".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_command() {
+        let params = vec!["command".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Command {
+                param: CommandParam::All
+            }
+        ));
 
-        assert!(result.is_err());
+        let params = vec!["command".to_string(), "COUNT".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Command {
+                param: CommandParam::Count
+            }
+        ));
 
-        let params = vec!["sadd".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["command".to_string(), "info".to_string(), "get".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Command {
+                param: CommandParam::Info(names)
+            } if names == vec!["get".to_string()]
+        ));
 
-        assert!(result.is_err())
+        let params = vec!["command".to_string(), "list".to_string()];
+        assert!(generate(params, 1).is_err());
     }
 
     #[test]
-    fn generate_command_lpushx_ok() {
-        let params = vec!["lpushx".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_validates_arity_with_command_table() {
+        let params = vec!["GET".to_string(), "a".to_string(), "b".to_string()];
+        assert_eq!(
+            "ERR wrong number of arguments for 'get' command",
+            generate(params, 1).unwrap_err()
+        );
 
-        let _key = "key".to_string();
-        let _value = vec!["value".to_string()];
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Lpushx {
-                key: _key,
-                value: _value,
-            } => true,
-            _ => false,
-        });
+        let params = vec!["mset".to_string(), "a".to_string()];
+        assert_eq!(
+            "ERR wrong number of arguments for 'mset' command",
+            generate(params, 1).unwrap_err()
+        );
     }
 
     #[test]
-    fn generate_command_sadd_ok() {
-        let params = vec!["sadd".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        let mut _values = HashSet::new();
-        _values.insert("value1".to_string());
-        _values.insert("value2".to_string());
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Sadd {
-                key: _key,
-                values: _values,
-            } => true,
-            _ => false,
-        });
+    fn generate_command_table_covers_every_command() {
+        for spec in COMMAND_TABLE {
+            let result = generate(vec![spec.name.to_string()], 1);
+            assert_ne!(Some("Command not valid".to_string()), result.err());
+        }
     }
 
     #[test]
-    fn generate_command_scard_without_param_err() {
-        let params = vec!["scard".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_cluster() {
+        let params = vec![
+            "cluster".to_string(),
+            "KEYSLOT".to_string(),
+            "foo".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
-    }
+        assert!(matches!(
+            result.unwrap(),
+            Command::Cluster {
+                param: ClusterParam::KeySlot(key)
+            } if key == "foo"
+        ));
 
-    #[test]
-    fn generate_command_scard_ok() {
-        let params = vec!["scard".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["cluster".to_string(), "slots".to_string(), "x".to_string()];
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Scard { key: _key } => true,
-            _ => false,
-        });
+        assert!(result.is_err());
     }
 
     #[test]
-    fn generate_command_sismember_without_param_err() {
-        let params = vec!["sismember".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_memory_usage() {
+        let params = vec!["memory".to_string(), "USAGE".to_string(), "key".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
-    }
+        assert!(matches!(
+            result.unwrap(),
+            Command::Memory {
+                param: MemoryParam::Usage { key, samples: 5 }
+            } if key == "key"
+        ));
 
-    #[test]
-    fn generate_command_sismember_ok() {
         let params = vec![
-            "sismember".to_string(),
+            "memory".to_string(),
+            "usage".to_string(),
             "key".to_string(),
-            "value".to_string(),
+            "SAMPLES".to_string(),
+            "0".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        let _key = "key".to_string();
-        let _value = "value".to_string();
+        assert!(matches!(
+            result.unwrap(),
+            Command::Memory {
+                param: MemoryParam::Usage { samples: 0, .. }
+            }
+        ));
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Sismember {
-                key: _key,
-                value: _value,
-            } => true,
-            _ => false,
-        });
+        let params = vec![
+            "memory".to_string(),
+            "usage".to_string(),
+            "key".to_string(),
+            "samples".to_string(),
+            "-1".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert_eq!("ERR syntax error", result.unwrap_err());
     }
 
     #[test]
-    fn generate_command_srem_incorrect_params_err() {
-        let params = vec!["srem".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_slowlog() {
+        let params = vec!["slowlog".to_string(), "GET".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Slowlog {
+                param: SlowlogParam::Get { count: 10 }
+            }
+        ));
 
-        assert!(result.is_err());
+        let params = vec!["slowlog".to_string(), "get".to_string(), "-1".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Slowlog {
+                param: SlowlogParam::Get { count: usize::MAX }
+            }
+        ));
 
-        let params = vec!["srem".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["slowlog".to_string(), "len".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Slowlog {
+                param: SlowlogParam::Len
+            }
+        ));
 
-        assert!(result.is_err())
+        let params = vec!["slowlog".to_string(), "reset".to_string(), "x".to_string()];
+        assert!(generate(params, 1).is_err());
     }
 
     #[test]
-    fn generate_command_srem_ok() {
-        let params = vec!["srem".to_string(), "key".to_string(), "value".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _key = "key".to_string();
-        let mut _values = HashSet::new();
-        _values.insert("value1".to_string());
-        _values.insert("value2".to_string());
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Srem {
-                key: _key,
-                values: _values,
-            } => true,
-            _ => false,
-        });
-    }
+    fn generate_command_eval() {
+        let params = vec![
+            "eval".to_string(),
+            "return KEYS[1]".to_string(),
+            "1".to_string(),
+            "key".to_string(),
+            "arg".to_string(),
+        ];
+        match generate(params, 1).unwrap() {
+            Command::Eval { script, keys, args } => {
+                assert_eq!("return KEYS[1]", script);
+                assert_eq!(vec!["key".to_string()], keys);
+                assert_eq!(vec!["arg".to_string()], args);
+            }
+            _ => panic!("El comando no es EVAL"),
+        }
 
-    #[test]
-    fn generate_command_smembers_without_param_err() {
-        let params = vec!["smembers".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["evalsha".to_string(), "ABC".to_string(), "0".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Evalsha { sha1, keys, .. } if sha1 == "abc" && keys.is_empty()
+        ));
 
-        assert!(result.is_err())
+        let params = vec![
+            "eval".to_string(),
+            "return 1".to_string(),
+            "2".to_string(),
+            "k".to_string(),
+        ];
+        assert!(generate(params, 1).is_err());
+        let params = vec!["eval".to_string(), "return 1".to_string(), "-1".to_string()];
+        assert!(generate(params, 1).is_err());
     }
 
     #[test]
-    fn generate_command_smembers_ok() {
-        let params = vec!["smembers".to_string(), "key".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_script() {
+        let params = vec!["script".to_string(), "EXISTS".to_string(), "A".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Script { param: ScriptParam::Exists { sha1s } } if sha1s == vec!["a".to_string()]
+        ));
 
-        let _key = "key".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Smembers { key: _key } => true,
-            _ => false,
-        });
+        let params = vec![
+            "script".to_string(),
+            "flush".to_string(),
+            "async".to_string(),
+        ];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Script {
+                param: ScriptParam::Flush
+            }
+        ));
+
+        let params = vec!["script".to_string(), "kill".to_string()];
+        assert!(generate(params, 1).is_err());
     }
 
     #[test]
-    fn generate_command_keys_ok() {
-        let params = vec!["keys".to_string(), "/*".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_debug() {
+        let params = vec!["debug".to_string(), "SLEEP".to_string(), "0.5".to_string()];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Debug { param: DebugParam::Sleep { duration } } if duration == Duration::from_millis(500)
+        ));
 
-        let _pattern = "/*".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Keys { pattern: _pattern } => true,
-            _ => false,
-        });
-    }
+        let params = vec![
+            "debug".to_string(),
+            "set-active-expire".to_string(),
+            "0".to_string(),
+        ];
+        assert!(matches!(
+            generate(params, 1).unwrap(),
+            Command::Debug {
+                param: DebugParam::SetActiveExpire { enabled: false }
+            }
+        ));
 
-    #[test]
-    fn generate_command_store_without_param_err() {
-        let params = vec!["store".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["debug".to_string(), "sleep".to_string(), "x".to_string()];
+        assert!(generate(params, 1).is_err());
 
-        assert!(result.is_err())
+        let params = vec!["debug".to_string(), "jmap".to_string()];
+        assert!(generate(params, 1).is_err());
     }
 
     #[test]
-    fn generate_command_store_ok() {
-        let params = vec!["store".to_string(), "/store.file".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_client() {
+        let params = vec!["client".to_string(), "LIST".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Client {
+                param: ClientParam::List,
+                ..
+            }
+        ));
 
-        let _path = "/store.file".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Store { path: _path } => true,
-            _ => false,
-        });
-    }
+        let params = vec![
+            "client".to_string(),
+            "setname".to_string(),
+            "worker".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        match result.unwrap() {
+            Command::Client {
+                param: ClientParam::SetName(name),
+                client_id,
+            } => {
+                assert_eq!("worker", name);
+                assert_eq!(1, client_id);
+            }
+            _ => panic!("expected CLIENT SETNAME"),
+        }
 
-    #[test]
-    fn generate_command_config_set_without_param_err() {
-        let params = vec!["config".to_string(), "set".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec!["client".to_string(), "id".to_string()];
+        let result = generate(params, 1);
 
-        assert!(result.is_err())
-    }
+        assert!(matches!(
+            result.unwrap(),
+            Command::Client {
+                param: ClientParam::Id,
+                client_id: 1
+            }
+        ));
 
-    #[test]
-    fn generate_command_config_set_ok() {
         let params = vec![
-            "config".to_string(),
-            "set".to_string(),
-            "verbose".to_string(),
-            "1".to_string(),
+            "client".to_string(),
+            "info".to_string(),
+            "extra".to_string(),
         ];
-        let result = generate(params, "client-test".to_string());
+        let result = generate(params, 1);
 
-        assert!(result.is_ok());
-        let _parameter = "verbose".to_string();
-        let _value = "1".to_string();
-        assert!(match result.unwrap() {
-            Command::ConfigSet {
-                parameter: _parameter,
-                value: _value,
-            } => true,
-            _ => false,
-        });
+        assert!(result.is_err());
     }
 
     #[test]
-    fn generate_command_config_get_ok() {
-        let params = vec!["config".to_string(), "get".to_string()];
-        let result = generate(params, "client-test".to_string());
+    fn generate_command_client_kill() {
+        let params = vec![
+            "client".to_string(),
+            "kill".to_string(),
+            "127.0.0.1:5000".to_string(),
+        ];
+        let result = generate(params, 1);
 
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::ConfigGet => true,
-            _ => false,
-        });
-    }
+        assert!(matches!(
+            result.unwrap(),
+            Command::Client {
+                param: ClientParam::KillAddr(_),
+                ..
+            }
+        ));
 
-    #[test]
-    fn generate_command_load_without_param_err() {
-        let params = vec!["load".to_string()];
-        let result = generate(params, "client-test".to_string());
+        let params = vec![
+            "client".to_string(),
+            "KILL".to_string(),
+            "ID".to_string(),
+            "3".to_string(),
+            "skipme".to_string(),
+            "no".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        match result.unwrap() {
+            Command::Client {
+                param: ClientParam::Kill(filter),
+                ..
+            } => assert_eq!(
+                KillFilter {
+                    id: Some(3),
+                    addr: None,
+                    skipme: false
+                },
+                filter
+            ),
+            _ => panic!("expected CLIENT KILL"),
+        }
 
-        assert!(result.is_err())
+        let params = vec![
+            "client".to_string(),
+            "kill".to_string(),
+            "id".to_string(),
+            "0".to_string(),
+        ];
+        let result = generate(params, 1);
+
+        assert_eq!(
+            "ERR client-id should be greater than 0",
+            result.unwrap_err()
+        );
     }
 
     #[test]
-    fn generate_command_load_ok() {
-        let params = vec!["load".to_string(), "/store.file".to_string()];
-        let result = generate(params, "client-test".to_string());
-
-        let _path = "/store.file".to_string();
-        assert!(result.is_ok());
-        assert!(match result.unwrap() {
-            Command::Load { path: _path } => true,
-            _ => false,
-        });
+    fn generate_command_ping_and_echo_with_message() {
+        let params = vec!["ping".to_string(), "hello".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Ping { message: Some(message) } if message == "hello"
+        ));
+
+        let params = vec!["echo".to_string(), "hello".to_string()];
+        let result = generate(params, 1);
+
+        assert!(matches!(
+            result.unwrap(),
+            Command::Echo { message } if message == "hello"
+        ));
+
+        let params = vec!["echo".to_string()];
+        let result = generate(params, 1);
+
+        assert_eq!(
+            "ERR wrong number of arguments for 'echo' command",
+            result.unwrap_err()
+        );
     }
 }
@@ -0,0 +1,114 @@
+/// Indica si el texto coincide con el patrón glob, con la misma sintaxis que Redis:
+///
+/// * `*` coincide con cualquier secuencia de caracteres, incluso vacía.
+/// * `?` coincide con un único caracter.
+/// * `[abc]` coincide con uno de los caracteres indicados, `[a-z]` con un rango y `[^a]` con
+///   cualquier caracter salvo los indicados.
+/// * `\` escapa el caracter siguiente.
+///
+/// Si `nocase` es verdadero, no se distinguen mayúsculas de minúsculas.
+pub fn glob_match(pattern: &str, text: &str, nocase: bool) -> bool {
+    let normalize = |c: char| if nocase { c.to_ascii_lowercase() } else { c };
+    let pattern: Vec<char> = pattern.chars().map(normalize).collect();
+    let text: Vec<char> = text.chars().map(normalize).collect();
+    match_from(&pattern, &text)
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    let (first, rest) = match pattern.split_first() {
+        Some(split) => split,
+        None => return text.is_empty(),
+    };
+
+    match first {
+        '*' => (0..=text.len()).any(|skip| match_from(rest, &text[skip..])),
+        '?' => !text.is_empty() && match_from(rest, &text[1..]),
+        '[' => match (text.first(), class_end(rest)) {
+            (Some(c), Some(end)) => {
+                class_matches(&rest[..end], *c) && match_from(&rest[end + 1..], &text[1..])
+            }
+            // Un `[` sin cerrar se toma como un caracter literal.
+            (Some(c), None) => *c == '[' && match_from(rest, &text[1..]),
+            (None, _) => false,
+        },
+        '\\' if !rest.is_empty() => {
+            text.first() == Some(&rest[0]) && match_from(&rest[1..], &text[1..])
+        }
+        literal => text.first() == Some(literal) && match_from(rest, &text[1..]),
+    }
+}
+
+/// Devuelve la posición del `]` que cierra la clase de caracteres que comienza en `class`.
+fn class_end(class: &[char]) -> Option<usize> {
+    let mut i = 0;
+    while i < class.len() {
+        match class[i] {
+            '\\' => i += 2,
+            ']' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Indica si el caracter pertenece a la clase de caracteres, sin los corchetes.
+fn class_matches(class: &[char], c: char) -> bool {
+    let (negated, class) = match class.first() {
+        Some('^') => (true, &class[1..]),
+        _ => (false, class),
+    };
+
+    let mut matched = false;
+    let mut i = 0;
+    while i < class.len() {
+        if class[i] == '\\' && i + 1 < class.len() {
+            matched |= class[i + 1] == c;
+            i += 2;
+        } else if i + 2 < class.len() && class[i + 1] == '-' {
+            let (start, end) = if class[i] <= class[i + 2] {
+                (class[i], class[i + 2])
+            } else {
+                (class[i + 2], class[i])
+            };
+            matched |= start <= c && c <= end;
+            i += 3;
+        } else {
+            matched |= class[i] == c;
+            i += 1;
+        }
+    }
+    matched != negated
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::service::glob::glob_match;
+
+    #[test]
+    fn test_wildcards() {
+        assert!(glob_match("*", "", false));
+        assert!(glob_match("maxmemory*", "maxmemory-policy", false));
+        assert!(glob_match("h?llo", "hello", false));
+        assert!(!glob_match("h?llo", "hllo", false));
+        assert!(glob_match("*log*", "logfile", false));
+        assert!(!glob_match("port", "ports", false));
+    }
+
+    #[test]
+    fn test_classes_and_escapes() {
+        assert!(glob_match("h[ae]llo", "hallo", false));
+        assert!(!glob_match("h[ae]llo", "hillo", false));
+        assert!(glob_match("h[^e]llo", "hallo", false));
+        assert!(!glob_match("h[^e]llo", "hello", false));
+        assert!(glob_match("h[a-c]llo", "hbllo", false));
+        assert!(glob_match("h\\*llo", "h*llo", false));
+        assert!(!glob_match("h\\*llo", "hello", false));
+        assert!(glob_match("[", "[", false));
+    }
+
+    #[test]
+    fn test_nocase() {
+        assert!(glob_match("DBFILE*", "dbfilename", true));
+        assert!(!glob_match("DBFILE*", "dbfilename", false));
+    }
+}
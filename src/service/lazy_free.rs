@@ -0,0 +1,85 @@
+use crate::entities::redis_element::RedisElement;
+use std::sync::mpsc;
+use std::sync::mpsc::Sender;
+use std::thread;
+
+/// Cantidad de elementos a partir de la cual un valor se libera en segundo plano. Liberar valores
+/// más chicos en el momento es más barato que enviarlos a otro hilo.
+const LAZYFREE_THRESHOLD: usize = 64;
+
+#[derive(Debug)]
+/// LazyFree: Hilo encargado de liberar la memoria de los valores eliminados, para que borrar
+/// colecciones muy grandes no demore al hilo de la base de datos.
+pub struct LazyFree {
+    sender: Sender<Box<dyn Send>>,
+}
+
+impl LazyFree {
+    /// Crea el hilo de liberación. El hilo termina cuando se destruye el `LazyFree`.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<Box<dyn Send>>();
+        thread::spawn(move || {
+            for value in receiver {
+                drop(value);
+            }
+        });
+        LazyFree { sender }
+    }
+
+    /// Libera el valor en segundo plano.
+    pub fn free<T: Send + 'static>(&self, value: T) {
+        // Si el hilo terminó, el valor se libera al descartar el error.
+        let _ = self.sender.send(Box::new(value));
+    }
+
+    /// Libera el valor en segundo plano si tiene suficientes elementos, o en el momento si no.
+    pub fn free_value(&self, value: RedisElement) {
+        if free_effort(&value) > LAZYFREE_THRESHOLD {
+            self.free(value);
+        }
+    }
+}
+
+/// Devuelve una estimación del trabajo necesario para liberar el valor: su cantidad de elementos.
+fn free_effort(value: &RedisElement) -> usize {
+    match value {
+        RedisElement::List(list) => list.len(),
+        RedisElement::Set(set) => set.len(),
+        RedisElement::SortedSet(sorted_set) => sorted_set.len(),
+        RedisElement::Stream(stream) => stream.len(),
+        RedisElement::Array(array) => array.len(),
+        _ => 1,
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::service::lazy_free::{free_effort, LazyFree, LAZYFREE_THRESHOLD};
+    use std::sync::Arc;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    #[test]
+    fn test_free_drops_value_in_background() {
+        let lazy_free = LazyFree::new();
+        let value = Arc::new(());
+        lazy_free.free(Arc::clone(&value));
+
+        for _ in 0..100 {
+            if Arc::strong_count(&value) == 1 {
+                return;
+            }
+            sleep(Duration::from_millis(10));
+        }
+        panic!("the value was not dropped");
+    }
+
+    #[test]
+    fn test_free_effort() {
+        let list = RedisElement::List(vec!["x".to_string(); LAZYFREE_THRESHOLD + 1]);
+
+        assert_eq!(LAZYFREE_THRESHOLD + 1, free_effort(&list));
+        assert_eq!(1, free_effort(&RedisElement::String("x".repeat(100))));
+    }
+}
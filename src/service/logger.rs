@@ -1,7 +1,9 @@
 use crate::config::server_config::Config;
 use crate::entities::log::Log;
+use crate::entities::log_target::LogTarget;
+use crate::service::syslog::Syslog;
 use std::fmt::Debug;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io::{Error, Write};
 use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Mutex};
@@ -40,29 +42,49 @@ impl Logger {
     /// Servicio de loggeo
     pub fn log(mut self) {
         let _: JoinHandle<Result<(), Error>> = thread::spawn(move || {
-            let mut file = OpenOptions::new()
-                .write(true)
-                .create(true)
-                .append(true)
-                .open(self.config.lock().unwrap().get_logfile())?;
+            // El archivo y la conexión con syslog se abren recién al necesitarlos, ya que con
+            // `logtarget syslog` puede no haber un directorio con permisos de escritura.
+            let mut file: Option<File> = None;
+            let mut syslog: Option<Syslog> = None;
 
             while let Ok(log) = self.receiver.recv() {
-                if self.file != self.config.lock().unwrap().get_logfile() {
-                    file = OpenOptions::new()
-                        .write(true)
-                        .create(true)
-                        .append(true)
-                        .open(self.config.lock().unwrap().get_logfile())?;
-                    self.file = self.config.lock().unwrap().get_logfile();
-                }
-
                 if self.verbose == 1 {
                     println!("{:?}", log.clone().to_string());
                 }
 
+                // El nivel de log puede modificarse en ejecución con CONFIG SET.
+                self.loglevel = self.config.lock().unwrap().get_loglevel();
                 let level = log.clone().get_level();
-                if level <= self.loglevel {
-                    file.write(log.to_string().as_bytes());
+                if level > self.loglevel {
+                    continue;
+                }
+
+                let target = self.config.lock().unwrap().get_logtarget();
+                match target {
+                    LogTarget::File => {
+                        let logfile = self.config.lock().unwrap().get_logfile();
+                        if file.is_none() || self.file != logfile {
+                            file = Some(
+                                OpenOptions::new()
+                                    .create(true)
+                                    .append(true)
+                                    .open(&logfile)?,
+                            );
+                            self.file = logfile;
+                        }
+                        if let Some(file) = file.as_mut() {
+                            file.write_all(log.to_string().as_bytes());
+                        }
+                    }
+                    LogTarget::Syslog => {
+                        if syslog.is_none() {
+                            syslog = Syslog::connect().ok();
+                        }
+                        // Si el daemon se reinició, se reconecta con el próximo log.
+                        if let Some(Err(_)) = syslog.as_ref().map(|syslog| syslog.send(&log)) {
+                            syslog = None;
+                        }
+                    }
                 }
             }
             Ok(())
@@ -0,0 +1,1990 @@
+use crate::entities::redis_element::RedisElement;
+use crate::entities::sha1::sha1_hex;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Cantidad máxima de instrucciones que ejecuta un script. Como la base de datos queda tomada
+/// mientras corre, un bucle infinito bloquearía al resto de los clientes.
+const MAX_STEPS: u64 = 10_000_000;
+/// Profundidad máxima de anidamiento de bloques y expresiones de un script.
+const MAX_DEPTH: usize = 200;
+/// Prioridad de los operadores unarios, mayor a la de todos los binarios salvo `^`.
+const UNARY_PRIORITY: u8 = 12;
+/// Largo máximo de un string construido por un script, igual al de un bulk string
+/// (proto-max-bulk-len).
+const MAX_STRING_LEN: usize = 512 * 1024 * 1024;
+
+/// Palabras reservadas del lenguaje.
+const KEYWORDS: &[&str] = &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "if", "in", "local",
+    "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+/// Símbolos del lenguaje, con los de varios caracteres primero para reconocerlos completos.
+const SYMBOLS: &[&str] = &[
+    "...", "==", "~=", "<=", ">=", "..", "+", "-", "*", "/", "%", "^", "#", "<", ">", "=", "(",
+    ")", "{", "}", "[", "]", ";", ":", ",", ".",
+];
+
+/// Función de la que dispone el script para ejecutar un comando: recibe el comando y sus
+/// argumentos y devuelve la respuesta o el mensaje de error.
+pub type RedisCall<'a> = dyn FnMut(Vec<String>) -> Result<RedisElement, String> + 'a;
+
+#[derive(Debug, PartialEq)]
+/// ScriptError: Error al compilar o ejecutar un script.
+pub enum ScriptError {
+    /// El script no es válido. Indica la línea en la que se encontró el error.
+    Compile { line: usize, message: String },
+    /// Error al ejecutar el script, en la línea indicada.
+    Runtime { line: usize, message: String },
+    /// Error devuelto por un comando ejecutado con `redis.call`, en la línea indicada.
+    Command { line: usize, message: String },
+    /// Error devuelto por el script con `redis.error_reply` o `{err = ...}`, que se envía al
+    /// cliente tal cual.
+    Reply(String),
+}
+
+#[derive(Debug)]
+/// Script: Script compilado, listo para ejecutarse.
+///
+/// Se soporta un subconjunto de Lua: variables locales, tablas, `if`, `while`, `repeat`, `for`
+/// numérico y con `pairs`/`ipairs`, los operadores del lenguaje y las bibliotecas `redis`,
+/// `string`, `table` y `math` más usadas. No se pueden definir funciones.
+pub struct Script {
+    block: Block,
+}
+
+impl Script {
+    /// Compila el código fuente de un script.
+    pub fn compile(source: &str) -> Result<Script, ScriptError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            depth: 0,
+        };
+        let block = parser.block()?;
+        match parser.peek() {
+            Token::Eof => Ok(Script { block }),
+            token => Err(parser.error(format!("'<eof>' expected near {}", token))),
+        }
+    }
+
+    /// Ejecuta el script con las claves y argumentos indicados, disponibles en las tablas `KEYS`
+    /// y `ARGV`. Los comandos de `redis.call` se ejecutan con `call`.
+    ///
+    /// Retorna el valor devuelto por el script convertido en un `RedisElement`.
+    pub fn run(
+        &self,
+        keys: &[String],
+        argv: &[String],
+        call: &mut RedisCall,
+    ) -> Result<RedisElement, ScriptError> {
+        let mut interpreter = Interpreter::new(keys, argv, call);
+        let values = match interpreter.exec_block(&self.block)? {
+            Flow::Return(values) => values,
+            _ => vec![],
+        };
+        to_redis(values.into_iter().next().unwrap_or(Value::Nil))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Token: Elemento léxico del código fuente.
+enum Token {
+    Name(String),
+    Keyword(&'static str),
+    Number(f64),
+    Str(String),
+    Symbol(&'static str),
+    Eof,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "'{}'", name),
+            Token::Keyword(keyword) => write!(f, "'{}'", keyword),
+            Token::Number(number) => write!(f, "'{}'", format_number(*number)),
+            Token::Str(string) => write!(f, "'{}'", string),
+            Token::Symbol(symbol) => write!(f, "'{}'", symbol),
+            Token::Eof => write!(f, "'<eof>'"),
+        }
+    }
+}
+
+/// Separa el código fuente en tokens, junto con la línea en la que se encuentra cada uno.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ScriptError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    let error = |line, message: &str| ScriptError::Compile {
+        line,
+        message: message.to_string(),
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            // Comentario de bloque `--[[ ]]` o de línea.
+            i += 2;
+            if let Some(level) = long_bracket_level(&chars, i) {
+                let (_, end, lines) = read_long_bracket(&chars, i, level)
+                    .ok_or_else(|| error(line, "unfinished long comment"))?;
+                line += lines;
+                i = end;
+            } else {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            match KEYWORDS.iter().find(|keyword| **keyword == word) {
+                Some(keyword) => tokens.push((Token::Keyword(keyword), line)),
+                None => tokens.push((Token::Name(word), line)),
+            }
+        } else if c.is_ascii_digit()
+            || (c == '.' && matches!(chars.get(i + 1), Some(d) if d.is_ascii_digit()))
+        {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric()
+                    || chars[i] == '.'
+                    || ((chars[i] == '+' || chars[i] == '-')
+                        && matches!(chars[i - 1], 'e' | 'E')
+                        && !chars[start..i].contains(&'x')))
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = parse_number(&text)
+                .ok_or_else(|| error(line, &format!("malformed number near '{}'", text)))?;
+            tokens.push((Token::Number(number), line));
+        } else if c == '"' || c == '\'' {
+            let (string, end) = read_string(&chars, i).map_err(|message| error(line, &message))?;
+            tokens.push((Token::Str(string), line));
+            i = end;
+        } else if c == '[' && long_bracket_level(&chars, i).is_some() {
+            let level = long_bracket_level(&chars, i).unwrap_or(0);
+            let (string, end, lines) = read_long_bracket(&chars, i, level)
+                .ok_or_else(|| error(line, "unfinished long string"))?;
+            tokens.push((Token::Str(string), line));
+            line += lines;
+            i = end;
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| {
+                    symbol
+                        .chars()
+                        .enumerate()
+                        .all(|(offset, s)| chars.get(i + offset) == Some(&s))
+                })
+                .ok_or_else(|| error(line, &format!("unexpected symbol near '{}'", c)))?;
+            tokens.push((Token::Symbol(symbol), line));
+            i += symbol.len();
+        }
+    }
+    tokens.push((Token::Eof, line));
+    Ok(tokens)
+}
+
+/// Si en la posición comienza un corchete largo (`[[` o `[==[`), devuelve su nivel: la cantidad
+/// de `=` entre los corchetes.
+fn long_bracket_level(chars: &[char], start: usize) -> Option<usize> {
+    if chars.get(start) != Some(&'[') {
+        return None;
+    }
+    let mut level = 0;
+    while chars.get(start + 1 + level) == Some(&'=') {
+        level += 1;
+    }
+    match chars.get(start + 1 + level) {
+        Some('[') => Some(level),
+        _ => None,
+    }
+}
+
+/// Lee un string o comentario entre corchetes largos. Devuelve su contenido, la posición
+/// siguiente al cierre y la cantidad de saltos de línea que contiene.
+fn read_long_bracket(chars: &[char], start: usize, level: usize) -> Option<(String, usize, usize)> {
+    let mut i = start + level + 2;
+    // Como en Lua, se descarta el salto de línea que sigue a la apertura.
+    let mut lines = 0;
+    if chars.get(i) == Some(&'\n') {
+        i += 1;
+        lines += 1;
+    }
+    let content_start = i;
+    while i < chars.len() {
+        if chars[i] == ']'
+            && (0..level).all(|offset| chars.get(i + 1 + offset) == Some(&'='))
+            && chars.get(i + 1 + level) == Some(&']')
+        {
+            let content: String = chars[content_start..i].iter().collect();
+            lines += content.matches('\n').count();
+            return Some((content, i + level + 2, lines));
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Lee un string entre comillas, interpretando las secuencias de escape. Devuelve su contenido y
+/// la posición siguiente a la comilla de cierre.
+fn read_string(chars: &[char], start: usize) -> Result<(String, usize), String> {
+    let quote = chars[start];
+    let mut string = String::new();
+    let mut i = start + 1;
+    loop {
+        match chars.get(i) {
+            None | Some('\n') => return Err("unfinished string".to_string()),
+            Some(c) if *c == quote => return Ok((string, i + 1)),
+            Some('\\') => {
+                i += 1;
+                match chars.get(i) {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('r') => string.push('\r'),
+                    Some('a') => string.push('\u{7}'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('v') => string.push('\u{b}'),
+                    Some('0'..='9') => {
+                        let digits: String = chars[i..]
+                            .iter()
+                            .take(3)
+                            .take_while(|c| c.is_ascii_digit())
+                            .collect();
+                        let code = digits.parse::<u32>().unwrap_or(0);
+                        if code > 255 {
+                            return Err("escape sequence too large".to_string());
+                        }
+                        string.push(char::from(code as u8));
+                        i += digits.len() - 1;
+                    }
+                    Some(c @ ('\\' | '"' | '\'' | '\n')) => string.push(*c),
+                    _ => return Err("invalid escape sequence".to_string()),
+                }
+                i += 1;
+            }
+            Some(c) => {
+                string.push(*c);
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Convierte un texto en número como Lua: en decimal, con exponente opcional, o en hexadecimal.
+fn parse_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text),
+    };
+    let number = if let Some(hex) = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()? as f64
+    } else if unsigned
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+    {
+        unsigned.parse::<f64>().ok()?
+    } else {
+        return None;
+    };
+    Some(if negative { -number } else { number })
+}
+
+/// Convierte un número en texto como Lua: los enteros sin decimales.
+fn format_number(number: f64) -> String {
+    if number.is_nan() {
+        "nan".to_string()
+    } else if number.is_infinite() {
+        if number > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else if number.fract() == 0.0 && number.abs() < 1e15 {
+        format!("{}", number as i64)
+    } else {
+        format!("{}", number)
+    }
+}
+
+type Block = Vec<Stmt>;
+
+#[derive(Debug)]
+/// Stmt: Instrucción de un script, junto con la línea en la que se encuentra.
+struct Stmt {
+    kind: StmtKind,
+    line: usize,
+}
+
+#[derive(Debug)]
+enum StmtKind {
+    Local(Vec<String>, Vec<Expr>),
+    Assign(Vec<Expr>, Vec<Expr>),
+    Call(Expr),
+    If(Vec<(Expr, Block)>, Option<Block>),
+    While(Expr, Block),
+    Repeat(Block, Expr),
+    NumericFor(String, Expr, Expr, Option<Expr>, Block),
+    GenericFor(Vec<String>, Expr, Block),
+    Do(Block),
+    Return(Vec<Expr>),
+    Break,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Nil,
+    Boolean(bool),
+    Number(f64),
+    Str(String),
+    Name(String),
+    Paren(Box<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+    Method(Box<Expr>, String, Vec<Expr>),
+    Table(Vec<(Option<Expr>, Expr)>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Unary(&'static str, Box<Expr>),
+}
+
+/// Devuelve la prioridad a izquierda y a derecha de un operador binario, o `None` si el símbolo
+/// no es un operador binario. Los operadores con mayor prioridad a izquierda que a derecha son
+/// asociativos a derecha.
+fn binary_priority(token: &Token) -> Option<(&'static str, u8, u8)> {
+    let operator = match token {
+        Token::Keyword(keyword) => *keyword,
+        Token::Symbol(symbol) => *symbol,
+        _ => return None,
+    };
+    let (left, right) = match operator {
+        "or" => (1, 1),
+        "and" => (2, 2),
+        "<" | ">" | "<=" | ">=" | "~=" | "==" => (3, 3),
+        ".." => (9, 8),
+        "+" | "-" => (10, 10),
+        "*" | "/" | "%" => (11, 11),
+        "^" => (14, 13),
+        _ => return None,
+    };
+    Some((operator, left, right))
+}
+
+/// Parser: Arma el árbol de instrucciones de un script a partir de sus tokens.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.position].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].0.clone();
+        if self.position < self.tokens.len() - 1 {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: String) -> ScriptError {
+        ScriptError::Compile {
+            line: self.line(),
+            message,
+        }
+    }
+
+    /// Consume el token si es el símbolo o palabra reservada indicado.
+    fn accept(&mut self, expected: &str) -> bool {
+        let matches = match self.peek() {
+            Token::Symbol(symbol) => *symbol == expected,
+            Token::Keyword(keyword) => *keyword == expected,
+            _ => false,
+        };
+        if matches {
+            self.next();
+        }
+        matches
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), ScriptError> {
+        if self.accept(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("'{}' expected near {}", expected, self.peek())))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ScriptError> {
+        match self.peek().clone() {
+            Token::Name(name) => {
+                self.next();
+                Ok(name)
+            }
+            token => Err(self.error(format!("<name> expected near {}", token))),
+        }
+    }
+
+    /// Controla la profundidad de anidamiento, para no agotar el stack al parsear.
+    fn enter(&mut self) -> Result<(), ScriptError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error("chunk has too many syntax levels".to_string()));
+        }
+        Ok(())
+    }
+
+    fn block_ends(&self) -> bool {
+        matches!(
+            self.peek(),
+            Token::Eof | Token::Keyword("end" | "else" | "elseif" | "until")
+        )
+    }
+
+    fn block(&mut self) -> Result<Block, ScriptError> {
+        self.enter()?;
+        let mut block = Vec::new();
+        while !self.block_ends() {
+            if self.accept(";") {
+                continue;
+            }
+            let statement = self.statement()?;
+            let is_return = matches!(statement.kind, StmtKind::Return(_));
+            block.push(statement);
+            if is_return {
+                // Como en Lua, `return` tiene que ser la última instrucción del bloque.
+                self.accept(";");
+                if !self.block_ends() {
+                    return Err(self.error(format!("'end' expected near {}", self.peek())));
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(block)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ScriptError> {
+        let line = self.line();
+        let kind = match self.peek().clone() {
+            Token::Keyword("if") => self.if_statement()?,
+            Token::Keyword("while") => {
+                self.next();
+                let condition = self.expr(0)?;
+                self.expect("do")?;
+                let body = self.block()?;
+                self.expect("end")?;
+                StmtKind::While(condition, body)
+            }
+            Token::Keyword("repeat") => {
+                self.next();
+                let body = self.block()?;
+                self.expect("until")?;
+                StmtKind::Repeat(body, self.expr(0)?)
+            }
+            Token::Keyword("do") => {
+                self.next();
+                let body = self.block()?;
+                self.expect("end")?;
+                StmtKind::Do(body)
+            }
+            Token::Keyword("for") => self.for_statement()?,
+            Token::Keyword("local") => {
+                self.next();
+                if matches!(self.peek(), Token::Keyword("function")) {
+                    return Err(self.error("function definitions are not supported".to_string()));
+                }
+                let mut names = vec![self.name()?];
+                while self.accept(",") {
+                    names.push(self.name()?);
+                }
+                let values = if self.accept("=") {
+                    self.expr_list()?
+                } else {
+                    vec![]
+                };
+                StmtKind::Local(names, values)
+            }
+            Token::Keyword("function") => {
+                return Err(self.error("function definitions are not supported".to_string()))
+            }
+            Token::Keyword("return") => {
+                self.next();
+                if self.block_ends() || matches!(self.peek(), Token::Symbol(";")) {
+                    StmtKind::Return(vec![])
+                } else {
+                    StmtKind::Return(self.expr_list()?)
+                }
+            }
+            Token::Keyword("break") => {
+                self.next();
+                StmtKind::Break
+            }
+            _ => self.expr_statement()?,
+        };
+        Ok(Stmt { kind, line })
+    }
+
+    fn if_statement(&mut self) -> Result<StmtKind, ScriptError> {
+        let mut branches = Vec::new();
+        let mut otherwise = None;
+        self.next();
+        loop {
+            let condition = self.expr(0)?;
+            self.expect("then")?;
+            branches.push((condition, self.block()?));
+            if self.accept("elseif") {
+                continue;
+            }
+            if self.accept("else") {
+                otherwise = Some(self.block()?);
+            }
+            self.expect("end")?;
+            return Ok(StmtKind::If(branches, otherwise));
+        }
+    }
+
+    fn for_statement(&mut self) -> Result<StmtKind, ScriptError> {
+        self.next();
+        let first = self.name()?;
+        if self.accept("=") {
+            let start = self.expr(0)?;
+            self.expect(",")?;
+            let limit = self.expr(0)?;
+            let step = if self.accept(",") {
+                Some(self.expr(0)?)
+            } else {
+                None
+            };
+            self.expect("do")?;
+            let body = self.block()?;
+            self.expect("end")?;
+            return Ok(StmtKind::NumericFor(first, start, limit, step, body));
+        }
+        let mut names = vec![first];
+        while self.accept(",") {
+            names.push(self.name()?);
+        }
+        self.expect("in")?;
+        let iterator = self.expr(0)?;
+        self.expect("do")?;
+        let body = self.block()?;
+        self.expect("end")?;
+        Ok(StmtKind::GenericFor(names, iterator, body))
+    }
+
+    /// Instrucción que comienza con una expresión: una asignación o una llamada a una función.
+    fn expr_statement(&mut self) -> Result<StmtKind, ScriptError> {
+        let first = self.suffixed_expr()?;
+        if matches!(self.peek(), Token::Symbol("=" | ",")) {
+            let mut targets = vec![first];
+            while self.accept(",") {
+                targets.push(self.suffixed_expr()?);
+            }
+            if targets
+                .iter()
+                .any(|target| !matches!(target, Expr::Name(_) | Expr::Index(..)))
+            {
+                return Err(self.error("syntax error near '='".to_string()));
+            }
+            self.expect("=")?;
+            return Ok(StmtKind::Assign(targets, self.expr_list()?));
+        }
+        match first {
+            Expr::Call(..) | Expr::Method(..) => Ok(StmtKind::Call(first)),
+            _ => Err(self.error(format!("syntax error near {}", self.peek()))),
+        }
+    }
+
+    fn expr_list(&mut self) -> Result<Vec<Expr>, ScriptError> {
+        let mut exprs = vec![self.expr(0)?];
+        while self.accept(",") {
+            exprs.push(self.expr(0)?);
+        }
+        Ok(exprs)
+    }
+
+    /// Parsea una expresión cuyos operadores binarios tengan prioridad mayor a `limit`.
+    fn expr(&mut self, limit: u8) -> Result<Expr, ScriptError> {
+        self.enter()?;
+        let unary = match self.peek() {
+            Token::Keyword("not") => Some("not"),
+            Token::Symbol("-") => Some("-"),
+            Token::Symbol("#") => Some("#"),
+            _ => None,
+        };
+        let mut left = match unary {
+            Some(operator) => {
+                self.next();
+                Expr::Unary(operator, Box::new(self.expr(UNARY_PRIORITY)?))
+            }
+            None => self.simple_expr()?,
+        };
+        while let Some((operator, left_priority, right_priority)) = binary_priority(self.peek()) {
+            if left_priority <= limit {
+                break;
+            }
+            self.next();
+            let right = self.expr(right_priority)?;
+            left = Expr::Binary(operator, Box::new(left), Box::new(right));
+        }
+        self.depth -= 1;
+        Ok(left)
+    }
+
+    fn simple_expr(&mut self) -> Result<Expr, ScriptError> {
+        let expr = match self.peek().clone() {
+            Token::Number(number) => Expr::Number(number),
+            Token::Str(string) => Expr::Str(string),
+            Token::Keyword("nil") => Expr::Nil,
+            Token::Keyword("true") => Expr::Boolean(true),
+            Token::Keyword("false") => Expr::Boolean(false),
+            Token::Symbol("{") => return self.table(),
+            Token::Keyword("function") => {
+                return Err(self.error("function definitions are not supported".to_string()))
+            }
+            Token::Symbol("...") => {
+                return Err(self.error("cannot use '...' outside a vararg function".to_string()))
+            }
+            _ => return self.suffixed_expr(),
+        };
+        self.next();
+        Ok(expr)
+    }
+
+    fn primary_expr(&mut self) -> Result<Expr, ScriptError> {
+        match self.peek().clone() {
+            Token::Name(name) => {
+                self.next();
+                Ok(Expr::Name(name))
+            }
+            Token::Symbol("(") => {
+                self.next();
+                let expr = self.expr(0)?;
+                self.expect(")")?;
+                Ok(Expr::Paren(Box::new(expr)))
+            }
+            token => Err(self.error(format!("unexpected symbol near {}", token))),
+        }
+    }
+
+    fn suffixed_expr(&mut self) -> Result<Expr, ScriptError> {
+        let mut expr = self.primary_expr()?;
+        loop {
+            expr = match self.peek() {
+                Token::Symbol(".") => {
+                    self.next();
+                    Expr::Index(Box::new(expr), Box::new(Expr::Str(self.name()?)))
+                }
+                Token::Symbol("[") => {
+                    self.next();
+                    let key = self.expr(0)?;
+                    self.expect("]")?;
+                    Expr::Index(Box::new(expr), Box::new(key))
+                }
+                Token::Symbol(":") => {
+                    self.next();
+                    let method = self.name()?;
+                    Expr::Method(Box::new(expr), method, self.call_args()?)
+                }
+                Token::Symbol("(" | "{") | Token::Str(_) => {
+                    Expr::Call(Box::new(expr), self.call_args()?)
+                }
+                _ => return Ok(expr),
+            };
+        }
+    }
+
+    fn call_args(&mut self) -> Result<Vec<Expr>, ScriptError> {
+        match self.peek().clone() {
+            Token::Str(string) => {
+                self.next();
+                Ok(vec![Expr::Str(string)])
+            }
+            Token::Symbol("{") => Ok(vec![self.table()?]),
+            _ => {
+                self.expect("(")?;
+                if self.accept(")") {
+                    return Ok(vec![]);
+                }
+                let args = self.expr_list()?;
+                self.expect(")")?;
+                Ok(args)
+            }
+        }
+    }
+
+    fn table(&mut self) -> Result<Expr, ScriptError> {
+        self.expect("{")?;
+        let mut fields = Vec::new();
+        while !self.accept("}") {
+            if self.accept("[") {
+                let key = self.expr(0)?;
+                self.expect("]")?;
+                self.expect("=")?;
+                fields.push((Some(key), self.expr(0)?));
+            } else if matches!(self.peek(), Token::Name(_))
+                && matches!(self.tokens[self.position + 1].0, Token::Symbol("="))
+            {
+                let key = Expr::Str(self.name()?);
+                self.next();
+                fields.push((Some(key), self.expr(0)?));
+            } else {
+                fields.push((None, self.expr(0)?));
+            }
+            if !self.accept(",") && !self.accept(";") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(Expr::Table(fields))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Builtin: Funciones de las bibliotecas disponibles en los scripts.
+enum Builtin {
+    RedisCall,
+    RedisPcall,
+    RedisStatusReply,
+    RedisErrorReply,
+    RedisSha1hex,
+    Tonumber,
+    Tostring,
+    Type,
+    Unpack,
+    Pairs,
+    Ipairs,
+    Error,
+    Assert,
+    TableInsert,
+    TableRemove,
+    TableConcat,
+    StringLen,
+    StringSub,
+    StringUpper,
+    StringLower,
+    StringRep,
+    MathFloor,
+    MathCeil,
+    MathAbs,
+    MathMax,
+    MathMin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Key: Clave de una tabla. Los números con decimales no pueden usarse como clave.
+enum Key {
+    Boolean(bool),
+    Int(i64),
+    Str(String),
+}
+
+#[derive(Debug, Default)]
+struct Table {
+    entries: HashMap<Key, Value>,
+}
+
+impl Table {
+    fn get(&self, key: &Key) -> Value {
+        self.entries.get(key).cloned().unwrap_or(Value::Nil)
+    }
+
+    fn set(&mut self, key: Key, value: Value) {
+        if matches!(value, Value::Nil) {
+            self.entries.remove(&key);
+        } else {
+            self.entries.insert(key, value);
+        }
+    }
+
+    /// Largo de la secuencia que comienza en 1, como el operador `#`.
+    fn len(&self) -> i64 {
+        let mut len = 0;
+        while self.entries.contains_key(&Key::Int(len + 1)) {
+            len += 1;
+        }
+        len
+    }
+
+    /// Pares de la tabla, primero la secuencia y luego el resto de las claves ordenadas, para que
+    /// recorrerla con `pairs` sea determinístico.
+    fn pairs(&self) -> Vec<(Key, Value)> {
+        let mut pairs: Vec<(Key, Value)> = self
+            .entries
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        pairs
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Nil,
+    Boolean(bool),
+    Number(f64),
+    Str(String),
+    Table(Rc<RefCell<Table>>),
+    Builtin(Builtin),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+            Value::Boolean(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::Str(_) => "string",
+            Value::Table(_) => "table",
+            Value::Builtin(_) => "function",
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        !matches!(self, Value::Nil | Value::Boolean(false))
+    }
+
+    fn table(entries: Vec<(Key, Value)>) -> Value {
+        let mut table = Table::default();
+        for (key, value) in entries {
+            table.set(key, value);
+        }
+        Value::Table(Rc::new(RefCell::new(table)))
+    }
+
+    fn sequence(values: Vec<Value>) -> Value {
+        Value::table(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| (Key::Int(i as i64 + 1), value))
+                .collect(),
+        )
+    }
+
+    /// Número que representa el valor, convirtiendo los strings numéricos como Lua.
+    fn to_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            Value::Str(string) => parse_number(string),
+            _ => None,
+        }
+    }
+
+    /// String que representa el valor, si es un string o un número.
+    fn to_str(&self) -> Option<String> {
+        match self {
+            Value::Str(string) => Some(string.clone()),
+            Value::Number(number) => Some(format_number(*number)),
+            _ => None,
+        }
+    }
+
+    fn to_key(&self) -> Result<Key, String> {
+        match self {
+            Value::Nil => Err("table index is nil".to_string()),
+            Value::Boolean(boolean) => Ok(Key::Boolean(*boolean)),
+            Value::Number(number) if number.fract() == 0.0 => Ok(Key::Int(*number as i64)),
+            Value::Str(string) => Ok(Key::Str(string.clone())),
+            value => Err(format!(
+                "{} values can not be used as table keys",
+                value.type_name()
+            )),
+        }
+    }
+
+    fn field(&self, name: &str) -> Value {
+        match self {
+            Value::Table(table) => table.borrow().get(&Key::Str(name.to_string())),
+            _ => Value::Nil,
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Table(a), Value::Table(b)) => Rc::ptr_eq(a, b),
+            (Value::Builtin(a), Value::Builtin(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl From<Key> for Value {
+    fn from(key: Key) -> Value {
+        match key {
+            Key::Boolean(boolean) => Value::Boolean(boolean),
+            Key::Int(int) => Value::Number(int as f64),
+            Key::Str(string) => Value::Str(string),
+        }
+    }
+}
+
+/// Convierte la respuesta de un comando en un valor del script, como Redis: los enteros en
+/// números, los strings en strings, `nil` en `false`, las respuestas de estado en una tabla con
+/// el campo `ok` y las colecciones en tablas.
+fn to_lua(element: RedisElement) -> Value {
+    let strings =
+        |members: Vec<String>| Value::sequence(members.into_iter().map(Value::Str).collect());
+    match element {
+        RedisElement::String(string) => Value::Str(string),
+        RedisElement::Bytes(bytes) => Value::Str(String::from_utf8_lossy(&bytes).into_owned()),
+        RedisElement::SimpleString(string) => {
+            Value::table(vec![(Key::Str("ok".to_string()), Value::Str(string))])
+        }
+        RedisElement::Integer(integer) => Value::Number(integer as f64),
+        RedisElement::Nil => Value::Boolean(false),
+        RedisElement::List(list) => strings(list),
+        RedisElement::Set(set) => {
+            let mut members: Vec<String> = set.into_iter().collect();
+            members.sort();
+            strings(members)
+        }
+        RedisElement::SortedSet(sorted_set) => strings(
+            sorted_set
+                .iter()
+                .map(|(member, _)| member.to_string())
+                .collect(),
+        ),
+        RedisElement::Stream(stream) => {
+            strings(stream.iter().map(|(id, _)| id.to_string()).collect())
+        }
+        RedisElement::Array(array) => Value::sequence(array.into_iter().map(to_lua).collect()),
+    }
+}
+
+/// Convierte el valor devuelto por el script en la respuesta al cliente, como Redis: los números
+/// se truncan a enteros, `true` es 1, `false` y `nil` son nil y las tablas con el campo `ok` o
+/// `err` son respuestas de estado o de error. El resto de las tablas se convierte en un array con
+/// sus elementos hasta el primer `nil`.
+fn to_redis(value: Value) -> Result<RedisElement, ScriptError> {
+    match value {
+        Value::Number(number) => Ok(RedisElement::Integer(number as i64)),
+        Value::Str(string) => Ok(RedisElement::String(string)),
+        Value::Boolean(true) => Ok(RedisElement::Integer(1)),
+        Value::Table(table) => {
+            let table = table.borrow();
+            if let Some(error) = table.get(&Key::Str("err".to_string())).to_str() {
+                return Err(ScriptError::Reply(error));
+            }
+            if let Some(status) = table.get(&Key::Str("ok".to_string())).to_str() {
+                return Ok(RedisElement::SimpleString(status));
+            }
+            let elements = (1..=table.len())
+                .map(|i| to_redis(table.get(&Key::Int(i))))
+                .collect::<Result<Vec<RedisElement>, ScriptError>>()?;
+            Ok(RedisElement::Array(elements))
+        }
+        _ => Ok(RedisElement::Nil),
+    }
+}
+
+/// Resultado de ejecutar un bloque: si terminó normalmente o con `break` o `return`.
+enum Flow {
+    Normal,
+    Break,
+    Return(Vec<Value>),
+}
+
+/// Interpreter: Ejecuta las instrucciones de un script.
+struct Interpreter<'a, 'b> {
+    globals: HashMap<String, Value>,
+    scopes: Vec<HashMap<String, Value>>,
+    call: &'a mut RedisCall<'b>,
+    steps: u64,
+    line: usize,
+}
+
+impl<'a, 'b> Interpreter<'a, 'b> {
+    fn new(keys: &[String], argv: &[String], call: &'a mut RedisCall<'b>) -> Self {
+        let library = |functions: &[(&str, Builtin)]| {
+            Value::table(
+                functions
+                    .iter()
+                    .map(|(name, builtin)| (Key::Str(name.to_string()), Value::Builtin(*builtin)))
+                    .collect(),
+            )
+        };
+        let strings =
+            |strings: &[String]| Value::sequence(strings.iter().cloned().map(Value::Str).collect());
+
+        let mut globals = HashMap::new();
+        globals.insert("KEYS".to_string(), strings(keys));
+        globals.insert("ARGV".to_string(), strings(argv));
+        globals.insert(
+            "redis".to_string(),
+            library(&[
+                ("call", Builtin::RedisCall),
+                ("pcall", Builtin::RedisPcall),
+                ("status_reply", Builtin::RedisStatusReply),
+                ("error_reply", Builtin::RedisErrorReply),
+                ("sha1hex", Builtin::RedisSha1hex),
+            ]),
+        );
+        globals.insert(
+            "table".to_string(),
+            library(&[
+                ("insert", Builtin::TableInsert),
+                ("remove", Builtin::TableRemove),
+                ("concat", Builtin::TableConcat),
+            ]),
+        );
+        globals.insert(
+            "string".to_string(),
+            library(&[
+                ("len", Builtin::StringLen),
+                ("sub", Builtin::StringSub),
+                ("upper", Builtin::StringUpper),
+                ("lower", Builtin::StringLower),
+                ("rep", Builtin::StringRep),
+            ]),
+        );
+        globals.insert(
+            "math".to_string(),
+            library(&[
+                ("floor", Builtin::MathFloor),
+                ("ceil", Builtin::MathCeil),
+                ("abs", Builtin::MathAbs),
+                ("max", Builtin::MathMax),
+                ("min", Builtin::MathMin),
+            ]),
+        );
+        for (name, builtin) in [
+            ("tonumber", Builtin::Tonumber),
+            ("tostring", Builtin::Tostring),
+            ("type", Builtin::Type),
+            ("unpack", Builtin::Unpack),
+            ("pairs", Builtin::Pairs),
+            ("ipairs", Builtin::Ipairs),
+            ("error", Builtin::Error),
+            ("assert", Builtin::Assert),
+        ] {
+            globals.insert(name.to_string(), Value::Builtin(builtin));
+        }
+
+        Interpreter {
+            globals,
+            scopes: Vec::new(),
+            call,
+            steps: 0,
+            line: 0,
+        }
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, ScriptError> {
+        Err(ScriptError::Runtime {
+            line: self.line,
+            message,
+        })
+    }
+
+    /// Cuenta una instrucción ejecutada, cortando el script si supera `MAX_STEPS`.
+    fn step(&mut self) -> Result<(), ScriptError> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return self.error("script exceeded the maximum number of instructions".to_string());
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str) -> Result<Value, ScriptError> {
+        for scope in self.scopes.iter().rev() {
+            if let Some(value) = scope.get(name) {
+                return Ok(value.clone());
+            }
+        }
+        match self.globals.get(name) {
+            Some(value) => Ok(value.clone()),
+            None => self.error(format!(
+                "Script attempted to access nonexistent global variable '{}'",
+                name
+            )),
+        }
+    }
+
+    /// Asigna una variable local o global existente. Como en Redis, los scripts no pueden crear
+    /// variables globales.
+    fn assign(&mut self, name: &str, value: Value) -> Result<(), ScriptError> {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(variable) = scope.get_mut(name) {
+                *variable = value;
+                return Ok(());
+            }
+        }
+        match self.globals.get_mut(name) {
+            Some(variable) => {
+                *variable = value;
+                Ok(())
+            }
+            None => self.error(format!(
+                "Script attempted to create global variable '{}'",
+                name
+            )),
+        }
+    }
+
+    fn declare(&mut self, name: &str, value: Value) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+
+    fn exec_block(&mut self, block: &[Stmt]) -> Result<Flow, ScriptError> {
+        self.scopes.push(HashMap::new());
+        let mut flow = Ok(Flow::Normal);
+        for statement in block {
+            flow = self.exec(statement);
+            if !matches!(flow, Ok(Flow::Normal)) {
+                break;
+            }
+        }
+        self.scopes.pop();
+        flow
+    }
+
+    /// Ejecuta el cuerpo de un bucle con las variables indicadas declaradas.
+    fn exec_loop_body(
+        &mut self,
+        variables: Vec<(&String, Value)>,
+        body: &[Stmt],
+    ) -> Result<Flow, ScriptError> {
+        self.scopes.push(
+            variables
+                .into_iter()
+                .map(|(name, value)| (name.clone(), value))
+                .collect(),
+        );
+        let flow = self.exec_block(body);
+        self.scopes.pop();
+        flow
+    }
+
+    fn exec(&mut self, statement: &Stmt) -> Result<Flow, ScriptError> {
+        self.line = statement.line;
+        self.step()?;
+        match &statement.kind {
+            StmtKind::Local(names, exprs) => {
+                let mut values = self.eval_list(exprs)?.into_iter();
+                for name in names {
+                    let value = values.next().unwrap_or(Value::Nil);
+                    self.declare(name, value);
+                }
+            }
+            StmtKind::Assign(targets, exprs) => {
+                let mut values = self.eval_list(exprs)?.into_iter();
+                for target in targets {
+                    let value = values.next().unwrap_or(Value::Nil);
+                    match target {
+                        Expr::Name(name) => self.assign(name, value)?,
+                        Expr::Index(table, key) => {
+                            let table = self.eval(table)?;
+                            let key = self.eval(key)?;
+                            self.set_index(table, key, value)?;
+                        }
+                        _ => return self.error("cannot assign to this expression".to_string()),
+                    }
+                }
+            }
+            StmtKind::Call(expr) => {
+                self.eval_multi(expr)?;
+            }
+            StmtKind::If(branches, otherwise) => {
+                for (condition, body) in branches {
+                    if self.eval(condition)?.is_truthy() {
+                        return self.exec_block(body);
+                    }
+                }
+                if let Some(body) = otherwise {
+                    return self.exec_block(body);
+                }
+            }
+            StmtKind::While(condition, body) => {
+                while self.eval(condition)?.is_truthy() {
+                    match self.exec_block(body)? {
+                        Flow::Break => break,
+                        Flow::Return(values) => return Ok(Flow::Return(values)),
+                        Flow::Normal => self.step()?,
+                    }
+                }
+            }
+            StmtKind::Repeat(body, condition) => loop {
+                // Como en Lua, la condición puede usar las variables locales del cuerpo.
+                self.scopes.push(HashMap::new());
+                let flow = self.exec_block_in_scope(body).and_then(|flow| match flow {
+                    Flow::Normal if self.eval(condition)?.is_truthy() => Ok(Flow::Break),
+                    flow => Ok(flow),
+                });
+                self.scopes.pop();
+                match flow? {
+                    Flow::Break => break,
+                    Flow::Return(values) => return Ok(Flow::Return(values)),
+                    Flow::Normal => self.step()?,
+                }
+            },
+            StmtKind::NumericFor(name, start, limit, step, body) => {
+                let start = self.eval_number(start, "'for' initial value")?;
+                let limit = self.eval_number(limit, "'for' limit")?;
+                let step = match step {
+                    Some(step) => self.eval_number(step, "'for' step")?,
+                    None => 1.0,
+                };
+                if step == 0.0 {
+                    return self.error("'for' step is zero".to_string());
+                }
+                let mut i = start;
+                while (step > 0.0 && i <= limit) || (step < 0.0 && i >= limit) {
+                    match self.exec_loop_body(vec![(name, Value::Number(i))], body)? {
+                        Flow::Break => break,
+                        Flow::Return(values) => return Ok(Flow::Return(values)),
+                        Flow::Normal => self.step()?,
+                    }
+                    i += step;
+                }
+            }
+            StmtKind::GenericFor(names, iterator, body) => {
+                return self.exec_generic_for(names, iterator, body)
+            }
+            StmtKind::Do(body) => return self.exec_block(body),
+            StmtKind::Return(exprs) => return Ok(Flow::Return(self.eval_list(exprs)?)),
+            StmtKind::Break => return Ok(Flow::Break),
+        }
+        Ok(Flow::Normal)
+    }
+
+    /// Ejecuta las instrucciones de un bloque en el scope actual, sin abrir uno nuevo.
+    fn exec_block_in_scope(&mut self, block: &[Stmt]) -> Result<Flow, ScriptError> {
+        for statement in block {
+            match self.exec(statement)? {
+                Flow::Normal => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    /// Ejecuta un `for` genérico. Sólo se soportan los iteradores `pairs` e `ipairs`.
+    fn exec_generic_for(
+        &mut self,
+        names: &[String],
+        iterator: &Expr,
+        body: &[Stmt],
+    ) -> Result<Flow, ScriptError> {
+        let (function, args) = match iterator {
+            Expr::Call(function, args) => (self.eval(function)?, args),
+            _ => return self.error("'for' iterator must be pairs or ipairs".to_string()),
+        };
+        let table = match self.eval_list(args)?.into_iter().next() {
+            Some(Value::Table(table)) => table,
+            Some(value) => {
+                return self.error(format!(
+                    "bad argument #1 to 'for iterator' (table expected, got {})",
+                    value.type_name()
+                ))
+            }
+            None => {
+                return self.error(
+                    "bad argument #1 to 'for iterator' (table expected, got no value)".to_string(),
+                )
+            }
+        };
+        let pairs: Box<dyn Iterator<Item = (Key, Value)>> = match function {
+            Value::Builtin(Builtin::Pairs) => Box::new(table.borrow().pairs().into_iter()),
+            Value::Builtin(Builtin::Ipairs) => {
+                // ipairs recorre la tabla hasta el primer nil, aunque se modifique en el cuerpo.
+                let table = Rc::clone(&table);
+                Box::new(
+                    (1..)
+                        .map(move |i| (Key::Int(i), table.borrow().get(&Key::Int(i))))
+                        .take_while(|(_, value)| !matches!(value, Value::Nil)),
+                )
+            }
+            _ => return self.error("'for' iterator must be pairs or ipairs".to_string()),
+        };
+        for (key, value) in pairs {
+            let mut values = vec![Value::from(key), value].into_iter();
+            let variables = names
+                .iter()
+                .map(|name| (name, values.next().unwrap_or(Value::Nil)))
+                .collect();
+            match self.exec_loop_body(variables, body)? {
+                Flow::Break => break,
+                Flow::Return(values) => return Ok(Flow::Return(values)),
+                Flow::Normal => self.step()?,
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn eval_number(&mut self, expr: &Expr, what: &str) -> Result<f64, ScriptError> {
+        match self.eval(expr)?.to_number() {
+            Some(number) => Ok(number),
+            None => self.error(format!("{} must be a number", what)),
+        }
+    }
+
+    /// Evalúa una lista de expresiones. Si la última es una llamada, se agregan todos los valores
+    /// que devuelve.
+    fn eval_list(&mut self, exprs: &[Expr]) -> Result<Vec<Value>, ScriptError> {
+        let mut values = Vec::with_capacity(exprs.len());
+        for (i, expr) in exprs.iter().enumerate() {
+            if i == exprs.len() - 1 {
+                values.extend(self.eval_multi(expr)?);
+            } else {
+                values.push(self.eval(expr)?);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Evalúa una expresión que puede devolver varios valores, como una llamada a una función.
+    fn eval_multi(&mut self, expr: &Expr) -> Result<Vec<Value>, ScriptError> {
+        match expr {
+            Expr::Call(function, args) => {
+                let function = self.eval(function)?;
+                let args = self.eval_list(args)?;
+                self.call_function(function, args)
+            }
+            Expr::Method(object, method, args) => {
+                let object = self.eval(object)?;
+                let function = match &object {
+                    Value::Str(_) => self.lookup("string")?.field(method),
+                    Value::Table(_) => self.index(object.clone(), Value::Str(method.clone()))?,
+                    value => {
+                        return self
+                            .error(format!("attempt to index a {} value", value.type_name()))
+                    }
+                };
+                let mut values = vec![object];
+                values.extend(self.eval_list(args)?);
+                self.call_function(function, values)
+            }
+            expr => Ok(vec![self.eval(expr)?]),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, ScriptError> {
+        match expr {
+            Expr::Nil => Ok(Value::Nil),
+            Expr::Boolean(boolean) => Ok(Value::Boolean(*boolean)),
+            Expr::Number(number) => Ok(Value::Number(*number)),
+            Expr::Str(string) => Ok(Value::Str(string.clone())),
+            Expr::Name(name) => self.lookup(name),
+            Expr::Paren(expr) => self.eval(expr),
+            Expr::Index(table, key) => {
+                let table = self.eval(table)?;
+                let key = self.eval(key)?;
+                self.index(table, key)
+            }
+            Expr::Call(..) | Expr::Method(..) => Ok(self
+                .eval_multi(expr)?
+                .into_iter()
+                .next()
+                .unwrap_or(Value::Nil)),
+            Expr::Table(fields) => {
+                let table = Value::table(vec![]);
+                let mut position = 1;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    match key {
+                        Some(key) => {
+                            let key = self.eval(key)?;
+                            let value = self.eval(value)?;
+                            self.set_index(table.clone(), key, value)?;
+                        }
+                        // Como en las listas de argumentos, la última llamada agrega todos sus valores.
+                        None if i == fields.len() - 1 => {
+                            for value in self.eval_multi(value)? {
+                                self.set_index(
+                                    table.clone(),
+                                    Value::Number(position as f64),
+                                    value,
+                                )?;
+                                position += 1;
+                            }
+                        }
+                        None => {
+                            let value = self.eval(value)?;
+                            self.set_index(table.clone(), Value::Number(position as f64), value)?;
+                            position += 1;
+                        }
+                    }
+                }
+                Ok(table)
+            }
+            Expr::Binary("and", left, right) => {
+                let left = self.eval(left)?;
+                if left.is_truthy() {
+                    self.eval(right)
+                } else {
+                    Ok(left)
+                }
+            }
+            Expr::Binary("or", left, right) => {
+                let left = self.eval(left)?;
+                if left.is_truthy() {
+                    Ok(left)
+                } else {
+                    self.eval(right)
+                }
+            }
+            Expr::Binary(operator, left, right) => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                self.binary(operator, left, right)
+            }
+            Expr::Unary(operator, operand) => {
+                let operand = self.eval(operand)?;
+                match (*operator, operand) {
+                    ("not", operand) => Ok(Value::Boolean(!operand.is_truthy())),
+                    ("#", Value::Str(string)) => Ok(Value::Number(string.len() as f64)),
+                    ("#", Value::Table(table)) => Ok(Value::Number(table.borrow().len() as f64)),
+                    ("#", operand) => self.error(format!(
+                        "attempt to get length of a {} value",
+                        operand.type_name()
+                    )),
+                    (_, operand) => match operand.to_number() {
+                        Some(number) => Ok(Value::Number(-number)),
+                        None => self.error(format!(
+                            "attempt to perform arithmetic on a {} value",
+                            operand.type_name()
+                        )),
+                    },
+                }
+            }
+        }
+    }
+
+    fn index(&self, table: Value, key: Value) -> Result<Value, ScriptError> {
+        match table {
+            Value::Table(table) => match key.to_key() {
+                Ok(key) => Ok(table.borrow().get(&key)),
+                // Una clave que no puede estar en la tabla no tiene valor.
+                Err(_) => Ok(Value::Nil),
+            },
+            value => self.error(format!("attempt to index a {} value", value.type_name())),
+        }
+    }
+
+    fn set_index(&self, table: Value, key: Value, value: Value) -> Result<(), ScriptError> {
+        match table {
+            Value::Table(table) => match key.to_key() {
+                Ok(key) => {
+                    table.borrow_mut().set(key, value);
+                    Ok(())
+                }
+                Err(message) => self.error(message),
+            },
+            value => self.error(format!("attempt to index a {} value", value.type_name())),
+        }
+    }
+
+    fn binary(&self, operator: &str, left: Value, right: Value) -> Result<Value, ScriptError> {
+        match operator {
+            "==" => Ok(Value::Boolean(left == right)),
+            "~=" => Ok(Value::Boolean(left != right)),
+            "<" | ">" | "<=" | ">=" => {
+                let ordering = match (&left, &right) {
+                    (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+                    (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+                    _ if left.type_name() == right.type_name() => {
+                        return self.error(format!(
+                            "attempt to compare two {} values",
+                            left.type_name()
+                        ))
+                    }
+                    _ => {
+                        return self.error(format!(
+                            "attempt to compare {} with {}",
+                            left.type_name(),
+                            right.type_name()
+                        ))
+                    }
+                };
+                Ok(Value::Boolean(match (operator, ordering) {
+                    (_, None) => false,
+                    ("<", Some(ordering)) => ordering.is_lt(),
+                    (">", Some(ordering)) => ordering.is_gt(),
+                    ("<=", Some(ordering)) => ordering.is_le(),
+                    (_, Some(ordering)) => ordering.is_ge(),
+                }))
+            }
+            ".." => match (left.to_str(), right.to_str()) {
+                (Some(a), Some(b)) => Ok(Value::Str(a + &b)),
+                (None, _) => self.error(format!(
+                    "attempt to concatenate a {} value",
+                    left.type_name()
+                )),
+                (_, None) => self.error(format!(
+                    "attempt to concatenate a {} value",
+                    right.type_name()
+                )),
+            },
+            _ => {
+                let (a, b) = match (left.to_number(), right.to_number()) {
+                    (Some(a), Some(b)) => (a, b),
+                    (None, _) => {
+                        return self.error(format!(
+                            "attempt to perform arithmetic on a {} value",
+                            left.type_name()
+                        ))
+                    }
+                    (_, None) => {
+                        return self.error(format!(
+                            "attempt to perform arithmetic on a {} value",
+                            right.type_name()
+                        ))
+                    }
+                };
+                Ok(Value::Number(match operator {
+                    "+" => a + b,
+                    "-" => a - b,
+                    "*" => a * b,
+                    "/" => a / b,
+                    "%" => a - (a / b).floor() * b,
+                    _ => a.powf(b),
+                }))
+            }
+        }
+    }
+
+    fn call_function(
+        &mut self,
+        function: Value,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>, ScriptError> {
+        let builtin = match function {
+            Value::Builtin(builtin) => builtin,
+            value => return self.error(format!("attempt to call a {} value", value.type_name())),
+        };
+        let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Nil);
+        let one = |value: Value| Ok(vec![value]);
+        match builtin {
+            Builtin::RedisCall | Builtin::RedisPcall => {
+                if args.is_empty() {
+                    return self.error(
+                        "Please specify at least one argument for this redis lib call".to_string(),
+                    );
+                }
+                let command = match args
+                    .iter()
+                    .map(Value::to_str)
+                    .collect::<Option<Vec<String>>>()
+                {
+                    Some(command) => command,
+                    None => {
+                        return self.error(
+                            "Lua redis lib command arguments must be strings or integers"
+                                .to_string(),
+                        )
+                    }
+                };
+                match ((self.call)(command), builtin) {
+                    (Ok(element), _) => one(to_lua(element)),
+                    (Err(message), Builtin::RedisPcall) => one(Value::table(vec![(
+                        Key::Str("err".to_string()),
+                        Value::Str(message),
+                    )])),
+                    (Err(message), _) => Err(ScriptError::Command {
+                        line: self.line,
+                        message,
+                    }),
+                }
+            }
+            Builtin::RedisStatusReply | Builtin::RedisErrorReply => {
+                let field = if builtin == Builtin::RedisStatusReply {
+                    "ok"
+                } else {
+                    "err"
+                };
+                match arg(0).to_str() {
+                    Some(text) => one(Value::table(vec![(
+                        Key::Str(field.to_string()),
+                        Value::Str(text),
+                    )])),
+                    None => self.error(format!(
+                        "bad argument #1 to '{}' (string expected)",
+                        if field == "ok" {
+                            "status_reply"
+                        } else {
+                            "error_reply"
+                        }
+                    )),
+                }
+            }
+            Builtin::RedisSha1hex => match arg(0).to_str() {
+                Some(text) => one(Value::Str(sha1_hex(text.as_bytes()))),
+                None => self.error("wrong number of arguments".to_string()),
+            },
+            Builtin::Tonumber => match (arg(0), arg(1).to_number()) {
+                (value, None) => one(value.to_number().map(Value::Number).unwrap_or(Value::Nil)),
+                (value, Some(base)) => one(value
+                    .to_str()
+                    .and_then(|text| i64::from_str_radix(text.trim(), base as u32).ok())
+                    .map(|number| Value::Number(number as f64))
+                    .unwrap_or(Value::Nil)),
+            },
+            Builtin::Tostring => one(Value::Str(match arg(0) {
+                Value::Nil => "nil".to_string(),
+                Value::Boolean(boolean) => boolean.to_string(),
+                Value::Table(table) => format!("table: {:p}", Rc::as_ptr(&table)),
+                Value::Builtin(builtin) => format!("function: builtin: {:?}", builtin),
+                value => value.to_str().unwrap_or_default(),
+            })),
+            Builtin::Type => one(Value::Str(arg(0).type_name().to_string())),
+            Builtin::Unpack => match arg(0) {
+                Value::Table(table) => {
+                    let table = table.borrow();
+                    Ok((1..=table.len()).map(|i| table.get(&Key::Int(i))).collect())
+                }
+                value => self.error(format!(
+                    "bad argument #1 to 'unpack' (table expected, got {})",
+                    value.type_name()
+                )),
+            },
+            Builtin::Pairs | Builtin::Ipairs => {
+                self.error("pairs and ipairs can only be used in a 'for' loop".to_string())
+            }
+            Builtin::Error => match arg(0) {
+                Value::Table(table) => {
+                    match table.borrow().get(&Key::Str("err".to_string())).to_str() {
+                        Some(message) => Err(ScriptError::Reply(message)),
+                        None => self.error("unknown error".to_string()),
+                    }
+                }
+                value => self.error(value.to_str().unwrap_or_else(|| "nil".to_string())),
+            },
+            Builtin::Assert => {
+                if arg(0).is_truthy() {
+                    Ok(args)
+                } else {
+                    self.error(
+                        arg(1)
+                            .to_str()
+                            .unwrap_or_else(|| "assertion failed!".to_string()),
+                    )
+                }
+            }
+            Builtin::TableInsert | Builtin::TableRemove | Builtin::TableConcat => {
+                let table = match arg(0) {
+                    Value::Table(table) => table,
+                    value => {
+                        return self.error(format!(
+                            "bad argument #1 (table expected, got {})",
+                            value.type_name()
+                        ))
+                    }
+                };
+                let len = table.borrow().len();
+                match builtin {
+                    Builtin::TableInsert => {
+                        let (position, value) = match args.len() {
+                            2 => (len + 1, arg(1)),
+                            3 => match arg(1).to_number() {
+                                Some(position) if position >= 1.0 && position as i64 <= len + 1 => {
+                                    (position as i64, arg(2))
+                                }
+                                _ => {
+                                    return self.error(
+                                        "bad argument #2 to 'insert' (position out of bounds)"
+                                            .to_string(),
+                                    )
+                                }
+                            },
+                            _ => {
+                                return self
+                                    .error("wrong number of arguments to 'insert'".to_string())
+                            }
+                        };
+                        let mut table = table.borrow_mut();
+                        for i in (position..=len).rev() {
+                            let moved = table.get(&Key::Int(i));
+                            table.set(Key::Int(i + 1), moved);
+                        }
+                        table.set(Key::Int(position), value);
+                        Ok(vec![])
+                    }
+                    Builtin::TableRemove => {
+                        let position = arg(1).to_number().map(|p| p as i64).unwrap_or(len);
+                        if len == 0 {
+                            return one(Value::Nil);
+                        }
+                        let mut table = table.borrow_mut();
+                        let removed = table.get(&Key::Int(position));
+                        for i in position..len {
+                            let moved = table.get(&Key::Int(i + 1));
+                            table.set(Key::Int(i), moved);
+                        }
+                        table.set(Key::Int(len), Value::Nil);
+                        one(removed)
+                    }
+                    _ => {
+                        let separator = arg(1).to_str().unwrap_or_default();
+                        let first = arg(2).to_number().map(|i| i as i64).unwrap_or(1);
+                        let last = arg(3).to_number().map(|i| i as i64).unwrap_or(len);
+                        let table = table.borrow();
+                        let mut parts = Vec::new();
+                        for i in first..=last {
+                            match table.get(&Key::Int(i)).to_str() {
+                                Some(part) => parts.push(part),
+                                None => {
+                                    return self.error(format!(
+                                        "invalid value (at index {}) in table for 'concat'",
+                                        i
+                                    ))
+                                }
+                            }
+                        }
+                        one(Value::Str(parts.join(&separator)))
+                    }
+                }
+            }
+            Builtin::StringLen
+            | Builtin::StringSub
+            | Builtin::StringUpper
+            | Builtin::StringLower
+            | Builtin::StringRep => {
+                let text = match arg(0).to_str() {
+                    Some(text) => text,
+                    None => {
+                        return self.error(format!(
+                            "bad argument #1 (string expected, got {})",
+                            arg(0).type_name()
+                        ))
+                    }
+                };
+                match builtin {
+                    Builtin::StringLen => one(Value::Number(text.len() as f64)),
+                    Builtin::StringUpper => one(Value::Str(text.to_uppercase())),
+                    Builtin::StringLower => one(Value::Str(text.to_lowercase())),
+                    Builtin::StringRep => {
+                        let count = arg(1).to_number().unwrap_or(0.0).max(0.0) as usize;
+                        match text.len().checked_mul(count) {
+                            Some(len) if len <= MAX_STRING_LEN => {
+                                one(Value::Str(text.repeat(count)))
+                            }
+                            _ => self.error("resulting string too large".to_string()),
+                        }
+                    }
+                    _ => {
+                        // Como en Lua, las posiciones empiezan en 1 y las negativas cuentan desde el final.
+                        let len = text.len() as i64;
+                        let position = |value: Option<f64>, default: i64| match value
+                            .map(|v| v as i64)
+                            .unwrap_or(default)
+                        {
+                            p if p < 0 => (len + p + 1).max(0),
+                            p => p,
+                        };
+                        let start = position(arg(1).to_number(), 1).max(1);
+                        let end = position(arg(2).to_number(), -1).min(len);
+                        if start > end {
+                            return one(Value::Str(String::new()));
+                        }
+                        let bytes = &text.as_bytes()[start as usize - 1..end as usize];
+                        one(Value::Str(String::from_utf8_lossy(bytes).to_string()))
+                    }
+                }
+            }
+            Builtin::MathFloor
+            | Builtin::MathCeil
+            | Builtin::MathAbs
+            | Builtin::MathMax
+            | Builtin::MathMin => {
+                let numbers = match args
+                    .iter()
+                    .map(Value::to_number)
+                    .collect::<Option<Vec<f64>>>()
+                {
+                    Some(numbers) if !numbers.is_empty() => numbers,
+                    _ => return self.error("bad argument #1 (number expected)".to_string()),
+                };
+                one(Value::Number(match builtin {
+                    Builtin::MathFloor => numbers[0].floor(),
+                    Builtin::MathCeil => numbers[0].ceil(),
+                    Builtin::MathAbs => numbers[0].abs(),
+                    Builtin::MathMax => numbers.into_iter().fold(f64::MIN, f64::max),
+                    _ => numbers.into_iter().fold(f64::MAX, f64::min),
+                }))
+            }
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::service::lua::{Script, ScriptError};
+
+    #[allow(dead_code)]
+    fn run(source: &str, keys: &[&str], argv: &[&str]) -> Result<RedisElement, ScriptError> {
+        let keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+        let argv: Vec<String> = argv.iter().map(|arg| arg.to_string()).collect();
+        let mut call = |command: Vec<String>| Ok(RedisElement::String(command.join(" ")));
+        Script::compile(source)?.run(&keys, &argv, &mut call)
+    }
+
+    #[test]
+    fn test_return_values_are_converted_like_redis() {
+        assert_eq!(Ok(RedisElement::Integer(3)), run("return 3.9", &[], &[]));
+        assert_eq!(Ok(RedisElement::Integer(1)), run("return true", &[], &[]));
+        assert_eq!(Ok(RedisElement::Nil), run("return false", &[], &[]));
+        assert_eq!(Ok(RedisElement::Nil), run("local x = 1", &[], &[]));
+        assert_eq!(
+            Ok(RedisElement::SimpleString("OK".to_string())),
+            run("return redis.status_reply('OK')", &[], &[])
+        );
+        assert_eq!(
+            Err(ScriptError::Reply("ERR custom".to_string())),
+            run("return {err = 'ERR custom'}", &[], &[])
+        );
+        // El array termina en el primer nil.
+        assert_eq!(
+            Ok(RedisElement::Array(vec![
+                RedisElement::String("k".to_string()),
+                RedisElement::Integer(2),
+            ])),
+            run("return {KEYS[1], #ARGV, nil, 4}", &["k"], &["a", "b"])
+        );
+    }
+
+    #[test]
+    fn test_control_flow_and_operators() {
+        let source = "
+            -- suma los argumentos pares
+            local total, count = 0, 0
+            for i = 1, #ARGV do
+                local n = tonumber(ARGV[i])
+                if n % 2 == 0 then
+                    total = total + n
+                elseif n > 100 then
+                    break
+                end
+                count = count + 1
+            end
+            local words = {}
+            for _, word in ipairs({'a', 'b', 'c'}) do
+                table.insert(words, word:upper())
+            end
+            local i = 0
+            while true do
+                i = i + 1
+                if i >= 3 then break end
+            end
+            return {total, count, table.concat(words, '-') .. i, 2 ^ 3 - -1, not nil and 'x' or 'y'}
+        ";
+        assert_eq!(
+            Ok(RedisElement::Array(vec![
+                RedisElement::Integer(6),
+                RedisElement::Integer(4),
+                RedisElement::String("A-B-C3".to_string()),
+                RedisElement::Integer(9),
+                RedisElement::String("x".to_string()),
+            ])),
+            run(source, &[], &["1", "2", "3", "4", "501", "6"])
+        );
+    }
+
+    #[test]
+    fn test_redis_call_receives_the_arguments() {
+        assert_eq!(
+            Ok(RedisElement::String("set k 10".to_string())),
+            run("return redis.call('set', KEYS[1], 10)", &["k"], &[])
+        );
+        assert_eq!(
+            Ok(RedisElement::String("del a b".to_string())),
+            run("return redis.call('del', unpack(KEYS))", &["a", "b"], &[])
+        );
+    }
+
+    #[test]
+    fn test_errors_indicate_the_line() {
+        assert_eq!(
+            Err(ScriptError::Compile {
+                line: 2,
+                message: "'then' expected near 'return'".to_string()
+            }),
+            run("if true\nreturn 1 end", &[], &[]).map(|_| RedisElement::Nil)
+        );
+        assert_eq!(
+            Err(ScriptError::Runtime {
+                line: 2,
+                message: "attempt to perform arithmetic on a nil value".to_string()
+            }),
+            run("local x\nreturn x + 1", &[], &[])
+        );
+        assert!(matches!(
+            run("x = 1", &[], &[]),
+            Err(ScriptError::Runtime { .. })
+        ));
+        assert!(matches!(
+            run("while true do end", &[], &[]),
+            Err(ScriptError::Runtime { .. })
+        ));
+    }
+
+    #[test]
+    fn test_string_rep_rejects_results_over_the_bulk_limit() {
+        assert_eq!(
+            Ok(RedisElement::String("xxxxxx".to_string())),
+            run("return string.rep('xx', 3)", &[], &[])
+        );
+        let too_large = || ScriptError::Runtime {
+            line: 1,
+            message: "resulting string too large".to_string(),
+        };
+        assert_eq!(
+            Err(too_large()),
+            run("return string.rep('xx', 1e19)", &[], &[])
+        );
+        assert_eq!(
+            Err(too_large()),
+            run("return string.rep('xx', 1e15)", &[], &[])
+        );
+    }
+
+    #[test]
+    fn test_pcall_returns_the_error_as_a_table() {
+        let keys: Vec<String> = vec![];
+        let mut call = |_: Vec<String>| Err("WRONGTYPE Operation".to_string());
+        let script = Script::compile("local r = redis.pcall('get', 'k') return r.err").unwrap();
+        assert_eq!(
+            Ok(RedisElement::String("WRONGTYPE Operation".to_string())),
+            script.run(&keys, &keys, &mut call)
+        );
+        let script = Script::compile("\nreturn redis.call('get', 'k')").unwrap();
+        assert_eq!(
+            Err(ScriptError::Command {
+                line: 2,
+                message: "WRONGTYPE Operation".to_string()
+            }),
+            script.run(&keys, &keys, &mut call)
+        );
+    }
+}
@@ -1,6 +1,15 @@
+pub mod benchmark;
 pub mod command_generator;
 pub mod constants;
+pub mod glob;
+pub mod lazy_free;
 pub mod logger;
+pub mod lua;
+pub mod monitor_line;
+pub mod random;
+pub mod rdb_check;
 pub mod redis;
+pub mod redis_handle;
 pub mod server;
+pub mod syslog;
 pub mod timestamp_to_string;
@@ -0,0 +1,69 @@
+use std::time::{Duration, SystemTime};
+
+/// Formatea un comando como lo informa MONITOR en Redis: el timestamp de Unix con microsegundos,
+/// la base de datos y la dirección del cliente, y los argumentos entre comillas.
+///
+/// Ejemplo: `1339518083.107412 [0 127.0.0.1:60866] "set" "key" "value"`
+pub fn monitor_line(time: SystemTime, db: u32, addr: &str, args: &[String]) -> String {
+    let time = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_else(|_| Duration::from_secs(0));
+    let args: Vec<String> = args.iter().map(|arg| quote(arg)).collect();
+    format!(
+        "{}.{:06} [{} {}] {}",
+        time.as_secs(),
+        time.subsec_micros(),
+        db,
+        addr,
+        args.join(" ")
+    )
+}
+
+/// Devuelve el argumento entre comillas, escapando las comillas, las barras invertidas, los
+/// caracteres de control y los bytes no imprimibles (`\xHH`), de modo que la línea no contenga
+/// saltos de línea.
+fn quote(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for byte in arg.bytes() {
+        match byte {
+            b'\\' => quoted.push_str("\\\\"),
+            b'"' => quoted.push_str("\\\""),
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            b'\t' => quoted.push_str("\\t"),
+            0x07 => quoted.push_str("\\a"),
+            0x08 => quoted.push_str("\\b"),
+            b' '..=b'~' => quoted.push(byte as char),
+            _ => quoted.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::service::monitor_line::monitor_line;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_monitor_line_format() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_micros(1_339_518_083_107_412);
+        let args = vec!["set".to_string(), "key".to_string(), "a value".to_string()];
+        assert_eq!(
+            r#"1339518083.107412 [0 127.0.0.1:60866] "set" "key" "a value""#,
+            monitor_line(time, 0, "127.0.0.1:60866", &args)
+        );
+    }
+
+    #[test]
+    fn test_monitor_line_escapes_arguments() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+        let args = vec!["echo".to_string(), "\"a\\b\"\r\n\tñ".to_string()];
+        assert_eq!(
+            r#"1.000000 [0 internal] "echo" "\"a\\b\"\r\n\t\xc3\xb1""#,
+            monitor_line(time, 0, "internal", &args)
+        );
+    }
+}
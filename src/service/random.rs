@@ -0,0 +1,65 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::SystemTime;
+
+/// Genera un número pseudoaleatorio.
+/// Se utiliza la semilla aleatoria de `RandomState` (que cambia en cada instancia) combinada con el
+/// horario actual, por lo que no es apto para usos criptográficos.
+pub fn random_u64() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(nanos);
+    hasher.finish()
+}
+
+/// Genera un índice pseudoaleatorio en el rango `[0, bound)`. `bound` debe ser mayor a 0.
+pub fn random_index(bound: usize) -> usize {
+    (random_u64() % bound as u64) as usize
+}
+
+/// Genera un string pseudoaleatorio de `len` dígitos hexadecimales.
+pub fn random_hex(len: usize) -> String {
+    (0..len)
+        .map(|_| format!("{:x}", random_u64() % 16))
+        .collect()
+}
+
+/// Mezcla aleatoriamente los elementos del vector (algoritmo de Fisher-Yates).
+pub fn shuffle<T>(vector: &mut [T]) {
+    for i in (1..vector.len()).rev() {
+        vector.swap(i, random_index(i + 1));
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::service::random::{random_hex, random_index, shuffle};
+
+    #[test]
+    fn test_random_index_is_in_bounds() {
+        for _ in 0..100 {
+            assert!(random_index(3) < 3);
+        }
+    }
+
+    #[test]
+    fn test_random_hex_length() {
+        let hex = random_hex(40);
+
+        assert_eq!(40, hex.len());
+        assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_shuffle_keeps_elements() {
+        let mut vector = vec![1, 2, 3, 4, 5];
+        shuffle(&mut vector);
+        vector.sort_unstable();
+
+        assert_eq!(vec![1, 2, 3, 4, 5], vector);
+    }
+}
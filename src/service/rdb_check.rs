@@ -0,0 +1,159 @@
+use crate::entities::redis_element::RedisElement;
+use crate::entities::ttl_hash_map::TtlHashMap;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Default, PartialEq)]
+/// TypeStats: Estadísticas de las claves de un mismo tipo de dato.
+pub struct TypeStats {
+    /// Cantidad de claves del tipo.
+    pub keys: usize,
+    /// Cantidad total de elementos guardados en esas claves (bytes en el caso de los strings).
+    pub elements: usize,
+}
+
+#[derive(Debug, Default)]
+/// RdbReport: Resultado de validar un archivo RDB.
+pub struct RdbReport {
+    /// Tamaño del archivo en bytes.
+    pub size: usize,
+    /// Cantidad de claves cargadas de la base 0, sin contar las expiradas.
+    pub keys: usize,
+    /// Cantidad de claves con tiempo de expiración.
+    pub expires: usize,
+    /// Estadísticas por tipo de dato, indexadas por el nombre del tipo.
+    pub types: BTreeMap<&'static str, TypeStats>,
+}
+
+impl fmt::Display for RdbReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(fmt, "[info] {} bytes inspected", self.size)?;
+        writeln!(
+            fmt,
+            "[info] {} keys read ({} with expire)",
+            self.keys, self.expires
+        )?;
+        for (name, stats) in self.types.iter() {
+            let unit = if *name == "string" {
+                "bytes"
+            } else {
+                "elements"
+            };
+            writeln!(
+                fmt,
+                "[info] {}: {} keys, {} {}",
+                name, stats.keys, stats.elements, unit
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Devuelve el nombre del tipo de dato del valor y la cantidad de elementos que guarda.
+fn type_stats(value: &RedisElement) -> (&'static str, usize) {
+    match value {
+        RedisElement::String(string) => ("string", string.len()),
+        RedisElement::Integer(integer) => ("string", integer.to_string().len()),
+        RedisElement::Bytes(bytes) => ("string", bytes.len()),
+        RedisElement::List(list) => ("list", list.len()),
+        RedisElement::Set(set) => ("set", set.len()),
+        RedisElement::SortedSet(sorted_set) => ("zset", sorted_set.len()),
+        RedisElement::Stream(stream) => ("stream", stream.len()),
+        _ => ("none", 0),
+    }
+}
+
+/// Valida el archivo RDB usando el mismo deserializador con el cual el servidor lo carga, y
+/// devuelve las estadísticas de las claves que contiene.
+///
+/// Retorna error indicando la posición de la corrupción si el archivo no puede cargarse.
+pub fn check(bytes: &[u8]) -> Result<RdbReport, String> {
+    let mut map = TtlHashMap::deserialize(bytes).map_err(|e| e.to_string())?;
+    let mut report = RdbReport {
+        size: bytes.len(),
+        ..RdbReport::default()
+    };
+
+    let keys: Vec<String> = map.keys().cloned().collect();
+    for key in keys {
+        // Las claves persistentes tienen un ttl de 0.
+        if map
+            .get_ttl(&key)
+            .is_some_and(|ttl| ttl > Duration::from_secs(0))
+        {
+            report.expires += 1;
+        }
+        if let Some(value) = map.get(&key) {
+            let (name, elements) = type_stats(value);
+            let stats = report.types.entry(name).or_default();
+            stats.keys += 1;
+            stats.elements += elements;
+            report.keys += 1;
+        }
+    }
+    Ok(report)
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::ttl_hash_map::TtlHashMap;
+    use crate::service::rdb_check::{check, TypeStats};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_check_reports_key_statistics() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        map.insert("a".to_string(), RedisElement::String("value".to_string()));
+        map.insert("b".to_string(), RedisElement::String("xy".to_string()));
+        map.insert(
+            "list".to_string(),
+            RedisElement::List(vec!["1".to_string(), "2".to_string(), "3".to_string()]),
+        );
+        map.set_ttl_absolute("a".to_string(), SystemTime::now() + Duration::from_secs(60));
+        let bytes = map.serialize();
+
+        let report = check(&bytes).unwrap();
+
+        assert_eq!(bytes.len(), report.size);
+        assert_eq!(3, report.keys);
+        assert_eq!(1, report.expires);
+        assert_eq!(
+            Some(&TypeStats {
+                keys: 2,
+                elements: 7
+            }),
+            report.types.get("string")
+        );
+        assert_eq!(
+            Some(&TypeStats {
+                keys: 1,
+                elements: 3
+            }),
+            report.types.get("list")
+        );
+        assert!(report
+            .to_string()
+            .contains("[info] 3 keys read (1 with expire)"));
+    }
+
+    #[test]
+    fn test_check_reports_corruption_offset() {
+        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
+        map.insert("key".to_string(), RedisElement::String("value".to_string()));
+        let bytes = map.serialize();
+        let eof_offset = bytes.len() - 9;
+
+        assert_eq!(
+            format!("Unexpected end of file at offset {}", eof_offset + 1),
+            check(&bytes[..eof_offset + 5]).unwrap_err()
+        );
+
+        let mut corrupted = bytes.clone();
+        corrupted[eof_offset - 1] = b'x';
+        assert!(check(&corrupted)
+            .unwrap_err()
+            .ends_with(&format!("at offset {}", eof_offset + 1)));
+    }
+}
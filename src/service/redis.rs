@@ -1,49 +1,184 @@
 use crate::config::server_config::Config;
+use crate::entities::bitmap;
+use crate::entities::bitmap::{BitOperation, BitRange, BitUnit};
+use crate::entities::blocked_clients::{BlockedClients, ListSide, PopSide, Waiter};
+use crate::entities::client_param::ClientParam;
+use crate::entities::clock::{Clock, SystemClock};
+use crate::entities::cluster;
+use crate::entities::cluster_param::ClusterParam;
 use crate::entities::command::Command;
+use crate::entities::command_param::CommandParam;
+use crate::entities::command_stats::CommandStats;
+use crate::entities::command_table;
+use crate::entities::command_table::{CommandSpec, COMMAND_TABLE};
+use crate::entities::connected_clients::{ClientInfo, ConnectedClients};
+use crate::entities::consumer_group_param::{PendingRange, XgroupParam};
+use crate::entities::debug_param::DebugParam;
+use crate::entities::expire_condition::ExpireCondition;
+use crate::entities::geo;
+use crate::entities::geo::{GeoAddOptions, GeoOrigin, GeoSearchOptions, GeoUnit};
 use crate::entities::info_param::InfoParam;
+use crate::entities::key_change::KeyChange;
+use crate::entities::keyspace::{Keyspace, Shard};
+use crate::entities::keyspace_events::EventClass;
 use crate::entities::log::Log;
 use crate::entities::log_level::LogLevel;
+use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+use crate::entities::memory;
+use crate::entities::memory_param::MemoryParam;
+use crate::entities::message_queue::{
+    message_queue, MessageReceiver, MessageSender, OverflowPolicy,
+};
+use crate::entities::object_param::ObjectParam;
 use crate::entities::pubsub_param::PubSubParam;
+use crate::entities::rdb;
 use crate::entities::redis_element::{RedisElement as Re, RedisElement};
 use crate::entities::response::Response;
+use crate::entities::script_param::ScriptParam;
+use crate::entities::server_stats::ServerStats;
+use crate::entities::set_options::{SetCondition, SetExpiration, SetOptions};
+use crate::entities::sha1::sha1_hex;
+use crate::entities::shared_commands::{SharedCommand, SharedCommands};
+use crate::entities::slowlog::Slowlog;
+use crate::entities::slowlog_param::SlowlogParam;
+use crate::entities::sort_options::SortOptions;
+use crate::entities::sorted_set::{Aggregate, ScoreBound, ScoreSide, SortedSet};
+use crate::entities::stream::{Stream, StreamFields, StreamId};
 use crate::entities::ttl_hash_map::TtlHashMap;
+use crate::service::command_generator::generate;
+use crate::service::glob::glob_match;
+use crate::service::lazy_free::LazyFree;
+use crate::service::lua::{Script, ScriptError};
+use crate::service::monitor_line::monitor_line;
+use crate::service::random::{random_hex, random_index, shuffle};
 use crate::service::timestamp_to_string::timestamp_to_string;
 use regex::Regex;
+use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::io::Write;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
 use std::sync::mpsc;
-use std::sync::mpsc::{Receiver, Sender};
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::sync::mpsc::{Receiver, Sender, TrySendError};
+use std::sync::{Arc, Mutex, RwLockWriteGuard};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
 use std::{fs, process};
 
 /// Mensaje de error usado para el tipo de dato Erroneo
 const WRONGTYPE_MSG: &str = "WRONGTYPE Operation against a key holding the wrong kind of value";
 /// Mensaje de error usado cuando el valor no es entero o está fuera de rango permitido.
 const OUT_OF_RANGE_MSG: &str = "ERR value is not an integer or out of range";
-const VERSION_NUMBER: &str = "0001";
+/// Mensaje de error usado cuando un incremento o decremento produce overflow.
+const OVERFLOW_MSG: &str = "ERR increment or decrement would overflow";
+/// Mensaje de error usado cuando un incremento produce un score que no es un número.
+const NAN_SCORE_MSG: &str = "ERR resulting score is not a number (NaN)";
+/// Mensaje de error usado cuando una clave supera el largo máximo configurado.
+const KEY_TOO_LONG_MSG: &str = "ERR key exceeds maximum allowed length (max-key-length)";
+/// Mensaje de error usado cuando un valor supera el tamaño máximo configurado.
+const VALUE_TOO_BIG_MSG: &str = "ERR value exceeds maximum allowed size (max-value-size)";
+/// Mensaje de error usado cuando una colección supera la cantidad máxima de elementos configurada.
+const TOO_MANY_ELEMENTS_MSG: &str =
+    "ERR collection exceeds maximum allowed number of elements (max-collection-elements)";
+/// Mensaje de error usado cuando un comando que agrega datos supera el límite de memoria.
+const OOM_MSG: &str = "OOM command not allowed when used memory > 'maxmemory'.";
+/// Mensaje de error usado al consultar la frecuencia de acceso si no se registran los accesos.
+const ACCESS_NOT_TRACKED_MSG: &str =
+    "ERR key access is not tracked, enable it with CONFIG SET track-key-access yes";
+/// Cantidad de dígitos hexadecimales de un identificador de replicación.
+const REPLICATION_ID_LEN: usize = 40;
+/// Dirección con la que MONITOR informa los comandos que no fueron enviados por un cliente
+/// conectado (ej: los ejecutados desde la interfaz REST o al embeber la base de datos).
+const INTERNAL_CLIENT_ADDR: &str = "internal";
+/// Cantidad de claves con expiración que revisa cada iteración del ciclo de expiración activa.
+const ACTIVE_EXPIRE_SAMPLES: usize = 20;
+/// Tiempo mínimo entre dos ciclos de expiración activa.
+const ACTIVE_EXPIRE_PERIOD: Duration = Duration::from_millis(100);
+/// Tiempo máximo que puede durar un ciclo de expiración activa.
+const ACTIVE_EXPIRE_TIME_LIMIT: Duration = Duration::from_millis(25);
+/// Cantidad de claves entre las que se elige la próxima a descartar al superar `maxmemory`, como
+/// `maxmemory-samples` en Redis.
+const EVICTION_SAMPLES: usize = 5;
+/// Dígitos significativos con los que se escribe el resultado de INCRBYFLOAT. Redis suma con
+/// `long double` y escribe 17 dígitos; con `f64` sólo los primeros 15 son exactos (`DBL_DIG`), por
+/// lo que se usan esos para obtener el mismo resultado (ej: 0.1 + 0.2 = 0.3).
+const FLOAT_DIGITS: usize = 15;
+/// Bits del reloj LRU que informa DEBUG OBJECT, como en Redis.
+const LRU_CLOCK_MAX: u64 = (1 << 24) - 1;
+/// Cantidad máxima de miembros que puede devolver SRANDMEMBER con `count` negativo, para no
+/// bloquear la base de datos ni agotar la memoria armando la respuesta.
+const MAX_RANDOM_MEMBERS: u64 = 1_000_000;
 
 #[derive(Debug)]
 /// Entidad que representa la Base de Datos Redis dentro de nuestro modelado.
 pub struct Redis {
-    /// Atributo en el cual se guardarán los datos de la DB
-    db: TtlHashMap<String, RedisElement>,
+    /// Atributo en el cual se guardarán los datos de la DB, dividido en shards que los hilos de
+    /// los clientes pueden tomar por separado.
+    db: Keyspace,
     /// Canal para enviar eventos de loggeo al Logger
     log_sender: Sender<Log>,
     /// Mapa en donde se guardan los Senders de los clientes subscriptos al Command::Monitor
-    monitor_subs_vec: Vec<Sender<Re>>,
+    monitor_subs_vec: Vec<(u64, MessageSender<Re>)>,
     /// Mapa en donde se guarda {id_canal, Vec<Senders de los Usuarios subscriptos a esos canales>}.
-    subscribers: HashMap<String, Vec<(String, Sender<Re>)>>,
+    subscribers: HashMap<String, Vec<(u64, MessageSender<Re>)>>,
     /// Mapa en donde se guarda {Id_cliente, Vec<Canales a los que esta subscripto>}.
-    client_channel: HashMap<String, Vec<String>>,
+    client_channel: HashMap<u64, Vec<String>>,
     /// Cantidad de usuarios conectados
     users_connected: u64,
     /// Hora en cuando comenzó el servicio.
     server_time: SystemTime,
     /// Configuración del servidor compartida.
     config: Arc<Mutex<Config>>,
+    /// Registro de los clientes bloqueados por BLPOP/BRPOP.
+    blocked_clients: BlockedClients,
+    /// Registro de los clientes conectados, compartido con el servidor.
+    connected_clients: Arc<Mutex<ConnectedClients>>,
+    /// Hilo que persiste la base de datos en disco, si hay un guardado en curso.
+    background_save: Option<JoinHandle<()>>,
+    /// Cantidad de modificaciones realizadas desde el último guardado.
+    dirty: u64,
+    /// Momento en el cual comenzó el último guardado.
+    last_save: SystemTime,
+    /// Identificador de replicación del servidor, generado al iniciarlo.
+    replication_id: String,
+    /// Hilo que libera en segundo plano los valores eliminados con UNLINK y FLUSHDB ASYNC.
+    lazy_free: LazyFree,
+    /// Estadísticas de ejecución de cada tipo de comando.
+    command_stats: CommandStats,
+    /// Cantidad de búsquedas de claves existentes realizadas por comandos de lectura.
+    keyspace_hits: u64,
+    /// Cantidad de búsquedas de claves inexistentes realizadas por comandos de lectura.
+    keyspace_misses: u64,
+    /// Cantidad de claves eliminadas por haber expirado.
+    expired_keys: u64,
+    /// Cantidad de claves descartadas por superar `maxmemory`.
+    evicted_keys: u64,
+    /// Estadísticas de comandos procesados y tráfico de red, compartidas con el servidor.
+    server_stats: Arc<Mutex<ServerStats>>,
+    /// Comandos atendidos desde los hilos de los clientes que todavía no se registraron en las
+    /// estadísticas.
+    shared_commands: SharedCommands,
+    /// Canales de los observadores registrados con `watch_changes`, a los que se envía cada
+    /// modificación de una clave.
+    change_observers: Vec<Sender<KeyChange>>,
+    /// Cantidad de mensajes publicados descartados por estar llena la cola del suscriptor.
+    pubsub_dropped_messages: u64,
+    /// Cantidad de suscriptores desconectados por estar llena su cola de mensajes.
+    pubsub_disconnections: u64,
+    /// Últimos comandos que demoraron más que `slowlog-log-slower-than`.
+    slowlog: Slowlog,
+    /// Scripts compilados por EVAL y SCRIPT LOAD, indexados por el SHA1 de su código.
+    scripts: HashMap<String, Arc<Script>>,
+    /// Indica si está habilitado el ciclo de expiración activa, modificable con
+    /// DEBUG SET-ACTIVE-EXPIRE.
+    active_expire: bool,
+    /// Momento en el cual se ejecutó el último ciclo de expiración activa.
+    last_active_expire: Instant,
+    /// Reloj con el que se calculan las expiraciones, compartido con la base de datos.
+    clock: Arc<dyn Clock>,
 }
 
 impl Redis {
@@ -52,9 +187,18 @@ impl Redis {
     ///
     /// * Un canal de tipo `Sender` en el cual se envíen mensajes al Logger.
     /// * Una configuracion compartida
-    pub fn new(log_sender: Sender<Log>, config: Arc<Mutex<Config>>) -> Self {
-        let db = TtlHashMap::new();
-        let vec_senders: Vec<Sender<Re>> = Vec::new();
+    /// * Un registro de clientes conectados compartido
+    /// * Las estadísticas del servidor compartidas
+    pub fn new(
+        log_sender: Sender<Log>,
+        config: Arc<Mutex<Config>>,
+        connected_clients: Arc<Mutex<ConnectedClients>>,
+        server_stats: Arc<Mutex<ServerStats>>,
+    ) -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let mut db = Keyspace::with_clock(Arc::clone(&clock));
+        db.set_access_tracking(config.lock().unwrap().is_track_key_access());
+        let vec_senders: Vec<(u64, MessageSender<Re>)> = Vec::new();
 
         Self {
             db,
@@ -63,18 +207,43 @@ impl Redis {
             users_connected: 0,
             subscribers: HashMap::new(),
             client_channel: HashMap::new(),
-            server_time: SystemTime::now(),
+            server_time: clock.now(),
             config,
+            blocked_clients: BlockedClients::new(),
+            connected_clients,
+            background_save: None,
+            dirty: 0,
+            last_save: clock.now(),
+            replication_id: random_hex(REPLICATION_ID_LEN),
+            lazy_free: LazyFree::new(),
+            command_stats: CommandStats::new(),
+            keyspace_hits: 0,
+            keyspace_misses: 0,
+            expired_keys: 0,
+            evicted_keys: 0,
+            server_stats,
+            shared_commands: SharedCommands::new(),
+            change_observers: Vec::new(),
+            pubsub_dropped_messages: 0,
+            pubsub_disconnections: 0,
+            slowlog: Slowlog::new(),
+            scripts: HashMap::new(),
+            active_expire: true,
+            last_active_expire: Instant::now(),
+            clock,
         }
     }
 
     #[allow(dead_code)]
     /// Constructor de la entidad Redis exclusiva para TEST.
     fn new_for_test() -> Self {
-        let db = TtlHashMap::new();
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let mut db = Keyspace::with_clock(Arc::clone(&clock));
+        db.set_access_tracking(true);
         let (log_sender, _): (Sender<Log>, _) = mpsc::channel();
-        let vec_senders: Vec<Sender<Re>> = Vec::new();
+        let vec_senders: Vec<(u64, MessageSender<Re>)> = Vec::new();
         let config = Arc::new(Mutex::new(Config::new()));
+        let connected_clients = Arc::new(Mutex::new(ConnectedClients::new()));
 
         Self {
             db,
@@ -83,11 +252,41 @@ impl Redis {
             users_connected: 0,
             subscribers: HashMap::new(),
             client_channel: HashMap::new(),
-            server_time: SystemTime::now(),
+            server_time: clock.now(),
             config,
+            blocked_clients: BlockedClients::new(),
+            connected_clients,
+            background_save: None,
+            dirty: 0,
+            last_save: clock.now(),
+            replication_id: random_hex(REPLICATION_ID_LEN),
+            lazy_free: LazyFree::new(),
+            command_stats: CommandStats::new(),
+            keyspace_hits: 0,
+            keyspace_misses: 0,
+            expired_keys: 0,
+            evicted_keys: 0,
+            server_stats: Arc::new(Mutex::new(ServerStats::new())),
+            shared_commands: SharedCommands::new(),
+            change_observers: Vec::new(),
+            pubsub_dropped_messages: 0,
+            pubsub_disconnections: 0,
+            slowlog: Slowlog::new(),
+            scripts: HashMap::new(),
+            active_expire: true,
+            last_active_expire: Instant::now(),
+            clock,
         }
     }
 
+    /// Reemplaza el reloj con el que se calculan las expiraciones de las claves, los timeouts de
+    /// los clientes bloqueados y los guardados periódicos, para poder avanzar el tiempo sin
+    /// esperar.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.db.set_clock(Arc::clone(&clock));
+        self.clock = clock;
+    }
+
     #[allow(dead_code)]
     /// Metodo utilizado para ejecutar un comando dentro de la Base de datos Redis.
     ///
@@ -96,27 +295,63 @@ impl Redis {
     /// En caso de error en la ejecución se retornará Err(msg) con el mensaje de error.
     /// En caso de ejecución efectiva del comando se retornará un Response.
     pub fn execute(&mut self, command: Command) -> Result<Response, String> {
-        self.notify_monitor(&command);
+        self.execute_from_client(command, None, &[])
+    }
 
-        match command {
+    /// Ejecuta un comando enviado por un cliente, como `execute`.
+    ///
+    /// El comando se informa a los clientes en MONITOR, y se registra en el slowlog si es lento,
+    /// con los argumentos recibidos (`args`) y la dirección del cliente con el id indicado. Si no
+    /// se indican los argumentos se informa sólo el nombre del comando, y si no se indica el
+    /// cliente se informa como un comando interno.
+    pub fn execute_from_client(
+        &mut self,
+        command: Command,
+        client_id: Option<u64>,
+        args: &[String],
+    ) -> Result<Response, String> {
+        self.apply_shared_commands();
+        self.notify_monitor(&command, client_id, args);
+        self.check_limits(&command)?;
+        self.check_maxmemory(&command)?;
+
+        let name = command.as_str();
+        let secrets_from = Redis::secrets_from(&command);
+        let start = Instant::now();
+        let response = match command {
             // Server
-            Command::Ping => Ok(self.ping_method()),
-            Command::Flushdb => Ok(self.flushdb_method()),
+            Command::Ping { message } => Ok(self.ping_method(message)),
+            Command::Echo { message } => Ok(self.echo_method(message)),
+            Command::Flushdb { asynchronous } => Ok(self.flushdb_method(asynchronous)),
+            Command::Flushall { asynchronous } => Ok(self.flushall_method(asynchronous)),
             Command::Dbsize => Ok(self.dbsize_method()),
-            Command::Monitor => self.monitor_method(),
+            Command::Monitor { client_id } => self.monitor_method(client_id),
+            Command::Quit => Ok(Response::Normal(Re::SimpleString("OK".to_string()))),
             Command::Info { param } => self.info_method(param),
 
             // System
             Command::Store { path } => self.store_method(path),
             Command::Load { path } => self.load_method(path),
-            Command::ConfigGet => Ok(Response::Normal(Re::List(self.config_get_method()))),
+            Command::ConfigGet { patterns } => {
+                Ok(Response::Normal(Re::List(self.config_get_method(patterns))))
+            }
             Command::ConfigSet { parameter, value } => self.config_set_method(parameter, value),
+            Command::ConfigResetstat => Ok(self.config_resetstat_method()),
+            Command::Auth { password } => self.auth_method(password),
+            Command::Client { param, client_id } => self.client_method(param, client_id),
+            Command::Cluster { param } => self.cluster_method(param),
+            Command::Memory { param } => Ok(Response::Normal(self.memory_method(param))),
+            Command::Slowlog { param } => Ok(Response::Normal(self.slowlog_method(param))),
+            Command::Debug { param } => self.debug_method(param),
             Command::AddClient => Ok(self.addclient_method()),
-            Command::RemoveClient => Ok(self.removeclient_method()),
+            Command::RemoveClient { client_id } => Ok(self.removeclient_method(client_id)),
 
             // Strings
             Command::Append { key, value } => self.append_method(key, value),
-            Command::Decrby { key, decrement } => self.incrby_method(key, -(decrement as i32)),
+            Command::Decrby { key, decrement } => match decrement.checked_neg() {
+                Some(increment) => self.incrby_method(key, increment),
+                None => Err(OVERFLOW_MSG.to_string()),
+            },
             Command::Get { key } => match self.get_method(key) {
                 Ok(re) => Ok(Response::Normal(re)),
                 Err(e) => Err(e),
@@ -126,44 +361,140 @@ impl Redis {
                 Err(e) => Err(e),
             },
             Command::Getset { key, value } => self.getset_method(key, value),
-            Command::Incrby { key, increment } => self.incrby_method(key, increment as i32),
+            Command::Incrby { key, increment } => self.incrby_method(key, increment),
+            Command::Incrbyfloat { key, increment } => self.incrbyfloat_method(key, increment),
             Command::Mget { keys } => Ok(self.mget_method(keys)),
             Command::Mset { key_values } => Ok(self.mset_method(key_values)),
-            Command::Set { key, value } => Ok(Response::Normal(Re::SimpleString(
-                self.set_method(key, value),
-            ))),
+            Command::Msetnx { key_values } => Ok(self.msetnx_method(key_values)),
+            Command::Set {
+                key,
+                value,
+                options,
+            } => self.set_with_options_method(key, value, options),
+            Command::Setnx { key, value } => Ok(self.setnx_method(key, value)),
             Command::Strlen { key } => self.strlen_method(key),
 
+            // Bitmaps
+            Command::Setbit { key, offset, value } => self.setbit_method(key, offset, value),
+            Command::Getbit { key, offset } => self.getbit_method(key, offset),
+            Command::Bitcount { key, range } => self.bitcount_method(key, range),
+            Command::Bitpos {
+                key,
+                bit,
+                start,
+                end,
+                unit,
+            } => self.bitpos_method(key, bit, start, end, unit),
+            Command::Bitop {
+                operation,
+                destkey,
+                keys,
+            } => self.bitop_method(operation, destkey, keys),
+
             // Keys
             Command::Copy {
                 key_origin,
                 key_destination,
-            } => Ok(self.copy_method(key_origin, key_destination)),
-            Command::Del { keys } => Ok(Response::Normal(Re::String(self.del_method(keys)))),
-            Command::Exists { keys } => Ok(self.exists_method(keys)),
-            Command::Expire { key, ttl } => {
-                Ok(Response::Normal(Re::String(self.expire_method(key, ttl))))
+            } => {
+                let response = self.copy_method(key_origin, key_destination.clone());
+                self.serve_blocked_clients(key_destination);
+                Ok(response)
             }
-            Command::Expireat { key, ttl } => {
-                Ok(Response::Normal(Re::String(self.expireat_method(key, ttl))))
-            }
-            Command::Persist { key } => Ok(Response::Normal(Re::String(self.persist_method(key)))),
+            Command::Del { keys } => Ok(Response::Normal(Re::Integer(self.del_method(keys)))),
+            Command::Unlink { keys } => Ok(Response::Normal(Re::Integer(self.unlink_method(keys)))),
+            Command::Dump { key } => Ok(Response::Normal(self.dump_method(key))),
+            Command::Exists { keys } => Ok(self.exists_method(keys)),
+            Command::Expire {
+                key,
+                ttl,
+                conditions,
+            } => self
+                .expire_method(key, ttl, conditions, "expire")
+                .map(|result| Response::Normal(Re::Integer(result))),
+            Command::Expireat {
+                key,
+                ttl,
+                conditions,
+            } => Ok(Response::Normal(Re::Integer(
+                self.expireat_method(key, ttl, conditions),
+            ))),
+            Command::Pexpire {
+                key,
+                ttl,
+                conditions,
+            } => self
+                .expire_method(key, ttl, conditions, "pexpire")
+                .map(|result| Response::Normal(Re::Integer(result))),
+            Command::Pexpireat {
+                key,
+                ttl,
+                conditions,
+            } => Ok(Response::Normal(Re::Integer(
+                self.expireat_method(key, ttl, conditions),
+            ))),
+            Command::Persist { key } => Ok(Response::Normal(Re::Integer(self.persist_method(key)))),
+            Command::Randomkey => Ok(Response::Normal(self.randomkey_method())),
             Command::Rename {
                 key_origin,
                 key_destination,
-            } => self.rename_method(key_origin, key_destination),
+            } => {
+                let response = self.rename_method(key_origin, key_destination.clone());
+                self.serve_blocked_clients(key_destination);
+                response
+            }
+            Command::Renamenx {
+                key_origin,
+                key_destination,
+            } => {
+                let response = self.renamenx_method(key_origin, key_destination.clone());
+                self.serve_blocked_clients(key_destination);
+                response
+            }
+            Command::Restore {
+                key,
+                ttl,
+                payload,
+                replace,
+            } => {
+                let response = self.restore_method(key.clone(), ttl, payload, replace);
+                self.serve_blocked_clients(key);
+                response
+            }
             Command::Keys { pattern } => Ok(Response::Normal(Re::List(self.keys_method(pattern)))),
-            Command::Touch { keys } => Ok(Response::Normal(Re::String(self.touch_method(keys)))),
-            Command::Ttl { key } => Ok(Response::Normal(Re::String(self.ttl_method(key)))),
+            Command::Object { param } => self.object_method(param).map(Response::Normal),
+            Command::Touch { keys } => Ok(Response::Normal(Re::Integer(self.touch_method(keys)))),
+            Command::Ttl { key } => Ok(Response::Normal(Re::Integer(self.ttl_method(key)))),
+            Command::Pttl { key } => Ok(Response::Normal(Re::Integer(self.pttl_method(key)))),
+            Command::Expiretime { key } => Ok(Response::Normal(Re::Integer(
+                self.expiretime_method(key, |time| time.as_secs() as i64),
+            ))),
+            Command::Pexpiretime { key } => Ok(Response::Normal(Re::Integer(
+                self.expiretime_method(key, |time| time.as_millis() as i64),
+            ))),
             Command::Type { key } => Ok(Response::Normal(Re::String(self.type_method(key)))),
-            Command::Sort { key } => self.sort_method(key),
+            Command::Sort { key, options } => {
+                let destination = options.store.clone();
+                let response = self.sort_method(key, options);
+                if let Some(destination) = destination {
+                    self.serve_blocked_clients(destination);
+                }
+                response
+            }
 
             // Lists
             Command::Lindex { key, index } => self.lindex_method(key, index),
             Command::Llen { key } => self.llen_method(key),
-            Command::Lpop { key, count } => self.lpop_method(key, count),
-            Command::Lpush { key, value } => self.lpush_method(key, value),
-            Command::Lpushx { key, value } => self.lpushx_method(key, value),
+            Command::Lpop { key, count } => self.pop_method(key, count, ListSide::Left),
+            Command::Lpush { key, value } => {
+                let response = self.lpush_method(key.clone(), value);
+                self.serve_blocked_clients(key);
+                response
+            }
+            Command::Lpushx { key, value } => {
+                let response = self.lpushx_method(key.clone(), value);
+                self.serve_blocked_clients(key);
+                response
+            }
             Command::Lrange { key, begin, end } => self.lrange_method(key, begin, end),
             Command::Lrem {
                 key,
@@ -175,16 +506,164 @@ impl Redis {
                 index,
                 element,
             } => self.lset_method(key, index, element),
-            Command::Rpop { key, count } => self.rpop_method(key, count),
-            Command::Rpush { key, value } => self.rpush_method(key, value),
-            Command::Rpushx { key, value } => self.rpushx_method(key, value),
+            Command::Rpop { key, count } => self.pop_method(key, count, ListSide::Right),
+            Command::Rpush { key, value } => {
+                let response = self.rpush_method(key.clone(), value);
+                self.serve_blocked_clients(key);
+                response
+            }
+            Command::Rpushx { key, value } => {
+                let response = self.rpushx_method(key.clone(), value);
+                self.serve_blocked_clients(key);
+                response
+            }
+            Command::Blpop { keys, timeout } => {
+                self.blocking_pop_method(keys, timeout, ListSide::Left)
+            }
+            Command::Brpop { keys, timeout } => {
+                self.blocking_pop_method(keys, timeout, ListSide::Right)
+            }
 
             // Sets
             Command::Sadd { key, values } => self.sadd_method(key, values),
             Command::Scard { key } => self.scard_method(key),
             Command::Sismember { key, value } => self.sismember_method(key, value),
             Command::Smembers { key } => self.smembers_method(key),
+            Command::Smismember { key, members } => self.smismember_method(key, members),
+            Command::Sinter { keys } => self.sinter_method(keys),
+            Command::Sintercard { keys, limit } => self.sintercard_method(keys, limit),
+            Command::Sunion { keys } => self.sunion_method(keys),
+            Command::Sdiff { keys } => self.sdiff_method(keys),
+            Command::Spop { key, count } => self.spop_method(key, count),
+            Command::Srandmember { key, count } => self.srandmember_method(key, count),
             Command::Srem { key, values } => self.srem_method(key, values),
+            Command::Sscan {
+                key,
+                cursor,
+                pattern,
+                count,
+            } => self.sscan_method(key, cursor, pattern, count),
+
+            // Sorted Sets
+            Command::Bzpopmax { keys, timeout } => {
+                self.blocking_zpop_method(keys, timeout, ScoreSide::Max)
+            }
+            Command::Bzpopmin { keys, timeout } => {
+                self.blocking_zpop_method(keys, timeout, ScoreSide::Min)
+            }
+            Command::Zadd { key, members } => {
+                let response = self.zadd_method(key.clone(), members);
+                self.serve_blocked_clients(key);
+                response
+            }
+            Command::Zcard { key } => self.zcard_method(key),
+            Command::Zincrby {
+                key,
+                increment,
+                member,
+            } => {
+                let response = self.zincrby_method(key.clone(), increment, member);
+                self.serve_blocked_clients(key);
+                response
+            }
+            Command::Zinterstore {
+                destination,
+                keys,
+                weights,
+                aggregate,
+            } => {
+                let response =
+                    self.zinterstore_method(destination.clone(), keys, weights, aggregate);
+                self.serve_blocked_clients(destination);
+                response
+            }
+            Command::Zpopmax { key, count } => self.zpop_method(key, count, ScoreSide::Max),
+            Command::Zpopmin { key, count } => self.zpop_method(key, count, ScoreSide::Min),
+            Command::Zrange {
+                key,
+                start,
+                stop,
+                with_scores,
+            } => self.zrange_method(key, start, stop, with_scores),
+            Command::Zrangebyscore {
+                key,
+                min,
+                max,
+                with_scores,
+            } => self.zrangebyscore_method(key, min, max, with_scores),
+            Command::Zrank { key, member } => self.zrank_method(key, member),
+            Command::Zrem { key, members } => self.zrem_method(key, members),
+            Command::Zscore { key, member } => self.zscore_method(key, member),
+            Command::Zunionstore {
+                destination,
+                keys,
+                weights,
+                aggregate,
+            } => {
+                let response =
+                    self.zunionstore_method(destination.clone(), keys, weights, aggregate);
+                self.serve_blocked_clients(destination);
+                response
+            }
+
+            // Geo
+            Command::Geoadd {
+                key,
+                options,
+                members,
+            } => {
+                let response = self.geoadd_method(key.clone(), options, members);
+                self.serve_blocked_clients(key);
+                response
+            }
+            Command::Geodist {
+                key,
+                member1,
+                member2,
+                unit,
+            } => self.geodist_method(key, member1, member2, unit),
+            Command::Geopos { key, members } => self.geopos_method(key, members),
+            Command::Geosearch { key, options } => self.geosearch_method(key, options),
+
+            // Streams
+            Command::Xadd {
+                key,
+                id,
+                max_len,
+                fields,
+            } => self.xadd_method(key, id, max_len, fields),
+            Command::Xlen { key } => self.xlen_method(key),
+            Command::Xrange {
+                key,
+                start,
+                end,
+                count,
+            } => self.xrange_method(key, start, end, count, false),
+            Command::Xrevrange {
+                key,
+                start,
+                end,
+                count,
+            } => self.xrange_method(key, start, end, count, true),
+            Command::Xread { count, streams } => self.xread_method(count, streams),
+            Command::Xgroup { key, param } => self.xgroup_method(key, param),
+            Command::Xreadgroup {
+                group,
+                consumer,
+                count,
+                no_ack,
+                streams,
+            } => self.xreadgroup_method(group, consumer, count, no_ack, streams),
+            Command::Xack { key, group, ids } => self.xack_method(key, group, ids),
+            Command::Xpending { key, group, range } => self.xpending_method(key, group, range),
+            Command::Xclaim {
+                key,
+                group,
+                consumer,
+                min_idle,
+                ids,
+                just_id,
+            } => self.xclaim_method(key, group, consumer, min_idle, ids, just_id),
 
             // Pubsub
             Command::Pubsub { param } => Ok(self.pubsub_method(param)),
@@ -197,8 +676,432 @@ impl Redis {
                 channels,
                 client_id,
             } => Ok(self.unsubscribe_method(channels, client_id)),
-            Command::Command => (Ok(Response::Normal(Re::SimpleString("OK".to_string())))),
+            Command::Command { param } => Ok(Response::Normal(self.command_method(param))),
+
+            // Scripting
+            Command::Eval { script, keys, args } => self.eval_method(script, keys, args),
+            Command::Evalsha { sha1, keys, args } => self.evalsha_method(sha1, keys, args),
+            Command::Script { param } => self.script_method(param),
+        };
+
+        if !name.is_empty() {
+            let elapsed = start.elapsed();
+            self.command_stats.record(name, elapsed);
+            self.log_execution(name, client_id, elapsed);
+            self.record_slowlog(name, client_id, args, secrets_from, elapsed);
+        }
+
+        self.notify_expired_keys();
+        response
+    }
+
+    /// Verifica que el comando no supere los límites de tamaño configurados: el largo de las claves
+    /// que escribe, el tamaño de los valores que guarda y la cantidad de elementos de la colección a
+    /// la que agrega elementos. Así un cliente no puede agotar la memoria con un único comando.
+    fn check_limits(&self, command: &Command) -> Result<(), String> {
+        self.check_limits_in(command, |key| self.db.read(key))
+    }
+
+    /// Verifica los límites de `check_limits` leyendo el valor actual de la clave que crece del
+    /// shard que devuelve `shard`, para poder verificarlos con el shard ya tomado.
+    fn check_limits_in<S: Deref<Target = Shard>>(
+        &self,
+        command: &Command,
+        shard: impl Fn(&str) -> S,
+    ) -> Result<(), String> {
+        let (max_key_length, max_value_size, max_elements) = {
+            let config = self.config.lock().unwrap();
+            (
+                config.get_max_key_length(),
+                config.get_max_value_size(),
+                config.get_max_collection_elements(),
+            )
+        };
+        let exceeds = |len: usize, max: u64| max > 0 && len as u64 > max;
+        let data = command.written_data();
+
+        if data
+            .keys
+            .iter()
+            .any(|key| exceeds(key.len(), max_key_length))
+        {
+            return Err(KEY_TOO_LONG_MSG.to_string());
+        }
+        if data
+            .values
+            .iter()
+            .any(|value| exceeds(value.len(), max_value_size))
+        {
+            return Err(VALUE_TOO_BIG_MSG.to_string());
+        }
+        if let (Some((key, appended)), true) = (data.appended, max_value_size > 0) {
+            let current = match shard(key).get_shared(&key.to_string()).flatten() {
+                Some(value) => value.as_bytes().map_or(0, |value| value.len()),
+                None => 0,
+            };
+            if exceeds(current + appended, max_value_size) {
+                return Err(VALUE_TOO_BIG_MSG.to_string());
+            }
+        }
+        if let (Some((key, elements)), true) = (data.added, max_elements > 0) {
+            // En los sets y sorted sets sólo se cuentan los miembros que todavía no están.
+            let (current, added) = match shard(key).get_shared(&key.to_string()).flatten() {
+                Some(Re::List(list)) => (list.len(), elements.len()),
+                Some(Re::Set(set)) => (
+                    set.len(),
+                    elements.iter().filter(|e| !set.contains(**e)).count(),
+                ),
+                Some(Re::SortedSet(set)) => (
+                    set.len(),
+                    elements.iter().filter(|e| set.score(e).is_none()).count(),
+                ),
+                Some(_) => (0, 0),
+                None => (0, elements.len()),
+            };
+            if added > 0 && current + added > max_elements {
+                return Err(TOO_MANY_ELEMENTS_MSG.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifica que la memoria usada por los datos no supere `maxmemory` antes de ejecutar un
+    /// comando que puede agregar datos (los marcados como `denyoom`), como hace Redis. Los
+    /// comandos que sólo leen o eliminan datos se ejecutan siempre.
+    ///
+    /// Si se supera el límite se descartan claves según `maxmemory-policy` hasta volver a estar
+    /// por debajo. Si la política no permite descartar claves o no quedan claves candidatas, se
+    /// responde un error. La memoria usada se toma de la cuenta que lleva la base de datos, sin
+    /// recorrer las claves.
+    fn check_maxmemory(&mut self, command: &Command) -> Result<(), String> {
+        let (maxmemory, policy) = {
+            let config = self.config.lock().unwrap();
+            (config.get_maxmemory(), config.get_maxmemory_policy())
+        };
+        let denyoom = command_table::lookup(command.as_str())
+            .is_some_and(|spec| spec.flags.contains(&"denyoom"));
+        if maxmemory == 0 || !denyoom {
+            return Ok(());
+        }
+        while self.db.used_memory() as u64 > maxmemory {
+            let candidate = match policy {
+                MaxmemoryPolicy::NoEviction => None,
+                MaxmemoryPolicy::AllkeysLfu => self.db.lfu_candidate(EVICTION_SAMPLES, false),
+                MaxmemoryPolicy::VolatileLfu => self.db.lfu_candidate(EVICTION_SAMPLES, true),
+            };
+            let key = candidate.ok_or_else(|| OOM_MSG.to_string())?;
+            self.db.delete(&key);
+            self.evicted_keys += 1;
+            self.notify_keyspace_event(EventClass::Evicted, "evicted", &key);
+        }
+        Ok(())
+    }
+
+    /// Registra en el log de debug la ejecución del comando enviado por un cliente, junto con su
+    /// id, su dirección y lo que demoró, para poder rastrear a los clientes lentos o abusivos.
+    fn log_execution(&self, name: &str, client_id: Option<u64>, elapsed: Duration) {
+        let client_id = match client_id {
+            Some(client_id) => client_id,
+            None => return,
+        };
+        let log = Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!("Command {} executed", name.to_uppercase()),
+        )
+        .with_client(client_id, self.client_addr(Some(client_id)))
+        .with_elapsed(elapsed);
+        let _ = self.log_sender.send(log);
+    }
+
+    /// Registra el comando en el slowlog si demoró más que `slowlog-log-slower-than`, con sus
+    /// argumentos recortados como en Redis. Si no se indican los argumentos, se registra el nombre
+    /// del comando. Las contraseñas (a partir de `secrets_from`) se ocultan.
+    fn record_slowlog(
+        &mut self,
+        name: &str,
+        client_id: Option<u64>,
+        args: &[String],
+        secrets_from: Option<usize>,
+        elapsed: Duration,
+    ) {
+        let (slower_than, max_len) = {
+            let config = self.config.lock().unwrap();
+            (
+                config.get_slowlog_log_slower_than(),
+                config.get_slowlog_max_len(),
+            )
+        };
+        if slower_than < 0 || elapsed.as_micros() < slower_than as u128 {
+            return;
+        }
+
+        let args = match args {
+            [] => vec![name.to_string()],
+            args => Redis::redacted_args(args, secrets_from),
+        };
+        let client_name = client_id
+            .and_then(|id| {
+                let clients = self.connected_clients.lock().unwrap();
+                clients.get(id).map(|client| client.name.clone())
+            })
+            .unwrap_or_default();
+        let addr = self.client_addr(client_id);
+        self.slowlog
+            .push(&args, elapsed, addr, client_name, max_len);
+    }
+
+    /// Devuelve la dirección del cliente con el id indicado, o la dirección interna si el comando
+    /// no fue enviado por un cliente conectado.
+    fn client_addr(&self, client_id: Option<u64>) -> String {
+        client_id
+            .and_then(|id| {
+                let clients = self.connected_clients.lock().unwrap();
+                clients.get(id).map(|client| client.addr.clone())
+            })
+            .unwrap_or_else(|| INTERNAL_CLIENT_ADDR.to_string())
+    }
+
+    /// Ejecuta un comando de una única clave con una referencia compartida, tomando sólo el shard
+    /// de la clave, para que los hilos de los clientes puedan atenderlo en paralelo mientras la DB
+    /// está tomada en modo lectura. Se atienden las lecturas GET, EXISTS y TTL, y las escrituras
+    /// SET, INCRBY, DECRBY y APPEND. Las estadísticas, el slowlog, los accesos a las claves leídas
+    /// y las modificaciones para las reglas de guardado se registran luego con
+    /// `apply_shared_commands`.
+    ///
+    /// Retorna None si el comando debe ejecutarse con `execute`: si no es uno de esos comandos, si
+    /// hay clientes en MONITOR, si alguna clave leída expiró y hay que borrarla e informarlo, o si
+    /// la escritura requiere al hilo de la DB (ver `writes_from_clients`).
+    pub fn execute_shared(&self, command: &Command) -> Option<Result<Response, String>> {
+        self.execute_shared_from_client(command, None, &[])
+    }
+
+    /// Ejecuta un comando de una única clave enviado por un cliente, como `execute_shared`,
+    /// registrando en el log y en el slowlog el id y la dirección del cliente con el id indicado, y
+    /// los argumentos recibidos (`args`).
+    pub fn execute_shared_from_client(
+        &self,
+        command: &Command,
+        client_id: Option<u64>,
+        args: &[String],
+    ) -> Option<Result<Response, String>> {
+        if !self.monitor_subs_vec.is_empty() {
+            return None;
+        }
+        let write = matches!(
+            command,
+            Command::Set { .. }
+                | Command::Incrby { .. }
+                | Command::Decrby { .. }
+                | Command::Append { .. }
+        );
+        if write && !self.writes_from_clients() {
+            return None;
+        }
+
+        let start = Instant::now();
+        let (response, lookup, modified) = match command {
+            Command::Get { key } => {
+                let shard = self.db.read(key);
+                let value = shard.get_shared(key)?;
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command GET Received - key: ".to_string() + key,
+                ));
+                let response = match value.and_then(Re::string_reply) {
+                    Some(reply) => Ok(Response::Normal(reply)),
+                    None if value.is_some() => Err(WRONGTYPE_MSG.to_string()),
+                    None => Ok(Response::Normal(Re::Nil)),
+                };
+                (response, Some((key.to_string(), value.is_some())), false)
+            }
+            Command::Exists { keys } => {
+                let mut count = 0;
+                for key in keys.iter() {
+                    if self.db.read(key).get_shared(key)?.is_some() {
+                        count += 1;
+                    }
+                }
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command EXISTS Received - key: ".to_string() + &keys.join(" - "),
+                ));
+                (Ok(Response::Normal(Re::Integer(count))), None, false)
+            }
+            Command::Ttl { key } => {
+                let ttl = self.db.read(key).get_ttl_shared(key)?;
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command TTL Received - key: ".to_string() + key,
+                ));
+                (
+                    Ok(Response::Normal(Re::Integer(format_ttl(ttl)))),
+                    None,
+                    false,
+                )
+            }
+            Command::Set {
+                key,
+                value,
+                options,
+            } => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command SET Received - key: ".to_string() + key,
+                ));
+                let mut shard = match self.write_shard(command, key) {
+                    Ok(shard) => shard,
+                    Err(e) => return Some(Err(e)),
+                };
+                match set_in_shard(&mut shard, key.clone(), value.clone(), options) {
+                    Ok((reply, written)) => (Ok(Response::Normal(reply)), None, written),
+                    Err(e) => (Err(e), None, false),
+                }
+            }
+            Command::Incrby { key, increment } => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command INCRBY Received - key: ".to_string() + key,
+                ));
+                let mut shard = match self.write_shard(command, key) {
+                    Ok(shard) => shard,
+                    Err(e) => return Some(Err(e)),
+                };
+                let result = incrby_in_shard(&mut shard, key, *increment);
+                let modified = result.is_ok();
+                (
+                    result.map(|n| Response::Normal(Re::Integer(n))),
+                    None,
+                    modified,
+                )
+            }
+            Command::Decrby { key, decrement } => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command DECRBY Received - key: ".to_string() + key,
+                ));
+                let mut shard = match self.write_shard(command, key) {
+                    Ok(shard) => shard,
+                    Err(e) => return Some(Err(e)),
+                };
+                let result = match decrement.checked_neg() {
+                    Some(increment) => incrby_in_shard(&mut shard, key, increment),
+                    None => Err(OVERFLOW_MSG.to_string()),
+                };
+                let modified = result.is_ok();
+                (
+                    result.map(|n| Response::Normal(Re::Integer(n))),
+                    None,
+                    modified,
+                )
+            }
+            Command::Append { key, value } => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command APPEND Received - key: ".to_string() + key,
+                ));
+                let mut shard = match self.write_shard(command, key) {
+                    Ok(shard) => shard,
+                    Err(e) => return Some(Err(e)),
+                };
+                let result = append_in_shard(&mut shard, key, value.clone());
+                let modified = result.is_ok();
+                (
+                    result.map(|len| Response::Normal(Re::Integer(len as i64))),
+                    None,
+                    modified,
+                )
+            }
+            _ => return None,
+        };
+
+        let elapsed = start.elapsed();
+        self.log_execution(command.as_str(), client_id, elapsed);
+        self.shared_commands.push(SharedCommand {
+            command: command.as_str(),
+            client_id,
+            args: args.to_vec(),
+            elapsed,
+            lookup,
+            modified,
+        });
+        Some(response)
+    }
+
+    /// Indica si las escrituras pueden atenderse desde los hilos de los clientes. Se envían al hilo
+    /// de la DB si su efecto no se limita al shard de la clave: si hay que publicar eventos de
+    /// keyspace, avisar a los observadores de `watch_changes` o verificar `maxmemory`, que puede
+    /// descartar claves de cualquier shard.
+    fn writes_from_clients(&self) -> bool {
+        if !self.change_observers.is_empty() {
+            return false;
+        }
+        let config = self.config.lock().unwrap();
+        config.get_maxmemory() == 0 && config.get_notify_keyspace_events().is_empty()
+    }
+
+    /// Toma en modo escritura el shard de la clave que escribe el comando, verificando con el shard
+    /// tomado que no se superen los límites de `check_limits`.
+    fn write_shard(
+        &self,
+        command: &Command,
+        key: &str,
+    ) -> Result<RwLockWriteGuard<'_, Shard>, String> {
+        let shard = self.db.write(key);
+        self.check_limits_in(command, |_| &*shard)?;
+        Ok(shard)
+    }
+
+    /// Registra en las estadísticas y en el slowlog los comandos atendidos con `execute_shared`, el
+    /// acceso a las claves leídas por GET para OBJECT IDLETIME y OBJECT FREQ, y las modificaciones
+    /// para las reglas de guardado.
+    pub fn apply_shared_commands(&mut self) {
+        for shared in self.shared_commands.take() {
+            self.command_stats.record(shared.command, shared.elapsed);
+            self.record_slowlog(
+                shared.command,
+                shared.client_id,
+                &shared.args,
+                None,
+                shared.elapsed,
+            );
+            match shared.lookup {
+                Some((key, true)) => {
+                    self.keyspace_hits += 1;
+                    self.db.update_last_access(&key);
+                }
+                Some((_, false)) => self.keyspace_misses += 1,
+                None => (),
+            }
+            if shared.modified {
+                self.dirty += 1;
+            }
         }
+        // Las claves expiradas al escribir desde los hilos de los clientes se informan ahora.
+        self.notify_expired_keys();
     }
 
     /// El comando PUBSUB Es un comando de análisis que permite inspeccionar el estado del sistema Pub/Sub.
@@ -214,12 +1117,14 @@ impl Redis {
     /// * `ChannelsWithChannel` - Indica si ese canal existe o no.
     /// * `Numsub` - retorna una lista vacía
     /// * `NumsubWithChannel` - Indica la cantidad de usuarios subscriptos a ese canal.
+    /// * `Numpat` - Indica la cantidad de patrones distintos con usuarios subscriptos.
     fn pubsub_method(&mut self, param: PubSubParam) -> Response {
         Response::Normal(match param {
             PubSubParam::Channels => self.channels_method(),
             PubSubParam::ChannelsWithChannel(channel) => self.channels_with_channel_method(channel),
             PubSubParam::Numsub => self.numsub_method(),
             PubSubParam::NumsubWithChannels(channels) => self.numsub_with_channels_method(channels),
+            PubSubParam::Numpat => self.numpat_method(),
         })
     }
 
@@ -274,6 +1179,21 @@ impl Redis {
         Re::List(vec![])
     }
 
+    /// Indica la cantidad de patrones distintos a los que hay usuarios subscriptos.
+    ///
+    /// Las suscripciones a patrones (PSUBSCRIBE) no están soportadas, por lo que siempre es 0.
+    fn numpat_method(&mut self) -> Re {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command Pubsub Numpat Received".to_string(),
+        ));
+
+        Re::Integer(0)
+    }
+
     /// Indica la cantidad de usuarios subscriptos a ese canal.
     fn numsub_with_channels_method(&mut self, channels: Vec<String>) -> Re {
         let _ = self.log_sender.send(Log::new(
@@ -288,19 +1208,19 @@ impl Redis {
 
         for channel in channels {
             if let Some(value) = self.subscribers.get(&channel) {
-                vec_response.push(channel.to_string());
-                vec_response.push(value.len().to_string());
+                vec_response.push(Re::String(channel.to_string()));
+                vec_response.push(Re::Integer(value.len() as i64));
             } else {
-                vec_response.push(channel.to_string());
-                vec_response.push("0".to_string());
+                vec_response.push(Re::String(channel.to_string()));
+                vec_response.push(Re::Integer(0));
             }
         }
 
-        Re::List(vec_response)
+        Re::Array(vec_response)
     }
 
     /// Permite suscribirse a uno o mas canales
-    fn subscribe_method(&mut self, channels: Vec<String>, client_id: String) -> Response {
+    fn subscribe_method(&mut self, channels: Vec<String>, client_id: u64) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -309,26 +1229,19 @@ impl Redis {
             "Command Subscribe Received".to_string(),
         ));
 
-        let (sen, rec): (Sender<Re>, Receiver<Re>) = mpsc::channel();
+        let capacity = self.config.lock().unwrap().get_pubsub_queue_size();
+        let (sen, rec): (MessageSender<Re>, MessageReceiver<Re>) = message_queue(capacity);
         for channel in channels {
-            let mut vector_sender;
-
-            if let Some(vector) = self.subscribers.get_mut(&channel) {
-                vector_sender = vector.clone();
-                vector_sender.push((client_id.clone(), sen.clone()));
-            } else {
-                vector_sender = vec![(client_id.clone(), sen.clone())];
+            // Como en Redis, suscribirse otra vez a un canal sólo repite la confirmación.
+            if !self.is_subscribed(client_id, &channel) {
+                self.add_subscriber(channel.clone(), client_id, sen.clone());
             }
 
-            self.subscribers
-                .insert(channel.clone(), vector_sender.to_vec());
-
             if sen
-                .clone()
-                .send(Re::List(vec![
-                    "subscribe".to_string(),
-                    channel.clone(),
-                    "1".to_string(),
+                .send(Re::Array(vec![
+                    Re::String("subscribe".to_string()),
+                    Re::String(channel),
+                    Re::Integer(self.subscription_count(client_id) as i64),
                 ]))
                 .is_err()
             {
@@ -340,14 +1253,44 @@ impl Redis {
                     "Error Subscribing".to_string(),
                 ));
             }
-
-            self.set_client_channels(client_id.clone(), channel);
         }
 
         Response::Stream(rec)
     }
 
-    fn set_client_channels(&mut self, client_id: String, channel: String) {
+    /// Indica si el cliente ya está suscripto al canal.
+    fn is_subscribed(&self, client_id: u64, channel: &str) -> bool {
+        self.client_channel
+            .get(&client_id)
+            .is_some_and(|channels| channels.iter().any(|sub| sub == channel))
+    }
+
+    /// Registra el Sender del cliente entre los suscriptores del canal.
+    fn add_subscriber(&mut self, channel: String, client_id: u64, sen: MessageSender<Re>) {
+        let mut vector_sender;
+
+        if let Some(vector) = self.subscribers.get_mut(&channel) {
+            vector_sender = vector.clone();
+            vector_sender.push((client_id, sen.clone()));
+        } else {
+            vector_sender = vec![(client_id, sen.clone())];
+        }
+
+        self.subscribers
+            .insert(channel.clone(), vector_sender.to_vec());
+
+        self.set_client_channels(client_id, channel);
+    }
+
+    /// Devuelve la cantidad de canales a los que está suscripto el cliente. Mientras sea mayor a
+    /// cero, el cliente se encuentra en modo suscriptor.
+    pub fn subscription_count(&self, client_id: u64) -> usize {
+        self.client_channel
+            .get(&client_id)
+            .map_or(0, |channels| channels.len())
+    }
+
+    fn set_client_channels(&mut self, client_id: u64, channel: String) {
         let mut vector_channels;
         if let Some(vector) = self.client_channel.get_mut(&client_id) {
             vector_channels = vector.clone();
@@ -371,21 +1314,49 @@ impl Redis {
         ));
 
         if !self.subscribers.contains_key(&channel) {
-            return Response::Normal(Re::String("0".to_string()));
+            return Response::Normal(Re::Integer(0));
         }
 
+        self.send_to_subscribers(channel, msg);
+
+        Response::Normal(Re::SimpleString("OK".to_string()))
+    }
+
+    /// Envía el mensaje a los clientes suscriptos al canal, desuscribiendo a los clientes que ya
+    /// no están conectados.
+    ///
+    /// Si la cola de mensajes de un cliente está llena, según `pubsub-overflow-policy` se descarta
+    /// su mensaje más antiguo o se lo desconecta.
+    fn send_to_subscribers(&mut self, channel: String, msg: String) {
+        let policy = self.config.lock().unwrap().get_pubsub_overflow_policy();
+        let mut slow_clients = Vec::new();
         if let Some(vector) = self.subscribers.get_mut(&channel) {
-            let mut empty_vec: Vec<(String, Sender<RedisElement>)> = Vec::new();
+            let mut empty_vec: Vec<(u64, MessageSender<RedisElement>)> = Vec::new();
             for (client, sender) in vector {
-                if sender
-                    .send(Re::List(vec![
-                        "message".to_string(),
-                        channel.clone(),
-                        msg.to_string(),
-                    ]))
-                    .is_ok()
-                {
-                    empty_vec.push((client.to_string(), sender.clone()));
+                let message = Re::List(vec![
+                    "message".to_string(),
+                    channel.clone(),
+                    msg.to_string(),
+                ]);
+                let delivered = match policy {
+                    OverflowPolicy::DropOldest => match sender.send_dropping_oldest(message) {
+                        Ok(dropped) => {
+                            self.pubsub_dropped_messages += dropped as u64;
+                            true
+                        }
+                        Err(_) => false,
+                    },
+                    OverflowPolicy::Disconnect => match sender.try_send(message) {
+                        Err(TrySendError::Full(_)) => {
+                            slow_clients.push(*client);
+                            true
+                        }
+                        result => result.is_ok(),
+                    },
+                };
+
+                if delivered {
+                    empty_vec.push((*client, sender.clone()));
                 } else if let Some(vector) = self.client_channel.get_mut(client) {
                     let mut vector_update: Vec<String> = Vec::new();
                     for element in vector {
@@ -393,19 +1364,91 @@ impl Redis {
                             vector_update.push(element.to_string());
                         }
                     }
-                    self.client_channel
-                        .insert(client.to_string(), vector_update);
+                    self.client_channel.insert(*client, vector_update);
                 }
             }
 
             self.subscribers.insert(channel, empty_vec);
         }
 
-        Response::Normal(Re::SimpleString("OK".to_string()))
+        for client_id in slow_clients {
+            self.disconnect_slow_subscriber(client_id);
+        }
+    }
+
+    /// Desconecta al suscriptor que no lee sus mensajes a tiempo, quitando sus registros para no
+    /// seguir encolándole mensajes.
+    fn disconnect_slow_subscriber(&mut self, client_id: u64) {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Info,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!("Disconnecting slow subscriber {}", client_id),
+        ));
+        self.pubsub_disconnections += 1;
+        self.remove_client_registrations(client_id);
+        self.connected_clients.lock().unwrap().kill(client_id);
+    }
+
+    /// Publica el evento `expired` por cada clave que la base de datos eliminó por haber expirado
+    /// desde la última notificación.
+    fn notify_expired_keys(&mut self) {
+        for key in self.db.take_expired_keys() {
+            self.expired_keys += 1;
+            self.notify_keyspace_event(EventClass::Expired, "expired", &key);
+        }
+    }
+
+    /// Publica un evento sobre una clave en los canales de keyspace y keyevent, según lo habilitado
+    /// en el parámetro `notify-keyspace-events` de la configuración.
+    ///
+    /// Todo evento sobre una clave implica una modificación, por lo que se cuenta para las reglas
+    /// de guardado.
+    fn notify_keyspace_event(&mut self, class: EventClass, event: &str, key: &str) {
+        self.dirty += 1;
+        // Las claves expiradas al acceder se informan antes que el evento que produjo el acceso.
+        self.notify_expired_keys();
+        let events = self.config.lock().unwrap().get_notify_keyspace_events();
+        for (channel, message) in events.messages(class, event, key) {
+            self.send_to_subscribers(channel, message);
+        }
+        self.notify_change(event, key);
+    }
+
+    /// Registra un observador de las modificaciones de la base de datos, para que quien embebe la
+    /// base de datos pueda reaccionar a cada cambio sin consultarla periódicamente.
+    ///
+    /// Por el canal devuelto se recibe, en orden, cada clave modificada junto con el evento y su
+    /// nuevo valor, independientemente de `notify-keyspace-events`. El observador se descarta
+    /// cuando se libera el `Receiver`.
+    pub fn watch_changes(&mut self) -> Receiver<KeyChange> {
+        let (sender, receiver) = mpsc::channel();
+        self.change_observers.push(sender);
+        receiver
+    }
+
+    /// Envía la modificación de la clave a los observadores registrados, descartando aquellos que
+    /// ya no la reciben. El valor sólo se copia si hay algún observador.
+    fn notify_change(&mut self, event: &str, key: &str) {
+        if self.change_observers.is_empty() {
+            return;
+        }
+        let change = KeyChange {
+            key: key.to_string(),
+            event: event.to_string(),
+            value: self.db.peek(&key.to_string()).map(|value| match value {
+                Re::Integer(integer) => Re::String(integer.to_string()),
+                value => value.clone(),
+            }),
+        };
+        self.change_observers
+            .retain(|observer| observer.send(change.clone()).is_ok());
     }
 
-    /// Permite desuscribirse a uno o mas canales
-    fn unsubscribe_method(&mut self, channels: Vec<String>, client_id: String) -> Response {
+    /// Permite desuscribirse a uno o mas canales. Si no se indica ninguno, desuscribe al cliente de
+    /// todos sus canales. Cada confirmación incluye la cantidad de suscripciones que le quedan.
+    fn unsubscribe_method(&mut self, channels: Vec<String>, client_id: u64) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -415,104 +1458,579 @@ impl Redis {
         ));
 
         let mut return_vec = Vec::new();
+        let mut remaining = self.subscription_count(client_id);
         if let Some(subscribed_channels) = self.client_channel.get_mut(&client_id) {
             let mut channels_to_keep = Vec::new();
             let mut channels_to_delete = Vec::new();
             for sub_channel in subscribed_channels {
-                if channels.iter().any(|i| *i == *sub_channel) {
+                if channels.is_empty() || channels.contains(sub_channel) {
+                    remaining -= 1;
                     channels_to_delete.push(sub_channel.to_string());
-                    return_vec.push("unsubscribe".to_string());
-                    return_vec.push(sub_channel.to_string());
-                    return_vec.push("0".to_string());
+                    return_vec.push(Re::String("unsubscribe".to_string()));
+                    return_vec.push(Re::String(sub_channel.to_string()));
+                    return_vec.push(Re::Integer(remaining as i64));
                 } else {
                     channels_to_keep.push(sub_channel.to_string());
                 }
             }
 
-            self.client_channel
-                .insert(client_id.clone(), channels_to_keep);
+            self.client_channel.insert(client_id, channels_to_keep);
 
             for channel in channels_to_delete {
                 if let Some(senders) = self.subscribers.get(&channel) {
-                    let mut vec_senders: Vec<(String, Sender<Re>)> = Vec::new();
+                    let mut vec_senders: Vec<(u64, MessageSender<Re>)> = Vec::new();
                     for (client, sender) in senders {
                         if client_id != *client {
-                            vec_senders.push((client.to_string(), sender.clone()));
+                            vec_senders.push((*client, sender.clone()));
                         }
                     }
-                    self.subscribers.insert(channel, vec_senders);
+                    if vec_senders.is_empty() {
+                        self.subscribers.remove(&channel);
+                    } else {
+                        self.subscribers.insert(channel, vec_senders);
+                    }
                 }
             }
 
-            return Response::Normal(Re::List(return_vec));
+            if !return_vec.is_empty() {
+                return Response::Normal(Re::Array(return_vec));
+            }
         }
 
-        Response::Normal(Re::List(vec![
-            "unsubscribe".to_string(),
-            "nil".to_string(),
-            "0".to_string(),
+        Response::Normal(Re::Array(vec![
+            Re::String("unsubscribe".to_string()),
+            Re::String("nil".to_string()),
+            Re::Integer(0),
         ]))
     }
 
-    /// Comando interno que es ejecutado cuando un cliente nuevo se conecta.
-    fn addclient_method(&mut self) -> Response {
-        self.users_connected += 1;
-        Response::Normal(RedisElement::String("OK".to_string()))
-    }
-
-    /// Comando interno que es ejecutado cuando un cliente nuevo se desconecta.
-    fn removeclient_method(&mut self) -> Response {
-        self.users_connected -= 1;
-        Response::Normal(RedisElement::String("OK".to_string()))
-    }
-
-    /// El comando INFO retorna información y estadísticas sobre el servidor en un formato facil de
-    /// parsear por computadores y facil de leer por humanos.
+    /// El comando CLIENT permite inspeccionar y modificar el estado de las conexiones de los
+    /// clientes.
     ///
     /// # Arguments
     ///
-    /// Los parametros que este puede recibir están definidos en el enum `InfoParam`, los cuales
-    /// pueden ser:
+    /// Los subcomandos que este puede recibir están definidos en el enum `ClientParam`:
     ///
-    /// * `ConnectedClients` - Indica la cantidad de Clientes connectados en nuestro servidor.
-    /// * `Port` - Indica el Puerto en el cual está levantado el servidor.
-    /// * `ConfigFile` - Indica el nombre del archivo de Configuración del servidor.
-    /// * `Uptime` - Indica el tiempo en el que el servidor está en funcionamiento.
-    /// * `ServerTime` - Indica la hora del servidor. (UTC-0).
-    /// * `ProcessID` - Indica el processID del proceso en el SO.
-    fn info_method(&mut self, param: InfoParam) -> Result<Response, String> {
+    /// * `Id` - Devuelve el id del cliente que envía el comando.
+    /// * `List` - Describe a todos los clientes conectados, uno por línea.
+    /// * `Info` - Describe al cliente que envía el comando.
+    /// * `SetName` - Asigna un nombre al cliente. No puede contener espacios.
+    /// * `GetName` - Devuelve el nombre del cliente, o nil si no tiene uno asignado.
+    /// * `KillAddr` - Cierra la conexión del cliente conectado desde la dirección indicada.
+    /// * `Kill` - Cierra las conexiones de los clientes que cumplen con los filtros, devolviendo
+    ///   cuántas se cerraron.
+    fn client_method(&mut self, param: ClientParam, client_id: u64) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command Info Received".to_string(),
+            "Command Client Received".to_string(),
         ));
 
+        let clients = Arc::clone(&self.connected_clients);
+        let mut clients = clients.lock().unwrap();
+
         match param {
-            InfoParam::ConnectedClients => Ok(Response::Normal(RedisElement::String(
-                self.users_connected.to_string(),
-            ))),
-            InfoParam::Port => Ok(Response::Normal(RedisElement::String(
-                self.config.lock().unwrap().get_port(),
-            ))),
-            InfoParam::ConfigFile => Ok(Response::Normal(RedisElement::String(
-                self.config.lock().unwrap().get_configfile(),
+            ClientParam::Id => Ok(Response::Normal(Re::Integer(client_id as i64))),
+            ClientParam::List => Ok(Response::Normal(Re::String(
+                clients
+                    .iter()
+                    .map(|client| self.describe_client(client))
+                    .collect(),
+            ))),
+            ClientParam::Info => Ok(Response::Normal(match clients.get(client_id) {
+                Some(client) => Re::String(self.describe_client(client)),
+                None => Re::Nil,
+            })),
+            ClientParam::SetName(name) => {
+                if name.chars().any(|c| c <= ' ' || c > '~') {
+                    return Err(
+                        "ERR Client names cannot contain spaces, newlines or special characters."
+                            .to_string(),
+                    );
+                }
+                clients.set_name(client_id, name);
+                Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+            }
+            ClientParam::GetName => Ok(Response::Normal(match clients.get(client_id) {
+                Some(client) if !client.name.is_empty() => Re::String(client.name.clone()),
+                _ => Re::Nil,
+            })),
+            ClientParam::KillAddr(addr) => {
+                let target = clients
+                    .iter()
+                    .find(|client| client.addr == addr)
+                    .map(|client| client.id);
+                match target {
+                    Some(target) => {
+                        clients.kill(target);
+                        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+                    }
+                    None => Err("ERR No such client".to_string()),
+                }
+            }
+            ClientParam::Kill(filter) => {
+                let targets: Vec<u64> = clients
+                    .iter()
+                    .filter(|client| filter.id.is_none_or(|target| client.id == target))
+                    .filter(|client| filter.addr.as_ref().is_none_or(|addr| client.addr == *addr))
+                    .filter(|client| !filter.skipme || client.id != client_id)
+                    .map(|client| client.id)
+                    .collect();
+                targets.iter().for_each(|target| {
+                    clients.kill(*target);
+                });
+                Ok(Response::Normal(Re::Integer(targets.len() as i64)))
+            }
+        }
+    }
+
+    /// El comando CLUSTER permite inspeccionar el reparto de los hash slots entre los nodos del
+    /// cluster. Devuelve error si el servidor no se ejecuta en modo cluster.
+    ///
+    /// # Arguments
+    ///
+    /// Los subcomandos que este puede recibir están definidos en el enum `ClusterParam`:
+    ///
+    /// * `KeySlot` - Devuelve el hash slot de la clave.
+    /// * `Slots` - Devuelve, por cada nodo, el primer y último slot asignados y su dirección.
+    /// * `Info` - Describe el estado del cluster.
+    fn cluster_method(&mut self, param: ClusterParam) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command Cluster Received".to_string(),
+        ));
+
+        let cluster = match self.config.lock().unwrap().get_cluster() {
+            Some(cluster) => cluster,
+            None => return Err("ERR This instance has cluster support disabled".to_string()),
+        };
+
+        match param {
+            ClusterParam::KeySlot(key) => Ok(Response::Normal(Re::Integer(
+                cluster::key_slot(&key) as i64,
+            ))),
+            ClusterParam::Slots => Ok(Response::Normal(cluster.slots())),
+            ClusterParam::Info => Ok(Response::Normal(Re::String(cluster.info()))),
+        }
+    }
+
+    /// El comando COMMAND permite inspeccionar los comandos que soporta el servidor.
+    ///
+    /// # Arguments
+    ///
+    /// Los subcomandos que este puede recibir están definidos en el enum `CommandParam`:
+    ///
+    /// * `All` - Describe todos los comandos, con el mismo formato que `Info`.
+    /// * `Count` - Devuelve la cantidad de comandos.
+    /// * `Info` - Describe cada comando con su nombre, aridad, flags, primera clave, última clave y
+    ///   distancia entre claves. Los comandos inexistentes se describen como nil.
+    /// * `Docs` - Devuelve el nombre de cada comando seguido de su documentación. Los comandos
+    ///   inexistentes se omiten.
+    fn command_method(&mut self, param: CommandParam) -> Re {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command COMMAND Received".to_string(),
+        ));
+
+        match param {
+            CommandParam::All => Re::Array(COMMAND_TABLE.iter().map(CommandSpec::info).collect()),
+            CommandParam::Count => Re::Integer(COMMAND_TABLE.len() as i64),
+            CommandParam::Info(names) if names.is_empty() => self.command_method(CommandParam::All),
+            CommandParam::Info(names) => Re::Array(
+                names
+                    .iter()
+                    .map(|name| command_table::lookup(name).map_or(Re::Nil, CommandSpec::info))
+                    .collect(),
+            ),
+            CommandParam::Docs(names) => {
+                let specs: Vec<&CommandSpec> = if names.is_empty() {
+                    COMMAND_TABLE.iter().collect()
+                } else {
+                    names
+                        .iter()
+                        .filter_map(|name| command_table::lookup(name))
+                        .collect()
+                };
+                Re::Array(specs.into_iter().map(CommandSpec::docs).collect())
+            }
+        }
+    }
+
+    /// El comando MEMORY permite inspeccionar el uso de memoria del servidor.
+    ///
+    /// # Arguments
+    ///
+    /// Los subcomandos que este puede recibir están definidos en el enum `MemoryParam`:
+    ///
+    /// * `Usage` - Estima los bytes que ocupa la clave junto con su valor, o devuelve nil si la
+    ///   clave no existe. En las colecciones, el tamaño se extrapola a partir de una muestra de
+    ///   sus elementos.
+    fn memory_method(&mut self, param: MemoryParam) -> Re {
+        match param {
+            MemoryParam::Usage { key, samples } => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command MEMORY USAGE Received - key: ".to_string() + &*key,
+                ));
+
+                match self.db.peek(&key) {
+                    Some(value) => Re::Integer(memory::memory_usage(&key, value, samples) as i64),
+                    None => Re::Nil,
+                }
+            }
+        }
+    }
+
+    /// Consulta o descarta los comandos registrados en el slowlog.
+    fn slowlog_method(&mut self, param: SlowlogParam) -> Re {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SLOWLOG Received".to_string(),
+        ));
+
+        match param {
+            SlowlogParam::Get { count } => Re::Array(self.slowlog.get(count)),
+            SlowlogParam::Len => Re::Integer(self.slowlog.len() as i64),
+            SlowlogParam::Reset => {
+                self.slowlog.reset();
+                Re::SimpleString("OK".to_string())
+            }
+        }
+    }
+
+    /// Ejecuta los subcomandos de DEBUG, pensados para pruebas: SLEEP bloquea la base de datos,
+    /// SET-ACTIVE-EXPIRE habilita o deshabilita la expiración activa y OBJECT muestra los
+    /// metadatos internos de una clave.
+    fn debug_method(&mut self, param: DebugParam) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!("Command DEBUG Received - {:?}", param),
+        ));
+
+        match param {
+            DebugParam::Sleep { duration } => thread::sleep(duration),
+            DebugParam::SetActiveExpire { enabled } => self.active_expire = enabled,
+            DebugParam::Object { key } => {
+                return match self.debug_object(&key) {
+                    Some(description) => Ok(Response::Normal(Re::SimpleString(description))),
+                    None => Err("ERR no such key".to_string()),
+                }
+            }
+        }
+        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+    }
+
+    /// Describe los metadatos internos de la clave con el formato de DEBUG OBJECT de Redis, sin
+    /// registrar un acceso. Devuelve None si la clave no existe.
+    fn debug_object(&mut self, key: &str) -> Option<String> {
+        let key = key.to_string();
+        let (address, encoding, serialized_length) = {
+            let value = self.db.peek(&key)?;
+            let mut serialized = Vec::new();
+            rdb::write_value(&mut serialized, value);
+            (
+                value as *const RedisElement as usize,
+                memory::encoding(value),
+                serialized.len(),
+            )
+        };
+        let idle = self
+            .db
+            .idle_time(&key)
+            .unwrap_or_else(|| Duration::from_secs(0));
+        let now = self
+            .clock
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        let lru = now.saturating_sub(idle).as_secs() & LRU_CLOCK_MAX;
+
+        Some(format!(
+            "Value at:{:#x} refcount:1 encoding:{} serializedlength:{} lru:{} lru_seconds_idle:{}",
+            address,
+            encoding,
+            serialized_length,
+            lru,
+            idle.as_secs()
+        ))
+    }
+
+    /// Compila el script, lo guarda en el cache y lo ejecuta con las claves y argumentos indicados.
+    fn eval_method(
+        &mut self,
+        script: String,
+        keys: Vec<String>,
+        args: Vec<String>,
+    ) -> Result<Response, String> {
+        let sha1 = self.load_script(&script)?;
+        self.run_script(&sha1, keys, args)
+    }
+
+    /// Ejecuta un script guardado en el cache, identificado por su SHA1.
+    fn evalsha_method(
+        &mut self,
+        sha1: String,
+        keys: Vec<String>,
+        args: Vec<String>,
+    ) -> Result<Response, String> {
+        if !self.scripts.contains_key(&sha1) {
+            return Err("NOSCRIPT No matching script. Please use EVAL.".to_string());
+        }
+        self.run_script(&sha1, keys, args)
+    }
+
+    fn script_method(&mut self, param: ScriptParam) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SCRIPT Received".to_string(),
+        ));
+
+        match param {
+            ScriptParam::Load { script } => {
+                Ok(Response::Normal(Re::String(self.load_script(&script)?)))
+            }
+            ScriptParam::Exists { sha1s } => Ok(Response::Normal(Re::Array(
+                sha1s
+                    .iter()
+                    .map(|sha1| Re::Integer(self.scripts.contains_key(sha1) as i64))
+                    .collect(),
+            ))),
+            ScriptParam::Flush => {
+                self.scripts.clear();
+                Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+            }
+        }
+    }
+
+    /// Compila el script y lo guarda en el cache, si no estaba. Retorna su SHA1.
+    fn load_script(&mut self, source: &str) -> Result<String, String> {
+        let sha1 = sha1_hex(source.as_bytes());
+        if !self.scripts.contains_key(&sha1) {
+            let script = Script::compile(source).map_err(|e| script_error(&sha1, e))?;
+            self.scripts.insert(sha1.clone(), Arc::new(script));
+        }
+        Ok(sha1)
+    }
+
+    /// Ejecuta el script del cache. Como la base de datos queda tomada mientras corre, los
+    /// comandos que ejecuta con `redis.call` se aplican de forma atómica.
+    fn run_script(
+        &mut self,
+        sha1: &str,
+        keys: Vec<String>,
+        args: Vec<String>,
+    ) -> Result<Response, String> {
+        let script = match self.scripts.get(sha1) {
+            Some(script) => Arc::clone(script),
+            None => return Err("NOSCRIPT No matching script. Please use EVAL.".to_string()),
+        };
+        let mut call = |command: Vec<String>| self.script_call(command);
+        match script.run(&keys, &args, &mut call) {
+            Ok(element) => Ok(Response::Normal(element)),
+            Err(e) => Err(script_error(sha1, e)),
+        }
+    }
+
+    /// Ejecuta un comando llamado desde un script con `redis.call` o `redis.pcall`. No se
+    /// permiten los comandos marcados como `noscript`, como los bloqueantes o los de pub/sub.
+    fn script_call(&mut self, command: Vec<String>) -> Result<Re, String> {
+        let spec = match command_table::lookup(&command[0]) {
+            Some(spec) => spec,
+            None => return Err("ERR Unknown Redis command called from script".to_string()),
+        };
+        if spec.flags.contains(&"noscript") {
+            return Err("ERR This Redis command is not allowed from script".to_string());
+        }
+        if !spec.accepts(command.len()) {
+            return Err("ERR Wrong number of args calling Redis command from script".to_string());
+        }
+
+        let parsed = generate(command.clone(), 0)?;
+        match self.execute_from_client(parsed, None, &command)? {
+            Response::Normal(element) => Ok(element),
+            Response::Error(msg) => Err(msg),
+            _ => Err("ERR This Redis command is not allowed from script".to_string()),
+        }
+    }
+
+    /// Quita al cliente de los suscriptores de canales y de los clientes en MONITOR, descartando
+    /// los canales que quedan sin suscriptores.
+    fn remove_client_registrations(&mut self, client_id: u64) {
+        for subscribers in self.subscribers.values_mut() {
+            subscribers.retain(|(id, _)| *id != client_id);
+        }
+        self.subscribers
+            .retain(|_, subscribers| !subscribers.is_empty());
+        self.client_channel.remove(&client_id);
+        self.monitor_subs_vec.retain(|(id, _)| *id != client_id);
+    }
+
+    /// Describe al cliente con el formato de CLIENT LIST, incluyendo la cantidad de canales y
+    /// patrones a los que está subscripto. Como no se soporta PSUBSCRIBE, no hay patrones.
+    fn describe_client(&self, client: &ClientInfo) -> String {
+        let sub = self.subscription_count(client.id);
+        client.describe(sub, 0) + "\n"
+    }
+
+    /// Comando interno que es ejecutado cuando un cliente nuevo se conecta.
+    fn addclient_method(&mut self) -> Response {
+        self.users_connected += 1;
+        Response::Normal(RedisElement::String("OK".to_string()))
+    }
+
+    /// Comando interno que es ejecutado cuando un cliente nuevo se desconecta. Se quitan los
+    /// registros del cliente en los canales, patrones y MONITOR, sin esperar a que falle el envío
+    /// de un mensaje.
+    fn removeclient_method(&mut self, client_id: u64) -> Response {
+        self.users_connected -= 1;
+        self.remove_client_registrations(client_id);
+        Response::Normal(RedisElement::String("OK".to_string()))
+    }
+
+    /// El comando INFO retorna información y estadísticas sobre el servidor en un formato facil de
+    /// parsear por computadores y facil de leer por humanos.
+    ///
+    /// # Arguments
+    ///
+    /// Los parametros que este puede recibir están definidos en el enum `InfoParam`, los cuales
+    /// pueden ser:
+    ///
+    /// * `ConnectedClients` - Indica la cantidad de Clientes connectados en nuestro servidor.
+    /// * `Port` - Indica el Puerto en el cual está levantado el servidor.
+    /// * `ConfigFile` - Indica el nombre del archivo de Configuración del servidor.
+    /// * `Uptime` - Indica el tiempo en el que el servidor está en funcionamiento.
+    /// * `ServerTime` - Indica la hora del servidor. (UTC-0).
+    /// * `ProcessID` - Indica el processID del proceso en el SO.
+    fn info_method(&mut self, param: InfoParam) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command Info Received".to_string(),
+        ));
+
+        match param {
+            InfoParam::ConnectedClients => Ok(Response::Normal(RedisElement::Integer(
+                self.users_connected as i64,
+            ))),
+            InfoParam::Port => Ok(Response::Normal(RedisElement::String(
+                self.config.lock().unwrap().get_port(),
+            ))),
+            InfoParam::ConfigFile => Ok(Response::Normal(RedisElement::String(
+                self.config.lock().unwrap().get_configfile(),
             ))),
             InfoParam::Uptime => self.get_server_uptime(),
             InfoParam::ServerTime => Ok(Response::Normal(Re::String(timestamp_to_string(
-                SystemTime::now(),
+                self.clock.now(),
             )))),
-            InfoParam::ProcessId => Ok(Response::Normal(Re::String(process::id().to_string()))),
+            InfoParam::ProcessId => Ok(Response::Normal(Re::Integer(process::id() as i64))),
+            InfoParam::Replication => Ok(Response::Normal(Re::String(self.replication_info()))),
+            InfoParam::Commandstats => Ok(Response::Normal(Re::String(self.command_stats.info()))),
+            InfoParam::Stats => Ok(Response::Normal(Re::String(self.stats_info()))),
+            InfoParam::Memory => Ok(Response::Normal(Re::String(self.memory_info()))),
         }
     }
 
+    /// Devuelve la sección Replication de INFO. El servidor no soporta replicación, por lo que
+    /// siempre es un master sin réplicas conectadas y su offset de replicación es 0.
+    fn replication_info(&self) -> String {
+        [
+            "# Replication".to_string(),
+            "role:master".to_string(),
+            "connected_slaves:0".to_string(),
+            format!("master_replid:{}", self.replication_id),
+            format!("master_replid2:{}", "0".repeat(REPLICATION_ID_LEN)),
+            "master_repl_offset:0".to_string(),
+            "second_repl_offset:-1".to_string(),
+            "repl_backlog_active:0".to_string(),
+        ]
+        .join("\r\n")
+            + "\r\n"
+    }
+
+    /// Estima la memoria usada por los datos, sumando lo que estima MEMORY USAGE para cada clave.
+    /// Recorre todas las claves, por lo que sólo se usa para informarla en INFO; al ejecutar
+    /// comandos se usa la cuenta que lleva la base de datos.
+    fn used_memory(&self) -> usize {
+        let mut used_memory = 0;
+        self.db.for_each_valid(|key, value| {
+            used_memory += memory::memory_usage(key, value, memory::DEFAULT_SAMPLES);
+        });
+        used_memory
+    }
+
+    /// Devuelve la sección Memory de INFO. Como no se lleva la cuenta de la memoria reservada,
+    /// `used_memory` es la suma de lo que estima MEMORY USAGE para cada clave.
+    fn memory_info(&self) -> String {
+        let used_memory = self.used_memory();
+        let (maxmemory, policy) = {
+            let config = self.config.lock().unwrap();
+            (config.get_maxmemory(), config.get_maxmemory_policy())
+        };
+        [
+            "# Memory".to_string(),
+            format!("used_memory:{}", used_memory),
+            format!(
+                "used_memory_human:{}",
+                memory::human_bytes(used_memory as u64)
+            ),
+            format!("maxmemory:{}", maxmemory),
+            format!("maxmemory_human:{}", memory::human_bytes(maxmemory)),
+            format!("maxmemory_policy:{}", policy),
+            format!("number_of_cached_scripts:{}", self.scripts.len()),
+        ]
+        .join("\r\n")
+            + "\r\n"
+    }
+
+    /// Devuelve la sección Stats de INFO, con los comandos procesados, el tráfico de red, las
+    /// claves expiradas, los aciertos y fallos en la búsqueda de claves de los comandos de lectura y los mensajes y
+    /// suscriptores descartados por no leer a tiempo.
+    fn stats_info(&self) -> String {
+        let mut lines = vec!["# Stats".to_string()];
+        lines.extend(self.server_stats.lock().unwrap().info());
+        lines.push(format!("expired_keys:{}", self.expired_keys));
+        lines.push(format!("evicted_keys:{}", self.evicted_keys));
+        lines.push(format!("keyspace_hits:{}", self.keyspace_hits));
+        lines.push(format!("keyspace_misses:{}", self.keyspace_misses));
+        lines.push(format!(
+            "pubsub_dropped_messages:{}",
+            self.pubsub_dropped_messages
+        ));
+        lines.push(format!(
+            "pubsub_slow_disconnections:{}",
+            self.pubsub_disconnections
+        ));
+        lines.join("\r\n") + "\r\n"
+    }
+
     /// Indica el tiempo en el que el servidor está en funcionamiento.
     fn get_server_uptime(&mut self) -> Result<Response, String> {
-        let result_time = SystemTime::now().duration_since(self.server_time);
+        let result_time = self.clock.now().duration_since(self.server_time);
         match result_time {
-            Ok(duration) => Ok(Response::Normal(RedisElement::String(
-                duration.as_secs().to_string(),
+            Ok(duration) => Ok(Response::Normal(RedisElement::Integer(
+                duration.as_secs() as i64
             ))),
             Err(e) => {
                 let _ = self.log_sender.send(Log::new(
@@ -527,13 +2045,15 @@ impl Redis {
         }
     }
 
-    /// Indica cuantos datos están guardados en la DB.
+    /// Indica cuantos datos están guardados en la DB. Antes de contarlos elimina las claves que
+    /// expiraron y todavía no fueron accedidas.
     fn dbsize_method(&mut self) -> Response {
-        Response::Normal(Re::String(self.db.len().to_string()))
+        Response::Normal(Re::Integer(self.db.keys_purged().count() as i64))
     }
 
     /// Methodo para chequear si la DB responde. En caso que responda se retorna PONG.
-    fn ping_method(&mut self) -> Response {
+    /// Si se indica un mensaje, se retorna el mismo.
+    fn ping_method(&mut self, message: Option<String>) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -542,28 +2062,83 @@ impl Redis {
             "Command PING Received".to_string(),
         ));
 
-        Response::Normal(Re::SimpleString("PONG".to_string()))
+        match message {
+            Some(message) => Response::Normal(Re::String(message)),
+            None => Response::Normal(Re::SimpleString("PONG".to_string())),
+        }
+    }
+
+    /// Retorna el mensaje recibido.
+    fn echo_method(&mut self, message: String) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ECHO Received".to_string(),
+        ));
+
+        Response::Normal(Re::String(message))
+    }
+
+    /// Indica si hay clientes en MONITOR, en cuyo caso se deben indicar los argumentos recibidos
+    /// al ejecutar los comandos.
+    pub fn is_monitored(&self) -> bool {
+        !self.monitor_subs_vec.is_empty()
     }
 
     /// Metodo utilizado para notificar a los subscriptores de Monitor los nuevos comandos que van a
-    /// ser ejecutados.
-    fn notify_monitor(&mut self, command: &Command) {
-        let command_str = command.as_str().to_string();
-        if !command_str.is_empty() {
-            let mut empty_vec: Vec<Sender<Re>> = Vec::new();
-            for sender in &self.monitor_subs_vec {
-                if sender.send(Re::String(command_str.to_string())).is_ok() {
-                    empty_vec.push(sender.clone());
-                }
+    /// ser ejecutados, con el mismo formato que Redis.
+    ///
+    /// Los comandos internos y AUTH, cuyo argumento es la contraseña, no se informan. La nueva
+    /// contraseña de CONFIG SET requirepass se oculta.
+    fn notify_monitor(&mut self, command: &Command, client_id: Option<u64>, args: &[String]) {
+        let name = command.as_str();
+        if self.monitor_subs_vec.is_empty()
+            || name.is_empty()
+            || matches!(command, Command::Auth { .. })
+        {
+            return;
+        }
+
+        let args = match args {
+            [] => vec![name.to_string()],
+            args => Redis::redacted_args(args, Redis::secrets_from(command)),
+        };
+        let addr = self.client_addr(client_id);
+        let line = monitor_line(self.clock.now(), 0, &addr, &args);
+        self.monitor_subs_vec
+            .retain(|(_, sender)| sender.send(Re::SimpleString(line.clone())).is_ok());
+    }
+
+    /// Devuelve la posición del primer argumento del comando que es una contraseña (en AUTH y
+    /// CONFIG SET requirepass), o None si el comando no recibe contraseñas.
+    fn secrets_from(command: &Command) -> Option<usize> {
+        match command {
+            Command::Auth { .. } => Some(1),
+            Command::ConfigSet { parameter, .. }
+                if parameter.eq_ignore_ascii_case("requirepass") =>
+            {
+                Some(3)
             }
+            _ => None,
+        }
+    }
 
-            self.monitor_subs_vec = empty_vec;
+    /// Devuelve los argumentos reemplazando las contraseñas, a partir de `secrets_from`, por
+    /// "(redacted)", como hace Redis.
+    fn redacted_args(args: &[String], secrets_from: Option<usize>) -> Vec<String> {
+        let secrets_from = secrets_from.unwrap_or(args.len());
+        let mut args = args.to_vec();
+        for arg in args.iter_mut().skip(secrets_from) {
+            *arg = "(redacted)".to_string();
         }
+        args
     }
 
     /// Es un comando de depuración que envía al cliente cada comando procesado por el servidor.
     /// Puede ayudar entender qúe está sucediendo en la base de datos.
-    fn monitor_method(&mut self) -> Result<Response, String> {
+    fn monitor_method(&mut self, client_id: u64) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -572,14 +2147,15 @@ impl Redis {
             "Command MONITOR Received".to_string(),
         ));
 
-        let (sen, rec): (Sender<Re>, Receiver<Re>) = mpsc::channel();
+        // Los comandos informados por MONITOR no se descartan.
+        let (sen, rec): (MessageSender<Re>, MessageReceiver<Re>) = message_queue(0);
 
         let sen_clone = sen.clone();
 
         let result = sen_clone.send(Re::SimpleString("OK".to_string()));
         match result {
             Ok(_) => {
-                self.monitor_subs_vec.push(sen);
+                self.monitor_subs_vec.push((client_id, sen));
                 Ok(Response::Stream(rec))
             }
             Err(e) => {
@@ -596,7 +2172,8 @@ impl Redis {
     }
 
     /// Borra todas las claves de la base de datos. Este comando nunca falla.
-    fn flushdb_method(&mut self) -> Response {
+    /// Si `asynchronous` es true, la base de datos anterior se libera en segundo plano.
+    fn flushdb_method(&mut self, asynchronous: bool) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -605,10 +2182,37 @@ impl Redis {
             "Command FLUSHDB Received".to_string(),
         ));
 
-        self.db = TtlHashMap::new();
+        self.dirty += self.db.len() as u64;
+        let mut empty = Keyspace::with_clock(Arc::clone(&self.clock));
+        empty.set_access_tracking(self.db.tracks_access());
+        let db = std::mem::replace(&mut self.db, empty);
+        if !self.change_observers.is_empty() {
+            let mut keys = Vec::new();
+            db.for_each_valid(|key, _| keys.push(key.clone()));
+            for key in keys {
+                self.notify_change("flushdb", &key);
+            }
+        }
+        if asynchronous {
+            self.lazy_free.free(db);
+        }
         Response::Normal(Re::SimpleString("OK".to_string()))
     }
 
+    /// Borra las claves de todas las bases de datos. Como el servidor tiene una única base de
+    /// datos, es equivalente a FLUSHDB.
+    fn flushall_method(&mut self, asynchronous: bool) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command FLUSHALL Received".to_string(),
+        ));
+
+        self.flushdb_method(asynchronous)
+    }
+
     #[allow(dead_code)]
     /// Copia el valor almacenado en una clave origen a una clave destino.
     fn copy_method(&mut self, key_origin: String, key_destination: String) -> Response {
@@ -623,21 +2227,38 @@ impl Redis {
                 + &*key_destination,
         ));
 
-        let value_origin;
-        match self.db.get(&key_origin) {
-            Some(value) => value_origin = value.clone(),
-            None => return Response::Normal(Re::String("0".to_string())),
+        let value_origin = match self.db.get(&key_origin) {
+            Some(value) => value.clone(),
+            None => return Response::Normal(Re::Integer(0)),
         };
 
         match self.db.get(&key_destination) {
-            Some(_) => Response::Normal(Re::String("0".to_string())),
+            Some(_) => Response::Normal(Re::Integer(0)),
             None => {
-                self.db.insert(key_destination, value_origin);
-                Response::Normal(Re::String("1".to_string()))
+                self.db.insert(key_destination.clone(), value_origin);
+                self.notify_keyspace_event(EventClass::Generic, "copy_to", &key_destination);
+                Response::Normal(Re::Integer(1))
             }
         }
     }
 
+    /// Devuelve el valor de la clave serializado con el formato de Redis, que puede cargarse en otra
+    /// clave o en otro servidor con RESTORE. Si la clave no existe, se retorna nil.
+    fn dump_method(&mut self, key: String) -> Re {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command DUMP Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key).and_then(rdb::dump_value) {
+            Some(payload) => Re::String(payload),
+            None => Re::Nil,
+        }
+    }
+
     #[allow(dead_code)]
     /// Devuelve el valor de una clave, si la clave no existe, se retorna el valor especial nil.
     ///
@@ -652,9 +2273,9 @@ impl Redis {
             "Command GET Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.get(&key) {
-            Some(return_value) => match return_value {
-                Re::String(s) => Ok(Re::String(s.to_string())),
+        match self.lookup_read(&key) {
+            Some(return_value) => match return_value.string_reply() {
+                Some(reply) => Ok(reply),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -670,6 +2291,18 @@ impl Redis {
         }
     }
 
+    /// Busca el valor de la clave para un comando de lectura, registrando el acierto o el fallo en
+    /// las estadísticas keyspace_hits y keyspace_misses de INFO stats.
+    fn lookup_read(&mut self, key: &str) -> Option<&Re> {
+        let value = self.db.get(&key.to_string());
+        if value.is_some() {
+            self.keyspace_hits += 1;
+        } else {
+            self.keyspace_misses += 1;
+        }
+        value
+    }
+
     /// Retorna el largo del valor de tipo string almacenado en una clave. Retorna error si la clave
     /// no almacena un string.
     fn strlen_method(&mut self, key: String) -> Result<Response, String> {
@@ -681,9 +2314,9 @@ impl Redis {
             "Command STRLEN Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.get(&key) {
-            Some(return_value) => match return_value {
-                Re::String(s) => Ok(Response::Normal(Re::String(s.len().to_string()))),
+        match self.lookup_read(&key) {
+            Some(return_value) => match return_value.as_bytes() {
+                Some(bytes) => Ok(Response::Normal(Re::Integer(bytes.len() as i64))),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -695,32 +2328,210 @@ impl Redis {
                     Err(WRONGTYPE_MSG.to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Re::Integer(0))),
         }
     }
 
-    #[allow(dead_code)]
-    /// Atómicamente setea el valor a la clave deseada, y retorna el valor anterior almacenado en la
-    /// clave.
-    fn getset_method(&mut self, key: String, value: String) -> Result<Response, String> {
+    /// Setea o limpia el bit en la posición indicada del string almacenado en la clave, y retorna
+    /// el valor previo del bit.
+    ///
+    /// Si la clave no existe se crea un string nuevo, que se agranda con ceros si es necesario.
+    fn setbit_method(
+        &mut self,
+        key: String,
+        offset: usize,
+        value: bool,
+    ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command GETSET Received - key: ".to_string() + &*key,
+            "Command SETBIT Received - key: ".to_string() + &*key,
         ));
 
-        match self.get_method(key.clone()) {
-            Ok(return_value) => {
-                self.set_method(key, value);
-                Ok(Response::Normal(return_value))
-            }
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
+        // Se modifica el valor en el lugar para conservar la expiración de la clave. El bitmap se
+        // guarda como `Bytes`, por lo que sólo se copia el valor la primera vez que se modifica un
+        // string de texto.
+        let previous = match self.db.get_mut(&key) {
+            Some(Re::Bytes(bytes)) => bitmap::set_bit(bytes, offset, value),
+            Some(stored) => match stored.as_bytes() {
+                Some(bytes) => {
+                    let mut bytes = bytes.into_owned();
+                    let previous = bitmap::set_bit(&mut bytes, offset, value);
+                    *stored = Re::Bytes(bytes);
+                    previous
+                }
+                None => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        WRONGTYPE_MSG.to_string(),
+                    ));
+                    return Err(WRONGTYPE_MSG.to_string());
+                }
+            },
+            None => {
+                let mut bytes = vec![];
+                let previous = bitmap::set_bit(&mut bytes, offset, value);
+                self.db.insert(key.clone(), Re::Bytes(bytes));
+                previous
+            }
+        };
+        self.notify_keyspace_event(EventClass::String, "setbit", &key);
+
+        Ok(Response::Normal(Re::Integer(previous as i64)))
+    }
+
+    /// Retorna el valor del bit en la posición indicada del string almacenado en la clave.
+    fn getbit_method(&mut self, key: String, offset: usize) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command GETBIT Received - key: ".to_string() + &*key,
+        ));
+
+        let bytes = self.get_bitmap(&key)?;
+        let bit = bitmap::get_bit(&bytes, offset);
+
+        Ok(Response::Normal(Re::Integer(bit as i64)))
+    }
+
+    /// Retorna la cantidad de bits en 1 del string almacenado en la clave, opcionalmente dentro del
+    /// rango indicado.
+    fn bitcount_method(
+        &mut self,
+        key: String,
+        range: Option<BitRange>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command BITCOUNT Received - key: ".to_string() + &*key,
+        ));
+
+        let bytes = self.get_bitmap(&key)?;
+
+        Ok(Response::Normal(Re::Integer(
+            bitmap::count(&bytes, range) as i64
+        )))
+    }
+
+    /// Retorna la posición del primer bit con el valor indicado del string almacenado en la clave,
+    /// dentro del rango indicado. Retorna -1 si no se encuentra.
+    fn bitpos_method(
+        &mut self,
+        key: String,
+        bit: bool,
+        start: i64,
+        end: Option<i64>,
+        unit: BitUnit,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command BITPOS Received - key: ".to_string() + &*key,
+        ));
+
+        let bytes = self.get_bitmap(&key)?;
+
+        Ok(Response::Normal(Re::Integer(bitmap::position(
+            &bytes, bit, start, end, unit,
+        ))))
+    }
+
+    /// Realiza la operación de bits indicada entre los strings de las claves, y almacena el
+    /// resultado en la clave destino. Retorna el largo del string resultante.
+    ///
+    /// Las claves inexistentes se consideran strings vacíos. Si el resultado es vacío, la clave
+    /// destino se elimina.
+    fn bitop_method(
+        &mut self,
+        operation: BitOperation,
+        destkey: String,
+        keys: Vec<String>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command BITOP Received - destkey: ".to_string() + &*destkey,
+        ));
+
+        let mut sources = vec![];
+        for key in keys.iter() {
+            sources.push(self.get_bitmap(key)?.into_owned());
+        }
+
+        let result = bitmap::operate(operation, &sources);
+        let len = result.len();
+        if result.is_empty() {
+            if self.db.delete(&destkey) {
+                self.notify_keyspace_event(EventClass::Generic, "del", &destkey);
+            }
+        } else {
+            self.db.insert(destkey.clone(), Re::Bytes(result));
+            self.notify_keyspace_event(EventClass::String, "set", &destkey);
+        }
+
+        Ok(Response::Normal(Re::Integer(len as i64)))
+    }
+
+    /// Obtiene los bytes del string almacenado en la clave, sin copiarlos. Si la clave no existe,
+    /// retorna un bitmap vacío.
+    fn get_bitmap(&mut self, key: &str) -> Result<Cow<'_, [u8]>, String> {
+        if let Some(false) = self
+            .lookup_read(key)
+            .map(|value| value.as_bytes().is_some())
+        {
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Error,
+                line!(),
+                column!(),
+                file!().to_string(),
+                WRONGTYPE_MSG.to_string(),
+            ));
+            return Err(WRONGTYPE_MSG.to_string());
+        }
+        Ok(self
+            .db
+            .peek(&key.to_string())
+            .and_then(Re::as_bytes)
+            .unwrap_or_default())
+    }
+
+    #[allow(dead_code)]
+    /// Atómicamente setea el valor a la clave deseada, y retorna el valor anterior almacenado en la
+    /// clave.
+    fn getset_method(&mut self, key: String, value: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command GETSET Received - key: ".to_string() + &*key,
+        ));
+
+        match self.get_method(key.clone()) {
+            Ok(return_value) => {
+                self.set_method(key.clone(), value);
+                self.notify_keyspace_event(EventClass::String, "set", &key);
+                Ok(Response::Normal(return_value))
+            }
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
                     file!().to_string(),
                     e.clone(),
                 ));
@@ -743,17 +2554,76 @@ impl Redis {
             "Command SET Received - key: ".to_string() + &*key,
         ));
 
-        self.db.insert(key, Re::String(value));
+        self.db.insert(key, Re::from_string(value));
 
         "OK".to_string()
     }
 
+    /// Setea el valor de la clave como SET, respetando las opciones indicadas.
+    ///
+    /// Con NX solo se escribe el valor si la clave no existe, y con XX solo si la clave ya existe.
+    /// Si no se cumple la condición se retorna nil.
+    ///
+    /// La expiración indicada se aplica junto con el valor. Con KEEPTTL se conserva la expiración
+    /// previa de la clave en lugar de descartarla.
+    ///
+    /// Con GET se retorna el valor previo de la clave (nil si no existía) en lugar de OK, se haya
+    /// escrito o no el valor. Si el valor previo no es un string se retorna error sin escribirlo.
+    fn set_with_options_method(
+        &mut self,
+        key: String,
+        value: String,
+        options: SetOptions,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SET Received - key: ".to_string() + &*key,
+        ));
+
+        let (reply, written) = set_in_shard(self.db.shard_mut(&key), key.clone(), value, &options)?;
+        if written {
+            self.notify_keyspace_event(EventClass::String, "set", &key);
+        } else {
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Debug,
+                line!(),
+                column!(),
+                file!().to_string(),
+                "Command SET skipped by NX/XX condition - key: ".to_string() + &*key,
+            ));
+        }
+        Ok(Response::Normal(reply))
+    }
+
+    /// Setea el valor de la clave solamente si la clave no existe. Retorna 1 si se seteó el valor y
+    /// 0 en caso contrario.
+    fn setnx_method(&mut self, key: String, value: String) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SETNX Received - key: ".to_string() + &*key,
+        ));
+
+        if self.db.contains_key(&key) {
+            return Response::Normal(Re::Integer(0));
+        }
+
+        self.set_method(key.clone(), value);
+        self.notify_keyspace_event(EventClass::String, "set", &key);
+        Response::Normal(Re::Integer(1))
+    }
+
     #[allow(dead_code)]
-    /// Incrementa el número almacenado en la clave en un incremento.
+    /// Incrementa el número almacenado en la clave en un incremento, y retorna el nuevo valor.
     ///
     /// Si la clave no existe, es seteado a 0 antes de realizar la operación. Devuelve error si la clave contiene un valor de
-    /// tipo erróneo o un string que no puede ser representado como entero.
-    fn incrby_method(&mut self, key: String, increment: i32) -> Result<Response, String> {
+    /// tipo erróneo, un string que no puede ser representado como entero de 64 bits, o si la operación produce overflow.
+    fn incrby_method(&mut self, key: String, increment: i64) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -762,45 +2632,70 @@ impl Redis {
             "Command INCRBY Received - key: ".to_string() + &*key,
         ));
 
-        match self.get_method(key.clone()) {
-            Ok(return_value) => match return_value {
-                Re::String(value) => {
-                    let my_int: Result<i32, _> = value.parse();
-                    if my_int.is_err() {
-                        let _ = self.log_sender.send(Log::new(
-                            LogLevel::Error,
-                            line!(),
-                            column!(),
-                            file!().to_string(),
-                            OUT_OF_RANGE_MSG.to_string(),
-                        ));
+        let result =
+            incrby_in_shard(self.db.shard_mut(&key), &key, increment).inspect_err(|e| {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    e.to_string(),
+                ));
+            })?;
+        self.notify_keyspace_event(EventClass::String, "incrby", &key);
 
-                        return Err(OUT_OF_RANGE_MSG.to_string());
-                    }
+        Ok(Response::Normal(Re::Integer(result)))
+    }
 
-                    let my_int = my_int.unwrap() + increment;
-                    Ok(Response::Normal(Re::String(
-                        self.set_method(key, my_int.to_string()),
-                    )))
-                }
-                Re::Nil => Ok(Response::Normal(Re::String(
-                    self.set_method(key, increment.to_string()),
-                ))),
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
+    /// Incrementa el número de punto flotante almacenado en la clave en un incremento, y retorna
+    /// el nuevo valor. Si la clave no existe, es seteado a 0 antes de realizar la operación.
+    ///
+    /// Devuelve error si la clave contiene un valor de tipo erróneo, un string que no puede ser
+    /// representado como número de punto flotante, o si el resultado no es un número finito.
+    fn incrbyfloat_method(&mut self, key: String, increment: f64) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command INCRBYFLOAT Received - key: ".to_string() + &*key,
+        ));
+
+        let current = match self.db.get(&key) {
+            Some(Re::Integer(value)) => *value as f64,
+            Some(value @ (Re::String(_) | Re::Bytes(_))) => {
+                match value.as_string().unwrap_or_default().parse::<f64>() {
+                    Ok(value) if value.is_finite() => value,
+                    _ => return Err("ERR value is not a valid float".to_string()),
                 }
-            },
-            Err(_) => Ok(Response::Normal(Re::String(
-                self.set_method(key, increment.to_string()),
-            ))),
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => 0.0,
+        };
+
+        let result = current + increment;
+        if !result.is_finite() {
+            return Err("ERR increment would produce NaN or Infinity".to_string());
+        }
+
+        let result = format_float(result);
+        // Se modifica el valor en el lugar para conservar la expiración de la clave.
+        match self.db.get_mut(&key) {
+            Some(value) => *value = Re::String(result.clone()),
+            None => self.db.insert(key.clone(), Re::String(result.clone())),
         }
+        self.notify_keyspace_event(EventClass::String, "incrbyfloat", &key);
+
+        Ok(Response::Normal(Re::String(result)))
     }
 
     #[allow(dead_code)]
@@ -817,15 +2712,11 @@ impl Redis {
             "Command MGET Received - keys: ".to_string() + &keys.join(" - "),
         ));
 
-        let mut elements: Vec<String> = Vec::new();
-        for key in keys.iter() {
-            elements.push(
-                self.get_method(key.to_string())
-                    .unwrap_or(Re::Nil)
-                    .to_string(),
-            );
-        }
-        Response::Normal(Re::List(elements))
+        let elements = keys
+            .into_iter()
+            .map(|key| self.get_method(key).unwrap_or(Re::Nil))
+            .collect();
+        Response::Normal(Re::Array(elements))
     }
 
     #[allow(dead_code)]
@@ -845,13 +2736,40 @@ impl Redis {
 
         for (key, value) in key_values.iter() {
             self.set_method(key.to_string(), value.to_string());
+            self.notify_keyspace_event(EventClass::String, "set", key);
         }
 
         Response::Normal(Re::SimpleString("OK".to_string()))
     }
 
+    /// Setea las claves dadas a sus respectivos valores como MSET, solamente si ninguna de las
+    /// claves existe. Si alguna existe no se setea ninguna.
+    ///
+    /// Retorna 1 si se setearon todas las claves y 0 si no se seteó ninguna.
+    fn msetnx_method(&mut self, key_values: Vec<(String, String)>) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command MSETNX Received".to_string(),
+        ));
+
+        if key_values.iter().any(|(key, _)| self.db.contains_key(key)) {
+            return Response::Normal(Re::Integer(0));
+        }
+
+        for (key, value) in key_values {
+            self.set_method(key.clone(), value);
+            self.notify_keyspace_event(EventClass::String, "set", &key);
+        }
+
+        Response::Normal(Re::Integer(1))
+    }
+
     #[allow(dead_code)]
     /// obtiene el valor y elimina la clave. Es similar a GET, pero adicionalmente elimina la clave.
+    /// Si la clave no existe se retorna el valor especial nil.
     fn getdel_method(&mut self, key: String) -> Result<Re, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
@@ -863,11 +2781,12 @@ impl Redis {
 
         match self.get_method(key.clone()) {
             Ok(return_value) => match return_value {
-                Re::String(_) => {
-                    self.db.remove(&key);
+                Re::String(_) | Re::Bytes(_) => {
+                    self.db.delete(&key);
+                    self.notify_keyspace_event(EventClass::Generic, "del", &key);
                     Ok(return_value)
                 }
-                Re::Nil => Err("ERR no such key".to_string()),
+                Re::Nil => Ok(Re::Nil),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -885,7 +2804,7 @@ impl Redis {
 
     #[allow(dead_code)]
     /// Elimina una clave específica. La clave es ignorada si no existe.
-    fn del_method(&mut self, keys: Vec<String>) -> String {
+    fn del_method(&mut self, keys: Vec<String>) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -896,18 +2815,43 @@ impl Redis {
 
         let mut count = 0;
         for key in keys.iter() {
-            if self.db.remove(key).is_some() {
+            if self.db.delete(key) {
+                self.notify_keyspace_event(EventClass::Generic, "del", key);
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Elimina las claves como DEL, pero los valores se liberan en segundo plano, por lo que
+    /// eliminar colecciones muy grandes no demora al resto de los comandos.
+    fn unlink_method(&mut self, keys: Vec<String>) -> i64 {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command UNLINK Received - keys: ".to_string() + &keys.join(" - "),
+        ));
+
+        let mut count = 0;
+        for key in keys.iter() {
+            if let Some(value) = self.db.remove(key) {
+                self.lazy_free.free_value(value);
+                self.notify_keyspace_event(EventClass::Generic, "del", key);
                 count += 1;
             }
         }
 
-        count.to_string()
+        count
     }
 
     #[allow(dead_code)]
     /// Si la clave ya existe y es un string, este comando agrega el valor al final del string. Si
     /// no existe, es creada con el string vacío y luego le agrega el valor deseado. En este caso es
-    /// similar al comando SET.
+    /// similar al comando SET. Retorna el largo del string luego de agregar el valor, y conserva la
+    /// expiración de la clave.
     fn append_method(&mut self, key: String, value: String) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
@@ -917,35 +2861,17 @@ impl Redis {
             "Command APPEND Received - key: ".to_string() + &*key,
         ));
 
-        match self.get_method(key.clone()) {
-            Ok(redis_element) => match redis_element {
-                Re::String(s) => {
-                    let value = s + &value;
-                    Ok(Response::Normal(Re::String(self.set_method(key, value))))
-                }
-                Re::Nil => Ok(Response::Normal(Re::String(self.set_method(key, value)))),
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    e.to_string(),
-                ));
-                Err(e)
-            }
-        }
+        let len = append_in_shard(self.db.shard_mut(&key), &key, value).inspect_err(|e| {
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Error,
+                line!(),
+                column!(),
+                file!().to_string(),
+                e.to_string(),
+            ));
+        })?;
+        self.notify_keyspace_event(EventClass::String, "append", &key);
+        Ok(Response::Normal(Re::Integer(len as i64)))
     }
 
     /// Retorna si la/s clave/s existe/n.
@@ -965,12 +2891,21 @@ impl Redis {
             }
         }
 
-        Response::Normal(Re::String(count.to_string()))
+        Response::Normal(Re::Integer(count))
     }
 
     /// Configura un tiempo de expiración sobre una clave (la clave se dice que es volátil). Luego
-    /// de ese tiempo de expiración, la clave es automáticamente eliminada.
-    fn expire_method(&mut self, key: String, ttl: Duration) -> String {
+    /// de ese tiempo de expiración, la clave es automáticamente eliminada. La expiración solo se
+    /// modifica si se cumplen todas las condiciones (NX, XX, GT, LT) indicadas.
+    ///
+    /// Retorna error si el momento de expiración no puede representarse.
+    fn expire_method(
+        &mut self,
+        key: String,
+        ttl: Duration,
+        conditions: Vec<ExpireCondition>,
+        name: &str,
+    ) -> Result<i64, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -979,16 +2914,33 @@ impl Redis {
             "Command EXPIRE Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.set_ttl_relative(key, ttl) {
-            Some(_) => "1".to_string(),
-            None => "0".to_string(),
+        let deadline = match self.clock.now().checked_add(ttl) {
+            Some(deadline) => deadline,
+            None => return Err(format!("ERR invalid expire time in '{}' command", name)),
+        };
+        if !self.db.set_ttl_if(key.clone(), deadline, &conditions) {
+            return Ok(0);
+        }
+
+        // Un tiempo nulo (o negativo) elimina la clave en el acto, como hace Redis.
+        if ttl.is_zero() {
+            self.db.delete(&key);
+            self.notify_keyspace_event(EventClass::Generic, "del", &key);
+        } else {
+            self.notify_keyspace_event(EventClass::Generic, "expire", &key);
         }
+        Ok(1)
     }
 
     /// Tiene el mismo efecto que EXPIRE, pero en lugar de indicar el número de segundos que
     /// representa el TTL (time to live), toma el tiempo absoluto en el timestamp de Unix (segundos
     /// desde el 1ro de enero de 1970).
-    fn expireat_method(&mut self, key: String, ttl: SystemTime) -> String {
+    fn expireat_method(
+        &mut self,
+        key: String,
+        ttl: SystemTime,
+        conditions: Vec<ExpireCondition>,
+    ) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -997,15 +2949,16 @@ impl Redis {
             "Command EXPIREAT Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.set_ttl_absolute(key, ttl) {
-            Some(_) => "1".to_string(),
-            None => "0".to_string(),
+        if self.db.set_ttl_if(key.clone(), ttl, &conditions) {
+            self.notify_keyspace_event(EventClass::Generic, "expire", &key);
+            return 1;
         }
+        0
     }
 
     /// Elimina el tiempo de expiración existente en una clave, tornando una clave volátil en
     /// persistente (una clave que no expira, dado que no tiene timeout asociado)
-    fn persist_method(&mut self, key: String) -> String {
+    fn persist_method(&mut self, key: String) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -1015,8 +2968,27 @@ impl Redis {
         ));
 
         match self.db.delete_ttl(&key) {
-            Some(_) => "1".to_string(),
-            None => "0".to_string(),
+            Some(_) => {
+                self.notify_keyspace_event(EventClass::Generic, "persist", &key);
+                1
+            }
+            None => 0,
+        }
+    }
+
+    /// Retorna una clave al azar de la base de datos, o nil si está vacía.
+    fn randomkey_method(&mut self) -> Re {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command RANDOMKEY Received".to_string(),
+        ));
+
+        match self.db.random_key() {
+            Some(key) => Re::String(key),
+            None => Re::Nil,
         }
     }
 
@@ -1037,10 +3009,66 @@ impl Redis {
                 + &*key_destination,
         ));
 
-        match self.getdel_method(key_origin) {
-            Ok(value) => Ok(Response::Normal(Re::String(
-                self.set_method(key_destination, value.to_string()),
-            ))),
+        if !self.db.rename(&key_origin, key_destination.clone()) {
+            return Err("ERR no such key".to_string());
+        }
+        self.notify_keyspace_event(EventClass::Generic, "rename_from", &key_origin);
+        self.notify_keyspace_event(EventClass::Generic, "rename_to", &key_destination);
+        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+    }
+
+    /// Renombra una clave a un nuevo nombre de clave, solamente si la clave destino no existe.
+    /// Retorna 1 si la clave fue renombrada y 0 si la clave destino ya existía.
+    fn renamenx_method(
+        &mut self,
+        key_origin: String,
+        key_destination: String,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command RENAMENX Received - key origin: ".to_string()
+                + &*key_origin
+                + " - key destination: "
+                + &*key_destination,
+        ));
+
+        if !self.db.contains_key(&key_origin) {
+            return Err("ERR no such key".to_string());
+        }
+        if self.db.contains_key(&key_destination) {
+            return Ok(Response::Normal(Re::Integer(0)));
+        }
+        self.rename_method(key_origin, key_destination)?;
+        Ok(Response::Normal(Re::Integer(1)))
+    }
+
+    /// Crea la clave con el valor serializado por DUMP, y con el ttl indicado si corresponde.
+    ///
+    /// Retorna error si la clave ya existe y no se indicó REPLACE, o si el valor serializado no es
+    /// válido. Si el ttl ya venció, la clave no se crea.
+    fn restore_method(
+        &mut self,
+        key: String,
+        ttl: Option<SystemTime>,
+        payload: String,
+        replace: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command RESTORE Received - key: ".to_string() + &*key,
+        ));
+
+        if !replace && self.db.contains_key(&key) {
+            return Err("BUSYKEY Target key name already exists.".to_string());
+        }
+        let value = match rdb::restore_value(&payload) {
+            Ok(value) => value,
             Err(msg) => {
                 let _ = self.log_sender.send(Log::new(
                     LogLevel::Error,
@@ -1049,13 +3077,32 @@ impl Redis {
                     file!().to_string(),
                     msg.clone(),
                 ));
-                Err(msg)
+                return Err(msg);
+            }
+        };
+
+        if ttl.is_some_and(|ttl| ttl <= self.clock.now()) {
+            if self.db.delete(&key) {
+                self.notify_keyspace_event(EventClass::Generic, "del", &key);
+            }
+        } else {
+            self.db.insert(key.clone(), value);
+            if let Some(ttl) = ttl {
+                self.db.set_ttl_absolute(key.clone(), ttl);
             }
+            self.notify_keyspace_event(EventClass::Generic, "restore", &key);
         }
+        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
     }
 
-    /// Retorna ordenados los elementos de una clave
-    fn sort_method(&mut self, key: String) -> Result<Response, String> {
+    /// Retorna ordenados los elementos de una lista, un set o un sorted set. Por defecto los
+    /// elementos se ordenan numéricamente de menor a mayor.
+    ///
+    /// Las opciones permiten ordenar lexicográficamente (ALPHA) o de mayor a menor (DESC), ordenar
+    /// por el valor de claves externas (BY), devolver una parte del resultado (LIMIT), devolver el
+    /// valor de claves externas en lugar de los elementos (GET) y guardar el resultado en una
+    /// lista (STORE), en cuyo caso se retorna su largo.
+    fn sort_method(&mut self, key: String, options: SortOptions) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -1064,54 +3111,216 @@ impl Redis {
             "Command SORT Received - key: ".to_string() + &key,
         ));
 
-        let collection = match self.db.get(&key) {
-            Some(element) => match element {
-                Re::List(list) => list.clone(),
-                Re::Set(set) => set.clone().into_iter().collect::<Vec<String>>(),
-                _ => {
+        let mut elements = match self.lookup_read(&key) {
+            Some(Re::List(list)) => list.clone(),
+            Some(Re::Set(set)) => set.iter().cloned().collect(),
+            Some(Re::SortedSet(sorted_set)) => sorted_set
+                .iter()
+                .map(|(member, _)| member.to_string())
+                .collect(),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => vec![],
+        };
+
+        // Un patrón BY sin `*` indica que no se ordena.
+        let sort = match &options.by {
+            Some(pattern) => pattern.contains('*'),
+            None => true,
+        };
+        if sort {
+            elements = match self.sort_elements(elements, &options) {
+                Ok(elements) => elements,
+                Err(msg) => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
                         line!(),
                         column!(),
                         file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
+                        msg.to_string(),
                     ));
-                    return Err(WRONGTYPE_MSG.to_string());
+                    return Err(msg);
                 }
-            },
-            None => {
-                return Ok(Response::Normal(Re::List(vec![])));
+            };
+        }
+
+        let (offset, count) = options.limit.unwrap_or((0, -1));
+        let count = if count < 0 {
+            usize::MAX
+        } else {
+            count as usize
+        };
+        let elements = elements
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(count);
+
+        let mut result = vec![];
+        for element in elements {
+            if options.get.is_empty() {
+                result.push(Some(element));
+                continue;
+            }
+            for pattern in options.get.iter() {
+                result.push(self.sort_lookup(pattern, &element));
             }
+        }
+
+        match options.store {
+            Some(destination) => {
+                let list: Vec<String> = result.into_iter().map(Option::unwrap_or_default).collect();
+                let len = list.len();
+                if list.is_empty() {
+                    if self.db.delete(&destination) {
+                        self.notify_keyspace_event(EventClass::Generic, "del", &destination);
+                    }
+                } else {
+                    self.db.insert(destination.clone(), Re::List(list));
+                    self.notify_keyspace_event(EventClass::List, "sortstore", &destination);
+                }
+                Ok(Response::Normal(Re::Integer(len as i64)))
+            }
+            None if options.get.is_empty() => Ok(Response::Normal(Re::List(
+                result.into_iter().flatten().collect(),
+            ))),
+            None => Ok(Response::Normal(Re::Array(
+                result
+                    .into_iter()
+                    .map(|value| value.map(Re::String).unwrap_or(Re::Nil))
+                    .collect(),
+            ))),
+        }
+    }
+
+    /// Ordena los elementos para SORT según su peso, que es el propio elemento o el valor de la
+    /// clave externa indicada por el patrón BY. Los pesos inexistentes valen 0, o se ubican
+    /// primero si se ordena con ALPHA. Los elementos con el mismo peso se ordenan
+    /// lexicográficamente.
+    fn sort_elements(
+        &mut self,
+        elements: Vec<String>,
+        options: &SortOptions,
+    ) -> Result<Vec<String>, String> {
+        let mut weighted = vec![];
+        for element in elements {
+            let weight = match &options.by {
+                Some(pattern) => self.sort_lookup(pattern, &element),
+                None => Some(element.clone()),
+            };
+            weighted.push((element, weight));
+        }
+
+        let mut sorted: Vec<String> = if options.alpha {
+            weighted.sort_by(|(a, a_weight), (b, b_weight)| {
+                a_weight.cmp(b_weight).then_with(|| a.cmp(b))
+            });
+            weighted.into_iter().map(|(element, _)| element).collect()
+        } else {
+            let mut scored = vec![];
+            for (element, weight) in weighted {
+                let score = match weight {
+                    Some(weight) => weight
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|score| !score.is_nan())
+                        .ok_or_else(|| {
+                            "ERR One or more scores can't be converted into double".to_string()
+                        })?,
+                    None => 0.0,
+                };
+                scored.push((element, score));
+            }
+            scored.sort_by(|(a, a_score), (b, b_score)| {
+                a_score
+                    .partial_cmp(b_score)
+                    .unwrap_or(Ordering::Equal)
+                    .then_with(|| a.cmp(b))
+            });
+            scored.into_iter().map(|(element, _)| element).collect()
         };
-        let transformed_collection: Result<Vec<f64>, String> = collection
-            .iter()
-            .map(|a| a.parse::<f64>())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|_| "ERR One or more scores can't be converted into double".to_string());
-        let mut transformed_collection = match transformed_collection {
-            Ok(vec) => vec,
-            Err(msg) => {
+
+        if options.descending {
+            sorted.reverse();
+        }
+        Ok(sorted)
+    }
+
+    /// Obtiene el valor de la clave externa que corresponde al elemento según un patrón de SORT:
+    /// el primer `*` del patrón se reemplaza por el elemento, y el patrón `#` devuelve el propio
+    /// elemento. Retorna None si la clave no existe o no es un string.
+    fn sort_lookup(&mut self, pattern: &str, element: &str) -> Option<String> {
+        if pattern == "#" {
+            return Some(element.to_string());
+        }
+        if !pattern.contains('*') {
+            return None;
+        }
+
+        self.db
+            .get(&pattern.replacen('*', element, 1))
+            .and_then(Re::as_string)
+            .map(|value| value.into_owned())
+    }
+
+    /// El comando OBJECT permite inspeccionar la información interna asociada a una clave.
+    ///
+    /// # Arguments
+    ///
+    /// Los subcomandos que este puede recibir están definidos en el enum `ObjectParam`:
+    ///
+    /// * `Freq` - Devuelve la frecuencia de acceso a la clave, un contador logarítmico que crece
+    ///   con los accesos y decrece con el paso del tiempo. Consultarla no cuenta como un acceso.
+    /// * `Encoding` - Devuelve la codificación que usaría Redis para el valor (ej: `int` para los
+    ///   strings que representan un entero). Consultarla no cuenta como un acceso.
+    ///
+    /// Devuelve nil si la clave no existe, y error al consultar la frecuencia si no se registran
+    /// los accesos a las claves (`track-key-access no`).
+    fn object_method(&mut self, param: ObjectParam) -> Result<Re, String> {
+        match param {
+            ObjectParam::Freq(key) => {
                 let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
+                    LogLevel::Debug,
                     line!(),
                     column!(),
                     file!().to_string(),
-                    msg.to_string(),
+                    "Command OBJECT FREQ Received - key: ".to_string() + &*key,
                 ));
-                return Err(msg);
+
+                if !self.db.tracks_access() {
+                    return Err(ACCESS_NOT_TRACKED_MSG.to_string());
+                }
+                Ok(match self.db.get_frequency(&key) {
+                    Some(frequency) => Re::Integer(frequency as i64),
+                    None => Re::Nil,
+                })
             }
-        };
+            ObjectParam::Encoding(key) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Debug,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Command OBJECT ENCODING Received - key: ".to_string() + &*key,
+                ));
 
-        transformed_collection.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let sorted = transformed_collection
-            .iter()
-            .map(|a| a.to_string())
-            .collect();
-        Ok(Response::Normal(Re::List(sorted)))
+                Ok(match self.db.peek(&key) {
+                    Some(value) => Re::String(memory::encoding(value).to_string()),
+                    None => Re::Nil,
+                })
+            }
+        }
     }
 
     /// Actualiza el valor de último acceso a la clave.
-    fn touch_method(&mut self, keys: Vec<String>) -> String {
+    fn touch_method(&mut self, keys: Vec<String>) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -1138,12 +3347,12 @@ impl Redis {
             }
         }
 
-        count.to_string()
+        count
     }
 
     /// Retorna el tiempo que le queda a una clave para que se cumpla su timeout. Permite a un
     /// cliente Redis conocer cuántos segundos le quedan a una clave como parte del dataset.
-    fn ttl_method(&mut self, key: String) -> String {
+    fn ttl_method(&mut self, key: String) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -1152,14 +3361,51 @@ impl Redis {
             "Command TTL Received - key: ".to_string() + &*key,
         ));
 
+        format_ttl(self.db.get_ttl(&key))
+    }
+
+    /// Retorna el timestamp de Unix en el que expira la clave, expresado con la unidad que
+    /// devuelve `unit` (segundos para EXPIRETIME y milisegundos para PEXPIRETIME).
+    /// Retorna -1 si la clave no tiene timeout y -2 si no existe.
+    fn expiretime_method(&mut self, key: String, unit: fn(&Duration) -> i64) -> i64 {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command EXPIRETIME Received - key: ".to_string() + &*key,
+        ));
+
+        match self.db.get_expiration(&key) {
+            Some(Some(time)) => unit(
+                &time
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default(),
+            ),
+            Some(None) => -1,
+            None => -2,
+        }
+    }
+
+    /// Retorna el tiempo en milisegundos que le queda a una clave para que se cumpla su timeout.
+    /// Retorna -1 si la clave no tiene timeout y -2 si no existe.
+    fn pttl_method(&mut self, key: String) -> i64 {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command PTTL Received - key: ".to_string() + &*key,
+        ));
+
         match self.db.get_ttl(&key) {
             Some(value) => {
                 if value == Duration::from_secs(0) {
-                    return "-1".to_string();
+                    return -1;
                 }
-                value.as_secs().to_string()
+                value.as_millis() as i64
             }
-            None => "-2".to_string(),
+            None => -2,
         }
     }
 
@@ -1177,10 +3423,12 @@ impl Redis {
 
         match self.db.get(&key) {
             Some(return_value) => match return_value {
-                Re::String(_) => "string".to_string(),
+                Re::String(_) | Re::Integer(_) | Re::Bytes(_) => "string".to_string(),
                 Re::List(_) => "list".to_string(),
                 Re::Set(_) => "set".to_string(),
-                Re::Nil => "none".to_string(),
+                Re::SortedSet(_) => "zset".to_string(),
+                Re::Stream(_) => "stream".to_string(),
+                Re::Nil | Re::Array(_) => "none".to_string(),
                 Re::SimpleString(_) => "string".to_string(),
             },
             None => "none".to_string(),
@@ -1202,7 +3450,7 @@ impl Redis {
             "Command LINDEX Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.get_mut(&key) {
+        match self.lookup_read(&key) {
             Some(value) => match value {
                 Re::List(value) => {
                     let len_value = value.len() as i32;
@@ -1248,9 +3496,9 @@ impl Redis {
             "Command LLEN Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.get_mut(&key) {
+        match self.lookup_read(&key) {
             Some(value) => match value {
-                Re::List(value) => Ok(Response::Normal(Re::String(value.len().to_string()))),
+                Re::List(value) => Ok(Response::Normal(Re::Integer(value.len() as i64))),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -1262,62 +3510,66 @@ impl Redis {
                     Err(WRONGTYPE_MSG.to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Re::Integer(0))),
         }
     }
 
-    /// Elimina y retorna el primer elemento de la lista almacenada en la clave. Se puede indicar un
-    /// parámetro adicional `count` para indicar obtener esa cantidad de elementos.
-    fn lpop_method(&mut self, key: String, count: usize) -> Result<Response, String> {
+    /// Elimina y retorna el primer o el último elemento de la lista almacenada en la clave, según el
+    /// extremo indicado (LPOP / RPOP). Si la clave no existe o la lista está vacía se retorna nil.
+    ///
+    /// Si se indica `count` se retorna un array con hasta esa cantidad de elementos, en el orden en
+    /// el cual se quitaron de la lista (vacío si `count` es 0).
+    fn pop_method(
+        &mut self,
+        key: String,
+        count: Option<usize>,
+        side: ListSide,
+    ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LPOP Received - key: ".to_string() + &*key,
+            format!(
+                "Command {} Received - key: {}",
+                side.pop_event().to_uppercase(),
+                key
+            ),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let return_value: Vec<String>;
-                    let vector_to_save: Vec<String>;
-                    if count == 0 && !value.clone().is_empty() {
-                        return_value = Vec::from(value.get(..=count).unwrap());
-                        vector_to_save = Vec::from(value.get(count + 1..).unwrap());
-                    } else {
-                        let qty = match count {
-                            x if x >= value.len() => value.len(),
-                            _ => count,
-                        };
-                        return_value = Vec::from(value.get(..qty).unwrap());
-                        vector_to_save = Vec::from(value.get(qty..).unwrap());
-                    }
-
-                    self.db.insert(key, Re::List(vector_to_save));
-
-                    if return_value.len() == 1 && return_value.first().is_some() {
-                        let value = return_value.first();
-                        return Ok(Response::Normal(Re::String(value.unwrap().to_string())));
-                    }
-
-                    match return_value.len() {
-                        x if x > 0 => Ok(Response::Normal(Re::List(return_value.to_vec()))),
-                        _ => Ok(Response::Normal(Re::Nil)),
+        // Se modifica la lista en el lugar para conservar la expiración de la clave.
+        let popped: Vec<String> = match self.db.get_mut(&key) {
+            Some(Re::List(list)) if list.is_empty() => return Ok(Response::Normal(Re::Nil)),
+            Some(Re::List(list)) => {
+                let qty = count.unwrap_or(1).min(list.len());
+                match side {
+                    ListSide::Left => list.drain(..qty).collect(),
+                    ListSide::Right => {
+                        let mut popped = list.split_off(list.len() - qty);
+                        popped.reverse();
+                        popped
                     }
                 }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => Ok(Response::Normal(Re::Nil)),
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => return Ok(Response::Normal(Re::Nil)),
+        };
+
+        if !popped.is_empty() {
+            self.notify_keyspace_event(EventClass::List, side.pop_event(), &key);
+        }
+        match count {
+            Some(_) => Ok(Response::Normal(Re::List(popped))),
+            None => Ok(Response::Normal(Re::String(popped[0].clone()))),
         }
     }
 
@@ -1342,13 +3594,11 @@ impl Redis {
         match self.db.get_mut(&key) {
             Some(value) => match value {
                 Re::List(value) => {
-                    let saved_vector = value.clone();
-                    redis_element.extend(saved_vector);
-                    self.db.insert(key, Re::List(redis_element.clone()));
+                    value.splice(0..0, redis_element);
+                    let len = value.len();
+                    self.notify_keyspace_event(EventClass::List, "lpush", &key);
 
-                    Ok(Response::Normal(Re::String(
-                        redis_element.len().to_string(),
-                    )))
+                    Ok(Response::Normal(Re::Integer(len as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1362,42 +3612,45 @@ impl Redis {
                 }
             },
             None => {
-                self.db.insert(key, Re::List(redis_element.clone()));
+                let len = redis_element.len();
+                self.db.insert(key.clone(), Re::List(redis_element));
+                self.notify_keyspace_event(EventClass::List, "lpush", &key);
 
-                Ok(Response::Normal(Re::String(
-                    redis_element.len().to_string(),
-                )))
+                Ok(Response::Normal(Re::Integer(len as i64)))
             }
         }
     }
 
-    /// Inserta los valores especificados al inicio de lalista, solamente si la clave existe y
-    /// almacena una lista. A diferencia de LPUSH, no se realiza operación si la clave no existe.
-    fn lpushx_method(&mut self, key: String, values: Vec<String>) -> Result<Response, String> {
+    /// Elimina y retorna el primer (BLPOP) o último (BRPOP) elemento de la primera lista no vacía
+    /// entre las claves indicadas, junto al nombre de la misma.
+    ///
+    /// Si todas las listas están vacías, el cliente queda bloqueado hasta que otro cliente agregue
+    /// elementos a alguna de ellas, o hasta que venza el timeout, en cuyo caso se retorna nil.
+    ///
+    /// Retorna error si alguna de las claves almacena un elemento que no es una lista.
+    fn blocking_pop_method(
+        &mut self,
+        keys: Vec<String>,
+        timeout: Option<Duration>,
+        side: ListSide,
+    ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LPUSHX Received - key: ".to_string() + &*key,
+            "Command BLPOP/BRPOP Received - keys: ".to_string() + &*keys.join(" "),
         ));
 
-        let mut redis_element: Vec<String> = values;
-        redis_element.reverse();
-
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                RedisElement::List(value) => {
-                    let saved_vector = value.clone();
-                    redis_element.extend(saved_vector);
-                    self.db
-                        .insert(key, RedisElement::List(redis_element.clone()));
-
-                    Ok(Response::Normal(Re::String(
-                        redis_element.len().to_string(),
-                    )))
+        for key in &keys {
+            match self.db.get_mut(key) {
+                Some(Re::List(list)) => {
+                    if let Some(element) = Redis::pop_side(list, side) {
+                        self.notify_keyspace_event(EventClass::List, side.pop_event(), key);
+                        return Ok(Response::Normal(Re::List(vec![key.to_string(), element])));
+                    }
                 }
-                _ => {
+                Some(_) => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
                         line!(),
@@ -1405,117 +3658,69 @@ impl Redis {
                         file!().to_string(),
                         WRONGTYPE_MSG.to_string(),
                     ));
-                    Err(WRONGTYPE_MSG.to_string())
+                    return Err(WRONGTYPE_MSG.to_string());
                 }
-            },
-            None => {
-                self.db.insert(key, Re::List(vec![]));
-                Ok(Response::Normal(Re::String("0".to_string())))
+                None => {}
             }
         }
-    }
-
-    /// Retorna los elementos especificados de la lista almacenada en la clave indicada.
-    ///
-    /// Los inicios
-    /// y fin de rango se consideran con el 0 como primer elemento de la lista. Estos valores pueden
-    /// ser negativos, indicando que corresponde al final de la lista: -1 es el último elemento.
-    fn lrange_method(&mut self, key: String, begin: i32, end: i32) -> Result<Response, String> {
-        let _ = self.log_sender.send(Log::new(
-            LogLevel::Debug,
-            line!(),
-            column!(),
-            file!().to_string(),
-            "Command LRANGE Received - key: ".to_string() + &*key,
-        ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let len_value = value.len() as i32;
-                    let mut begin_position: i32 = begin;
-
-                    if begin < 0 {
-                        begin_position = begin + len_value + 1;
-                    };
-
-                    let mut end_position: i32 = end;
-
-                    if end < 0 {
-                        end_position = end + len_value + 1;
-                    }
-
-                    let begin_position: usize = begin_position as usize;
-                    let end_position: usize = end_position as usize;
-                    let return_value = value.get(begin_position..end_position);
-                    if return_value.is_none() {
-                        return Ok(Response::Normal(Re::List(vec![])));
-                    }
+        let now = self.clock.now();
+        let (sender, receiver): (Sender<Re>, Receiver<Re>) = mpsc::channel();
+        self.blocked_clients.register(Waiter {
+            sender,
+            keys,
+            side: PopSide::List(side),
+            deadline: timeout.map(|timeout| now + timeout),
+        });
 
-                    Ok(Response::Normal(Re::List(return_value.unwrap().to_vec())))
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => Ok(Response::Normal(Re::List(vec![]))),
-        }
+        Ok(Response::Blocked(receiver))
     }
 
-    /// Elimina la primer cantidad count de ocurrencias de elementos de la lista almacenada en la
-    /// clave, igual al elemento indicado por parámetro.
+    /// Elimina y retorna el miembro de menor o mayor score del primer sorted set no vacío entre las
+    /// claves indicadas, junto al nombre de la misma y su score. Si el sorted set queda vacío, se
+    /// elimina la clave.
     ///
-    /// El parámetro cantidad influye de esta manera:
+    /// Si todos los sorted sets están vacíos, el cliente queda bloqueado hasta que otro cliente
+    /// agregue miembros a alguno de ellos, o hasta que venza el timeout, en cuyo caso se retorna
+    /// nil.
     ///
-    /// * `count > 0` - Elimina elementos iguales al indicado comenzando desde el inicio de la lista.
-    /// * `count < 0` - Elimina elementos iguales al indicado comenzando desde el final de la lista.
-    /// * `count = 0` - Elimina todos los elementos iguales al indicado.
-    fn lrem_method(
+    /// Retorna error si alguna de las claves almacena un elemento que no es un sorted set.
+    fn blocking_zpop_method(
         &mut self,
-        key: String,
-        count: i32,
-        element: String,
+        keys: Vec<String>,
+        timeout: Option<Duration>,
+        side: ScoreSide,
     ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LREM Received - key: ".to_string() + &*key,
+            format!(
+                "Command B{} Received - keys: {}",
+                side.pop_event().to_uppercase(),
+                keys.join(" ")
+            ),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => match count.cmp(&0) {
-                    Ordering::Greater => {
-                        let (final_vector, deleted) =
-                            Self::remove_repeats(count as usize, element, value.clone());
-                        self.db.insert(key.clone(), Re::List(final_vector));
-                        Ok(Response::Normal(Re::String(deleted.to_string())))
-                    }
-                    Ordering::Less => {
-                        value.reverse();
-                        let (mut final_vector, deleted) =
-                            Self::remove_repeats(count as usize, element, value.clone());
-                        final_vector.reverse();
-                        self.db.insert(key.clone(), Re::List(final_vector));
-                        Ok(Response::Normal(Re::String(deleted.to_string())))
-                    }
-                    Ordering::Equal => {
-                        let (final_vector, deleted) =
-                            Self::remove_all_repeats(element, value.clone());
-                        self.db.insert(key.clone(), Re::List(final_vector));
-                        Ok(Response::Normal(Re::String(deleted.to_string())))
+        for key in &keys {
+            match self.db.get_mut(key) {
+                Some(Re::SortedSet(sorted_set)) => {
+                    if let Some((member, score)) = sorted_set.pop(side, 1).pop() {
+                        let is_empty = sorted_set.is_empty();
+                        self.notify_keyspace_event(EventClass::SortedSet, side.pop_event(), key);
+                        if is_empty {
+                            self.db.delete(key);
+                            self.notify_keyspace_event(EventClass::Generic, "del", key);
+                        }
+                        return Ok(Response::Normal(Re::List(vec![
+                            key.to_string(),
+                            member,
+                            score.to_string(),
+                        ])));
                     }
-                },
-                _ => {
+                }
+                Some(_) => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
                         line!(),
@@ -1523,37 +3728,310 @@ impl Redis {
                         file!().to_string(),
                         WRONGTYPE_MSG.to_string(),
                     ));
-                    Err(WRONGTYPE_MSG.to_string())
+                    return Err(WRONGTYPE_MSG.to_string());
                 }
-            },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+                None => {}
+            }
         }
+
+        let now = self.clock.now();
+        let (sender, receiver): (Sender<Re>, Receiver<Re>) = mpsc::channel();
+        self.blocked_clients.register(Waiter {
+            sender,
+            keys,
+            side: PopSide::SortedSet(side),
+            deadline: timeout.map(|timeout| now + timeout),
+        });
+
+        Ok(Response::Blocked(receiver))
     }
 
-    fn remove_repeats(
-        count: usize,
-        element: String,
-        mut vector: Vec<String>,
-    ) -> (Vec<String>, usize) {
-        let mut n = 0;
-        for i in 0..vector.len() {
-            if n <= count && vector.get(i).is_some() && *vector.get(i).unwrap() == element {
-                vector.remove(i);
-                n += 1;
+    /// Entrega los elementos de la lista o sorted set indicado a los clientes bloqueados sobre
+    /// él, según el tipo de dato que almacene la clave.
+    fn serve_blocked_clients(&mut self, key: String) {
+        match self.db.peek(&key) {
+            Some(Re::List(_)) => self.serve_list_waiters(key),
+            Some(Re::SortedSet(_)) => self.serve_sorted_set_waiters(key),
+            _ => {}
+        }
+    }
+
+    /// Entrega los elementos de la lista indicada a los clientes bloqueados por BLPOP/BRPOP sobre
+    /// ella, en orden de llegada, mientras la lista tenga elementos.
+    fn serve_list_waiters(&mut self, key: String) {
+        while let Some(Re::List(list)) = self.db.get_mut(&key) {
+            if list.is_empty() {
+                return;
+            }
+            let is_list = |waiter: &Waiter| matches!(waiter.side, PopSide::List(_));
+            let waiter = match self.blocked_clients.pop_waiter(&key, is_list) {
+                Some(waiter) => waiter,
+                None => return,
+            };
+            let side = match waiter.side {
+                PopSide::List(side) => side,
+                PopSide::SortedSet(_) => unreachable!(),
+            };
+
+            let element = Redis::pop_side(list, side).unwrap();
+            let response = Re::List(vec![key.to_string(), element.clone()]);
+            // Si el cliente se desconectó, el elemento vuelve a la lista.
+            if waiter.sender.send(response).is_err() {
+                match side {
+                    ListSide::Left => list.insert(0, element),
+                    ListSide::Right => list.push(element),
+                }
+            } else {
+                self.notify_keyspace_event(EventClass::List, side.pop_event(), &key);
+            }
+        }
+    }
+
+    /// Entrega los miembros del sorted set indicado a los clientes bloqueados por
+    /// BZPOPMIN/BZPOPMAX sobre él, en orden de llegada, mientras el sorted set tenga miembros. Si
+    /// el sorted set queda vacío, se elimina la clave.
+    fn serve_sorted_set_waiters(&mut self, key: String) {
+        while let Some(Re::SortedSet(sorted_set)) = self.db.get_mut(&key) {
+            if sorted_set.is_empty() {
+                break;
+            }
+            let is_sorted_set = |waiter: &Waiter| matches!(waiter.side, PopSide::SortedSet(_));
+            let waiter = match self.blocked_clients.pop_waiter(&key, is_sorted_set) {
+                Some(waiter) => waiter,
+                None => break,
+            };
+            let side = match waiter.side {
+                PopSide::SortedSet(side) => side,
+                PopSide::List(_) => unreachable!(),
+            };
+
+            let (member, score) = sorted_set.pop(side, 1).remove(0);
+            let response = Re::List(vec![key.to_string(), member.clone(), score.to_string()]);
+            // Si el cliente se desconectó, el miembro vuelve al sorted set.
+            if waiter.sender.send(response).is_err() {
+                sorted_set.insert(member, score);
+            } else {
+                self.notify_keyspace_event(EventClass::SortedSet, side.pop_event(), &key);
+            }
+        }
+
+        if let Some(Re::SortedSet(sorted_set)) = self.db.peek(&key) {
+            if sorted_set.is_empty() {
+                self.db.delete(&key);
+                self.notify_keyspace_event(EventClass::Generic, "del", &key);
+            }
+        }
+    }
+
+    /// Persiste la base de datos en el archivo configurado si se cumple alguna de las reglas
+    /// `save` de la configuración.
+    pub fn save_if_needed(&mut self) {
+        let (save_rules, path) = {
+            let config = self.config.lock().unwrap();
+            (config.get_save_rules(), config.get_dbfilename())
+        };
+        let elapsed = self
+            .clock
+            .now()
+            .duration_since(self.last_save)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+
+        let must_save = save_rules
+            .iter()
+            .any(|rule| self.dirty >= rule.changes && elapsed >= Duration::from_secs(rule.seconds));
+        if must_save {
+            let _ = self.store_method(path);
+        }
+    }
+
+    /// Elimina las claves expiradas que no vuelven a ser accedidas, como el ciclo de expiración
+    /// activa de Redis: revisa muestras de claves con expiración mientras más de un cuarto de la
+    /// muestra haya expirado, sin superar `ACTIVE_EXPIRE_TIME_LIMIT`. Se ejecuta a lo sumo una
+    /// vez cada `ACTIVE_EXPIRE_PERIOD`, y no hace nada si fue deshabilitado con
+    /// DEBUG SET-ACTIVE-EXPIRE.
+    pub fn active_expire_cycle(&mut self) {
+        if !self.active_expire || self.last_active_expire.elapsed() < ACTIVE_EXPIRE_PERIOD {
+            return;
+        }
+
+        let start = Instant::now();
+        self.last_active_expire = start;
+        while self.db.has_volatile_keys()
+            && self.db.expire_sample(ACTIVE_EXPIRE_SAMPLES) * 4 > ACTIVE_EXPIRE_SAMPLES
+            && start.elapsed() < ACTIVE_EXPIRE_TIME_LIMIT
+        {}
+        self.notify_expired_keys();
+    }
+
+    /// Responde nil a los clientes bloqueados cuyo timeout venció.
+    pub fn expire_blocked_clients(&mut self) {
+        if self.blocked_clients.is_empty() {
+            return;
+        }
+
+        for waiter in self.blocked_clients.expire(self.clock.now()) {
+            let _ = waiter.sender.send(Re::Nil);
+        }
+    }
+
+    /// Elimina y retorna el elemento del extremo indicado de la lista.
+    fn pop_side(list: &mut Vec<String>, side: ListSide) -> Option<String> {
+        match side {
+            ListSide::Left if !list.is_empty() => Some(list.remove(0)),
+            ListSide::Left => None,
+            ListSide::Right => list.pop(),
+        }
+    }
+
+    /// Inserta los valores especificados al inicio de lalista, solamente si la clave existe y
+    /// almacena una lista. A diferencia de LPUSH, no se realiza operación si la clave no existe.
+    fn lpushx_method(&mut self, key: String, values: Vec<String>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LPUSHX Received - key: ".to_string() + &*key,
+        ));
+
+        let mut redis_element: Vec<String> = values;
+        redis_element.reverse();
+
+        match self.db.get_mut(&key) {
+            Some(value) => match value {
+                RedisElement::List(value) => {
+                    value.splice(0..0, redis_element);
+                    let len = value.len();
+                    self.notify_keyspace_event(EventClass::List, "lpush", &key);
+
+                    Ok(Response::Normal(Re::Integer(len as i64)))
+                }
+                _ => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        WRONGTYPE_MSG.to_string(),
+                    ));
+                    Err(WRONGTYPE_MSG.to_string())
+                }
+            },
+            None => {
+                self.db.insert(key, Re::List(vec![]));
+                Ok(Response::Normal(Re::Integer(0)))
             }
         }
-        (vector, n)
     }
 
-    fn remove_all_repeats(element: String, mut vector: Vec<String>) -> (Vec<String>, usize) {
-        let mut n = 0;
-        for i in 0..vector.len() {
-            if vector.get(i).is_some() && *vector.get(i).unwrap() == element {
-                vector.remove(i);
-                n += 1;
+    /// Retorna los elementos especificados de la lista almacenada en la clave indicada.
+    ///
+    /// Los inicios
+    /// y fin de rango se consideran con el 0 como primer elemento de la lista, y ambos se incluyen
+    /// en el resultado. Estos valores pueden ser negativos, indicando que corresponde al final de
+    /// la lista: -1 es el último elemento. Los índices fuera de la lista se ajustan a sus extremos.
+    fn lrange_method(&mut self, key: String, begin: i32, end: i32) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LRANGE Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key) {
+            Some(value) => match value {
+                Re::List(value) => {
+                    let len = value.len() as i64;
+                    let begin = begin as i64;
+                    let end = end as i64;
+                    let begin = if begin < 0 { begin + len } else { begin }.max(0);
+                    let end = if end < 0 { end + len } else { end }.min(len - 1);
+
+                    if begin > end {
+                        return Ok(Response::Normal(Re::List(vec![])));
+                    }
+                    Ok(Response::Normal(Re::List(
+                        value[begin as usize..=end as usize].to_vec(),
+                    )))
+                }
+                _ => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        WRONGTYPE_MSG.to_string(),
+                    ));
+                    Err(WRONGTYPE_MSG.to_string())
+                }
+            },
+            None => Ok(Response::Normal(Re::List(vec![]))),
+        }
+    }
+
+    /// Elimina la primer cantidad count de ocurrencias de elementos de la lista almacenada en la
+    /// clave, igual al elemento indicado por parámetro.
+    ///
+    /// El parámetro cantidad influye de esta manera:
+    ///
+    /// * `count > 0` - Elimina elementos iguales al indicado comenzando desde el inicio de la lista.
+    /// * `count < 0` - Elimina elementos iguales al indicado comenzando desde el final de la lista.
+    /// * `count = 0` - Elimina todos los elementos iguales al indicado.
+    fn lrem_method(
+        &mut self,
+        key: String,
+        count: i32,
+        element: String,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LREM Received - key: ".to_string() + &*key,
+        ));
+
+        // Se modifica la lista en el lugar para conservar la expiración de la clave.
+        let removed = match self.db.get_mut(&key) {
+            Some(Re::List(list)) => {
+                let limit = match count {
+                    0 => usize::MAX,
+                    count => count.unsigned_abs() as usize,
+                };
+                if count < 0 {
+                    list.reverse();
+                }
+                let mut removed = 0;
+                list.retain(|value| {
+                    if removed < limit && *value == element {
+                        removed += 1;
+                        return false;
+                    }
+                    true
+                });
+                if count < 0 {
+                    list.reverse();
+                }
+                removed
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
             }
+            None => 0,
+        };
+
+        if removed > 0 {
+            self.notify_keyspace_event(EventClass::List, "lrem", &key);
         }
-        (vector, n)
+        Ok(Response::Normal(Re::Integer(removed as i64)))
     }
 
     /// Setea el elemento de la posición index de la lista con el elemento suministrado.
@@ -1598,6 +4076,7 @@ impl Redis {
                     let saved_value = value;
 
                     saved_value[position as usize] = element;
+                    self.notify_keyspace_event(EventClass::List, "lset", &key);
 
                     Ok(Response::Normal(Re::SimpleString("OK".to_string())))
                 }
@@ -1625,64 +4104,6 @@ impl Redis {
         }
     }
 
-    /// Elimina y obtiene el/los último/s elemento/s de la lista almacenada en la clave indicada.
-    /// Por defecto, es un solo elemento, se puede indicar una cantidad.
-    fn rpop_method(&mut self, key: String, count: usize) -> Result<Response, String> {
-        let _ = self.log_sender.send(Log::new(
-            LogLevel::Debug,
-            line!(),
-            column!(),
-            file!().to_string(),
-            "Command RPOP Received - key: ".to_string() + &*key,
-        ));
-
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let return_value: Vec<String>;
-                    let mut vector_to_save: Vec<String>;
-                    value.reverse();
-
-                    if count == 0 {
-                        return_value = Vec::from(value.get(..=count).unwrap());
-                        vector_to_save = Vec::from(value.get(count + 1..).unwrap());
-                    } else {
-                        let qty = match count {
-                            x if x >= value.len() => value.len(),
-                            _ => count,
-                        };
-                        return_value = Vec::from(value.get(..qty).unwrap());
-                        vector_to_save = Vec::from(value.get(qty..).unwrap());
-                    }
-
-                    vector_to_save.reverse();
-                    self.db.insert(key, Re::List(vector_to_save));
-
-                    if return_value.len() == 1 && return_value.first().is_some() {
-                        let value = return_value.first();
-                        return Ok(Response::Normal(Re::String(value.unwrap().to_string())));
-                    }
-
-                    match return_value.len() {
-                        x if x > 0 => Ok(Response::Normal(Re::List(return_value.to_vec()))),
-                        _ => Ok(Response::Normal(Re::Nil)),
-                    }
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => Ok(Response::Normal(Re::Nil)),
-        }
-    }
-
     /// Inserta todos los valores especificados al final de la lista indicada en la clave.
     ///
     /// Si la
@@ -1702,11 +4123,11 @@ impl Redis {
         match self.db.get_mut(&key) {
             Some(value) => match value {
                 Re::List(value) => {
-                    let mut saved_vector = value.clone();
-                    saved_vector.extend(values);
-                    self.db.insert(key, Re::List(saved_vector.clone()));
+                    value.extend(values);
+                    let len = value.len();
+                    self.notify_keyspace_event(EventClass::List, "rpush", &key);
 
-                    Ok(Response::Normal(Re::String(saved_vector.len().to_string())))
+                    Ok(Response::Normal(Re::Integer(len as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1720,9 +4141,11 @@ impl Redis {
                 }
             },
             None => {
-                self.db.insert(key, Re::List(values.clone()));
+                let len = values.len();
+                self.db.insert(key.clone(), Re::List(values));
+                self.notify_keyspace_event(EventClass::List, "rpush", &key);
 
-                Ok(Response::Normal(Re::String(values.len().to_string())))
+                Ok(Response::Normal(Re::Integer(len as i64)))
             }
         }
     }
@@ -1741,12 +4164,11 @@ impl Redis {
         match self.db.get_mut(&key) {
             Some(value) => match value {
                 RedisElement::List(value) => {
-                    let mut saved_vector = value.clone();
-                    saved_vector.extend(values);
-                    self.db
-                        .insert(key, RedisElement::List(saved_vector.clone()));
+                    value.extend(values);
+                    let len = value.len();
+                    self.notify_keyspace_event(EventClass::List, "rpush", &key);
 
-                    Ok(Response::Normal(Re::String(saved_vector.len().to_string())))
+                    Ok(Response::Normal(Re::Integer(len as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1759,7 +4181,7 @@ impl Redis {
                     Err(WRONGTYPE_MSG.to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Re::Integer(0))),
         }
     }
 
@@ -1780,15 +4202,16 @@ impl Redis {
 
         match self.db.get_mut(&key) {
             Some(value) => match value {
-                RedisElement::Set(value) => {
-                    let mut set = value.clone();
+                RedisElement::Set(set) => {
                     let start_set_len = set.len();
                     set.extend(values);
                     let final_set_len = set.len();
-                    self.db.insert(key, RedisElement::Set(set));
+                    if final_set_len > start_set_len {
+                        self.notify_keyspace_event(EventClass::Set, "sadd", &key);
+                    }
 
-                    Ok(Response::Normal(Re::String(
-                        (final_set_len - start_set_len).to_string(),
+                    Ok(Response::Normal(Re::Integer(
+                        (final_set_len - start_set_len) as i64,
                     )))
                 }
                 _ => {
@@ -1803,8 +4226,10 @@ impl Redis {
                 }
             },
             None => {
-                self.db.insert(key, RedisElement::Set(values.clone()));
-                Ok(Response::Normal(Re::String(values.len().to_string())))
+                let len = values.len();
+                self.db.insert(key.clone(), RedisElement::Set(values));
+                self.notify_keyspace_event(EventClass::Set, "sadd", &key);
+                Ok(Response::Normal(Re::Integer(len as i64)))
             }
         }
     }
@@ -1819,11 +4244,11 @@ impl Redis {
             "Command SCARD Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.get_mut(&key) {
+        match self.lookup_read(&key) {
             Some(value) => match value {
                 RedisElement::Set(value) => {
                     let set = value.clone();
-                    Ok(Response::Normal(Re::String(set.len().to_string())))
+                    Ok(Response::Normal(Re::Integer(set.len() as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1837,7 +4262,7 @@ impl Redis {
                     Err("WRONGTYPE A hashset data type expected".to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Re::Integer(0))),
         }
     }
 
@@ -1851,14 +4276,14 @@ impl Redis {
             "Command SISMEMBER Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.get_mut(&key) {
+        match self.lookup_read(&key) {
             Some(redis_element) => match redis_element {
                 RedisElement::Set(redis_element) => {
                     let set = redis_element.clone();
                     if set.contains(&value) {
-                        Ok(Response::Normal(Re::String("1".to_string())))
+                        Ok(Response::Normal(Re::Integer(1)))
                     } else {
-                        Ok(Response::Normal(Re::String("0".to_string())))
+                        Ok(Response::Normal(Re::Integer(0)))
                     }
                 }
                 _ => {
@@ -1896,7 +4321,7 @@ impl Redis {
             "Command SMEMBERS Received - key: ".to_string() + &*key,
         ));
 
-        match self.db.get_mut(&key) {
+        match self.lookup_read(&key) {
             Some(redis_element) => match redis_element {
                 RedisElement::Set(redis_element) => {
                     Ok(Response::Normal(Re::Set(redis_element.clone())))
@@ -1944,16 +4369,17 @@ impl Redis {
 
         match self.db.get_mut(&key) {
             Some(redis_element) => match redis_element {
-                RedisElement::Set(redis_element) => {
-                    let mut set = redis_element.clone();
+                RedisElement::Set(set) => {
                     let mut count = 0;
                     for value in values {
                         if set.remove(&value) {
                             count += 1;
                         }
                     }
-                    self.db.insert(key.clone(), RedisElement::Set(set));
-                    Ok(Response::Normal(Re::String(count.to_string())))
+                    if count > 0 {
+                        self.notify_keyspace_event(EventClass::Set, "srem", &key);
+                    }
+                    Ok(Response::Normal(Re::Integer(count as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1966,566 +4392,6030 @@ impl Redis {
                     Err("WRONGTYPE A hashset data type expected".to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Re::Integer(0))),
         }
     }
 
-    /// Retorna todas las claves que hacen match con un patrón.
-    fn keys_method(&mut self, pattern: String) -> Vec<String> {
+    /// Retorna, para cada uno de los miembros indicados, si pertenece (1) o no (0) al set
+    /// almacenado en la clave indicada. Si la clave no existe, se considera como un set vacío.
+    ///
+    /// Retorna error si el valor almacenado en esa clave no es un set.
+    fn smismember_method(&mut self, key: String, members: Vec<String>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command KEYS Received".to_string(),
+            "Command SMISMEMBER Received - key: ".to_string() + &*key,
         ));
 
-        let mut vector = vec![];
-        for key in self.db.keys() {
-            if let Ok(re) = Regex::new(&*pattern) {
-                if re.is_match(key) {
-                    vector.push(key.to_string());
-                }
-            }
-        }
-        vector
+        let set = self.get_sets(&[key])?.pop().flatten().unwrap_or_default();
+        let result = members
+            .iter()
+            .map(|member| Re::Integer(set.contains(member) as i64))
+            .collect();
+
+        Ok(Response::Normal(Re::Array(result)))
     }
 
-    /// Comando interno para Persistir los elementos de la Base de datos en un archivo
-    fn store_method(&self, path: String) -> Result<Response, String> {
+    /// Retorna los miembros que pertenecen a todos los sets almacenados en las claves indicadas.
+    /// Si alguna de las claves no existe, el resultado es el set vacío.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set.
+    fn sinter_method(&mut self, keys: Vec<String>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command STORE Received - path: ".to_string() + &*path,
+            "Command SINTER Received - keys: ".to_string() + &*keys.join(" "),
         ));
 
-        let mut file = match fs::File::create(path) {
-            Ok(file) => file,
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    e.to_string(),
-                ));
-                return Err(e.to_string());
-            }
-        };
-
-        let rdb_file = [
-            "REDIS".as_bytes(),
-            VERSION_NUMBER.as_bytes(),
-            &self.db.serialize(),
-        ]
-        .concat();
-
-        match file.write_all(&rdb_file) {
-            Ok(_) => Ok(Response::Normal(RedisElement::String("Ok".to_string()))),
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    e.to_string(),
-                ));
-                Err(e.to_string())
-            }
-        }
+        Ok(Response::Normal(Re::Set(self.intersect_sets(&keys)?)))
     }
 
-    /// Comando interno para Cargar los elementos de la Base de datos desde un archivo
-    fn load_method(&mut self, path: String) -> Result<Response, String> {
+    /// Retorna la cantidad de miembros de la intersección de los sets almacenados en las claves
+    /// indicadas. Si `limit` es mayor a 0, el resultado se acota a ese valor.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set.
+    fn sintercard_method(&mut self, keys: Vec<String>, limit: usize) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LOAD Received - path: ".to_string() + &*path,
+            "Command SINTERCARD Received - keys: ".to_string() + &*keys.join(" "),
         ));
 
-        let stream = match fs::read(path) {
-            Ok(stream) => stream,
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    format!("{:?}", e),
-                ));
-                return Err(format!("{:?}", e));
-            }
-        };
+        let mut cardinality = self.intersect_sets(&keys)?.len();
+        if limit > 0 {
+            cardinality = cardinality.min(limit);
+        }
 
-        let mut stream = stream.to_vec();
+        Ok(Response::Normal(Re::Integer(cardinality as i64)))
+    }
 
-        if stream.len() < 5 || stream.drain(0..5).collect::<Vec<u8>>() != "REDIS".as_bytes() {
-            return Err("Error: file is not RDB type".to_string());
+    /// Calcula la intersección de los sets almacenados en las claves indicadas. Si alguna de las
+    /// claves no existe, el resultado es el set vacío.
+    fn intersect_sets(&mut self, keys: &[String]) -> Result<HashSet<String>, String> {
+        let sets = self.get_sets(keys)?;
+        if sets.iter().any(|set| set.is_none()) {
+            return Ok(HashSet::new());
         }
 
-        if stream.len() < 4
-            || String::from_utf8_lossy(&stream.drain(0..4).collect::<Vec<u8>>()) != VERSION_NUMBER
-        {
-            return Err("Error: file is not same redis version.".to_string());
+        let mut sets = sets.into_iter().flatten();
+        let mut result = sets.next().unwrap_or_default();
+        for set in sets {
+            result.retain(|member| set.contains(member));
         }
-        match TtlHashMap::deserialize(stream) {
-            Ok(map) => {
-                self.db = map;
-                Ok(Response::Normal(RedisElement::String("OK".to_string())))
-            }
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    format!("{:?}", e),
-                ));
-                Err(format!("{:?}", e))
+
+        Ok(result)
+    }
+
+    /// Retorna los miembros que pertenecen a alguno de los sets almacenados en las claves
+    /// indicadas. Las claves que no existen se consideran sets vacíos.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set.
+    fn sunion_method(&mut self, keys: Vec<String>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SUNION Received - keys: ".to_string() + &*keys.join(" "),
+        ));
+
+        let result: HashSet<String> = self
+            .get_sets(&keys)?
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect();
+
+        Ok(Response::Normal(Re::Set(result)))
+    }
+
+    /// Retorna los miembros del set almacenado en la primera clave que no pertenecen a ninguno de
+    /// los sets almacenados en el resto de las claves. Las claves que no existen se consideran sets
+    /// vacíos.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set.
+    fn sdiff_method(&mut self, keys: Vec<String>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SDIFF Received - keys: ".to_string() + &*keys.join(" "),
+        ));
+
+        let mut sets = self.get_sets(&keys)?.into_iter();
+        let mut result = sets.next().flatten().unwrap_or_default();
+        for set in sets.flatten() {
+            result.retain(|member| !set.contains(member));
+        }
+
+        Ok(Response::Normal(Re::Set(result)))
+    }
+
+    /// Obtiene los sets almacenados en las claves indicadas, en el mismo orden. Las claves que no
+    /// existen se devuelven como None.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set.
+    fn get_sets(&mut self, keys: &[String]) -> Result<Vec<Option<HashSet<String>>>, String> {
+        let mut sets = vec![];
+        for key in keys {
+            match self.lookup_read(key) {
+                Some(Re::Set(set)) => sets.push(Some(set.clone())),
+                Some(_) => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        WRONGTYPE_MSG.to_string(),
+                    ));
+                    return Err(WRONGTYPE_MSG.to_string());
+                }
+                None => sets.push(None),
             }
         }
+        Ok(sets)
     }
 
-    /// El comando CONFIG GET se utiliza para leer los parámetros de configuración de un servidor en
-    /// ejecución.
-    fn config_get_method(&mut self) -> Vec<String> {
+    /// Elimina y retorna miembros aleatorios del set almacenado en la clave indicada.
+    ///
+    /// Sin `count` retorna un único miembro, o nil si la clave no existe. Con `count` retorna una
+    /// lista de hasta `count` miembros distintos.
+    ///
+    /// Retorna error si el valor almacenado en esa clave no es un set.
+    fn spop_method(&mut self, key: String, count: Option<usize>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command CONFIG GET Received".to_string(),
+            "Command SPOP Received - key: ".to_string() + &*key,
         ));
 
-        let config = self.config.lock().unwrap();
-        vec![
-            config.get_dbfilename(),
-            config.get_logfile(),
-            config.get_port(),
-            config.get_verbose(),
-            config.get_timeout().to_string(),
-        ]
+        let set = match self.db.get_mut(&key) {
+            Some(Re::Set(set)) => set,
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => {
+                return match count {
+                    Some(_) => Ok(Response::Normal(Re::List(vec![]))),
+                    None => Ok(Response::Normal(Re::Nil)),
+                }
+            }
+        };
+
+        let mut members: Vec<String> = set.iter().cloned().collect();
+        shuffle(&mut members);
+        members.truncate(count.unwrap_or(1));
+        for member in &members {
+            set.remove(member);
+        }
+        if !members.is_empty() {
+            self.notify_keyspace_event(EventClass::Set, "spop", &key);
+        }
+
+        match count {
+            Some(_) => Ok(Response::Normal(Re::List(members))),
+            None => match members.pop() {
+                Some(member) => Ok(Response::Normal(Re::String(member))),
+                None => Ok(Response::Normal(Re::Nil)),
+            },
+        }
     }
 
-    /// El comando CONFIG SET se utiliza para reconfigurar un servidor en tiempo de ejecución sin
-    /// necesidad de reiniciarlo.
-    fn config_set_method(&mut self, parameter: String, value: String) -> Result<Response, String> {
+    /// Retorna miembros aleatorios del set almacenado en la clave indicada, sin eliminarlos.
+    ///
+    /// Sin `count` retorna un único miembro, o nil si la clave no existe. Con `count` positivo
+    /// retorna una lista de hasta `count` miembros distintos, y con `count` negativo retorna una
+    /// lista de exactamente `-count` miembros, que pueden repetirse.
+    ///
+    /// Retorna error si el valor almacenado en esa clave no es un set, o si `count` es negativo y
+    /// su valor absoluto supera `MAX_RANDOM_MEMBERS`.
+    fn srandmember_method(&mut self, key: String, count: Option<i64>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command CONFIG SET Received - parameter: ".to_string() + &parameter,
+            "Command SRANDMEMBER Received - key: ".to_string() + &*key,
         ));
-        let mut config = self.config.lock().unwrap();
 
-        match parameter.as_str() {
-            "verbose" => config.set_verbose(value),
-            "dbfilename" => config.set_dbfilename(value),
-            "logfile" => config.set_logfile(value),
-            _ => {
+        if matches!(count, Some(count) if count < 0 && count.unsigned_abs() > MAX_RANDOM_MEMBERS) {
+            let msg = format!(
+                "ERR value is out of range, must be between -{} and {}",
+                MAX_RANDOM_MEMBERS,
+                i64::MAX
+            );
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Error,
+                line!(),
+                column!(),
+                file!().to_string(),
+                msg.clone(),
+            ));
+            return Err(msg);
+        }
+
+        let mut members: Vec<String> = match self.lookup_read(&key) {
+            Some(Re::Set(set)) => set.iter().cloned().collect(),
+            Some(_) => {
                 let _ = self.log_sender.send(Log::new(
                     LogLevel::Error,
                     line!(),
                     column!(),
                     file!().to_string(),
-                    "Parameter does not exist".to_string(),
+                    WRONGTYPE_MSG.to_string(),
                 ));
-                return Err("Parameter does not exist".to_string());
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => vec![],
+        };
+
+        match count {
+            None if members.is_empty() => Ok(Response::Normal(Re::Nil)),
+            None => Ok(Response::Normal(Re::String(
+                members.swap_remove(random_index(members.len())),
+            ))),
+            Some(_) if members.is_empty() => Ok(Response::Normal(Re::List(vec![]))),
+            Some(count) if count < 0 => {
+                let random_members = (0..count.unsigned_abs())
+                    .map(|_| members[random_index(members.len())].to_string())
+                    .collect();
+                Ok(Response::Normal(Re::List(random_members)))
+            }
+            Some(count) => {
+                shuffle(&mut members);
+                members.truncate(count as usize);
+                Ok(Response::Normal(Re::List(members)))
             }
         }
-        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
     }
-}
 
-#[allow(unused_imports)]
-mod test {
-    use crate::entities::command::Command;
-    use crate::entities::info_param::InfoParam;
-    use crate::service::redis::TtlHashMap;
-    use crate::service::redis::{Re, Redis, Response};
-    use std::collections::HashSet;
-    use std::fs;
-    use std::io::Write;
-    use std::process;
-    use std::thread::{self, sleep};
-    use std::time::{Duration, SystemTime};
+    /// Itera incrementalmente los miembros del set almacenado en la clave indicada.
+    ///
+    /// Los miembros se recorren ordenados por un hash fijo de su contenido, y el cursor indica la
+    /// posición en ese orden desde la cual continuar la iteración. Como la posición de cada
+    /// miembro no depende del resto, agregar o quitar miembros durante la iteración no hace que se
+    /// saltee ningún miembro que esté en el set durante toda la iteración. `count` es la cantidad
+    /// de miembros a recorrer en esta llamada. Si se indica un patrón, solo se devuelven los
+    /// miembros recorridos que hagan match con el mismo. Retorna el próximo cursor (0 cuando la
+    /// iteración terminó) junto a los miembros encontrados.
+    ///
+    /// Si la clave no existe, se considera como un set vacío.
+    ///
+    /// Retorna error si el valor almacenado en esa clave no es un set.
+    fn sscan_method(
+        &mut self,
+        key: String,
+        cursor: u64,
+        pattern: Option<String>,
+        count: usize,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SSCAN Received - key: ".to_string() + &*key,
+        ));
 
-    #[allow(dead_code)]
-    fn eq_response(content: Re, response: Response) -> bool {
-        if let Response::Normal(redis_element) = response {
-            return content == redis_element;
+        let mut pending: Vec<(u64, &String)> = match self.lookup_read(&key) {
+            Some(Re::Set(set)) => set
+                .iter()
+                .map(|member| (scan_position(member), member))
+                .filter(|(position, _)| *position >= cursor)
+                .collect(),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => vec![],
         };
-        false
+
+        let count = count.max(1);
+        let next_cursor = if pending.len() > count {
+            // Se separan los `count` miembros con menor posición, sin ordenar el resto. Los que
+            // comparten la posición del último también se devuelven, para no saltearlos.
+            let (_, last, _) = pending.select_nth_unstable(count - 1);
+            let last = last.0;
+            pending.retain(|(position, _)| *position <= last);
+            last.checked_add(1).unwrap_or(0)
+        } else {
+            0
+        };
+
+        let mut found: Vec<String> = pending
+            .into_iter()
+            .map(|(_, member)| member)
+            .filter(|member| match &pattern {
+                Some(pattern) => glob_match(pattern, member, false),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        found.sort();
+
+        Ok(Response::Normal(Re::Array(vec![
+            Re::String(next_cursor.to_string()),
+            Re::List(found),
+        ])))
     }
 
-    #[test]
-    fn test_strlen_element_fail_if_is_not_string() {
-        let mut redis: Redis = Redis::new_for_test();
+    /// Agrega todos los miembros indicados, con sus scores, al sorted set almacenado en la clave.
+    ///
+    /// Si un miembro ya existía se actualiza su score. Si la clave no existe, se crea un sorted set
+    /// vacío antes de realizar la operación. Retorna la cantidad de miembros nuevos agregados.
+    ///
+    /// Retorna error si el valor almacenado en la clave no es un sorted set.
+    fn zadd_method(
+        &mut self,
+        key: String,
+        members: Vec<(f64, String)>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZADD Received - key: ".to_string() + &*key,
+        ));
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        match self.db.get_mut(&key) {
+            Some(Re::SortedSet(sorted_set)) => {
+                let mut added = 0;
+                for (score, member) in members {
+                    if sorted_set.insert(member, score).is_none() {
+                        added += 1;
+                    }
+                }
+                self.notify_keyspace_event(EventClass::SortedSet, "zadd", &key);
+                Ok(Response::Normal(Re::Integer(added as i64)))
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => {
+                let mut sorted_set = SortedSet::new();
+                for (score, member) in members {
+                    sorted_set.insert(member, score);
+                }
+                let added = sorted_set.len();
+                self.db.insert(key.clone(), Re::SortedSet(sorted_set));
+                self.notify_keyspace_event(EventClass::SortedSet, "zadd", &key);
+                Ok(Response::Normal(Re::Integer(added as i64)))
+            }
+        }
+    }
 
-        let key = "key".to_string();
-        let strlen = redis.execute(Command::Strlen { key });
+    /// Retorna la cantidad de miembros del sorted set almacenado en la clave indicada.
+    fn zcard_method(&mut self, key: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZCARD Received - key: ".to_string() + &*key,
+        ));
 
-        assert!(strlen.is_err());
+        match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => {
+                Ok(Response::Normal(Re::Integer(sorted_set.len() as i64)))
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(Response::Normal(Re::Integer(0))),
+        }
     }
 
-    #[ignore]
-    #[test]
-    fn test_strlen_element_not_found() {
-        let mut redis: Redis = Redis::new_for_test();
+    /// Suma `increment` al score del miembro en el sorted set almacenado en la clave, y retorna el
+    /// nuevo score. Si el miembro no existe se agrega con score `increment`, y si la clave no
+    /// existe se crea un sorted set con ese único miembro.
+    ///
+    /// Retorna error si el valor almacenado en la clave no es un sorted set, o si el nuevo score no
+    /// es un número.
+    fn zincrby_method(
+        &mut self,
+        key: String,
+        increment: f64,
+        member: String,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZINCRBY Received - key: ".to_string() + &*key,
+        ));
 
-        let key = "key".to_string();
-        let strlen = redis.execute(Command::Strlen { key });
+        let score = match self.db.get_mut(&key) {
+            Some(Re::SortedSet(sorted_set)) => sorted_set.increment(member, increment),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => {
+                let mut sorted_set = SortedSet::new();
+                let score = sorted_set.increment(member, increment);
+                if score.is_some() {
+                    self.db.insert(key.clone(), Re::SortedSet(sorted_set));
+                }
+                score
+            }
+        };
+
+        match score {
+            Some(score) => {
+                self.notify_keyspace_event(EventClass::SortedSet, "zincr", &key);
+                Ok(Response::Normal(Re::String(score.to_string())))
+            }
+            None => Err(NAN_SCORE_MSG.to_string()),
+        }
+    }
+
+    /// Elimina y retorna los miembros con menor o mayor score del sorted set almacenado en la
+    /// clave, según el extremo indicado (ZPOPMIN / ZPOPMAX), intercalados con sus scores. Sin
+    /// `count` se quita un único miembro. Si el sorted set queda vacío, se elimina la clave.
+    ///
+    /// Retorna error si el valor almacenado en la clave no es un sorted set.
+    fn zpop_method(
+        &mut self,
+        key: String,
+        count: Option<usize>,
+        side: ScoreSide,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!(
+                "Command {} Received - key: {}",
+                side.pop_event().to_uppercase(),
+                key
+            ),
+        ));
+
+        let (popped, is_empty) = match self.db.get_mut(&key) {
+            Some(Re::SortedSet(sorted_set)) => {
+                let popped = sorted_set.pop(side, count.unwrap_or(1));
+                (popped, sorted_set.is_empty())
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => (vec![], false),
+        };
+
+        if !popped.is_empty() {
+            self.notify_keyspace_event(EventClass::SortedSet, side.pop_event(), &key);
+        }
+        if is_empty {
+            self.db.delete(&key);
+            self.notify_keyspace_event(EventClass::Generic, "del", &key);
+        }
+        Ok(Response::Normal(Re::List(Redis::scored_members(
+            popped, true,
+        ))))
+    }
+
+    /// Convierte los pares (miembro, score) de un sorted set en la respuesta de los comandos de
+    /// rango: sólo los miembros, o cada miembro seguido de su score si se indicó WITHSCORES.
+    fn scored_members(members: Vec<(String, f64)>, with_scores: bool) -> Vec<String> {
+        if !with_scores {
+            return members.into_iter().map(|(member, _)| member).collect();
+        }
+        members
+            .into_iter()
+            .flat_map(|(member, score)| vec![member, score.to_string()])
+            .collect()
+    }
+
+    /// Retorna los miembros del sorted set almacenado en la clave, entre las posiciones `start` y
+    /// `stop` (ambas inclusive), ordenados de menor a mayor score. Con `with_scores`, cada miembro
+    /// es seguido de su score.
+    ///
+    /// Las posiciones pueden ser negativas, indicando que corresponden al final del sorted set: -1
+    /// es el último elemento.
+    fn zrange_method(
+        &mut self,
+        key: String,
+        start: i64,
+        stop: i64,
+        with_scores: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZRANGE Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => Ok(Response::Normal(Re::List(
+                Redis::scored_members(sorted_set.range(start, stop), with_scores),
+            ))),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(Response::Normal(Re::List(vec![]))),
+        }
+    }
+
+    /// Retorna los miembros del sorted set almacenado en la clave cuyo score se encuentra entre
+    /// `min` y `max`, ordenados de menor a mayor score. Con `with_scores`, cada miembro es seguido
+    /// de su score.
+    fn zrangebyscore_method(
+        &mut self,
+        key: String,
+        min: ScoreBound,
+        max: ScoreBound,
+        with_scores: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZRANGEBYSCORE Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => Ok(Response::Normal(Re::List(
+                Redis::scored_members(sorted_set.range_by_score(min, max), with_scores),
+            ))),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(Response::Normal(Re::List(vec![]))),
+        }
+    }
+
+    /// Retorna la posición del miembro en el sorted set almacenado en la clave, ordenado de menor a
+    /// mayor score y empezando en 0. Si el miembro o la clave no existen, retorna nil.
+    fn zrank_method(&mut self, key: String, member: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZRANK Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => match sorted_set.rank(&member) {
+                Some(rank) => Ok(Response::Normal(Re::Integer(rank as i64))),
+                None => Ok(Response::Normal(Re::Nil)),
+            },
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(Response::Normal(Re::Nil)),
+        }
+    }
+
+    /// Elimina los miembros indicados del sorted set almacenado en la clave. Retorna la cantidad
+    /// de miembros eliminados. Si el sorted set queda vacío, se elimina la clave.
+    fn zrem_method(&mut self, key: String, members: Vec<String>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZREM Received - key: ".to_string() + &*key,
+        ));
+
+        let (removed, is_empty) = match self.db.get_mut(&key) {
+            Some(Re::SortedSet(sorted_set)) => {
+                let removed = members
+                    .iter()
+                    .filter(|member| sorted_set.remove(member))
+                    .count();
+                (removed, sorted_set.is_empty())
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => (0, false),
+        };
+
+        if removed > 0 {
+            self.notify_keyspace_event(EventClass::SortedSet, "zrem", &key);
+        }
+        if is_empty {
+            self.db.delete(&key);
+            self.notify_keyspace_event(EventClass::Generic, "del", &key);
+        }
+        Ok(Response::Normal(Re::Integer(removed as i64)))
+    }
+
+    /// Retorna el score del miembro en el sorted set almacenado en la clave. Si el miembro o la
+    /// clave no existen, retorna nil.
+    fn zscore_method(&mut self, key: String, member: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZSCORE Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => match sorted_set.score(&member) {
+                Some(score) => Ok(Response::Normal(Re::String(score.to_string()))),
+                None => Ok(Response::Normal(Re::Nil)),
+            },
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(Response::Normal(Re::Nil)),
+        }
+    }
+
+    /// Guarda en `destination` la unión de los sorted sets almacenados en las claves indicadas,
+    /// multiplicando los scores de cada uno por su peso y combinando los de los miembros repetidos
+    /// según `aggregate`. Las claves que no existen se consideran vacías, y los sets se toman como
+    /// sorted sets con score 1. Retorna la cantidad de miembros del resultado.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set ni un sorted set.
+    fn zunionstore_method(
+        &mut self,
+        destination: String,
+        keys: Vec<String>,
+        weights: Vec<f64>,
+        aggregate: Aggregate,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZUNIONSTORE Received - destination: ".to_string() + &*destination,
+        ));
+
+        let sets: Vec<(SortedSet, f64)> = self
+            .get_sorted_sets(&keys)?
+            .into_iter()
+            .zip(weights)
+            .filter_map(|(set, weight)| set.map(|set| (set, weight)))
+            .collect();
+        let result = SortedSet::union(&sets, aggregate);
+        Ok(self.store_sorted_set(destination, result, "zunionstore"))
+    }
+
+    /// Guarda en `destination` la intersección de los sorted sets almacenados en las claves
+    /// indicadas, multiplicando los scores de cada uno por su peso y combinándolos según
+    /// `aggregate`. Si alguna de las claves no existe, el resultado es vacío. Los sets se toman como
+    /// sorted sets con score 1. Retorna la cantidad de miembros del resultado.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set ni un sorted set.
+    fn zinterstore_method(
+        &mut self,
+        destination: String,
+        keys: Vec<String>,
+        weights: Vec<f64>,
+        aggregate: Aggregate,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZINTERSTORE Received - destination: ".to_string() + &*destination,
+        ));
+
+        let sets: Option<Vec<(SortedSet, f64)>> = self
+            .get_sorted_sets(&keys)?
+            .into_iter()
+            .zip(weights)
+            .map(|(set, weight)| set.map(|set| (set, weight)))
+            .collect();
+        let result = match sets {
+            Some(sets) => SortedSet::intersection(&sets, aggregate),
+            None => SortedSet::new(),
+        };
+        Ok(self.store_sorted_set(destination, result, "zinterstore"))
+    }
+
+    /// Obtiene los sorted sets almacenados en las claves indicadas, o None para las claves que no
+    /// existen. Los sets se convierten en sorted sets con score 1.
+    ///
+    /// Retorna error si alguna de las claves almacena un valor que no es un set ni un sorted set.
+    fn get_sorted_sets(&mut self, keys: &[String]) -> Result<Vec<Option<SortedSet>>, String> {
+        let mut sets = vec![];
+        for key in keys {
+            match self.lookup_read(key) {
+                Some(Re::SortedSet(sorted_set)) => sets.push(Some(sorted_set.clone())),
+                Some(Re::Set(set)) => sets.push(Some(
+                    set.iter().map(|member| (member.as_str(), 1.0)).collect(),
+                )),
+                Some(_) => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        WRONGTYPE_MSG.to_string(),
+                    ));
+                    return Err(WRONGTYPE_MSG.to_string());
+                }
+                None => sets.push(None),
+            }
+        }
+        Ok(sets)
+    }
+
+    /// Guarda el sorted set en `destination`, reemplazando su valor y su expiración anteriores, y
+    /// retorna su cantidad de miembros. Si el sorted set está vacío, se elimina la clave.
+    fn store_sorted_set(
+        &mut self,
+        destination: String,
+        result: SortedSet,
+        event: &str,
+    ) -> Response {
+        let len = result.len();
+        if result.is_empty() {
+            if self.db.delete(&destination) {
+                self.notify_keyspace_event(EventClass::Generic, "del", &destination);
+            }
+        } else {
+            self.db.insert(destination.clone(), Re::SortedSet(result));
+            self.notify_keyspace_event(EventClass::SortedSet, event, &destination);
+        }
+        Response::Normal(Re::Integer(len as i64))
+    }
+
+    /// Agrega las posiciones de los miembros al sorted set almacenado en la clave, usando su
+    /// geohash como score. Retorna la cantidad de miembros agregados, o también la de miembros
+    /// modificados si se indicó CH.
+    fn geoadd_method(
+        &mut self,
+        key: String,
+        options: GeoAddOptions,
+        members: Vec<(f64, f64, String)>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command GEOADD Received - key: ".to_string() + &*key,
+        ));
+
+        let added = match self.db.get_mut(&key) {
+            Some(Re::SortedSet(sorted_set)) => Redis::add_geo_members(sorted_set, options, members),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => {
+                let mut sorted_set = SortedSet::new();
+                let added = Redis::add_geo_members(&mut sorted_set, options, members);
+                if !sorted_set.is_empty() {
+                    self.db.insert(key.clone(), Re::SortedSet(sorted_set));
+                }
+                added
+            }
+        };
+        if self.db.contains_key(&key) {
+            self.notify_keyspace_event(EventClass::SortedSet, "zadd", &key);
+        }
+
+        Ok(Response::Normal(Re::Integer(added as i64)))
+    }
+
+    /// Agrega las posiciones de los miembros al sorted set respetando las opciones de GEOADD.
+    /// Retorna la cantidad de miembros agregados (y modificados, con CH).
+    fn add_geo_members(
+        sorted_set: &mut SortedSet,
+        options: GeoAddOptions,
+        members: Vec<(f64, f64, String)>,
+    ) -> usize {
+        let mut added = 0;
+        for (longitude, latitude, member) in members {
+            let exists = sorted_set.score(&member).is_some();
+            match options.condition {
+                SetCondition::IfNotExists if exists => continue,
+                SetCondition::IfExists if !exists => continue,
+                _ => {}
+            }
+
+            let score = geo::encode(longitude, latitude) as f64;
+            match sorted_set.insert(member, score) {
+                None => added += 1,
+                Some(previous) if options.changed && previous != score => added += 1,
+                Some(_) => {}
+            }
+        }
+        added
+    }
+
+    /// Retorna la distancia entre dos miembros en la unidad indicada, o nil si alguno no existe.
+    fn geodist_method(
+        &mut self,
+        key: String,
+        member1: String,
+        member2: String,
+        unit: GeoUnit,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command GEODIST Received - key: ".to_string() + &*key,
+        ));
+
+        let positions = match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => sorted_set
+                .score(&member1)
+                .zip(sorted_set.score(&member2))
+                .map(|(score1, score2)| (geo::decode(score1 as u64), geo::decode(score2 as u64))),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => None,
+        };
+
+        match positions {
+            Some(((longitude1, latitude1), (longitude2, latitude2))) => {
+                let distance = geo::distance(longitude1, latitude1, longitude2, latitude2);
+                Ok(Response::Normal(Re::String(format!(
+                    "{:.4}",
+                    distance / unit.to_meters()
+                ))))
+            }
+            None => Ok(Response::Normal(Re::Nil)),
+        }
+    }
+
+    /// Retorna la longitud y latitud de cada miembro, o nil para los miembros que no existen.
+    fn geopos_method(&mut self, key: String, members: Vec<String>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command GEOPOS Received - key: ".to_string() + &*key,
+        ));
+
+        let sorted_set = match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => Some(sorted_set),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => None,
+        };
+
+        let positions = members
+            .iter()
+            .map(
+                |member| match sorted_set.and_then(|sorted_set| sorted_set.score(member)) {
+                    Some(score) => {
+                        let (longitude, latitude) = geo::decode(score as u64);
+                        Re::Array(vec![
+                            Re::String(longitude.to_string()),
+                            Re::String(latitude.to_string()),
+                        ])
+                    }
+                    None => Re::Nil,
+                },
+            )
+            .collect();
+
+        Ok(Response::Normal(Re::Array(positions)))
+    }
+
+    /// Retorna los miembros que se encuentran dentro del área indicada, opcionalmente ordenados
+    /// por distancia y acompañados de su distancia, geohash y coordenadas.
+    fn geosearch_method(
+        &mut self,
+        key: String,
+        options: GeoSearchOptions,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command GEOSEARCH Received - key: ".to_string() + &*key,
+        ));
+
+        let sorted_set = match self.lookup_read(&key) {
+            Some(Re::SortedSet(sorted_set)) => sorted_set,
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => return Ok(Response::Normal(Re::Array(vec![]))),
+        };
+
+        let center = match &options.origin {
+            GeoOrigin::Member(member) => match sorted_set.score(member) {
+                Some(score) => geo::decode(score as u64),
+                None => return Err("ERR could not decode requested zset member".to_string()),
+            },
+            GeoOrigin::LonLat(longitude, latitude) => (*longitude, *latitude),
+        };
+
+        let mut found = vec![];
+        for (member, score) in sorted_set.iter() {
+            if options.any && options.count.is_some_and(|count| found.len() >= count) {
+                break;
+            }
+            let hash = score as u64;
+            let position = geo::decode(hash);
+            if let Some(distance) = geo::within(options.shape, center, position) {
+                found.push((member.to_string(), distance, hash, position));
+            }
+        }
+
+        match options.ascending {
+            Some(true) => found.sort_by(|a, b| a.1.total_cmp(&b.1)),
+            Some(false) => found.sort_by(|a, b| b.1.total_cmp(&a.1)),
+            None => {}
+        }
+        if let Some(count) = options.count {
+            found.truncate(count);
+        }
+
+        if !options.with_coord && !options.with_dist && !options.with_hash {
+            return Ok(Response::Normal(Re::List(
+                found.into_iter().map(|(member, ..)| member).collect(),
+            )));
+        }
+
+        let results = found
+            .into_iter()
+            .map(|(member, distance, hash, (longitude, latitude))| {
+                let mut result = vec![Re::String(member)];
+                if options.with_dist {
+                    result.push(Re::String(format!(
+                        "{:.4}",
+                        distance / options.unit.to_meters()
+                    )));
+                }
+                if options.with_hash {
+                    result.push(Re::Integer(hash as i64));
+                }
+                if options.with_coord {
+                    result.push(Re::Array(vec![
+                        Re::String(longitude.to_string()),
+                        Re::String(latitude.to_string()),
+                    ]));
+                }
+                Re::Array(result)
+            })
+            .collect();
+
+        Ok(Response::Normal(Re::Array(results)))
+    }
+
+    /// Agrega una entrada al stream almacenado en la clave, creándolo si no existe, y retorna el
+    /// ID de la entrada. Si no se indica un ID, se genera a partir del horario actual.
+    ///
+    /// Si se indica `max_len`, se eliminan las entradas más antiguas hasta no superar ese largo.
+    fn xadd_method(
+        &mut self,
+        key: String,
+        id: Option<StreamId>,
+        max_len: Option<usize>,
+        fields: Vec<(String, String)>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XADD Received - key: ".to_string() + &*key,
+        ));
+
+        let now_ms = self
+            .clock
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        // Se modifica el stream en el lugar para conservar la expiración de la clave.
+        let id = match self.db.get_mut(&key) {
+            Some(Re::Stream(stream)) => {
+                Redis::add_stream_entry(stream, id, now_ms, max_len, fields)?
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => {
+                let mut stream = Stream::new();
+                let id = Redis::add_stream_entry(&mut stream, id, now_ms, max_len, fields)?;
+                self.db.insert(key.clone(), Re::Stream(stream));
+                id
+            }
+        };
+        self.notify_keyspace_event(EventClass::Stream, "xadd", &key);
+
+        Ok(Response::Normal(Re::String(id.to_string())))
+    }
+
+    /// Agrega la entrada al stream y lo recorta, si corresponde. Retorna el ID de la entrada.
+    fn add_stream_entry(
+        stream: &mut Stream,
+        id: Option<StreamId>,
+        now_ms: u64,
+        max_len: Option<usize>,
+        fields: Vec<(String, String)>,
+    ) -> Result<StreamId, String> {
+        let id = match id {
+            Some(id) => id,
+            None => stream.next_id(now_ms).ok_or_else(|| {
+                "ERR The stream has exhausted the last possible ID, unable to add more items"
+                    .to_string()
+            })?,
+        };
+
+        stream.add(id, fields)?;
+        if let Some(max_len) = max_len {
+            stream.trim(max_len);
+        }
+        Ok(id)
+    }
+
+    /// Retorna la cantidad de entradas del stream almacenado en la clave.
+    fn xlen_method(&mut self, key: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XLEN Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key) {
+            Some(Re::Stream(stream)) => Ok(Response::Normal(Re::Integer(stream.len() as i64))),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(Response::Normal(Re::Integer(0))),
+        }
+    }
+
+    /// Retorna las entradas del stream cuyo ID se encuentra entre `start` y `end`, ambos
+    /// inclusive. Si `reverse` es true, las entradas se retornan en orden descendente.
+    fn xrange_method(
+        &mut self,
+        key: String,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+        reverse: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XRANGE Received - key: ".to_string() + &*key,
+        ));
+
+        match self.lookup_read(&key) {
+            Some(Re::Stream(stream)) => {
+                let entries = if reverse {
+                    stream.rev_range(start, end, count)
+                } else {
+                    stream.range(start, end, count)
+                };
+                Ok(Response::Normal(Redis::stream_entries_element(
+                    entries
+                        .into_iter()
+                        .map(|(id, fields)| (id, Some(fields)))
+                        .collect(),
+                )))
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(Response::Normal(Re::Array(vec![]))),
+        }
+    }
+
+    /// Retorna, para cada stream, las entradas con ID mayor al indicado. Los streams sin entradas
+    /// nuevas se omiten, y si ninguno tiene entradas nuevas se retorna nil.
+    ///
+    /// El ID `$` (None) corresponde a la última entrada del stream, por lo que sin bloquear al
+    /// cliente nunca retorna entradas.
+    fn xread_method(
+        &mut self,
+        count: Option<usize>,
+        streams: Vec<(String, Option<StreamId>)>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XREAD Received - keys: ".to_string()
+                + &streams
+                    .iter()
+                    .map(|(key, _)| key.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" - "),
+        ));
+
+        let mut response = vec![];
+        for (key, id) in streams {
+            let entries = match self.db.get(&key) {
+                Some(Re::Stream(stream)) => match id.and_then(|id| id.next()) {
+                    Some(start) => stream.range(start, StreamId::MAX, count),
+                    None => vec![],
+                },
+                Some(_) => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        WRONGTYPE_MSG.to_string(),
+                    ));
+                    return Err(WRONGTYPE_MSG.to_string());
+                }
+                None => vec![],
+            };
+
+            if !entries.is_empty() {
+                response.push(Re::Array(vec![
+                    Re::String(key),
+                    Redis::stream_entries_element(
+                        entries
+                            .into_iter()
+                            .map(|(id, fields)| (id, Some(fields)))
+                            .collect(),
+                    ),
+                ]));
+            }
+        }
+
+        if response.is_empty() {
+            Ok(Response::Normal(Re::Nil))
+        } else {
+            self.dirty += 1;
+            Ok(Response::Normal(Re::Array(response)))
+        }
+    }
+
+    /// Crea, elimina o modifica los grupos de consumidores del stream almacenado en la clave, o sus
+    /// consumidores.
+    fn xgroup_method(&mut self, key: String, param: XgroupParam) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XGROUP Received - key: ".to_string() + &*key,
+        ));
+
+        if let XgroupParam::Create { mkstream: true, .. } = param {
+            if !self.db.contains_key(&key) {
+                self.db.insert(key.clone(), Re::Stream(Stream::new()));
+            }
+        }
+
+        let stream = match self.db.get_mut(&key) {
+            Some(Re::Stream(stream)) => stream,
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => return Err("ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.".to_string()),
+        };
+
+        let no_group = |group: &str| {
+            format!(
+                "NOGROUP No such consumer group '{}' for key name '{}'",
+                group, key
+            )
+        };
+        // Evento a notificar, si el subcomando modificó el stream.
+        let (response, event) = match param {
+            XgroupParam::Create { group, id, .. } => {
+                stream.create_group(&group, id)?;
+                (Re::SimpleString("OK".to_string()), Some("xgroup-create"))
+            }
+            XgroupParam::Destroy { group } => {
+                let destroyed = stream.destroy_group(&group);
+                let event = if destroyed {
+                    Some("xgroup-destroy")
+                } else {
+                    None
+                };
+                (Re::Integer(destroyed as i64), event)
+            }
+            XgroupParam::CreateConsumer { group, consumer } => {
+                let created = stream
+                    .group_mut(&group)
+                    .ok_or_else(|| no_group(&group))?
+                    .create_consumer(&consumer, self.clock.now());
+                let event = if created {
+                    Some("xgroup-createconsumer")
+                } else {
+                    None
+                };
+                (Re::Integer(created as i64), event)
+            }
+            XgroupParam::DelConsumer { group, consumer } => {
+                let pending = stream
+                    .group_mut(&group)
+                    .ok_or_else(|| no_group(&group))?
+                    .delete_consumer(&consumer);
+                let event = pending.map(|_| "xgroup-delconsumer");
+                (Re::Integer(pending.unwrap_or(0) as i64), event)
+            }
+            XgroupParam::SetId { group, id } => {
+                let last_id = stream.last_id();
+                stream
+                    .group_mut(&group)
+                    .ok_or_else(|| no_group(&group))?
+                    .last_delivered = id.unwrap_or(last_id);
+                (Re::SimpleString("OK".to_string()), Some("xgroup-setid"))
+            }
+        };
+
+        if let Some(event) = event {
+            self.notify_keyspace_event(EventClass::Stream, event, &key);
+        }
+        Ok(Response::Normal(response))
+    }
+
+    /// Lee entradas de los streams como el consumidor indicado del grupo. Con el ID `>` (None) se
+    /// reciben las entradas que el grupo aún no entregó, y con otro ID se reciben las entradas
+    /// pendientes del consumidor.
+    ///
+    /// Si no hay entradas para ningún stream, se retorna nil.
+    fn xreadgroup_method(
+        &mut self,
+        group: String,
+        consumer: String,
+        count: Option<usize>,
+        no_ack: bool,
+        streams: Vec<(String, Option<StreamId>)>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XREADGROUP Received - group: ".to_string() + &*group,
+        ));
+
+        let now = self.clock.now();
+        let mut response = vec![];
+        for (key, id) in streams {
+            let mut new_consumer = false;
+            let entries = match self.db.get_mut(&key) {
+                Some(Re::Stream(stream)) => {
+                    new_consumer = stream
+                        .group(&group)
+                        .is_some_and(|group| !group.consumers.contains_key(&consumer));
+                    stream.read_group(&group, &consumer, id, count, no_ack, now)
+                }
+                Some(_) => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        WRONGTYPE_MSG.to_string(),
+                    ));
+                    return Err(WRONGTYPE_MSG.to_string());
+                }
+                None => None,
+            };
+            let entries = entries.ok_or_else(|| {
+                format!(
+                    "NOGROUP No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option",
+                    key, group
+                )
+            })?;
+
+            if new_consumer {
+                self.notify_keyspace_event(EventClass::Stream, "xgroup-createconsumer", &key);
+            }
+            // Entregar entradas nuevas modifica el grupo (y su lista de pendientes).
+            if id.is_none() && !entries.is_empty() {
+                self.dirty += 1;
+                self.notify_change("xreadgroup", &key);
+            }
+
+            // Las entradas pendientes se informan aunque no haya ninguna.
+            if !entries.is_empty() || id.is_some() {
+                response.push(Re::Array(vec![
+                    Re::String(key),
+                    Redis::stream_entries_element(entries),
+                ]));
+            }
+        }
+
+        if response.is_empty() {
+            Ok(Response::Normal(Re::Nil))
+        } else {
+            Ok(Response::Normal(Re::Array(response)))
+        }
+    }
+
+    /// Confirma las entradas indicadas, quitándolas de la lista de pendientes del grupo. Retorna la
+    /// cantidad de entradas confirmadas.
+    fn xack_method(
+        &mut self,
+        key: String,
+        group: String,
+        ids: Vec<StreamId>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XACK Received - key: ".to_string() + &*key,
+        ));
+
+        let acked = match self.db.get_mut(&key) {
+            Some(Re::Stream(stream)) => match stream.group_mut(&group) {
+                Some(group) => group.ack(&ids),
+                None => 0,
+            },
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => 0,
+        };
+
+        if acked > 0 {
+            self.dirty += acked as u64;
+            self.notify_change("xack", &key);
+        }
+        Ok(Response::Normal(Re::Integer(acked as i64)))
+    }
+
+    /// Retorna información sobre las entradas pendientes del grupo.
+    ///
+    /// Sin rango, retorna la cantidad de entradas pendientes, el menor y mayor ID, y la cantidad de
+    /// entradas pendientes de cada consumidor. Con rango, retorna para cada entrada su ID, su
+    /// consumidor, los milisegundos desde su última entrega y la cantidad de entregas.
+    fn xpending_method(
+        &mut self,
+        key: String,
+        group: String,
+        range: Option<PendingRange>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XPENDING Received - key: ".to_string() + &*key,
+        ));
+
+        let now = self.clock.now();
+        let consumer_group = match self.lookup_read(&key) {
+            Some(Re::Stream(stream)) => stream.group(&group),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => None,
+        };
+        let consumer_group = consumer_group.ok_or_else(|| {
+            format!(
+                "NOGROUP No such key '{}' or consumer group '{}'",
+                key, group
+            )
+        })?;
+        let pending = &consumer_group.pending;
+
+        let range = match range {
+            Some(range) => range,
+            None => {
+                let (first, last) = match (pending.keys().next(), pending.keys().last()) {
+                    (Some(first), Some(last)) => (first, last),
+                    _ => {
+                        return Ok(Response::Normal(Re::Array(vec![
+                            Re::Integer(0),
+                            Re::Nil,
+                            Re::Nil,
+                            Re::Nil,
+                        ])))
+                    }
+                };
+
+                let mut consumers: Vec<(String, usize)> = vec![];
+                for entry in pending.values() {
+                    match consumers
+                        .iter_mut()
+                        .find(|(name, _)| *name == entry.consumer)
+                    {
+                        Some((_, count)) => *count += 1,
+                        None => consumers.push((entry.consumer.clone(), 1)),
+                    }
+                }
+                consumers.sort();
+
+                return Ok(Response::Normal(Re::Array(vec![
+                    Re::Integer(pending.len() as i64),
+                    Re::String(first.to_string()),
+                    Re::String(last.to_string()),
+                    Re::Array(
+                        consumers
+                            .into_iter()
+                            .map(|(name, count)| {
+                                Re::Array(vec![Re::String(name), Re::String(count.to_string())])
+                            })
+                            .collect(),
+                    ),
+                ])));
+            }
+        };
+
+        if range.start > range.end {
+            return Ok(Response::Normal(Re::Array(vec![])));
+        }
+        let entries = pending
+            .range(range.start..=range.end)
+            .map(|(id, entry)| {
+                let idle = now.duration_since(entry.delivery_time).unwrap_or_default();
+                (id, entry, idle)
+            })
+            .filter(|(_, entry, idle)| {
+                range
+                    .consumer
+                    .as_ref()
+                    .is_none_or(|consumer| *consumer == entry.consumer)
+                    && range.min_idle.is_none_or(|min_idle| *idle >= min_idle)
+            })
+            .take(range.count)
+            .map(|(id, entry, idle)| {
+                Re::Array(vec![
+                    Re::String(id.to_string()),
+                    Re::String(entry.consumer.clone()),
+                    Re::Integer(idle.as_millis() as i64),
+                    Re::Integer(entry.delivery_count as i64),
+                ])
+            })
+            .collect();
+
+        Ok(Response::Normal(Re::Array(entries)))
+    }
+
+    /// Transfiere al consumidor indicado las entradas pendientes del grupo que llevan inactivas al
+    /// menos `min_idle`. Retorna las entradas transferidas, o solo sus IDs con JUSTID.
+    fn xclaim_method(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+        min_idle: Duration,
+        ids: Vec<StreamId>,
+        just_id: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command XCLAIM Received - key: ".to_string() + &*key,
+        ));
+
+        let mut new_consumer = false;
+        let claimed = match self.db.get_mut(&key) {
+            Some(Re::Stream(stream)) => {
+                new_consumer = stream
+                    .group(&group)
+                    .is_some_and(|group| !group.consumers.contains_key(&consumer));
+                stream.claim(&group, &consumer, min_idle, &ids, just_id, self.clock.now())
+            }
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                return Err(WRONGTYPE_MSG.to_string());
+            }
+            None => None,
+        };
+        let claimed = claimed.ok_or_else(|| {
+            format!(
+                "NOGROUP No such key '{}' or consumer group '{}'",
+                key, group
+            )
+        })?;
+
+        if new_consumer {
+            self.notify_keyspace_event(EventClass::Stream, "xgroup-createconsumer", &key);
+        }
+        if !claimed.is_empty() {
+            self.dirty += claimed.len() as u64;
+            self.notify_change("xclaim", &key);
+        }
+        if just_id {
+            Ok(Response::Normal(Re::List(
+                claimed.into_iter().map(|(id, _)| id.to_string()).collect(),
+            )))
+        } else {
+            Ok(Response::Normal(Redis::stream_entries_element(
+                claimed
+                    .into_iter()
+                    .map(|(id, fields)| (id, Some(fields)))
+                    .collect(),
+            )))
+        }
+    }
+
+    /// Arma la respuesta de las entradas de un stream: cada entrada se representa como su ID
+    /// seguido de la lista de campos y valores, o de nil si la entrada ya no existe.
+    fn stream_entries_element(entries: Vec<(StreamId, Option<StreamFields>)>) -> RedisElement {
+        Re::Array(
+            entries
+                .into_iter()
+                .map(|(id, fields)| {
+                    let fields = match fields {
+                        Some(fields) => Re::List(
+                            fields
+                                .into_iter()
+                                .flat_map(|(field, value)| vec![field, value])
+                                .collect(),
+                        ),
+                        None => Re::Nil,
+                    };
+                    Re::Array(vec![Re::String(id.to_string()), fields])
+                })
+                .collect(),
+        )
+    }
+
+    /// Retorna todas las claves que hacen match con un patrón.
+    fn keys_method(&mut self, pattern: String) -> Vec<String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command KEYS Received".to_string(),
+        ));
+
+        let mut vector = vec![];
+        for key in self.db.keys_purged() {
+            if let Ok(re) = Regex::new(&pattern) {
+                if re.is_match(key) {
+                    vector.push(key.to_string());
+                }
+            }
+        }
+        vector
+    }
+
+    /// Comando interno para Persistir los elementos de la Base de datos en un archivo
+    ///
+    /// El guardado se realiza en un hilo aparte a partir de una copia de la base de datos, por lo
+    /// que no bloquea la ejecución del resto de los comandos. No se admite más de un guardado a la
+    /// vez.
+    fn store_method(&mut self, path: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command STORE Received - path: ".to_string() + &*path,
+        ));
+
+        if let Some(handle) = self.background_save.take() {
+            if !handle.is_finished() {
+                self.background_save = Some(handle);
+                return Err("ERR Background save already in progress".to_string());
+            }
+            let _ = handle.join();
+        }
+
+        let snapshot = self.db.snapshot();
+        self.dirty = 0;
+        self.last_save = self.clock.now();
+        let log_sender = self.log_sender.clone();
+        self.background_save = Some(thread::spawn(move || {
+            if let Err(e) = Redis::write_rdb(&snapshot, &path) {
+                let _ = log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    e.to_string(),
+                ));
+            }
+        }));
+
+        Ok(Response::Normal(RedisElement::SimpleString(
+            "Background saving started".to_string(),
+        )))
+    }
+
+    /// Serializa la copia de la base de datos y la escribe en el archivo indicado.
+    ///
+    /// Se escribe primero un archivo temporal que luego se renombra, de modo que el archivo nunca
+    /// quede a medio escribir si el servidor se detiene durante el guardado.
+    fn write_rdb(db: &Keyspace, path: &str) -> std::io::Result<()> {
+        let temp_path = path.to_string() + ".tmp";
+        fs::write(&temp_path, db.serialize())?;
+        fs::rename(temp_path, path)
+    }
+
+    /// Comando interno para Cargar los elementos de la Base de datos desde un archivo
+    fn load_method(&mut self, path: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LOAD Received - path: ".to_string() + &*path,
+        ));
+
+        let stream = match fs::read(path) {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    format!("{:?}", e),
+                ));
+                return Err(format!("{:?}", e));
+            }
+        };
+
+        match TtlHashMap::deserialize_with_clock(&stream, Arc::clone(&self.clock)) {
+            Ok(mut map) => {
+                map.set_access_tracking(self.db.tracks_access());
+                self.db = Keyspace::from_map(map);
+                self.dirty = 0;
+                self.last_save = self.clock.now();
+                Ok(Response::Normal(RedisElement::SimpleString(
+                    "OK".to_string(),
+                )))
+            }
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    e.to_string(),
+                ));
+                Err(e.to_string())
+            }
+        }
+    }
+
+    /// El comando CONFIG GET se utiliza para leer los parámetros de configuración de un servidor en
+    /// ejecución. Retorna alternadamente el nombre y el valor de cada parámetro cuyo nombre
+    /// coincide con alguno de los patrones glob indicados (por ejemplo `*` o `db*`).
+    fn config_get_method(&mut self, patterns: Vec<String>) -> Vec<String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command CONFIG GET Received".to_string(),
+        ));
+
+        let parameters = self.config.lock().unwrap().parameters();
+        parameters
+            .into_iter()
+            .filter(|(name, _)| {
+                patterns
+                    .iter()
+                    .any(|pattern| glob_match(pattern, name, true))
+            })
+            .flat_map(|(name, value)| vec![name, value])
+            .collect()
+    }
+
+    /// El comando CONFIG RESETSTAT descarta las estadísticas de ejecución de los comandos que se
+    /// muestran en INFO commandstats, y las estadísticas de INFO stats.
+    fn config_resetstat_method(&mut self) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command CONFIG RESETSTAT Received".to_string(),
+        ));
+
+        self.command_stats.reset();
+        self.keyspace_hits = 0;
+        self.keyspace_misses = 0;
+        self.expired_keys = 0;
+        self.evicted_keys = 0;
+        self.pubsub_dropped_messages = 0;
+        self.pubsub_disconnections = 0;
+        self.server_stats.lock().unwrap().reset();
+        Response::Normal(Re::SimpleString("OK".to_string()))
+    }
+
+    /// El comando CONFIG SET se utiliza para reconfigurar un servidor en tiempo de ejecución sin
+    /// necesidad de reiniciarlo.
+    fn config_set_method(&mut self, parameter: String, value: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command CONFIG SET Received - parameter: ".to_string() + &parameter,
+        ));
+        let mut config = self.config.lock().unwrap();
+
+        // El logger y el servidor leen de la configuración compartida el nivel de log y el
+        // timeout, por lo que los cambios se aplican a los próximos logs y conexiones.
+        let result = match parameter.as_str() {
+            "verbose" => {
+                config.set_verbose(value);
+                Ok(())
+            }
+            "dbfilename" => {
+                config.set_dbfilename(value);
+                Ok(())
+            }
+            "logfile" => {
+                config.set_logfile(value);
+                Ok(())
+            }
+            "requirepass" => {
+                config.set_requirepass(value);
+                Ok(())
+            }
+            "notify-keyspace-events" => config.set_notify_keyspace_events(value),
+            "timeout" => config.set_timeout(value),
+            "maxmemory" => config.set_maxmemory(value),
+            "maxmemory-policy" => config.set_maxmemory_policy(value),
+            "pubsub-queue-size" => config.set_pubsub_queue_size(value),
+            "pubsub-overflow-policy" => config.set_pubsub_overflow_policy(value),
+            "slowlog-log-slower-than" => config.set_slowlog_log_slower_than(value),
+            "slowlog-max-len" => config.set_slowlog_max_len(value),
+            "max-key-length" => config.set_max_key_length(value),
+            "max-value-size" => config.set_max_value_size(value),
+            "max-collection-elements" => config.set_max_collection_elements(value),
+            "client-max-commands-per-sec" => config.set_client_max_commands_per_sec(value),
+            "client-rate-limit-policy" => config.set_client_rate_limit_policy(value),
+            "track-key-access" => {
+                let result = config.set_track_key_access(value);
+                self.db.set_access_tracking(config.is_track_key_access());
+                result
+            }
+            "save" => config.set_save(value),
+            "loglevel" => config.set_loglevel(value),
+            "logtarget" => config.set_logtarget(value),
+            _ => Err("Parameter does not exist".to_string()),
+        };
+
+        if let Err(e) = result {
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Error,
+                line!(),
+                column!(),
+                file!().to_string(),
+                e.clone(),
+            ));
+            return Err(e);
+        }
+        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+    }
+
+    /// Valida la contraseña recibida contra el parámetro `requirepass` de la configuración.
+    /// Retorna error si la contraseña es incorrecta o si el servidor no tiene contraseña
+    /// configurada.
+    fn auth_method(&mut self, password: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command AUTH Received".to_string(),
+        ));
+
+        let requirepass = self.config.lock().unwrap().get_requirepass();
+        if requirepass.is_empty() {
+            return Err(
+                "ERR AUTH <password> called without any password configured for the \
+            default user. Are you sure your configuration is correct?"
+                    .to_string(),
+            );
+        }
+
+        if requirepass != password {
+            return Err(
+                "WRONGPASS invalid username-password pair or user is disabled.".to_string(),
+            );
+        }
+
+        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+    }
+}
+
+/// Escribe el valor de la clave en el shard como SET, respetando las opciones indicadas. Devuelve
+/// la respuesta del comando y si se escribió el valor.
+///
+/// Con NX solo se escribe el valor si la clave no existe, y con XX solo si la clave ya existe. La
+/// expiración indicada se aplica junto con el valor, y con KEEPTTL se conserva la expiración previa
+/// de la clave en lugar de descartarla. Con GET se responde el valor previo de la clave (nil si no
+/// existía) en lugar de OK, se haya escrito o no el valor; si el valor previo no es un string se
+/// retorna error sin escribirlo.
+fn set_in_shard(
+    db: &mut Shard,
+    key: String,
+    value: String,
+    options: &SetOptions,
+) -> Result<(Re, bool), String> {
+    let previous = if options.get {
+        match db.peek(&key) {
+            Some(previous) => match previous.string_reply() {
+                Some(previous) => Some(previous),
+                None => return Err(WRONGTYPE_MSG.to_string()),
+            },
+            None => Some(Re::Nil),
+        }
+    } else {
+        None
+    };
+    let exists = db.contains_key(&key);
+    let should_set = match options.condition {
+        SetCondition::Always => true,
+        SetCondition::IfNotExists => !exists,
+        SetCondition::IfExists => exists,
+    };
+    if !should_set {
+        return Ok((previous.unwrap_or(Re::Nil), false));
+    }
+
+    let previous_ttl = match options.expiration {
+        SetExpiration::Keep => db.delete_ttl(&key),
+        _ => None,
+    };
+    db.insert(key.clone(), Re::from_string(value));
+    match options.expiration {
+        SetExpiration::Relative(duration) => {
+            db.set_ttl_relative(key, duration);
+        }
+        SetExpiration::Absolute(ttl) => {
+            db.set_ttl_absolute(key, ttl);
+        }
+        SetExpiration::Keep => {
+            if let Some(ttl) = previous_ttl {
+                db.set_ttl_absolute(key, ttl);
+            }
+        }
+        SetExpiration::Discard => {}
+    }
+
+    let reply = previous.unwrap_or_else(|| Re::SimpleString("OK".to_string()));
+    Ok((reply, true))
+}
+
+/// Incrementa el número almacenado en la clave del shard, como INCRBY, y devuelve el nuevo valor.
+/// Si la clave no existe, es seteada a 0 antes de realizar la operación.
+///
+/// Devuelve error si la clave contiene un valor de tipo erróneo, un string que no puede ser
+/// representado como entero de 64 bits, o si la operación produce overflow.
+fn incrby_in_shard(db: &mut Shard, key: &str, increment: i64) -> Result<i64, String> {
+    let key = key.to_string();
+    let current = match db.get(&key) {
+        Some(Re::Integer(value)) => *value,
+        Some(value @ (Re::String(_) | Re::Bytes(_))) => value
+            .as_string()
+            .unwrap_or_default()
+            .parse::<i64>()
+            .map_err(|_| OUT_OF_RANGE_MSG.to_string())?,
+        Some(_) => return Err(WRONGTYPE_MSG.to_string()),
+        None => 0,
+    };
+    let result = current
+        .checked_add(increment)
+        .ok_or_else(|| OVERFLOW_MSG.to_string())?;
+
+    // Se modifica el valor en el lugar para conservar la expiración de la clave.
+    match db.get_mut(&key) {
+        Some(value) => *value = Re::Integer(result),
+        None => db.insert(key, Re::Integer(result)),
+    }
+    Ok(result)
+}
+
+/// Agrega el valor al final del string guardado en la clave del shard, como APPEND, y devuelve el
+/// largo resultante. Si la clave no existe, es creada con el valor. Conserva la expiración de la
+/// clave, y devuelve error si la clave no contiene un string.
+fn append_in_shard(db: &mut Shard, key: &str, value: String) -> Result<usize, String> {
+    let key = key.to_string();
+    // Se modifica el valor en el lugar para conservar la expiración de la clave.
+    match db.get_mut(&key) {
+        Some(Re::String(s)) => {
+            s.push_str(&value);
+            Ok(s.len())
+        }
+        Some(Re::Bytes(bytes)) => {
+            bytes.extend_from_slice(value.as_bytes());
+            Ok(bytes.len())
+        }
+        // Como en Redis, agregar al final de un string con codificación `int` lo convierte en un
+        // string común.
+        Some(stored @ Re::Integer(_)) => {
+            let appended = format!("{}{}", stored, value);
+            let len = appended.len();
+            *stored = Re::String(appended);
+            Ok(len)
+        }
+        Some(_) => Err(WRONGTYPE_MSG.to_string()),
+        None => {
+            let len = value.len();
+            db.insert(key, Re::from_string(value));
+            Ok(len)
+        }
+    }
+}
+
+/// Formatea la respuesta de TTL: los segundos restantes, -1 si la clave no tiene timeout y -2 si
+/// no existe.
+fn format_ttl(ttl: Option<Duration>) -> i64 {
+    match ttl {
+        Some(value) if value == Duration::from_secs(0) => -1,
+        Some(value) => value.as_secs() as i64,
+        None => -2,
+    }
+}
+
+/// Escribe el resultado de INCRBYFLOAT como Redis (`%.17Lg`): redondeado a `FLOAT_DIGITS` dígitos
+/// significativos, sin notación exponencial y sin ceros finales (ej: 3.0 -> "3").
+fn format_float(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let exponential = format!("{:.*e}", FLOAT_DIGITS - 1, value);
+    let (mantissa, exponent) = exponential.split_once('e').unwrap_or((&exponential, "0"));
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    // Posición del punto decimal dentro de los dígitos.
+    let point = exponent.parse::<i32>().unwrap_or(0) + 1;
+
+    let mut text = if point <= 0 {
+        format!("0.{}{}", "0".repeat(point.unsigned_abs() as usize), digits)
+    } else if point as usize >= digits.len() {
+        digits.clone() + &"0".repeat(point as usize - digits.len())
+    } else {
+        format!(
+            "{}.{}",
+            &digits[..point as usize],
+            &digits[point as usize..]
+        )
+    };
+    if text.contains('.') {
+        text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    if value < 0.0 {
+        text.insert(0, '-');
+    }
+    text
+}
+
+/// Devuelve la posición del miembro en el orden en el que lo recorre SSCAN: un hash de su
+/// contenido, que no cambia al agregar o quitar otros miembros del set.
+fn scan_position(member: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    member.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Arma el mensaje de error de un script con el formato de Redis, indicando el script y la línea
+/// en la que ocurrió.
+fn script_error(sha1: &str, error: ScriptError) -> String {
+    match error {
+        ScriptError::Compile { line, message } => format!(
+            "ERR Error compiling script (new function): user_script:{}: {}",
+            line, message
+        ),
+        ScriptError::Runtime { line, message } => format!(
+            "ERR user_script:{}: {} script: {}, on @user_script:{}.",
+            line, message, sha1, line
+        ),
+        ScriptError::Command { line, message } => {
+            format!("{} script: {}, on @user_script:{}.", message, sha1, line)
+        }
+        ScriptError::Reply(message) => message,
+    }
+}
+
+#[allow(unused_imports, clippy::clone_on_copy, clippy::redundant_field_names)]
+mod test {
+    use crate::config::server_config::SaveRule;
+    use crate::entities::bitmap::{BitOperation, BitUnit};
+    use crate::entities::client_param::{ClientParam, KillFilter};
+    use crate::entities::clock::MockClock;
+    use crate::entities::cluster::Cluster;
+    use crate::entities::cluster_param::ClusterParam;
+    use crate::entities::command::Command;
+    use crate::entities::command_param::CommandParam;
+    use crate::entities::command_table::COMMAND_TABLE;
+    use crate::entities::consumer_group_param::{PendingRange, XgroupParam};
+    use crate::entities::debug_param::DebugParam;
+    use crate::entities::expire_condition::ExpireCondition;
+    use crate::entities::geo::{GeoAddOptions, GeoOrigin, GeoSearchOptions, GeoShape, GeoUnit};
+    use crate::entities::info_param::InfoParam;
+    use crate::entities::key_change::KeyChange;
+    use crate::entities::memory_param::MemoryParam;
+    use crate::entities::message_queue::{MessageReceiver, OverflowPolicy};
+    use crate::entities::object_param::ObjectParam;
+    use crate::entities::pubsub_param::PubSubParam;
+    use crate::entities::rdb;
+    use crate::entities::script_param::ScriptParam;
+    use crate::entities::set_options::{SetCondition, SetExpiration, SetOptions};
+    use crate::entities::slowlog_param::SlowlogParam;
+    use crate::entities::sort_options::SortOptions;
+    use crate::entities::sorted_set::{Aggregate, ScoreBound};
+    use crate::entities::stream::StreamId;
+    use crate::service::redis::TtlHashMap;
+    use crate::service::redis::{
+        format_float, KEY_TOO_LONG_MSG, NAN_SCORE_MSG, OOM_MSG, TOO_MANY_ELEMENTS_MSG,
+        VALUE_TOO_BIG_MSG,
+    };
+    use crate::service::redis::{Re, Redis, Response};
+    use std::collections::HashSet;
+    use std::fs;
+    use std::io::Write;
+    use std::process;
+    use std::sync::mpsc::Receiver;
+    use std::sync::{Arc, RwLock};
+    use std::thread::{self, sleep};
+    use std::time::{Duration, SystemTime};
+
+    #[allow(dead_code)]
+    fn wait_for_background_save(redis: &mut Redis) {
+        if let Some(handle) = redis.background_save.take() {
+            handle.join().unwrap();
+        }
+    }
+
+    #[allow(dead_code)]
+    fn eq_response(content: Re, response: Response) -> bool {
+        if let Response::Normal(redis_element) = response {
+            return content == redis_element;
+        };
+        false
+    }
+
+    #[test]
+    fn test_strlen_element_fail_if_is_not_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let strlen = redis.execute(Command::Strlen { key });
+
+        assert!(strlen.is_err());
+    }
+
+    #[ignore]
+    #[test]
+    fn test_strlen_element_not_found() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let strlen = redis.execute(Command::Strlen { key });
+
+        assert!(strlen.is_ok());
+        assert!(eq_response(Re::Integer(0), strlen.unwrap()));
+    }
+
+    #[test]
+    fn test_strlen_element_saved_before() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".to_string();
+        let key = "hola".to_string();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "hola".to_string();
+        let strlen = redis.execute(Command::Strlen { key });
+
+        assert!(strlen.is_ok());
+        assert!(eq_response(Re::Integer(5), strlen.unwrap()));
+    }
+
+    #[allow(unused_imports)]
+    #[test]
+    fn test_set_element_and_get_the_same() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".to_string();
+        let key = "hola".to_string();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "hola".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_set_element_twice_and_get_the_last_set() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "hola".to_string();
+        let value = "chau".to_string();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "hola".to_string();
+        let value = "test".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "hola".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("test".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_get_on_empty_key_returns_nil() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "hola".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::Nil, get.unwrap()));
+    }
+
+    #[test]
+    fn test_get_element_fail_if_is_not_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_err());
+    }
+
+    #[test]
+    fn test_getset_fails_if_is_not_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let getset = redis.execute(Command::Getset { key, value });
+
+        assert!(getset.is_err());
+    }
+
+    #[test]
+    fn test_getset_on_empty_key_returns_nil() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let getset = redis.execute(Command::Getset { key, value });
+
+        assert!(getset.is_ok());
+        assert!(eq_response(Re::Nil, getset.unwrap()));
+    }
+
+    #[test]
+    fn test_getset_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "1".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let getset = redis.execute(Command::Getset { key, value });
+        assert!(getset.is_ok());
+        assert!(eq_response(Re::String("1".to_string()), getset.unwrap()));
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_ping_returns_pong() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let ping = redis.execute(Command::Ping { message: None });
+
+        assert!(ping.is_ok());
+        assert!(eq_response(
+            Re::SimpleString("PONG".to_string()),
+            ping.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_incrby_with_2_as_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "1".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let increment: i64 = 1;
+        let _incrby = redis.execute(Command::Incrby { key, increment });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        let key = "key".to_string();
+        let increment: i64 = 2;
+        let _incrby = redis.execute(Command::Incrby { key, increment });
+
+        let key = "key".to_string();
+        let second_get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("2".to_string()), get.unwrap()));
+
+        assert!(second_get.is_ok());
+        assert!(eq_response(
+            Re::String("4".to_string()),
+            second_get.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_incrby_value_err_initial_value_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "hola".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let increment: i64 = 1;
+        let incrby = redis.execute(Command::Incrby { key, increment });
+
+        assert!(incrby.is_err());
+    }
+
+    #[test]
+    fn test_incrby_not_saved_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let increment: i64 = 1;
+        let _incrby = redis.execute(Command::Incrby { key, increment });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("1".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_decrby_on_new_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let decrement: i64 = 3;
+        let _decrby = redis.execute(Command::Decrby { key, decrement });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("-3".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_decrby_on_existing_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "5".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let decrement: i64 = 3;
+        let _decrby = redis.execute(Command::Decrby { key, decrement });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("2".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_mset_sets_2_values() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key_values = vec![
+            ("key1".to_string(), "value1".to_string()),
+            ("key2".to_string(), "value2".to_string()),
+        ];
+        let _mset = redis.execute(Command::Mset { key_values });
+
+        let key = "key1".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+
+        let key = "key2".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("value2".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_mget_gets_2_values() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key_values = vec![
+            ("key1".to_string(), "value1".to_string()),
+            ("key2".to_string(), "value2".to_string()),
+        ];
+        let _mset = redis.execute(Command::Mset { key_values });
+
+        let keys = vec!["key1".to_string(), "key2".to_string()];
+        let mget = redis.execute(Command::Mget { keys });
+
+        assert!(mget.is_ok());
+        assert!(eq_response(
+            Re::Array(vec![
+                Re::String("value1".to_string()),
+                Re::String("value2".to_string())
+            ]),
+            mget.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_mget_nil_for_missing_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let keys = vec!["key".to_string(), "key_empty".to_string()];
+        let mget = redis.execute(Command::Mget { keys });
+
+        assert!(mget.is_ok());
+        assert!(eq_response(
+            Re::Array(vec![Re::String("value".to_string()), Re::Nil]),
+            mget.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_mget_nil_for_non_string_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key_list".to_string();
+        let value = vec!["value1".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let keys = vec!["key".to_string(), "key_list".to_string()];
+        let mget = redis.execute(Command::Mget { keys });
+
+        assert!(mget.is_ok());
+        assert!(eq_response(
+            Re::Array(vec![Re::String("value".to_string()), Re::Nil]),
+            mget.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_set_element_and_getdel() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".to_string();
+        let key = "key".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        let key = "key".to_string();
+        let getdel = redis.execute(Command::Getdel { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+
+        assert!(getdel.is_ok());
+        assert!(eq_response(
+            Re::String("value".to_string()),
+            getdel.unwrap(),
+        ));
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+    }
+
+    #[test]
+    fn test_getdel_on_missing_key_returns_nil() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let getdel = redis.execute(Command::Getdel { key });
+
+        assert!(eq_response(Re::Nil, getdel.unwrap()));
+    }
+
+    #[test]
+    fn test_dbsize() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Re::Integer(0), dbsize.unwrap()));
+
+        let value = "value".to_string();
+        let key = "key".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Re::Integer(1), dbsize.unwrap()));
+
+        let key = "key".to_string();
+        let _getdel = redis.execute(Command::Getdel { key });
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Re::Integer(0), dbsize.unwrap()));
+    }
+
+    #[test]
+    fn test_dbsize_excludes_expired_keys() {
+        let mut redis: Redis = Redis::new_for_test();
+        for key in ["a", "b", "c"].iter() {
+            let _set = redis.execute(Command::Set {
+                key: key.to_string(),
+                value: "value".to_string(),
+                options: SetOptions::default(),
+            });
+        }
+        redis
+            .db
+            .set_ttl_absolute("a".to_string(), SystemTime::UNIX_EPOCH);
+        redis
+            .db
+            .set_ttl_absolute("b".to_string(), SystemTime::UNIX_EPOCH);
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Re::Integer(1), dbsize.unwrap()));
+        assert_eq!(2, redis.expired_keys);
+
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Stats,
+        });
+        match info.unwrap() {
+            Response::Normal(Re::String(info)) => assert!(info.contains("\r\nexpired_keys:2\r\n")),
+            _ => panic!("INFO stats did not return a string"),
+        }
+    }
+
+    #[test]
+    fn test_set_element_and_del() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".to_string();
+        let key = "key".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let keys = vec!["key".to_string()];
+        let del = redis.execute(Command::Del { keys });
+        assert!(eq_response(Re::Integer(1), del.unwrap()));
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+    }
+
+    #[test]
+    fn test_pexpire_and_pttl() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+
+        let pttl = redis.execute(Command::Pttl {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Integer(-1), pttl.unwrap()));
+
+        let pexpire = redis.execute(Command::Pexpire {
+            key: "key".to_string(),
+            ttl: Duration::from_millis(1500),
+            conditions: vec![],
+        });
+        assert!(eq_response(Re::Integer(1), pexpire.unwrap()));
+
+        match redis.execute(Command::Pttl {
+            key: "key".to_string(),
+        }) {
+            Ok(Response::Normal(Re::Integer(pttl))) => assert!(pttl > 1000 && pttl <= 1500),
+            _ => panic!("PTTL did not return a number"),
+        }
+        let pttl = redis.execute(Command::Pttl {
+            key: "other".to_string(),
+        });
+        assert!(eq_response(Re::Integer(-2), pttl.unwrap()));
+    }
+
+    #[test]
+    fn test_expire_deadline_overflow_err() {
+        let mut redis: Redis = Redis::new_for_test();
+        let clock = SystemTime::UNIX_EPOCH + Duration::from_secs(i64::MAX as u64 - 10);
+        redis.set_clock(Arc::new(MockClock::new(clock)));
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+
+        let expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(60),
+            conditions: vec![ExpireCondition::Greater],
+        });
+        assert_eq!(
+            "ERR invalid expire time in 'expire' command",
+            expire.err().unwrap()
+        );
+
+        let pexpire = redis.execute(Command::Pexpire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(60),
+            conditions: vec![],
+        });
+        assert_eq!(
+            "ERR invalid expire time in 'pexpire' command",
+            pexpire.err().unwrap()
+        );
+
+        let ttl = redis.execute(Command::Ttl {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Integer(-1), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_expire_with_non_positive_time_deletes_key() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+
+        let expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(0),
+            conditions: vec![ExpireCondition::HasExpiry],
+        });
+        assert!(eq_response(Re::Integer(0), expire.unwrap()));
+
+        let expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(0),
+            conditions: vec![],
+        });
+        assert!(eq_response(Re::Integer(1), expire.unwrap()));
+
+        let exists = redis.execute(Command::Exists {
+            keys: vec!["key".to_string()],
+        });
+        assert!(eq_response(Re::Integer(0), exists.unwrap()));
+        assert_eq!(0, redis.db.len());
+    }
+
+    #[test]
+    fn test_expire_with_conditions() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+
+        let expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(100),
+            conditions: vec![ExpireCondition::HasExpiry],
+        });
+        assert!(eq_response(Re::Integer(0), expire.unwrap()));
+
+        let expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(100),
+            conditions: vec![ExpireCondition::NoExpiry],
+        });
+        assert!(eq_response(Re::Integer(1), expire.unwrap()));
+
+        let expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(50),
+            conditions: vec![ExpireCondition::Greater],
+        });
+        assert!(eq_response(Re::Integer(0), expire.unwrap()));
+
+        let expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(50),
+            conditions: vec![ExpireCondition::Less],
+        });
+        assert!(eq_response(Re::Integer(1), expire.unwrap()));
+
+        let ttl = redis.execute(Command::Ttl {
+            key: "key".to_string(),
+        });
+        match ttl.unwrap() {
+            Response::Normal(Re::Integer(ttl)) => assert!(ttl == 49 || ttl == 50),
+            _ => panic!("TTL did not return a number"),
+        }
+    }
+
+    #[test]
+    fn test_expiretime_and_pexpiretime() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+
+        let expiretime = redis.execute(Command::Expiretime {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Integer(-1), expiretime.unwrap()));
+
+        let _expireat = redis.execute(Command::Pexpireat {
+            key: "key".to_string(),
+            ttl: SystemTime::UNIX_EPOCH + Duration::from_millis(33177117420500),
+            conditions: vec![],
+        });
+        let expiretime = redis.execute(Command::Expiretime {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Integer(33177117420), expiretime.unwrap()));
+        let pexpiretime = redis.execute(Command::Pexpiretime {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(
+            Re::Integer(33177117420500),
+            pexpiretime.unwrap()
+        ));
+
+        let expiretime = redis.execute(Command::Expiretime {
+            key: "other".to_string(),
+        });
+        assert!(eq_response(Re::Integer(-2), expiretime.unwrap()));
+    }
+
+    #[test]
+    fn test_randomkey() {
+        let mut redis: Redis = Redis::new_for_test();
+        let randomkey = redis.execute(Command::Randomkey);
+        assert!(eq_response(Re::Nil, randomkey.unwrap()));
+
+        for key in ["key1", "key2"].iter() {
+            let _set = redis.execute(Command::Set {
+                key: key.to_string(),
+                value: "value".to_string(),
+                options: SetOptions::default(),
+            });
+        }
+
+        match redis.execute(Command::Randomkey).unwrap() {
+            Response::Normal(Re::String(key)) => assert!(key == "key1" || key == "key2"),
+            _ => panic!("RANDOMKEY did not return a key"),
+        }
+    }
+
+    #[test]
+    fn test_unlink_removes_keys() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".to_string(),
+            value: (0..1000).map(|i| i.to_string()).collect(),
+        });
+
+        let keys = vec!["key".to_string(), "list".to_string(), "other".to_string()];
+        let unlink = redis.execute(Command::Unlink { keys });
+        assert!(eq_response(Re::Integer(2), unlink.unwrap()));
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Re::Integer(0), dbsize.unwrap()));
+    }
+
+    #[test]
+    fn test_set_two_elements_and_del_both() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".to_string();
+        let key = "key1".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let value = "value".to_string();
+        let key = "key2".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let keys = vec!["key1".to_string(), "key2".to_string()];
+        let del = redis.execute(Command::Del { keys });
+
+        assert!(eq_response(Re::Integer(2), del.unwrap()));
+    }
+
+    #[test]
+    fn test_append_adds_word() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let value = " appended".to_string();
+        let _append = redis.execute(Command::Append { key, value });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(
+            Re::String("value appended".to_string()),
+            get.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_append_on_non_existent_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = " appended".to_string();
+        let _append = redis.execute(Command::Append { key, value });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(eq_response(
+            Re::String(" appended".to_string()),
+            get.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_append_returns_length_and_keeps_ttl() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "hello".to_string(),
+            options: SetOptions {
+                condition: SetCondition::Always,
+                expiration: SetExpiration::Relative(Duration::from_secs(100)),
+                get: false,
+            },
+        });
+
+        let append = redis.execute(Command::Append {
+            key: "key".to_string(),
+            value: " world".to_string(),
+        });
+        assert!(eq_response(Re::Integer(11), append.unwrap()));
+        assert!(redis.db.get_ttl(&"key".to_string()).is_some());
+    }
+
+    #[test]
+    fn test_set_get_returns_previous_value() {
+        let mut redis: Redis = Redis::new_for_test();
+        let options = SetOptions {
+            get: true,
+            ..SetOptions::default()
+        };
+
+        let set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "first".to_string(),
+            options: options.clone(),
+        });
+        assert!(eq_response(Re::Nil, set.unwrap()));
+
+        let set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "second".to_string(),
+            options: SetOptions {
+                condition: SetCondition::IfNotExists,
+                ..options.clone()
+            },
+        });
+        assert!(eq_response(Re::String("first".to_string()), set.unwrap()));
+
+        let set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "third".to_string(),
+            options: options.clone(),
+        });
+        assert!(eq_response(Re::String("first".to_string()), set.unwrap()));
+
+        let _lpush = redis.execute(Command::Lpush {
+            key: "list".to_string(),
+            value: vec!["a".to_string()],
+        });
+        let set = redis.execute(Command::Set {
+            key: "list".to_string(),
+            value: "value".to_string(),
+            options,
+        });
+        assert!(set.unwrap_err().starts_with("WRONGTYPE"));
+    }
+
+    #[test]
+    fn test_set_two_elements_and_check_exists_equal_2() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key2".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let keys = vec!["key1".to_string(), "key2".to_string()];
+        let exists = redis.execute(Command::Exists { keys });
+        assert!(eq_response(Re::Integer(2), exists.unwrap()));
+
+        let keys = vec!["key1".to_string(), "key2".to_string(), "key3".to_string()];
+        let exists = redis.execute(Command::Exists { keys });
+        assert!(eq_response(Re::Integer(2), exists.unwrap()));
+    }
+
+    #[test]
+    fn test_copy_on_existing_key_returns_0() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".to_string();
+        let value = "value1".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key2".to_string();
+        let value = "value2".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key_origin: String = "key1".to_string();
+        let key_destination: String = "key2".to_string();
+        let copy = redis.execute(Command::Copy {
+            key_destination,
+            key_origin,
+        });
+
+        assert!(eq_response(Re::Integer(0), copy.unwrap()));
+    }
+
+    #[test]
+    fn test_copy_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".to_string();
+        let value = "value1".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key_origin: String = "key1".to_string();
+        let key_destination: String = "key2".to_string();
+        let _copy = redis.execute(Command::Copy {
+            key_destination,
+            key_origin,
+        });
+
+        let key = "key2".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_copy_serves_blocked_clients() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let blpop = redis.execute(Command::Blpop {
+            keys: vec!["key2".to_string()],
+            timeout: None,
+        });
+        let receiver = match blpop.unwrap() {
+            Response::Blocked(receiver) => receiver,
+            _ => panic!("BLPOP should block on an empty list"),
+        };
+
+        let key = "key1".to_string();
+        let value = vec!["a".to_string(), "b".to_string()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
+        let _copy = redis.execute(Command::Copy {
+            key_origin: "key1".to_string(),
+            key_destination: "key2".to_string(),
+        });
+
+        assert_eq!(
+            Re::List(vec!["key2".to_string(), "a".to_string()]),
+            receiver.recv().unwrap()
+        );
+        let key = "key2".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(eq_response(Re::Integer(1), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_expire_deletes_key() {
+        let mut redis: Redis = Redis::new_for_test();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        redis.set_clock(clock.clone());
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let ttl = Duration::from_secs(1);
+        let expire = redis.execute(Command::Expire {
+            key,
+            ttl,
+            conditions: vec![],
+        });
+
+        clock.advance(Duration::from_secs(1));
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+        assert!(eq_response(Re::Integer(1), expire.unwrap()));
+    }
+
+    #[test]
+    fn test_expire_returns_0_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let ttl = Duration::from_secs(1);
+        let expire = redis.execute(Command::Expire {
+            key,
+            ttl,
+            conditions: vec![],
+        });
+
+        assert!(eq_response(Re::Integer(0), expire.unwrap()));
+    }
+
+    #[test]
+    fn test_expireat_with_past_time_deletes_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
+        let expire = redis.execute(Command::Expireat {
+            key,
+            ttl,
+            conditions: vec![],
+        });
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+        assert!(eq_response(Re::Integer(1), expire.unwrap()));
+    }
+
+    #[test]
+    fn test_expireat_returns_0_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
+        let expire = redis.execute(Command::Expireat {
+            key,
+            ttl,
+            conditions: vec![],
+        });
+
+        assert!(eq_response(Re::Integer(0), expire.unwrap()));
+    }
+
+    #[test]
+    fn test_persist_deletes_expire_time() {
+        let mut redis: Redis = Redis::new_for_test();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        redis.set_clock(clock.clone());
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let ttl = Duration::from_secs(1);
+        let _expire = redis.execute(Command::Expire {
+            key,
+            ttl,
+            conditions: vec![],
+        });
+
+        let key = "key".to_string();
+        let persist = redis.execute(Command::Persist { key });
+
+        clock.advance(Duration::from_secs(1));
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(eq_response(Re::Integer(1), persist.unwrap()));
+        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_persist_returns_0_on_persistent_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let persist = redis.execute(Command::Persist { key });
+
+        let key: String = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(eq_response(Re::Integer(0), persist.unwrap()));
+        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_persist_returns_0_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let persist = redis.execute(Command::Persist { key });
+        assert!(eq_response(Re::Integer(0), persist.unwrap()));
+    }
+
+    #[test]
+    fn test_set_and_rename() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".to_string();
+        let value = "value1".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key_origin: String = "key1".to_string();
+        let key_destination: String = "key2".to_string();
+        let rename = redis.execute(Command::Rename {
+            key_origin,
+            key_destination,
+        });
+        assert!(rename.is_ok());
+
+        let key = "key1".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+
+        let key = "key2".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_rename_keeps_type_and_ttl() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".to_string(),
+            value: vec!["a".to_string(), "b".to_string()],
+        });
+        let _expire = redis.execute(Command::Expire {
+            key: "list".to_string(),
+            ttl: Duration::from_secs(100),
+            conditions: vec![],
+        });
+
+        let rename = redis.execute(Command::Rename {
+            key_origin: "list".to_string(),
+            key_destination: "other".to_string(),
+        });
+        assert!(rename.is_ok());
+
+        let lrange = redis.execute(Command::Lrange {
+            key: "other".to_string(),
+            begin: 0,
+            end: -1,
+        });
+        assert!(eq_response(
+            Re::List(vec!["a".to_string(), "b".to_string()]),
+            lrange.unwrap()
+        ));
+        let ttl = redis.execute(Command::Ttl {
+            key: "other".to_string(),
+        });
+        match ttl.unwrap() {
+            Response::Normal(Re::Integer(ttl)) => assert!(ttl == 99 || ttl == 100),
+            _ => panic!("TTL did not return a number"),
+        }
+
+        let rename = redis.execute(Command::Rename {
+            key_origin: "list".to_string(),
+            key_destination: "other".to_string(),
+        });
+        assert_eq!("ERR no such key", rename.unwrap_err());
+    }
+
+    #[test]
+    fn test_renamenx() {
+        let mut redis: Redis = Redis::new_for_test();
+        for key in ["key1", "key2"].iter() {
+            let _set = redis.execute(Command::Set {
+                key: key.to_string(),
+                value: key.to_string(),
+                options: SetOptions::default(),
+            });
+        }
+
+        let renamenx = redis.execute(Command::Renamenx {
+            key_origin: "key1".to_string(),
+            key_destination: "key2".to_string(),
+        });
+        assert!(eq_response(Re::Integer(0), renamenx.unwrap()));
+
+        let renamenx = redis.execute(Command::Renamenx {
+            key_origin: "key1".to_string(),
+            key_destination: "key3".to_string(),
+        });
+        assert!(eq_response(Re::Integer(1), renamenx.unwrap()));
+
+        let get = redis.execute(Command::Get {
+            key: "key3".to_string(),
+        });
+        assert!(eq_response(Re::String("key1".to_string()), get.unwrap()));
+    }
+
+    #[allow(dead_code)]
+    fn dump(redis: &mut Redis, key: &str) -> String {
+        match redis.execute(Command::Dump {
+            key: key.to_string(),
+        }) {
+            Ok(Response::Normal(Re::String(payload))) => payload,
+            _ => panic!("DUMP did not return a payload"),
+        }
+    }
+
+    #[test]
+    fn test_dump_and_restore() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".to_string(),
+            value: vec!["a".to_string(), "b".to_string()],
+        });
+        let payload = dump(&mut redis, "list");
+
+        let restore = redis.execute(Command::Restore {
+            key: "copy".to_string(),
+            ttl: Some(SystemTime::now() + Duration::from_secs(100)),
+            payload: payload.clone(),
+            replace: false,
+        });
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            restore.unwrap()
+        ));
+
+        assert_eq!(payload, dump(&mut redis, "copy"));
+        let ttl = redis.execute(Command::Ttl {
+            key: "copy".to_string(),
+        });
+        assert!(eq_response(Re::Integer(99), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_dump_unexisting_key_returns_nil() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let dump = redis.execute(Command::Dump {
+            key: "key".to_string(),
+        });
+
+        assert!(eq_response(Re::Nil, dump.unwrap()));
+    }
+
+    #[test]
+    fn test_restore_existing_key_requires_replace() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _set = redis.execute(Command::Set {
+            key: "other".to_string(),
+            value: "other value".to_string(),
+            options: SetOptions::default(),
+        });
+        let payload = dump(&mut redis, "other");
+
+        let restore = redis.execute(Command::Restore {
+            key: "key".to_string(),
+            ttl: None,
+            payload: payload.clone(),
+            replace: false,
+        });
+        assert_eq!(
+            "BUSYKEY Target key name already exists.",
+            restore.unwrap_err()
+        );
+
+        let _restore = redis.execute(Command::Restore {
+            key: "key".to_string(),
+            ttl: None,
+            payload,
+            replace: true,
+        });
+        let get = redis.execute(Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(
+            Re::String("other value".to_string()),
+            get.unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_restore_invalid_payload_returns_err() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let mut payload = dump(&mut redis, "key");
+        payload.replace_range(4..6, "ff");
+
+        let restore = redis.execute(Command::Restore {
+            key: "copy".to_string(),
+            ttl: None,
+            payload,
+            replace: false,
+        });
+
+        assert_eq!(
+            "ERR DUMP payload version or checksum are wrong",
+            restore.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_restore_with_expired_ttl_does_not_create_key() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let payload = dump(&mut redis, "key");
+
+        let restore = redis.execute(Command::Restore {
+            key: "key".to_string(),
+            ttl: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1)),
+            payload,
+            replace: true,
+        });
+
+        assert!(restore.is_ok());
+        let exists = redis.execute(Command::Exists {
+            keys: vec!["key".to_string()],
+        });
+        assert!(eq_response(Re::Integer(0), exists.unwrap()));
+    }
+
+    #[test]
+    fn test_sort_set() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("2".to_string());
+        values.insert("1".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let sort = redis.execute(Command::Sort {
+            key,
+            options: SortOptions::default(),
+        });
+        assert!(eq_response(
+            Re::List(vec!["1".to_string(), "2".to_string()]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_list() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["3".to_string(), "2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let sort = redis.execute(Command::Sort {
+            key,
+            options: SortOptions::default(),
+        });
+
+        assert!(eq_response(
+            Re::List(vec!["2".to_string(), "3".to_string()]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_string_returns_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let sort = redis.execute(Command::Sort {
+            key,
+            options: SortOptions::default(),
+        });
+        assert_eq!(
+            sort.err(),
+            Some("WRONGTYPE Operation against a key holding the wrong kind of value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sort_empty_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let sort = redis.execute(Command::Sort {
+            key,
+            options: SortOptions::default(),
+        });
+        assert!(eq_response(Re::List(vec![]), sort.unwrap()));
+    }
+
+    #[test]
+    fn test_sort_non_numeric_value_returns_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value1".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let sort = redis.execute(Command::Sort {
+            key,
+            options: SortOptions::default(),
+        });
+        assert_eq!(
+            sort.err(),
+            Some("ERR One or more scores can't be converted into double".to_string())
+        );
+    }
+
+    #[allow(dead_code)]
+    fn sort_fixture() -> Redis {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".to_string(),
+            value: vec!["b".to_string(), "c".to_string(), "a".to_string()],
+        });
+        for (element, weight, name) in [("a", "3", "Ana"), ("b", "1", "Beto"), ("c", "2", "Ciro")] {
+            let _set = redis.execute(Command::Set {
+                key: "weight_".to_string() + element,
+                value: weight.to_string(),
+                options: SetOptions::default(),
+            });
+            let _set = redis.execute(Command::Set {
+                key: "name_".to_string() + element,
+                value: name.to_string(),
+                options: SetOptions::default(),
+            });
+        }
+        redis
+    }
+
+    #[test]
+    fn test_sort_alpha_desc_with_limit() {
+        let mut redis = sort_fixture();
+
+        let sort = redis.execute(Command::Sort {
+            key: "key".to_string(),
+            options: SortOptions {
+                alpha: true,
+                descending: true,
+                limit: Some((1, 5)),
+                ..SortOptions::default()
+            },
+        });
+        assert!(eq_response(
+            Re::List(vec!["b".to_string(), "a".to_string()]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_by_and_get_patterns() {
+        let mut redis = sort_fixture();
+
+        let sort = redis.execute(Command::Sort {
+            key: "key".to_string(),
+            options: SortOptions {
+                by: Some("weight_*".to_string()),
+                get: vec!["#".to_string(), "name_*".to_string(), "other_*".to_string()],
+                ..SortOptions::default()
+            },
+        });
+        assert!(eq_response(
+            Re::Array(vec![
+                Re::String("b".to_string()),
+                Re::String("Beto".to_string()),
+                Re::Nil,
+                Re::String("c".to_string()),
+                Re::String("Ciro".to_string()),
+                Re::Nil,
+                Re::String("a".to_string()),
+                Re::String("Ana".to_string()),
+                Re::Nil,
+            ]),
+            sort.unwrap(),
+        ));
+
+        let sort = redis.execute(Command::Sort {
+            key: "key".to_string(),
+            options: SortOptions {
+                by: Some("nosort".to_string()),
+                ..SortOptions::default()
+            },
+        });
+        assert!(eq_response(
+            Re::List(vec!["b".to_string(), "c".to_string(), "a".to_string()]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_store() {
+        let mut redis = sort_fixture();
+
+        let sort = redis.execute(Command::Sort {
+            key: "key".to_string(),
+            options: SortOptions {
+                by: Some("weight_*".to_string()),
+                descending: true,
+                store: Some("dest".to_string()),
+                ..SortOptions::default()
+            },
+        });
+        assert!(eq_response(Re::Integer(3), sort.unwrap()));
+
+        let lrange = redis.execute(Command::Lrange {
+            key: "dest".to_string(),
+            begin: 0,
+            end: -1,
+        });
+        assert!(eq_response(
+            Re::List(vec!["a".to_string(), "c".to_string(), "b".to_string()]),
+            lrange.unwrap(),
+        ));
+
+        let sort = redis.execute(Command::Sort {
+            key: "missing".to_string(),
+            options: SortOptions {
+                store: Some("dest".to_string()),
+                ..SortOptions::default()
+            },
+        });
+        assert!(eq_response(Re::Integer(0), sort.unwrap()));
+        assert!(redis.db.get(&"dest".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_sort_store_serves_blocked_clients() {
+        let mut redis = sort_fixture();
+
+        let blpop = redis.execute(Command::Blpop {
+            keys: vec!["dest".to_string()],
+            timeout: None,
+        });
+        let receiver = match blpop.unwrap() {
+            Response::Blocked(receiver) => receiver,
+            _ => panic!("BLPOP should block on an empty list"),
+        };
+
+        let _sort = redis.execute(Command::Sort {
+            key: "key".to_string(),
+            options: SortOptions {
+                by: Some("weight_*".to_string()),
+                store: Some("dest".to_string()),
+                ..SortOptions::default()
+            },
+        });
+
+        assert_eq!(
+            Re::List(vec!["dest".to_string(), "b".to_string()]),
+            receiver.recv().unwrap()
+        );
+        let llen = redis.execute(Command::Llen {
+            key: "dest".to_string(),
+        });
+        assert!(eq_response(Re::Integer(2), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_ttl_returns_neg2_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let ttl = redis.execute(Command::Ttl { key });
+
+        assert!(eq_response(Re::Integer(-2), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_ttl_returns_neg1_on_persistent_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let ttl = redis.execute(Command::Ttl { key });
+
+        assert!(eq_response(Re::Integer(-1), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_ttl_returns_secs_remaining() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let ttl = Duration::from_secs(5);
+        let _expire = redis.execute(Command::Expire {
+            key,
+            ttl,
+            conditions: vec![],
+        });
+
+        let key = "key".to_string();
+        let ttl = redis.execute(Command::Ttl { key });
+
+        let _key: String = "key".to_string();
+
+        assert!(eq_response(Re::Integer(4), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_type_on_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Re::String("string".to_string()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_type_on_empty_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let type_method = redis.execute(Command::Type { key });
+
+        assert!(eq_response(
+            Re::String("none".to_string()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_type_on_list() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Re::String("list".to_string()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_type_on_set() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Re::String("set".to_string()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lindex_with_key_used_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let index = 1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_err());
+    }
+
+    #[test]
+    fn test_lindex_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let index = 0;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_ok());
+        assert!(eq_response(
+            Re::String("value2".to_string()),
+            lindex.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lindex_negative_index_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let index = -1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_ok());
+        assert!(eq_response(
+            Re::String("value".to_string()),
+            lindex.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lindex_negative_index_result_nil_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let index = -3;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::Nil, lindex.unwrap()));
+    }
+
+    #[test]
+    fn test_llen_key_saved_as_string_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+
+        assert!(llen.is_err());
+    }
+
+    #[test]
+    fn test_llen_key_not_found_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+
+        assert!(llen.is_ok());
+        assert!(eq_response(Re::Integer(0), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_llen_key_used_twice_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+
+        assert!(eq_response(Re::Integer(4), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_lpop_without_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let lpop = redis.execute(Command::Lpop { key, count: None });
+        assert!(lpop.is_ok());
+        assert!(eq_response(Re::String("value2".to_string()), lpop.unwrap()));
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Re::Integer(1), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_lpop_with_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value4".to_string(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let lpop = redis.execute(Command::Lpop {
+            key,
+            count: Some(2),
+        });
+        assert!(lpop.is_ok());
+        assert!(eq_response(
+            Re::List(vec!["value4".to_string(), "value3".to_string()]),
+            lpop.unwrap(),
+        ));
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Re::Integer(2), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_lpop_with_count_major_than_len_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value4".to_string(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let lpop = redis.execute(Command::Lpop {
+            key,
+            count: Some(5),
+        });
+        assert!(lpop.is_ok());
+        assert!(eq_response(
+            Re::List(vec![
+                "value4".to_string(),
+                "value3".to_string(),
+                "value2".to_string(),
+                "value".to_string()
+            ]),
+            lpop.unwrap(),
+        ));
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Re::Integer(0), llen.unwrap()));
+
+        let key = "key".to_string();
+        let lpop = redis.execute(Command::Lpop {
+            key,
+            count: Some(5),
+        });
+        assert!(lpop.is_ok());
+        assert!(eq_response(Re::Nil, lpop.unwrap()));
+    }
+
+    #[test]
+    fn test_lpop_with_saved_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let lpop = redis.execute(Command::Lpop {
+            key,
+            count: Some(5),
+        });
+        assert!(lpop.is_err());
+    }
+
+    #[test]
+    fn test_pop_count_zero_and_out_of_bounds() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".to_string(),
+            value: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        });
+
+        let lpop = redis.execute(Command::Lpop {
+            key: "key".to_string(),
+            count: Some(0),
+        });
+        assert!(eq_response(Re::List(vec![]), lpop.unwrap()));
+
+        let rpop = redis.execute(Command::Rpop {
+            key: "key".to_string(),
+            count: None,
+        });
+        assert!(eq_response(Re::String("c".to_string()), rpop.unwrap()));
+
+        let rpop = redis.execute(Command::Rpop {
+            key: "key".to_string(),
+            count: Some(10),
+        });
+        assert!(eq_response(
+            Re::List(vec!["b".to_string(), "a".to_string()]),
+            rpop.unwrap()
+        ));
+
+        for count in [None, Some(0), Some(3)].iter() {
+            let lpop = redis.execute(Command::Lpop {
+                key: "key".to_string(),
+                count: *count,
+            });
+            assert!(eq_response(Re::Nil, lpop.unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_lrange_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value1".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value4".to_string(),
+        ];
+
+        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "key".to_string();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(
+            Re::List(vec![
+                "value4".to_string(),
+                "value3".to_string(),
+                "value2".to_string(),
+                "value1".to_string()
+            ]),
+            lrange.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lrange_ranges_incorrect_return_empty_vec_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value1".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value4".to_string(),
+        ];
+
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: -1,
+            end: 0,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(Re::List(vec![]), lrange.unwrap()));
+    }
+
+    #[test]
+    fn test_lrange_using_ranges_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value1".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value4".to_string(),
+        ];
+
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 2,
+            end: 4,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(
+            Re::List(vec!["value2".to_string(), "value1".to_string(),]),
+            lrange.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lrange_matches_redis_documentation() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "mylist".to_string(),
+            value: vec!["one".to_string(), "two".to_string(), "three".to_string()],
+        });
+        let mut lrange = |begin: i32, end: i32| {
+            redis.execute(Command::Lrange {
+                key: "mylist".to_string(),
+                begin,
+                end,
+            })
+        };
+
+        let all = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+        assert!(eq_response(
+            Re::List(vec!["one".to_string()]),
+            lrange(0, 0).unwrap()
+        ));
+        assert!(eq_response(Re::List(all.clone()), lrange(-3, 2).unwrap()));
+        assert!(eq_response(Re::List(all), lrange(-100, 100).unwrap()));
+        assert!(eq_response(Re::List(vec![]), lrange(5, 10).unwrap()));
+        assert!(eq_response(
+            Re::List(vec!["three".to_string()]),
+            lrange(-1, -1).unwrap()
+        ));
+        assert!(eq_response(Re::List(vec![]), lrange(-1, -2).unwrap()));
+    }
+
+    #[test]
+    fn test_lrange_for_string_value_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value1".to_string();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 2,
+            end: 4,
+        });
+
+        assert!(lrange.is_err());
+    }
+
+    #[test]
+    fn test_lset_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let index = -1;
+        let element = "Nuevos".to_string();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_ok());
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            lset.unwrap(),
+        ));
+
+        let key = "key".to_string();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(
+            Re::List(vec!["value2".to_string(), "Nuevos".to_string(),]),
+            lrange.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lset_out_of_range_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let index = -50;
+        let element = "Nuevos".to_string();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_lset_out_of_range_upper_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let index = 70;
+        let element = "Nuevos".to_string();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_lset_key_not_found_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let index = 70;
+        let element = "Nuevos".to_string();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_lset_value_saved_was_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".to_string();
+        let key = "key".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let index = 70;
+        let element = "Nuevos".to_string();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_rpop_without_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let rpop = redis.execute(Command::Rpop { key, count: None });
+        assert!(rpop.is_ok());
+        assert!(eq_response(Re::String("value".to_string()), rpop.unwrap()));
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Re::Integer(1), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_rpop_with_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value4".to_string(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let rpop = redis.execute(Command::Rpop {
+            key,
+            count: Some(2),
+        });
+        assert!(rpop.is_ok());
+        assert!(eq_response(
+            Re::List(vec!["value".to_string(), "value2".to_string(),]),
+            rpop.unwrap(),
+        ));
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Re::Integer(2), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_rpop_with_count_major_than_len_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value4".to_string(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let rpop = redis.execute(Command::Rpop {
+            key,
+            count: Some(5),
+        });
+        assert!(rpop.is_ok());
+        assert!(eq_response(
+            Re::List(vec![
+                "value".to_string(),
+                "value2".to_string(),
+                "value3".to_string(),
+                "value4".to_string()
+            ]),
+            rpop.unwrap(),
+        ));
+
+        let key = "key".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Re::Integer(0), llen.unwrap()));
+
+        let key = "key".to_string();
+        let rpop = redis.execute(Command::Rpop {
+            key,
+            count: Some(5),
+        });
+        assert!(rpop.is_ok());
+        assert!(eq_response(Re::Nil, rpop.unwrap()));
+    }
+
+    #[test]
+    fn test_rpop_with_saved_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let rpop = redis.execute(Command::Rpop {
+            key,
+            count: Some(5),
+        });
+        assert!(rpop.is_err());
+    }
+
+    #[test]
+    fn test_lpush_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Re::Integer(2), lpush.unwrap()));
+    }
+
+    #[test]
+    fn test_lpush_with_key_used_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_err());
+    }
+
+    #[test]
+    fn test_lpush_key_used_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Re::Integer(2), lpush.unwrap()));
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Re::Integer(4), lpush.unwrap()));
+    }
+
+    #[test]
+    fn test_lpush_key_used_check_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["1".to_string(), "2".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let value = vec!["3".to_string(), "4".to_string()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+        let index = -1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("1".to_string()), lindex.unwrap()));
+        let key = "key".to_string();
+        let index = -2;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("2".to_string()), lindex.unwrap()));
+        let key = "key".to_string();
+        let index = -3;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("3".to_string()), lindex.unwrap()));
+        let key = "key".to_string();
+        let index = -4;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("4".to_string()), lindex.unwrap()));
+    }
+
+    #[test]
+    fn test_rpush_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpush = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpush.is_ok());
+        assert!(eq_response(Re::Integer(2), rpush.unwrap()));
+    }
+
+    #[test]
+    fn test_rpush_with_key_used_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpush = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpush.is_err());
+    }
+
+    #[test]
+    fn test_rpush_key_used_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpush = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpush.is_ok());
+        assert!(eq_response(Re::Integer(2), rpush.unwrap()));
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpush = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpush.is_ok());
+        assert!(eq_response(Re::Integer(4), rpush.unwrap()));
+    }
+
+    #[test]
+    fn test_rpush_key_used_check_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["1".to_string(), "2".to_string()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
+
+        let key = "key".to_string();
+        let value = vec!["3".to_string(), "4".to_string()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
+
+        let key = "key".to_string();
+        let index = -1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("4".to_string()), lindex.unwrap()));
+        let key = "key".to_string();
+        let index = -2;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("3".to_string()), lindex.unwrap()));
+        let key = "key".to_string();
+        let index = -3;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("2".to_string()), lindex.unwrap()));
+        let key = "key".to_string();
+        let index = -4;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Re::String("1".to_string()), lindex.unwrap()));
+    }
+
+    #[test]
+    fn test_sadd() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let sadd = redis.execute(Command::Sadd { key, values });
+
+        assert!(eq_response(Re::Integer(3), sadd.unwrap()));
+    }
+
+    #[test]
+    fn test_sadd_with_existing_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "set".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let sadd = redis.execute(Command::Sadd { key, values });
+
+        assert!(eq_response(Re::Integer(3), sadd.unwrap()));
+
+        let key = "set".to_string();
+        let mut values = HashSet::new();
+        values.insert("value3".to_string());
+        values.insert("value4".to_string());
+
+        let sadd2 = redis.execute(Command::Sadd { key, values });
+        assert!(eq_response(Re::Integer(1), sadd2.unwrap()));
+    }
+
+    #[test]
+    fn test_sadd_error() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "set".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "set".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let sadd = redis.execute(Command::Sadd { key, values });
+
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            sadd.err().unwrap()
+        )
+    }
+
+    #[test]
+    fn test_scard() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let scard = redis.execute(Command::Scard { key });
+
+        assert!(eq_response(Re::Integer(3), scard.unwrap()));
+    }
+
+    #[test]
+    fn test_scard_error() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "set".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "set".to_string();
+        let scard = redis.execute(Command::Scard { key });
+
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            scard.err().unwrap()
+        )
+    }
+
+    #[test]
+    fn test_sismember() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let value = "value1".to_string();
+        let sismember = redis.execute(Command::Sismember { key, value });
+
+        assert!(eq_response(Re::Integer(1), sismember.unwrap()));
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let sismember = redis.execute(Command::Sismember { key, value });
+
+        assert!(eq_response(Re::Integer(0), sismember.unwrap()));
+    }
+
+    #[test]
+    fn test_sismember_error() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key1".to_string();
+        let value = "value1".to_string();
+        let sismember = redis.execute(Command::Sismember { key, value });
+
+        assert_eq!(
+            "The key doesn't exist".to_string(),
+            sismember.err().unwrap()
+        );
+
+        let key = "set".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "set".to_string();
+        let value = "value".to_string();
+        let sismember = redis.execute(Command::Sismember { key, value });
+
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            sismember.err().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_srem() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert!(eq_response(Re::Integer(1), srem.unwrap()));
+
+        let key = "key_inexistente".to_string();
+        let mut values = HashSet::new();
+        values.insert("value2".to_string());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert!(eq_response(Re::Integer(0), srem.unwrap()));
+    }
+
+    #[test]
+    fn test_srem_value_two_times() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert!(eq_response(Re::Integer(1), srem.unwrap()));
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert!(eq_response(Re::Integer(0), srem.unwrap()));
+    }
+
+    #[test]
+    fn test_srem_error() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "set".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "set".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            srem.err().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_smembers() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let mut values = HashSet::new();
+        values.insert("value1".to_string());
+        values.insert("value2".to_string());
+        values.insert("value3".to_string());
+        let smembers = redis.execute(Command::Smembers { key });
+
+        assert!(eq_response(Re::Set(values), smembers.unwrap()));
+    }
+
+    #[test]
+    fn test_lpushx_not_pre_save_return_0() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpushx = redis.execute(Command::Lpushx { key, value });
+
+        assert!(lpushx.is_ok());
+        assert!(eq_response(Re::Integer(0), lpushx.unwrap()));
+    }
+
+    #[test]
+    fn test_lpushx_with_key_used_with_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpushx = redis.execute(Command::Lpushx { key, value });
+
+        assert!(lpushx.is_err());
+    }
+
+    #[test]
+    fn test_lpushx_after_lpush_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Re::Integer(2), lpush.unwrap()));
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let lpush = redis.execute(Command::Lpushx { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Re::Integer(4), lpush.unwrap()));
+    }
+
+    #[test]
+    fn test_rpushx_not_pre_save_return_0() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Re::Integer(0), rpushx.unwrap()));
+    }
+
+    #[test]
+    fn test_rpushx_with_key_used_with_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
+
+        assert!(rpushx.is_err());
+    }
+
+    #[test]
+    fn test_rpushx_after_rpush_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpushx = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Re::Integer(2), rpushx.unwrap()));
+
+        let key = "key".to_string();
+        let value = vec!["value".to_string(), "value2".to_string()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Re::Integer(4), rpushx.unwrap()));
+    }
+
+    #[test]
+    fn test_rpush_and_check_elements_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "1".to_string(),
+            "2".to_string(),
+            "3".to_string(),
+            "4".to_string(),
+        ];
+        let rpushx = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Re::Integer(4), rpushx.unwrap()));
+
+        let key = "key".to_string();
+        let value = vec![
+            "1".to_string(),
+            "2".to_string(),
+            "3".to_string(),
+            "4".to_string(),
+        ];
+        let rpushx = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Re::List(value), rpushx.unwrap()));
+    }
+
+    #[test]
+    fn test_rpush_rpushx_and_check_elements_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec!["1".to_string(), "2".to_string()];
+        let rpush = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpush.is_ok());
+        assert!(eq_response(Re::Integer(2), rpush.unwrap()));
+
+        let key = "key".to_string();
+        let value = vec!["3".to_string(), "4".to_string()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Re::Integer(4), rpushx.unwrap()));
+
+        let key = "key".to_string();
+        let rpushx = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(
+            Re::List(vec![
+                "1".to_string(),
+                "2".to_string(),
+                "3".to_string(),
+                "4".to_string()
+            ]),
+            rpushx.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lrem_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value".to_string(),
+            "value1".to_string(),
+            "value2".to_string(),
+            "value".to_string(),
+            "value".to_string(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: 2,
+            element: "value".to_string(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Re::Integer(2), lrem.unwrap()));
+
+        let key = "key".to_string();
+
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        let mut vector = vec![
+            "value".to_string(),
+            "value1".to_string(),
+            "value2".to_string(),
+        ];
+        vector.reverse();
+        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+    }
+
+    #[test]
+    fn test_lrem_reverse_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value".to_string(),
+            "value".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value1".to_string(),
+            "value".to_string(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: -2,
+            element: "value".to_string(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Re::Integer(2), lrem.unwrap()));
+
+        let key = "key".to_string();
+
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        let mut vector = vec![
+            "value2".to_string(),
+            "value3".to_string(),
+            "value1".to_string(),
+            "value".to_string(),
+        ];
+
+        vector.reverse();
+
+        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+    }
+
+    #[test]
+    fn test_lrem_all_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let value = vec![
+            "value".to_string(),
+            "value2".to_string(),
+            "value3".to_string(),
+            "value1".to_string(),
+            "value".to_string(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".to_string();
+
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: 0,
+            element: "value".to_string(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Re::Integer(2), lrem.unwrap()));
+
+        let key = "key".to_string();
+
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        let mut vector = vec![
+            "value2".to_string(),
+            "value3".to_string(),
+            "value1".to_string(),
+        ];
+
+        vector.reverse();
+
+        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+    }
+
+    #[test]
+    fn test_lrem_matches_redis_documentation() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "mylist".to_string(),
+            value: vec![
+                "hello".to_string(),
+                "hello".to_string(),
+                "foo".to_string(),
+                "hello".to_string(),
+            ],
+        });
+
+        let lrem = redis.execute(Command::Lrem {
+            key: "mylist".to_string(),
+            count: -2,
+            element: "hello".to_string(),
+        });
+        assert!(eq_response(Re::Integer(2), lrem.unwrap()));
+
+        let lrange = redis.execute(Command::Lrange {
+            key: "mylist".to_string(),
+            begin: 0,
+            end: -1,
+        });
+        assert!(eq_response(
+            Re::List(vec!["hello".to_string(), "foo".to_string()]),
+            lrange.unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_lrem_removes_consecutive_matches() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "mylist".to_string(),
+            value: vec![
+                "a".to_string(),
+                "a".to_string(),
+                "a".to_string(),
+                "b".to_string(),
+            ],
+        });
+
+        let lrem = redis.execute(Command::Lrem {
+            key: "mylist".to_string(),
+            count: 2,
+            element: "a".to_string(),
+        });
+        assert!(eq_response(Re::Integer(2), lrem.unwrap()));
+
+        let lrem = redis.execute(Command::Lrem {
+            key: "mylist".to_string(),
+            count: i32::MIN,
+            element: "a".to_string(),
+        });
+        assert!(eq_response(Re::Integer(1), lrem.unwrap()));
+    }
+
+    #[test]
+    fn test_lrem_invalid_key_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
 
-        assert!(strlen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), strlen.unwrap()));
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: 0,
+            element: "value".to_string(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Re::Integer(0), lrem.unwrap()));
     }
 
     #[test]
-    fn test_strlen_element_saved_before() {
+    fn test_keys_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
+        let key = "key".to_string();
         let value = "value".to_string();
-        let key = "hola".to_string();
-
-        let _set = redis.execute(Command::Set { key, value });
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
-        let key = "hola".to_string();
-        let strlen = redis.execute(Command::Strlen { key });
+        let key = "key1".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
-        assert!(strlen.is_ok());
-        assert!(eq_response(Re::String("5".to_string()), strlen.unwrap()));
-    }
+        let pattern: String = "/*".to_string();
 
-    #[allow(unused_imports)]
-    #[test]
-    fn test_set_element_and_get_the_same() {
-        let mut redis: Redis = Redis::new_for_test();
+        let keys = redis.execute(Command::Keys { pattern });
 
-        let value = "value".to_string();
-        let key = "hola".to_string();
+        assert!(keys.is_ok());
 
-        let _set = redis.execute(Command::Set { key, value });
+        let pattern: String = "k**".to_string();
 
-        let key = "hola".to_string();
-        let get = redis.execute(Command::Get { key });
+        let keys = redis.execute(Command::Keys { pattern });
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+        assert!(keys.is_ok());
     }
 
     #[test]
-    fn test_set_element_twice_and_get_the_last_set() {
+    fn test_keys_skips_expired_keys() {
         let mut redis: Redis = Redis::new_for_test();
+        for key in ["valid", "expired"] {
+            redis
+                .db
+                .insert(key.to_string(), Re::String("v".to_string()));
+        }
+        redis
+            .db
+            .set_ttl_absolute("expired".to_string(), SystemTime::UNIX_EPOCH);
 
-        let key = "hola".to_string();
-        let value = "chau".to_string();
-
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "hola".to_string();
-        let value = "test".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "hola".to_string();
-        let get = redis.execute(Command::Get { key });
+        let keys = redis.execute(Command::Keys {
+            pattern: ".*".to_string(),
+        });
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("test".to_string()), get.unwrap()));
+        assert!(eq_response(
+            Re::List(vec!["valid".to_string()]),
+            keys.unwrap()
+        ));
+        assert_eq!(1, redis.expired_keys);
     }
 
     #[test]
-    fn test_get_on_empty_key_returns_nil() {
+    fn test_memory_usage() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "v".repeat(100),
+            options: SetOptions::default(),
+        });
 
-        let key = "hola".to_string();
-        let get = redis.execute(Command::Get { key });
+        let usage = redis.execute(Command::Memory {
+            param: MemoryParam::Usage {
+                key: "key".to_string(),
+                samples: 5,
+            },
+        });
+        match usage.unwrap() {
+            Response::Normal(Re::Integer(bytes)) => assert!(bytes > 100),
+            _ => panic!("MEMORY USAGE did not return a number"),
+        }
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::Nil, get.unwrap()));
+        let usage = redis.execute(Command::Memory {
+            param: MemoryParam::Usage {
+                key: "other".to_string(),
+                samples: 5,
+            },
+        });
+        assert!(eq_response(Re::Nil, usage.unwrap()));
     }
 
     #[test]
-    fn test_get_element_fail_if_is_not_string() {
+    fn test_info_memory_adds_the_usage_of_every_key() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "v".repeat(100),
+            options: SetOptions::default(),
+        });
+        let usage = match redis.execute(Command::Memory {
+            param: MemoryParam::Usage {
+                key: "key".to_string(),
+                samples: 5,
+            },
+        }) {
+            Ok(Response::Normal(Re::Integer(bytes))) => bytes,
+            _ => panic!("MEMORY USAGE did not return a number"),
+        };
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-
-        assert!(get.is_err());
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Memory,
+        });
+        match info.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.starts_with("# Memory\r\n"));
+                assert!(info.contains(&format!("used_memory:{}\r\n", usage)));
+                assert!(info.contains("maxmemory:0\r\n"));
+            }
+            _ => panic!("INFO MEMORY did not return a string"),
+        }
     }
 
     #[test]
-    fn test_getset_fails_if_is_not_string() {
+    fn test_slowlog_records_commands_slower_than_the_threshold() {
         let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "slowlog-log-slower-than".to_string(),
+            value: "0".to_string(),
+        });
+        let args = vec!["set".to_string(), "k".to_string(), "v".to_string()];
+        let _ = redis.execute_from_client(
+            Command::Set {
+                key: "k".to_string(),
+                value: "v".to_string(),
+                options: SetOptions::default(),
+            },
+            None,
+            &args,
+        );
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let getset = redis.execute(Command::Getset { key, value });
+        let len = redis.execute(Command::Slowlog {
+            param: SlowlogParam::Len,
+        });
+        assert!(eq_response(Re::Integer(2), len.unwrap()));
+        let entries = redis.execute(Command::Slowlog {
+            param: SlowlogParam::Get { count: 2 },
+        });
+        match entries.unwrap() {
+            Response::Normal(Re::Array(entries)) => {
+                assert_eq!(2, entries.len());
+                match &entries[0] {
+                    Re::Array(fields) => {
+                        assert_eq!(Re::List(vec!["slowlog".to_string()]), fields[3]);
+                    }
+                    _ => panic!("SLOWLOG entry is not an array"),
+                }
+                match &entries[1] {
+                    Re::Array(fields) => assert_eq!(Re::List(args.clone()), fields[3]),
+                    _ => panic!("SLOWLOG entry is not an array"),
+                }
+            }
+            _ => panic!("SLOWLOG GET did not return an array"),
+        }
 
-        assert!(getset.is_err());
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "slowlog-log-slower-than".to_string(),
+            value: "-1".to_string(),
+        });
+        let _ = redis.execute(Command::Slowlog {
+            param: SlowlogParam::Reset,
+        });
+        let len = redis.execute(Command::Slowlog {
+            param: SlowlogParam::Len,
+        });
+        assert!(eq_response(Re::Integer(0), len.unwrap()));
     }
 
     #[test]
-    fn test_getset_on_empty_key_returns_nil() {
+    fn test_slowlog_records_shared_reads() {
         let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "slowlog-log-slower-than".to_string(),
+            value: "0".to_string(),
+        });
+        let _ = redis.execute(Command::Slowlog {
+            param: SlowlogParam::Reset,
+        });
+        let args = vec!["GET".to_string(), "key".to_string()];
+        let get = redis.execute_shared_from_client(
+            &Command::Get {
+                key: "key".to_string(),
+            },
+            None,
+            &args,
+        );
+        assert!(get.is_some());
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let getset = redis.execute(Command::Getset { key, value });
-
-        assert!(getset.is_ok());
-        assert!(eq_response(Re::Nil, getset.unwrap()));
+        let entries = redis.execute(Command::Slowlog {
+            param: SlowlogParam::Get { count: 1 },
+        });
+        match entries.unwrap() {
+            Response::Normal(Re::Array(entries)) => match &entries[0] {
+                Re::Array(fields) => assert_eq!(Re::List(args), fields[3]),
+                _ => panic!("SLOWLOG entry is not an array"),
+            },
+            _ => panic!("SLOWLOG GET did not return an array"),
+        }
     }
 
     #[test]
-    fn test_getset_ok() {
+    fn test_slowlog_redacts_passwords() {
         let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "slowlog-log-slower-than".to_string(),
+            value: "0".to_string(),
+        });
+        let args = vec!["AUTH".to_string(), "secret".to_string()];
+        let _ = redis.execute_from_client(
+            Command::Auth {
+                password: "secret".to_string(),
+            },
+            None,
+            &args,
+        );
 
-        let key = "key".to_string();
-        let value = "1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let getset = redis.execute(Command::Getset { key, value });
-        assert!(getset.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), getset.unwrap()));
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+        let entries = redis.execute(Command::Slowlog {
+            param: SlowlogParam::Get { count: 1 },
+        });
+        match entries.unwrap() {
+            Response::Normal(Re::Array(entries)) => match &entries[0] {
+                Re::Array(fields) => assert_eq!(
+                    Re::List(vec!["AUTH".to_string(), "(redacted)".to_string()]),
+                    fields[3]
+                ),
+                _ => panic!("SLOWLOG entry is not an array"),
+            },
+            _ => panic!("SLOWLOG GET did not return an array"),
+        }
     }
 
     #[test]
-    fn test_ping_returns_pong() {
+    fn test_debug_object_describes_the_key() {
         let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::Set {
+            key: "number".to_string(),
+            value: "12".to_string(),
+            options: SetOptions::default(),
+        });
 
-        let ping = redis.execute(Command::Ping);
+        let response = redis.execute(Command::Debug {
+            param: DebugParam::Object {
+                key: "number".to_string(),
+            },
+        });
+        match response {
+            Ok(Response::Normal(Re::SimpleString(description))) => {
+                assert!(description.starts_with("Value at:0x"));
+                assert!(description.contains(" refcount:1 encoding:int serializedlength:2 "));
+                assert!(description.ends_with(" lru_seconds_idle:0"));
+            }
+            _ => panic!("DEBUG OBJECT should describe the key"),
+        }
 
-        assert!(ping.is_ok());
-        assert!(eq_response(
-            Re::SimpleString("PONG".to_string()),
-            ping.unwrap(),
-        ));
+        let response = redis.execute(Command::Debug {
+            param: DebugParam::Object {
+                key: "missing".to_string(),
+            },
+        });
+        assert_eq!(Err("ERR no such key".to_string()), response.map(|_| ()));
     }
 
     #[test]
-    fn test_incrby_with_2_as_value() {
+    fn test_ttl_and_active_expire_follow_the_clock() {
         let mut redis: Redis = Redis::new_for_test();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        redis.set_clock(clock.clone());
+        let receiver = redis.watch_changes();
 
-        let key = "key".to_string();
-        let value = "1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let increment: u32 = 1;
-        let _incrby = redis.execute(Command::Incrby { key, increment });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-
-        let key = "key".to_string();
-        let increment: u32 = 2;
-        let _incrby = redis.execute(Command::Incrby { key, increment });
-
-        let key = "key".to_string();
-        let second_get = redis.execute(Command::Get { key });
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _expire = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(100),
+            conditions: vec![],
+        });
+        assert!(receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_ok());
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), get.unwrap()));
+        clock.advance(Duration::from_secs(40));
+        let ttl = redis.execute(Command::Ttl {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Integer(60), ttl.unwrap()));
 
-        assert!(second_get.is_ok());
-        assert!(eq_response(
-            Re::String("4".to_string()),
-            second_get.unwrap(),
-        ));
+        clock.advance(Duration::from_secs(60));
+        redis.last_active_expire -= Duration::from_secs(1);
+        redis.active_expire_cycle();
+        assert_eq!("key", receiver.try_recv().unwrap().key);
+        assert_eq!(0, redis.db.len());
     }
 
     #[test]
-    fn test_incrby_value_err_initial_value_string() {
+    fn test_active_expire_cycle_can_be_disabled() {
         let mut redis: Redis = Redis::new_for_test();
+        let receiver = redis.watch_changes();
+        redis
+            .db
+            .insert("expired".to_string(), Re::String("value".to_string()));
+        redis
+            .db
+            .set_ttl_absolute("expired".to_string(), SystemTime::UNIX_EPOCH);
+        redis.last_active_expire -= Duration::from_secs(1);
+
+        let _ = redis.execute(Command::Debug {
+            param: DebugParam::SetActiveExpire { enabled: false },
+        });
+        redis.active_expire_cycle();
+        assert!(receiver.try_recv().is_err());
 
-        let key = "key".to_string();
-        let value = "hola".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _ = redis.execute(Command::Debug {
+            param: DebugParam::SetActiveExpire { enabled: true },
+        });
+        redis.active_expire_cycle();
+        assert_eq!("expired", receiver.try_recv().unwrap().key);
+    }
 
-        let key = "key".to_string();
-        let increment: u32 = 1;
-        let incrby = redis.execute(Command::Incrby { key, increment });
+    #[test]
+    fn test_eval_runs_commands_atomically() {
+        let mut redis: Redis = Redis::new_for_test();
+        let script = "
+            local current = tonumber(redis.call('get', KEYS[1])) or 0
+            if current + ARGV[1] > tonumber(ARGV[2]) then
+                return redis.error_reply('ERR limit exceeded')
+            end
+            redis.call('set', KEYS[1], current + ARGV[1])
+            return {current + ARGV[1], redis.call('type', KEYS[1])}
+        ";
+        let eval = |redis: &mut Redis| {
+            redis.execute(Command::Eval {
+                script: script.to_string(),
+                keys: vec!["counter".to_string()],
+                args: vec!["4".to_string(), "10".to_string()],
+            })
+        };
 
-        assert!(incrby.is_err());
+        assert!(eq_response(
+            Re::Array(vec![Re::Integer(4), Re::String("string".to_string())]),
+            eval(&mut redis).unwrap()
+        ));
+        let _ = eval(&mut redis);
+        assert_eq!(
+            Err("ERR limit exceeded".to_string()),
+            eval(&mut redis).map(|_| ())
+        );
+        let get = redis.execute(Command::Get {
+            key: "counter".to_string(),
+        });
+        assert!(eq_response(Re::String("8".to_string()), get.unwrap()));
     }
 
     #[test]
-    fn test_incrby_not_saved_value() {
+    fn test_eval_errors_indicate_the_script_and_line() {
         let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::Sadd {
+            key: "set".to_string(),
+            values: ["a".to_string()].iter().cloned().collect(),
+        });
 
-        let key = "key".to_string();
-        let increment: u32 = 1;
-        let _incrby = redis.execute(Command::Incrby { key, increment });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let result = redis.execute(Command::Eval {
+            script: "return redis.call('get', KEYS[1])".to_string(),
+            keys: vec!["set".to_string()],
+            args: vec![],
+        });
+        let sha1 = "4e6d8fc8bb01276962cce5371fa795a7763657ae";
+        assert_eq!(
+            Err(format!(
+                "WRONGTYPE Operation against a key holding the wrong kind of value script: {}, on @user_script:1.",
+                sha1
+            )),
+            result.map(|_| ())
+        );
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), get.unwrap()));
+        let result = redis.execute(Command::Eval {
+            script: "return redis.call('blpop', 'list', 0)".to_string(),
+            keys: vec![],
+            args: vec![],
+        });
+        assert!(result
+            .unwrap_err()
+            .starts_with("ERR This Redis command is not allowed from script"));
+
+        let result = redis.execute(Command::Eval {
+            script: "return (".to_string(),
+            keys: vec![],
+            args: vec![],
+        });
+        assert_eq!(
+            Err("ERR Error compiling script (new function): user_script:1: unexpected symbol near '<eof>'".to_string()),
+            result.map(|_| ())
+        );
     }
 
     #[test]
-    fn test_decrby_on_new_key() {
+    fn test_script_load_exists_and_flush() {
         let mut redis: Redis = Redis::new_for_test();
+        let sha1 = "e0e1f9fabfc9d4800c877a703b823ac0578ff8db".to_string();
+        let evalsha = |redis: &mut Redis| {
+            redis.execute(Command::Evalsha {
+                sha1: sha1.clone(),
+                keys: vec![],
+                args: vec![],
+            })
+        };
+        assert!(evalsha(&mut redis).unwrap_err().starts_with("NOSCRIPT"));
 
-        let key = "key".to_string();
-        let decrement: u32 = 3;
-        let _decrby = redis.execute(Command::Decrby { key, decrement });
+        let load = redis.execute(Command::Script {
+            param: ScriptParam::Load {
+                script: "return 1".to_string(),
+            },
+        });
+        assert!(eq_response(Re::String(sha1.clone()), load.unwrap()));
+        assert!(eq_response(Re::Integer(1), evalsha(&mut redis).unwrap()));
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let exists = redis.execute(Command::Script {
+            param: ScriptParam::Exists {
+                sha1s: vec![sha1.clone(), "ffff".to_string()],
+            },
+        });
+        assert!(eq_response(
+            Re::Array(vec![Re::Integer(1), Re::Integer(0)]),
+            exists.unwrap()
+        ));
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("-3".to_string()), get.unwrap()));
+        let _ = redis.execute(Command::Script {
+            param: ScriptParam::Flush,
+        });
+        assert!(evalsha(&mut redis).is_err());
     }
 
     #[test]
-    fn test_decrby_on_existing_key() {
+    fn test_object_freq_counts_accesses() {
         let mut redis: Redis = Redis::new_for_test();
-
         let key = "key".to_string();
-        let value = "5".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _set = redis.execute(Command::Set {
+            key: key.clone(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
 
-        let key = "key".to_string();
-        let decrement: u32 = 3;
-        let _decrby = redis.execute(Command::Decrby { key, decrement });
+        let freq = redis.execute(Command::Object {
+            param: ObjectParam::Freq(key.clone()),
+        });
+        assert!(eq_response(Re::Integer(5), freq.unwrap()));
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let _get = redis.execute(Command::Get { key: key.clone() });
+        let freq = redis.execute(Command::Object {
+            param: ObjectParam::Freq(key),
+        });
+        assert!(eq_response(Re::Integer(6), freq.unwrap()));
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), get.unwrap()));
+        let freq = redis.execute(Command::Object {
+            param: ObjectParam::Freq("other".to_string()),
+        });
+        assert!(eq_response(Re::Nil, freq.unwrap()));
     }
 
     #[test]
-    fn test_mset_sets_2_values() {
+    fn test_object_freq_without_access_tracking() {
         let mut redis: Redis = Redis::new_for_test();
+        let key = "key".to_string();
+        let _set = redis.execute(Command::Set {
+            key: key.clone(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _config_set = redis.execute(Command::ConfigSet {
+            parameter: "track-key-access".to_string(),
+            value: "no".to_string(),
+        });
+        assert!(!redis.db.tracks_access());
 
-        let key_values = vec![
-            ("key1".to_string(), "value1".to_string()),
-            ("key2".to_string(), "value2".to_string()),
-        ];
-        let _mset = redis.execute(Command::Mset { key_values });
-
-        let key = "key1".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
-
-        let key = "key2".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value2".to_string()), get.unwrap()));
+        let freq = redis.execute(Command::Object {
+            param: ObjectParam::Freq(key.clone()),
+        });
+        assert!(freq.unwrap_err().contains("track-key-access"));
+        let touch = redis.execute(Command::Touch { keys: vec![key] });
+        assert!(eq_response(Re::Integer(1), touch.unwrap()));
+
+        // La base de datos vacía de FLUSHDB conserva la configuración.
+        let _flushdb = redis.execute(Command::Flushdb {
+            asynchronous: false,
+        });
+        assert!(!redis.db.tracks_access());
     }
 
     #[test]
-    fn test_mget_gets_2_values() {
+    fn test_integer_strings_use_int_encoding() {
         let mut redis: Redis = Redis::new_for_test();
+        let encoding = |redis: &mut Redis, key: &str| {
+            redis.execute(Command::Object {
+                param: ObjectParam::Encoding(key.to_string()),
+            })
+        };
+        let _set = redis.execute(Command::Set {
+            key: "counter".to_string(),
+            value: "10".to_string(),
+            options: SetOptions::default(),
+        });
+        let _set = redis.execute(Command::Set {
+            key: "padded".to_string(),
+            value: "010".to_string(),
+            options: SetOptions::default(),
+        });
+        let _incrby = redis.execute(Command::Incrby {
+            key: "new".to_string(),
+            increment: -3,
+        });
 
-        let key_values = vec![
-            ("key1".to_string(), "value1".to_string()),
-            ("key2".to_string(), "value2".to_string()),
-        ];
-        let _mset = redis.execute(Command::Mset { key_values });
+        for key in ["counter", "new"].iter() {
+            assert!(eq_response(
+                Re::String("int".to_string()),
+                encoding(&mut redis, key).unwrap()
+            ));
+        }
+        assert!(eq_response(
+            Re::String("embstr".to_string()),
+            encoding(&mut redis, "padded").unwrap()
+        ));
+        assert!(eq_response(
+            Re::Nil,
+            encoding(&mut redis, "missing").unwrap()
+        ));
 
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let mget = redis.execute(Command::Mget { keys });
+        let incrby = redis.execute(Command::Incrby {
+            key: "counter".to_string(),
+            increment: 5,
+        });
+        assert!(eq_response(Re::Integer(15), incrby.unwrap()));
+        let get = redis.execute(Command::Get {
+            key: "counter".to_string(),
+        });
+        assert!(eq_response(Re::String("15".to_string()), get.unwrap()));
+        let get = redis.execute(Command::Get {
+            key: "padded".to_string(),
+        });
+        assert!(eq_response(Re::String("010".to_string()), get.unwrap()));
 
-        assert!(mget.is_ok());
+        let _append = redis.execute(Command::Append {
+            key: "counter".to_string(),
+            value: "0".to_string(),
+        });
         assert!(eq_response(
-            Re::List(vec!["value1".to_string(), "value2".to_string()]),
-            mget.unwrap(),
+            Re::String("embstr".to_string()),
+            encoding(&mut redis, "counter").unwrap()
         ));
+        let strlen = redis.execute(Command::Strlen {
+            key: "new".to_string(),
+        });
+        assert!(eq_response(Re::Integer(2), strlen.unwrap()));
     }
 
     #[test]
-    fn test_mget_nil_for_missing_value() {
+    fn test_touch_deletes_expired_key() {
         let mut redis: Redis = Redis::new_for_test();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        redis.set_clock(clock.clone());
 
         let key = "key".to_string();
         let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
-        let keys = vec!["key".to_string(), "key_empty".to_string()];
-        let mget = redis.execute(Command::Mget { keys });
+        let key = "key".to_string();
+        let ttl = Duration::from_secs(1);
+        let _expire = redis.execute(Command::Expire {
+            key,
+            ttl,
+            conditions: vec![],
+        });
 
-        assert!(mget.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value".to_string(), "(nil)".to_string()]),
-            mget.unwrap(),
-        ));
+        clock.advance(Duration::from_secs(1));
+
+        let keys = vec!["key".to_string()];
+        let touch = redis.execute(Command::Touch { keys });
+
+        let pattern = "*".to_string();
+        let keys = redis.execute(Command::Keys { pattern });
+
+        assert!(eq_response(Re::Integer(0), touch.unwrap()));
+        assert!(eq_response(Re::List(Vec::new()), keys.unwrap()));
     }
 
     #[test]
-    fn test_mget_nil_for_non_string_value() {
+    fn test_touch_returns_number_of_keys_touched() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
+        let key = "key1".to_string();
         let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
-        let key = "key_list".to_string();
-        let value = vec!["value1".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "key2".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
-        let keys = vec!["key".to_string(), "key_list".to_string()];
-        let mget = redis.execute(Command::Mget { keys });
+        let keys = vec!["key1".to_string(), "key2".to_string()];
+        let touch = redis.execute(Command::Touch { keys });
 
-        assert!(mget.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value".to_string(), "(nil)".to_string()]),
-            mget.unwrap(),
-        ));
+        assert!(eq_response(Re::Integer(2), touch.unwrap()));
     }
 
     #[test]
-    fn test_set_element_and_getdel() {
+    fn test_set_element_and_flushdb() {
         let mut redis: Redis = Redis::new_for_test();
 
         let value = "value".to_string();
         let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
         let key = "key".to_string();
         let get = redis.execute(Command::Get { key });
-
-        let key = "key".to_string();
-        let getdel = redis.execute(Command::Getdel { key });
-
-        assert!(get.is_ok());
         assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
 
-        assert!(getdel.is_ok());
-        assert!(eq_response(
-            Re::String("value".to_string()),
-            getdel.unwrap(),
-        ));
+        let flushdb = redis.execute(Command::Flushdb {
+            asynchronous: false,
+        });
+        assert!(flushdb.is_ok());
 
         let key = "key".to_string();
         let get = redis.execute(Command::Get { key });
@@ -2533,2008 +10423,3647 @@ mod test {
     }
 
     #[test]
-    fn test_getdel_without_previews_saving_err() {
+    fn test_flushall_async_removes_all_keys() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
 
-        let key = "key".to_string();
-        let getdel = redis.execute(Command::Getdel { key });
+        let flushall = redis.execute(Command::Flushall { asynchronous: true });
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            flushall.unwrap()
+        ));
 
-        assert_eq!(true, getdel.is_err());
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Re::Integer(0), dbsize.unwrap()));
     }
 
     #[test]
-    fn test_dbsize() {
+    fn test_store_then_load() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let dbsize = redis.execute(Command::Dbsize);
-        assert!(eq_response(Re::String("0".to_string()), dbsize.unwrap()));
+        let key1 = "key1".to_string();
+        let value1 = "value1".to_string();
+        let _set = redis.execute(Command::Set {
+            key: key1.clone(),
+            value: value1.clone(),
+            options: SetOptions::default(),
+        });
+        let key2 = "key2".to_string();
+        let value2 = "value2".to_string();
+        let _set = redis.execute(Command::Set {
+            key: key2.clone(),
+            value: value2.clone(),
+            options: SetOptions::default(),
+        });
+        let expire = Duration::from_secs(2);
+        let _ttl = redis.execute(Command::Expire {
+            key: key2.clone(),
+            ttl: expire.clone(),
+            conditions: vec![],
+        });
 
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let path = "test_store_then_load.rdb".to_string();
+        let _store = redis.execute(Command::Store { path: path.clone() });
+        wait_for_background_save(&mut redis);
 
-        let dbsize = redis.execute(Command::Dbsize);
-        assert!(eq_response(Re::String("1".to_string()), dbsize.unwrap()));
+        let _content = fs::read(path.clone()).unwrap();
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::Load { path: path });
 
-        let key = "key".to_string();
-        let _getdel = redis.execute(Command::Getdel { key });
+        let get = redis_new.execute(Command::Get { key: key1 });
+        assert!(eq_response(Re::String(value1), get.unwrap()));
 
-        let dbsize = redis.execute(Command::Dbsize);
-        assert!(eq_response(Re::String("0".to_string()), dbsize.unwrap()));
+        let get = redis_new.execute(Command::Get { key: key2.clone() });
+        assert!(eq_response(Re::String(value2), get.unwrap()));
+
+        let ttl = redis_new.execute(Command::Ttl { key: key2 });
+        assert!(eq_response(
+            Re::Integer(expire.as_secs() as i64 - 1),
+            ttl.unwrap(),
+        ));
+
+        fs::remove_file("test_store_then_load.rdb").unwrap();
     }
 
     #[test]
-    fn test_set_element_and_del() {
+    fn test_load_corrupt_file_returns_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let path = "test_load_empy_file_returns_err.rdb".to_string();
+        let mut file = fs::File::create(path.clone()).unwrap();
 
-        let keys = vec!["key".to_string()];
-        let del = redis.execute(Command::Del { keys });
-        assert!(eq_response(Re::String("1".to_string()), del.unwrap()));
+        let mut bytes = b"REDIS0009".to_vec();
+        bytes.extend_from_slice(&[rdb::OP_RESIZEDB, 1, 0, rdb::TYPE_STRING]);
+        rdb::write_string(&mut bytes, b"key");
+        bytes.push(rdb::OP_EOF);
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
+        let _ = file.write_all(&bytes);
+
+        let load = redis.execute(Command::Load { path });
+
+        assert!(load.is_err());
+        fs::remove_file("test_load_empy_file_returns_err.rdb").unwrap();
     }
 
     #[test]
-    fn test_set_two_elements_and_del_both() {
+    fn test_config_get_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
-        let key = "key1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let value = "value".to_string();
-        let key = "key2".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let config_get = redis.execute(Command::ConfigGet {
+            patterns: vec!["DB*".to_string(), "port".to_string(), "*file*".to_string()],
+        });
+        let conf = vec![
+            "dbfilename".to_string(),
+            "dump.rdb".to_string(),
+            "logfile".to_string(),
+            "log.log".to_string(),
+            "port".to_string(),
+            "8080".to_string(),
+        ];
+        assert!(eq_response(Re::List(conf), config_get.unwrap()));
 
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let del = redis.execute(Command::Del { keys });
+        let config_get = redis.execute(Command::ConfigGet {
+            patterns: vec!["*".to_string()],
+        });
+        match config_get.unwrap() {
+            Response::Normal(Re::List(conf)) => {
+                assert_eq!(50, conf.len());
+                assert!(conf.windows(2).any(|pair| pair == ["timeout", "0"]));
+                assert!(conf.windows(2).any(|pair| pair == ["loglevel", "debug"]));
+            }
+            _ => panic!("CONFIG GET did not return a list"),
+        }
 
-        assert!(eq_response(Re::String("2".to_string()), del.unwrap()));
+        let config_get = redis.execute(Command::ConfigGet {
+            patterns: vec!["nonexistent".to_string()],
+        });
+        assert!(eq_response(Re::List(vec![]), config_get.unwrap()));
     }
 
     #[test]
-    fn test_append_adds_word() {
+    fn test_config_set_runtime_parameters() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        for (parameter, value) in [
+            ("timeout", "60"),
+            ("maxmemory", "1mb"),
+            ("save", "60 100"),
+            ("loglevel", "info"),
+        ] {
+            let config_set = redis.execute(Command::ConfigSet {
+                parameter: parameter.to_string(),
+                value: value.to_string(),
+            });
+            assert!(eq_response(
+                Re::SimpleString("OK".to_string()),
+                config_set.unwrap()
+            ));
+        }
 
-        let key = "key".to_string();
-        let value = " appended".to_string();
-        let _append = redis.execute(Command::Append { key, value });
+        let config_get = redis.execute(Command::ConfigGet {
+            patterns: vec![
+                "timeout".to_string(),
+                "maxmemory".to_string(),
+                "save".to_string(),
+                "loglevel".to_string(),
+            ],
+        });
+        let conf = vec![
+            "timeout",
+            "60",
+            "loglevel",
+            "info",
+            "save",
+            "60 100",
+            "maxmemory",
+        ]
+        .into_iter()
+        .map(|value| value.to_string())
+        .chain(vec![(1024 * 1024).to_string()])
+        .collect();
+        assert!(eq_response(Re::List(conf), config_get.unwrap()));
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(
-            Re::String("value appended".to_string()),
-            get.unwrap(),
-        ));
+        let config_set = redis.execute(Command::ConfigSet {
+            parameter: "timeout".to_string(),
+            value: "soon".to_string(),
+        });
+        assert_eq!(
+            Some("ERR Invalid argument 'soon' for CONFIG SET 'timeout'".to_string()),
+            config_set.err()
+        );
+        assert_eq!(60, redis.config.lock().unwrap().get_timeout());
     }
 
     #[test]
-    fn test_append_on_non_existent_key() {
+    fn test_config_set_verbose() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = " appended".to_string();
-        let _append = redis.execute(Command::Append { key, value });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let parameter = "verbose".to_string();
+        let value = "1".to_string();
+        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        assert!(eq_response(
-            Re::String(" appended".to_string()),
-            get.unwrap(),
-        ));
+        assert_eq!("1", redis.config.lock().unwrap().get_verbose());
     }
 
     #[test]
-    fn test_set_two_elements_and_check_exists_equal_2() {
+    fn test_config_set_dbfilename() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key1".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key2".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let exists = redis.execute(Command::Exists { keys });
-        assert!(eq_response(Re::String("2".to_string()), exists.unwrap()));
+        let parameter = "dbfilename".to_string();
+        let value = "new_dump.rdb".to_string();
+        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        let keys = vec!["key1".to_string(), "key2".to_string(), "key3".to_string()];
-        let exists = redis.execute(Command::Exists { keys });
-        assert!(eq_response(Re::String("2".to_string()), exists.unwrap()));
+        assert_eq!(
+            "new_dump.rdb",
+            redis.config.lock().unwrap().get_dbfilename()
+        );
     }
 
     #[test]
-    fn test_copy_on_existing_key_returns_0() {
+    fn test_config_set_logfile() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key2".to_string();
-        let value = "value2".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key_origin: String = "key1".to_string();
-        let key_destination: String = "key2".to_string();
-        let copy = redis.execute(Command::Copy {
-            key_destination,
-            key_origin,
-        });
+        let parameter = "logfile".to_string();
+        let value = "new_log.log".to_string();
+        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        assert!(eq_response(Re::String("0".to_string()), copy.unwrap()));
+        assert_eq!("new_log.log", redis.config.lock().unwrap().get_logfile());
     }
 
     #[test]
-    fn test_copy_ok() {
+    fn test_config_set_wrong_parameter() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key_origin: String = "key1".to_string();
-        let key_destination: String = "key2".to_string();
-        let _copy = redis.execute(Command::Copy {
-            key_destination,
-            key_origin,
-        });
+        let parameter = "appendonly".to_string();
+        let value = "yes".to_string();
+        let config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        let key = "key2".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+        assert!(config_set.is_err());
     }
 
-    #[ignore]
     #[test]
-    fn test_expire_deletes_key() {
+    fn test_info_connected_clients_ok() {
         let mut redis: Redis = Redis::new_for_test();
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConnectedClients,
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let expire = redis.execute(Command::Expire { key, ttl });
+        assert!(info.is_ok());
+        assert!(eq_response(Re::Integer(0), info.unwrap()));
 
-        thread::sleep(Duration::from_secs(1));
+        let _ = redis.execute(Command::AddClient);
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConnectedClients,
+        });
+        assert!(info.is_ok());
+        assert!(eq_response(Re::Integer(1), info.unwrap()));
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
-        assert!(eq_response(Re::String("1".to_string()), expire.unwrap()));
+        let _ = redis.execute(Command::RemoveClient { client_id: 1 });
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConnectedClients,
+        });
+        assert!(info.is_ok());
+        assert!(eq_response(Re::Integer(0), info.unwrap()));
     }
 
     #[test]
-    fn test_expire_returns_0_on_unexisting_key() {
+    fn test_info_port_ok() {
         let mut redis: Redis = Redis::new_for_test();
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Port,
+        });
 
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let expire = redis.execute(Command::Expire { key, ttl });
-
-        assert!(eq_response(Re::String("0".to_string()), expire.unwrap()));
+        assert!(info.is_ok());
+        assert!(eq_response(Re::String("8080".to_string()), info.unwrap()));
     }
 
     #[test]
-    fn test_expireat_with_past_time_deletes_key() {
+    fn test_info_config_file_ok() {
         let mut redis: Redis = Redis::new_for_test();
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConfigFile,
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
-        let expire = redis.execute(Command::Expireat { key, ttl });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
-        assert!(eq_response(Re::String("1".to_string()), expire.unwrap()));
+        assert!(info.is_ok());
+        assert!(eq_response(
+            Re::String(redis.config.lock().unwrap().get_configfile()),
+            info.unwrap()
+        ));
     }
 
     #[test]
-    fn test_expireat_returns_0_on_unexisting_key() {
+    fn test_info_process_id_ok() {
         let mut redis: Redis = Redis::new_for_test();
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ProcessId,
+        });
 
-        let key = "key".to_string();
-        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
-        let expire = redis.execute(Command::Expireat { key, ttl });
-
-        assert!(eq_response(Re::String("0".to_string()), expire.unwrap()));
+        assert!(info.is_ok());
+        assert!(eq_response(
+            Re::Integer(process::id() as i64),
+            info.unwrap()
+        ));
     }
 
-    #[ignore]
     #[test]
-    fn test_persist_deletes_expire_time() {
+    fn test_info_replication_ok() {
         let mut redis: Redis = Redis::new_for_test();
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Replication,
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let _expire = redis.execute(Command::Expire { key, ttl });
+        match info.unwrap() {
+            Response::Normal(Re::String(section)) => {
+                let lines: Vec<&str> = section.split("\r\n").collect();
+                assert_eq!("# Replication", lines[0]);
+                assert!(lines.contains(&"role:master"));
+                assert!(lines.contains(&"connected_slaves:0"));
+                assert!(lines.contains(&"master_repl_offset:0"));
+                assert!(lines.contains(&format!("master_replid:{}", redis.replication_id).as_str()));
+                assert_eq!(40, redis.replication_id.len());
+            }
+            _ => panic!("INFO replication did not return a string"),
+        }
+    }
 
-        let key = "key".to_string();
-        let persist = redis.execute(Command::Persist { key });
+    #[test]
+    fn test_command_introspection() {
+        let mut redis: Redis = Redis::new_for_test();
 
-        thread::sleep(Duration::from_secs(1));
+        let count = redis.execute(Command::Command {
+            param: CommandParam::Count,
+        });
+        assert!(eq_response(
+            Re::Integer(COMMAND_TABLE.len() as i64),
+            count.unwrap()
+        ));
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let info = redis.execute(Command::Command {
+            param: CommandParam::Info(vec!["GET".to_string(), "nonexistent".to_string()]),
+        });
+        assert!(eq_response(
+            Re::Array(vec![
+                Re::Array(vec![
+                    Re::String("get".to_string()),
+                    Re::Integer(2),
+                    Re::Array(vec![
+                        Re::SimpleString("readonly".to_string()),
+                        Re::SimpleString("fast".to_string()),
+                    ]),
+                    Re::Integer(1),
+                    Re::Integer(1),
+                    Re::Integer(1),
+                ]),
+                Re::Nil,
+            ]),
+            info.unwrap()
+        ));
 
-        assert!(eq_response(Re::String("1".to_string()), persist.unwrap()));
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+        let docs = redis.execute(Command::Command {
+            param: CommandParam::Docs(vec!["lpush".to_string(), "nonexistent".to_string()]),
+        });
+        assert!(eq_response(
+            Re::Array(vec![Re::Array(vec![
+                Re::String("lpush".to_string()),
+                Re::Array(vec![
+                    Re::String("group".to_string()),
+                    Re::String("list".to_string()),
+                    Re::String("arity".to_string()),
+                    Re::Integer(-3),
+                ]),
+            ])]),
+            docs.unwrap()
+        ));
     }
 
     #[test]
-    fn test_persist_returns_0_on_persistent_value() {
+    fn test_info_commandstats_and_resetstat() {
         let mut redis: Redis = Redis::new_for_test();
+        for _ in 0..2 {
+            let _get = redis.execute(Command::Get {
+                key: "key".to_string(),
+            });
+        }
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let persist = redis.execute(Command::Persist { key });
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Commandstats,
+        });
+        match info.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.starts_with("# Commandstats\r\n"));
+                assert!(info.contains("cmdstat_get:calls=2,"));
+            }
+            _ => panic!("INFO commandstats did not return a string"),
+        }
 
-        let key: String = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let resetstat = redis.execute(Command::ConfigResetstat);
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            resetstat.unwrap()
+        ));
 
-        assert!(eq_response(Re::String("0".to_string()), persist.unwrap()));
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+        // Solo queda registrada la ejecución de CONFIG RESETSTAT.
+        let info = redis.command_stats.info();
+        assert!(!info.contains("cmdstat_get:"));
+        assert!(info.contains("cmdstat_config|resetstat:calls=1,"));
     }
 
     #[test]
-    fn test_persist_returns_0_on_unexisting_key() {
+    fn test_info_stats_counts_keyspace_hits_and_misses() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _mget = redis.execute(Command::Mget {
+            keys: vec!["key".to_string(), "missing".to_string()],
+        });
+        let _llen = redis.execute(Command::Llen {
+            key: "missing".to_string(),
+        });
 
-        let key = "key".to_string();
-        let persist = redis.execute(Command::Persist { key });
-        assert!(eq_response(Re::String("0".to_string()), persist.unwrap()));
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Stats,
+        });
+        match info.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.starts_with("# Stats\r\n"));
+                assert!(info.contains("\r\nkeyspace_hits:1\r\nkeyspace_misses:2\r\n"));
+                assert!(info.contains("\r\ntotal_net_input_bytes:0\r\n"));
+            }
+            _ => panic!("INFO stats did not return a string"),
+        }
+
+        let _resetstat = redis.execute(Command::ConfigResetstat);
+        assert_eq!((0, 0), (redis.keyspace_hits, redis.keyspace_misses));
     }
 
     #[test]
-    fn test_set_and_rename() {
+    fn test_push_and_set_mutations_keep_ttl() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".to_string(),
+            value: vec!["b".to_string()],
+        });
+        let _sadd = redis.execute(Command::Sadd {
+            key: "set".to_string(),
+            values: vec!["a".to_string(), "b".to_string()].into_iter().collect(),
+        });
+        for key in ["list", "set"] {
+            let _expire = redis.execute(Command::Expire {
+                key: key.to_string(),
+                ttl: Duration::from_secs(100),
+                conditions: vec![],
+            });
+        }
 
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let lpush = redis.execute(Command::Lpush {
+            key: "list".to_string(),
+            value: vec!["a1".to_string(), "a2".to_string()],
+        });
+        assert!(eq_response(Re::Integer(3), lpush.unwrap()));
+        let rpush = redis.execute(Command::Rpush {
+            key: "list".to_string(),
+            value: vec!["c".to_string()],
+        });
+        assert!(eq_response(Re::Integer(4), rpush.unwrap()));
+        let srem = redis.execute(Command::Srem {
+            key: "set".to_string(),
+            values: vec!["a".to_string()].into_iter().collect(),
+        });
+        assert!(eq_response(Re::Integer(1), srem.unwrap()));
 
-        let key_origin: String = "key1".to_string();
-        let key_destination: String = "key2".to_string();
-        let rename = redis.execute(Command::Rename {
-            key_origin,
-            key_destination,
+        let lrange = redis.execute(Command::Lrange {
+            key: "list".to_string(),
+            begin: 0,
+            end: -1,
         });
-        assert!(rename.is_ok());
+        let expected = vec!["a2", "a1", "b", "c"]
+            .into_iter()
+            .map(|value| value.to_string())
+            .collect();
+        assert!(eq_response(Re::List(expected), lrange.unwrap()));
+        for key in ["list", "set"] {
+            let ttl = redis.execute(Command::Ttl {
+                key: key.to_string(),
+            });
+            assert!(eq_response(Re::Integer(99), ttl.unwrap()));
+        }
+    }
 
-        let key = "key1".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
+    #[test]
+    fn test_execute_shared_reads() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _lpush = redis.execute(Command::Lpush {
+            key: "list".to_string(),
+            value: vec!["a".to_string()],
+        });
 
-        let key = "key2".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+        let get = redis.execute_shared(&Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(
+            Re::String("value".to_string()),
+            get.unwrap().unwrap()
+        ));
+        let get = redis.execute_shared(&Command::Get {
+            key: "list".to_string(),
+        });
+        assert!(matches!(get, Some(Err(e)) if e.starts_with("WRONGTYPE")));
+        let exists = redis.execute_shared(&Command::Exists {
+            keys: vec!["key".to_string(), "missing".to_string()],
+        });
+        assert!(eq_response(Re::Integer(1), exists.unwrap().unwrap()));
+        let ttl = redis.execute_shared(&Command::Ttl {
+            key: "missing".to_string(),
+        });
+        assert!(eq_response(Re::Integer(-2), ttl.unwrap().unwrap()));
+        let lpush = redis.execute_shared(&Command::Lpush {
+            key: "list".to_string(),
+            value: vec!["b".to_string()],
+        });
+        assert!(lpush.is_none());
+
+        // Las lecturas se registran en las estadísticas al ejecutar el próximo comando.
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Commandstats,
+        });
+        match info.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.contains("cmdstat_get:calls=2,"));
+                assert!(info.contains("cmdstat_exists:calls=1,"));
+                assert!(info.contains("cmdstat_ttl:calls=1,"));
+            }
+            _ => panic!("INFO commandstats did not return a string"),
+        }
+        assert_eq!((2, 0), (redis.keyspace_hits, redis.keyspace_misses));
     }
 
     #[test]
-    fn test_sort_set() {
+    fn test_execute_shared_writes() {
         let mut redis: Redis = Redis::new_for_test();
+        let _lpush = redis.execute(Command::Lpush {
+            key: "list".to_string(),
+            value: vec!["a".to_string()],
+        });
+        let dirty = redis.dirty;
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("2".to_string());
-        values.insert("1".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
-
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
+        let set = redis.execute_shared(&Command::Set {
+            key: "key".to_string(),
+            value: "10".to_string(),
+            options: SetOptions::default(),
+        });
         assert!(eq_response(
-            Re::List(vec!["1".to_string(), "2".to_string()]),
-            sort.unwrap(),
+            Re::SimpleString("OK".to_string()),
+            set.unwrap().unwrap()
         ));
+        let incrby = redis.execute_shared(&Command::Incrby {
+            key: "key".to_string(),
+            increment: 5,
+        });
+        assert!(eq_response(Re::Integer(15), incrby.unwrap().unwrap()));
+        let decrby = redis.execute_shared(&Command::Decrby {
+            key: "key".to_string(),
+            decrement: 20,
+        });
+        assert!(eq_response(Re::Integer(-5), decrby.unwrap().unwrap()));
+        let append = redis.execute_shared(&Command::Append {
+            key: "key".to_string(),
+            value: "0".to_string(),
+        });
+        assert!(eq_response(Re::Integer(3), append.unwrap().unwrap()));
+        let incrby = redis.execute_shared(&Command::Incrby {
+            key: "list".to_string(),
+            increment: 1,
+        });
+        assert!(matches!(incrby, Some(Err(e)) if e.starts_with("WRONGTYPE")));
+        let set = redis.execute_shared(&Command::Set {
+            key: "key".to_string(),
+            value: "other".to_string(),
+            options: SetOptions {
+                condition: SetCondition::IfNotExists,
+                ..SetOptions::default()
+            },
+        });
+        assert!(eq_response(Re::Nil, set.unwrap().unwrap()));
+
+        // Sólo las escrituras que modificaron la clave cuentan para las reglas de guardado.
+        redis.apply_shared_commands();
+        assert_eq!(dirty + 4, redis.dirty);
+        let get = redis.execute(Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::String("-50".to_string()), get.unwrap()));
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Commandstats,
+        });
+        match info.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.contains("cmdstat_set:calls=2,"));
+                assert!(info.contains("cmdstat_incrby:calls=2,"));
+            }
+            _ => panic!("INFO commandstats did not return a string"),
+        }
     }
 
     #[test]
-    fn test_sort_list() {
+    fn test_execute_shared_writes_fall_back_to_the_db_thread() {
         let mut redis: Redis = Redis::new_for_test();
+        let set = Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        };
+        let config_set = |redis: &mut Redis, parameter: &str, value: &str| {
+            let _ = redis.execute(Command::ConfigSet {
+                parameter: parameter.to_string(),
+                value: value.to_string(),
+            });
+        };
 
-        let key = "key".to_string();
-        let value = vec!["3".to_string(), "2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        config_set(&mut redis, "notify-keyspace-events", "K$");
+        assert!(redis.execute_shared(&set).is_none());
+        config_set(&mut redis, "notify-keyspace-events", "");
+        config_set(&mut redis, "maxmemory", "1000000");
+        assert!(redis.execute_shared(&set).is_none());
+        config_set(&mut redis, "maxmemory", "0");
+        assert!(redis.execute_shared(&set).is_some());
+        // Los límites de tamaño se verifican con el shard tomado.
+        config_set(&mut redis, "max-value-size", "8");
+        let append = redis.execute_shared(&Command::Append {
+            key: "key".to_string(),
+            value: "abcd".to_string(),
+        });
+        assert!(matches!(append, Some(Err(e)) if e == VALUE_TOO_BIG_MSG));
 
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
+        let _receiver = redis.watch_changes();
+        assert!(redis.execute_shared(&set).is_none());
+    }
 
-        assert!(eq_response(
-            Re::List(vec!["2".to_string(), "3".to_string()]),
-            sort.unwrap(),
-        ));
+    #[test]
+    fn test_execute_shared_writes_from_many_threads() {
+        let redis = Arc::new(RwLock::new(Redis::new_for_test()));
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let redis = Arc::clone(&redis);
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        let incr = Command::Incrby {
+                            key: "counter".to_string(),
+                            increment: 1,
+                        };
+                        let set = Command::Set {
+                            key: format!("key:{}", i),
+                            value: "value".to_string(),
+                            options: SetOptions::default(),
+                        };
+                        let redis = redis.read().unwrap();
+                        assert!(redis.execute_shared(&incr).is_some());
+                        assert!(redis.execute_shared(&set).is_some());
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let mut redis = redis.write().unwrap();
+        let get = redis.execute(Command::Get {
+            key: "counter".to_string(),
+        });
+        assert!(eq_response(Re::String("800".to_string()), get.unwrap()));
+        assert_eq!(101, redis.db.len());
+        assert_eq!(1600, redis.dirty);
     }
 
     #[test]
-    fn test_sort_string_returns_err() {
+    fn test_execute_shared_falls_back_on_expired_keys() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        redis
+            .db
+            .set_ttl_absolute("key".to_string(), SystemTime::UNIX_EPOCH);
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let get = redis.execute_shared(&Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(get.is_none());
+        let exists = redis.execute_shared(&Command::Exists {
+            keys: vec!["key".to_string()],
+        });
+        assert!(exists.is_none());
 
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
+        let get = redis.execute(Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+        assert_eq!(0, redis.db.len());
+    }
+
+    #[test]
+    fn test_cluster_commands() {
+        let mut redis: Redis = Redis::new_for_test();
+        let keyslot = redis.execute(Command::Cluster {
+            param: ClusterParam::KeySlot("foo".to_string()),
+        });
         assert_eq!(
-            sort.err(),
-            Some("WRONGTYPE Operation against a key holding the wrong kind of value".to_string())
+            "ERR This instance has cluster support disabled",
+            keyslot.unwrap_err()
         );
-    }
 
-    #[test]
-    fn test_sort_empty_key() {
-        let mut redis: Redis = Redis::new_for_test();
+        let cluster = Cluster::new(
+            &["127.0.0.1:8080".to_string(), "127.0.0.1:8081".to_string()],
+            8080,
+        );
+        redis.config.lock().unwrap().set_cluster(cluster.ok());
+
+        let keyslot = redis.execute(Command::Cluster {
+            param: ClusterParam::KeySlot("foo".to_string()),
+        });
+        assert!(eq_response(Re::Integer(12182), keyslot.unwrap()));
+
+        let slots = redis.execute(Command::Cluster {
+            param: ClusterParam::Slots,
+        });
+        match slots.unwrap() {
+            Response::Normal(Re::Array(nodes)) => {
+                assert_eq!(2, nodes.len());
+                assert!(matches!(&nodes[1], Re::Array(node) if node[0] == Re::Integer(8192)));
+            }
+            _ => panic!("CLUSTER SLOTS did not return an array"),
+        }
 
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
-        assert!(eq_response(Re::List(vec![]), sort.unwrap()));
+        let info = redis.execute(Command::Cluster {
+            param: ClusterParam::Info,
+        });
+        match info.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.contains("cluster_state:ok\r\n"));
+                assert!(info.contains("cluster_known_nodes:2\r\n"));
+            }
+            _ => panic!("CLUSTER INFO did not return a string"),
+        }
     }
 
     #[test]
-    fn test_sort_non_numeric_value_returns_err() {
+    fn test_zadd_and_zrange_ordered_by_score() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = vec!["value1".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let members = vec![
+            (3.0, "c".to_string()),
+            (1.0, "a".to_string()),
+            (2.0, "b".to_string()),
+        ];
+        let zadd = redis.execute(Command::Zadd { key, members });
+        assert!(eq_response(Re::Integer(3), zadd.unwrap()));
 
         let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
-        assert_eq!(
-            sort.err(),
-            Some("ERR One or more scores can't be converted into double".to_string())
-        );
+        let zrange = redis.execute(Command::Zrange {
+            key,
+            start: 0,
+            stop: -1,
+            with_scores: false,
+        });
+        assert!(eq_response(
+            Re::List(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            zrange.unwrap()
+        ));
     }
 
     #[test]
-    fn test_ttl_returns_neg2_on_unexisting_key() {
+    fn test_zadd_existing_member_updates_score() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let ttl = redis.execute(Command::Ttl { key });
-
-        assert!(eq_response(Re::String("-2".to_string()), ttl.unwrap()));
-    }
-
-    #[test]
-    fn test_ttl_returns_neg1_on_persistent_value() {
-        let mut redis: Redis = Redis::new_for_test();
+        let members = vec![(1.0, "a".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
         let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let members = vec![(5.5, "a".to_string()), (2.0, "b".to_string())];
+        let zadd = redis.execute(Command::Zadd { key, members });
+        assert!(eq_response(Re::Integer(1), zadd.unwrap()));
 
         let key = "key".to_string();
-        let ttl = redis.execute(Command::Ttl { key });
+        let member = "a".to_string();
+        let zscore = redis.execute(Command::Zscore { key, member });
+        assert!(eq_response(Re::String("5.5".to_string()), zscore.unwrap()));
 
-        assert!(eq_response(Re::String("-1".to_string()), ttl.unwrap()));
+        let key = "key".to_string();
+        let zcard = redis.execute(Command::Zcard { key });
+        assert!(eq_response(Re::Integer(2), zcard.unwrap()));
     }
 
     #[test]
-    fn test_ttl_returns_secs_remaining() {
+    fn test_zadd_fails_if_is_not_sorted_set() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
         let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(5);
-        let _expire = redis.execute(Command::Expire { key, ttl });
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
         let key = "key".to_string();
-        let ttl = redis.execute(Command::Ttl { key });
-
-        let _key: String = "key".to_string();
+        let members = vec![(1.0, "a".to_string())];
+        let zadd = redis.execute(Command::Zadd { key, members });
 
-        assert!(eq_response(Re::String("4".to_string()), ttl.unwrap()));
+        assert!(zadd.is_err());
     }
 
     #[test]
-    fn test_type_on_string() {
+    fn test_zscore_on_missing_member_returns_nil() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let members = vec![(1.0, "a".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
         let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
-        assert!(eq_response(
-            Re::String("string".to_string()),
-            type_method.unwrap(),
-        ));
+        let member = "b".to_string();
+        let zscore = redis.execute(Command::Zscore { key, member });
+        assert!(eq_response(Re::Nil, zscore.unwrap()));
     }
 
     #[test]
-    fn test_type_on_empty_key() {
+    fn test_zrangebyscore_with_exclusive_min() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
+        let members = vec![
+            (1.0, "a".to_string()),
+            (2.0, "b".to_string()),
+            (3.0, "c".to_string()),
+        ];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
+        let key = "key".to_string();
+        let zrangebyscore = redis.execute(Command::Zrangebyscore {
+            key,
+            min: ScoreBound::Exclusive(1.0),
+            max: ScoreBound::Inclusive(f64::INFINITY),
+            with_scores: false,
+        });
         assert!(eq_response(
-            Re::String("none".to_string()),
-            type_method.unwrap(),
+            Re::List(vec!["b".to_string(), "c".to_string()]),
+            zrangebyscore.unwrap()
         ));
     }
 
     #[test]
-    fn test_type_on_list() {
+    fn test_zrange_withscores() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = vec!["value".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let members = vec![(2.5, "b".to_string()), (1.0, "a".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
         let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
+        let zrange = redis.execute(Command::Zrange {
+            key,
+            start: 0,
+            stop: -1,
+            with_scores: true,
+        });
         assert!(eq_response(
-            Re::String("list".to_string()),
-            type_method.unwrap(),
+            Re::List(vec![
+                "a".to_string(),
+                "1".to_string(),
+                "b".to_string(),
+                "2.5".to_string()
+            ]),
+            zrange.unwrap()
+        ));
+
+        let key = "key".to_string();
+        let zrangebyscore = redis.execute(Command::Zrangebyscore {
+            key,
+            min: ScoreBound::Inclusive(2.0),
+            max: ScoreBound::Inclusive(3.0),
+            with_scores: true,
+        });
+        assert!(eq_response(
+            Re::List(vec!["b".to_string(), "2.5".to_string()]),
+            zrangebyscore.unwrap()
         ));
     }
 
     #[test]
-    fn test_type_on_set() {
+    fn test_zincrby_creates_and_increments_members() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let zincrby = redis.execute(Command::Zincrby {
+            key: "key".to_string(),
+            increment: 2.0,
+            member: "a".to_string(),
+        });
+        assert!(eq_response(Re::String("2".to_string()), zincrby.unwrap()));
 
-        let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
-        assert!(eq_response(
-            Re::String("set".to_string()),
-            type_method.unwrap(),
-        ));
+        let zincrby = redis.execute(Command::Zincrby {
+            key: "key".to_string(),
+            increment: -0.5,
+            member: "a".to_string(),
+        });
+        assert!(eq_response(Re::String("1.5".to_string()), zincrby.unwrap()));
+
+        let zincrby = redis.execute(Command::Zincrby {
+            key: "key".to_string(),
+            increment: f64::INFINITY,
+            member: "b".to_string(),
+        });
+        assert!(eq_response(Re::String("inf".to_string()), zincrby.unwrap()));
+
+        let zincrby = redis.execute(Command::Zincrby {
+            key: "key".to_string(),
+            increment: f64::NEG_INFINITY,
+            member: "b".to_string(),
+        });
+        assert_eq!(NAN_SCORE_MSG, zincrby.unwrap_err());
+
+        let zincrby = redis.execute(Command::Zincrby {
+            key: "missing".to_string(),
+            increment: f64::NAN,
+            member: "a".to_string(),
+        });
+        assert!(zincrby.is_err());
+        assert!(redis.db.get(&"missing".to_string()).is_none());
     }
 
     #[test]
-    fn test_lindex_with_key_used_err() {
+    fn test_zrank() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let members = vec![(3.0, "c".to_string()), (1.0, "a".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
-        let key = "key".to_string();
-        let index = 1;
-        let lindex = redis.execute(Command::Lindex { key, index });
+        let zrank = redis.execute(Command::Zrank {
+            key: "key".to_string(),
+            member: "c".to_string(),
+        });
+        assert!(eq_response(Re::Integer(1), zrank.unwrap()));
 
-        assert!(lindex.is_err());
+        let zrank = redis.execute(Command::Zrank {
+            key: "key".to_string(),
+            member: "b".to_string(),
+        });
+        assert!(eq_response(Re::Nil, zrank.unwrap()));
+
+        let zrank = redis.execute(Command::Zrank {
+            key: "missing".to_string(),
+            member: "a".to_string(),
+        });
+        assert!(eq_response(Re::Nil, zrank.unwrap()));
     }
 
     #[test]
-    fn test_lindex_ok() {
+    fn test_zpopmin_and_zpopmax() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let members = vec![
+            (1.0, "a".to_string()),
+            (2.0, "b".to_string()),
+            (3.0, "c".to_string()),
+        ];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
-        let key = "key".to_string();
-        let index = 0;
-        let lindex = redis.execute(Command::Lindex { key, index });
+        let zpopmax = redis.execute(Command::Zpopmax {
+            key: "key".to_string(),
+            count: None,
+        });
+        assert!(eq_response(
+            Re::List(vec!["c".to_string(), "3".to_string()]),
+            zpopmax.unwrap()
+        ));
 
-        assert!(lindex.is_ok());
+        let zpopmin = redis.execute(Command::Zpopmin {
+            key: "key".to_string(),
+            count: Some(5),
+        });
         assert!(eq_response(
-            Re::String("value2".to_string()),
-            lindex.unwrap(),
+            Re::List(vec![
+                "a".to_string(),
+                "1".to_string(),
+                "b".to_string(),
+                "2".to_string()
+            ]),
+            zpopmin.unwrap()
         ));
+
+        let _type = redis.execute(Command::Type {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::String("none".to_string()), _type.unwrap()));
+
+        let zpopmin = redis.execute(Command::Zpopmin {
+            key: "key".to_string(),
+            count: None,
+        });
+        assert!(eq_response(Re::List(vec![]), zpopmin.unwrap()));
     }
 
     #[test]
-    fn test_lindex_negative_index_ok() {
+    fn test_zunionstore_with_weights_and_sets() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "zset".to_string();
+        let members = vec![(1.0, "a".to_string()), (2.0, "b".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
+        let key = "set".to_string();
+        let values = vec!["b".to_string(), "c".to_string()].into_iter().collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
-        let key = "key".to_string();
-        let index = -1;
-        let lindex = redis.execute(Command::Lindex { key, index });
+        let zunionstore = redis.execute(Command::Zunionstore {
+            destination: "dest".to_string(),
+            keys: vec!["zset".to_string(), "set".to_string(), "missing".to_string()],
+            weights: vec![10.0, 1.0, 1.0],
+            aggregate: Aggregate::Sum,
+        });
+        assert!(eq_response(Re::Integer(3), zunionstore.unwrap()));
 
-        assert!(lindex.is_ok());
+        let zrange = redis.execute(Command::Zrange {
+            key: "dest".to_string(),
+            start: 0,
+            stop: -1,
+            with_scores: true,
+        });
         assert!(eq_response(
-            Re::String("value".to_string()),
-            lindex.unwrap(),
+            Re::List(vec![
+                "c".to_string(),
+                "1".to_string(),
+                "a".to_string(),
+                "10".to_string(),
+                "b".to_string(),
+                "21".to_string()
+            ]),
+            zrange.unwrap()
         ));
+
+        let key = "string".to_string();
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+        let zunionstore = redis.execute(Command::Zunionstore {
+            destination: "dest".to_string(),
+            keys: vec!["zset".to_string(), "string".to_string()],
+            weights: vec![1.0, 1.0],
+            aggregate: Aggregate::Sum,
+        });
+        assert!(zunionstore.is_err());
     }
 
     #[test]
-    fn test_lindex_negative_index_result_nil_ok() {
+    fn test_zinterstore_aggregate_and_empty_result() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let index = -3;
-        let lindex = redis.execute(Command::Lindex { key, index });
+        let key = "z1".to_string();
+        let members = vec![(1.0, "a".to_string()), (5.0, "b".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
+        let key = "z2".to_string();
+        let members = vec![(3.0, "b".to_string()), (4.0, "c".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
+
+        let zinterstore = redis.execute(Command::Zinterstore {
+            destination: "z1".to_string(),
+            keys: vec!["z1".to_string(), "z2".to_string()],
+            weights: vec![1.0, 1.0],
+            aggregate: Aggregate::Min,
+        });
+        assert!(eq_response(Re::Integer(1), zinterstore.unwrap()));
+        let zscore = redis.execute(Command::Zscore {
+            key: "z1".to_string(),
+            member: "b".to_string(),
+        });
+        assert!(eq_response(Re::String("3".to_string()), zscore.unwrap()));
 
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::Nil, lindex.unwrap()));
+        let zinterstore = redis.execute(Command::Zinterstore {
+            destination: "z1".to_string(),
+            keys: vec!["z2".to_string(), "missing".to_string()],
+            weights: vec![1.0, 1.0],
+            aggregate: Aggregate::Sum,
+        });
+        assert!(eq_response(Re::Integer(0), zinterstore.unwrap()));
+        let _type = redis.execute(Command::Type {
+            key: "z1".to_string(),
+        });
+        assert!(eq_response(Re::String("none".to_string()), _type.unwrap()));
     }
 
     #[test]
-    fn test_llen_key_saved_as_string_ok() {
+    fn test_zrem_last_member_deletes_key() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let members = vec![(1.0, "a".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
         let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
+        let members = vec!["a".to_string(), "b".to_string()];
+        let zrem = redis.execute(Command::Zrem { key, members });
+        assert!(eq_response(Re::Integer(1), zrem.unwrap()));
 
-        assert!(llen.is_err());
+        let key = "key".to_string();
+        let _type = redis.execute(Command::Type { key });
+        assert!(eq_response(Re::String("none".to_string()), _type.unwrap()));
     }
 
     #[test]
-    fn test_llen_key_not_found_ok() {
+    fn test_type_sorted_set() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
+        let members = vec![(1.0, "a".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), llen.unwrap()));
+        let key = "key".to_string();
+        let _type = redis.execute(Command::Type { key });
+        assert!(eq_response(Re::String("zset".to_string()), _type.unwrap()));
     }
 
     #[test]
-    fn test_llen_key_used_twice_ok() {
+    fn test_auth_without_requirepass_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let auth = redis.execute(Command::Auth {
+            password: "secret".to_string(),
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        assert!(auth.is_err());
+    }
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
+    #[test]
+    fn test_auth_with_right_password_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+        redis
+            .config
+            .lock()
+            .unwrap()
+            .set_requirepass("secret".to_string());
+
+        let auth = redis.execute(Command::Auth {
+            password: "secret".to_string(),
+        });
 
-        assert!(eq_response(Re::String("4".to_string()), llen.unwrap()));
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            auth.unwrap()
+        ));
     }
 
     #[test]
-    fn test_lpop_without_count_ok() {
+    fn test_auth_with_wrong_password_err() {
         let mut redis: Redis = Redis::new_for_test();
+        redis
+            .config
+            .lock()
+            .unwrap()
+            .set_requirepass("secret".to_string());
+
+        let auth = redis.execute(Command::Auth {
+            password: "wrong".to_string(),
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        assert_eq!(
+            "WRONGPASS invalid username-password pair or user is disabled.",
+            auth.err().unwrap()
+        );
+    }
 
-        let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 0 });
-        assert!(lpop.is_ok());
-        assert!(eq_response(Re::String("value2".to_string()), lpop.unwrap()));
+    #[test]
+    fn test_sscan_iterates_all_members_with_cursor() {
+        let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), llen.unwrap()));
+        let values: HashSet<String> = vec!["a".to_string(), "b".to_string(), "c".to_string()]
+            .into_iter()
+            .collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let mut cursor = 0;
+        let mut pages = vec![];
+        loop {
+            let sscan = redis.execute(Command::Sscan {
+                key: "key".to_string(),
+                cursor,
+                pattern: None,
+                count: 2,
+            });
+            match sscan.unwrap() {
+                Response::Normal(Re::Array(reply)) => match &reply[..] {
+                    [Re::String(next), Re::List(members)] => {
+                        pages.push(members.clone());
+                        cursor = next.parse().unwrap();
+                    }
+                    _ => panic!(),
+                },
+                _ => panic!(),
+            }
+            if cursor == 0 {
+                break;
+            }
+        }
+
+        assert_eq!(2, pages.len());
+        assert_eq!(2, pages[0].len());
+        let mut members: Vec<String> = pages.concat();
+        members.sort();
+        assert_eq!(vec!["a", "b", "c"], members);
     }
 
     #[test]
-    fn test_lpop_with_count_ok() {
+    fn test_sscan_returns_members_present_during_the_whole_iteration() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let values: HashSet<String> = (0..20).map(|i| format!("member{}", i)).collect();
+        let _sadd = redis.execute(Command::Sadd {
+            key: "key".to_string(),
+            values: values.clone(),
+        });
 
-        let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 2 });
-        assert!(lpop.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value4".to_string(), "value3".to_string()]),
-            lpop.unwrap(),
-        ));
+        let mut cursor = 0;
+        let mut found: HashSet<String> = HashSet::new();
+        let mut removed: Option<String> = None;
+        loop {
+            let sscan = redis.execute(Command::Sscan {
+                key: "key".to_string(),
+                cursor,
+                pattern: None,
+                count: 3,
+            });
+            let members = match sscan.unwrap() {
+                Response::Normal(Re::Array(reply)) => match &reply[..] {
+                    [Re::String(next), Re::List(members)] => {
+                        cursor = next.parse().unwrap();
+                        members.clone()
+                    }
+                    _ => panic!(),
+                },
+                _ => panic!(),
+            };
+            // Se quita un miembro ya devuelto, lo que antes corría a los que faltaban recorrer.
+            if removed.is_none() {
+                removed = members.first().cloned();
+                let _srem = redis.execute(Command::Srem {
+                    key: "key".to_string(),
+                    values: removed.iter().cloned().collect(),
+                });
+            }
+            found.extend(members);
+            if cursor == 0 {
+                break;
+            }
+        }
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), llen.unwrap()));
+        assert_eq!(values, found);
     }
 
     #[test]
-    fn test_lpop_with_count_major_than_len_ok() {
+    fn test_sscan_with_match_filters_members() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let values: HashSet<String> = vec![
+            "apple".to_string(),
+            "banana".to_string(),
+            "avocado".to_string(),
+        ]
+        .into_iter()
+        .collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
         let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 5 });
-        assert!(lpop.is_ok());
+        let sscan = redis.execute(Command::Sscan {
+            key,
+            cursor: 0,
+            pattern: Some("a*".to_string()),
+            count: 10,
+        });
         assert!(eq_response(
-            Re::List(vec![
-                "value4".to_string(),
-                "value3".to_string(),
-                "value2".to_string(),
-                "value".to_string()
+            Re::Array(vec![
+                Re::String("0".to_string()),
+                Re::List(vec!["apple".to_string(), "avocado".to_string()])
             ]),
-            lpop.unwrap(),
+            sscan.unwrap()
         ));
+    }
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), llen.unwrap()));
+    #[test]
+    fn test_sscan_match_uses_glob_patterns() {
+        let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 5 });
-        assert!(lpop.is_ok());
-        assert!(eq_response(Re::Nil, lpop.unwrap()));
+        let values: HashSet<String> = vec![
+            "a.b".to_string(),
+            "axb".to_string(),
+            "ab".to_string(),
+            "abc".to_string(),
+        ]
+        .into_iter()
+        .collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let mut sscan = |pattern: &str| {
+            redis.execute(Command::Sscan {
+                key: "key".to_string(),
+                cursor: 0,
+                pattern: Some(pattern.to_string()),
+                count: 10,
+            })
+        };
+        let found = |members: Vec<&str>| {
+            Re::Array(vec![
+                Re::String("0".to_string()),
+                Re::List(members.into_iter().map(String::from).collect()),
+            ])
+        };
+
+        assert!(eq_response(
+            found(vec!["a.b", "ab", "abc", "axb"]),
+            sscan("*").unwrap()
+        ));
+        assert!(eq_response(
+            found(vec!["a.b", "axb"]),
+            sscan("a?b").unwrap()
+        ));
+        assert!(eq_response(found(vec!["a.b"]), sscan("a.b").unwrap()));
+        assert!(eq_response(found(vec!["ab", "abc"]), sscan("ab*").unwrap()));
     }
 
     #[test]
-    fn test_lpop_with_saved_string_err() {
+    fn test_sscan_wrong_type_err() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
         let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
         let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 5 });
-        assert!(lpop.is_err());
+        let sscan = redis.execute(Command::Sscan {
+            key,
+            cursor: 0,
+            pattern: None,
+            count: 10,
+        });
+        assert!(sscan.is_err());
     }
 
     #[test]
-    fn test_lrange_ok() {
+    fn test_blpop_returns_first_element_of_first_non_empty_list() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
+        let key = "list".to_string();
+        let value = vec!["a".to_string(), "b".to_string()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
 
-        let _lpush = redis.execute(Command::Lpush { key, value });
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let keys = vec!["empty".to_string(), "list".to_string()];
+        let blpop = redis.execute(Command::Blpop {
+            keys,
+            timeout: None,
         });
-
-        assert!(lrange.is_ok());
         assert!(eq_response(
-            Re::List(vec![
-                "value4".to_string(),
-                "value3".to_string(),
-                "value2".to_string(),
-                "value1".to_string()
-            ]),
-            lrange.unwrap(),
+            Re::List(vec!["list".to_string(), "a".to_string()]),
+            blpop.unwrap()
         ));
     }
 
     #[test]
-    fn test_lrange_ranges_incorrect_return_empty_vec_ok() {
+    fn test_brpop_blocked_client_is_served_on_push() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
+        let keys = vec!["list".to_string()];
+        let brpop = redis.execute(Command::Brpop {
+            keys,
+            timeout: None,
+        });
+        let receiver = match brpop.unwrap() {
+            Response::Blocked(receiver) => receiver,
+            _ => panic!("BRPOP should block on an empty list"),
+        };
 
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "list".to_string();
+        let value = vec!["a".to_string(), "b".to_string()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
 
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: -1,
-            end: 0,
-        });
+        assert_eq!(
+            Re::List(vec!["list".to_string(), "b".to_string()]),
+            receiver.recv().unwrap()
+        );
 
-        assert!(lrange.is_ok());
-        assert!(eq_response(Re::List(vec![]), lrange.unwrap()));
+        let key = "list".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(eq_response(Re::Integer(1), llen.unwrap()));
     }
 
     #[test]
-    fn test_lrange_using_ranges_ok() {
+    fn test_blpop_expired_client_receives_nil() {
         let mut redis: Redis = Redis::new_for_test();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        redis.set_clock(clock.clone());
 
-        let key = "key".to_string();
-        let value = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
+        let keys = vec!["list".to_string()];
+        let blpop = redis.execute(Command::Blpop {
+            keys,
+            timeout: Some(Duration::from_secs(1)),
+        });
+        let receiver = match blpop.unwrap() {
+            Response::Blocked(receiver) => receiver,
+            _ => panic!("BLPOP should block on an empty list"),
+        };
 
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        clock.advance(Duration::from_millis(999));
+        redis.expire_blocked_clients();
+        assert!(receiver.try_recv().is_err());
 
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 2,
-            end: 4,
-        });
+        clock.advance(Duration::from_millis(1));
+        redis.expire_blocked_clients();
+        assert_eq!(Re::Nil, receiver.recv().unwrap());
 
-        assert!(lrange.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value2".to_string(), "value1".to_string(),]),
-            lrange.unwrap(),
-        ));
+        let key = "list".to_string();
+        let value = vec!["a".to_string()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
+
+        let key = "list".to_string();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(eq_response(Re::Integer(1), llen.unwrap()));
     }
 
     #[test]
-    fn test_lrange_for_string_value_err() {
+    fn test_blpop_wrong_type_err() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = "value1".to_string();
-
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
             key,
-            begin: 2,
-            end: 4,
+            value,
+            options: SetOptions::default(),
         });
 
-        assert!(lrange.is_err());
+        let keys = vec!["key".to_string()];
+        let blpop = redis.execute(Command::Blpop {
+            keys,
+            timeout: None,
+        });
+        assert!(blpop.is_err());
     }
 
     #[test]
-    fn test_lset_ok() {
+    fn test_bzpopmin_pops_from_first_non_empty_sorted_set() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "zset".to_string();
+        let members = vec![(2.0, "b".to_string()), (1.0, "a".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
 
-        let key = "key".to_string();
-        let index = -1;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
-            key,
-            index,
-            element,
+        let keys = vec!["empty".to_string(), "zset".to_string()];
+        let bzpopmin = redis.execute(Command::Bzpopmin {
+            keys,
+            timeout: None,
         });
-
-        assert!(lset.is_ok());
         assert!(eq_response(
-            Re::SimpleString("OK".to_string()),
-            lset.unwrap(),
+            Re::List(vec!["zset".to_string(), "a".to_string(), "1".to_string()]),
+            bzpopmin.unwrap()
         ));
 
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let keys = vec!["zset".to_string()];
+        let bzpopmin = redis.execute(Command::Bzpopmin {
+            keys,
+            timeout: None,
         });
-
-        assert!(lrange.is_ok());
         assert!(eq_response(
-            Re::List(vec!["value2".to_string(), "Nuevos".to_string(),]),
-            lrange.unwrap(),
+            Re::List(vec!["zset".to_string(), "b".to_string(), "2".to_string()]),
+            bzpopmin.unwrap()
         ));
+
+        let key = "zset".to_string();
+        let exists = redis.execute(Command::Exists { keys: vec![key] });
+        assert!(eq_response(Re::Integer(0), exists.unwrap()));
     }
 
     #[test]
-    fn test_lset_out_of_range_err() {
+    fn test_bzpopmax_blocked_client_is_served_on_zadd() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let keys = vec!["zset".to_string()];
+        let blpop = redis.execute(Command::Blpop {
+            keys,
+            timeout: None,
+        });
+        let list_receiver = match blpop.unwrap() {
+            Response::Blocked(receiver) => receiver,
+            _ => panic!("BLPOP should block on a missing key"),
+        };
 
-        let key = "key".to_string();
-        let index = -50;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
-            key,
-            index,
-            element,
+        let keys = vec!["zset".to_string()];
+        let bzpopmax = redis.execute(Command::Bzpopmax {
+            keys,
+            timeout: None,
         });
+        let receiver = match bzpopmax.unwrap() {
+            Response::Blocked(receiver) => receiver,
+            _ => panic!("BZPOPMAX should block on a missing key"),
+        };
 
-        assert!(lset.is_err());
+        let key = "zset".to_string();
+        let members = vec![(1.0, "a".to_string()), (2.0, "b".to_string())];
+        let _zadd = redis.execute(Command::Zadd { key, members });
+
+        assert_eq!(
+            Re::List(vec!["zset".to_string(), "b".to_string(), "2".to_string()]),
+            receiver.recv().unwrap()
+        );
+        assert!(list_receiver.try_recv().is_err());
+
+        let key = "zset".to_string();
+        let zcard = redis.execute(Command::Zcard { key });
+        assert!(eq_response(Re::Integer(1), zcard.unwrap()));
     }
 
     #[test]
-    fn test_lset_out_of_range_upper_err() {
+    fn test_bzpopmin_expired_client_receives_nil() {
+        let mut redis: Redis = Redis::new_for_test();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        redis.set_clock(clock.clone());
+
+        let keys = vec!["zset".to_string()];
+        let bzpopmin = redis.execute(Command::Bzpopmin {
+            keys,
+            timeout: Some(Duration::from_secs(1)),
+        });
+        let receiver = match bzpopmin.unwrap() {
+            Response::Blocked(receiver) => receiver,
+            _ => panic!("BZPOPMIN should block on a missing key"),
+        };
+
+        clock.advance(Duration::from_secs(1));
+        redis.expire_blocked_clients();
+        assert_eq!(Re::Nil, receiver.recv().unwrap());
+    }
+
+    #[test]
+    fn test_spop_removes_returned_members() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let values: HashSet<String> = vec!["a".to_string(), "b".to_string(), "c".to_string()]
+            .into_iter()
+            .collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
         let key = "key".to_string();
-        let index = 70;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
+        let spop = redis.execute(Command::Spop {
             key,
-            index,
-            element,
+            count: Some(2),
         });
+        let popped = match spop.unwrap() {
+            Response::Normal(Re::List(popped)) => popped,
+            _ => vec![],
+        };
+        assert_eq!(2, popped.len());
 
-        assert!(lset.is_err());
+        let key = "key".to_string();
+        let scard = redis.execute(Command::Scard { key });
+        assert!(eq_response(Re::Integer(1), scard.unwrap()));
+
+        for member in popped {
+            let key = "key".to_string();
+            let sismember = redis.execute(Command::Sismember { key, value: member });
+            assert!(eq_response(Re::Integer(0), sismember.unwrap()));
+        }
     }
 
     #[test]
-    fn test_lset_key_not_found_err() {
+    fn test_spop_without_count_on_missing_key_returns_nil() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let index = 70;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
+        let spop = redis.execute(Command::Spop { key, count: None });
+        assert!(eq_response(Re::Nil, spop.unwrap()));
+    }
+
+    #[test]
+    fn test_srandmember_positive_count_returns_distinct_members() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".to_string();
+        let values: HashSet<String> = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".to_string();
+        let srandmember = redis.execute(Command::Srandmember {
             key,
-            index,
-            element,
+            count: Some(5),
         });
+        let mut members = match srandmember.unwrap() {
+            Response::Normal(Re::List(members)) => members,
+            _ => vec![],
+        };
+        members.sort();
+        assert_eq!(vec!["a".to_string(), "b".to_string()], members);
 
-        assert!(lset.is_err());
+        let key = "key".to_string();
+        let scard = redis.execute(Command::Scard { key });
+        assert!(eq_response(Re::Integer(2), scard.unwrap()));
     }
 
     #[test]
-    fn test_lset_value_saved_was_string_err() {
+    fn test_srandmember_negative_count_allows_repetition() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
         let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let values: HashSet<String> = vec!["a".to_string()].into_iter().collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
         let key = "key".to_string();
-        let index = 70;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
+        let srandmember = redis.execute(Command::Srandmember {
             key,
-            index,
-            element,
+            count: Some(-3),
         });
-
-        assert!(lset.is_err());
+        assert!(eq_response(
+            Re::List(vec!["a".to_string(), "a".to_string(), "a".to_string()]),
+            srandmember.unwrap()
+        ));
     }
 
     #[test]
-    fn test_rpop_without_count_ok() {
+    fn test_srandmember_negative_count_too_large_is_rejected() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let values: HashSet<String> = vec!["a".to_string()].into_iter().collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
         let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 0 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(Re::String("value".to_string()), rpop.unwrap()));
+        let srandmember = redis.execute(Command::Srandmember {
+            key,
+            count: Some(i64::MIN),
+        });
+        assert!(srandmember
+            .unwrap_err()
+            .starts_with("ERR value is out of range"));
 
         let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), llen.unwrap()));
+        let srandmember = redis.execute(Command::Srandmember {
+            key,
+            count: Some(-1_000_000),
+        });
+        match srandmember.unwrap() {
+            Response::Normal(Re::List(members)) => {
+                assert_eq!(1_000_000, members.len())
+            }
+            _ => panic!("SRANDMEMBER did not return a list"),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn sadd_members(redis: &mut Redis, key: &str, members: Vec<&str>) {
+        let key = key.to_string();
+        let values: HashSet<String> = members.into_iter().map(|m| m.to_string()).collect();
+        let _sadd = redis.execute(Command::Sadd { key, values });
     }
 
     #[test]
-    fn test_rpop_with_count_ok() {
+    fn test_sinter_returns_common_members() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key1", vec!["a", "b", "c"]);
+        sadd_members(&mut redis, "key2", vec!["b", "c", "d"]);
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let keys = vec!["key1".to_string(), "key2".to_string()];
+        let sinter = redis.execute(Command::Sinter { keys });
 
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 2 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value".to_string(), "value2".to_string(),]),
-            rpop.unwrap(),
-        ));
+        let expected: HashSet<String> =
+            vec!["b".to_string(), "c".to_string()].into_iter().collect();
+        assert!(eq_response(Re::Set(expected), sinter.unwrap()));
+    }
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), llen.unwrap()));
+    #[test]
+    fn test_sinter_with_missing_key_returns_empty_set() {
+        let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key1", vec!["a"]);
+
+        let keys = vec!["key1".to_string(), "missing".to_string()];
+        let sinter = redis.execute(Command::Sinter { keys });
+
+        assert!(eq_response(Re::Set(HashSet::new()), sinter.unwrap()));
+    }
+
+    #[test]
+    fn test_sunion_returns_all_members() {
+        let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key1", vec!["a", "b"]);
+        sadd_members(&mut redis, "key2", vec!["b", "c"]);
+
+        let keys = vec![
+            "key1".to_string(),
+            "key2".to_string(),
+            "missing".to_string(),
+        ];
+        let sunion = redis.execute(Command::Sunion { keys });
+
+        let expected: HashSet<String> = vec!["a".to_string(), "b".to_string(), "c".to_string()]
+            .into_iter()
+            .collect();
+        assert!(eq_response(Re::Set(expected), sunion.unwrap()));
     }
 
     #[test]
-    fn test_rpop_with_count_major_than_len_ok() {
+    fn test_sdiff_returns_members_only_in_first_set() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key1", vec!["a", "b", "c"]);
+        sadd_members(&mut redis, "key2", vec!["b"]);
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
+        let keys = vec![
+            "key1".to_string(),
+            "key2".to_string(),
+            "missing".to_string(),
         ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 5 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(
-            Re::List(vec![
-                "value".to_string(),
-                "value2".to_string(),
-                "value3".to_string(),
-                "value4".to_string()
-            ]),
-            rpop.unwrap(),
-        ));
-
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), llen.unwrap()));
+        let sdiff = redis.execute(Command::Sdiff { keys });
 
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 5 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(Re::Nil, rpop.unwrap()));
+        let expected: HashSet<String> =
+            vec!["a".to_string(), "c".to_string()].into_iter().collect();
+        assert!(eq_response(Re::Set(expected), sdiff.unwrap()));
     }
 
     #[test]
-    fn test_rpop_with_saved_string_err() {
+    fn test_sunion_wrong_type_err() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key1", vec!["a"]);
 
-        let key = "key".to_string();
+        let key = "key2".to_string();
         let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 5 });
-        assert!(rpop.is_err());
+        let keys = vec!["key1".to_string(), "key2".to_string()];
+        let sunion = redis.execute(Command::Sunion { keys });
+        assert!(sunion.is_err());
     }
 
     #[test]
-    fn test_lpush_ok() {
+    fn test_smismember_returns_membership_of_each_member() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key", vec!["a", "b"]);
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
+        let members = vec!["a".to_string(), "c".to_string(), "b".to_string()];
+        let smismember = redis.execute(Command::Smismember { key, members });
 
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lpush.unwrap()));
+        assert!(eq_response(
+            Re::Array(vec![Re::Integer(1), Re::Integer(0), Re::Integer(1)]),
+            smismember.unwrap()
+        ));
     }
 
     #[test]
-    fn test_lpush_with_key_used_err() {
+    fn test_sintercard_with_limit() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key1", vec!["a", "b", "c"]);
+        sadd_members(&mut redis, "key2", vec!["a", "b", "c", "d"]);
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
+        let keys = vec!["key1".to_string(), "key2".to_string()];
+        let sintercard = redis.execute(Command::Sintercard {
+            keys: keys.clone(),
+            limit: 0,
+        });
+        assert!(eq_response(Re::Integer(3), sintercard.unwrap()));
 
-        assert!(lpush.is_err());
+        let sintercard = redis.execute(Command::Sintercard { keys, limit: 2 });
+        assert!(eq_response(Re::Integer(2), sintercard.unwrap()));
     }
 
     #[test]
-    fn test_lpush_key_used_ok() {
+    fn test_set_nx_only_sets_missing_key() {
         let mut redis: Redis = Redis::new_for_test();
+        let options = SetOptions {
+            condition: SetCondition::IfNotExists,
+            expiration: SetExpiration::Discard,
+            get: false,
+        };
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
-
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lpush.unwrap()));
+        let value = "value".to_string();
+        let set = redis.execute(Command::Set {
+            key,
+            value,
+            options: options.clone(),
+        });
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            set.unwrap()
+        ));
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
+        let value = "other".to_string();
+        let set = redis.execute(Command::Set {
+            key,
+            value,
+            options,
+        });
+        assert!(eq_response(Re::Nil, set.unwrap()));
 
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lpush.unwrap()));
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
     }
 
     #[test]
-    fn test_lpush_key_used_check_ok() {
+    fn test_set_xx_only_sets_existing_key() {
         let mut redis: Redis = Redis::new_for_test();
+        let options = SetOptions {
+            condition: SetCondition::IfExists,
+            expiration: SetExpiration::Discard,
+            get: false,
+        };
 
         let key = "key".to_string();
-        let value = vec!["1".to_string(), "2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["3".to_string(), "4".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let value = "value".to_string();
+        let set = redis.execute(Command::Set {
+            key,
+            value,
+            options,
+        });
+        assert!(eq_response(Re::Nil, set.unwrap()));
 
         let key = "key".to_string();
-        let index = -1;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -2;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -3;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("3".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -4;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lindex.unwrap()));
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
     }
 
     #[test]
-    fn test_rpush_ok() {
+    fn test_setnx_returns_whether_value_was_set() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
+        let value = "value".to_string();
+        let setnx = redis.execute(Command::Setnx { key, value });
+        assert!(eq_response(Re::Integer(1), setnx.unwrap()));
 
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpush.unwrap()));
+        let key = "key".to_string();
+        let value = "other".to_string();
+        let setnx = redis.execute(Command::Setnx { key, value });
+        assert!(eq_response(Re::Integer(0), setnx.unwrap()));
     }
 
     #[test]
-    fn test_rpush_with_key_used_err() {
+    fn test_msetnx_is_all_or_nothing() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
+        let key = "key2".to_string();
         let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let _setnx = redis.execute(Command::Setnx { key, value });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
+        let key_values = vec![
+            ("key1".to_string(), "value1".to_string()),
+            ("key2".to_string(), "value2".to_string()),
+        ];
+        let msetnx = redis.execute(Command::Msetnx { key_values });
+        assert!(eq_response(Re::Integer(0), msetnx.unwrap()));
 
-        assert!(rpush.is_err());
+        let key = "key1".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+
+        let key_values = vec![
+            ("key1".to_string(), "value1".to_string()),
+            ("key3".to_string(), "value3".to_string()),
+        ];
+        let msetnx = redis.execute(Command::Msetnx { key_values });
+        assert!(eq_response(Re::Integer(1), msetnx.unwrap()));
     }
 
     #[test]
-    fn test_rpush_key_used_ok() {
+    fn test_set_with_relative_expiration_sets_ttl() {
         let mut redis: Redis = Redis::new_for_test();
+        let options = SetOptions {
+            condition: SetCondition::Always,
+            expiration: SetExpiration::Relative(Duration::from_secs(100)),
+            get: false,
+        };
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpush.unwrap()));
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options,
+        });
 
         let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpush.unwrap()));
+        let ttl = redis.execute(Command::Ttl { key });
+        assert!(eq_response(Re::Integer(99), ttl.unwrap()));
     }
 
     #[test]
-    fn test_rpush_key_used_check_ok() {
+    fn test_set_keepttl_preserves_previous_ttl() {
         let mut redis: Redis = Redis::new_for_test();
+        let options = SetOptions {
+            condition: SetCondition::Always,
+            expiration: SetExpiration::Relative(Duration::from_secs(100)),
+            get: false,
+        };
 
         let key = "key".to_string();
-        let value = vec!["1".to_string(), "2".to_string()];
-        let _rpush = redis.execute(Command::Rpush { key, value });
+        let value = "value".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options,
+        });
 
+        let options = SetOptions {
+            condition: SetCondition::Always,
+            expiration: SetExpiration::Keep,
+            get: false,
+        };
         let key = "key".to_string();
-        let value = vec!["3".to_string(), "4".to_string()];
-        let _rpush = redis.execute(Command::Rpush { key, value });
+        let value = "other".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options,
+        });
 
         let key = "key".to_string();
-        let index = -1;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -2;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("3".to_string()), lindex.unwrap()));
+        let ttl = redis.execute(Command::Ttl { key });
+        assert!(eq_response(Re::Integer(99), ttl.unwrap()));
+
         let key = "key".to_string();
-        let index = -3;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lindex.unwrap()));
+        let value = "another".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
         let key = "key".to_string();
-        let index = -4;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), lindex.unwrap()));
+        let ttl = redis.execute(Command::Ttl { key });
+        assert!(eq_response(Re::Integer(-1), ttl.unwrap()));
     }
 
     #[test]
-    fn test_sadd() {
+    fn test_incrbyfloat_trims_trailing_zeros() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let sadd = redis.execute(Command::Sadd { key, values });
+        let value = "10.50".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let incrbyfloat = redis.execute(Command::Incrbyfloat {
+            key,
+            increment: 0.1,
+        });
+        assert!(eq_response(
+            Re::String("10.6".to_string()),
+            incrbyfloat.unwrap()
+        ));
 
-        assert!(eq_response(Re::String("3".to_string()), sadd.unwrap()));
+        let key = "key".to_string();
+        let incrbyfloat = redis.execute(Command::Incrbyfloat {
+            key,
+            increment: -0.6,
+        });
+        assert!(eq_response(
+            Re::String("10".to_string()),
+            incrbyfloat.unwrap()
+        ));
     }
 
     #[test]
-    fn test_sadd_with_existing_key() {
+    fn test_incrbyfloat_rounds_like_redis() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let sadd = redis.execute(Command::Sadd { key, values });
-
-        assert!(eq_response(Re::String("3".to_string()), sadd.unwrap()));
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "0.1".to_string(),
+            options: SetOptions::default(),
+        });
+        let incrbyfloat = redis.execute(Command::Incrbyfloat {
+            key: "key".to_string(),
+            increment: 0.2,
+        });
+        assert!(eq_response(
+            Re::String("0.3".to_string()),
+            incrbyfloat.unwrap()
+        ));
 
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value3".to_string());
-        values.insert("value4".to_string());
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "10.5".to_string(),
+            options: SetOptions::default(),
+        });
+        let incrbyfloat = redis.execute(Command::Incrbyfloat {
+            key: "key".to_string(),
+            increment: -0.5,
+        });
+        assert!(eq_response(
+            Re::String("10".to_string()),
+            incrbyfloat.unwrap()
+        ));
+        let get = redis.execute(Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::String("10".to_string()), get.unwrap()));
+    }
 
-        let sadd2 = redis.execute(Command::Sadd { key, values });
-        assert!(eq_response(Re::String("1".to_string()), sadd2.unwrap()));
+    #[test]
+    fn test_format_float_without_exponent() {
+        assert_eq!("5000000000000000000000", format_float(5e21));
+        assert_eq!("0.00000123", format_float(1.23e-6));
+        assert_eq!("-1.5", format_float(-1.5));
+        assert_eq!("0", format_float(-0.0));
+        assert_eq!("0.8", format_float(0.1 + 0.7));
     }
 
     #[test]
-    fn test_sadd_error() {
+    fn test_incrbyfloat_on_missing_key() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".to_string();
+        let incrbyfloat = redis.execute(Command::Incrbyfloat {
+            key,
+            increment: 2.5,
+        });
+        assert!(eq_response(
+            Re::String("2.5".to_string()),
+            incrbyfloat.unwrap()
+        ));
+    }
 
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let sadd = redis.execute(Command::Sadd { key, values });
+    #[test]
+    fn test_incrbyfloat_on_non_numeric_value_err() {
+        let mut redis: Redis = Redis::new_for_test();
 
-        assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            sadd.err().unwrap()
-        )
+        let key = "key".to_string();
+        let value = "abc".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+
+        let key = "key".to_string();
+        let incrbyfloat = redis.execute(Command::Incrbyfloat {
+            key,
+            increment: 1.0,
+        });
+        assert_eq!("ERR value is not a valid float", incrbyfloat.err().unwrap());
     }
 
     #[test]
-    fn test_scard() {
+    fn test_incrby_returns_new_value() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let increment: i64 = 5_000_000_000;
+        let incrby = redis.execute(Command::Incrby { key, increment });
+        assert!(eq_response(Re::Integer(5000000000), incrby.unwrap()));
 
         let key = "key".to_string();
-        let scard = redis.execute(Command::Scard { key });
-
-        assert!(eq_response(Re::String("3".to_string()), scard.unwrap()));
+        let increment: i64 = -5_000_000_001;
+        let incrby = redis.execute(Command::Incrby { key, increment });
+        assert!(eq_response(Re::Integer(-1), incrby.unwrap()));
     }
 
     #[test]
-    fn test_scard_error() {
+    fn test_incrby_overflow_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "set".to_string();
-        let scard = redis.execute(Command::Scard { key });
+        let key = "key".to_string();
+        let value = i64::MAX.to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
+        let key = "key".to_string();
+        let increment: i64 = 1;
+        let incrby = redis.execute(Command::Incrby { key, increment });
         assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            scard.err().unwrap()
-        )
+            "ERR increment or decrement would overflow",
+            incrby.err().unwrap()
+        );
+
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::String(i64::MAX.to_string()), get.unwrap()));
     }
 
     #[test]
-    fn test_sismember() {
+    fn test_decrby_min_value_overflow_err() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let decrement: i64 = i64::MIN;
+        let decrby = redis.execute(Command::Decrby { key, decrement });
+        assert!(decrby.is_err());
+    }
+
+    #[test]
+    fn test_setbit_and_getbit() {
+        let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let value = "value1".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
+        let setbit = redis.execute(Command::Setbit {
+            key,
+            offset: 7,
+            value: true,
+        });
+        assert!(eq_response(Re::Integer(0), setbit.unwrap()));
+
+        let key = "key".to_string();
+        let setbit = redis.execute(Command::Setbit {
+            key,
+            offset: 7,
+            value: false,
+        });
+        assert!(eq_response(Re::Integer(1), setbit.unwrap()));
 
-        assert!(eq_response(Re::String("1".to_string()), sismember.unwrap()));
+        let key = "key".to_string();
+        let _setbit = redis.execute(Command::Setbit {
+            key,
+            offset: 1,
+            value: true,
+        });
+        let key = "key".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::String("@".to_string()), get.unwrap()));
 
         let key = "key".to_string();
-        let value = "value".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
+        let getbit = redis.execute(Command::Getbit { key, offset: 1 });
+        assert!(eq_response(Re::Integer(1), getbit.unwrap()));
 
-        assert!(eq_response(Re::String("0".to_string()), sismember.unwrap()));
+        let key = "key".to_string();
+        let getbit = redis.execute(Command::Getbit { key, offset: 100 });
+        assert!(eq_response(Re::Integer(0), getbit.unwrap()));
     }
 
     #[test]
-    fn test_sismember_error() {
+    fn test_setbit_stores_bytes_over_0x7f() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
-
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
-
-        assert_eq!(
-            "The key doesn't exist".to_string(),
-            sismember.err().unwrap()
-        );
-
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
+        let setbit = redis.execute(Command::Setbit {
+            key: "key".to_string(),
+            offset: 0,
+            value: true,
+        });
+        assert!(eq_response(Re::Integer(0), setbit.unwrap()));
+        let get = redis.execute(Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Bytes(vec![0x80]), get.unwrap()));
+        let strlen = redis.execute(Command::Strlen {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::Integer(1), strlen.unwrap()));
 
-        assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            sismember.err().unwrap()
-        );
+        // Los strings de texto se interpretan con sus bytes UTF-8: "é" es C3 A9.
+        let _set = redis.execute(Command::Set {
+            key: "text".to_string(),
+            value: "é".to_string(),
+            options: SetOptions::default(),
+        });
+        let bitcount = redis.execute(Command::Bitcount {
+            key: "text".to_string(),
+            range: None,
+        });
+        assert!(eq_response(Re::Integer(8), bitcount.unwrap()));
+        let _setbit = redis.execute(Command::Setbit {
+            key: "text".to_string(),
+            offset: 8,
+            value: false,
+        });
+        let get = redis.execute(Command::Get {
+            key: "text".to_string(),
+        });
+        assert!(eq_response(Re::Bytes(vec![0xc3, 0x29]), get.unwrap()));
     }
 
     #[test]
-    fn test_srem() {
+    fn test_bitcount_and_bitpos() {
         let mut redis: Redis = Redis::new_for_test();
 
         let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let value = "foobar".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
         let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
-
-        assert!(eq_response(Re::String("1".to_string()), srem.unwrap()));
+        let bitcount = redis.execute(Command::Bitcount { key, range: None });
+        assert!(eq_response(Re::Integer(26), bitcount.unwrap()));
 
-        let key = "key_inexistente".to_string();
-        let mut values = HashSet::new();
-        values.insert("value2".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
+        let key = "key".to_string();
+        let bitpos = redis.execute(Command::Bitpos {
+            key,
+            bit: true,
+            start: 1,
+            end: None,
+            unit: BitUnit::Byte,
+        });
+        assert!(eq_response(Re::Integer(9), bitpos.unwrap()));
 
-        assert!(eq_response(Re::String("0".to_string()), srem.unwrap()));
+        let key = "missing".to_string();
+        let bitcount = redis.execute(Command::Bitcount { key, range: None });
+        assert!(eq_response(Re::Integer(0), bitcount.unwrap()));
     }
 
     #[test]
-    fn test_srem_value_two_times() {
+    fn test_bitop_stores_result_and_deletes_empty_destination() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let key = "a".to_string();
+        let value = "abc".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
+        let key = "b".to_string();
+        let value = "a".to_string();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            options: SetOptions::default(),
+        });
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
+        let bitop = redis.execute(Command::Bitop {
+            operation: BitOperation::Xor,
+            destkey: "dest".to_string(),
+            keys: vec!["a".to_string(), "b".to_string()],
+        });
+        assert!(eq_response(Re::Integer(3), bitop.unwrap()));
 
-        assert!(eq_response(Re::String("1".to_string()), srem.unwrap()));
+        let key = "dest".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::String("\0bc".to_string()), get.unwrap()));
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
+        let bitop = redis.execute(Command::Bitop {
+            operation: BitOperation::And,
+            destkey: "dest".to_string(),
+            keys: vec!["missing".to_string()],
+        });
+        assert!(eq_response(Re::Integer(0), bitop.unwrap()));
 
-        assert!(eq_response(Re::String("0".to_string()), srem.unwrap()));
+        let key = "dest".to_string();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Re::Nil, get.unwrap()));
     }
 
     #[test]
-    fn test_srem_error() {
+    fn test_setbit_on_wrong_type_err() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "set", vec!["a"]);
 
         let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
-
+        let setbit = redis.execute(Command::Setbit {
+            key,
+            offset: 0,
+            value: true,
+        });
         assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            srem.err().unwrap()
+            setbit.err(),
+            Some("WRONGTYPE Operation against a key holding the wrong kind of value".to_string())
         );
     }
 
+    #[allow(dead_code)]
+    fn xadd(redis: &mut Redis, key: &str, ms: u64, fields: Vec<(&str, &str)>) {
+        let _xadd = redis.execute(Command::Xadd {
+            key: key.to_string(),
+            id: Some(StreamId::new(ms, 0)),
+            max_len: None,
+            fields: fields
+                .into_iter()
+                .map(|(field, value)| (field.to_string(), value.to_string()))
+                .collect(),
+        });
+    }
+
     #[test]
-    fn test_smembers() {
+    fn test_xadd_generates_ids_and_trims() {
         let mut redis: Redis = Redis::new_for_test();
+        xadd(&mut redis, "stream", 5, vec![("a", "1")]);
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let xadd = redis.execute(Command::Xadd {
+            key: "stream".to_string(),
+            id: Some(StreamId::new(5, 0)),
+            max_len: None,
+            fields: vec![("a".to_string(), "2".to_string())],
+        });
+        assert_eq!(
+            xadd.err(),
+            Some(
+                "ERR The ID specified in XADD is equal or smaller than the target stream top item"
+                    .to_string()
+            )
+        );
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let smembers = redis.execute(Command::Smembers { key });
+        let xadd = redis.execute(Command::Xadd {
+            key: "stream".to_string(),
+            id: None,
+            max_len: Some(1),
+            fields: vec![("a".to_string(), "2".to_string())],
+        });
+        let id = match xadd.unwrap() {
+            Response::Normal(Re::String(id)) => id.parse::<StreamId>().unwrap(),
+            _ => panic!("XADD must return the new ID"),
+        };
+        assert!(id > StreamId::new(5, 0));
 
-        assert!(eq_response(Re::Set(values), smembers.unwrap()));
+        let key = "stream".to_string();
+        let xlen = redis.execute(Command::Xlen { key });
+        assert!(eq_response(Re::Integer(1), xlen.unwrap()));
+
+        let key = "stream".to_string();
+        let r#type = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Re::String("stream".to_string()),
+            r#type.unwrap()
+        ));
     }
 
     #[test]
-    fn test_lpushx_not_pre_save_return_0() {
+    fn test_xrange_and_xrevrange() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpushx = redis.execute(Command::Lpushx { key, value });
-
-        assert!(lpushx.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), lpushx.unwrap()));
+        xadd(&mut redis, "stream", 1, vec![("a", "x")]);
+        xadd(&mut redis, "stream", 2, vec![("b", "y"), ("c", "z")]);
+        xadd(&mut redis, "stream", 3, vec![("d", "w")]);
+
+        let xrange = redis.execute(Command::Xrange {
+            key: "stream".to_string(),
+            start: StreamId::new(2, 0),
+            end: StreamId::MAX,
+            count: Some(1),
+        });
+        let expected = Re::Array(vec![Re::Array(vec![
+            Re::String("2-0".to_string()),
+            Re::List(vec![
+                "b".to_string(),
+                "y".to_string(),
+                "c".to_string(),
+                "z".to_string(),
+            ]),
+        ])]);
+        assert!(eq_response(expected, xrange.unwrap()));
+
+        let xrevrange = redis.execute(Command::Xrevrange {
+            key: "stream".to_string(),
+            start: StreamId::MIN,
+            end: StreamId::new(2, u64::MAX),
+            count: None,
+        });
+        let expected = Re::Array(vec![
+            Re::Array(vec![
+                Re::String("2-0".to_string()),
+                Re::List(vec![
+                    "b".to_string(),
+                    "y".to_string(),
+                    "c".to_string(),
+                    "z".to_string(),
+                ]),
+            ]),
+            Re::Array(vec![
+                Re::String("1-0".to_string()),
+                Re::List(vec!["a".to_string(), "x".to_string()]),
+            ]),
+        ]);
+        assert!(eq_response(expected, xrevrange.unwrap()));
     }
 
     #[test]
-    fn test_lpushx_with_key_used_with_string_err() {
+    fn test_xread_returns_entries_after_id() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpushx = redis.execute(Command::Lpushx { key, value });
-
-        assert!(lpushx.is_err());
+        xadd(&mut redis, "s1", 1, vec![("a", "x")]);
+        xadd(&mut redis, "s1", 2, vec![("b", "y")]);
+        xadd(&mut redis, "s2", 1, vec![("c", "z")]);
+
+        let xread = redis.execute(Command::Xread {
+            count: None,
+            streams: vec![
+                ("s1".to_string(), Some(StreamId::new(1, 0))),
+                ("s2".to_string(), Some(StreamId::new(1, 0))),
+                ("missing".to_string(), Some(StreamId::MIN)),
+            ],
+        });
+        let expected = Re::Array(vec![Re::Array(vec![
+            Re::String("s1".to_string()),
+            Re::Array(vec![Re::Array(vec![
+                Re::String("2-0".to_string()),
+                Re::List(vec!["b".to_string(), "y".to_string()]),
+            ])]),
+        ])]);
+        assert!(eq_response(expected, xread.unwrap()));
+
+        let xread = redis.execute(Command::Xread {
+            count: None,
+            streams: vec![("s1".to_string(), None)],
+        });
+        assert!(eq_response(Re::Nil, xread.unwrap()));
     }
 
     #[test]
-    fn test_lpushx_after_lpush_ok() {
+    fn test_xgroup_create_requires_stream_or_mkstream() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
-
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lpush.unwrap()));
+        let xgroup = redis.execute(Command::Xgroup {
+            key: "stream".to_string(),
+            param: XgroupParam::Create {
+                group: "group".to_string(),
+                id: None,
+                mkstream: false,
+            },
+        });
+        assert!(xgroup.is_err());
+
+        let xgroup = redis.execute(Command::Xgroup {
+            key: "stream".to_string(),
+            param: XgroupParam::Create {
+                group: "group".to_string(),
+                id: None,
+                mkstream: true,
+            },
+        });
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            xgroup.unwrap()
+        ));
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpushx { key, value });
+        let xgroup = redis.execute(Command::Xgroup {
+            key: "stream".to_string(),
+            param: XgroupParam::Create {
+                group: "group".to_string(),
+                id: None,
+                mkstream: true,
+            },
+        });
+        assert_eq!(
+            xgroup.err(),
+            Some("BUSYGROUP Consumer Group name already exists".to_string())
+        );
 
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lpush.unwrap()));
+        let key = "stream".to_string();
+        let xlen = redis.execute(Command::Xlen { key });
+        assert!(eq_response(Re::Integer(0), xlen.unwrap()));
     }
 
     #[test]
-    fn test_rpushx_not_pre_save_return_0() {
+    fn test_xreadgroup_xpending_and_xack() {
         let mut redis: Redis = Redis::new_for_test();
+        xadd(&mut redis, "stream", 1, vec![("a", "x")]);
+        xadd(&mut redis, "stream", 2, vec![("b", "y")]);
+        let _xgroup = redis.execute(Command::Xgroup {
+            key: "stream".to_string(),
+            param: XgroupParam::Create {
+                group: "group".to_string(),
+                id: Some(StreamId::MIN),
+                mkstream: false,
+            },
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
-
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), rpushx.unwrap()));
+        let xreadgroup = redis.execute(Command::Xreadgroup {
+            group: "group".to_string(),
+            consumer: "alice".to_string(),
+            count: Some(1),
+            no_ack: false,
+            streams: vec![("stream".to_string(), None)],
+        });
+        let expected = Re::Array(vec![Re::Array(vec![
+            Re::String("stream".to_string()),
+            Re::Array(vec![Re::Array(vec![
+                Re::String("1-0".to_string()),
+                Re::List(vec!["a".to_string(), "x".to_string()]),
+            ])]),
+        ])]);
+        assert!(eq_response(expected, xreadgroup.unwrap()));
+
+        let xpending = redis.execute(Command::Xpending {
+            key: "stream".to_string(),
+            group: "group".to_string(),
+            range: None,
+        });
+        let expected = Re::Array(vec![
+            Re::Integer(1),
+            Re::String("1-0".to_string()),
+            Re::String("1-0".to_string()),
+            Re::Array(vec![Re::Array(vec![
+                Re::String("alice".to_string()),
+                Re::String("1".to_string()),
+            ])]),
+        ]);
+        assert!(eq_response(expected, xpending.unwrap()));
+
+        let xack = redis.execute(Command::Xack {
+            key: "stream".to_string(),
+            group: "group".to_string(),
+            ids: vec![StreamId::new(1, 0), StreamId::new(2, 0)],
+        });
+        assert!(eq_response(Re::Integer(1), xack.unwrap()));
+
+        let xpending = redis.execute(Command::Xpending {
+            key: "stream".to_string(),
+            group: "group".to_string(),
+            range: Some(PendingRange {
+                min_idle: None,
+                start: StreamId::MIN,
+                end: StreamId::MAX,
+                count: 10,
+                consumer: None,
+            }),
+        });
+        assert!(eq_response(Re::Array(vec![]), xpending.unwrap()));
     }
 
     #[test]
-    fn test_rpushx_with_key_used_with_string_err() {
+    fn test_consumer_group_commands_notify_changes() {
         let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "Et".to_string(),
+        });
+        let receiver = subscribe_receiver(
+            &mut redis,
+            vec![
+                "__keyevent@0__:xgroup-create",
+                "__keyevent@0__:xgroup-createconsumer",
+            ],
+        );
+        let changes = redis.watch_changes();
+
+        let _xgroup = redis.execute(Command::Xgroup {
+            key: "stream".to_string(),
+            param: XgroupParam::Create {
+                group: "group".to_string(),
+                id: None,
+                mkstream: true,
+            },
+        });
+        xadd(&mut redis, "stream", 1, vec![("a", "x")]);
+        let _xreadgroup = redis.execute(Command::Xreadgroup {
+            group: "group".to_string(),
+            consumer: "alice".to_string(),
+            count: None,
+            no_ack: false,
+            streams: vec![("stream".to_string(), None)],
+        });
+        let _xclaim = redis.execute(Command::Xclaim {
+            key: "stream".to_string(),
+            group: "group".to_string(),
+            consumer: "bob".to_string(),
+            min_idle: Duration::from_millis(0),
+            ids: vec![StreamId::new(1, 0)],
+            just_id: true,
+        });
+        let _xack = redis.execute(Command::Xack {
+            key: "stream".to_string(),
+            group: "group".to_string(),
+            ids: vec![StreamId::new(1, 0)],
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
+        assert_eq!(
+            message("__keyevent@0__:xgroup-create", "stream"),
+            receiver.try_recv().unwrap()
+        );
+        assert_eq!(
+            message("__keyevent@0__:xgroup-createconsumer", "stream"),
+            receiver.try_recv().unwrap()
+        );
+        assert_eq!(
+            message("__keyevent@0__:xgroup-createconsumer", "stream"),
+            receiver.try_recv().unwrap()
+        );
+        assert!(receiver.try_recv().is_err());
 
-        assert!(rpushx.is_err());
+        let events: Vec<String> = changes.try_iter().map(|change| change.event).collect();
+        assert_eq!(
+            vec![
+                "xgroup-create",
+                "xadd",
+                "xgroup-createconsumer",
+                "xreadgroup",
+                "xgroup-createconsumer",
+                "xclaim",
+                "xack"
+            ],
+            events
+        );
     }
 
     #[test]
-    fn test_rpushx_after_rpush_ok() {
+    fn test_xreadgroup_and_xclaim_on_missing_group_err() {
         let mut redis: Redis = Redis::new_for_test();
+        xadd(&mut redis, "stream", 1, vec![("a", "x")]);
+
+        let xreadgroup = redis.execute(Command::Xreadgroup {
+            group: "group".to_string(),
+            consumer: "alice".to_string(),
+            count: None,
+            no_ack: false,
+            streams: vec![("stream".to_string(), None)],
+        });
+        assert_eq!(
+            xreadgroup.err(),
+            Some(
+                "NOGROUP No such key 'stream' or consumer group 'group' in XREADGROUP with GROUP option"
+                    .to_string()
+            )
+        );
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpushx.unwrap()));
+        let xclaim = redis.execute(Command::Xclaim {
+            key: "stream".to_string(),
+            group: "group".to_string(),
+            consumer: "alice".to_string(),
+            min_idle: Duration::from_secs(0),
+            ids: vec![StreamId::new(1, 0)],
+            just_id: true,
+        });
+        assert!(xclaim.is_err());
+    }
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
+    #[allow(dead_code)]
+    fn geoadd_sicily(redis: &mut Redis) {
+        let _geoadd = redis.execute(Command::Geoadd {
+            key: "Sicily".to_string(),
+            options: GeoAddOptions {
+                condition: SetCondition::Always,
+                changed: false,
+            },
+            members: vec![
+                (13.361389, 38.115556, "Palermo".to_string()),
+                (15.087269, 37.502669, "Catania".to_string()),
+            ],
+        });
+    }
 
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpushx.unwrap()));
+    #[allow(dead_code)]
+    fn geosearch_options(shape: GeoShape, unit: GeoUnit) -> GeoSearchOptions {
+        GeoSearchOptions {
+            origin: GeoOrigin::LonLat(15.0, 37.0),
+            shape,
+            unit,
+            ascending: Some(true),
+            count: None,
+            any: false,
+            with_coord: false,
+            with_dist: false,
+            with_hash: false,
+        }
     }
 
     #[test]
-    fn test_rpush_and_check_elements_ok() {
+    fn test_geoadd_and_geodist() {
         let mut redis: Redis = Redis::new_for_test();
+        geoadd_sicily(&mut redis);
 
-        let key = "key".to_string();
-        let value = vec![
-            "1".to_string(),
-            "2".to_string(),
-            "3".to_string(),
-            "4".to_string(),
-        ];
-        let rpushx = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpushx.unwrap()));
+        let geoadd = redis.execute(Command::Geoadd {
+            key: "Sicily".to_string(),
+            options: GeoAddOptions {
+                condition: SetCondition::IfNotExists,
+                changed: true,
+            },
+            members: vec![
+                (13.0, 38.0, "Palermo".to_string()),
+                (13.583333, 37.316667, "Agrigento".to_string()),
+            ],
+        });
+        assert!(eq_response(Re::Integer(1), geoadd.unwrap()));
 
-        let key = "key".to_string();
-        let value = vec![
-            "1".to_string(),
-            "2".to_string(),
-            "3".to_string(),
-            "4".to_string(),
-        ];
-        let rpushx = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let geodist = redis.execute(Command::Geodist {
+            key: "Sicily".to_string(),
+            member1: "Palermo".to_string(),
+            member2: "Catania".to_string(),
+            unit: GeoUnit::Kilometers,
         });
+        assert!(eq_response(
+            Re::String("166.2742".to_string()),
+            geodist.unwrap()
+        ));
 
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::List(value), rpushx.unwrap()));
+        let geodist = redis.execute(Command::Geodist {
+            key: "Sicily".to_string(),
+            member1: "Palermo".to_string(),
+            member2: "Rome".to_string(),
+            unit: GeoUnit::Meters,
+        });
+        assert!(eq_response(Re::Nil, geodist.unwrap()));
     }
 
     #[test]
-    fn test_rpush_rpushx_and_check_elements_ok() {
+    fn test_geopos_with_missing_member() {
         let mut redis: Redis = Redis::new_for_test();
+        geoadd_sicily(&mut redis);
 
-        let key = "key".to_string();
-        let value = vec!["1".to_string(), "2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpush.unwrap()));
-
-        let key = "key".to_string();
-        let value = vec!["3".to_string(), "4".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
+        let geopos = redis.execute(Command::Geopos {
+            key: "Sicily".to_string(),
+            members: vec!["Palermo".to_string(), "Rome".to_string()],
+        });
+        match geopos.unwrap() {
+            Response::Normal(Re::Array(positions)) => {
+                assert_eq!(2, positions.len());
+                assert!(matches!(&positions[0], Re::Array(coordinates) if coordinates.len() == 2));
+                assert_eq!(Re::Nil, positions[1]);
+            }
+            _ => panic!("GEOPOS must return an array"),
+        }
+    }
 
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpushx.unwrap()));
+    #[test]
+    fn test_geosearch_by_radius_and_box() {
+        let mut redis: Redis = Redis::new_for_test();
+        geoadd_sicily(&mut redis);
 
-        let key = "key".to_string();
-        let rpushx = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let geosearch = redis.execute(Command::Geosearch {
+            key: "Sicily".to_string(),
+            options: geosearch_options(GeoShape::Radius(200000.0), GeoUnit::Kilometers),
         });
-
-        assert!(rpushx.is_ok());
         assert!(eq_response(
-            Re::List(vec![
-                "1".to_string(),
-                "2".to_string(),
-                "3".to_string(),
-                "4".to_string()
-            ]),
-            rpushx.unwrap(),
+            Re::List(vec!["Catania".to_string(), "Palermo".to_string()]),
+            geosearch.unwrap()
         ));
+
+        let mut options = geosearch_options(GeoShape::Box(400000.0, 400000.0), GeoUnit::Kilometers);
+        options.ascending = Some(false);
+        options.count = Some(1);
+        options.with_dist = true;
+        let geosearch = redis.execute(Command::Geosearch {
+            key: "Sicily".to_string(),
+            options,
+        });
+        let expected = Re::Array(vec![Re::Array(vec![
+            Re::String("Palermo".to_string()),
+            Re::String("190.4424".to_string()),
+        ])]);
+        assert!(eq_response(expected, geosearch.unwrap()));
+
+        let mut options = geosearch_options(GeoShape::Radius(1000.0), GeoUnit::Meters);
+        options.origin = GeoOrigin::Member("Rome".to_string());
+        let geosearch = redis.execute(Command::Geosearch {
+            key: "Sicily".to_string(),
+            options,
+        });
+        assert!(geosearch.is_err());
     }
 
     #[test]
-    fn test_lrem_ok() {
+    fn test_pubsub_numpat_and_numsub_without_arguments() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value1".to_string(),
-            "value2".to_string(),
-            "value".to_string(),
-            "value".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: 2,
-            element: "value".to_string(),
+        let numpat = redis.execute(Command::Pubsub {
+            param: PubSubParam::Numpat,
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lrem.unwrap()));
+        assert!(eq_response(Re::Integer(0), numpat.unwrap()));
 
-        let key = "key".to_string();
+        let numsub = redis.execute(Command::Pubsub {
+            param: PubSubParam::Numsub,
+        });
+        assert!(eq_response(Re::List(vec![]), numsub.unwrap()));
+    }
 
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+    #[allow(dead_code)]
+    fn subscribe_receiver(redis: &mut Redis, channels: Vec<&str>) -> MessageReceiver<Re> {
+        let subscribe = redis.execute(Command::Subscribe {
+            channels: channels.iter().map(|channel| channel.to_string()).collect(),
+            client_id: 1,
+        });
+        let receiver = match subscribe.unwrap() {
+            Response::Stream(receiver) => receiver,
+            _ => panic!("SUBSCRIBE debe devolver un stream"),
+        };
+        // Se descartan las confirmaciones de suscripción.
+        channels.iter().for_each(|_| {
+            let _ = receiver.try_recv();
         });
+        receiver
+    }
 
-        let mut vector = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value".to_string(),
-        ];
-        vector.reverse();
-        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+    #[allow(dead_code)]
+    fn message(channel: &str, msg: &str) -> Re {
+        Re::List(vec![
+            "message".to_string(),
+            channel.to_string(),
+            msg.to_string(),
+        ])
     }
 
     #[test]
-    fn test_lrem_reverse_ok() {
+    fn test_keyspace_events_disabled_by_default() {
         let mut redis: Redis = Redis::new_for_test();
+        let receiver = subscribe_receiver(&mut redis, vec!["__keyspace@0__:key"]);
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-            "value".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: -2,
-            element: "value".to_string(),
+        let _ = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lrem.unwrap()));
 
-        let key = "key".to_string();
+        assert!(receiver.try_recv().is_err());
+    }
 
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+    #[test]
+    fn test_keyspace_and_keyevent_notifications() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "KEA".to_string(),
         });
+        let receiver =
+            subscribe_receiver(&mut redis, vec!["__keyspace@0__:key", "__keyevent@0__:del"]);
 
-        let mut vector = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-        ];
-
-        vector.reverse();
+        let _ = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _ = redis.execute(Command::Expire {
+            key: "key".to_string(),
+            ttl: Duration::from_secs(100),
+            conditions: vec![],
+        });
+        let _ = redis.execute(Command::Del {
+            keys: vec!["key".to_string(), "missing".to_string()],
+        });
 
-        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+        assert_eq!(
+            message("__keyspace@0__:key", "set"),
+            receiver.try_recv().unwrap()
+        );
+        assert_eq!(
+            message("__keyspace@0__:key", "expire"),
+            receiver.try_recv().unwrap()
+        );
+        assert_eq!(
+            message("__keyspace@0__:key", "del"),
+            receiver.try_recv().unwrap()
+        );
+        assert_eq!(
+            message("__keyevent@0__:del", "key"),
+            receiver.try_recv().unwrap()
+        );
+        assert!(receiver.try_recv().is_err());
     }
 
     #[test]
-    fn test_lrem_all_ok() {
+    fn test_keyspace_events_only_for_enabled_classes() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-            "value".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: 0,
-            element: "value".to_string(),
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "El".to_string(),
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lrem.unwrap()));
-
-        let key = "key".to_string();
+        let receiver = subscribe_receiver(
+            &mut redis,
+            vec!["__keyevent@0__:lpush", "__keyevent@0__:sadd"],
+        );
 
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let _ = redis.execute(Command::Lpush {
+            key: "list".to_string(),
+            value: vec!["a".to_string()],
         });
+        sadd_members(&mut redis, "set", vec!["a"]);
 
-        let mut vector = vec![
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-        ];
-
-        vector.reverse();
-
-        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+        assert_eq!(
+            message("__keyevent@0__:lpush", "list"),
+            receiver.try_recv().unwrap()
+        );
+        assert!(receiver.try_recv().is_err());
     }
 
     #[test]
-    fn test_lrem_invalid_key_ok() {
+    fn test_config_set_invalid_notify_keyspace_events() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: 0,
-            element: "value".to_string(),
+        let config_set = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "KEw".to_string(),
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), lrem.unwrap()));
+
+        assert_eq!(
+            "ERR Invalid argument 'KEw' for CONFIG SET 'notify-keyspace-events'".to_string(),
+            config_set.unwrap_err()
+        );
     }
 
     #[test]
-    fn test_keys_ok() {
+    fn test_expired_keys_notified_on_access() {
         let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "Ex".to_string(),
+        });
+        let receiver = subscribe_receiver(&mut redis, vec!["__keyevent@0__:expired"]);
+        let _ = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _ = redis.execute(Command::Expireat {
+            key: "key".to_string(),
+            ttl: SystemTime::now(),
+            conditions: vec![],
+        });
+        assert!(receiver.try_recv().is_err());
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let get = redis.execute(Command::Get {
+            key: "key".to_string(),
+        });
 
-        let key = "key1".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        assert!(eq_response(Re::Nil, get.unwrap()));
+        assert_eq!(
+            message("__keyevent@0__:expired", "key"),
+            receiver.try_recv().unwrap()
+        );
+        assert!(receiver.try_recv().is_err());
+    }
 
-        let pattern: String = "/*".to_string();
+    #[test]
+    fn test_client_list_and_info() {
+        let mut redis: Redis = Redis::new_for_test();
+        let (first, second) = {
+            let mut clients = redis.connected_clients.lock().unwrap();
+            (
+                clients.register("127.0.0.1:5000".to_string(), None),
+                clients.register("127.0.0.1:5001".to_string(), None),
+            )
+        };
+        let _ = redis.execute(Command::Subscribe {
+            channels: vec!["news".to_string(), "sports".to_string()],
+            client_id: second,
+        });
 
-        let keys = redis.execute(Command::Keys { pattern });
+        let list = redis
+            .execute(Command::Client {
+                param: ClientParam::List,
+                client_id: first,
+            })
+            .unwrap();
+        let expected = format!(
+            "id={} addr=127.0.0.1:5000 name= age=0 idle=0 db=0 sub=0 psub=0 cmd=NULL\n\
+             id={} addr=127.0.0.1:5001 name= age=0 idle=0 db=0 sub=2 psub=0 cmd=NULL\n",
+            first, second
+        );
+        assert!(eq_response(Re::String(expected), list));
+
+        let info = redis
+            .execute(Command::Client {
+                param: ClientParam::Info,
+                client_id: second,
+            })
+            .unwrap();
+        let expected = format!(
+            "id={} addr=127.0.0.1:5001 name= age=0 idle=0 db=0 sub=2 psub=0 cmd=NULL\n",
+            second
+        );
+        assert!(eq_response(Re::String(expected), info));
+    }
 
-        assert!(keys.is_ok());
+    #[test]
+    fn test_client_setname_and_getname() {
+        let mut redis: Redis = Redis::new_for_test();
+        let id = redis
+            .connected_clients
+            .lock()
+            .unwrap()
+            .register("127.0.0.1:5000".to_string(), None);
+
+        let getname = redis.execute(Command::Client {
+            param: ClientParam::GetName,
+            client_id: id,
+        });
+        assert!(eq_response(Re::Nil, getname.unwrap()));
 
-        let pattern: String = "k**".to_string();
+        let setname = redis.execute(Command::Client {
+            param: ClientParam::SetName("worker".to_string()),
+            client_id: id,
+        });
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            setname.unwrap()
+        ));
 
-        let keys = redis.execute(Command::Keys { pattern });
+        let getname = redis.execute(Command::Client {
+            param: ClientParam::GetName,
+            client_id: id,
+        });
+        assert!(eq_response(
+            Re::String("worker".to_string()),
+            getname.unwrap()
+        ));
 
-        assert!(keys.is_ok());
+        let invalid = redis.execute(Command::Client {
+            param: ClientParam::SetName("bad name".to_string()),
+            client_id: id,
+        });
+        assert_eq!(
+            "ERR Client names cannot contain spaces, newlines or special characters.",
+            invalid.unwrap_err()
+        );
     }
 
-    #[ignore]
     #[test]
-    fn test_touch_deletes_expired_key() {
+    fn test_client_kill() {
         let mut redis: Redis = Redis::new_for_test();
+        let (me, other, another) = {
+            let mut clients = redis.connected_clients.lock().unwrap();
+            (
+                clients.register("127.0.0.1:5000".to_string(), None),
+                clients.register("127.0.0.1:5001".to_string(), None),
+                clients.register("127.0.0.1:5002".to_string(), None),
+            )
+        };
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let _expire = redis.execute(Command::Expire { key, ttl });
+        let kill = redis.execute(Command::Client {
+            param: ClientParam::KillAddr("127.0.0.1:5001".to_string()),
+            client_id: me,
+        });
+        assert!(eq_response(
+            Re::SimpleString("OK".to_string()),
+            kill.unwrap()
+        ));
+        assert!(redis.connected_clients.lock().unwrap().get(other).is_none());
 
-        thread::sleep(Duration::from_secs(1));
+        let kill = redis.execute(Command::Client {
+            param: ClientParam::KillAddr("127.0.0.1:5001".to_string()),
+            client_id: me,
+        });
+        assert_eq!("ERR No such client", kill.unwrap_err());
+
+        let kill = redis.execute(Command::Client {
+            param: ClientParam::Kill(KillFilter {
+                skipme: true,
+                ..KillFilter::default()
+            }),
+            client_id: me,
+        });
+        assert!(eq_response(Re::Integer(1), kill.unwrap()));
+        assert!(redis
+            .connected_clients
+            .lock()
+            .unwrap()
+            .get(another)
+            .is_none());
+
+        let kill = redis.execute(Command::Client {
+            param: ClientParam::Kill(KillFilter {
+                id: Some(me),
+                addr: None,
+                skipme: false,
+            }),
+            client_id: me,
+        });
+        assert!(eq_response(Re::Integer(1), kill.unwrap()));
+        assert_eq!(0, redis.connected_clients.lock().unwrap().iter().count());
+    }
 
-        let keys = vec!["key".to_string()];
-        let touch = redis.execute(Command::Touch { keys });
+    #[test]
+    fn test_client_id() {
+        let mut redis: Redis = Redis::new_for_test();
 
-        let pattern = "*".to_string();
-        let keys = redis.execute(Command::Keys { pattern });
+        let id = redis.execute(Command::Client {
+            param: ClientParam::Id,
+            client_id: 7,
+        });
 
-        assert!(eq_response(Re::String("0".to_string()), touch.unwrap()));
-        assert!(eq_response(Re::List(Vec::new()), keys.unwrap()));
+        assert!(eq_response(Re::Integer(7), id.unwrap()));
     }
 
     #[test]
-    fn test_touch_returns_number_of_keys_touched() {
+    fn test_unsubscribe_only_affects_the_sending_client() {
         let mut redis: Redis = Redis::new_for_test();
+        let mut receivers = vec![];
+        for client_id in 1..=2 {
+            let subscribe = redis.execute(Command::Subscribe {
+                channels: vec!["news".to_string()],
+                client_id,
+            });
+            match subscribe.unwrap() {
+                Response::Stream(receiver) => {
+                    let _ = receiver.try_recv();
+                    receivers.push(receiver);
+                }
+                _ => panic!("SUBSCRIBE debe devolver un stream"),
+            }
+        }
 
-        let key = "key1".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key2".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let touch = redis.execute(Command::Touch { keys });
+        let _ = redis.execute(Command::Unsubscribe {
+            channels: vec!["news".to_string()],
+            client_id: 1,
+        });
+        let _ = redis.execute(Command::Publish {
+            channel: "news".to_string(),
+            message: "hello".to_string(),
+        });
 
-        assert!(eq_response(Re::String("2".to_string()), touch.unwrap()));
+        assert_eq!(message("news", "hello"), receivers[1].recv().unwrap());
+        assert!(receivers[0].try_recv().is_err());
     }
 
     #[test]
-    fn test_set_element_and_flushdb() {
+    fn test_subscription_count_is_reported_and_unsubscribe_without_channels_removes_all() {
         let mut redis: Redis = Redis::new_for_test();
+        let receiver = match redis
+            .execute(Command::Subscribe {
+                channels: vec!["news".to_string(), "sports".to_string(), "news".to_string()],
+                client_id: 1,
+            })
+            .unwrap()
+        {
+            Response::Stream(receiver) => receiver,
+            _ => panic!("SUBSCRIBE debe devolver un stream"),
+        };
+        let counts: Vec<Re> = receiver
+            .try_iter()
+            .map(|confirmation| match confirmation {
+                Re::Array(elements) => elements[2].clone(),
+                _ => panic!("La confirmación debe ser un array"),
+            })
+            .collect();
+        assert_eq!(vec![Re::Integer(1), Re::Integer(2), Re::Integer(2)], counts);
+        assert_eq!(2, redis.subscription_count(1));
 
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let unsubscribe = redis.execute(Command::Unsubscribe {
+            channels: vec![],
+            client_id: 1,
+        });
+        assert!(eq_response(
+            Re::Array(vec![
+                Re::String("unsubscribe".to_string()),
+                Re::String("news".to_string()),
+                Re::Integer(1),
+                Re::String("unsubscribe".to_string()),
+                Re::String("sports".to_string()),
+                Re::Integer(0),
+            ]),
+            unsubscribe.unwrap()
+        ));
+        assert_eq!(0, redis.subscription_count(1));
+        // Sin suscripciones, el stream del cliente se cierra.
+        assert!(receiver.recv().is_err());
+    }
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+    #[allow(dead_code)]
+    fn publish_to_slow_subscriber(policy: &str) -> (Redis, MessageReceiver<Re>) {
+        let mut redis: Redis = Redis::new_for_test();
+        for (parameter, value) in [
+            ("pubsub-queue-size", "2"),
+            ("pubsub-overflow-policy", policy),
+        ] {
+            let _ = redis.execute(Command::ConfigSet {
+                parameter: parameter.to_string(),
+                value: value.to_string(),
+            });
+        }
+        let receiver = subscribe_receiver(&mut redis, vec!["news"]);
+        for msg in ["first", "second", "third"] {
+            let _ = redis.execute(Command::Publish {
+                channel: "news".to_string(),
+                message: msg.to_string(),
+            });
+        }
+        (redis, receiver)
+    }
 
-        let flushdb = redis.execute(Command::Flushdb);
-        assert!(flushdb.is_ok());
+    #[test]
+    fn test_slow_subscriber_drops_oldest_messages() {
+        let (mut redis, receiver) = publish_to_slow_subscriber("drop-oldest");
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
+        assert_eq!(
+            vec![message("news", "second"), message("news", "third")],
+            receiver.try_iter().collect::<Vec<Re>>()
+        );
+        assert_eq!(1, redis.subscription_count(1));
+        let stats = redis.execute(Command::Info {
+            param: InfoParam::Stats,
+        });
+        match stats.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.contains("pubsub_dropped_messages:1\r\n"));
+                assert!(info.contains("pubsub_slow_disconnections:0\r\n"));
+            }
+            _ => panic!("INFO debe devolver un string"),
+        }
     }
 
     #[test]
-    fn test_store_then_load() {
-        let mut redis: Redis = Redis::new_for_test();
+    fn test_slow_subscriber_is_disconnected() {
+        let (mut redis, receiver) = publish_to_slow_subscriber("disconnect");
 
-        let key1 = "key1".to_string();
-        let value1 = "value1".to_string();
-        let _set = redis.execute(Command::Set {
-            key: key1.clone(),
-            value: value1.clone(),
-        });
-        let key2 = "key2".to_string();
-        let value2 = "value2".to_string();
-        let _set = redis.execute(Command::Set {
-            key: key2.clone(),
-            value: value2.clone(),
-        });
-        let expire = Duration::from_secs(2);
-        let _ttl = redis.execute(Command::Expire {
-            key: key2.clone(),
-            ttl: expire.clone(),
+        assert_eq!(
+            vec![message("news", "first"), message("news", "second")],
+            receiver.into_iter().collect::<Vec<Re>>()
+        );
+        assert_eq!(0, redis.subscription_count(1));
+        let stats = redis.execute(Command::Info {
+            param: InfoParam::Stats,
         });
+        match stats.unwrap() {
+            Response::Normal(Re::String(info)) => {
+                assert!(info.contains("pubsub_dropped_messages:0\r\n"));
+                assert!(info.contains("pubsub_slow_disconnections:1\r\n"));
+            }
+            _ => panic!("INFO debe devolver un string"),
+        }
+    }
 
-        let path = "test_store_then_load.rdb".to_string();
-        let _store = redis.execute(Command::Store { path: path.clone() });
+    #[test]
+    fn test_remove_client_cancels_its_subscriptions_and_monitor() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _ = redis.execute(Command::AddClient);
+        let _ = redis.execute(Command::AddClient);
+        let subscriber = subscribe_receiver(&mut redis, vec!["news"]);
+        let monitor = match redis.execute(Command::Monitor { client_id: 2 }).unwrap() {
+            Response::Stream(receiver) => receiver,
+            _ => panic!("MONITOR debe devolver un stream"),
+        };
 
-        let _content = fs::read(path.clone()).unwrap();
-        let mut redis_new: Redis = Redis::new_for_test();
-        let _load = redis_new.execute(Command::Load { path: path });
+        let _ = redis.execute(Command::RemoveClient { client_id: 1 });
+        let _ = redis.execute(Command::RemoveClient { client_id: 2 });
 
-        let get = redis_new.execute(Command::Get { key: key1 });
-        assert!(eq_response(Re::String(value1), get.unwrap()));
+        assert_eq!(0, redis.subscription_count(1));
+        assert!(!redis.is_monitored());
+        assert!(subscriber.recv().is_err());
+        assert!(monitor.try_iter().count() > 0);
+        assert!(monitor.recv().is_err());
+        let channels = redis.execute(Command::Pubsub {
+            param: PubSubParam::Channels,
+        });
+        assert!(eq_response(Re::List(vec![]), channels.unwrap()));
+    }
 
-        let get = redis_new.execute(Command::Get { key: key2.clone() });
-        assert!(eq_response(Re::String(value2), get.unwrap()));
+    #[test]
+    fn test_ping_and_echo_return_the_message() {
+        let mut redis: Redis = Redis::new_for_test();
 
-        let ttl = redis_new.execute(Command::Ttl { key: key2 });
+        let ping = redis.execute(Command::Ping {
+            message: Some("hello".to_string()),
+        });
+        assert!(eq_response(Re::String("hello".to_string()), ping.unwrap()));
+
+        let echo = redis.execute(Command::Echo {
+            message: "hello world".to_string(),
+        });
         assert!(eq_response(
-            Re::String((expire.as_secs() - 1).to_string()),
-            ttl.unwrap(),
+            Re::String("hello world".to_string()),
+            echo.unwrap()
         ));
-
-        fs::remove_file("test_store_then_load.rdb").unwrap();
     }
 
     #[test]
-    fn test_load_corrupt_file_returns_err() {
+    fn test_store_runs_in_background_from_a_snapshot() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "before".to_string(),
+            options: SetOptions::default(),
+        });
 
-        let path = "test_load_empy_file_returns_err.rdb".to_string();
-        let mut file = fs::File::create(path.clone()).unwrap();
-
-        let op_resizedb = 0xfb;
-        let mut store_len = TtlHashMap::length_encode(1);
-        let mut ttl_len = TtlHashMap::length_encode(0);
-        let byte_value_type = TtlHashMap::value_type_encode(&Re::String("value".to_string()));
-        let key = "key".to_string();
-        let mut key_encoded = TtlHashMap::string_encode(key.clone());
-        let op_eof = 0xff;
-
-        let mut bytes = ["REDIS".as_bytes(), "0001".as_bytes()].concat();
-        bytes.push(op_resizedb);
-        bytes.append(&mut store_len);
-        bytes.append(&mut ttl_len);
-        bytes.push(byte_value_type);
-        bytes.append(&mut key_encoded);
-        bytes.push(op_eof);
-
-        let _ = file.write_all(&bytes);
+        let path = "test_store_runs_in_background.rdb".to_string();
+        let store = redis.execute(Command::Store { path: path.clone() });
+        assert!(eq_response(
+            Re::SimpleString("Background saving started".to_string()),
+            store.unwrap()
+        ));
+        // Los cambios posteriores al STORE no forman parte del archivo guardado.
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "after".to_string(),
+            options: SetOptions::default(),
+        });
+        wait_for_background_save(&mut redis);
 
-        let load = redis.execute(Command::Load { path });
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::Load { path: path.clone() });
+        let get = redis_new.execute(Command::Get {
+            key: "key".to_string(),
+        });
+        assert!(eq_response(Re::String("before".to_string()), get.unwrap()));
 
-        assert!(load.is_err());
-        fs::remove_file("test_load_empy_file_returns_err.rdb").unwrap();
+        fs::remove_file(path).unwrap();
     }
 
     #[test]
-    fn test_config_get_ok() {
+    fn test_save_if_needed_follows_save_rules() {
         let mut redis: Redis = Redis::new_for_test();
+        let path = "test_save_if_needed_follows_save_rules.rdb".to_string();
+        {
+            let mut config = redis.config.lock().unwrap();
+            config.set_dbfilename(path.clone());
+            config.set_save_rules(vec![SaveRule {
+                seconds: 0,
+                changes: 2,
+            }]);
+        }
 
-        let config_get = redis.execute(Command::ConfigGet);
-        let conf = vec![
-            "dump.rdb".to_string(),
-            "log.log".to_string(),
-            "8080".to_string(),
-            "0".to_string(),
-            "0".to_string(),
-        ];
+        let _set = redis.execute(Command::Set {
+            key: "key1".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        redis.save_if_needed();
+        assert!(redis.background_save.is_none());
 
-        assert!(eq_response(Re::List(conf), config_get.unwrap()));
+        let _set = redis.execute(Command::Set {
+            key: "key2".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        redis.save_if_needed();
+        wait_for_background_save(&mut redis);
+        assert_eq!(0, redis.dirty);
+        assert!(fs::metadata(&path).is_ok());
+
+        redis.save_if_needed();
+        assert!(redis.background_save.is_none());
+
+        fs::remove_file(path).unwrap();
     }
 
     #[test]
-    fn test_config_set_verbose() {
+    fn test_watch_changes_receives_every_mutation() {
         let mut redis: Redis = Redis::new_for_test();
+        let changes = redis.watch_changes();
 
-        let parameter = "verbose".to_string();
-        let value = "1".to_string();
-        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
+        let _get = redis.execute(Command::Get {
+            key: "key".to_string(),
+        });
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".to_string(),
+            value: vec!["a".to_string(), "b".to_string()],
+        });
+        let _del = redis.execute(Command::Del {
+            keys: vec!["key".to_string()],
+        });
+        let _flushdb = redis.execute(Command::Flushdb {
+            asynchronous: false,
+        });
 
-        assert_eq!("1", redis.config.lock().unwrap().get_verbose());
+        let expected = vec![
+            ("key", "set", Some(Re::String("value".to_string()))),
+            (
+                "list",
+                "rpush",
+                Some(Re::List(vec!["a".to_string(), "b".to_string()])),
+            ),
+            ("key", "del", None),
+            ("list", "flushdb", None),
+        ];
+        let received: Vec<KeyChange> = changes.try_iter().collect();
+        assert_eq!(expected.len(), received.len());
+        for ((key, event, value), change) in expected.into_iter().zip(received) {
+            assert_eq!(key, change.key);
+            assert_eq!(event, change.event);
+            assert_eq!(value, change.value);
+        }
     }
 
     #[test]
-    fn test_config_set_dbfilename() {
+    fn test_watch_changes_drops_released_observers() {
         let mut redis: Redis = Redis::new_for_test();
+        let changes = redis.watch_changes();
+        drop(redis.watch_changes());
 
-        let parameter = "dbfilename".to_string();
-        let value = "new_dump.rdb".to_string();
-        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            options: SetOptions::default(),
+        });
 
-        assert_eq!(
-            "new_dump.rdb",
-            redis.config.lock().unwrap().get_dbfilename()
-        );
+        assert_eq!(1, redis.change_observers.len());
+        assert_eq!("set", changes.try_recv().unwrap().event);
     }
 
     #[test]
-    fn test_config_set_logfile() {
+    fn test_command_execution_is_logged_with_client_and_elapsed_time() {
         let mut redis: Redis = Redis::new_for_test();
+        let (log_sender, logs) = std::sync::mpsc::channel();
+        redis.log_sender = log_sender;
+        let client_id = redis
+            .connected_clients
+            .lock()
+            .unwrap()
+            .register("127.0.0.1:5000".to_string(), None);
+
+        let _ = redis.execute_from_client(Command::Dbsize, Some(client_id), &[]);
+        let _ = redis.execute_shared_from_client(
+            &Command::Get {
+                key: "key".to_string(),
+            },
+            Some(client_id),
+            &[],
+        );
+        let _ = redis.execute(Command::Dbsize);
 
-        let parameter = "logfile".to_string();
-        let value = "new_log.log".to_string();
-        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
-
-        assert_eq!("new_log.log", redis.config.lock().unwrap().get_logfile());
+        let client = format!("client id={} addr=127.0.0.1:5000", client_id);
+        let executions: Vec<String> = logs
+            .try_iter()
+            .map(|log| log.to_string())
+            .filter(|log| log.contains(" executed"))
+            .collect();
+        assert_eq!(2, executions.len());
+        assert!(executions[0].contains(&format!("Command DBSIZE executed - {} - ", client)));
+        assert!(executions[1].contains(&format!("Command GET executed - {} - ", client)));
+        assert!(executions.iter().all(|log| log.trim_end().ends_with("us")));
     }
 
     #[test]
-    fn test_config_set_wrong_parameter() {
+    fn test_monitor_reports_commands_in_redis_format() {
         let mut redis: Redis = Redis::new_for_test();
+        let client_id = redis
+            .connected_clients
+            .lock()
+            .unwrap()
+            .register("127.0.0.1:5000".to_string(), None);
+        let receiver = match redis.execute(Command::Monitor { client_id: 1 }) {
+            Ok(Response::Stream(receiver)) => receiver,
+            _ => panic!("MONITOR debe devolver un stream"),
+        };
+        assert_eq!(Re::SimpleString("OK".to_string()), receiver.recv().unwrap());
 
-        let parameter = "timeout".to_string();
-        let value = "1".to_string();
-        let config_set = redis.execute(Command::ConfigSet { parameter, value });
+        let args: Vec<String> = vec!["SET", "key", "a \"value\""]
+            .into_iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let _set = redis.execute_from_client(
+            Command::Set {
+                key: "key".to_string(),
+                value: "a \"value\"".to_string(),
+                options: SetOptions::default(),
+            },
+            Some(client_id),
+            &args,
+        );
+        let _auth = redis.execute(Command::Auth {
+            password: "secret".to_string(),
+        });
+        let _dbsize = redis.execute(Command::Dbsize);
+        let args: Vec<String> = vec!["CONFIG", "SET", "requirepass", "secret"]
+            .into_iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let _config_set = redis.execute_from_client(
+            Command::ConfigSet {
+                parameter: "requirepass".to_string(),
+                value: "secret".to_string(),
+            },
+            Some(client_id),
+            &args,
+        );
 
-        assert!(config_set.is_err());
-        assert_ne!(1, redis.config.lock().unwrap().get_timeout());
+        let lines: Vec<String> = receiver
+            .try_iter()
+            .map(|line| match line {
+                Re::SimpleString(line) => line,
+                _ => panic!("MONITOR debe enviar simple strings"),
+            })
+            .collect();
+        assert_eq!(3, lines.len());
+        assert!(lines[0].ends_with(r#" [0 127.0.0.1:5000] "SET" "key" "a \"value\"""#));
+        assert!(lines[1].ends_with(r#" [0 internal] "dbsize""#));
+        assert!(lines[2].ends_with(r#""CONFIG" "SET" "requirepass" "(redacted)""#));
+        let timestamp = lines[0].split(' ').next().unwrap();
+        assert_eq!(6, timestamp.split('.').nth(1).unwrap().len());
+        assert!(timestamp.parse::<f64>().is_ok());
     }
 
     #[test]
-    fn test_info_connected_clients_ok() {
+    fn test_key_and_value_size_limits() {
         let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConnectedClients,
-        });
+        {
+            let mut config = redis.config.lock().unwrap();
+            config.set_max_key_length("4".to_string()).unwrap();
+            config.set_max_value_size("5".to_string()).unwrap();
+        }
 
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), info.unwrap()));
+        let set = redis.execute(Command::Set {
+            key: "long_key".to_string(),
+            value: "v".to_string(),
+            options: SetOptions::default(),
+        });
+        assert_eq!(Some(KEY_TOO_LONG_MSG.to_string()), set.err());
+        let set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "too big".to_string(),
+            options: SetOptions::default(),
+        });
+        assert_eq!(Some(VALUE_TOO_BIG_MSG.to_string()), set.err());
+        assert!(!redis.db.contains_key(&"key".to_string()));
 
-        let _ = redis.execute(Command::AddClient);
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConnectedClients,
+        let _set = redis.execute(Command::Set {
+            key: "key".to_string(),
+            value: "abc".to_string(),
+            options: SetOptions::default(),
         });
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), info.unwrap()));
+        let append = redis.execute(Command::Append {
+            key: "key".to_string(),
+            value: "def".to_string(),
+        });
+        assert_eq!(Some(VALUE_TOO_BIG_MSG.to_string()), append.err());
+        let append = redis.execute(Command::Append {
+            key: "key".to_string(),
+            value: "de".to_string(),
+        });
+        assert!(append.is_ok());
 
-        let _ = redis.execute(Command::RemoveClient);
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConnectedClients,
+        // Los comandos de lectura no están limitados.
+        let get = redis.execute(Command::Get {
+            key: "long_key".to_string(),
         });
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), info.unwrap()));
+        assert!(get.is_ok());
     }
 
     #[test]
-    fn test_info_port_ok() {
+    fn test_max_collection_elements_limit() {
         let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::Port,
+        let _config_set = redis.execute(Command::ConfigSet {
+            parameter: "max-collection-elements".to_string(),
+            value: "3".to_string(),
         });
 
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("8080".to_string()), info.unwrap()));
+        let push = |redis: &mut Redis, values: &[&str]| {
+            redis.execute(Command::Rpush {
+                key: "list".to_string(),
+                value: values.iter().map(|v| v.to_string()).collect(),
+            })
+        };
+        assert!(push(&mut redis, &["a", "a"]).is_ok());
+        assert_eq!(
+            Some(TOO_MANY_ELEMENTS_MSG.to_string()),
+            push(&mut redis, &["b", "c"]).err()
+        );
+        assert!(push(&mut redis, &["b"]).is_ok());
+
+        let sadd = |redis: &mut Redis, values: &[&str]| {
+            redis.execute(Command::Sadd {
+                key: "set".to_string(),
+                values: values.iter().map(|v| v.to_string()).collect(),
+            })
+        };
+        assert!(sadd(&mut redis, &["a", "b", "c"]).is_ok());
+        // Los miembros que ya están en el set no cuentan para el límite.
+        assert!(sadd(&mut redis, &["a", "b"]).is_ok());
+        assert_eq!(
+            Some(TOO_MANY_ELEMENTS_MSG.to_string()),
+            sadd(&mut redis, &["a", "d"]).err()
+        );
+
+        let zadd = redis.execute(Command::Zadd {
+            key: "zset".to_string(),
+            members: vec![
+                (1.0, "a".to_string()),
+                (2.0, "a".to_string()),
+                (3.0, "b".to_string()),
+                (4.0, "c".to_string()),
+                (5.0, "d".to_string()),
+            ],
+        });
+        assert_eq!(Some(TOO_MANY_ELEMENTS_MSG.to_string()), zadd.err());
     }
 
     #[test]
-    fn test_info_config_file_ok() {
+    fn test_writes_fail_when_used_memory_exceeds_maxmemory() {
         let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConfigFile,
+        let set = |redis: &mut Redis, key: &str| {
+            redis.execute(Command::Set {
+                key: key.to_string(),
+                value: "x".repeat(1000),
+                options: SetOptions::default(),
+            })
+        };
+        assert!(set(&mut redis, "a").is_ok());
+        let _config_set = redis.execute(Command::ConfigSet {
+            parameter: "maxmemory".to_string(),
+            value: "100".to_string(),
         });
 
-        assert!(info.is_ok());
-        assert!(eq_response(
-            Re::String(redis.config.lock().unwrap().get_configfile()),
-            info.unwrap()
-        ));
+        assert_eq!(Some(OOM_MSG.to_string()), set(&mut redis, "b").err());
+        assert!(!redis.db.contains_key(&"b".to_string()));
+        // Las lecturas y los borrados se siguen ejecutando, y liberan memoria.
+        let get = redis.execute(Command::Get {
+            key: "a".to_string(),
+        });
+        assert!(get.is_ok());
+        let del = redis.execute(Command::Del {
+            keys: vec!["a".to_string()],
+        });
+        assert!(del.is_ok());
+        assert!(set(&mut redis, "b").is_ok());
     }
 
     #[test]
-    fn test_info_process_id_ok() {
+    fn test_lfu_policies_evict_the_least_frequently_used_keys() {
         let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ProcessId,
-        });
+        let set = |redis: &mut Redis, key: &str| {
+            redis.execute(Command::Set {
+                key: key.to_string(),
+                value: "x".repeat(1000),
+                options: SetOptions::default(),
+            })
+        };
+        let config_set = |redis: &mut Redis, parameter: &str, value: &str| {
+            redis.execute(Command::ConfigSet {
+                parameter: parameter.to_string(),
+                value: value.to_string(),
+            })
+        };
+        assert!(set(&mut redis, "a").is_ok());
+        assert!(set(&mut redis, "b").is_ok());
+        let maxmemory = redis.used_memory().to_string();
+        assert!(set(&mut redis, "c").is_ok());
+        for key in ["b", "c"] {
+            let _get = redis.execute(Command::Get {
+                key: key.to_string(),
+            });
+        }
+        assert!(config_set(&mut redis, "maxmemory", &maxmemory).is_ok());
 
-        assert!(info.is_ok());
-        assert!(eq_response(
-            Re::String(process::id().to_string()),
-            info.unwrap()
-        ));
+        // Sin claves con expiración, volatile-lfu no puede descartar ninguna.
+        assert!(config_set(&mut redis, "maxmemory-policy", "volatile-lfu").is_ok());
+        assert_eq!(Some(OOM_MSG.to_string()), set(&mut redis, "d").err());
+
+        assert!(config_set(&mut redis, "maxmemory-policy", "allkeys-lfu").is_ok());
+        assert!(set(&mut redis, "d").is_ok());
+        assert!(!redis.db.contains_key(&"a".to_string()));
+        for key in ["b", "c", "d"] {
+            assert!(redis.db.contains_key(&key.to_string()));
+        }
+        assert_eq!(1, redis.evicted_keys);
     }
 }
@@ -0,0 +1,293 @@
+use crate::config::server_config::Config;
+use crate::entities::clock::Clock;
+use crate::entities::command::Command;
+use crate::entities::connected_clients::ConnectedClients;
+use crate::entities::key_change::KeyChange;
+use crate::entities::log::Log;
+use crate::entities::redis_element::RedisElement;
+use crate::entities::response::Response;
+use crate::entities::server_stats::ServerStats;
+use crate::entities::set_options::SetOptions;
+use crate::service::command_generator::generate;
+use crate::service::redis::Redis;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Id de cliente usado para los comandos ejecutados a través de un `RedisHandle`.
+const EMBEDDED_CLIENT_ID: u64 = 0;
+
+/// RedisHandle: Base de datos embebida en el proceso, para usarla desde otros programas en Rust
+/// sin levantar el servidor ni abrir sockets.
+///
+/// Los métodos tipados devuelven los valores ya convertidos; cualquier otro comando puede
+/// ejecutarse con `command` (a partir de sus argumentos, como los enviaría un cliente) o con
+/// `execute`. Los errores son los mismos que recibiría un cliente.
+pub struct RedisHandle {
+    redis: Redis,
+}
+
+impl RedisHandle {
+    /// Crea una base de datos vacía con la configuración por defecto.
+    pub fn new() -> Self {
+        RedisHandle::with_config(Config::new())
+    }
+
+    /// Crea una base de datos vacía con la configuración indicada. Los logs se descartan.
+    pub fn with_config(config: Config) -> Self {
+        let (log_sender, _): (Sender<Log>, _) = mpsc::channel();
+        RedisHandle {
+            redis: Redis::new(
+                log_sender,
+                Arc::new(Mutex::new(config)),
+                Arc::new(Mutex::new(ConnectedClients::new())),
+                Arc::new(Mutex::new(ServerStats::new())),
+            ),
+        }
+    }
+
+    /// Reemplaza el reloj con el que se calculan las expiraciones de las claves, por ejemplo por
+    /// un `MockClock` para controlar el paso del tiempo.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.redis.set_clock(clock);
+    }
+
+    /// Ejecuta un comando ya construido y devuelve la respuesta de la base de datos.
+    pub fn execute(&mut self, command: Command) -> Result<Response, String> {
+        self.redis.execute(command)
+    }
+
+    /// Ejecuta un comando a partir de sus argumentos, como los enviaría un cliente
+    /// (ej: `["SET", "key", "value"]`), y devuelve el valor de la respuesta.
+    pub fn command(&mut self, args: &[&str]) -> Result<RedisElement, String> {
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        let command = generate(args, EMBEDDED_CLIENT_ID)?;
+        self.execute_element(command)
+    }
+
+    /// Guarda el valor en la clave, reemplazando su valor y su expiración anteriores.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.execute_element(Command::Set {
+            key: key.to_string(),
+            value: value.to_string(),
+            options: SetOptions::default(),
+        })?;
+        Ok(())
+    }
+
+    /// Devuelve el valor de la clave, o None si no existe.
+    pub fn get(&mut self, key: &str) -> Result<Option<String>, String> {
+        match self.execute_element(Command::Get {
+            key: key.to_string(),
+        })? {
+            RedisElement::String(value) => Ok(Some(value)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Elimina las claves indicadas. Devuelve la cantidad de claves eliminadas.
+    pub fn del(&mut self, keys: &[&str]) -> Result<u64, String> {
+        let reply = self.execute_element(Command::Del {
+            keys: to_strings(keys),
+        })?;
+        parse_integer(reply)
+    }
+
+    /// Devuelve si la clave existe.
+    pub fn exists(&mut self, key: &str) -> Result<bool, String> {
+        let reply = self.execute_element(Command::Exists {
+            keys: vec![key.to_string()],
+        })?;
+        Ok(parse_integer::<u64>(reply)? > 0)
+    }
+
+    /// Incrementa el entero guardado en la clave y devuelve el nuevo valor.
+    pub fn incrby(&mut self, key: &str, increment: i64) -> Result<i64, String> {
+        let reply = self.execute_element(Command::Incrby {
+            key: key.to_string(),
+            increment,
+        })?;
+        parse_integer(reply)
+    }
+
+    /// Configura la expiración de la clave. Devuelve false si la clave no existe.
+    pub fn expire(&mut self, key: &str, ttl: Duration) -> Result<bool, String> {
+        let reply = self.execute_element(Command::Expire {
+            key: key.to_string(),
+            ttl,
+            conditions: vec![],
+        })?;
+        Ok(parse_integer::<u64>(reply)? > 0)
+    }
+
+    /// Inserta los valores al principio de la lista y devuelve su nuevo largo.
+    pub fn lpush(&mut self, key: &str, values: &[&str]) -> Result<u64, String> {
+        let reply = self.execute_element(Command::Lpush {
+            key: key.to_string(),
+            value: to_strings(values),
+        })?;
+        parse_integer(reply)
+    }
+
+    /// Inserta los valores al final de la lista y devuelve su nuevo largo.
+    pub fn rpush(&mut self, key: &str, values: &[&str]) -> Result<u64, String> {
+        let reply = self.execute_element(Command::Rpush {
+            key: key.to_string(),
+            value: to_strings(values),
+        })?;
+        parse_integer(reply)
+    }
+
+    /// Devuelve los elementos de la lista entre las posiciones indicadas, inclusive. Las
+    /// posiciones negativas se cuentan desde el final de la lista.
+    pub fn lrange(&mut self, key: &str, start: i32, stop: i32) -> Result<Vec<String>, String> {
+        match self.execute_element(Command::Lrange {
+            key: key.to_string(),
+            begin: start,
+            end: stop,
+        })? {
+            RedisElement::List(values) => Ok(values),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Agrega los miembros al set y devuelve la cantidad de miembros nuevos.
+    pub fn sadd(&mut self, key: &str, members: &[&str]) -> Result<u64, String> {
+        let reply = self.execute_element(Command::Sadd {
+            key: key.to_string(),
+            values: members.iter().map(|member| member.to_string()).collect(),
+        })?;
+        parse_integer(reply)
+    }
+
+    /// Devuelve los miembros del set, o un set vacío si la clave no existe.
+    pub fn smembers(&mut self, key: &str) -> Result<HashSet<String>, String> {
+        match self.execute_element(Command::Smembers {
+            key: key.to_string(),
+        })? {
+            RedisElement::Set(members) => Ok(members),
+            _ => Ok(HashSet::new()),
+        }
+    }
+
+    /// Registra un observador de las modificaciones de la base de datos. Por el canal devuelto se
+    /// recibe cada clave modificada, con el evento y su nuevo valor.
+    pub fn watch_changes(&mut self) -> Receiver<KeyChange> {
+        self.redis.watch_changes()
+    }
+
+    /// Ejecuta el comando y devuelve el valor de la respuesta. Las respuestas en forma de flujo
+    /// (MONITOR, SUBSCRIBE) o bloqueantes (BLPOP) no pueden usarse sin una conexión.
+    fn execute_element(&mut self, command: Command) -> Result<RedisElement, String> {
+        match self.redis.execute(command)? {
+            Response::Normal(element) => Ok(element),
+            Response::Error(msg) => Err(msg),
+            Response::Stream(_) | Response::Blocked(_) => {
+                Err("ERR command not supported by the embedded API".to_string())
+            }
+        }
+    }
+}
+
+impl Default for RedisHandle {
+    fn default() -> Self {
+        RedisHandle::new()
+    }
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+/// Convierte la respuesta entera de un comando al tipo numérico indicado.
+fn parse_integer<T: TryFrom<i64>>(reply: RedisElement) -> Result<T, String> {
+    match reply {
+        RedisElement::Integer(value) => {
+            T::try_from(value).map_err(|_| format!("ERR unexpected reply '{}'", value))
+        }
+        _ => Err("ERR unexpected reply".to_string()),
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::clock::MockClock;
+    use crate::entities::redis_element::RedisElement;
+    use crate::service::redis_handle::RedisHandle;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_typed_string_commands() {
+        let mut redis = RedisHandle::new();
+
+        assert_eq!(Ok(None), redis.get("key"));
+        assert_eq!(Ok(()), redis.set("key", "value"));
+        assert_eq!(Ok(Some("value".to_string())), redis.get("key"));
+        assert_eq!(Ok(true), redis.exists("key"));
+        assert_eq!(Ok(true), redis.expire("key", Duration::from_secs(10)));
+        assert_eq!(Ok(1), redis.del(&["key", "missing"]));
+        assert_eq!(Ok(false), redis.exists("key"));
+        assert_eq!(Ok(5), redis.incrby("counter", 5));
+        assert_eq!(Ok(3), redis.incrby("counter", -2));
+    }
+
+    #[test]
+    fn test_typed_collection_commands() {
+        let mut redis = RedisHandle::new();
+
+        assert_eq!(Ok(2), redis.rpush("list", &["b", "c"]));
+        assert_eq!(Ok(3), redis.lpush("list", &["a"]));
+        assert_eq!(
+            Ok(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            redis.lrange("list", 0, -1)
+        );
+        assert_eq!(Ok(2), redis.sadd("set", &["x", "y", "x"]));
+        assert_eq!(2, redis.smembers("set").unwrap().len());
+        assert!(redis.get("list").unwrap_err().starts_with("WRONGTYPE"));
+    }
+
+    #[test]
+    fn test_command_from_arguments() {
+        let mut redis = RedisHandle::new();
+
+        assert_eq!(
+            Ok(RedisElement::SimpleString("OK".to_string())),
+            redis.command(&["SET", "key", "value"])
+        );
+        assert_eq!(
+            Ok(RedisElement::Integer(5)),
+            redis.command(&["STRLEN", "key"])
+        );
+        assert!(redis.command(&["NOTACOMMAND"]).is_err());
+    }
+
+    #[test]
+    fn test_watch_changes() {
+        let mut redis = RedisHandle::new();
+        let changes = redis.watch_changes();
+
+        assert_eq!(Ok(2), redis.incrby("counter", 2));
+        let change = changes.try_recv().unwrap();
+        assert_eq!("counter", change.key);
+        assert_eq!("incrby", change.event);
+        assert_eq!(Some(RedisElement::String("2".to_string())), change.value);
+    }
+
+    #[test]
+    fn test_set_clock_controls_expiration() {
+        let mut redis = RedisHandle::new();
+        let clock = Arc::new(MockClock::new(SystemTime::now()));
+        redis.set_clock(clock.clone());
+
+        assert_eq!(Ok(()), redis.set("key", "value"));
+        assert_eq!(Ok(true), redis.expire("key", Duration::from_secs(10)));
+        clock.advance(Duration::from_secs(9));
+        assert_eq!(Ok(true), redis.exists("key"));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(Ok(false), redis.exists("key"));
+    }
+}
@@ -1,37 +1,150 @@
 use crate::config::server_config::Config;
 use crate::entities::command::Command;
+use crate::entities::connected_clients::ConnectedClients;
 use crate::entities::log::Log;
 use crate::entities::log_level::LogLevel;
+use crate::entities::message_queue::MessageReceiver;
+use crate::entities::rate_limiter::{RateLimitPolicy, RateLimiter};
+use crate::entities::redis_element::RedisElement;
 use crate::entities::response::Response;
+use crate::entities::server_stats::ServerStats;
 use crate::service::command_generator::generate;
 use crate::service::logger::Logger;
 use crate::service::redis::Redis;
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::io::{BufReader, Error, ErrorKind, Read, Write};
-use std::net::{TcpListener, TcpStream};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{Receiver, Sender};
-use std::sync::{mpsc, Arc, Mutex};
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
 use std::thread;
 
+use crate::protocol::http::auth::{is_authorized, UNAUTHORIZED_HEADERS};
 use crate::protocol::http::html::Html;
-use crate::protocol::http::parse_request::{parse_command_rest, HttpMethod};
+use crate::protocol::http::json::encode_result;
+use crate::protocol::http::parse_request::{
+    header, keep_alive, key_command, parse_command_rest, request_len, subscribe_channel,
+    HttpMethod, ResponseFormat,
+};
 use crate::protocol::http::parse_response::parse_response_rest;
+use crate::protocol::http::sse::{encode_event, SSE_HEADERS};
 use crate::protocol::lines_iterator::LinesIterator;
-use crate::protocol::parse_data::{parse_command, parse_response_error, parse_response_ok};
+use crate::protocol::parse_data::{parse_command, parse_response_error, write_response_ok};
+use crate::protocol::type_data::TypeData;
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-/// Tiempo de ejecución entre un ciclo y el siguiente, en el hilo de Mantenimiento.
-/// Este valor está representado en Segundos.
-static STORE_TIME_SEC: u64 = 120;
+/// Tiempo máximo que el hilo de la DB espera un comando antes de revisar si venció la espera de
+/// algún cliente bloqueado o si corresponde persistir la base de datos. Este valor está
+/// representado en Milisegundos.
+static BLOCKED_CLIENTS_TICK_MS: u64 = 100;
 
-/// Tipo de dato definido para guardar las conecciones de los usuarios y su estado en uso.
-type VecHandler = Vec<(JoinHandle<Result<(), io::Error>>, Arc<AtomicBool>)>;
+/// Id de cliente usado para los comandos recibidos por la interfaz REST. Los clientes conectados
+/// reciben ids a partir de 1, por lo que no se confunden con él.
+static REST_CLIENT_ID: u64 = 0;
+
+/// Ruta del dashboard de administración de la interfaz REST.
+static DASHBOARD_URL: &str = "/dashboard";
+
+/// Tamaño máximo, en bytes, que se lee de un request de la interfaz REST.
+static MAX_REST_REQUEST_SIZE: usize = 64 * 1024;
+
+/// Cantidad de hilos que atienden en paralelo las conexiones de la interfaz REST.
+static REST_WORKERS: usize = 8;
+
+/// Tiempo máximo que una conexión keep-alive de la interfaz REST espera el siguiente request
+/// antes de cerrarse. Este valor está representado en Segundos.
+static REST_KEEP_ALIVE_SECS: u64 = 5;
+
+/// Error con el que se rechazan los comandos de un cliente que supera su límite de comandos por
+/// segundo.
+static RATE_LIMIT_MSG: &str = "ERR rate limit exceeded";
+
+/// Tipo de dato definido para el hilo que atiende a un cliente.
+type ClientHandler = JoinHandle<Result<(), io::Error>>;
 /// Tipo de dato definido para el canal de envío de mensajes al hilo ejecutor de comandos en DB
-type DbSender = Sender<(Command, Sender<Response>)>;
+type DbSender = Sender<(DbRequest, Sender<Response>)>;
 /// Tipo de dato definido para el canal de envío de mensajes al hilo ejecutor de comandos en DB
-type DbReceiver = Receiver<(Command, Sender<Response>)>;
+type DbReceiver = Receiver<(DbRequest, Sender<Response>)>;
+
+/// Comando enviado al hilo de la DB, junto con el cliente que lo envió y los argumentos
+/// recibidos, que se informan a los clientes en MONITOR.
+struct DbRequest {
+    command: Command,
+    /// Id del cliente, o None si el comando no fue enviado por un cliente conectado.
+    client_id: Option<u64>,
+    /// Argumentos recibidos, que se informan en MONITOR y en el slowlog.
+    args: Vec<String>,
+}
+
+impl DbRequest {
+    /// Crea el pedido de un comando que no fue enviado por un cliente conectado.
+    fn internal(command: Command) -> Self {
+        DbRequest {
+            command,
+            client_id: None,
+            args: vec![],
+        }
+    }
+}
+
+/// Eventos recibidos por el hilo encargado de liberar los hilos de los clientes.
+enum HandlerEvent {
+    /// Se creó el hilo que atiende al cliente con el id indicado.
+    Started(u64, ClientHandler),
+    /// Terminó el hilo que atiende al cliente con el id indicado.
+    Finished(u64),
+}
+
+/// Avisa al hilo recolector que terminó el hilo de un cliente al salir de su scope, por lo que el
+/// aviso se envía aunque el hilo termine con error o por un panic.
+struct FinishedGuard {
+    client_id: u64,
+    sender: Sender<HandlerEvent>,
+}
+
+impl Drop for FinishedGuard {
+    fn drop(&mut self) {
+        let _ = self.sender.send(HandlerEvent::Finished(self.client_id));
+    }
+}
+
+/// Conexión con un cliente que contabiliza en las estadísticas del servidor los bytes leídos y
+/// escritos.
+struct CountingStream {
+    stream: TcpStream,
+    stats: Arc<Mutex<ServerStats>>,
+}
+
+impl CountingStream {
+    /// Crea otra referencia a la misma conexión, que contabiliza en las mismas estadísticas.
+    fn try_clone(&self) -> io::Result<Self> {
+        Ok(CountingStream {
+            stream: self.stream.try_clone()?,
+            stats: Arc::clone(&self.stats),
+        })
+    }
+}
+
+impl Read for CountingStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.stream.read(buf)?;
+        self.stats.lock().unwrap().add_input(bytes);
+        Ok(bytes)
+    }
+}
+
+impl Write for CountingStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes = self.stream.write(buf)?;
+        self.stats.lock().unwrap().add_output(bytes);
+        Ok(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
 
 #[derive(Debug)]
 /// Struct utilizado para representar la entidad Server dentro del Modelo.
@@ -39,12 +152,18 @@ type DbReceiver = Receiver<(Command, Sender<Response>)>;
 /// - Las solicitudes de los clientes paa conectarse.
 /// - Se comunicará con la Base de datos Redis
 pub struct Server {
-    /// Instancia de la Base de Datos
-    redis: Redis,
+    /// Instancia de la Base de Datos. El hilo de la DB la toma en modo escritura para ejecutar cada
+    /// comando, mientras que los hilos de los clientes la toman en modo lectura para atender en
+    /// paralelo los comandos de lectura.
+    redis: Arc<RwLock<Redis>>,
     /// Canal para enviar eventos de loggeo al Logger
     log_sender: Sender<Log>,
     /// Configuración del servidor compartida.
     config: Arc<Mutex<Config>>,
+    /// Registro de los clientes conectados, compartido con la DB.
+    connected_clients: Arc<Mutex<ConnectedClients>>,
+    /// Estadísticas de comandos procesados y tráfico de red, compartidas con la DB.
+    stats: Arc<Mutex<ServerStats>>,
 }
 
 impl Server {
@@ -56,26 +175,39 @@ impl Server {
         let loglevel = config.get_loglevel();
         let config = Arc::new(Mutex::new(config));
         let logger = Logger::new(log_receiver, Arc::clone(&config), loglevel);
-        let redis = Redis::new(log_sender.clone(), Arc::clone(&config));
+        let connected_clients = Arc::new(Mutex::new(ConnectedClients::new()));
+        let stats = Arc::new(Mutex::new(ServerStats::new()));
+        let redis = Redis::new(
+            log_sender.clone(),
+            Arc::clone(&config),
+            Arc::clone(&connected_clients),
+            Arc::clone(&stats),
+        );
 
         logger.log();
 
         Ok(Self {
-            redis,
+            redis: Arc::new(RwLock::new(redis)),
             log_sender,
             config,
+            connected_clients,
+            stats,
         })
     }
 
     /// Methodo del Server para ponerlo operativo.
-    pub fn serve(mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn serve(self) -> Result<(), Box<dyn std::error::Error>> {
         let command = Command::Load {
             path: self.config.lock().unwrap().get_dbfilename(),
         };
-        let _ = self.redis.execute(command);
+        let _ = self.redis.write().unwrap().execute(command);
 
         let address = "0.0.0.0:".to_owned() + self.config.lock().unwrap().get_port().as_str();
-        let address_rest = "0.0.0.0:7878".to_owned();
+        let address_rest = if self.config.lock().unwrap().is_httpenabled() {
+            Some("0.0.0.0:".to_owned() + self.config.lock().unwrap().get_httpport().as_str())
+        } else {
+            None
+        };
 
         let log_sender = self.log_sender.clone();
         log_sender
@@ -88,7 +220,7 @@ impl Server {
             ))
             .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Log Sender error"))?;
 
-        self.server_run(&address, &address_rest)?;
+        self.server_run(&address, address_rest.as_deref())?;
 
         log_sender
             .send(Log::new(
@@ -102,44 +234,94 @@ impl Server {
         Ok(())
     }
 
-    fn server_run(self, address: &str, address_rest: &str) -> io::Result<()> {
+    fn server_run(self, address: &str, address_rest: Option<&str>) -> io::Result<()> {
         let listener = TcpListener::bind(address)?;
-        let rest_listener = TcpListener::bind(address_rest)?;
+        // Si las peticiones REST están deshabilitadas no se abre su puerto.
+        let rest_listener = address_rest.map(TcpListener::bind).transpose()?;
         let (db_sender, db_receiver): (DbSender, DbReceiver) = mpsc::channel();
 
         let log_sender = self.log_sender.clone();
-        let timeout = self.config.lock().unwrap().get_timeout();
 
-        let db_filename = self.config.lock().unwrap().get_dbfilename();
-        let db_sender_maintenance = db_sender.clone();
-
-        let _: JoinHandle<Result<(), io::Error>> = thread::spawn(move || {
-            Server::maintenance_thread(db_filename, db_sender_maintenance)?;
-            Ok(())
-        });
+        let config = Arc::clone(&self.config);
+        let connected_clients = Arc::clone(&self.connected_clients);
+        let stats = Arc::clone(&self.stats);
+        let redis = Arc::clone(&self.redis);
 
         self.db_thread(db_receiver);
 
-        let _ = Server::accepter_rest_thread(rest_listener, db_sender.clone(), log_sender.clone());
-        Server::receive_connections(listener, db_sender, log_sender, timeout)?;
+        if let Some(rest_listener) = rest_listener {
+            let _ = Server::accepter_rest_thread(
+                rest_listener,
+                db_sender.clone(),
+                log_sender.clone(),
+                Arc::clone(&config),
+                Arc::clone(&connected_clients),
+            );
+        }
+        Server::receive_connections(
+            listener,
+            db_sender,
+            log_sender,
+            config,
+            connected_clients,
+            stats,
+            redis,
+        )?;
 
         Ok(())
     }
 
-    /// Metodo encargado de capturar cada request rest y enviarlo al metodo correspondiente para que
-    /// sea atendido.
+    /// Metodo encargado de capturar cada conexión rest y repartirla entre `REST_WORKERS` hilos,
+    /// para que un request lento o una conexión keep-alive no demore al resto.
     fn accepter_rest_thread(
         listener: TcpListener,
-        db_sender: Sender<(Command, Sender<Response>)>,
+        db_sender: DbSender,
         log_sender: Sender<Log>,
+        config: Arc<Mutex<Config>>,
+        connected_clients: Arc<Mutex<ConnectedClients>>,
     ) -> JoinHandle<Result<(), io::Error>> {
         thread::spawn(move || {
-            let mut html = Html::new()?;
-            for stream in listener.incoming() {
-                let stream = stream.unwrap();
-                let db_sender_clone = db_sender.clone();
-                let log_sender_clone = log_sender.clone();
-                Server::rest_client_handler(stream, db_sender_clone, log_sender_clone, &mut html)?;
+            let html = Arc::new(Mutex::new(Html::new()?));
+            let (stream_sender, stream_receiver) = mpsc::channel::<TcpStream>();
+            let stream_receiver = Arc::new(Mutex::new(stream_receiver));
+
+            for _ in 0..REST_WORKERS {
+                let stream_receiver = Arc::clone(&stream_receiver);
+                let db_sender = db_sender.clone();
+                let log_sender = log_sender.clone();
+                let config = Arc::clone(&config);
+                let connected_clients = Arc::clone(&connected_clients);
+                let html = Arc::clone(&html);
+                thread::spawn(move || loop {
+                    // El lock se libera al recibir la conexión, antes de atenderla.
+                    let stream = stream_receiver.lock().unwrap().recv();
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => break,
+                    };
+                    if let Err(e) = Server::rest_connection_handler(
+                        stream,
+                        &db_sender,
+                        &log_sender,
+                        &config,
+                        &connected_clients,
+                        &html,
+                    ) {
+                        let _ = log_sender.send(Log::new(
+                            LogLevel::Error,
+                            line!(),
+                            column!(),
+                            file!().to_string(),
+                            format!("REST connection error: {}", e),
+                        ));
+                    }
+                });
+            }
+
+            for stream in listener.incoming().flatten() {
+                if stream_sender.send(stream).is_err() {
+                    break;
+                }
             }
             Ok(())
         })
@@ -149,11 +331,15 @@ impl Server {
     /// que sea atendido.
     fn receive_connections(
         listener: TcpListener,
-        db_sender: Sender<(Command, Sender<Response>)>,
+        db_sender: DbSender,
         log_sender: Sender<Log>,
-        timeout: u64,
+        config: Arc<Mutex<Config>>,
+        connected_clients: Arc<Mutex<ConnectedClients>>,
+        stats: Arc<Mutex<ServerStats>>,
+        redis: Arc<RwLock<Redis>>,
     ) -> io::Result<()> {
-        let mut handlers: VecHandler = vec![];
+        let (handler_sender, handler_receiver) = mpsc::channel();
+        let _ = Server::reaper_thread(handler_receiver, log_sender.clone());
 
         while let Ok(connection) = listener.accept() {
             //accepter thread
@@ -167,68 +353,197 @@ impl Server {
                 ))
                 .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Log Sender error"))?;
 
-            let (client, _) = connection;
+            let (client, addr) = connection;
+            // El timeout se lee en cada conexión, ya que puede modificarse con CONFIG SET.
+            let timeout = config.lock().unwrap().get_timeout();
             if timeout != 0 {
                 client.set_read_timeout(Option::from(Duration::from_secs(timeout)))?;
             }
-            let db_sender_clone: Sender<(Command, Sender<Response>)> = db_sender.clone();
+            let db_sender_clone: DbSender = db_sender.clone();
 
-            let flag = Arc::new(AtomicBool::new(true));
-            let used_flag = flag.clone();
             let logger_client = log_sender.clone();
-            let handler: JoinHandle<Result<(), io::Error>> = thread::spawn(move || {
-                Server::client_handler(client, db_sender_clone, logger_client, &used_flag)?;
-                Ok(())
+            let config_client = Arc::clone(&config);
+            let clients = Arc::clone(&connected_clients);
+            let redis_client = Arc::clone(&redis);
+            let client_id = clients
+                .lock()
+                .unwrap()
+                .register(addr.to_string(), client.try_clone().ok());
+            let client = CountingStream {
+                stream: client,
+                stats: Arc::clone(&stats),
+            };
+            let finished_guard = FinishedGuard {
+                client_id,
+                sender: handler_sender.clone(),
+            };
+            let handler: ClientHandler = thread::spawn(move || {
+                let _finished_guard = finished_guard;
+                let result = Server::client_handler(
+                    client,
+                    client_id,
+                    db_sender_clone,
+                    logger_client,
+                    config_client,
+                    &clients,
+                    &redis_client,
+                );
+                // El cliente se quita del registro aunque la conexión haya terminado con error.
+                clients.lock().unwrap().unregister(client_id);
+                result
             });
-            handlers.push((handler, flag));
-
-            let mut handlers_actives: VecHandler = vec![];
-            let mut handlers_inactives: VecHandler = vec![];
-            for (handler, used) in handlers {
-                if used.load(Ordering::Relaxed) {
-                    handlers_actives.push((handler, used));
-                } else {
-                    handlers_inactives.push((handler, used));
-                }
-            }
+            let _ = handler_sender.send(HandlerEvent::Started(client_id, handler));
+        }
+
+        Ok(())
+    }
+
+    /// Metodo encargado de esperar a los hilos de los clientes a medida que terminan, liberando
+    /// sus recursos aunque no se conecten nuevos clientes.
+    fn reaper_thread(receiver: Receiver<HandlerEvent>, log_sender: Sender<Log>) -> JoinHandle<()> {
+        thread::spawn(move || {
+            let mut handlers: HashMap<u64, ClientHandler> = HashMap::new();
+            // Clientes cuyo hilo terminó antes de recibir su handler.
+            let mut finished: HashSet<u64> = HashSet::new();
+
+            for event in receiver {
+                let handler = match event {
+                    HandlerEvent::Started(client_id, handler) => {
+                        if !finished.remove(&client_id) {
+                            handlers.insert(client_id, handler);
+                            continue;
+                        }
+                        handler
+                    }
+                    HandlerEvent::Finished(client_id) => match handlers.remove(&client_id) {
+                        Some(handler) => handler,
+                        None => {
+                            finished.insert(client_id);
+                            continue;
+                        }
+                    },
+                };
 
-            for (handler, _) in handlers_inactives {
                 if handler.join().is_err() {
-                    log_sender
-                        .send(Log::new(
-                            LogLevel::Error,
-                            line!(),
-                            column!(),
-                            file!().to_string(),
-                            "Error joining handler".to_string(),
-                        ))
-                        .map_err(|_| {
-                            Error::new(ErrorKind::ConnectionAborted, "Log Sender error")
-                        })?;
+                    let _ = log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        "Error joining handler".to_string(),
+                    ));
                 }
             }
+        })
+    }
 
-            handlers = handlers_actives;
+    /// Atiende los requests de una conexión rest. Mientras el cliente no pida cerrarla, la conexión
+    /// se mantiene abierta esperando el siguiente request hasta `REST_KEEP_ALIVE_SECS`.
+    fn rest_connection_handler(
+        mut stream: TcpStream,
+        db_sender: &DbSender,
+        logger: &Sender<Log>,
+        config: &Mutex<Config>,
+        connected_clients: &Arc<Mutex<ConnectedClients>>,
+        html: &Mutex<Html>,
+    ) -> io::Result<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(REST_KEEP_ALIVE_SECS)))?;
+        let mut pending = Vec::new();
+        while let Some(data) = Server::read_rest_request(&mut stream, &mut pending)? {
+            let open = Server::rest_client_handler(
+                &mut stream,
+                &data,
+                db_sender,
+                logger,
+                config,
+                connected_clients,
+                html,
+            )?;
+            if !open || !keep_alive(&data) {
+                break;
+            }
         }
-
         Ok(())
     }
 
+    /// Lee el siguiente request de la conexión, incluyendo el body indicado en `Content-Length`
+    /// aunque llegue en varios segmentos. Los bytes recibidos a continuación, que pertenecen al
+    /// request siguiente, quedan en `pending`.
+    ///
+    /// Retorna `None` si el cliente cerró la conexión, si no envió otro request a tiempo o si el
+    /// request supera `MAX_REST_REQUEST_SIZE`, en cuyo caso se le responde `413`.
+    fn read_rest_request(
+        stream: &mut TcpStream,
+        pending: &mut Vec<u8>,
+    ) -> io::Result<Option<Vec<u8>>> {
+        let mut buffer = [0; 1024];
+        loop {
+            match request_len(pending) {
+                Some(len) if len > MAX_REST_REQUEST_SIZE => {
+                    Server::payload_too_large_handler(stream)?;
+                    return Ok(None);
+                }
+                Some(len) if pending.len() >= len => {
+                    let next = pending.split_off(len);
+                    return Ok(Some(std::mem::replace(pending, next)));
+                }
+                None if pending.len() > MAX_REST_REQUEST_SIZE => {
+                    Server::payload_too_large_handler(stream)?;
+                    return Ok(None);
+                }
+                _ => {}
+            }
+            let size = match stream.read(&mut buffer) {
+                Ok(size) => size,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => 0,
+                Err(e) => return Err(e),
+            };
+            if size == 0 {
+                return Ok(None);
+            }
+            pending.extend_from_slice(&buffer[..size]);
+        }
+    }
+
     /// Metodo encargado de capturar los eventos de cada petición rest.
+    ///
+    /// Retorna `false` si la conexión ya no puede atender otros requests, como cuando pasa a
+    /// enviar Server-Sent Events.
     fn rest_client_handler(
-        mut stream: TcpStream,
-        db_sender_clone: Sender<(Command, Sender<Response>)>,
-        logger: Sender<Log>,
-        html: &mut Html,
-    ) -> io::Result<()> {
-        let mut buffer = [0; 3024];
-        let _ = stream.read(&mut buffer)?;
+        stream: &mut TcpStream,
+        data: &[u8],
+        db_sender: &DbSender,
+        logger: &Sender<Log>,
+        config: &Mutex<Config>,
+        connected_clients: &Arc<Mutex<ConnectedClients>>,
+        html: &Mutex<Html>,
+    ) -> io::Result<bool> {
+        // Si el servidor tiene contraseña, se exige también en la interfaz REST.
+        let requirepass = config.lock().unwrap().get_requirepass();
+        if !is_authorized(header(data, "authorization").as_deref(), &requirepass) {
+            Server::unauthorized_handler(stream)?;
+            return Ok(true);
+        }
 
-        let request: HttpMethod = parse_command_rest(&buffer);
+        let request: HttpMethod = parse_command_rest(data);
+
+        if let Some(channel) = subscribe_channel(&request) {
+            Server::sse_handler(
+                stream.try_clone()?,
+                db_sender.clone(),
+                channel,
+                Arc::clone(connected_clients),
+            )?;
+            return Ok(false);
+        }
+        if let Some(command) = key_command(&request) {
+            Server::key_handler(stream, db_sender, command)?;
+            return Ok(true);
+        }
 
         match request {
-            HttpMethod::Get(url) => Server::get_handler(&mut stream, html, &url)?,
-            HttpMethod::Post(command) => {
+            HttpMethod::Get(url) => Server::get_handler(stream, &mut html.lock().unwrap(), &url)?,
+            HttpMethod::Post(command, format) => {
                 logger
                     .send(Log::new(
                         LogLevel::Info,
@@ -239,35 +554,184 @@ impl Server {
                     ))
                     .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Log Sender error"))?;
 
-                Server::post_handler(stream, db_sender_clone, command, html)?
+                Server::post_handler(stream, db_sender, command, format, html)?
             }
-            _ => Server::unknown_handler(&mut stream)?,
+            _ => Server::unknown_handler(stream)?,
         };
-        Ok(())
+        Ok(true)
     }
 
     fn post_handler(
-        mut stream: TcpStream,
-        db_sender_clone: Sender<(Command, Sender<Response>)>,
+        stream: &mut TcpStream,
+        db_sender: &DbSender,
         command: Vec<String>,
-        html: &mut Html,
+        format: ResponseFormat,
+        html: &Mutex<Html>,
     ) -> io::Result<()> {
+        let input = command.join(" ");
+        let result = Server::execute_rest_command(db_sender, command)?;
+
+        match format {
+            ResponseFormat::Html => {
+                let mut html = html.lock().unwrap();
+                html.append_input(&input);
+                match result {
+                    Ok(redis_string) => html.append_response(&parse_response_rest(redis_string)),
+                    Err(msg) => html.append_error(&msg),
+                }
+                stream.write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                        html.get_index().len(),
+                        html.get_index()
+                    )
+                    .as_bytes(),
+                )?;
+            }
+            ResponseFormat::Json => {
+                let body = encode_result(result);
+                stream.write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .as_bytes(),
+                )?;
+            }
+        }
+        stream.flush()?;
+
+        Ok(())
+    }
+
+    /// Suscribe la conexión al canal y le envía los mensajes publicados como Server-Sent Events,
+    /// desde un hilo propio para no demorar al resto de los requests REST.
+    ///
+    /// La conexión se registra como un cliente más, por lo que aparece en CLIENT LIST y puede
+    /// cerrarse con CLIENT KILL. Al cerrarse se quitan sus suscripciones.
+    fn sse_handler(
+        stream: TcpStream,
+        db_sender: DbSender,
+        channel: String,
+        connected_clients: Arc<Mutex<ConnectedClients>>,
+    ) -> io::Result<()> {
+        // La conexión ya no espera requests, por lo que no se corta por inactividad.
+        stream.set_read_timeout(None)?;
+        let addr = stream.peer_addr()?.to_string();
+        let client_id = connected_clients
+            .lock()
+            .unwrap()
+            .register(addr, stream.try_clone().ok());
+        Server::connected_user(&db_sender);
+
+        let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) = mpsc::channel();
+        let request = DbRequest {
+            command: Command::Subscribe {
+                channels: vec![channel.clone()],
+                client_id,
+            },
+            client_id: Some(client_id),
+            args: vec!["subscribe".to_string(), channel],
+        };
+        let rec = match db_sender
+            .send((request, client_sndr))
+            .ok()
+            .and(client_rcvr.recv().ok())
+        {
+            Some(Response::Stream(rec)) => rec,
+            _ => {
+                Server::disconnected_user(&db_sender, client_id);
+                connected_clients.lock().unwrap().unregister(client_id);
+                return Err(Error::new(ErrorKind::ConnectionAborted, "Subscribe error"));
+            }
+        };
+
+        let mut output = stream.try_clone()?;
+        let mut input = stream;
+        thread::spawn(move || {
+            let writer = thread::spawn(move || {
+                if output.write_all(SSE_HEADERS.as_bytes()).is_ok() {
+                    for event in rec.into_iter().filter_map(encode_event) {
+                        if output.write_all(event.as_bytes()).is_err() {
+                            break;
+                        }
+                    }
+                }
+                // Al cerrar el socket termina la lectura, que se encarga de desuscribir al cliente.
+                let _ = output.shutdown(Shutdown::Both);
+            });
+
+            // El cliente no envía nada más: la lectura sólo termina cuando cierra la conexión.
+            let mut buffer = [0; 64];
+            while matches!(input.read(&mut buffer), Ok(size) if size > 0) {}
+            Server::disconnected_user(&db_sender, client_id);
+            connected_clients.lock().unwrap().unregister(client_id);
+            let _ = writer.join();
+        });
+        Ok(())
+    }
+
+    /// Atiende los requests sobre el recurso `/keys`, respondiendo siempre en JSON. Si la clave no
+    /// existe responde `404 Not Found` y si el comando falla, `400 Bad Request`.
+    fn key_handler(
+        stream: &mut TcpStream,
+        db_sender: &DbSender,
+        command: Vec<String>,
+    ) -> io::Result<()> {
+        let result = Server::execute_rest_command(db_sender, command)?;
+        let status = match result {
+            Ok(RedisElement::Nil) => "404 Not Found",
+            Ok(_) => "200 OK",
+            Err(_) => "400 Bad Request",
+        };
+        let body = encode_result(result);
+        stream.write_all(
+            format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        )?;
+        stream.flush()
+    }
+
+    /// Ejecuta un comando recibido por la interfaz REST.
+    ///
+    /// Retorna la respuesta del comando o el mensaje de error a mostrar, incluyendo los comandos
+    /// que no pueden ejecutarse desde la interfaz REST.
+    fn execute_rest_command(
+        db_sender: &DbSender,
+        command: Vec<String>,
+    ) -> io::Result<Result<RedisElement, String>> {
         let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) = mpsc::channel();
         let help_msg = "I'm sorry, I don't recognize that command. Please type HELP for one of \
         these commands: DECRBY, DEL, EXISTS, EXPIRE, GET, GETSET, INCRBY, KEYS, LINDEX, LLEN, LPOP, \
          LPUSH, LRANGE, LREM, LSET, LTRIM, MGET, MSET, RENAME, RPOP, RPUSH, SADD, SCARD, SET, SORT, \
          TTL, TYPE";
 
-        html.append_input(&command.join(" "));
-        match generate(command, "REST".to_string()) {
-            Ok(Command::Monitor) => html.append_error(help_msg),
-            Ok(Command::Publish { .. }) => html.append_error(help_msg),
-            Ok(Command::Command) => html.append_error(help_msg),
-            Ok(Command::Subscribe { .. }) => html.append_error(help_msg),
-            Ok(Command::Unsubscribe { .. }) => html.append_error(help_msg),
+        let args = command.clone();
+        let result = match generate(command, REST_CLIENT_ID) {
+            Ok(Command::Monitor { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Auth { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Blpop { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Brpop { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Bzpopmax { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Bzpopmin { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Publish { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Command { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Subscribe { .. }) => Err(help_msg.to_string()),
+            Ok(Command::Unsubscribe { .. }) => Err(help_msg.to_string()),
             Ok(command) => {
-                db_sender_clone
-                    .send((command, client_sndr))
+                let request = DbRequest {
+                    command,
+                    client_id: None,
+                    args,
+                };
+                db_sender
+                    .send((request, client_sndr))
                     .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Db Sender error"))?;
 
                 let response = client_rcvr.recv().map_err(|_| {
@@ -275,29 +739,34 @@ impl Server {
                 })?;
 
                 match response {
-                    Response::Normal(redis_string) => {
-                        html.append_response(&parse_response_rest(redis_string));
-                    }
-                    Response::Error(msg) => html.append_error(&msg),
-                    Response::Stream(_) => html.append_error(help_msg),
+                    Response::Normal(redis_string) => Ok(redis_string),
+                    Response::Error(msg) => Err(msg),
+                    Response::Stream(_) => Err(help_msg.to_string()),
+                    Response::Blocked(_) => Err(help_msg.to_string()),
                 }
             }
-            Err(err) => html.append_error(&err),
-        }
-        stream.write_all(
-            format!(
-                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
-                html.get_index().len(),
-                html.get_index()
-            )
-            .as_bytes(),
-        )?;
-        stream.flush()?;
-
-        Ok(())
+            Err(err) => Err(err),
+        };
+        Ok(result)
     }
 
     fn get_handler(stream: &mut TcpStream, html: &mut Html, url: &str) -> io::Result<()> {
+        if url == DASHBOARD_URL {
+            return match Html::get_dashboard() {
+                Ok(dashboard) => {
+                    stream.write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\r\n{}",
+                            dashboard.len(),
+                            dashboard
+                        )
+                        .as_bytes(),
+                    )?;
+                    stream.flush()
+                }
+                Err(_) => Server::unknown_handler(stream),
+            };
+        }
         if let Some(url_stripped) = url.strip_prefix('/') {
             if let Ok(image) = Html::get_resource(url_stripped) {
                 stream.write_all(
@@ -326,6 +795,27 @@ impl Server {
         Ok(())
     }
 
+    fn unauthorized_handler(stream: &mut TcpStream) -> io::Result<()> {
+        let body = encode_result(Err("NOAUTH Authentication required.".to_string()));
+        stream.write_all(
+            format!(
+                "{}Content-Length: {}\r\n\r\n{}",
+                UNAUTHORIZED_HEADERS,
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        )?;
+        stream.flush()
+    }
+
+    fn payload_too_large_handler(stream: &mut TcpStream) -> io::Result<()> {
+        stream.write_all(
+            b"HTTP/1.1 413 Payload Too Large\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        )?;
+        stream.flush()
+    }
+
     fn unknown_handler(stream: &mut TcpStream) -> io::Result<()> {
         if let Ok(file) = Html::get_404() {
             stream.write_all(
@@ -336,65 +826,198 @@ impl Server {
                 )
                 .as_bytes(),
             )?;
-            stream.flush()?;
+        } else {
+            // Sin respuesta, un cliente keep-alive seguiría esperándola.
+            stream.write_all(b"HTTP/1.1 404 Not found\r\nContent-Length: 0\r\n\r\n")?;
         }
-        Ok(())
+        stream.flush()
     }
 
     #[allow(clippy::while_let_on_iterator)]
     /// Metodo encargado de capturar los eventos de cada cliente.
     fn client_handler(
-        client: TcpStream,
-        db_sender_clone: Sender<(Command, Sender<Response>)>,
+        client: CountingStream,
+        client_id: u64,
+        db_sender_clone: DbSender,
         logger: Sender<Log>,
-        used: &AtomicBool,
+        config: Arc<Mutex<Config>>,
+        connected_clients: &Mutex<ConnectedClients>,
+        redis: &RwLock<Redis>,
     ) -> io::Result<()> {
-        let client_input: TcpStream = client.try_clone()?;
-        let client_output: TcpStream = client;
+        let stats = Arc::clone(&client.stats);
+        let client_input = client.try_clone()?;
+        let client_output = client;
         let mut input = BufReader::new(client_input);
-        let mut output = client_output;
-
-        let client_id = output.try_clone()?.local_addr()?.to_string();
+        // Las respuestas se acumulan mientras haya comandos en pipeline por procesar. La salida se
+        // comparte con los hilos que envían los mensajes de los canales a los que está suscripto.
+        let output = Arc::new(Mutex::new(BufWriter::new(client_output)));
+        // Indica si el cliente se autenticó mediante AUTH.
+        let mut authenticated = false;
+        // Indica si el cliente está en modo suscriptor, es decir, suscripto a algún canal.
+        let mut subscribed = false;
+        let mut forwarders: Vec<JoinHandle<()>> = vec![];
+        let mut rate_limiter = RateLimiter::new(Instant::now());
 
         Server::connected_user(&db_sender_clone);
 
-        // iteramos las lineas que recibimos de nuestro cliente
-        'principal: while let Some(line) = LinesIterator::new(&mut input).next() {
+        // iteramos las lineas que recibimos de nuestro cliente. El iterador se conserva entre
+        // comandos para no perder los bytes recibidos de los comandos siguientes.
+        let mut lines = LinesIterator::new(&mut input);
+        'principal: while let Some(line) = lines.next() {
             let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) =
                 mpsc::channel();
 
+            // Los errores de protocolo se informan al cliente y, como en Redis, se cierra la
+            // conexión, ya que no se puede saber dónde comienza el siguiente comando.
+            if let TypeData::Error(msg) = line {
+                output
+                    .lock()
+                    .unwrap()
+                    .write_all(&parse_response_error(msg))?;
+                break 'principal;
+            }
+
             let vector = parse_command(line);
+            if let Some(name) = vector.first() {
+                connected_clients
+                    .lock()
+                    .unwrap()
+                    .touch(client_id, &name.to_lowercase());
+            }
 
-            let command = generate(vector, client_id.clone());
+            // Los comandos que superan el límite de comandos por segundo del cliente se demoran o
+            // se rechazan, para que un único cliente no acapare el hilo de la DB.
+            let (rate, policy) = {
+                let config = config.lock().unwrap();
+                (
+                    config.get_client_max_commands_per_sec(),
+                    config.get_client_rate_limit_policy(),
+                )
+            };
+            let mut wait = rate_limiter.acquire(rate, Instant::now());
+            if wait.is_some() && policy == RateLimitPolicy::Reject {
+                output
+                    .lock()
+                    .unwrap()
+                    .write_all(&parse_response_error(RATE_LIMIT_MSG.to_string()))?;
+                Server::flush_if_idle(&mut output.lock().unwrap(), &mut lines)?;
+                continue;
+            }
+            while let Some(duration) = wait {
+                // Se envían las respuestas pendientes antes de esperar.
+                output.lock().unwrap().flush()?;
+                thread::sleep(duration);
+                wait = rate_limiter.acquire(rate, Instant::now());
+            }
+
+            // En modo cluster, los comandos cuyas claves pertenecen a slots de otro nodo no se
+            // ejecutan y se redirige al cliente.
+            let redirect = config
+                .lock()
+                .unwrap()
+                .get_cluster()
+                .and_then(|cluster| cluster.check_command(&vector).err());
+            let args = vector.clone();
+            let command = generate(vector, client_id);
 
             match command {
                 Ok(command) => {
-                    db_sender_clone
-                        .send((command, client_sndr))
-                        .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Db Sender error"))?;
+                    let is_auth = matches!(command, Command::Auth { .. });
+                    if !authenticated
+                        && !is_auth
+                        && !config.lock().unwrap().get_requirepass().is_empty()
+                    {
+                        output.lock().unwrap().write_all(&parse_response_error(
+                            "NOAUTH Authentication required.".to_string(),
+                        ))?;
+                        Server::flush_if_idle(&mut output.lock().unwrap(), &mut lines)?;
+                        continue;
+                    }
 
-                    let response = client_rcvr.recv().map_err(|_| {
-                        Error::new(ErrorKind::ConnectionAborted, "Client receiver error")
-                    })?;
+                    if let Some(redirect) = redirect {
+                        output
+                            .lock()
+                            .unwrap()
+                            .write_all(&parse_response_error(redirect))?;
+                        Server::flush_if_idle(&mut output.lock().unwrap(), &mut lines)?;
+                        continue;
+                    }
+
+                    stats.lock().unwrap().add_command();
+                    let is_subscribe = matches!(command, Command::Subscribe { .. });
+                    let is_unsubscribe = matches!(command, Command::Unsubscribe { .. });
+                    let is_quit = matches!(command, Command::Quit);
+                    let subscriber_response = if subscribed {
+                        Server::subscriber_mode_response(&command)
+                    } else {
+                        None
+                    };
+                    // Las lecturas y las escrituras de una única clave se atienden desde este hilo
+                    // si es posible, tomando sólo el shard de la clave, sin pasar por el hilo de la
+                    // DB.
+                    let shared = match subscriber_response {
+                        Some(response) => Some(Ok(response)),
+                        None => redis.read().unwrap().execute_shared_from_client(
+                            &command,
+                            Some(client_id),
+                            &args,
+                        ),
+                    };
+                    let response = match shared {
+                        Some(response) => response.unwrap_or_else(Response::Error),
+                        None => {
+                            let request = DbRequest {
+                                command,
+                                client_id: Some(client_id),
+                                args,
+                            };
+                            db_sender_clone.send((request, client_sndr)).map_err(|_| {
+                                Error::new(ErrorKind::ConnectionAborted, "Db Sender error")
+                            })?;
+
+                            client_rcvr.recv().map_err(|_| {
+                                Error::new(ErrorKind::ConnectionAborted, "Client receiver error")
+                            })?
+                        }
+                    };
 
                     match response {
                         Response::Normal(redis_string) => {
-                            output.write_all(&parse_response_ok(redis_string))?;
+                            authenticated |= is_auth;
+                            write_response_ok(redis_string, &mut *output.lock().unwrap())?;
+                            if is_unsubscribe {
+                                subscribed =
+                                    redis.read().unwrap().subscription_count(client_id) > 0;
+                            }
                         }
                         Response::Stream(rec) => {
-                            'inner: while let Ok(redis_element) = rec.recv() {
-                                if output.write_all(&parse_response_ok(redis_element)).is_err() {
-                                    break 'inner;
-                                }
+                            // Las confirmaciones ya están en el canal y se responden en orden,
+                            // antes que los comandos siguientes. Los mensajes siguientes se
+                            // envían desde otro hilo, y este sigue atendiendo al cliente para
+                            // advertir cuando se desconecta.
+                            for confirmation in rec.try_iter() {
+                                write_response_ok(confirmation, &mut *output.lock().unwrap())?;
                             }
-
-                            std::mem::drop(rec);
-                            break 'principal;
+                            subscribed |= is_subscribe;
+                            forwarders.push(Server::forward_messages(rec, Arc::clone(&output)));
+                        }
+                        Response::Blocked(rec) => {
+                            // Se envían las respuestas pendientes antes de quedar bloqueado.
+                            output.lock().unwrap().flush()?;
+                            let redis_element = rec.recv().unwrap_or(RedisElement::Nil);
+                            write_response_ok(redis_element, &mut *output.lock().unwrap())?;
                         }
                         Response::Error(msg) => {
-                            output.write_all(&parse_response_error(msg))?;
+                            output
+                                .lock()
+                                .unwrap()
+                                .write_all(&parse_response_error(msg))?;
                         }
                     }
+
+                    if is_quit {
+                        break 'principal;
+                    }
                 }
                 Err(err) => {
                     logger
@@ -408,39 +1031,123 @@ impl Server {
                         .map_err(|_| {
                             Error::new(ErrorKind::ConnectionAborted, "Log Sender error")
                         })?;
-                    output.write_all(&parse_response_error(err))?;
+                    output
+                        .lock()
+                        .unwrap()
+                        .write_all(&parse_response_error(err))?;
                 }
             };
+
+            Server::flush_if_idle(&mut output.lock().unwrap(), &mut lines)?;
         }
+        let _ = output.lock().unwrap().flush();
 
-        used.swap(false, Ordering::Relaxed);
-        Server::disconnected_user(&db_sender_clone);
+        // Al quitar al cliente se cancelan sus suscripciones y su MONITOR, por lo que terminan los
+        // hilos que le envían los mensajes.
+        Server::disconnected_user(&db_sender_clone, client_id);
+        for forwarder in forwarders {
+            let _ = forwarder.join();
+        }
 
         Ok(())
     }
 
+    /// Respuesta a los comandos que no se ejecutan por estar el cliente en modo suscriptor. Como
+    /// en Redis, sólo se permiten SUBSCRIBE, UNSUBSCRIBE, PING y QUIT, y PING responde con el
+    /// formato de los mensajes.
+    fn subscriber_mode_response(command: &Command) -> Option<Response> {
+        match command {
+            Command::Subscribe { .. } | Command::Unsubscribe { .. } | Command::Quit => None,
+            Command::Ping { message } => Some(Response::Normal(RedisElement::Array(vec![
+                RedisElement::String("pong".to_string()),
+                RedisElement::String(message.clone().unwrap_or_default()),
+            ]))),
+            _ => Some(Response::Error(format!(
+                "ERR Can't execute '{}': only SUBSCRIBE / UNSUBSCRIBE / PING / QUIT are allowed in this context",
+                command.as_str()
+            ))),
+        }
+    }
+
+    /// Envía al cliente los mensajes publicados en los canales a los que se suscribió, o los
+    /// comandos informados por MONITOR, mientras su hilo sigue atendiendo los comandos que recibe.
+    /// Termina cuando el cliente se desuscribe de esos canales o se cierra la conexión.
+    fn forward_messages<W: Write + Send + 'static>(
+        rec: MessageReceiver<RedisElement>,
+        output: Arc<Mutex<BufWriter<W>>>,
+    ) -> JoinHandle<()> {
+        thread::spawn(move || {
+            for message in rec {
+                let mut output = output.lock().unwrap();
+                if write_response_ok(message, &mut *output)
+                    .and_then(|_| output.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Envía las respuestas acumuladas al cliente, salvo que ya haya recibido otro comando en
+    /// pipeline, en cuyo caso se procesa antes de responder.
+    fn flush_if_idle<R: BufRead, W: Write>(
+        output: &mut BufWriter<W>,
+        lines: &mut LinesIterator<R>,
+    ) -> io::Result<()> {
+        if lines.has_pending_command() {
+            return Ok(());
+        }
+        output.flush()
+    }
+
     /// Metodo encargado de Enviarle una señal a la DB indicando que se ha conectado otro usuario.
-    fn connected_user(db_sender_clone: &Sender<(Command, Sender<Response>)>) {
+    fn connected_user(db_sender_clone: &DbSender) {
         let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) = mpsc::channel();
-        let _ = db_sender_clone.send((Command::AddClient, client_sndr));
+        let _ = db_sender_clone.send((DbRequest::internal(Command::AddClient), client_sndr));
         let _ = client_rcvr.recv();
     }
 
     /// Metodo encargado de Enviarle una señal a la DB indicando que se ha desconectado un usuario.
-    fn disconnected_user(db_sender_clone: &Sender<(Command, Sender<Response>)>) {
+    fn disconnected_user(db_sender_clone: &DbSender, client_id: u64) {
         let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) = mpsc::channel();
-        let _ = db_sender_clone.send((Command::RemoveClient, client_sndr));
+        let command = Command::RemoveClient { client_id };
+        let _ = db_sender_clone.send((DbRequest::internal(command), client_sndr));
         let _ = client_rcvr.recv();
     }
 
     /// Metodo encargado de centralizar las ejecuciones de los comandos que se ejecutan en la DB.
     /// El servidor le envía un canal de Recepción de Comandos y Senders donde debe enviar la
     /// respuesta al cliente.
-    fn db_thread(mut self, db_receiver: Receiver<(Command, Sender<Response>)>) {
+    fn db_thread(self, db_receiver: DbReceiver) {
         let log_sender = self.log_sender.clone();
         let _: JoinHandle<Result<(), io::Error>> = thread::spawn(move || {
-            while let Ok((command, sender)) = db_receiver.recv() {
-                let redis_response = self.redis.execute(command);
+            loop {
+                let (request, sender) = match db_receiver
+                    .recv_timeout(Duration::from_millis(BLOCKED_CLIENTS_TICK_MS))
+                {
+                    Ok(message) => message,
+                    Err(RecvTimeoutError::Timeout) => {
+                        let mut redis = self.redis.write().unwrap();
+                        redis.apply_shared_commands();
+                        redis.active_expire_cycle();
+                        redis.expire_blocked_clients();
+                        redis.save_if_needed();
+                        self.stats.lock().unwrap().sample();
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                let mut redis = self.redis.write().unwrap();
+                redis.apply_shared_commands();
+                redis.active_expire_cycle();
+                redis.expire_blocked_clients();
+                redis.save_if_needed();
+                self.stats.lock().unwrap().sample();
+
+                let redis_response =
+                    redis.execute_from_client(request.command, request.client_id, &request.args);
+                drop(redis);
                 match redis_response {
                     Ok(value) => {
                         if sender.send(value).is_err() {
@@ -477,28 +1184,4 @@ impl Server {
             Ok(())
         });
     }
-
-    /// Metodo ejecutado en el hilo de mantenimiento el cual se encarga de ejecutar acciones dentro
-    /// del server que sean de Mantenimiento. Como por ejemplo persistir la base de datos en caso de
-    /// fallas.
-    fn maintenance_thread(
-        file: String,
-        db_receiver: Sender<(Command, Sender<Response>)>,
-    ) -> io::Result<()> {
-        loop {
-            let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) =
-                mpsc::channel();
-            let command = Command::Store {
-                path: file.to_string(),
-            };
-
-            db_receiver
-                .send((command, client_sndr))
-                .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "DB receiver error"))?;
-            client_rcvr
-                .recv()
-                .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "DB sender error"))?;
-            thread::sleep(Duration::from_secs(STORE_TIME_SEC));
-        }
-    }
 }
@@ -0,0 +1,81 @@
+use crate::entities::log::Log;
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+use std::process;
+
+/// Sockets en los que escucha el daemon de syslog local, en Linux y en macOS.
+const SYSLOG_SOCKETS: [&str; 2] = ["/dev/log", "/var/run/syslog"];
+/// Identificador con el que se registran los logs, como el `syslog-ident` por defecto de Redis.
+const SYSLOG_IDENT: &str = "redis";
+
+#[derive(Debug)]
+/// Conexión con el daemon de syslog local, por la que se envía un datagrama por cada log.
+pub struct Syslog {
+    socket: UnixDatagram,
+    pid: u32,
+}
+
+impl Syslog {
+    /// Se conecta al primer socket de syslog disponible.
+    pub fn connect() -> io::Result<Syslog> {
+        let mut error = io::Error::new(io::ErrorKind::NotFound, "Syslog socket not found");
+        for path in SYSLOG_SOCKETS.iter() {
+            match Syslog::connect_to(Path::new(path)) {
+                Ok(syslog) => return Ok(syslog),
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+
+    /// Se conecta al socket de syslog indicado.
+    pub fn connect_to(path: &Path) -> io::Result<Syslog> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Syslog {
+            socket,
+            pid: process::id(),
+        })
+    }
+
+    /// Envía el log al daemon de syslog.
+    pub fn send(&self, log: &Log) -> io::Result<()> {
+        self.socket
+            .send(log.to_syslog(SYSLOG_IDENT, self.pid).as_bytes())
+            .map(|_| ())
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::log::Log;
+    use crate::entities::log_level::LogLevel;
+    use crate::service::syslog::Syslog;
+    use std::fs;
+    use std::os::unix::net::UnixDatagram;
+    use std::process;
+
+    #[test]
+    fn send_writes_a_datagram_to_the_syslog_socket() {
+        let path = std::env::temp_dir().join(format!("syslog_test_{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+        let daemon = UnixDatagram::bind(&path).unwrap();
+
+        let syslog = Syslog::connect_to(&path).unwrap();
+        let log = Log::new(
+            LogLevel::Error,
+            7,
+            3,
+            "server.rs".to_string(),
+            "mensaje".to_string(),
+        );
+        syslog.send(&log).unwrap();
+
+        let mut buffer = [0; 256];
+        let size = daemon.recv(&mut buffer).unwrap();
+        let expected = format!("<131>redis[{}]: server.rs:7:3 - mensaje", process::id());
+        assert_eq!(expected.as_bytes(), &buffer[..size]);
+        fs::remove_file(&path).unwrap();
+    }
+}